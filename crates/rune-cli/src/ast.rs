@@ -0,0 +1,62 @@
+use crate::{ExitCode, Io, SharedFlags};
+use anyhow::{Context, Result};
+use rune::ast;
+use rune::parse;
+use rune::termcolor::WriteColor;
+use rune::{Diagnostics, Source, Sources};
+use std::io::Write;
+use std::path::Path;
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug, Clone)]
+pub(crate) struct Flags {
+    /// Output the syntax tree as JSON instead of a pretty tree.
+    #[structopt(long)]
+    json: bool,
+
+    #[structopt(flatten)]
+    pub(crate) shared: SharedFlags,
+}
+
+pub(crate) fn run(io: &mut Io<'_>, flags: &Flags, path: &Path) -> Result<ExitCode> {
+    let source =
+        Source::from_path(path).with_context(|| format!("reading file: {}", path.display()))?;
+
+    let mut sources = Sources::new();
+    let source_id = sources.insert(source);
+
+    let source = sources
+        .get(source_id)
+        .context("missing source which was just inserted")?;
+
+    let file = match parse::parse_all::<ast::File>(source.as_str(), source_id, true) {
+        Ok(file) => file,
+        Err(error) => {
+            let mut diagnostics = Diagnostics::new();
+            diagnostics.error(source_id, error);
+            diagnostics.emit(&mut io.stdout.lock(), &sources)?;
+            return Ok(ExitCode::Failure);
+        }
+    };
+
+    let mut o = io.stdout.lock();
+
+    if flags.json {
+        write_json(&mut o, &file)?;
+    } else {
+        writeln!(o, "{:#?}", file)?;
+    }
+
+    Ok(ExitCode::Success)
+}
+
+/// Write the given serializable value as pretty-printed JSON followed by a
+/// newline.
+pub(crate) fn write_json<T>(o: &mut dyn WriteColor, value: &T) -> Result<()>
+where
+    T: serde::Serialize,
+{
+    serde_json::to_writer_pretty(&mut *o, value)?;
+    writeln!(o)?;
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use crate::{Config, ExitCode, Io, SharedFlags};
+use anyhow::{Context, Result};
+use rune::compile::FileSourceLoader;
+use rune::{Diagnostics, Options, Source, Sources};
+use std::io::Write;
+use std::path::Path;
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug, Clone)]
+pub(crate) struct Flags {
+    /// Include source lines with every instruction.
+    #[structopt(long)]
+    with_source: bool,
+
+    /// Print one JSON object per instruction instead of text.
+    #[structopt(long)]
+    json: bool,
+
+    #[structopt(flatten)]
+    pub(crate) shared: SharedFlags,
+}
+
+pub(crate) fn run(
+    io: &mut Io<'_>,
+    c: &Config,
+    flags: &Flags,
+    options: &Options,
+    path: &Path,
+) -> Result<ExitCode> {
+    let context = flags.shared.context(c)?;
+
+    let source =
+        Source::from_path(path).with_context(|| format!("reading file: {}", path.display()))?;
+
+    let mut sources = Sources::new();
+    sources.insert(source);
+
+    let mut diagnostics = if flags.shared.warnings {
+        Diagnostics::new()
+    } else {
+        Diagnostics::without_warnings()
+    };
+
+    let mut source_loader = FileSourceLoader::new();
+
+    let result = rune::prepare(&mut sources)
+        .with_context(&context)
+        .with_diagnostics(&mut diagnostics)
+        .with_options(options)
+        .with_source_loader(&mut source_loader)
+        .build();
+
+    diagnostics.emit(&mut io.stdout.lock(), &sources)?;
+
+    let unit = match result {
+        Ok(unit) => unit,
+        Err(..) => return Ok(ExitCode::Failure),
+    };
+
+    let mut o = io.stdout.lock();
+
+    if flags.json {
+        for record in unit.instructions() {
+            serde_json::to_writer(&mut o, &record)?;
+            writeln!(o)?;
+        }
+    } else {
+        unit.emit_instructions(&mut o, &sources, flags.with_source)?;
+    }
+
+    Ok(ExitCode::Success)
+}
@@ -0,0 +1,168 @@
+use crate::{Config, ExitCode, Io, SharedFlags};
+use anyhow::{Context, Result};
+use rune::ast::{self, Span, Spanned, Visit, Walk};
+use rune::compile::{CompileVisitor, FileSourceLoader};
+use rune::{Diagnostics, Options, Source, SourceId, Sources};
+use std::io::Write;
+use std::path::Path;
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug, Clone)]
+pub(crate) struct Flags {
+    /// Print every macro expansion separately instead of the expanded source.
+    #[structopt(long)]
+    each: bool,
+
+    #[structopt(flatten)]
+    pub(crate) shared: SharedFlags,
+}
+
+/// A compile visitor that records the output of every macro expansion.
+#[derive(Default)]
+struct ExpansionVisitor {
+    expansions: Vec<(SourceId, Span, String)>,
+}
+
+impl CompileVisitor for ExpansionVisitor {
+    fn visit_macro_expansion(&mut self, source_id: SourceId, span: Span, expanded: &str) {
+        self.expansions
+            .push((source_id, span, group_operands(expanded)));
+    }
+}
+
+/// Collects the spans of binary expressions which are operands of another
+/// binary expression.
+#[derive(Default)]
+struct BinaryOperands(Vec<Span>);
+
+impl Visit for BinaryOperands {
+    fn visit_expr_binary(&mut self, node: &ast::ExprBinary) {
+        for operand in [&*node.lhs, &*node.rhs] {
+            if let ast::Expr::Binary(operand) = operand {
+                self.0.push(operand.span());
+            }
+        }
+
+        node.walk(self);
+    }
+}
+
+/// Parenthesize nested binary expressions in the given expansion.
+///
+/// The output of a macro is a flat stream of tokens, so an expression like
+/// `1 + 2` interpolated into `#expr * 2` is parsed as `1 + (2 * 2)`. Making
+/// the grouping explicit shows how the expansion is actually compiled.
+/// Expansions which aren't a single expression are returned as they are.
+fn group_operands(expanded: &str) -> String {
+    let expr = match rune::parse::parse_all::<ast::Expr>(expanded, SourceId::empty(), false) {
+        Ok(expr) => expr,
+        Err(..) => return expanded.to_owned(),
+    };
+
+    let mut operands = BinaryOperands::default();
+    expr.accept(&mut operands);
+
+    // NB: closing parentheses sort before opening ones at the same position.
+    let mut parens = Vec::new();
+
+    for span in operands.0 {
+        let range = span.range();
+        parens.push((range.start, true));
+        parens.push((range.end, false));
+    }
+
+    parens.sort();
+
+    let mut out = String::with_capacity(expanded.len() + parens.len());
+    let mut last = 0;
+
+    for (at, open) in parens {
+        out.push_str(&expanded[last..at]);
+        out.push(if open { '(' } else { ')' });
+        last = at;
+    }
+
+    out.push_str(&expanded[last..]);
+    out
+}
+
+pub(crate) fn run(
+    io: &mut Io<'_>,
+    c: &Config,
+    flags: &Flags,
+    options: &Options,
+    path: &Path,
+) -> Result<ExitCode> {
+    let context = flags.shared.context(c)?;
+
+    let source =
+        Source::from_path(path).with_context(|| format!("reading file: {}", path.display()))?;
+
+    let mut sources = Sources::new();
+    let source_id = sources.insert(source);
+
+    let mut diagnostics = if flags.shared.warnings {
+        Diagnostics::new()
+    } else {
+        Diagnostics::without_warnings()
+    };
+
+    let mut visitor = ExpansionVisitor::default();
+    let mut source_loader = FileSourceLoader::new();
+
+    let _ = rune::prepare(&mut sources)
+        .with_context(&context)
+        .with_diagnostics(&mut diagnostics)
+        .with_options(options)
+        .with_visitor(&mut visitor)
+        .with_source_loader(&mut source_loader)
+        .build();
+
+    diagnostics.emit(&mut io.stdout.lock(), &sources)?;
+
+    if diagnostics.has_error() {
+        return Ok(ExitCode::Failure);
+    }
+
+    let mut o = io.stdout.lock();
+
+    if flags.each {
+        for (id, span, expanded) in &visitor.expansions {
+            let name = sources.name(*id).unwrap_or("?");
+            writeln!(o, "{}:{}: {}", name, span, expanded)?;
+        }
+
+        return Ok(ExitCode::Success);
+    }
+
+    let source = sources
+        .get(source_id)
+        .context("missing source which was just inserted")?;
+
+    let mut expansions = visitor
+        .expansions
+        .iter()
+        .filter(|(id, ..)| *id == source_id)
+        .map(|(_, span, expanded)| (span.range(), expanded))
+        .collect::<Vec<_>>();
+
+    // Outermost expansions first, any expansion nested inside of them is part
+    // of their output and is skipped below.
+    expansions.sort_by(|(a, _), (b, _)| a.start.cmp(&b.start).then(b.end.cmp(&a.end)));
+
+    let text = source.as_str();
+    let mut last = 0;
+
+    for (range, expanded) in expansions {
+        if range.start < last {
+            continue;
+        }
+
+        o.write_all(&text.as_bytes()[last..range.start])?;
+        o.write_all(expanded.as_bytes())?;
+        last = range.end;
+    }
+
+    o.write_all(&text.as_bytes()[last..])?;
+    Ok(ExitCode::Success)
+}
@@ -42,10 +42,16 @@ pub(crate) fn load(
         let f = fs::File::open(&bytecode_path)?;
 
         match bincode::deserialize_from::<_, Unit>(f) {
-            Ok(unit) => {
-                trace!("using cache: {}", bytecode_path.display());
-                Some(Arc::new(unit))
-            }
+            Ok(unit) => match unit.verify() {
+                Ok(()) => {
+                    trace!("using cache: {}", bytecode_path.display());
+                    Some(Arc::new(unit))
+                }
+                Err(e) => {
+                    error!("failed to verify: {}: {}", bytecode_path.display(), e);
+                    None
+                }
+            },
             Err(e) => {
                 error!("failed to deserialize: {}: {}", bytecode_path.display(), e);
                 None
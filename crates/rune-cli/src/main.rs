@@ -61,11 +61,15 @@ use std::path::{Path, PathBuf};
 use structopt::StructOpt;
 use tracing_subscriber::filter::EnvFilter;
 
+mod ast;
 mod benches;
 mod check;
+mod disasm;
+mod expand;
 mod loader;
 mod run;
 mod tests;
+mod tokens;
 mod visitor;
 
 pub const VERSION: &str = include_str!(concat!(env!("OUT_DIR"), "/version.txt"));
@@ -85,6 +89,14 @@ enum Command {
     Bench(benches::Flags),
     /// Run the designated script
     Run(run::Flags),
+    /// Parse the given script and dump its syntax tree
+    Ast(ast::Flags),
+    /// Lex the given script and dump its token stream
+    Tokens(tokens::Flags),
+    /// Compile the given script and print it with all macros expanded
+    Expand(expand::Flags),
+    /// Compile the given script and print its instructions
+    Disasm(disasm::Flags),
 }
 
 impl Command {
@@ -100,6 +112,7 @@ impl Command {
             Command::Run(args) => {
                 args.propagate_related_flags();
             }
+            Command::Ast(..) | Command::Tokens(..) | Command::Expand(..) | Command::Disasm(..) => {}
         }
     }
 
@@ -109,6 +122,10 @@ impl Command {
             Command::Test(..) => "Testing",
             Command::Bench(..) => "Benchmarking",
             Command::Run(..) => "Running",
+            Command::Ast(..) => "Parsing",
+            Command::Tokens(..) => "Lexing",
+            Command::Expand(..) => "Expanding",
+            Command::Disasm(..) => "Disassembling",
         }
     }
 
@@ -118,6 +135,10 @@ impl Command {
             Command::Test(args) => &args.shared,
             Command::Bench(args) => &args.shared,
             Command::Run(args) => &args.shared,
+            Command::Ast(args) => &args.shared,
+            Command::Tokens(args) => &args.shared,
+            Command::Expand(args) => &args.shared,
+            Command::Disasm(args) => &args.shared,
         }
     }
 
@@ -205,6 +226,20 @@ struct SharedFlags {
     /// macros[=<true/false>] - Enable or disable macros (experimental).
     ///
     /// bytecode[=<true/false>] - Enable or disable bytecode caching (experimental).
+    ///
+    /// emit-expansions[=<true/false>] - Report the output of every macro expansion to the compile visitor.
+    ///
+    /// peephole[=<true/false>] - Run the peephole optimizer over the instructions of every function.
+    ///
+    /// fold-constants[=<true/false>] - Fold constant expressions in function bodies at compile time.
+    ///
+    /// fold-budget=<n> - The number of operations which can be spent folding a single expression.
+    ///
+    /// inline[=<true/false>] - Inline calls to small functions at their call sites.
+    ///
+    /// inline-threshold=<n> - The largest number of expressions a function can consist of to be inlined.
+    ///
+    /// const-budget=<n> - The number of operations which can be spent evaluating a single constant item or const fn call.
     #[structopt(name = "option", short = "O", number_of_values = 1)]
     compiler_options: Vec<String>,
 
@@ -321,7 +356,15 @@ impl Args {
                 options.test(true);
                 options.bytecode(false);
             }
-            Command::Bench(_) | Command::Run(_) => (),
+            Command::Expand(_) => {
+                options.emit_expansions(true);
+                options.bytecode(false);
+            }
+            Command::Disasm(_) => {
+                options.debug_info(true);
+                options.bytecode(false);
+            }
+            Command::Bench(_) | Command::Run(_) | Command::Ast(_) | Command::Tokens(_) => (),
         }
 
         for option in &self.cmd.shared().compiler_options {
@@ -618,5 +661,9 @@ async fn run_path(
             let load = loader::load(io, &context, args, options, path, visitor::Attribute::None)?;
             run::run(io, c, flags, &context, load.unit, &load.sources).await
         }
+        Command::Ast(flags) => ast::run(io, flags, path),
+        Command::Tokens(flags) => tokens::run(io, flags, path),
+        Command::Expand(flags) => expand::run(io, c, flags, options, path),
+        Command::Disasm(flags) => disasm::run(io, c, flags, options, path),
     }
 }
@@ -1,8 +1,10 @@
 use crate::{Config, ExitCode, Io, SharedFlags};
 use anyhow::Result;
-use rune::runtime::{VmError, VmExecution};
+use rune::runtime::{Profiler, VmError, VmExecution};
 use rune::{Context, Sources, Unit, Value, Vm};
+use std::fs;
 use std::io::Write;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Instant;
 use structopt::StructOpt;
@@ -50,8 +52,23 @@ pub(crate) struct Flags {
     #[structopt(long)]
     with_source: bool,
 
+    /// Profile the time spent in each script function, and print a summary
+    /// after completion.
+    #[structopt(long)]
+    profile: bool,
+
+    /// Write the profile as collapsed stacks suitable for flamegraph tools to
+    /// the given path. Implies `--profile`.
+    #[structopt(long, parse(from_os_str))]
+    profile_output: Option<PathBuf>,
+
     #[structopt(flatten)]
     pub(crate) shared: SharedFlags,
+
+    /// Arguments passed to the script, which are available through
+    /// `env::args`. These follow a `--` separator.
+    #[structopt(last = true)]
+    args: Vec<String>,
 }
 
 impl Flags {
@@ -65,6 +82,10 @@ impl Flags {
             self.dump_native_functions = true;
             self.dump_native_types = true;
         }
+
+        if self.profile_output.is_some() {
+            self.profile = true;
+        }
     }
 
     fn emit_instructions(&self) -> bool {
@@ -171,6 +192,17 @@ pub(crate) async fn run(
     let last = Instant::now();
 
     let mut vm = Vm::new(runtime, unit);
+    vm.extensions_mut()
+        .insert(rune_modules::env::Args::new(args.args.iter().cloned()));
+
+    let profiler = if args.profile {
+        let profiler = Profiler::new();
+        vm.set_profiler(profiler.clone());
+        Some(profiler)
+    } else {
+        None
+    };
+
     let mut execution: VmExecution<_> = vm.execute(&["main"], ())?;
     let result = if args.trace {
         match do_trace(
@@ -269,6 +301,31 @@ pub(crate) async fn run(
         }
     }
 
+    if let Some(profiler) = &profiler {
+        let profile = profiler.profile();
+
+        writeln!(io.stdout, "# profile")?;
+        writeln!(
+            io.stdout,
+            "{:>12} {:>12} {:>8}  function",
+            "self", "total", "calls"
+        )?;
+
+        for function in profile.functions() {
+            writeln!(
+                io.stdout,
+                "{:>12?} {:>12?} {:>8}  {}",
+                function.self_time, function.total_time, function.calls, function.name
+            )?;
+        }
+
+        if let Some(path) = &args.profile_output {
+            let mut out = Vec::new();
+            profile.write_collapsed(&mut out)?;
+            fs::write(path, out)?;
+        }
+    }
+
     if let Some(error) = errored {
         error.emit(io.stdout, sources)?;
         Ok(ExitCode::VmError)
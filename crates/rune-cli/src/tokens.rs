@@ -0,0 +1,59 @@
+use crate::{ExitCode, Io, SharedFlags};
+use anyhow::{Context, Result};
+use rune::parse::Lexer;
+use rune::{Diagnostics, Source, Sources};
+use std::io::Write;
+use std::path::Path;
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug, Clone)]
+pub(crate) struct Flags {
+    /// Output the token stream as JSON instead of one token per line.
+    #[structopt(long)]
+    json: bool,
+
+    #[structopt(flatten)]
+    pub(crate) shared: SharedFlags,
+}
+
+pub(crate) fn run(io: &mut Io<'_>, flags: &Flags, path: &Path) -> Result<ExitCode> {
+    let source =
+        Source::from_path(path).with_context(|| format!("reading file: {}", path.display()))?;
+
+    let mut sources = Sources::new();
+    let source_id = sources.insert(source);
+
+    let source = sources
+        .get(source_id)
+        .context("missing source which was just inserted")?;
+
+    let mut lexer = Lexer::new(source.as_str(), source_id, true);
+    let mut tokens = Vec::new();
+
+    loop {
+        match lexer.next() {
+            Ok(Some(token)) => tokens.push(token),
+            Ok(None) => break,
+            Err(error) => {
+                let mut diagnostics = Diagnostics::new();
+                diagnostics.error(source_id, error);
+                diagnostics.emit(&mut io.stdout.lock(), &sources)?;
+                return Ok(ExitCode::Failure);
+            }
+        }
+    }
+
+    let mut o = io.stdout.lock();
+
+    if flags.json {
+        crate::ast::write_json(&mut o, &tokens)?;
+        return Ok(ExitCode::Success);
+    }
+
+    for token in &tokens {
+        let text = source.get(token.span.range()).unwrap_or_default();
+        writeln!(o, "{} {:?} {:?}", token.span, token.kind, text)?;
+    }
+
+    Ok(ExitCode::Success)
+}
@@ -0,0 +1,197 @@
+use crate::state::{Definition, DefinitionKind};
+use hashbrown::HashMap;
+use rune::ast::{self, Span, Spanned, Visit, Walk};
+use rune::SourceId;
+use std::collections::BTreeMap;
+
+/// Types inferred for a source.
+#[derive(Debug, Default)]
+pub struct Types {
+    /// Spans of expressions and the type they evaluate to.
+    pub(crate) exprs: Vec<(Span, String)>,
+    /// The types of variables, keyed by the span of the bound identifier.
+    pub(crate) bindings: HashMap<Span, String>,
+    /// Calls through a path, as the span of the path and the spans of the
+    /// arguments.
+    pub(crate) calls: Vec<(Span, Vec<Span>)>,
+}
+
+impl Types {
+    /// Find the type of the innermost expression at the given offset.
+    pub(crate) fn expr_at(&self, offset: usize) -> Option<(Span, &str)> {
+        self.exprs
+            .iter()
+            .filter(|(span, _)| span.start.into_usize() <= offset && offset < span.end.into_usize())
+            .min_by_key(|(span, _)| span.end.into_usize() - span.start.into_usize())
+            .map(|(span, ty)| (*span, ty.as_str()))
+    }
+}
+
+/// Infer the types of the expressions and variables in the given source text,
+/// using what the compiler resolved paths in it to.
+///
+/// This is best-effort, expressions whose type can't be determined without
+/// evaluating them are skipped.
+pub(crate) fn infer(text: &str, definitions: &BTreeMap<Span, Definition>) -> Types {
+    let file = match rune::parse::parse_all::<ast::File>(text, SourceId::new(0), true) {
+        Ok(file) => file,
+        Err(..) => return Types::default(),
+    };
+
+    let mut inferrer = Inferrer {
+        text,
+        definitions,
+        types: Types::default(),
+    };
+
+    file.accept(&mut inferrer);
+    inferrer.types
+}
+
+struct Inferrer<'a> {
+    text: &'a str,
+    definitions: &'a BTreeMap<Span, Definition>,
+    types: Types,
+}
+
+impl Inferrer<'_> {
+    /// Infer the type of the given expression.
+    fn expr(&self, expr: &ast::Expr) -> Option<String> {
+        let ty = match expr {
+            ast::Expr::Lit(lit) => match &lit.lit {
+                ast::Lit::Bool(..) => "bool",
+                ast::Lit::Byte(..) => "byte",
+                ast::Lit::Str(..) => "String",
+                ast::Lit::ByteStr(..) => "Bytes",
+                ast::Lit::Char(..) => "char",
+                ast::Lit::Number(number) => match number.source {
+                    ast::NumberSource::Text(text) if text.is_fractional => "float",
+                    _ => "int",
+                },
+                _ => return None,
+            },
+            ast::Expr::Vec(..) => "Vec",
+            ast::Expr::Tuple(..) => "Tuple",
+            ast::Expr::Range(..) => "Range",
+            ast::Expr::Closure(..) => "Function",
+            ast::Expr::Object(object) => match &object.ident {
+                ast::ObjectIdent::Anonymous(..) => "Object",
+                ast::ObjectIdent::Named(path) => return self.path(path),
+                _ => return None,
+            },
+            ast::Expr::Group(group) => return self.expr(&group.expr),
+            ast::Expr::Unary(unary) => match unary.op {
+                ast::UnOp::Not(..) | ast::UnOp::Neg(..) => return self.expr(&unary.expr),
+                _ => return None,
+            },
+            ast::Expr::Binary(binary) => match binary.op {
+                ast::BinOp::Eq(..)
+                | ast::BinOp::Neq(..)
+                | ast::BinOp::Gt(..)
+                | ast::BinOp::Lt(..)
+                | ast::BinOp::Gte(..)
+                | ast::BinOp::Lte(..)
+                | ast::BinOp::Is(..)
+                | ast::BinOp::IsNot(..)
+                | ast::BinOp::And(..)
+                | ast::BinOp::Or(..) => "bool",
+                ast::BinOp::DotDot(..) | ast::BinOp::DotDotEq(..) => "Range",
+                op if op.is_assign() => return None,
+                _ => {
+                    let lhs = self.expr(&binary.lhs)?;
+
+                    return match self.expr(&binary.rhs) {
+                        Some(rhs) if rhs != lhs => None,
+                        _ => Some(lhs),
+                    };
+                }
+            },
+            ast::Expr::Call(call) => match &*call.expr {
+                ast::Expr::Path(path) => match self.path_text(path) {
+                    "Some" => "Option",
+                    "Ok" | "Err" => "Result",
+                    _ => {
+                        let definition = self.definitions.get(&path.span())?;
+
+                        return match definition.kind {
+                            DefinitionKind::TupleStruct | DefinitionKind::TupleVariant => {
+                                self.path(path)
+                            }
+                            _ => None,
+                        };
+                    }
+                },
+                _ => return None,
+            },
+            ast::Expr::Path(path) => match self.path_text(path) {
+                "None" => "Option",
+                _ => return self.path(path),
+            },
+            ast::Expr::MacroCall(call) => match self.path_text(&call.path) {
+                "format" | "stringify" | "file" => "String",
+                "line" => "int",
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        Some(ty.to_owned())
+    }
+
+    /// Infer the type that the given path evaluates to, through what the
+    /// compiler resolved it to.
+    fn path(&self, path: &ast::Path) -> Option<String> {
+        let definition = self.definitions.get(&path.span())?;
+
+        match definition.kind {
+            DefinitionKind::Local => {
+                let span = definition.source.span();
+                self.types.bindings.get(&span).cloned()
+            }
+            DefinitionKind::UnitStruct | DefinitionKind::TupleStruct | DefinitionKind::Struct => {
+                Some(definition.item.as_ref()?.to_string())
+            }
+            DefinitionKind::UnitVariant
+            | DefinitionKind::TupleVariant
+            | DefinitionKind::StructVariant => {
+                let mut item = definition.item.clone()?;
+                item.pop()?;
+                Some(item.to_string())
+            }
+            _ => None,
+        }
+    }
+
+    fn path_text(&self, path: &ast::Path) -> &str {
+        self.text.get(path.span().range()).unwrap_or_default()
+    }
+}
+
+impl Visit for Inferrer<'_> {
+    fn visit_local(&mut self, node: &ast::Local) {
+        node.walk(self);
+
+        if let ast::Pat::PatPath(pat) = &node.pat {
+            if let Some(ident) = pat.path.try_as_ident() {
+                if let Some(ty) = self.expr(&node.expr) {
+                    self.types.bindings.insert(ident.span, ty);
+                }
+            }
+        }
+    }
+
+    fn visit_expr(&mut self, node: &ast::Expr) {
+        node.walk(self);
+
+        if let ast::Expr::Call(call) = node {
+            if let ast::Expr::Path(path) = &*call.expr {
+                let args = call.args.iter().map(|(arg, _)| arg.span()).collect();
+                self.types.calls.push((path.span(), args));
+            }
+        }
+
+        if let Some(ty) = self.expr(node) {
+            self.types.exprs.push((node.span(), ty));
+        }
+    }
+}
@@ -46,8 +46,11 @@
 
 mod connection;
 pub mod envelope;
+mod infer;
+pub mod protocol;
 mod server;
 mod state;
+mod symbols;
 
 pub const VERSION: &str = include_str!(concat!(env!("OUT_DIR"), "/version.txt"));
 
@@ -66,9 +69,16 @@ pub fn run(context: Context, options: Options) -> Result<()> {
 
     let mut server = Server::new(output, rebuild_tx, context, options);
 
-    server.request_handler::<lsp::request::Initialize, _, _>(initialize);
+    server.request_handler::<protocol::Initialize, _, _>(initialize);
 
     server.request_handler::<lsp::request::GotoDefinition, _, _>(goto_definition);
+    server.request_handler::<lsp::request::References, _, _>(references);
+    server.request_handler::<lsp::request::DocumentSymbolRequest, _, _>(document_symbols);
+    server.request_handler::<lsp::request::Rename, _, _>(rename);
+    server.request_handler::<lsp::request::Completion, _, _>(completion);
+    server.request_handler::<lsp::request::HoverRequest, _, _>(hover);
+    server.request_handler::<protocol::InlayHintRequest, _, _>(inlay_hints);
+    server.request_handler::<lsp::request::SemanticTokensFullRequest, _, _>(semantic_tokens);
 
     server.notification_handler::<lsp::notification::DidOpenTextDocument, _, _>(
         did_open_text_document,
@@ -112,7 +122,7 @@ async fn initialize(
     state: State,
     output: Output,
     _: lsp::InitializeParams,
-) -> Result<lsp::InitializeResult> {
+) -> Result<protocol::InitializeResult> {
     state.initialize();
 
     output
@@ -124,6 +134,22 @@ async fn initialize(
             lsp::TextDocumentSyncKind::INCREMENTAL,
         )),
         definition_provider: Some(lsp::OneOf::Left(true)),
+        references_provider: Some(lsp::OneOf::Left(true)),
+        document_symbol_provider: Some(lsp::OneOf::Left(true)),
+        rename_provider: Some(lsp::OneOf::Left(true)),
+        hover_provider: Some(lsp::HoverProviderCapability::Simple(true)),
+        completion_provider: Some(lsp::CompletionOptions {
+            trigger_characters: Some(vec![String::from("."), String::from(":")]),
+            ..Default::default()
+        }),
+        semantic_tokens_provider: Some(
+            lsp::SemanticTokensOptions {
+                legend: state::semantic_tokens_legend(),
+                full: Some(lsp::SemanticTokensFullOptions::Bool(true)),
+                ..Default::default()
+            }
+            .into(),
+        ),
         ..Default::default()
     };
 
@@ -132,7 +158,12 @@ async fn initialize(
         version: None,
     };
 
-    Ok(lsp::InitializeResult {
+    let capabilities = protocol::ServerCapabilities {
+        base: capabilities,
+        inlay_hint_provider: Some(true),
+    };
+
+    Ok(protocol::InitializeResult {
         capabilities,
         server_info: Some(server_info),
     })
@@ -160,6 +191,113 @@ async fn goto_definition(
     Ok(position.map(lsp::GotoDefinitionResponse::Scalar))
 }
 
+/// Handle find references request.
+async fn references(
+    state: State,
+    _: Output,
+    params: lsp::ReferenceParams,
+) -> Result<Option<Vec<lsp::Location>>> {
+    let locations = state
+        .references(
+            &params.text_document_position.text_document.uri,
+            params.text_document_position.position,
+            params.context.include_declaration,
+        )
+        .await;
+
+    Ok(locations)
+}
+
+/// Handle document symbols request.
+async fn document_symbols(
+    state: State,
+    _: Output,
+    params: lsp::DocumentSymbolParams,
+) -> Result<Option<lsp::DocumentSymbolResponse>> {
+    let symbols = state.document_symbols(&params.text_document.uri).await;
+    Ok(symbols.map(lsp::DocumentSymbolResponse::Flat))
+}
+
+/// Handle rename request.
+async fn rename(
+    state: State,
+    output: Output,
+    params: lsp::RenameParams,
+) -> Result<Option<lsp::WorkspaceEdit>> {
+    let position = params.text_document_position;
+
+    let result = state
+        .rename(
+            &position.text_document.uri,
+            position.position,
+            &params.new_name,
+        )
+        .await;
+
+    match result {
+        Ok(edit) => Ok(edit),
+        Err(error) => {
+            output
+                .notification::<lsp::notification::ShowMessage>(lsp::ShowMessageParams {
+                    typ: lsp::MessageType::WARNING,
+                    message: format!("Cannot rename: {}", error),
+                })
+                .await?;
+
+            Ok(None)
+        }
+    }
+}
+
+/// Handle completion request.
+async fn completion(
+    state: State,
+    _: Output,
+    params: lsp::CompletionParams,
+) -> Result<Option<lsp::CompletionResponse>> {
+    let position = params.text_document_position;
+
+    let results = state
+        .complete(&position.text_document.uri, position.position)
+        .await;
+
+    Ok(results.map(lsp::CompletionResponse::Array))
+}
+
+/// Handle hover request.
+async fn hover(state: State, _: Output, params: lsp::HoverParams) -> Result<Option<lsp::Hover>> {
+    let position = params.text_document_position_params;
+
+    let hover = state
+        .hover(&position.text_document.uri, position.position)
+        .await;
+
+    Ok(hover)
+}
+
+/// Handle inlay hints request.
+async fn inlay_hints(
+    state: State,
+    _: Output,
+    params: protocol::InlayHintParams,
+) -> Result<Option<Vec<protocol::InlayHint>>> {
+    let hints = state
+        .inlay_hints(&params.text_document.uri, params.range)
+        .await;
+
+    Ok(hints)
+}
+
+/// Handle semantic tokens request.
+async fn semantic_tokens(
+    state: State,
+    _: Output,
+    params: lsp::SemanticTokensParams,
+) -> Result<Option<lsp::SemanticTokensResult>> {
+    let tokens = state.semantic_tokens(&params.text_document.uri).await;
+    Ok(tokens.map(lsp::SemanticTokensResult::Tokens))
+}
+
 /// Handle open text document.
 async fn did_open_text_document(
     state: State,
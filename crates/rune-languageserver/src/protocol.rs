@@ -0,0 +1,86 @@
+//! Protocol extensions from newer versions of the language server protocol
+//! which are not yet provided by `lsp-types`.
+
+use serde::{Deserialize, Serialize};
+
+/// The `initialize` request, answered with the extended set of capabilities.
+pub enum Initialize {}
+
+impl lsp::request::Request for Initialize {
+    type Params = lsp::InitializeParams;
+    type Result = InitializeResult;
+    const METHOD: &'static str = <lsp::request::Initialize as lsp::request::Request>::METHOD;
+}
+
+/// The result of the `initialize` request.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitializeResult {
+    /// The capabilities of the server.
+    pub capabilities: ServerCapabilities,
+    /// Information about the server.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_info: Option<lsp::ServerInfo>,
+}
+
+/// Server capabilities, including the ones `lsp-types` doesn't know about.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerCapabilities {
+    /// Capabilities known to `lsp-types`.
+    #[serde(flatten)]
+    pub base: lsp::ServerCapabilities,
+    /// The server provides inlay hints.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inlay_hint_provider: Option<bool>,
+}
+
+/// The `textDocument/inlayHint` request.
+pub enum InlayHintRequest {}
+
+impl lsp::request::Request for InlayHintRequest {
+    type Params = InlayHintParams;
+    type Result = Option<Vec<InlayHint>>;
+    const METHOD: &'static str = "textDocument/inlayHint";
+}
+
+/// Parameters of the `textDocument/inlayHint` request.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlayHintParams {
+    /// The text document.
+    pub text_document: lsp::TextDocumentIdentifier,
+    /// The visible document range for which inlay hints should be computed.
+    pub range: lsp::Range,
+}
+
+/// An inlay hint.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlayHint {
+    /// The position of the hint.
+    pub position: lsp::Position,
+    /// The label of the hint.
+    pub label: String,
+    /// The kind of the hint.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<InlayHintKind>,
+    /// Render padding before the hint.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub padding_left: Option<bool>,
+    /// Render padding after the hint.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub padding_right: Option<bool>,
+}
+
+/// The kind of an inlay hint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct InlayHintKind(i32);
+
+impl InlayHintKind {
+    /// A hint for the type of a binding.
+    pub const TYPE: InlayHintKind = InlayHintKind(1);
+    /// A hint for the name of a parameter.
+    pub const PARAMETER: InlayHintKind = InlayHintKind(2);
+}
@@ -1,15 +1,19 @@
+use crate::infer::{self, Types};
+use crate::protocol::{InlayHint, InlayHintKind};
+use crate::symbols::{self, Symbol};
 use crate::Output;
 use anyhow::{anyhow, Result};
 use hashbrown::HashMap;
 use lsp::Url;
 use ropey::Rope;
-use rune::ast::{Span, Spanned};
+use rune::ast::{self, Span, Spanned};
 use rune::compile::{
-    CompileError, CompileVisitor, ComponentRef, FileSourceLoader, Item, LinkerError, Location,
-    MetaKind, MetaRef, SourceMeta,
+    CompileError, CompileVisitor, ComponentRef, ContextSignature, FileSourceLoader, Item,
+    LinkerError, Location, MetaKind, MetaRef, SourceMeta,
 };
 use rune::diagnostics::{Diagnostic, FatalDiagnosticKind};
-use rune::{Context, Options, SourceId};
+use rune::semantic::{SemanticKind, SemanticToken, SemanticTokens};
+use rune::{Context, InstFnKind, Options, SourceId};
 use std::collections::BTreeMap;
 use std::fmt;
 use std::path::Path;
@@ -104,8 +108,396 @@ impl State {
         Some(location)
     }
 
-    /// Rebuild the current project.
+    /// Find all references to the symbol at the given uri and LSP position,
+    /// across all open sources.
+    pub async fn references(
+        &self,
+        uri: &Url,
+        position: lsp::Position,
+        include_declaration: bool,
+    ) -> Option<Vec<lsp::Location>> {
+        let sources = self.inner.sources.read().await;
+
+        let source = sources.get(uri)?;
+        let offset = source.lsp_position_to_offset(position);
+        let target = source.target_at(uri, offset)?;
+
+        let mut locations = Vec::new();
+
+        for (url, other) in &sources.sources {
+            if include_declaration && *url == target.0 {
+                if let Some(symbol) = other.symbol_declared_at(target.1) {
+                    let range = other.span_to_lsp_range(symbol.name_span);
+                    locations.push(lsp::Location::new(url.clone(), range));
+                }
+            }
+
+            for (span, definition) in &other.index.definitions {
+                if other.definition_target(url, definition).as_ref() == Some(&target) {
+                    let range = other.span_to_lsp_range(*span);
+                    locations.push(lsp::Location::new(url.clone(), range));
+                }
+            }
+        }
+
+        Some(locations)
+    }
+
+    /// Rename the symbol at the given uri and LSP position, producing the
+    /// workspace edit that renames its declaration and every reference to it.
+    ///
+    /// The rename is refused if the new name isn't a valid identifier, if it
+    /// collides with an item in the same scope or with a name provided by the
+    /// native context, or if it would change what any reference resolves to.
+    pub async fn rename(
+        &self,
+        uri: &Url,
+        position: lsp::Position,
+        new_name: &str,
+    ) -> Result<Option<lsp::WorkspaceEdit>, RenameError> {
+        let sources = self.inner.sources.read().await;
+
+        let source = match sources.get(uri) {
+            Some(source) => source,
+            None => return Ok(None),
+        };
+
+        let offset = source.lsp_position_to_offset(position);
+
+        let target = match source.target_at(uri, offset) {
+            Some(target) => target,
+            None => return Ok(None),
+        };
+
+        let declaring = sources.get(&target.0).ok_or(RenameError::NotOpen)?;
+        let symbol = declaring
+            .symbol_declared_at(target.1)
+            .ok_or(RenameError::NotOpen)?;
+
+        if rune::parse::parse_all::<ast::Ident>(new_name, SourceId::empty(), false).is_err() {
+            return Err(RenameError::InvalidName(new_name.to_owned()));
+        }
+
+        let is_variable = symbol.kind == lsp::SymbolKind::VARIABLE;
+
+        if !is_variable {
+            if self.inner.context.contains_name(new_name) {
+                return Err(RenameError::ContextConflict(new_name.to_owned()));
+            }
+
+            let conflict = declaring.index.symbols.iter().any(|s| {
+                s.kind != lsp::SymbolKind::VARIABLE
+                    && s.container == symbol.container
+                    && s.name == new_name
+            });
+
+            if conflict {
+                return Err(RenameError::Conflict(new_name.to_owned()));
+            }
+        }
+
+        // Variables are only visible after they've been declared, while items
+        // are visible everywhere in their scope.
+        let declared_at = if is_variable {
+            symbol.span.start.into_usize()
+        } else {
+            0
+        };
+
+        if is_variable && declaring.is_shadowed_by(symbol, new_name) {
+            return Err(RenameError::Shadowing(new_name.to_owned()));
+        }
+
+        let mut changes = std::collections::HashMap::<Url, Vec<lsp::TextEdit>>::new();
+
+        changes
+            .entry(target.0.clone())
+            .or_default()
+            .push(lsp::TextEdit::new(
+                declaring.span_to_lsp_range(symbol.name_span),
+                new_name.to_owned(),
+            ));
+
+        for (url, other) in &sources.sources {
+            for (span, definition) in &other.index.definitions {
+                if other.definition_target(url, definition).as_ref() != Some(&target) {
+                    continue;
+                }
+
+                let span = match other.find_ident(*span, &symbol.name) {
+                    Some(span) => span,
+                    None => continue,
+                };
+
+                let after = if *url == target.0 { declared_at } else { 0 };
+
+                if other.binding_in_scope(span.start.into_usize(), new_name, after) {
+                    return Err(RenameError::Shadowing(new_name.to_owned()));
+                }
+
+                changes
+                    .entry(url.clone())
+                    .or_default()
+                    .push(lsp::TextEdit::new(
+                        other.span_to_lsp_range(span),
+                        new_name.to_owned(),
+                    ));
+            }
+        }
+
+        for edits in changes.values_mut() {
+            edits.sort_by_key(|e| (e.range.start.line, e.range.start.character));
+            edits.dedup_by_key(|e| e.range);
+        }
+
+        Ok(Some(lsp::WorkspaceEdit::new(changes)))
+    }
+
+    /// Complete the code at the given uri and LSP position.
+    pub async fn complete(
+        &self,
+        uri: &Url,
+        position: lsp::Position,
+    ) -> Option<Vec<lsp::CompletionItem>> {
+        let sources = self.inner.sources.read().await;
+        let source = sources.get(uri)?;
+
+        let offset = source.lsp_position_to_offset(position);
+        let context = &self.inner.context;
+
+        let mut results = Vec::new();
+
+        match source.completion_at(offset) {
+            Completion::Instance => {
+                complete_instance_fns(context, &source.index, &mut results);
+            }
+            Completion::Path(path) => {
+                complete_path(context, &source.index, &path, &mut results);
+            }
+            Completion::Ident => {
+                source.complete_in_scope(offset, &mut results);
+                complete_path(context, &source.index, &[], &mut results);
+
+                for (name, item) in context.iter_prelude() {
+                    results.push(context_completion(context, name, &item));
+                }
+            }
+        }
+
+        Some(results)
+    }
+
+    /// Describe what is at the given uri and LSP position.
+    pub async fn hover(&self, uri: &Url, position: lsp::Position) -> Option<lsp::Hover> {
+        let sources = self.inner.sources.read().await;
+        let source = sources.get(uri)?;
+        let offset = source.lsp_position_to_offset(position);
+
+        let declaration = source.target_at(uri, offset).and_then(|(url, span)| {
+            let declaring = sources.get(&url)?;
+            Some((declaring, declaring.symbol_declared_at(span)?))
+        });
+
+        let (span, value) = if let Some((declaring, symbol)) = declaration {
+            let mut signature = match &symbol.detail {
+                Some(detail) => detail.clone(),
+                None => format!("let {}", symbol.name),
+            };
+
+            if let Some(ty) = declaring.index.types.bindings.get(&symbol.span) {
+                signature.push_str(": ");
+                signature.push_str(ty);
+            }
+
+            let mut value = format!("```rune\n{}\n```", signature);
+
+            if let Some(docs) = &symbol.docs {
+                value.push_str("\n\n");
+                value.push_str(docs);
+            }
+
+            let span = source
+                .definition_span_at(offset)
+                .unwrap_or(symbol.name_span);
+
+            (span, value)
+        } else {
+            let (span, ty) = source.index.types.expr_at(offset)?;
+            (span, format!("```rune\n{}\n```", ty))
+        };
+
+        Some(lsp::Hover {
+            contents: lsp::HoverContents::Markup(lsp::MarkupContent {
+                kind: lsp::MarkupKind::Markdown,
+                value,
+            }),
+            range: Some(source.span_to_lsp_range(span)),
+        })
+    }
+
+    /// Get the inlay hints of the given uri in the given range, naming the
+    /// parameters at call sites and the inferred types of variables.
+    pub async fn inlay_hints(&self, uri: &Url, range: lsp::Range) -> Option<Vec<InlayHint>> {
+        let sources = self.inner.sources.read().await;
+        let source = sources.get(uri)?;
+
+        let start = source.lsp_position_to_offset(range.start);
+        let end = source.lsp_position_to_offset(range.end);
+        let in_range =
+            |span: &Span| start <= span.start.into_usize() && span.end.into_usize() <= end;
+
+        let mut hints = Vec::new();
+
+        for (span, ty) in &source.index.types.bindings {
+            if in_range(span) {
+                hints.push(InlayHint {
+                    position: source.offset_to_lsp_position(span.end.into_usize()),
+                    label: format!(": {}", ty),
+                    kind: Some(InlayHintKind::TYPE),
+                    padding_left: None,
+                    padding_right: None,
+                });
+            }
+        }
+
+        for (path, args) in &source.index.types.calls {
+            if !in_range(path) {
+                continue;
+            }
+
+            let callee = source.index.definitions.get(path).and_then(|definition| {
+                if !matches!(definition.kind, DefinitionKind::Function) {
+                    return None;
+                }
+
+                let (url, span) = source.definition_target(uri, definition)?;
+                sources.get(&url)?.symbol_declared_at(span)
+            });
+
+            let callee = match callee {
+                Some(callee) => callee,
+                None => continue,
+            };
+
+            for (name, arg) in callee.args.iter().zip(args) {
+                if source.text_at(*arg) == Some(name.as_str()) {
+                    continue;
+                }
+
+                hints.push(InlayHint {
+                    position: source.offset_to_lsp_position(arg.start.into_usize()),
+                    label: format!("{}:", name),
+                    kind: Some(InlayHintKind::PARAMETER),
+                    padding_left: None,
+                    padding_right: Some(true),
+                });
+            }
+        }
+
+        hints.sort_by_key(|h| (h.position.line, h.position.character));
+        Some(hints)
+    }
+
+    /// Get the symbols declared in the given uri, as of the last build.
+    pub async fn document_symbols(&self, uri: &Url) -> Option<Vec<lsp::SymbolInformation>> {
+        let sources = self.inner.sources.read().await;
+        let source = sources.get(uri)?;
+
+        let mut output = Vec::new();
+
+        for symbol in &source.index.symbols {
+            let location = lsp::Location::new(uri.clone(), source.span_to_lsp_range(symbol.span));
+
+            #[allow(deprecated)]
+            output.push(lsp::SymbolInformation {
+                name: symbol.name.clone(),
+                kind: symbol.kind,
+                tags: None,
+                deprecated: None,
+                location,
+                container_name: symbol.container.clone(),
+            });
+        }
+
+        Some(output)
+    }
+
+    /// Get the semantic tokens of the given uri, as of the last build.
+    pub async fn semantic_tokens(&self, uri: &Url) -> Option<lsp::SemanticTokens> {
+        let sources = self.inner.sources.read().await;
+
+        let source = sources.get(uri)?;
+        let built = source.built()?;
+
+        let mut data = Vec::new();
+        let mut last_line = 0;
+        let mut last_character = 0;
+
+        for token in &source.index.semantic_tokens {
+            let text = built.get(token.span.range())?;
+            let (mut line, mut character) =
+                built.position_to_utf16cu_line_char(token.span.start.into_usize())?;
+
+            // Tokens spanning multiple lines, like multiline comments, have to
+            // be split up since not every client supports them.
+            for (n, part) in text.split('\n').enumerate() {
+                if n > 0 {
+                    line += 1;
+                    character = 0;
+                }
+
+                let length = part.trim_end_matches('\r').encode_utf16().count();
+
+                if length == 0 {
+                    continue;
+                }
+
+                let delta_line = line - last_line;
+
+                let delta_start = if delta_line == 0 {
+                    character - last_character
+                } else {
+                    character
+                };
+
+                data.push(lsp::SemanticToken {
+                    delta_line: delta_line as u32,
+                    delta_start: delta_start as u32,
+                    length: length as u32,
+                    token_type: token.kind.index() as u32,
+                    token_modifiers_bitset: semantic_token_modifiers(token.kind),
+                });
+
+                last_line = line;
+                last_character = character;
+            }
+        }
+
+        Some(lsp::SemanticTokens {
+            result_id: None,
+            data,
+        })
+    }
+
+    /// Rebuild the current project and publish its diagnostics.
     pub async fn rebuild(&self, output: &Output) -> Result<()> {
+        for (url, diagnostics) in self.build().await {
+            let diagnostics = lsp::PublishDiagnosticsParams {
+                uri: url.clone(),
+                diagnostics,
+                version: None,
+            };
+
+            output
+                .notification::<lsp::notification::PublishDiagnostics>(diagnostics)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Build the current project, returning the diagnostics of every source.
+    async fn build(&self) -> HashMap<Url, Vec<lsp::Diagnostic>> {
         let mut inner = self.inner.sources.write().await;
 
         let mut by_url = HashMap::<Url, Vec<lsp::Diagnostic>>::new();
@@ -116,8 +508,7 @@ impl State {
 
         let mut builds = Vec::new();
 
-        let sources = std::mem::take(&mut inner.sources);
-        let mut source_loader = SourceLoader::new(sources);
+        let mut source_loader = SourceLoader::new(&inner.sources);
 
         for (url, source) in &inner.sources {
             tracing::trace!("build: {}", url);
@@ -131,10 +522,13 @@ impl State {
                 url.to_file_path().ok(),
             );
 
-            sources.insert(input);
+            let source_id = sources.insert(input);
 
             let mut diagnostics = rune::Diagnostics::new();
-            let mut visitor = Visitor::new(Index::default());
+            let mut visitor = Visitor::new(Index {
+                source_id,
+                ..Index::default()
+            });
 
             let _ = rune::prepare(&mut sources)
                 .with_context(&self.inner.context)
@@ -224,11 +618,10 @@ impl State {
                 }
             }
 
-            builds.push((url.clone(), sources, visitor.into_index()));
+            let index = visitor.into_index(&sources);
+            builds.push((url.clone(), sources, index));
         }
 
-        inner.sources = source_loader.into_sources();
-
         for (url, build_sources, index) in builds {
             if let Some(source) = inner.sources.get_mut(&url) {
                 source.index = index;
@@ -236,19 +629,7 @@ impl State {
             }
         }
 
-        for (url, diagnostics) in by_url {
-            let diagnostics = lsp::PublishDiagnosticsParams {
-                uri: url.clone(),
-                diagnostics,
-                version: None,
-            };
-
-            output
-                .notification::<lsp::notification::PublishDiagnostics>(diagnostics)
-                .await?;
-        }
-
-        Ok(())
+        by_url
     }
 }
 
@@ -317,9 +698,201 @@ pub struct Source {
 }
 
 impl Source {
+    /// Get the source as of the last build.
+    fn built(&self) -> Option<&rune::Source> {
+        self.build_sources.as_ref()?.get(self.index.source_id)
+    }
+
+    /// Find the url and span of the declaration that the given position
+    /// refers to, either through a use of it or by being on its name.
+    fn target_at(&self, uri: &Url, offset: usize) -> Option<(Url, Span)> {
+        let span = Span::point(offset);
+
+        if let Some(definition) = self.find_definition_at(span) {
+            return self.definition_target(uri, definition);
+        }
+
+        let symbol = self
+            .index
+            .symbols
+            .iter()
+            .find(|s| s.name_span.start <= span.start && span.end <= s.name_span.end)?;
+
+        Some((uri.clone(), symbol.span))
+    }
+
+    /// Resolve the url and span of the declaration of the given definition
+    /// found in this source.
+    fn definition_target(&self, uri: &Url, definition: &Definition) -> Option<(Url, Span)> {
+        let path = match definition.source.path() {
+            Some(path) => Some(path),
+            None => self
+                .build_sources
+                .as_ref()?
+                .path(definition.source.source_id()),
+        };
+
+        let url = match path {
+            Some(path) => Url::from_file_path(path).ok()?,
+            None => uri.clone(),
+        };
+
+        Some((url, definition.source.span()))
+    }
+
+    /// Find the symbol whose declaration has the given span.
+    fn symbol_declared_at(&self, span: Span) -> Option<&Symbol> {
+        self.index.symbols.iter().find(|s| s.span == span)
+    }
+
+    /// Find the span of the last identifier `name` inside of the given span,
+    /// as of the last build.
+    fn find_ident(&self, span: Span, name: &str) -> Option<Span> {
+        let source = self.built()?;
+        let start = self.index.idents.partition_point(|s| s.start < span.start);
+
+        self.index.idents[start..]
+            .iter()
+            .take_while(|s| s.start < span.end)
+            .filter(|s| s.end <= span.end && source.get(s.range()) == Some(name))
+            .last()
+            .copied()
+    }
+
+    /// Get the text at the given span, as of the last build.
+    fn text_at(&self, span: Span) -> Option<&str> {
+        self.built()?.get(span.range())
+    }
+
+    /// Find the span of the innermost function containing the given offset.
+    fn enclosing_function(&self, offset: usize) -> Option<Span> {
+        self.index
+            .symbols
+            .iter()
+            .filter(|s| s.kind == lsp::SymbolKind::FUNCTION)
+            .map(|s| s.span)
+            .filter(|span| span.start.into_usize() <= offset && offset < span.end.into_usize())
+            .min_by_key(|span| span.end.into_usize() - span.start.into_usize())
+    }
+
+    /// Test if a variable named `name` declared after the offset `after` is
+    /// in scope at the given offset.
+    fn binding_in_scope(&self, offset: usize, name: &str, after: usize) -> bool {
+        let scope = self.enclosing_function(offset);
+
+        self.index.symbols.iter().any(|s| {
+            let start = s.span.start.into_usize();
+
+            s.kind == lsp::SymbolKind::VARIABLE
+                && s.name == name
+                && after < start
+                && start < offset
+                && self.enclosing_function(start) == scope
+        })
+    }
+
+    /// Test if renaming the given variable to `name` would make it shadow
+    /// uses of an existing variable with that name.
+    fn is_shadowed_by(&self, symbol: &Symbol, name: &str) -> bool {
+        let declared_at = symbol.span.start.into_usize();
+        let scope = self.enclosing_function(declared_at);
+
+        self.index.definitions.iter().any(|(span, definition)| {
+            if !matches!(definition.kind, DefinitionKind::Local) {
+                return false;
+            }
+
+            let other = match self.symbol_declared_at(definition.source.span()) {
+                Some(other) => other,
+                None => return false,
+            };
+
+            other.name == name
+                && other.span.start.into_usize() < declared_at
+                && declared_at < span.start.into_usize()
+                && self.enclosing_function(span.start.into_usize()) == scope
+        })
+    }
+
+    /// Determine what kind of completion is requested at the given offset by
+    /// looking at the text preceding it on the same line.
+    fn completion_at(&self, offset: usize) -> Completion {
+        let end = self.content.byte_to_char(offset);
+        let start = self.content.line_to_char(self.content.char_to_line(end));
+        let line = self.content.slice(start..end).to_string();
+
+        let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+        let mut rest = line.trim_end_matches(is_ident);
+
+        if rest.ends_with('.') {
+            return Completion::Instance;
+        }
+
+        let mut path = Vec::new();
+
+        while let Some(prefix) = rest.strip_suffix("::") {
+            let segment = prefix.trim_end_matches(is_ident);
+
+            if segment.len() == prefix.len() {
+                break;
+            }
+
+            path.push(prefix[segment.len()..].to_owned());
+            rest = segment;
+        }
+
+        if path.is_empty() {
+            return Completion::Ident;
+        }
+
+        path.reverse();
+        Completion::Path(path)
+    }
+
+    /// Complete the variables in scope at the given offset, and the items
+    /// declared in the source.
+    fn complete_in_scope(&self, offset: usize, results: &mut Vec<lsp::CompletionItem>) {
+        let scope = self.enclosing_function(offset);
+
+        for symbol in &self.index.symbols {
+            let start = symbol.span.start.into_usize();
+
+            let visible = match symbol.kind {
+                lsp::SymbolKind::VARIABLE => {
+                    start < offset && self.enclosing_function(start) == scope
+                }
+                lsp::SymbolKind::ENUM_MEMBER => false,
+                _ => true,
+            };
+
+            if visible {
+                results.push(symbol_completion(symbol));
+            }
+        }
+    }
+
+    /// Find the span of the use of a definition at the given offset.
+    fn definition_span_at(&self, offset: usize) -> Option<Span> {
+        let (span, _) = self
+            .index
+            .definitions
+            .range(..=Span::new(offset, u32::MAX))
+            .next_back()?;
+
+        if span.start.into_usize() <= offset && offset < span.end.into_usize() {
+            Some(*span)
+        } else {
+            None
+        }
+    }
+
     /// Find the definition at the given span.
     pub fn find_definition_at(&self, span: Span) -> Option<&Definition> {
-        let (found_span, definition) = self.index.definitions.range(..=span).rev().next()?;
+        let (found_span, definition) = self
+            .index
+            .definitions
+            .range(..=Span::new(span.start, u32::MAX))
+            .next_back()?;
 
         if span.start >= found_span.start && span.end <= found_span.end {
             tracing::trace!("found {:?}", definition);
@@ -494,10 +1067,234 @@ where
     }
 }
 
+/// The legend of the semantic tokens produced by [State::semantic_tokens].
+///
+/// Token types are indexed by [SemanticKind::index].
+pub(crate) fn semantic_tokens_legend() -> lsp::SemanticTokensLegend {
+    let token_types = SemanticKind::ALL
+        .iter()
+        .map(|kind| match kind {
+            SemanticKind::Comment => lsp::SemanticTokenType::COMMENT,
+            SemanticKind::Keyword => lsp::SemanticTokenType::KEYWORD,
+            SemanticKind::String => lsp::SemanticTokenType::STRING,
+            SemanticKind::Number => lsp::SemanticTokenType::NUMBER,
+            SemanticKind::Module => lsp::SemanticTokenType::NAMESPACE,
+            SemanticKind::Type => lsp::SemanticTokenType::TYPE,
+            SemanticKind::Variant => lsp::SemanticTokenType::ENUM_MEMBER,
+            SemanticKind::Function => lsp::SemanticTokenType::FUNCTION,
+            SemanticKind::Method => lsp::SemanticTokenType::METHOD,
+            SemanticKind::Macro => lsp::SemanticTokenType::MACRO,
+            SemanticKind::Parameter => lsp::SemanticTokenType::PARAMETER,
+            SemanticKind::Constant | SemanticKind::Variable => lsp::SemanticTokenType::VARIABLE,
+            kind => lsp::SemanticTokenType::new(kind.as_str()),
+        })
+        .collect();
+
+    lsp::SemanticTokensLegend {
+        token_types,
+        token_modifiers: vec![lsp::SemanticTokenModifier::READONLY],
+    }
+}
+
+/// Modifiers bitset for the given kind, as declared in
+/// [semantic_tokens_legend].
+fn semantic_token_modifiers(kind: SemanticKind) -> u32 {
+    match kind {
+        SemanticKind::Constant => 1,
+        _ => 0,
+    }
+}
+
 #[derive(Default)]
 pub struct Index {
+    /// The id of the source in the sources it was built with.
+    source_id: SourceId,
     /// Spans mapping to their corresponding definitions.
     definitions: BTreeMap<Span, Definition>,
+    /// Classified tokens of the source.
+    semantic_tokens: Vec<SemanticToken>,
+    /// Symbols declared in the source.
+    symbols: Vec<Symbol>,
+    /// Spans of the identifiers in the source, in order.
+    idents: Vec<Span>,
+    /// Items indexed while building the source.
+    items: Vec<Item>,
+    /// Types inferred for the source.
+    types: Types,
+}
+
+/// The kind of completion requested.
+enum Completion {
+    /// Instance functions, after a `.`.
+    Instance,
+    /// Items under the given path, after a `::`.
+    Path(Vec<String>),
+    /// Anything which is in scope.
+    Ident,
+}
+
+/// Complete the instance functions known to the context, and the script
+/// functions which take `self`.
+fn complete_instance_fns(context: &Context, index: &Index, results: &mut Vec<lsp::CompletionItem>) {
+    for (_, signature) in context.iter_functions() {
+        if let ContextSignature::Instance {
+            name: InstFnKind::Instance(name),
+            ..
+        } = signature
+        {
+            results.push(lsp::CompletionItem {
+                label: name.to_string(),
+                kind: Some(lsp::CompletionItemKind::METHOD),
+                detail: Some(signature.to_string()),
+                ..Default::default()
+            });
+        }
+    }
+
+    for symbol in &index.symbols {
+        let is_instance_fn = symbol.kind == lsp::SymbolKind::FUNCTION
+            && matches!(&symbol.detail, Some(detail) if detail.contains("(self"));
+
+        if is_instance_fn {
+            let mut completion = symbol_completion(symbol);
+            completion.kind = Some(lsp::CompletionItemKind::METHOD);
+            results.push(completion);
+        }
+    }
+}
+
+/// Complete the items immediately under the given path, both those provided
+/// by the context and those declared by the script.
+fn complete_path(
+    context: &Context,
+    index: &Index,
+    path: &[String],
+    results: &mut Vec<lsp::CompletionItem>,
+) {
+    let item = match path.split_first() {
+        Some((first, rest)) if context.contains_crate(first) => Item::with_crate_item(first, rest),
+        _ => Item::with_item(path),
+    };
+
+    for component in context.iter_components(&item) {
+        let name = match component {
+            ComponentRef::Crate(name) | ComponentRef::Str(name) => name,
+            ComponentRef::Id(..) => continue,
+        };
+
+        results.push(context_completion(context, name, &item.extended(name)));
+    }
+
+    if path.is_empty() {
+        return;
+    }
+
+    let parent = Item::with_item(path);
+
+    for item in &index.items {
+        if item.iter().count() != path.len() + 1 || !item.starts_with(&parent) {
+            continue;
+        }
+
+        let name = match item.last() {
+            Some(ComponentRef::Str(name)) => name,
+            _ => continue,
+        };
+
+        let symbol = index
+            .symbols
+            .iter()
+            .find(|s| s.name == name && s.kind != lsp::SymbolKind::VARIABLE);
+
+        results.push(match symbol {
+            Some(symbol) => symbol_completion(symbol),
+            None => lsp::CompletionItem::new_simple(name.to_owned(), item.to_string()),
+        });
+    }
+}
+
+/// Construct the completion of a name referring to the given context item.
+fn context_completion(context: &Context, name: &str, item: &Item) -> lsp::CompletionItem {
+    let function = context
+        .iter_functions()
+        .find_map(|(_, signature)| match signature {
+            ContextSignature::Function { item: f, .. } if f == item => Some(signature),
+            _ => None,
+        });
+
+    let (kind, detail) = if let Some(signature) = function {
+        (lsp::CompletionItemKind::FUNCTION, signature.to_string())
+    } else if context.iter_types().any(|(_, ty)| ty.item == *item) {
+        (lsp::CompletionItemKind::STRUCT, item.to_string())
+    } else {
+        (lsp::CompletionItemKind::MODULE, item.to_string())
+    };
+
+    lsp::CompletionItem {
+        label: name.to_owned(),
+        kind: Some(kind),
+        detail: Some(detail),
+        ..Default::default()
+    }
+}
+
+/// Construct the completion of a symbol declared in a script.
+fn symbol_completion(symbol: &Symbol) -> lsp::CompletionItem {
+    let kind = match symbol.kind {
+        lsp::SymbolKind::FUNCTION => lsp::CompletionItemKind::FUNCTION,
+        lsp::SymbolKind::STRUCT => lsp::CompletionItemKind::STRUCT,
+        lsp::SymbolKind::ENUM => lsp::CompletionItemKind::ENUM,
+        lsp::SymbolKind::ENUM_MEMBER => lsp::CompletionItemKind::ENUM_MEMBER,
+        lsp::SymbolKind::CONSTANT => lsp::CompletionItemKind::CONSTANT,
+        lsp::SymbolKind::MODULE => lsp::CompletionItemKind::MODULE,
+        _ => lsp::CompletionItemKind::VARIABLE,
+    };
+
+    let documentation = symbol.docs.as_ref().map(|docs| {
+        lsp::Documentation::MarkupContent(lsp::MarkupContent {
+            kind: lsp::MarkupKind::Markdown,
+            value: docs.clone(),
+        })
+    });
+
+    lsp::CompletionItem {
+        label: symbol.name.clone(),
+        kind: Some(kind),
+        detail: symbol.detail.clone(),
+        documentation,
+        ..Default::default()
+    }
+}
+
+/// An error raised when a symbol can't be renamed.
+#[derive(Debug)]
+pub enum RenameError {
+    /// The symbol isn't declared in a source which is open.
+    NotOpen,
+    /// The new name is not a valid identifier.
+    InvalidName(String),
+    /// The new name is already declared in the same scope.
+    Conflict(String),
+    /// The new name is provided by the native context.
+    ContextConflict(String),
+    /// The new name would change what a variable refers to.
+    Shadowing(String),
+}
+
+impl fmt::Display for RenameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotOpen => write!(f, "the declaration is not in an open source"),
+            Self::InvalidName(name) => write!(f, "`{}` is not a valid identifier", name),
+            Self::Conflict(name) => write!(f, "`{}` is already declared in this scope", name),
+            Self::ContextConflict(name) => {
+                write!(f, "`{}` would collide with an item in the context", name)
+            }
+            Self::Shadowing(name) => {
+                write!(f, "renaming to `{}` would shadow another variable", name)
+            }
+        }
+    }
 }
 
 /// A definition source.
@@ -512,7 +1309,7 @@ pub enum DefinitionSource {
 }
 
 impl DefinitionSource {
-    fn span(&self) -> Span {
+    pub(crate) fn span(&self) -> Span {
         match self {
             Self::Source(..) => Span::empty(),
             Self::Location(location) => location.span,
@@ -542,6 +1339,8 @@ pub struct Definition {
     pub(crate) kind: DefinitionKind,
     /// The id of the source id the definition corresponds to.
     pub(crate) source: DefinitionSource,
+    /// The item being defined, if it is an item.
+    pub(crate) item: Option<Item>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -570,23 +1369,42 @@ pub enum DefinitionKind {
 
 struct Visitor {
     index: Index,
+    semantic: SemanticTokens,
 }
 
 impl Visitor {
     /// Construct a new visitor.
     pub fn new(index: Index) -> Self {
-        Self { index }
+        Self {
+            index,
+            semantic: SemanticTokens::new(),
+        }
     }
 
     /// Convert visitor back into an index.
-    pub fn into_index(self) -> Index {
+    pub fn into_index(mut self, sources: &rune::Sources) -> Index {
+        let source_id = self.index.source_id;
+        self.index.semantic_tokens = self.semantic.tokens(sources, source_id);
+
+        if let Some(source) = sources.get(source_id) {
+            self.index.symbols = symbols::collect(source.as_str());
+            self.index.idents = symbols::idents(source.as_str());
+            self.index.types = infer::infer(source.as_str(), &self.index.definitions);
+        }
+
         self.index
     }
 }
 
 impl CompileVisitor for Visitor {
+    fn visit_item_indexed(&mut self, _: Location, item: &Item) {
+        self.index.items.push(item.clone());
+    }
+
     fn visit_meta(&mut self, source_id: SourceId, meta: MetaRef<'_>, span: Span) {
-        if source_id.into_index() != 0 {
+        self.semantic.visit_meta(source_id, meta, span);
+
+        if source_id != self.index.source_id {
             return;
         }
 
@@ -610,6 +1428,7 @@ impl CompileVisitor for Visitor {
         let definition = Definition {
             kind,
             source: DefinitionSource::SourceMeta(source.clone()),
+            item: Some(meta.item.clone()),
         };
 
         if let Some(d) = self.index.definitions.insert(span, definition) {
@@ -618,13 +1437,16 @@ impl CompileVisitor for Visitor {
     }
 
     fn visit_variable_use(&mut self, source_id: SourceId, var_span: Span, span: Span) {
-        if source_id.into_index() != 0 {
+        self.semantic.visit_variable_use(source_id, var_span, span);
+
+        if source_id != self.index.source_id {
             return;
         }
 
         let definition = Definition {
             kind: DefinitionKind::Local,
             source: DefinitionSource::Location(Location::new(source_id, var_span)),
+            item: None,
         };
 
         if let Some(d) = self.index.definitions.insert(span, definition) {
@@ -633,13 +1455,14 @@ impl CompileVisitor for Visitor {
     }
 
     fn visit_mod(&mut self, source_id: SourceId, span: Span) {
-        if source_id.into_index() != 0 {
+        if source_id != self.index.source_id {
             return;
         }
 
         let definition = Definition {
             kind: DefinitionKind::Module,
             source: DefinitionSource::Source(source_id),
+            item: None,
         };
 
         if let Some(d) = self.index.definitions.insert(span, definition) {
@@ -648,25 +1471,20 @@ impl CompileVisitor for Visitor {
     }
 }
 
-struct SourceLoader {
-    sources: HashMap<Url, Source>,
+struct SourceLoader<'a> {
+    sources: &'a HashMap<Url, Source>,
     base: FileSourceLoader,
 }
 
-impl SourceLoader {
+impl<'a> SourceLoader<'a> {
     /// Construct a new source loader.
-    pub fn new(sources: HashMap<Url, Source>) -> Self {
+    pub fn new(sources: &'a HashMap<Url, Source>) -> Self {
         Self {
             sources,
             base: FileSourceLoader::new(),
         }
     }
 
-    /// Convert into sources.
-    fn into_sources(self) -> HashMap<Url, Source> {
-        self.sources
-    }
-
     /// Generate a collection of URl candidates.
     fn candidates(root: &Path, item: &Item) -> Option<[Url; 2]> {
         let mut base = root.to_owned();
@@ -707,7 +1525,7 @@ impl SourceLoader {
     }
 }
 
-impl rune::compile::SourceLoader for SourceLoader {
+impl rune::compile::SourceLoader for SourceLoader<'_> {
     fn load(&mut self, root: &Path, item: &Item, span: Span) -> Result<rune::Source, CompileError> {
         tracing::trace!("load {} (root: {})", item, root.display());
 
@@ -722,3 +1540,278 @@ impl rune::compile::SourceLoader for SourceLoader {
         self.base.load(root, item, span)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a state with the given sources open.
+    async fn build(sources: &[(&Url, &str)]) -> State {
+        let (rebuild_tx, _) = mpsc::channel(1);
+        let context = Context::with_default_modules().unwrap();
+        let state = State::new(rebuild_tx, context, Options::default());
+
+        for (url, text) in sources {
+            state
+                .sources_mut()
+                .await
+                .insert_text((*url).clone(), (*text).to_owned());
+        }
+
+        state.build().await;
+        state
+    }
+
+    fn url(name: &str) -> Url {
+        Url::parse(&format!("file:///{}", name)).unwrap()
+    }
+
+    /// Get the position of the `n`th occurence of `needle` in `text`.
+    fn position(text: &str, needle: &str, n: usize) -> lsp::Position {
+        let (offset, _) = text.match_indices(needle).nth(n).unwrap();
+        let line = text[..offset].matches('\n').count();
+        let start = text[..offset].rfind('\n').map_or(0, |at| at + 1);
+        lsp::Position::new(line as u32, (offset - start) as u32)
+    }
+
+    /// Get the ranges of the given locations, which must all be in `url`.
+    fn ranges(url: &Url, locations: Vec<lsp::Location>) -> Vec<lsp::Range> {
+        let mut ranges = locations
+            .into_iter()
+            .map(|location| {
+                assert_eq!(&location.uri, url);
+                location.range
+            })
+            .collect::<Vec<_>>();
+
+        ranges.sort_by_key(|r| (r.start.line, r.start.character));
+        ranges
+    }
+
+    fn range(text: &str, needle: &str, n: usize) -> lsp::Range {
+        let start = position(text, needle, n);
+        let end = lsp::Position::new(start.line, start.character + needle.len() as u32);
+        lsp::Range::new(start, end)
+    }
+
+    #[tokio::test]
+    async fn test_references() {
+        const SOURCE: &str = "fn add(a, b) {\n    a + b\n}\n\npub fn main() {\n    let total = add(1, 2);\n    add(total, total)\n}\n";
+
+        let main = url("main.rn");
+        let state = build(&[(&main, SOURCE)]).await;
+
+        let locations = state
+            .references(&main, position(SOURCE, "add", 2), false)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            ranges(&main, locations),
+            [range(SOURCE, "add", 1), range(SOURCE, "add", 2)]
+        );
+
+        let locations = state
+            .references(&main, position(SOURCE, "total", 2), true)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            ranges(&main, locations),
+            [
+                range(SOURCE, "total", 0),
+                range(SOURCE, "total", 1),
+                range(SOURCE, "total", 2),
+            ]
+        );
+    }
+
+    /// Get the edits of a workspace edit, which must all be in `url`.
+    fn edits(url: &Url, edit: lsp::WorkspaceEdit) -> Vec<(lsp::Range, String)> {
+        let mut changes = edit.changes.unwrap();
+        let edits = changes.remove(url).unwrap();
+        assert!(changes.is_empty());
+
+        edits
+            .into_iter()
+            .map(|edit| (edit.range, edit.new_text))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_rename() {
+        const SOURCE: &str = "fn add(a, b) {\n    a + b\n}\n\npub fn main() {\n    let total = add(1, 2);\n    add(total, total)\n}\n";
+
+        let main = url("main.rn");
+        let state = build(&[(&main, SOURCE)]).await;
+
+        let edit = state
+            .rename(&main, position(SOURCE, "add", 1), "sum")
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            edits(&main, edit),
+            [
+                (range(SOURCE, "add", 0), String::from("sum")),
+                (range(SOURCE, "add", 1), String::from("sum")),
+                (range(SOURCE, "add", 2), String::from("sum")),
+            ]
+        );
+
+        let edit = state
+            .rename(&main, position(SOURCE, "total", 0), "result")
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            edits(&main, edit),
+            [
+                (range(SOURCE, "total", 0), String::from("result")),
+                (range(SOURCE, "total", 1), String::from("result")),
+                (range(SOURCE, "total", 2), String::from("result")),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rename_conflict() {
+        const SOURCE: &str = "fn add(a, b) {\n    a + b\n}\n\npub fn main() {\n    add(1, 2)\n}\n";
+
+        let main = url("main.rn");
+        let state = build(&[(&main, SOURCE)]).await;
+        let at = position(SOURCE, "add", 1);
+
+        let error = state.rename(&main, at, "main").await.unwrap_err();
+        assert!(matches!(error, RenameError::Conflict(name) if name == "main"));
+
+        let error = state.rename(&main, at, "1add").await.unwrap_err();
+        assert!(matches!(error, RenameError::InvalidName(name) if name == "1add"));
+    }
+
+    #[tokio::test]
+    async fn test_rename_shadowing() {
+        const SOURCE: &str =
+            "pub fn main() {\n    let first = 1;\n    let second = 2;\n    first + second\n}\n";
+
+        let main = url("main.rn");
+        let state = build(&[(&main, SOURCE)]).await;
+
+        // `first` would refer to `second` in `first + second`.
+        let error = state
+            .rename(&main, position(SOURCE, "second", 0), "first")
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, RenameError::Shadowing(name) if name == "first"));
+
+        // The use of `first` would refer to `second` instead.
+        let error = state
+            .rename(&main, position(SOURCE, "first", 0), "second")
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, RenameError::Shadowing(name) if name == "second"));
+    }
+
+    fn markdown(hover: lsp::Hover) -> (String, Option<lsp::Range>) {
+        match hover.contents {
+            lsp::HoverContents::Markup(markup) => (markup.value, hover.range),
+            contents => panic!("unexpected contents: {:?}", contents),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hover() {
+        const SOURCE: &str = "/// Add two numbers.\nfn add(a, b) {\n    a + b\n}\n\npub fn main() {\n    let total = 1 + 2;\n    add(total, 3.5)\n}\n";
+
+        let main = url("main.rn");
+        let state = build(&[(&main, SOURCE)]).await;
+
+        let hover = state.hover(&main, position(SOURCE, "add", 1)).await;
+
+        assert_eq!(
+            hover.map(markdown),
+            Some((
+                String::from("```rune\nfn add(a, b)\n```\n\nAdd two numbers."),
+                Some(range(SOURCE, "add", 1)),
+            ))
+        );
+
+        let hover = state.hover(&main, position(SOURCE, "total", 1)).await;
+
+        assert_eq!(
+            hover.map(markdown),
+            Some((
+                String::from("```rune\nlet total: int\n```"),
+                Some(range(SOURCE, "total", 1)),
+            ))
+        );
+
+        let hover = state.hover(&main, position(SOURCE, "+", 1)).await;
+
+        assert_eq!(
+            hover.map(markdown),
+            Some((
+                String::from("```rune\nint\n```"),
+                Some(range(SOURCE, "1 + 2", 0)),
+            ))
+        );
+
+        let hover = state.hover(&main, position(SOURCE, "3.5", 0)).await;
+
+        assert_eq!(
+            hover.map(markdown),
+            Some((
+                String::from("```rune\nfloat\n```"),
+                Some(range(SOURCE, "3.5", 0)),
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_inlay_hints() {
+        const SOURCE: &str = "fn add(a, b) {\n    a + b\n}\n\npub fn main() {\n    let total = 1 + 2;\n    let b = 3;\n    add(total, b)\n}\n";
+
+        let main = url("main.rn");
+        let state = build(&[(&main, SOURCE)]).await;
+
+        let everything = lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(9, 0));
+
+        let hints = state
+            .inlay_hints(&main, everything)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|hint| (hint.position, hint.label, hint.kind))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            hints,
+            [
+                (
+                    range(SOURCE, "total", 0).end,
+                    String::from(": int"),
+                    Some(InlayHintKind::TYPE)
+                ),
+                (
+                    position(SOURCE, " = 3", 0),
+                    String::from(": int"),
+                    Some(InlayHintKind::TYPE)
+                ),
+                (
+                    position(SOURCE, "total", 1),
+                    String::from("a:"),
+                    Some(InlayHintKind::PARAMETER)
+                ),
+            ]
+        );
+
+        let first = lsp::Range::new(lsp::Position::new(5, 0), lsp::Position::new(6, 0));
+        let hints = state.inlay_hints(&main, first).await.unwrap();
+        assert_eq!(hints.len(), 1);
+        assert_eq!(hints[0].position, range(SOURCE, "total", 0).end);
+    }
+}
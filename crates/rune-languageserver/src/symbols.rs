@@ -0,0 +1,257 @@
+use rune::ast::{self, Span, Spanned, Visit, Walk};
+use rune::SourceId;
+
+/// A symbol declared in a source.
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    /// The name of the symbol.
+    pub(crate) name: String,
+    /// The kind of the symbol.
+    pub(crate) kind: lsp::SymbolKind,
+    /// The span of the whole declaration, which is what uses of the symbol
+    /// refer back to.
+    pub(crate) span: Span,
+    /// The span of the name of the symbol.
+    pub(crate) name_span: Span,
+    /// The name of the item the symbol is declared in, if any.
+    pub(crate) container: Option<String>,
+    /// A short description of the declaration, like the signature of a
+    /// function.
+    pub(crate) detail: Option<String>,
+    /// The doc comment preceding the declaration, if any.
+    pub(crate) docs: Option<String>,
+    /// The names of the arguments, if the symbol is a function.
+    pub(crate) args: Vec<String>,
+}
+
+/// Collect the symbols declared in the given source text.
+///
+/// Returns an empty collection if the source doesn't parse.
+pub(crate) fn collect(text: &str) -> Vec<Symbol> {
+    let file = match rune::parse::parse_all::<ast::File>(text, SourceId::new(0), true) {
+        Ok(file) => file,
+        Err(..) => return Vec::new(),
+    };
+
+    let mut collector = Collector {
+        text,
+        containers: Vec::new(),
+        symbols: Vec::new(),
+    };
+
+    file.accept(&mut collector);
+    collector.symbols
+}
+
+/// Collect the spans of every identifier in the given source text, in the
+/// order in which they appear.
+///
+/// Returns an empty collection if the source doesn't parse.
+pub(crate) fn idents(text: &str) -> Vec<Span> {
+    let file = match rune::parse::parse_all::<ast::File>(text, SourceId::new(0), true) {
+        Ok(file) => file,
+        Err(..) => return Vec::new(),
+    };
+
+    let mut idents = Idents::default();
+    file.accept(&mut idents);
+    idents.0.sort_by_key(|span| span.start);
+    idents.0
+}
+
+struct Collector<'a> {
+    text: &'a str,
+    containers: Vec<String>,
+    symbols: Vec<Symbol>,
+}
+
+impl Collector<'_> {
+    /// Declare a symbol and return its name.
+    fn declare(&mut self, ident: &ast::Ident, span: Span, kind: lsp::SymbolKind) -> String {
+        let name = self.text.get(ident.span.range()).unwrap_or_default();
+
+        let detail = match kind {
+            lsp::SymbolKind::STRUCT => Some(format!("struct {}", name)),
+            lsp::SymbolKind::ENUM => Some(format!("enum {}", name)),
+            lsp::SymbolKind::MODULE => Some(format!("mod {}", name)),
+            lsp::SymbolKind::CONSTANT => Some(format!("const {}", name)),
+            lsp::SymbolKind::ENUM_MEMBER => self
+                .containers
+                .last()
+                .map(|container| format!("{}::{}", container, name)),
+            _ => None,
+        };
+
+        self.symbols.push(Symbol {
+            name: name.to_owned(),
+            kind,
+            span,
+            name_span: ident.span,
+            container: self.containers.last().cloned(),
+            detail,
+            docs: docs(self.text, span.start.into_usize()),
+            args: Vec::new(),
+        });
+
+        name.to_owned()
+    }
+
+    /// Declare a symbol which contains other symbols, walking its children.
+    fn declare_container<T>(&mut self, ident: &ast::Ident, node: &T, kind: lsp::SymbolKind)
+    where
+        T: Spanned + Walk,
+    {
+        let name = self.declare(ident, node.span(), kind);
+        self.containers.push(name);
+        node.walk(self);
+        self.containers.pop();
+    }
+
+    /// Declare every variable bound by the given pattern.
+    fn bindings(&mut self, pat: &ast::Pat) {
+        let mut bindings = Bindings::default();
+        pat.accept(&mut bindings);
+
+        for ident in bindings.0 {
+            self.declare(&ident, ident.span, lsp::SymbolKind::VARIABLE);
+        }
+    }
+}
+
+impl Visit for Collector<'_> {
+    fn visit_item_fn(&mut self, node: &ast::ItemFn) {
+        let name = self.declare(&node.name, node.span(), lsp::SymbolKind::FUNCTION);
+
+        if let Some(symbol) = self.symbols.last_mut() {
+            let args = self.text.get(node.args.span().range()).unwrap_or_default();
+            let args = args.split_whitespace().collect::<Vec<_>>().join(" ");
+            symbol.detail = Some(format!("fn {}{}", name, args));
+
+            symbol.args = node
+                .args
+                .iter()
+                .map(|(arg, _)| self.text.get(arg.span().range()).unwrap_or_default())
+                .map(str::to_owned)
+                .collect();
+        }
+
+        self.containers.push(name);
+
+        for (arg, _) in node.args.iter() {
+            if let ast::FnArg::Pat(pat) = arg {
+                self.bindings(pat);
+            }
+        }
+
+        node.walk(self);
+        self.containers.pop();
+    }
+
+    fn visit_item_struct(&mut self, node: &ast::ItemStruct) {
+        self.declare_container(&node.ident, node, lsp::SymbolKind::STRUCT);
+    }
+
+    fn visit_item_enum(&mut self, node: &ast::ItemEnum) {
+        self.declare_container(&node.name, node, lsp::SymbolKind::ENUM);
+    }
+
+    fn visit_item_variant(&mut self, node: &ast::ItemVariant) {
+        // NB: uses of variants refer back to the name of the variant.
+        self.declare(&node.name, node.name.span, lsp::SymbolKind::ENUM_MEMBER);
+        node.walk(self);
+    }
+
+    fn visit_item_const(&mut self, node: &ast::ItemConst) {
+        self.declare(&node.name, node.span(), lsp::SymbolKind::CONSTANT);
+        node.walk(self);
+    }
+
+    fn visit_item_mod(&mut self, node: &ast::ItemMod) {
+        self.declare_container(&node.name, node, lsp::SymbolKind::MODULE);
+    }
+
+    fn visit_expr_closure(&mut self, node: &ast::ExprClosure) {
+        if let ast::ExprClosureArgs::List { args, .. } = &node.args {
+            for (arg, _) in args {
+                if let ast::FnArg::Pat(pat) = arg {
+                    self.bindings(pat);
+                }
+            }
+        }
+
+        node.walk(self);
+    }
+
+    fn visit_local(&mut self, node: &ast::Local) {
+        self.bindings(&node.pat);
+        node.walk(self);
+    }
+
+    fn visit_expr_let(&mut self, node: &ast::ExprLet) {
+        self.bindings(&node.pat);
+        node.walk(self);
+    }
+
+    fn visit_expr_for(&mut self, node: &ast::ExprFor) {
+        self.bindings(&node.binding);
+        node.walk(self);
+    }
+}
+
+/// Collect the doc comment made up of the `///` lines immediately preceding
+/// the line at the given offset.
+fn docs(text: &str, offset: usize) -> Option<String> {
+    let before = text.get(..offset)?;
+    let before = &before[..before.rfind('\n').map(|n| n + 1).unwrap_or_default()];
+
+    let mut lines = Vec::new();
+
+    for line in before.lines().rev() {
+        let line = line.trim();
+
+        // NB: skip over attributes between the doc comment and the item.
+        if line.starts_with("#[") {
+            continue;
+        }
+
+        match line.strip_prefix("///") {
+            Some(doc) => lines.push(doc.strip_prefix(' ').unwrap_or(doc)),
+            None => break,
+        }
+    }
+
+    if lines.is_empty() {
+        return None;
+    }
+
+    lines.reverse();
+    Some(lines.join("\n"))
+}
+
+/// Collects every identifier bound by a pattern.
+#[derive(Default)]
+struct Bindings(Vec<ast::Ident>);
+
+impl Visit for Bindings {
+    fn visit_pat_path(&mut self, node: &ast::PatPath) {
+        let path = &node.path;
+
+        if path.global.is_none() && path.rest.is_empty() && path.trailing.is_none() {
+            if let ast::PathSegment::Ident(ident) = &path.first {
+                self.0.push(*ident);
+            }
+        }
+    }
+
+    fn visit_expr(&mut self, _: &ast::Expr) {}
+}
+
+/// Collects the span of every identifier.
+#[derive(Default)]
+struct Idents(Vec<Span>);
+
+impl Visit for Idents {
+    fn visit_ident(&mut self, node: &ast::Ident) {
+        self.0.push(node.span);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::context::{Context, Generate, Tokens, TypeAttrs};
+use crate::internals::{PROTOCOL_INDEX_GET, PROTOCOL_INDEX_SET};
 use proc_macro2::TokenStream;
 use quote::{quote, quote_spanned, ToTokens};
 use syn::spanned::Spanned as _;
@@ -47,7 +48,14 @@ impl InternalCall {
         };
 
         let generics = syn::Generics::default();
-        expand_any(&self.path, &name, &expand_into, &tokens, &generics)
+        expand_any(
+            &self.path,
+            &name,
+            &expand_into,
+            &tokens,
+            &generics,
+            &generics,
+        )
     }
 }
 
@@ -75,12 +83,19 @@ impl Derive {
 
         let tokens = ctx.tokens_with_module(attrs.module.as_ref());
 
-        let generics = &self.input.generics;
-        let install_with =
-            match expand_install_with(&mut ctx, &self.input, &tokens, &attrs, generics) {
-                Some(install_with) => install_with,
-                None => return Err(ctx.errors),
-            };
+        let generics = &bound_type_params(&self.input.generics, &tokens);
+        let mut install_generics = generics.clone();
+
+        let install_with = match expand_install_with(
+            &mut ctx,
+            &self.input,
+            &tokens,
+            &attrs,
+            &mut install_generics,
+        ) {
+            Some(install_with) => install_with,
+            None => return Err(ctx.errors),
+        };
 
         let name = match attrs.name {
             Some(name) => name,
@@ -90,19 +105,57 @@ impl Derive {
         let name = &quote!(#name);
         let ident = &self.input.ident;
 
-        expand_any(&ident, name, &install_with, &tokens, generics)
+        expand_any(
+            ident,
+            name,
+            &install_with,
+            &tokens,
+            generics,
+            &install_generics,
+        )
     }
 }
 
+/// Add the bounds required by `Any` to every type parameter, so that they
+/// don't have to be spelled out on the type itself.
+///
+/// Every instantiation of a generic type is a distinct type with its own type
+/// hash, since the hash is derived from its [TypeId][std::any::TypeId].
+fn bound_type_params(generics: &syn::Generics, tokens: &Tokens) -> syn::Generics {
+    let named = &tokens.named;
+    let mut generics = generics.clone();
+
+    for param in generics.type_params_mut() {
+        param.bounds.push(syn::parse_quote!('static));
+        param.bounds.push(syn::parse_quote!(#named));
+    }
+
+    generics
+}
+
+/// Test if the given type mentions any of the given type parameters.
+fn mentions_type_param(ty: &syn::Type, params: &[&syn::Ident]) -> bool {
+    fn walk(stream: TokenStream, params: &[&syn::Ident]) -> bool {
+        stream.into_iter().any(|tt| match tt {
+            proc_macro2::TokenTree::Ident(ident) => params.iter().any(|p| **p == ident),
+            proc_macro2::TokenTree::Group(group) => walk(group.stream(), params),
+            _ => false,
+        })
+    }
+
+    walk(ty.to_token_stream(), params)
+}
+
 /// Expannd the install into impl.
 pub(crate) fn expand_install_with(
     ctx: &mut Context,
     input: &syn::DeriveInput,
     tokens: &Tokens,
     attrs: &TypeAttrs,
-    generics: &syn::Generics,
+    generics: &mut syn::Generics,
 ) -> Option<TokenStream> {
     let mut installers = Vec::new();
+    let mut predicates = Vec::<syn::WherePredicate>::new();
 
     if let Some(install_with) = &attrs.install_with {
         installers.push(quote_spanned! { input.span() =>
@@ -111,8 +164,11 @@ pub(crate) fn expand_install_with(
     }
 
     let mut fields = Vec::new();
+    let mut index_gets = Vec::new();
+    let mut index_sets = Vec::new();
 
     let ident = &input.ident;
+    let params = generics.type_params().map(|p| &p.ident).collect::<Vec<_>>();
     let (_, ty_generics, _) = generics.split_for_impl();
 
     match &input.data {
@@ -123,7 +179,7 @@ pub(crate) fn expand_install_with(
                 let field_ident = match &field.ident {
                     Some(ident) => ident,
                     None => {
-                        if !attrs.protocols.is_empty() {
+                        if !attrs.protocols.is_empty() || attrs.index {
                             ctx.errors.push(syn::Error::new_spanned(
                                 field,
                                 "only named fields can be used with protocol generators like `#[rune(get)]`",
@@ -136,9 +192,17 @@ pub(crate) fn expand_install_with(
                 };
 
                 let ty = &field.ty;
-                let name = syn::LitStr::new(&field_ident.to_string(), field_ident.span());
+                let name = match &attrs.name {
+                    Some(name) => name.clone(),
+                    None => syn::LitStr::new(&field_ident.to_string(), field_ident.span()),
+                };
 
                 for protocol in &attrs.protocols {
+                    if protocol.custom.is_none() && mentions_type_param(ty, &params) {
+                        let bounds = (protocol.bounds)(tokens, &attrs);
+                        predicates.push(syn::parse_quote!(#ty: #bounds));
+                    }
+
                     installers.push((protocol.generate)(Generate {
                         tokens,
                         protocol,
@@ -152,6 +216,32 @@ pub(crate) fn expand_install_with(
                     }));
                 }
 
+                if attrs.index {
+                    let to_value = &tokens.to_value;
+                    let from_value = &tokens.from_value;
+
+                    if mentions_type_param(ty, &params) {
+                        predicates.push(syn::parse_quote!(#ty: Clone + #to_value + #from_value));
+                    }
+
+                    let access = if attrs.copy {
+                        quote!(s.#field_ident)
+                    } else {
+                        quote!(Clone::clone(&s.#field_ident))
+                    };
+
+                    index_gets.push(quote_spanned! { field.span() =>
+                        #name => #to_value::to_value(#access)
+                    });
+
+                    index_sets.push(quote_spanned! { field.span() =>
+                        #name => {
+                            s.#field_ident = #from_value::from_value(value)?;
+                            Ok(())
+                        }
+                    });
+                }
+
                 if attrs.field {
                     fields.push(name);
                 }
@@ -173,10 +263,44 @@ pub(crate) fn expand_install_with(
         }
     }
 
+    if !index_gets.is_empty() {
+        let index_get = tokens.protocol(PROTOCOL_INDEX_GET);
+        let index_set = tokens.protocol(PROTOCOL_INDEX_SET);
+        let value = &tokens.value;
+        let vm_error = &tokens.vm_error;
+        let vm_error_kind = &tokens.vm_error_kind;
+        let type_of = &tokens.type_of;
+
+        let missing = quote! {
+            key => Err(#vm_error::from(#vm_error_kind::MissingField {
+                target: <#ident #ty_generics as #type_of>::type_info(),
+                field: key.to_owned(),
+            }))
+        };
+
+        installers.push(quote_spanned! { input.span() =>
+            module.inst_fn(#index_get, |s: &#ident #ty_generics, key: &str| -> ::std::result::Result<#value, #vm_error> {
+                match key {
+                    #(#index_gets,)*
+                    #missing
+                }
+            })?;
+
+            module.inst_fn(#index_set, |s: &mut #ident #ty_generics, key: &str, value: #value| -> ::std::result::Result<(), #vm_error> {
+                match key {
+                    #(#index_sets,)*
+                    #missing
+                }
+            })?;
+        });
+    }
+
     installers.push(quote! {
         module.struct_meta::<Self>(&[#(#fields),*][..])?;
     });
 
+    generics.make_where_clause().predicates.extend(predicates);
+
     Some(quote! {
         #(#installers)*
         Ok(())
@@ -190,6 +314,7 @@ pub(super) fn expand_any<T>(
     installers: &TokenStream,
     tokens: &Tokens,
     generics: &syn::Generics,
+    install_generics: &syn::Generics,
 ) -> Result<TokenStream, Vec<syn::Error>>
 where
     T: Copy + ToTokens,
@@ -216,6 +341,7 @@ where
     } = &tokens;
 
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (install_impl_generics, _, install_where_clause) = install_generics.split_for_impl();
 
     let generic_names = generics.type_params().map(|v| &v.ident).collect::<Vec<_>>();
 
@@ -225,7 +351,8 @@ where
                 const BASE_NAME: #raw_str  = #raw_str::from_str(#name);
 
                 fn full_name() -> Box<str> {
-                    [#name, "<", &#(#generic_names::full_name(),)* ">"].join("").into_boxed_str()
+                    let params: &[Box<str>] = &[#(<#generic_names as #named>::full_name()),*];
+                    format!("{}<{}>", #name, params.join(", ")).into_boxed_str()
                 }
             }
         }
@@ -245,7 +372,7 @@ where
             }
         }
 
-        impl #impl_generics #install_with for #ident #ty_generics #where_clause {
+        impl #install_impl_generics #install_with for #ident #ty_generics #install_where_clause {
             fn install_with(module: &mut #module) -> ::std::result::Result<(), #context_error> {
                 #installers
             }
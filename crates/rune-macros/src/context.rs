@@ -34,6 +34,15 @@ pub(crate) struct FieldAttrs {
     pub(crate) copy: bool,
     /// Whether this field should be known at compile time or not.
     pub(crate) field: bool,
+    /// `#[rune(index)]` to make the field accessible by name through the
+    /// index get and set protocols.
+    pub(crate) index: bool,
+    /// `#[rune(name = "..")]` to override the name of the field when it's
+    /// converted to and from values.
+    pub(crate) name: Option<syn::LitStr>,
+    /// `#[rune(default)]` or `#[rune(default = "..")]` to construct the field
+    /// if it's missing when converting from a value.
+    pub(crate) default: Option<TokenStream>,
 }
 
 impl FieldAttrs {
@@ -58,6 +67,14 @@ impl Default for ParseKind {
     }
 }
 
+/// Parsed `#[rune(..)]` variant attributes.
+#[derive(Default)]
+pub(crate) struct VariantAttrs {
+    /// `#[rune(name = "..")]` to override the name of the variant when it's
+    /// converted to and from values.
+    pub(crate) name: Option<syn::LitStr>,
+}
+
 /// Parsed field attributes.
 #[derive(Default)]
 pub(crate) struct TypeAttrs {
@@ -86,7 +103,10 @@ pub(crate) struct Generate<'a> {
 
 pub(crate) struct FieldProtocol {
     pub(crate) generate: fn(Generate<'_>) -> TokenStream,
-    custom: Option<syn::Path>,
+    /// Generate the bounds the field type has to satisfy for the protocol to
+    /// be installed, which are added to generic types.
+    pub(crate) bounds: fn(&Tokens, &FieldAttrs) -> TokenStream,
+    pub(crate) custom: Option<syn::Path>,
 }
 
 #[derive(Default)]
@@ -192,6 +212,15 @@ impl Context {
             };
         }
 
+        macro_rules! bounds_op {
+            ($trait:ident) => {
+                |tokens, _| {
+                    let unsafe_from_value = &tokens.unsafe_from_value;
+                    quote!(#unsafe_from_value + ::std::ops::$trait)
+                }
+            };
+        }
+
         let mut attrs = FieldAttrs::default();
 
         for attr in input {
@@ -246,6 +275,25 @@ impl Context {
                     Meta(Path(path)) if path == COPY => {
                         attrs.copy = true;
                     }
+                    // Parse `#[rune(index)]`.
+                    Meta(Path(path)) if path == INDEX => {
+                        attrs.index = true;
+                    }
+                    // Parse `#[rune(name = "..")]`.
+                    Meta(NameValue(MetaNameValue {
+                        path,
+                        lit: Lit::Str(name),
+                        ..
+                    })) if path == NAME => {
+                        attrs.name = Some(name);
+                    }
+                    // Parse `#[rune(default)]` or `#[rune(default = "..")]`.
+                    Meta(meta) if meta.path() == DEFAULT => {
+                        attrs.default = Some(match self.parse_field_custom(meta)? {
+                            Some(path) => quote_spanned!(span => #path()),
+                            None => quote_spanned!(span => ::std::default::Default::default()),
+                        });
+                    }
                     Meta(meta) if meta.path() == GET => {
                         attrs.field = true;
                         attrs.protocols.push(FieldProtocol {
@@ -271,6 +319,15 @@ impl Context {
                                     module.field_fn(#protocol, #name, |s: &#ident #ty_generics| #access)?;
                                 }
                             },
+                            bounds: |tokens, attrs| {
+                                let to_value = &tokens.to_value;
+
+                                if attrs.copy {
+                                    quote!(Copy + #to_value)
+                                } else {
+                                    quote!(Clone + #to_value)
+                                }
+                            },
                         });
                     }
                     Meta(meta) if meta.path() == SET => {
@@ -293,66 +350,80 @@ impl Context {
                                     })?;
                                 }
                             },
+                            bounds: |tokens, _| {
+                                let unsafe_from_value = &tokens.unsafe_from_value;
+                                quote!(#unsafe_from_value)
+                            },
                         });
                     }
                     Meta(meta) if meta.path() == ADD_ASSIGN => {
                         attrs.protocols.push(FieldProtocol {
                             custom: self.parse_field_custom(meta)?,
                             generate: generate_op!(PROTOCOL_ADD_ASSIGN, +=),
+                            bounds: bounds_op!(AddAssign),
                         });
                     }
                     Meta(meta) if meta.path() == SUB_ASSIGN => {
                         attrs.protocols.push(FieldProtocol {
                             custom: self.parse_field_custom(meta)?,
                             generate: generate_op!(PROTOCOL_SUB_ASSIGN, -=),
+                            bounds: bounds_op!(SubAssign),
                         });
                     }
                     Meta(meta) if meta.path() == DIV_ASSIGN => {
                         attrs.protocols.push(FieldProtocol {
                             custom: self.parse_field_custom(meta)?,
                             generate: generate_op!(PROTOCOL_DIV_ASSIGN, /=),
+                            bounds: bounds_op!(DivAssign),
                         });
                     }
                     Meta(meta) if meta.path() == MUL_ASSIGN => {
                         attrs.protocols.push(FieldProtocol {
                             custom: self.parse_field_custom(meta)?,
                             generate: generate_op!(PROTOCOL_MUL_ASSIGN, *=),
+                            bounds: bounds_op!(MulAssign),
                         });
                     }
                     Meta(meta) if meta.path() == BIT_AND_ASSIGN => {
                         attrs.protocols.push(FieldProtocol {
                             custom: self.parse_field_custom(meta)?,
                             generate: generate_op!(PROTOCOL_BIT_AND_ASSIGN, &=),
+                            bounds: bounds_op!(BitAndAssign),
                         });
                     }
                     Meta(meta) if meta.path() == BIT_OR_ASSIGN => {
                         attrs.protocols.push(FieldProtocol {
                             custom: self.parse_field_custom(meta)?,
                             generate: generate_op!(PROTOCOL_BIT_OR_ASSIGN, |=),
+                            bounds: bounds_op!(BitOrAssign),
                         });
                     }
                     Meta(meta) if meta.path() == BIT_XOR_ASSIGN => {
                         attrs.protocols.push(FieldProtocol {
                             custom: self.parse_field_custom(meta)?,
                             generate: generate_op!(PROTOCOL_BIT_XOR_ASSIGN, ^=),
+                            bounds: bounds_op!(BitXorAssign),
                         });
                     }
                     Meta(meta) if meta.path() == SHL_ASSIGN => {
                         attrs.protocols.push(FieldProtocol {
                             custom: self.parse_field_custom(meta)?,
                             generate: generate_op!(PROTOCOL_SHL_ASSIGN, <<=),
+                            bounds: bounds_op!(ShlAssign),
                         });
                     }
                     Meta(meta) if meta.path() == SHR_ASSIGN => {
                         attrs.protocols.push(FieldProtocol {
                             custom: self.parse_field_custom(meta)?,
                             generate: generate_op!(PROTOCOL_SHR_ASSIGN, >>=),
+                            bounds: bounds_op!(ShrAssign),
                         });
                     }
                     Meta(meta) if meta.path() == REM_ASSIGN => {
                         attrs.protocols.push(FieldProtocol {
                             custom: self.parse_field_custom(meta)?,
                             generate: generate_op!(PROTOCOL_REM_ASSIGN, %=),
+                            bounds: bounds_op!(RemAssign),
                         });
                     }
                     _ => {
@@ -368,6 +439,36 @@ impl Context {
         Some(attrs)
     }
 
+    /// Parse variant attributes.
+    pub(crate) fn variant_attrs(&mut self, input: &[syn::Attribute]) -> Option<VariantAttrs> {
+        let mut attrs = VariantAttrs::default();
+
+        for attr in input {
+            for meta in self.get_meta_items(attr, RUNE)? {
+                match meta {
+                    // Parse `#[rune(name = "..")]`.
+                    Meta(NameValue(MetaNameValue {
+                        path,
+                        lit: Lit::Str(name),
+                        ..
+                    })) if path == NAME => {
+                        attrs.name = Some(name);
+                    }
+                    meta => {
+                        self.errors.push(syn::Error::new_spanned(
+                            meta,
+                            "unsupported variant attribute",
+                        ));
+
+                        return None;
+                    }
+                }
+            }
+        }
+
+        Some(attrs)
+    }
+
     /// Parse field attributes.
     pub(crate) fn type_attrs(&mut self, input: &[syn::Attribute]) -> Option<TypeAttrs> {
         let mut attrs = TypeAttrs::default();
@@ -601,6 +702,8 @@ impl Context {
             any: quote!(#module::Any),
             context_error: quote!(#module::compile::ContextError),
             from_value: quote!(#module::runtime::FromValue),
+            function_meta_data: quote!(#module::compile::FunctionMetaData),
+            function_meta_kind: quote!(#module::compile::FunctionMetaKind),
             hash: quote!(#module::Hash),
             id: quote!(#module::parse::Id),
             install_with: quote!(#module::compile::InstallWith),
@@ -634,6 +737,9 @@ impl Context {
             variant_data: quote!(#module::runtime::VariantData),
             vm_error_kind: quote!(#module::runtime::VmErrorKind),
             vm_error: quote!(#module::runtime::VmError),
+            visit: quote!(#module::ast::Visit),
+            visit_mut: quote!(#module::ast::VisitMut),
+            walk: quote!(#module::ast::Walk),
         }
     }
 }
@@ -642,6 +748,8 @@ pub(crate) struct Tokens {
     pub(crate) any: TokenStream,
     pub(crate) context_error: TokenStream,
     pub(crate) from_value: TokenStream,
+    pub(crate) function_meta_data: TokenStream,
+    pub(crate) function_meta_kind: TokenStream,
     pub(crate) hash: TokenStream,
     pub(crate) id: TokenStream,
     pub(crate) install_with: TokenStream,
@@ -675,6 +783,9 @@ pub(crate) struct Tokens {
     pub(crate) variant_data: TokenStream,
     pub(crate) vm_error_kind: TokenStream,
     pub(crate) vm_error: TokenStream,
+    pub(crate) visit: TokenStream,
+    pub(crate) visit_mut: TokenStream,
+    pub(crate) walk: TokenStream,
 }
 
 impl Tokens {
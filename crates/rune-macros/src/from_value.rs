@@ -88,15 +88,27 @@ impl Expander {
         })
     }
 
-    /// Expand on a struct.
+    /// Expand on an enum.
+    ///
+    /// Variants can be converted from a variant value with a matching name, or
+    /// from the representation produced when deriving `ToValue`, where unit
+    /// variants are strings and other variants are objects with a single
+    /// entry.
     fn expand_enum(&mut self, input: &syn::DeriveInput, en: &syn::DataEnum) -> Option<TokenStream> {
         let mut unit_matches = Vec::new();
         let mut unnamed_matches = Vec::new();
         let mut named_matches = Vec::new();
+        let mut unnamed_tagged_matches = Vec::new();
+        let mut named_tagged_matches = Vec::new();
 
         for variant in &en.variants {
+            let attrs = self.ctx.variant_attrs(&variant.attrs)?;
             let ident = &variant.ident;
-            let lit_str = syn::LitStr::new(&ident.to_string(), variant.span());
+
+            let lit_str = match attrs.name {
+                Some(name) => name,
+                None => syn::LitStr::new(&ident.to_string(), variant.span()),
+            };
 
             match &variant.fields {
                 syn::Fields::Unit => {
@@ -106,21 +118,51 @@ impl Expander {
                 }
                 syn::Fields::Unnamed(named) => {
                     let expanded = self.expand_unnamed(named)?;
+                    let value = &self.tokens.value;
+                    let vm_error = &self.tokens.vm_error;
+                    let tuple = &self.tokens.tuple;
 
                     unnamed_matches.push(quote_spanned! { variant.span() =>
                         #lit_str => {
                             Ok( Self::#ident ( #expanded ) )
                         }
                     });
+
+                    unnamed_tagged_matches.push(quote_spanned! { variant.span() =>
+                        #lit_str => match value {
+                            #value::Tuple(tuple) => {
+                                let tuple = tuple.borrow_ref()?;
+                                Ok( Self::#ident ( #expanded ) )
+                            }
+                            actual => {
+                                Err(#vm_error::expected::<#tuple>(actual.type_info()?))
+                            }
+                        }
+                    });
                 }
                 syn::Fields::Named(named) => {
                     let expanded = self.expand_named(named)?;
+                    let value = &self.tokens.value;
+                    let vm_error = &self.tokens.vm_error;
+                    let object = &self.tokens.object;
 
                     named_matches.push(quote_spanned! { variant.span() =>
                         #lit_str => {
                             Ok( Self::#ident { #expanded } )
                         }
                     });
+
+                    named_tagged_matches.push(quote_spanned! { variant.span() =>
+                        #lit_str => match value {
+                            #value::Object(object) => {
+                                let object = object.borrow_ref()?;
+                                Ok( Self::#ident { #expanded } )
+                            }
+                            actual => {
+                                Err(#vm_error::expected::<#object>(actual.type_info()?))
+                            }
+                        }
+                    });
                 }
             }
         }
@@ -132,6 +174,12 @@ impl Expander {
         let vm_error = &self.tokens.vm_error;
         let vm_error_kind = &self.tokens.vm_error_kind;
 
+        let missing = quote_spanned! { input.span() =>
+            name => {
+                return Err(#vm_error::from(#vm_error_kind::MissingVariant { name: name.into() }))
+            }
+        };
+
         let variant = quote_spanned! { input.span() =>
             #value::Variant(variant) => {
                 let variant = variant.borrow_ref()?;
@@ -145,31 +193,54 @@ impl Expander {
                 match variant.data() {
                     #variant_data::Unit => match name {
                         #(#unit_matches,)*
-                        name => {
-                            return Err(#vm_error::from(#vm_error_kind::MissingVariant { name: name.into() }))
-                        }
+                        #missing
                     },
                     #variant_data::Tuple(tuple) => match name {
                         #(#unnamed_matches)*
-                        name => {
-                            return Err(#vm_error::from(#vm_error_kind::MissingVariant { name: name.into() }))
-                        }
+                        #missing
                     },
                     #variant_data::Struct(object) => match name {
                         #(#named_matches)*
-                        name => {
-                            return Err(#vm_error::from(#vm_error_kind::MissingVariant { name: name.into() }))
-                        }
+                        #missing
                     },
                 }
             }
+            #value::String(string) => {
+                let string = string.borrow_ref()?;
+
+                match string.as_str() {
+                    #(#unit_matches,)*
+                    #missing
+                }
+            }
+            #value::StaticString(string) => {
+                match string.as_str() {
+                    #(#unit_matches,)*
+                    #missing
+                }
+            }
+            #value::Object(object) => {
+                let object = object.borrow_ref()?;
+                let mut it = object.iter();
+
+                let (name, value) = match (it.next(), it.next()) {
+                    (Some(entry), None) => entry,
+                    _ => return Err(#vm_error::from(#vm_error_kind::MissingVariantName)),
+                };
+
+                match name.as_str() {
+                    #(#unnamed_tagged_matches,)*
+                    #(#named_tagged_matches,)*
+                    #missing
+                }
+            }
         };
 
         Some(quote_spanned! { input.span() =>
             impl #from_value for #ident {
                 fn from_value(value: #value) -> ::std::result::Result<Self, #vm_error> {
                     match value {
-                        #variant,
+                        #variant
                         actual => {
                             Err(#vm_error::from(#vm_error_kind::ExpectedVariant {
                                 actual: actual.type_info()?,
@@ -200,7 +271,7 @@ impl Expander {
         let mut from_values = Vec::new();
 
         for (index, field) in unnamed.unnamed.iter().enumerate() {
-            let _ = self.ctx.field_attrs(&field.attrs)?;
+            let attrs = self.ctx.field_attrs(&field.attrs)?;
 
             let from_value = &self.tokens.from_value;
             let vm_error = &self.tokens.vm_error;
@@ -210,16 +281,21 @@ impl Expander {
                 field.span() => #from_value::from_value(value.clone())?
             };
 
+            let missing = match attrs.default {
+                Some(default) => default,
+                None => quote_spanned! { field.span() =>
+                    return Err(#vm_error::from(#vm_error_kind::MissingTupleIndex {
+                        target: std::any::type_name::<Self>(),
+                        index: #index,
+                    }))
+                },
+            };
+
             from_values.push(quote_spanned! {
                 field.span() =>
                 match tuple.get(#index) {
                     Some(value) => #from_value,
-                    None => {
-                        return Err(#vm_error::from(#vm_error_kind::MissingTupleIndex {
-                            target: std::any::type_name::<Self>(),
-                            index: #index,
-                        }));
-                    }
+                    None => #missing,
                 }
             });
         }
@@ -233,9 +309,12 @@ impl Expander {
 
         for field in &named.named {
             let ident = self.field_ident(field)?;
-            let _ = self.ctx.field_attrs(&field.attrs)?;
+            let attrs = self.ctx.field_attrs(&field.attrs)?;
 
-            let name = &syn::LitStr::new(&ident.to_string(), ident.span());
+            let name = &match attrs.name {
+                Some(name) => name,
+                None => syn::LitStr::new(&ident.to_string(), ident.span()),
+            };
 
             let from_value = &self.tokens.from_value;
             let vm_error = &self.tokens.vm_error;
@@ -245,16 +324,21 @@ impl Expander {
                 field.span() => #from_value::from_value(value.clone())?
             };
 
+            let missing = match attrs.default {
+                Some(default) => default,
+                None => quote_spanned! { field.span() =>
+                    return Err(#vm_error::from(#vm_error_kind::MissingStructField {
+                        target: std::any::type_name::<Self>(),
+                        name: #name,
+                    }))
+                },
+            };
+
             from_values.push(quote_spanned! {
                 field.span() =>
                 #ident: match object.get(#name) {
                     Some(value) => #from_value,
-                    None => {
-                        return Err(#vm_error::from(#vm_error_kind::MissingStructField {
-                            target: std::any::type_name::<Self>(),
-                            name: #name,
-                        }));
-                    }
+                    None => #missing,
                 }
             });
         }
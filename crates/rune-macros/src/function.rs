@@ -0,0 +1,188 @@
+use crate::context::{Context, Tokens};
+use crate::internals::{INSTANCE, KEEP};
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned, ToTokens};
+use syn::spanned::Spanned as _;
+
+/// Parsed `#[rune::function(..)]` attributes.
+#[derive(Default)]
+pub struct FunctionAttrs {
+    /// `#[rune::function(instance)]` to register the function as an instance
+    /// function even though it doesn't take `self`.
+    instance: bool,
+    /// `#[rune::function(keep)]` to keep the function as-is, and generate the
+    /// metadata function under a separate name.
+    keep: bool,
+}
+
+impl FunctionAttrs {
+    /// Parse function attributes.
+    pub fn parse(args: syn::AttributeArgs) -> Result<Self, Vec<syn::Error>> {
+        let mut attrs = Self::default();
+        let mut errors = Vec::new();
+
+        for arg in args {
+            match arg {
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) if path == INSTANCE => {
+                    attrs.instance = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) if path == KEEP => {
+                    attrs.keep = true;
+                }
+                arg => {
+                    errors.push(syn::Error::new_spanned(arg, "unsupported attribute"));
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(attrs)
+    }
+}
+
+/// The function being annotated.
+pub struct Function {
+    f: syn::ItemFn,
+}
+
+impl syn::parse::Parse for Function {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        Ok(Self { f: input.parse()? })
+    }
+}
+
+impl Function {
+    /// Expand the function into a function producing its metadata, and the
+    /// function itself.
+    pub fn expand(self, attrs: FunctionAttrs) -> Result<TokenStream, Vec<syn::Error>> {
+        let ctx = Context::new();
+        let tokens = ctx.tokens_with_module(None);
+
+        let mut f = self.f;
+
+        if !f.sig.generics.params.is_empty() {
+            return Err(vec![syn::Error::new_spanned(
+                &f.sig.generics,
+                "generic functions are not supported",
+            )]);
+        }
+
+        let has_self = has_receiver(&f.sig);
+        let ident = f.sig.ident.clone();
+
+        let (meta_ident, real_ident) = if attrs.keep {
+            (
+                syn::Ident::new(&format!("{}__meta", ident), ident.span()),
+                ident.clone(),
+            )
+        } else {
+            (
+                ident.clone(),
+                syn::Ident::new(&format!("__rune_fn__{}", ident), ident.span()),
+            )
+        };
+
+        let path = if has_self {
+            quote!(Self::#real_ident)
+        } else {
+            quote!(#real_ident)
+        };
+
+        let function_meta_data = &tokens.function_meta_data;
+        let expanded = expand_meta(&tokens, &f.sig, &f.attrs, &path, attrs.instance);
+
+        let doc_attrs = f
+            .attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("doc"))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let vis = &f.vis;
+
+        let meta = quote_spanned! { f.sig.span() =>
+            #(#doc_attrs)*
+            #[allow(non_snake_case)]
+            #vis fn #meta_ident() -> #function_meta_data {
+                #expanded
+            }
+        };
+
+        if !attrs.keep {
+            f.sig.ident = real_ident;
+            f.attrs.retain(|attr| !attr.path.is_ident("doc"));
+            f.attrs.push(syn::parse_quote!(#[doc(hidden)]));
+            f.attrs.push(syn::parse_quote!(#[allow(non_snake_case)]));
+        }
+
+        Ok(quote! {
+            #meta
+            #f
+        })
+    }
+}
+
+/// Test if the function takes `self`.
+pub(crate) fn has_receiver(sig: &syn::Signature) -> bool {
+    matches!(sig.inputs.first(), Some(syn::FnArg::Receiver(..)))
+}
+
+/// Expand the expression constructing the metadata for the function with the
+/// given signature and attributes, which can be called through `path`.
+pub(crate) fn expand_meta(
+    tokens: &Tokens,
+    sig: &syn::Signature,
+    attrs: &[syn::Attribute],
+    path: &TokenStream,
+    instance: bool,
+) -> TokenStream {
+    let mut args = Vec::new();
+
+    for arg in &sig.inputs {
+        let name = match arg {
+            syn::FnArg::Receiver(..) => String::from("self"),
+            syn::FnArg::Typed(typed) => match &*typed.pat {
+                syn::Pat::Ident(ident) => ident.ident.to_string(),
+                pat => pat.to_token_stream().to_string(),
+            },
+        };
+
+        args.push(syn::LitStr::new(&name, arg.span()));
+    }
+
+    let docs = attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(syn::Meta::NameValue(syn::MetaNameValue {
+                lit: syn::Lit::Str(doc),
+                ..
+            })) => Some(doc),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let name = syn::LitStr::new(&sig.ident.to_string(), sig.ident.span());
+
+    let constructor = match (has_receiver(sig) || instance, sig.asyncness.is_some()) {
+        (false, false) => quote!(function),
+        (false, true) => quote!(async_function),
+        (true, false) => quote!(instance),
+        (true, true) => quote!(async_instance),
+    };
+
+    let function_meta_data = &tokens.function_meta_data;
+    let function_meta_kind = &tokens.function_meta_kind;
+
+    quote_spanned! { sig.span() =>
+        #function_meta_data::new(
+            #function_meta_kind::#constructor(#path),
+            #name,
+            &[#(#args),*],
+            &[#(#docs),*],
+        )
+    }
+}
@@ -18,10 +18,14 @@ pub const PARSE: Symbol = Symbol("parse");
 pub const NAME: Symbol = Symbol("name");
 pub const MODULE: Symbol = Symbol("module");
 pub const INSTALL_WITH: Symbol = Symbol("install_with");
+pub const INSTANCE: Symbol = Symbol("instance");
+pub const KEEP: Symbol = Symbol("keep");
 
 pub const GET: Symbol = Symbol("get");
 pub const SET: Symbol = Symbol("set");
 pub const COPY: Symbol = Symbol("copy");
+pub const INDEX: Symbol = Symbol("index");
+pub const DEFAULT: Symbol = Symbol("default");
 
 pub const ADD_ASSIGN: Symbol = Symbol("add_assign");
 pub const SUB_ASSIGN: Symbol = Symbol("sub_assign");
@@ -36,6 +40,8 @@ pub const REM_ASSIGN: Symbol = Symbol("rem_assign");
 
 pub const PROTOCOL_GET: Symbol = Symbol("GET");
 pub const PROTOCOL_SET: Symbol = Symbol("SET");
+pub const PROTOCOL_INDEX_GET: Symbol = Symbol("INDEX_GET");
+pub const PROTOCOL_INDEX_SET: Symbol = Symbol("INDEX_SET");
 pub const PROTOCOL_ADD_ASSIGN: Symbol = Symbol("ADD_ASSIGN");
 pub const PROTOCOL_SUB_ASSIGN: Symbol = Symbol("SUB_ASSIGN");
 pub const PROTOCOL_DIV_ASSIGN: Symbol = Symbol("DIV_ASSIGN");
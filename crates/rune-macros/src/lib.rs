@@ -48,8 +48,10 @@ extern crate proc_macro;
 mod any;
 mod context;
 mod from_value;
+mod function;
 mod instrument;
 mod internals;
+mod module;
 mod opaque;
 mod option_spanned;
 mod parse;
@@ -57,6 +59,7 @@ mod quote;
 mod spanned;
 mod to_tokens;
 mod to_value;
+mod walk;
 
 /// Macro helper function for quoting the token stream as macro output.
 ///
@@ -101,6 +104,14 @@ pub fn to_tokens(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     derive.expand().unwrap_or_else(to_compile_errors).into()
 }
 
+/// Helper derive to implement `Walk`.
+#[proc_macro_derive(Walk, attributes(rune))]
+#[doc(hidden)]
+pub fn walk(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let derive = syn::parse_macro_input!(input as walk::Derive);
+    derive.expand().unwrap_or_else(to_compile_errors).into()
+}
+
 /// Helper derive to implement `Parse`.
 #[proc_macro_derive(Parse, attributes(rune))]
 #[doc(hidden)]
@@ -165,6 +176,49 @@ pub fn opaque(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 /// assert_eq!(foo.field, 42);
 /// # Ok(()) }
 /// ```
+///
+/// ## `#[rune(name = "..")]` attribute
+///
+/// Fields and variants can be looked up under a different name:
+///
+/// ```
+/// use rune::FromValue;
+///
+/// #[derive(FromValue)]
+/// enum Shape {
+///     #[rune(name = "circle")]
+///     Circle {
+///         #[rune(name = "r")]
+///         radius: f64,
+///     },
+/// }
+/// ```
+///
+/// ## `#[rune(default)]` attribute
+///
+/// Fields which are missing from the value are set to their default value
+/// instead of raising an error. A custom default can be specified with
+/// `#[rune(default = "path::to::function")]`.
+///
+/// ```
+/// use rune::FromValue;
+///
+/// fn retries() -> u32 {
+///     3
+/// }
+///
+/// #[derive(FromValue)]
+/// struct Config {
+///     #[rune(default)]
+///     verbose: bool,
+///     #[rune(default = "retries")]
+///     retries: u32,
+/// }
+/// ```
+///
+/// Enums can also be converted from the representation produced by
+/// [ToValue][macro@ToValue], where unit variants are strings and other
+/// variants are objects with a single entry.
 #[proc_macro_derive(FromValue, attributes(rune))]
 pub fn from_value(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = syn::parse_macro_input!(input as syn::DeriveInput);
@@ -205,6 +259,11 @@ pub fn from_value(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 /// assert_eq!(foo, 43);
 /// # Ok(()) }
 /// ```
+///
+/// Enums are converted so that unit variants become the name of the variant,
+/// and other variants become an object with a single entry mapping the name
+/// of the variant to its data. Names can be changed with the
+/// `#[rune(name = "..")]` attribute on fields and variants.
 #[proc_macro_derive(ToValue, attributes(rune))]
 pub fn to_value(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = syn::parse_macro_input!(input as syn::DeriveInput);
@@ -240,6 +299,62 @@ pub fn to_value(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 ///     Ok(module)
 /// }
 /// ```
+///
+/// ## Field attributes
+///
+/// Fields can be exposed to scripts by generating protocol functions for them:
+///
+/// * `#[rune(get)]` and `#[rune(set)]` to read and assign the field, like
+///   `value.field` and `value.field = 42`. Fields are cloned when read unless
+///   they are marked with `#[rune(copy)]`.
+/// * `#[rune(add_assign)]`, `#[rune(sub_assign)]` and the other assign
+///   operators to support expressions like `value.field += 1`.
+/// * `#[rune(index)]` to read and assign the field by name through the index
+///   protocols, like `value["field"]`. Reading or assigning an unknown name
+///   raises an error.
+///
+/// Fields can be exposed under a different name with `#[rune(name = "..")]`.
+///
+/// ```
+/// use rune::Any;
+///
+/// #[derive(Any)]
+/// struct Player {
+///     #[rune(get, set, add_assign, index, copy)]
+///     health: i64,
+///     #[rune(get, index, name = "title")]
+///     name: String,
+/// }
+/// ```
+///
+/// ## Generic types
+///
+/// Generic types can derive `Any` as well. Every type parameter is required
+/// to be `'static` and implement `Named`, and fields using a protocol like
+/// `#[rune(get)]` add the bounds needed to install it.
+///
+/// Each instantiation of a generic type is a distinct type with its own type
+/// hash, so every instantiation used needs to be registered separately. They
+/// are named after their parameters, like `Pair<int, String>`.
+///
+/// ```
+/// use rune::Any;
+///
+/// #[derive(Any)]
+/// struct Pair<A, B> {
+///     #[rune(get, set)]
+///     first: A,
+///     #[rune(get)]
+///     second: B,
+/// }
+///
+/// fn install() -> Result<rune::Module, rune::ContextError> {
+///     let mut module = rune::Module::new();
+///     module.ty::<Pair<i64, String>>()?;
+///     module.ty::<Pair<f64, f64>>()?;
+///     Ok(module)
+/// }
+/// ```
 #[proc_macro_derive(Any, attributes(rune))]
 pub fn any(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let derive = syn::parse_macro_input!(input as any::Derive);
@@ -257,6 +372,147 @@ pub fn __internal_impl_any(input: proc_macro::TokenStream) -> proc_macro::TokenS
         .into()
 }
 
+/// Attribute macro for native functions, which captures the name, argument
+/// names, doc comments and async-ness of the function so that it can be
+/// registered with `Module::function_meta`.
+///
+/// The annotated function is replaced with a function producing its metadata,
+/// so it can't be called directly from Rust anymore. Use
+/// `#[rune::function(keep)]` to keep the function as-is, in which case the
+/// metadata function is named `<name>__meta` instead.
+///
+/// Methods taking `self` in an `impl` block are registered as instance
+/// functions. Free functions can be registered as instance functions with
+/// `#[rune::function(instance)]`, in which case the first argument is the
+/// instance.
+///
+/// # Examples
+///
+/// ```
+/// use rune::Any;
+///
+/// #[derive(Any)]
+/// struct Timer {
+///     elapsed: i64,
+/// }
+///
+/// impl Timer {
+///     /// Get the elapsed time.
+///     #[rune::function]
+///     fn elapsed(&self) -> i64 {
+///         self.elapsed
+///     }
+/// }
+///
+/// /// Wait for the given number of ticks.
+/// #[rune::function]
+/// async fn wait(ticks: i64) -> i64 {
+///     ticks
+/// }
+///
+/// /// Double the given number.
+/// #[rune::function(keep)]
+/// fn double(n: i64) -> i64 {
+///     n * 2
+/// }
+///
+/// # fn main() -> rune::Result<()> {
+/// let mut module = rune::Module::new();
+/// module.ty::<Timer>()?;
+/// module.function_meta(Timer::elapsed)?;
+/// module.function_meta(wait)?;
+/// module.function_meta(double__meta)?;
+///
+/// assert_eq!(double(2), 4);
+/// # Ok(()) }
+/// ```
+#[proc_macro_attribute]
+pub fn function(
+    attrs: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let attrs = syn::parse_macro_input!(attrs as syn::AttributeArgs);
+    let function = syn::parse_macro_input!(item as function::Function);
+
+    let output = match function::FunctionAttrs::parse(attrs) {
+        Ok(attrs) => function.expand(attrs),
+        Err(errors) => Err(errors),
+    };
+
+    output.unwrap_or_else(to_compile_errors).into()
+}
+
+/// Attribute macro for `mod` and `impl` blocks, which adds a `module`
+/// function to the block constructing a `Module` out of its public items.
+///
+/// * Public functions are registered with their argument names and doc
+///   comments, like with `#[rune::function]`.
+/// * In an `impl` block, the type is registered, methods taking `self` are
+///   registered as instance functions, and other functions as associated
+///   functions of the type.
+/// * Public constants are registered as constants.
+///
+/// Items can be left out of the module with `#[rune(skip)]`. The module can be
+/// given an item like `#[rune::module(::my_crate::utils)]`, where a leading
+/// `::` indicates that the first component is the name of a crate.
+///
+/// # Examples
+///
+/// ```
+/// use rune::Any;
+///
+/// #[rune::module(::shapes)]
+/// mod shapes {
+///     /// The number of sides of a triangle.
+///     pub const TRIANGLE: i64 = 3;
+///
+///     /// Calculate the area of a rectangle.
+///     pub fn area(width: i64, height: i64) -> i64 {
+///         width * height
+///     }
+///
+///     #[rune(skip)]
+///     pub fn internal() {}
+/// }
+///
+/// #[derive(Any)]
+/// struct Point {
+///     x: i64,
+///     y: i64,
+/// }
+///
+/// #[rune::module]
+/// impl Point {
+///     /// Construct a new point.
+///     pub fn new(x: i64, y: i64) -> Self {
+///         Self { x, y }
+///     }
+///
+///     /// The sum of the coordinates of the point.
+///     pub fn sum(&self) -> i64 {
+///         self.x + self.y
+///     }
+/// }
+///
+/// # fn main() -> rune::Result<()> {
+/// let mut context = rune::Context::new();
+/// context.install(&shapes::module()?)?;
+/// context.install(&Point::module()?)?;
+/// # Ok(()) }
+/// ```
+#[proc_macro_attribute]
+pub fn module(
+    attrs: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let attrs = syn::parse_macro_input!(attrs as module::ModuleAttrs);
+    let module = syn::parse_macro_input!(item as module::Module);
+    module
+        .expand(attrs)
+        .unwrap_or_else(to_compile_errors)
+        .into()
+}
+
 /// Internal macro to instrument a function which is threading AST.
 #[proc_macro_attribute]
 #[doc(hidden)]
@@ -0,0 +1,324 @@
+use crate::context::{Context, Tokens};
+use crate::function::{expand_meta, has_receiver};
+use crate::internals::{RUNE, SKIP};
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned as _;
+
+/// Parsed `#[rune::module(..)]` attributes.
+#[derive(Default)]
+pub struct ModuleAttrs {
+    /// The item the module is constructed for.
+    path: Option<syn::Path>,
+}
+
+impl syn::parse::Parse for ModuleAttrs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        if input.is_empty() {
+            return Ok(Self::default());
+        }
+
+        Ok(Self {
+            path: Some(input.parse()?),
+        })
+    }
+}
+
+/// The `mod` or `impl` block being annotated.
+pub struct Module {
+    item: syn::Item,
+}
+
+impl syn::parse::Parse for Module {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        Ok(Self {
+            item: input.parse()?,
+        })
+    }
+}
+
+impl Module {
+    /// Expand the block, adding a `module` function to it which constructs
+    /// the module.
+    pub fn expand(self, attrs: ModuleAttrs) -> Result<TokenStream, Vec<syn::Error>> {
+        let ctx = Context::new();
+        let tokens = ctx.tokens_with_module(None);
+
+        let mut expander = Expander {
+            tokens,
+            errors: Vec::new(),
+            installers: Vec::new(),
+        };
+
+        let item = match self.item {
+            syn::Item::Mod(item) => expander.expand_mod(&attrs, item),
+            syn::Item::Impl(item) => expander.expand_impl(&attrs, item),
+            item => {
+                return Err(vec![syn::Error::new_spanned(
+                    item,
+                    "expected a `mod` or an `impl` block",
+                )]);
+            }
+        };
+
+        if !expander.errors.is_empty() {
+            return Err(expander.errors);
+        }
+
+        Ok(item)
+    }
+}
+
+struct Expander {
+    tokens: Tokens,
+    errors: Vec<syn::Error>,
+    installers: Vec<TokenStream>,
+}
+
+impl Expander {
+    /// Expand a `mod` block, registering its public functions and constants.
+    fn expand_mod(&mut self, attrs: &ModuleAttrs, mut item: syn::ItemMod) -> TokenStream {
+        let content = match &mut item.content {
+            Some((_, content)) => content,
+            None => {
+                self.errors.push(syn::Error::new_spanned(
+                    &item,
+                    "expected a module with a body",
+                ));
+                return TokenStream::new();
+            }
+        };
+
+        for item in content.iter_mut() {
+            match item {
+                syn::Item::Fn(f) => {
+                    if take_skip(&mut f.attrs) || !is_visible(&f.vis) {
+                        continue;
+                    }
+
+                    let ident = &f.sig.ident;
+
+                    if let Some(meta) = self.meta(&f.sig, &f.attrs, &quote!(#ident)) {
+                        self.installers.push(quote_spanned! { f.span() =>
+                            module.function_meta(|| #meta)?;
+                        });
+                    }
+                }
+                syn::Item::Const(c) => {
+                    if take_skip(&mut c.attrs) || !is_visible(&c.vis) {
+                        continue;
+                    }
+
+                    let ident = &c.ident;
+                    let name = syn::LitStr::new(&ident.to_string(), ident.span());
+
+                    self.installers.push(quote_spanned! { c.span() =>
+                        module.constant(&[#name], #ident)?;
+                    });
+                }
+                _ => (),
+            }
+        }
+
+        let function = self.expand_function(attrs, None);
+        content.push(syn::Item::Verbatim(function));
+        quote!(#item)
+    }
+
+    /// Expand an `impl` block, registering its public functions and associated
+    /// constants.
+    fn expand_impl(&mut self, attrs: &ModuleAttrs, mut item: syn::ItemImpl) -> TokenStream {
+        if let Some((_, path, _)) = &item.trait_ {
+            self.errors.push(syn::Error::new_spanned(
+                path,
+                "trait implementations are not supported",
+            ));
+            return TokenStream::new();
+        }
+
+        if !item.generics.params.is_empty() {
+            self.errors.push(syn::Error::new_spanned(
+                &item.generics,
+                "generic implementations are not supported",
+            ));
+            return TokenStream::new();
+        }
+
+        let ty_name = match &*item.self_ty {
+            syn::Type::Path(path) => match path.path.segments.last() {
+                Some(segment) => syn::LitStr::new(&segment.ident.to_string(), segment.ident.span()),
+                None => return TokenStream::new(),
+            },
+            ty => {
+                self.errors
+                    .push(syn::Error::new_spanned(ty, "expected a named type"));
+                return TokenStream::new();
+            }
+        };
+
+        self.installers.push(quote_spanned! { item.self_ty.span() =>
+            module.ty::<Self>()?;
+        });
+
+        for impl_item in item.items.iter_mut() {
+            match impl_item {
+                syn::ImplItem::Method(f) => {
+                    if take_skip(&mut f.attrs) || !is_visible(&f.vis) {
+                        continue;
+                    }
+
+                    let ident = &f.sig.ident;
+                    let name = syn::LitStr::new(&ident.to_string(), ident.span());
+
+                    let meta = match self.meta(&f.sig, &f.attrs, &quote!(Self::#ident)) {
+                        Some(meta) => meta,
+                        None => continue,
+                    };
+
+                    if has_receiver(&f.sig) {
+                        self.installers.push(quote_spanned! { f.span() =>
+                            module.function_meta(|| #meta)?;
+                        });
+                    } else {
+                        self.installers.push(quote_spanned! { f.span() =>
+                            module.function_meta(|| #meta.with_item(&[#ty_name, #name]))?;
+                        });
+                    }
+                }
+                syn::ImplItem::Const(c) => {
+                    if take_skip(&mut c.attrs) || !is_visible(&c.vis) {
+                        continue;
+                    }
+
+                    let ident = &c.ident;
+                    let name = syn::LitStr::new(&ident.to_string(), ident.span());
+
+                    self.installers.push(quote_spanned! { c.span() =>
+                        module.constant(&[#ty_name, #name], Self::#ident)?;
+                    });
+                }
+                _ => (),
+            }
+        }
+
+        let function = self.expand_function(attrs, Some(&ty_name));
+        item.items.push(syn::ImplItem::Verbatim(function));
+        quote!(#item)
+    }
+
+    /// Expand the metadata for a function, making sure that it can be
+    /// registered.
+    fn meta(
+        &mut self,
+        sig: &syn::Signature,
+        attrs: &[syn::Attribute],
+        path: &TokenStream,
+    ) -> Option<TokenStream> {
+        if !sig.generics.params.is_empty() {
+            self.errors.push(syn::Error::new_spanned(
+                &sig.generics,
+                "generic functions are not supported, skip them with `#[rune(skip)]`",
+            ));
+            return None;
+        }
+
+        if let Some(attr) = attrs.iter().find(|attr| is_function_attr(attr)) {
+            self.errors.push(syn::Error::new_spanned(
+                attr,
+                "functions are already registered by `#[rune::module]`",
+            ));
+            return None;
+        }
+
+        Some(expand_meta(&self.tokens, sig, attrs, path, false))
+    }
+
+    /// Expand the function constructing the module.
+    fn expand_function(
+        &mut self,
+        attrs: &ModuleAttrs,
+        ty_name: Option<&syn::LitStr>,
+    ) -> TokenStream {
+        let module = &self.tokens.module;
+        let context_error = &self.tokens.context_error;
+        let installers = &self.installers;
+
+        let new = match &attrs.path {
+            Some(path) => {
+                let mut components = path
+                    .segments
+                    .iter()
+                    .map(|s| syn::LitStr::new(&s.ident.to_string(), s.ident.span()));
+
+                if path.leading_colon.is_some() {
+                    let first = components.next();
+                    let rest = components.collect::<Vec<_>>();
+
+                    if rest.is_empty() {
+                        quote!(#module::with_crate(#first))
+                    } else {
+                        quote!(#module::with_crate_item(#first, &[#(#rest),*]))
+                    }
+                } else {
+                    quote!(#module::with_item(&[#(#components),*]))
+                }
+            }
+            None => quote!(#module::new()),
+        };
+
+        let doc = match ty_name {
+            Some(ty_name) => format!(
+                " Construct a module containing the type `{}` and its public functions and constants.",
+                ty_name.value()
+            ),
+            None => String::from(
+                " Construct a module containing the public functions and constants of this module.",
+            ),
+        };
+
+        quote! {
+            #[doc = #doc]
+            pub fn module() -> ::std::result::Result<#module, #context_error> {
+                let mut module = #new;
+                #(#installers)*
+                Ok(module)
+            }
+        }
+    }
+}
+
+/// Test if the item is visible outside of the block.
+fn is_visible(vis: &syn::Visibility) -> bool {
+    !matches!(vis, syn::Visibility::Inherited)
+}
+
+/// Test if the attribute is `#[rune::function]`.
+fn is_function_attr(attr: &syn::Attribute) -> bool {
+    let mut it = attr.path.segments.iter();
+
+    matches!(
+        (it.next(), it.next(), it.next()),
+        (Some(a), Some(b), None) if a.ident == RUNE && b.ident == "function"
+    )
+}
+
+/// Remove a `#[rune(skip)]` attribute, returning `true` if it was present.
+fn take_skip(attrs: &mut Vec<syn::Attribute>) -> bool {
+    let len = attrs.len();
+
+    attrs.retain(|attr| {
+        if attr.path != RUNE {
+            return true;
+        }
+
+        !matches!(
+            attr.parse_meta(),
+            Ok(syn::Meta::List(list)) if list.nested.iter().any(|nested| matches!(
+                nested,
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) if path == SKIP
+            ))
+        )
+    });
+
+    attrs.len() != len
+}
@@ -159,6 +159,21 @@ impl Expander {
             }
         };
 
+        // NB: with a single spanned field the end is the same as the
+        // beginning, and joining them would compute the span twice which is
+        // exponential for recursive types like expressions.
+        let mut spanned = 0;
+
+        for (_, field) in &values {
+            if !self.ctx.field_attrs(&field.attrs)?.skip() {
+                spanned += 1;
+            }
+        }
+
+        if spanned == 1 {
+            return Some(begin);
+        }
+
         let (end_optional, end) =
             self.ctx
                 .build_spanned_iter(&self.tokens, true, values.into_iter().rev())?;
@@ -15,7 +15,7 @@ impl Expander {
         input: &syn::DeriveInput,
         st: &syn::DataStruct,
     ) -> Option<TokenStream> {
-        let inner = self.expand_fields(&st.fields)?;
+        let (pattern, inner) = self.expand_fields(&st.fields)?;
 
         let ident = &input.ident;
         let value = &self.tokens.value;
@@ -25,23 +25,85 @@ impl Expander {
         Some(quote! {
             impl #to_value for #ident {
                 fn to_value(self) -> ::std::result::Result<#value, #vm_error> {
-                    #inner
+                    let Self #pattern = self;
+                    Ok(#inner)
                 }
             }
         })
     }
 
-    /// Expand field decoding.
-    fn expand_fields(&mut self, fields: &syn::Fields) -> Option<TokenStream> {
+    /// Expand on an enum.
+    ///
+    /// Unit variants are converted into the name of the variant, and other
+    /// variants into an object with a single entry mapping the name of the
+    /// variant to its data.
+    fn expand_enum(&mut self, input: &syn::DeriveInput, en: &syn::DataEnum) -> Option<TokenStream> {
+        let mut matches = Vec::new();
+
+        for variant in &en.variants {
+            let attrs = self.ctx.variant_attrs(&variant.attrs)?;
+            let ident = &variant.ident;
+
+            let name = match attrs.name {
+                Some(name) => name,
+                None => syn::LitStr::new(&ident.to_string(), ident.span()),
+            };
+
+            let expanded = match &variant.fields {
+                syn::Fields::Unit => {
+                    let value = &self.tokens.value;
+
+                    quote_spanned! { variant.span() =>
+                        Self::#ident => Ok(#value::from(String::from(#name)))
+                    }
+                }
+                fields => {
+                    let (pattern, inner) = self.expand_fields(fields)?;
+                    let value = &self.tokens.value;
+                    let object = &self.tokens.object;
+
+                    quote_spanned! { variant.span() =>
+                        Self::#ident #pattern => {
+                            let value = #inner;
+                            let mut object = <#object>::with_capacity(1);
+                            object.insert(String::from(#name), value);
+                            Ok(#value::from(object))
+                        }
+                    }
+                }
+            };
+
+            matches.push(expanded);
+        }
+
+        let ident = &input.ident;
+        let value = &self.tokens.value;
+        let vm_error = &self.tokens.vm_error;
+        let to_value = &self.tokens.to_value;
+
+        Some(quote! {
+            impl #to_value for #ident {
+                fn to_value(self) -> ::std::result::Result<#value, #vm_error> {
+                    match self {
+                        #(#matches,)*
+                    }
+                }
+            }
+        })
+    }
+
+    /// Expand field encoding, returning the pattern used to bind the fields
+    /// and the expression which encodes them into a value.
+    fn expand_fields(&mut self, fields: &syn::Fields) -> Option<(TokenStream, TokenStream)> {
         match fields {
             syn::Fields::Unnamed(named) => self.expand_unnamed(named),
             syn::Fields::Named(named) => self.expand_named(named),
             syn::Fields::Unit => {
-                self.ctx.errors.push(syn::Error::new_spanned(
-                    fields,
-                    "unit structs are not supported",
-                ));
-                None
+                let value = &self.tokens.value;
+                Some((
+                    TokenStream::new(),
+                    quote_spanned!(fields.span() => #value::Unit),
+                ))
             }
         }
     }
@@ -61,61 +123,83 @@ impl Expander {
     }
 
     /// Expand unnamed fields.
-    fn expand_unnamed(&mut self, unnamed: &syn::FieldsUnnamed) -> Option<TokenStream> {
+    fn expand_unnamed(
+        &mut self,
+        unnamed: &syn::FieldsUnnamed,
+    ) -> Option<(TokenStream, TokenStream)> {
+        let mut bindings = Vec::new();
         let mut to_values = Vec::new();
 
         for (index, field) in unnamed.unnamed.iter().enumerate() {
             let _ = self.ctx.field_attrs(&field.attrs)?;
 
-            let index = syn::Index::from(index);
+            let binding = quote::format_ident!("f{}", index, span = field.span());
 
             let to_value = &self.tokens.to_value;
 
             to_values.push(quote_spanned! {
                 field.span() =>
-                tuple.push(#to_value::to_value(self.#index)?);
+                tuple.push(#to_value::to_value(#binding)?);
             });
+
+            bindings.push(binding);
         }
 
         let cap = unnamed.unnamed.len();
         let value = &self.tokens.value;
         let tuple = &self.tokens.tuple;
 
-        Some(quote_spanned! {
-            unnamed.span() =>
-            let mut tuple = Vec::with_capacity(#cap);
-            #(#to_values)*
-            Ok(#value::from(#tuple::from(tuple)))
-        })
+        let pattern = quote_spanned!(unnamed.span() => (#(#bindings),*));
+
+        let inner = quote_spanned! {
+            unnamed.span() => {
+                let mut tuple = Vec::with_capacity(#cap);
+                #(#to_values)*
+                #value::from(#tuple::from(tuple))
+            }
+        };
+
+        Some((pattern, inner))
     }
 
     /// Expand named fields.
-    fn expand_named(&mut self, named: &syn::FieldsNamed) -> Option<TokenStream> {
+    fn expand_named(&mut self, named: &syn::FieldsNamed) -> Option<(TokenStream, TokenStream)> {
+        let mut bindings = Vec::new();
         let mut to_values = Vec::new();
 
         for field in &named.named {
             let ident = self.field_ident(field)?;
-            let _ = self.ctx.field_attrs(&field.attrs)?;
+            let attrs = self.ctx.field_attrs(&field.attrs)?;
 
-            let name = &syn::LitStr::new(&ident.to_string(), ident.span());
+            let name = match attrs.name {
+                Some(name) => name,
+                None => syn::LitStr::new(&ident.to_string(), ident.span()),
+            };
 
             let to_value = &self.tokens.to_value;
 
             to_values.push(quote_spanned! {
                 field.span() =>
-                object.insert(String::from(#name), #to_value::to_value(self.#ident)?);
+                object.insert(String::from(#name), #to_value::to_value(#ident)?);
             });
+
+            bindings.push(ident);
         }
 
         let value = &self.tokens.value;
         let object = &self.tokens.object;
 
-        Some(quote_spanned! {
-            named.span() =>
-            let mut object = <#object>::new();
-            #(#to_values)*
-            Ok(#value::from(object))
-        })
+        let pattern = quote_spanned!(named.span() => { #(#bindings),* });
+
+        let inner = quote_spanned! {
+            named.span() => {
+                let mut object = <#object>::new();
+                #(#to_values)*
+                #value::from(object)
+            }
+        };
+
+        Some((pattern, inner))
     }
 }
 
@@ -143,10 +227,9 @@ pub(super) fn expand(input: &syn::DeriveInput) -> Result<TokenStream, Vec<syn::E
             }
         }
         syn::Data::Enum(en) => {
-            expander.ctx.errors.push(syn::Error::new_spanned(
-                en.enum_token,
-                "not supported on enums",
-            ));
+            if let Some(expanded) = expander.expand_enum(input, en) {
+                return Ok(expanded);
+            }
         }
         syn::Data::Union(un) => {
             expander.ctx.errors.push(syn::Error::new_spanned(
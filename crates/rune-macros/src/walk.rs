@@ -0,0 +1,237 @@
+use crate::context::{Context, Tokens};
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned as _;
+
+/// Derive implementation of the `Walk` macro.
+pub struct Derive {
+    input: syn::DeriveInput,
+}
+
+impl syn::parse::Parse for Derive {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        Ok(Self {
+            input: input.parse()?,
+        })
+    }
+}
+
+impl Derive {
+    pub(super) fn expand(self) -> Result<TokenStream, Vec<syn::Error>> {
+        let ctx = Context::with_crate();
+        let tokens = ctx.tokens_with_module(None);
+
+        let mut expander = Expander { ctx, tokens };
+
+        let _ = expander.ctx.type_attrs(&self.input.attrs);
+
+        let bodies = match &self.input.data {
+            syn::Data::Struct(st) => expander.expand_struct(&st.fields),
+            syn::Data::Enum(en) => expander.expand_enum(en),
+            syn::Data::Union(un) => {
+                expander.ctx.errors.push(syn::Error::new_spanned(
+                    un.union_token,
+                    "not supported on unions",
+                ));
+                None
+            }
+        };
+
+        match bodies {
+            Some((walk, walk_mut)) if expander.ctx.errors.is_empty() => {
+                Ok(expander.expand_impl(&self.input, walk, walk_mut))
+            }
+            _ => Err(expander.ctx.errors),
+        }
+    }
+}
+
+struct Expander {
+    ctx: Context,
+    tokens: Tokens,
+}
+
+impl Expander {
+    /// Expand the final implementation of `Walk`.
+    fn expand_impl(
+        &self,
+        input: &syn::DeriveInput,
+        walk: TokenStream,
+        walk_mut: TokenStream,
+    ) -> TokenStream {
+        let ident = &input.ident;
+        let generics = &input.generics;
+
+        let walk_trait = &self.tokens.walk;
+        let visit = &self.tokens.visit;
+        let visit_mut = &self.tokens.visit_mut;
+
+        // Generic nodes are transparent, since there's no single visitor
+        // method which would be able to receive them.
+        let accept = if generics.params.is_empty() {
+            let name = snake_case(&ident.to_string());
+            let visit_fn = syn::Ident::new(&format!("visit_{}", name), ident.span());
+            let visit_mut_fn = syn::Ident::new(&format!("visit_{}_mut", name), ident.span());
+
+            Some(quote! {
+                fn accept<V>(&self, visitor: &mut V)
+                where
+                    V: ?Sized + #visit,
+                {
+                    visitor.#visit_fn(self);
+                }
+
+                fn accept_mut<V>(&mut self, visitor: &mut V)
+                where
+                    V: ?Sized + #visit_mut,
+                {
+                    visitor.#visit_mut_fn(self);
+                }
+            })
+        } else {
+            None
+        };
+
+        let bounds = if generics.params.is_empty() {
+            None
+        } else {
+            let bound = generics
+                .params
+                .iter()
+                .map(|param| quote_spanned!(param.span() => #param: #walk_trait));
+
+            Some(quote!(where #(#bound,)*))
+        };
+
+        quote_spanned! { input.span() =>
+            impl #generics #walk_trait for #ident #generics #bounds {
+                #accept
+
+                #[allow(unused_variables)]
+                fn walk<V>(&self, visitor: &mut V)
+                where
+                    V: ?Sized + #visit,
+                {
+                    #walk
+                }
+
+                #[allow(unused_variables)]
+                fn walk_mut<V>(&mut self, visitor: &mut V)
+                where
+                    V: ?Sized + #visit_mut,
+                {
+                    #walk_mut
+                }
+            }
+        }
+    }
+
+    /// Expand walking a struct.
+    fn expand_struct(&mut self, fields: &syn::Fields) -> Option<(TokenStream, TokenStream)> {
+        let (pattern, walk, walk_mut) = self.expand_fields(fields)?;
+
+        Some((
+            quote!(let Self #pattern = self; #(#walk;)*),
+            quote!(let Self #pattern = self; #(#walk_mut;)*),
+        ))
+    }
+
+    /// Expand walking an enum.
+    fn expand_enum(&mut self, en: &syn::DataEnum) -> Option<(TokenStream, TokenStream)> {
+        let mut walk = Vec::new();
+        let mut walk_mut = Vec::new();
+
+        for variant in &en.variants {
+            let ident = &variant.ident;
+            let (pattern, fields, fields_mut) = self.expand_fields(&variant.fields)?;
+            walk.push(quote_spanned!(variant.span() => Self::#ident #pattern => { #(#fields;)* }));
+            walk_mut.push(
+                quote_spanned!(variant.span() => Self::#ident #pattern => { #(#fields_mut;)* }),
+            );
+        }
+
+        Some((
+            quote!(match self { #(#walk,)* }),
+            quote!(match self { #(#walk_mut,)* }),
+        ))
+    }
+
+    /// Expand a destructuring pattern and the per-field walk calls of the given
+    /// fields.
+    fn expand_fields(
+        &mut self,
+        fields: &syn::Fields,
+    ) -> Option<(TokenStream, Vec<TokenStream>, Vec<TokenStream>)> {
+        let walk_trait = &self.tokens.walk;
+
+        let mut walk = Vec::new();
+        let mut walk_mut = Vec::new();
+
+        let pattern = match fields {
+            syn::Fields::Named(named) => {
+                let mut idents = Vec::new();
+
+                for field in &named.named {
+                    let ident = self.ctx.field_ident(field)?;
+                    let attrs = self.ctx.field_attrs(&field.attrs)?;
+
+                    if attrs.skip() {
+                        idents.push(quote_spanned!(field.span() => #ident: _));
+                        continue;
+                    }
+
+                    idents.push(quote_spanned!(field.span() => #ident));
+                    walk.push(quote_spanned!(field.span() => #walk_trait::accept(#ident, visitor)));
+                    walk_mut.push(
+                        quote_spanned!(field.span() => #walk_trait::accept_mut(#ident, visitor)),
+                    );
+                }
+
+                quote!({ #(#idents,)* })
+            }
+            syn::Fields::Unnamed(unnamed) => {
+                let mut idents = Vec::new();
+
+                for (n, field) in unnamed.unnamed.iter().enumerate() {
+                    let attrs = self.ctx.field_attrs(&field.attrs)?;
+
+                    if attrs.skip() {
+                        idents.push(quote_spanned!(field.span() => _));
+                        continue;
+                    }
+
+                    let ident = syn::Ident::new(&format!("f{}", n), field.span());
+                    idents.push(quote_spanned!(field.span() => #ident));
+                    walk.push(quote_spanned!(field.span() => #walk_trait::accept(#ident, visitor)));
+                    walk_mut.push(
+                        quote_spanned!(field.span() => #walk_trait::accept_mut(#ident, visitor)),
+                    );
+                }
+
+                quote!((#(#idents,)*))
+            }
+            syn::Fields::Unit => quote!(),
+        };
+
+        Some((pattern, walk, walk_mut))
+    }
+}
+
+/// Convert a type name like `ExprCall` into `expr_call`.
+fn snake_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+
+    for (n, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if n != 0 {
+                out.push('_');
+            }
+
+            out.extend(c.to_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+
+    out
+}
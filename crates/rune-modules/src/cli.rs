@@ -0,0 +1,539 @@
+//! The native `cli` module for the [Rune Language].
+//!
+//! [Rune Language]: https://rune-rs.github.io
+//!
+//! ## Usage
+//!
+//! Add the following to your `Cargo.toml`:
+//!
+//! ```toml
+//! rune-modules = { version = "0.11.0", features = ["cli"] }
+//! ```
+//!
+//! Install it into your context:
+//!
+//! ```rust
+//! # fn main() -> rune::Result<()> {
+//! let mut context = rune::Context::with_default_modules()?;
+//! context.install(&rune_modules::cli::module(true)?)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Use it in Rune:
+//!
+//! ```rust,ignore
+//! fn main() {
+//!     let command = cli::Command::new("greet")
+//!         .about("Greet some people")
+//!         .flag("loud", "Greet loudly").short('l')
+//!         .option("greeting", "The greeting to use").short('g').default_value("Hello")
+//!         .positional("names", "Who to greet").required().multiple();
+//!
+//!     let args = match command.parse_env() {
+//!         Ok(args) => args,
+//!         Err(error) => {
+//!             println(`${error}`);
+//!             return;
+//!         }
+//!     };
+//!
+//!     for name in args.names {
+//!         let greeting = `${args.greeting}, ${name}!`;
+//!
+//!         if args.loud {
+//!             println(greeting.to_uppercase());
+//!         } else {
+//!             println(greeting);
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! Arguments are declared with `flag`, `option` and `positional`, and the
+//! modifiers `short`, `default_value`, `required` and `multiple` apply to the
+//! argument declared last. Parsing produces an object with a field for each
+//! argument, where dashes in names are replaced with underscores:
+//!
+//! * Flags are `true` if present, or counted if they are `multiple`.
+//! * Options and positionals are a vector of strings if they are `multiple`,
+//!   a string if they are required or have a default, and an optional string
+//!   otherwise.
+//!
+//! Options accept their value as `--name value`, `--name=value`, `-n value`
+//! or `-nvalue`, short flags can be grouped like `-abc`, and everything after
+//! `--` is treated as positional. Passing `-h` or `--help` makes parsing fail
+//! with an error whose `is_help` method returns `true` and which displays as
+//! the generated help.
+//!
+//! `parse_env` parses the arguments provided through the [env module], which
+//! is enabled together with this one.
+//!
+//! [env module]: crate::env
+
+use rune::runtime::{Extensions, Object, Protocol, ToValue as _, Value, VmError};
+use rune::{Any, ContextError, Module};
+use std::fmt;
+use std::fmt::Write as _;
+
+/// Construct the `cli` module.
+pub fn module(_stdio: bool) -> Result<Module, ContextError> {
+    let mut module = Module::with_crate("cli");
+
+    module.ty::<Command>()?;
+    module.function(&["Command", "new"], Command::new)?;
+    module.inst_fn("about", Command::about)?;
+    module.inst_fn("flag", Command::flag)?;
+    module.inst_fn("option", Command::option)?;
+    module.inst_fn("positional", Command::positional)?;
+    module.inst_fn("short", Command::short)?;
+    module.inst_fn("default_value", Command::default_value)?;
+    module.inst_fn("required", Command::required)?;
+    module.inst_fn("multiple", Command::multiple)?;
+    module.inst_fn("help", Command::help)?;
+    module.inst_fn("parse", Command::parse)?;
+    module.inst_fn("parse_env", Command::parse_env)?;
+
+    module.ty::<Error>()?;
+    module.inst_fn("is_help", Error::is_help)?;
+    module.inst_fn(Protocol::STRING_DISPLAY, Error::display)?;
+    Ok(module)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Flag,
+    Option,
+    Positional,
+}
+
+/// A declared argument.
+#[derive(Debug, Clone)]
+struct Arg {
+    name: String,
+    kind: Kind,
+    help: String,
+    short: Option<char>,
+    default: Option<String>,
+    required: bool,
+    multiple: bool,
+}
+
+impl Arg {
+    /// The left column of the argument in the help.
+    fn label(&self) -> String {
+        let mut label = match self.kind {
+            Kind::Positional => format!("<{}>", self.name),
+            Kind::Flag | Kind::Option => match self.short {
+                Some(c) => format!("-{}, --{}", c, self.name),
+                None => format!("    --{}", self.name),
+            },
+        };
+
+        if self.kind == Kind::Option {
+            label.push_str(&format!(" <{}>", self.name));
+        }
+
+        if self.multiple && self.kind != Kind::Flag {
+            label.push_str("...");
+        }
+
+        label
+    }
+}
+
+/// A declarative description of the command-line interface of a script.
+#[derive(Debug, Clone, Any)]
+struct Command {
+    name: String,
+    about: Option<String>,
+    args: Vec<Arg>,
+}
+
+impl Command {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            about: None,
+            args: Vec::new(),
+        }
+    }
+
+    /// Set a description of the command, which is shown in the help.
+    fn about(mut self, about: &str) -> Self {
+        self.about = Some(about.to_owned());
+        self
+    }
+
+    /// Declare a flag which is either present or not.
+    fn flag(self, name: &str, help: &str) -> Result<Self, VmError> {
+        self.push(name, Kind::Flag, help)
+    }
+
+    /// Declare an option which takes a value.
+    fn option(self, name: &str, help: &str) -> Result<Self, VmError> {
+        self.push(name, Kind::Option, help)
+    }
+
+    /// Declare a positional argument, which are assigned in the order they
+    /// are declared.
+    fn positional(self, name: &str, help: &str) -> Result<Self, VmError> {
+        self.push(name, Kind::Positional, help)
+    }
+
+    fn push(mut self, name: &str, kind: Kind, help: &str) -> Result<Self, VmError> {
+        if name.is_empty() || name.starts_with('-') {
+            return Err(VmError::panic(format!("invalid argument name `{}`", name)));
+        }
+
+        if name == "help" || self.args.iter().any(|arg| arg.name == name) {
+            return Err(VmError::panic(format!(
+                "argument `{}` is already defined",
+                name
+            )));
+        }
+
+        self.args.push(Arg {
+            name: name.to_owned(),
+            kind,
+            help: help.to_owned(),
+            short: None,
+            default: None,
+            required: false,
+            multiple: false,
+        });
+
+        Ok(self)
+    }
+
+    /// Get the argument declared last, which modifiers apply to.
+    fn last(&mut self, modifier: &str) -> Result<&mut Arg, VmError> {
+        match self.args.last_mut() {
+            Some(arg) => Ok(arg),
+            None => Err(VmError::panic(format!(
+                "`{}` must follow the declaration of an argument",
+                modifier
+            ))),
+        }
+    }
+
+    /// Give the last flag or option a short name.
+    fn short(mut self, short: char) -> Result<Self, VmError> {
+        if short == 'h' || short == '-' || self.args.iter().any(|a| a.short == Some(short)) {
+            return Err(VmError::panic(format!(
+                "short name `-{}` is already defined",
+                short
+            )));
+        }
+
+        let arg = self.last("short")?;
+
+        if arg.kind == Kind::Positional {
+            return Err(VmError::panic("positional arguments can't have a short name"));
+        }
+
+        arg.short = Some(short);
+        Ok(self)
+    }
+
+    /// Give the last option or positional a default value.
+    fn default_value(mut self, default: &str) -> Result<Self, VmError> {
+        let arg = self.last("default_value")?;
+
+        if arg.kind == Kind::Flag {
+            return Err(VmError::panic("flags can't have a default value"));
+        }
+
+        arg.default = Some(default.to_owned());
+        Ok(self)
+    }
+
+    /// Make the last option or positional required.
+    fn required(mut self) -> Result<Self, VmError> {
+        let arg = self.last("required")?;
+
+        if arg.kind == Kind::Flag {
+            return Err(VmError::panic("flags can't be required"));
+        }
+
+        arg.required = true;
+        Ok(self)
+    }
+
+    /// Allow the last argument to be given any number of times.
+    fn multiple(mut self) -> Result<Self, VmError> {
+        self.last("multiple")?.multiple = true;
+        Ok(self)
+    }
+
+    /// Generate the help for the command.
+    fn help(&self) -> String {
+        let mut out = String::new();
+
+        if let Some(about) = &self.about {
+            out.push_str(about);
+            out.push_str("\n\n");
+        }
+
+        out.push_str("Usage: ");
+        out.push_str(&self.name);
+
+        if self.args.iter().any(|arg| arg.kind != Kind::Positional) {
+            out.push_str(" [OPTIONS]");
+        }
+
+        for arg in self.positionals() {
+            let dots = if arg.multiple { "..." } else { "" };
+
+            if arg.required {
+                let _ = write!(out, " <{}>{}", arg.name, dots);
+            } else {
+                let _ = write!(out, " [{}]{}", arg.name, dots);
+            }
+        }
+
+        out.push('\n');
+
+        let help = Arg {
+            name: String::from("help"),
+            kind: Kind::Flag,
+            help: String::from("Print help"),
+            short: Some('h'),
+            default: None,
+            required: false,
+            multiple: false,
+        };
+
+        let positionals = self.positionals().collect::<Vec<_>>();
+
+        let options = self
+            .args
+            .iter()
+            .filter(|arg| arg.kind != Kind::Positional)
+            .chain(std::iter::once(&help))
+            .collect::<Vec<_>>();
+
+        let width = positionals
+            .iter()
+            .chain(&options)
+            .map(|arg| arg.label().chars().count())
+            .max()
+            .unwrap_or_default();
+
+        for (title, args) in [("Arguments", positionals), ("Options", options)] {
+            if args.is_empty() {
+                continue;
+            }
+
+            let _ = write!(out, "\n{}:\n", title);
+
+            for arg in args {
+                let _ = write!(out, "  {:width$}  {}", arg.label(), arg.help, width = width);
+
+                if let Some(default) = &arg.default {
+                    let _ = write!(out, " [default: {}]", default);
+                }
+
+                out.truncate(out.trim_end().len());
+                out.push('\n');
+            }
+        }
+
+        out
+    }
+
+    fn positionals(&self) -> impl std::iter::Iterator<Item = &Arg> {
+        self.args.iter().filter(|arg| arg.kind == Kind::Positional)
+    }
+
+    /// Parse the given arguments, not including the name of the script.
+    fn parse(&self, args: Vec<String>) -> Result<Result<Object, Error>, VmError> {
+        let values = match self.parse_values(args) {
+            Ok(values) => values,
+            Err(error) => return Ok(Err(error)),
+        };
+
+        let mut object = Object::new();
+
+        for (arg, values) in self.args.iter().zip(values) {
+            let value = match arg.kind {
+                Kind::Flag if arg.multiple => Value::from(values.len() as i64),
+                Kind::Flag => Value::from(!values.is_empty()),
+                _ if arg.multiple => values.to_value()?,
+                _ => {
+                    let value = values.into_iter().last().or_else(|| arg.default.clone());
+
+                    if arg.required || arg.default.is_some() {
+                        Value::from(value.unwrap_or_default())
+                    } else {
+                        value.to_value()?
+                    }
+                }
+            };
+
+            object.insert(arg.name.replace('-', "_"), value);
+        }
+
+        Ok(Ok(object))
+    }
+
+    /// Parse the arguments provided to the script through the `env` module.
+    fn parse_env(&self) -> Result<Result<Object, Error>, VmError> {
+        let args = Extensions::current()
+            .get::<crate::env::Args>()
+            .map(|args| args.as_slice().to_vec())
+            .unwrap_or_default();
+
+        self.parse(args)
+    }
+
+    /// Collect the values given for each argument, in declaration order.
+    fn parse_values(&self, args: Vec<String>) -> Result<Vec<Vec<String>>, Error> {
+        let mut values = vec![Vec::new(); self.args.len()];
+        let positionals = self
+            .args
+            .iter()
+            .enumerate()
+            .filter(|(_, arg)| arg.kind == Kind::Positional)
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+
+        let mut positional = 0;
+        let mut only_positional = false;
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            if !only_positional && arg == "--" {
+                only_positional = true;
+                continue;
+            }
+
+            if !only_positional && (arg == "-h" || arg == "--help") {
+                return Err(Error::help(self.help()));
+            }
+
+            if only_positional || arg == "-" || !arg.starts_with('-') {
+                let index = match positionals.get(positional) {
+                    Some(&index) => index,
+                    None => return Err(self.error(format!("unexpected argument `{}`", arg))),
+                };
+
+                values[index].push(arg);
+
+                if !self.args[index].multiple {
+                    positional += 1;
+                }
+
+                continue;
+            }
+
+            if let Some(long) = arg.strip_prefix("--") {
+                let (name, inline) = match long.split_once('=') {
+                    Some((name, value)) => (name, Some(value.to_owned())),
+                    None => (long, None),
+                };
+
+                let index = self
+                    .args
+                    .iter()
+                    .position(|a| a.kind != Kind::Positional && a.name == name)
+                    .ok_or_else(|| self.error(format!("unexpected argument `--{}`", name)))?;
+
+                let value = match (self.args[index].kind, inline) {
+                    (Kind::Flag, None) => String::new(),
+                    (Kind::Flag, Some(..)) => {
+                        return Err(self.error(format!("flag `--{}` doesn't take a value", name)))
+                    }
+                    (_, Some(value)) => value,
+                    (_, None) => self.value(&self.args[index], args.next())?,
+                };
+
+                values[index].push(value);
+                continue;
+            }
+
+            for (n, c) in arg[1..].char_indices() {
+                if c == 'h' {
+                    return Err(Error::help(self.help()));
+                }
+
+                let index = self
+                    .args
+                    .iter()
+                    .position(|a| a.short == Some(c))
+                    .ok_or_else(|| self.error(format!("unexpected argument `-{}`", c)))?;
+
+                if self.args[index].kind == Kind::Flag {
+                    values[index].push(String::new());
+                    continue;
+                }
+
+                let rest = &arg[1 + n + c.len_utf8()..];
+
+                let value = if rest.is_empty() {
+                    self.value(&self.args[index], args.next())?
+                } else {
+                    rest.to_owned()
+                };
+
+                values[index].push(value);
+                break;
+            }
+        }
+
+        for (arg, values) in self.args.iter().zip(&values) {
+            if arg.required && values.is_empty() && arg.default.is_none() {
+                let label = match arg.kind {
+                    Kind::Positional => format!("<{}>", arg.name),
+                    _ => format!("--{}", arg.name),
+                };
+
+                return Err(self.error(format!("missing required argument `{}`", label)));
+            }
+        }
+
+        Ok(values)
+    }
+
+    /// Get the value following an option.
+    fn value(&self, arg: &Arg, value: Option<String>) -> Result<String, Error> {
+        match value {
+            Some(value) => Ok(value),
+            None => Err(self.error(format!("option `--{}` requires a value", arg.name))),
+        }
+    }
+
+    fn error(&self, message: String) -> Error {
+        Error {
+            message: format!("error: {}\n\nFor more information, try `--help`", message),
+            help: false,
+        }
+    }
+}
+
+/// An error raised when the arguments don't match the command, or when help
+/// was requested.
+#[derive(Debug, Clone, Any)]
+struct Error {
+    message: String,
+    help: bool,
+}
+
+impl Error {
+    fn help(help: String) -> Self {
+        Self {
+            message: help,
+            help: true,
+        }
+    }
+
+    /// Test if the error is caused by help being requested, in which case it
+    /// displays as the help.
+    fn is_help(&self) -> bool {
+        self.help
+    }
+
+    fn display(&self, buf: &mut String) -> fmt::Result {
+        write!(buf, "{}", self.message)
+    }
+}
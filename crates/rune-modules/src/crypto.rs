@@ -0,0 +1,119 @@
+//! The native `crypto` module for the [Rune Language].
+//!
+//! [Rune Language]: https://rune-rs.github.io
+//!
+//! ## Usage
+//!
+//! Add the following to your `Cargo.toml`:
+//!
+//! ```toml
+//! rune-modules = { version = "0.11.0", features = ["crypto"] }
+//! ```
+//!
+//! Install it into your context:
+//!
+//! ```rust
+//! # fn main() -> rune::Result<()> {
+//! let mut context = rune::Context::with_default_modules()?;
+//! context.install(&rune_modules::crypto::module(true)?)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Use it in Rune:
+//!
+//! ```rust,ignore
+//! fn verify_webhook(secret, body, signature) {
+//!     let expected = crypto::hmac_sha256(secret, body);
+//!     crypto::constant_time_eq(expected, signature)
+//! }
+//! ```
+//!
+//! Every function accepts both strings and bytes, and digests are returned as
+//! bytes.
+
+use ring::{digest, hmac};
+use rune::runtime::{Bytes, Value, VmError};
+use rune::{ContextError, Module};
+
+/// Construct the `crypto` module.
+pub fn module(_stdio: bool) -> Result<Module, ContextError> {
+    let mut module = Module::with_crate("crypto");
+    module.function(&["sha1"], sha1)?;
+    module.function(&["sha256"], sha256)?;
+    module.function(&["sha512"], sha512)?;
+    module.function(&["hmac_sha1"], hmac_sha1)?;
+    module.function(&["hmac_sha256"], hmac_sha256)?;
+    module.function(&["hmac_sha512"], hmac_sha512)?;
+    module.function(&["constant_time_eq"], constant_time_eq)?;
+    Ok(module)
+}
+
+/// Coerce a string or bytes into the data to process.
+fn data_of(value: &Value) -> Result<Vec<u8>, VmError> {
+    Ok(match value {
+        Value::Bytes(bytes) => bytes.borrow_ref()?.to_vec(),
+        Value::String(string) => string.borrow_ref()?.as_bytes().to_vec(),
+        Value::StaticString(string) => string.as_bytes().to_vec(),
+        actual => return Err(VmError::expected::<Bytes>(actual.type_info()?)),
+    })
+}
+
+fn digest(algorithm: &'static digest::Algorithm, data: Value) -> Result<Bytes, VmError> {
+    let digest = digest::digest(algorithm, &data_of(&data)?);
+    Ok(Bytes::from_vec(digest.as_ref().to_vec()))
+}
+
+fn hmac(algorithm: hmac::Algorithm, key: Value, data: Value) -> Result<Bytes, VmError> {
+    let key = hmac::Key::new(algorithm, &data_of(&key)?);
+    let tag = hmac::sign(&key, &data_of(&data)?);
+    Ok(Bytes::from_vec(tag.as_ref().to_vec()))
+}
+
+/// Compute the SHA-1 digest of the given data.
+///
+/// SHA-1 is broken, and should only be used to interoperate with existing
+/// protocols.
+fn sha1(data: Value) -> Result<Bytes, VmError> {
+    digest(&digest::SHA1_FOR_LEGACY_USE_ONLY, data)
+}
+
+/// Compute the SHA-256 digest of the given data.
+fn sha256(data: Value) -> Result<Bytes, VmError> {
+    digest(&digest::SHA256, data)
+}
+
+/// Compute the SHA-512 digest of the given data.
+fn sha512(data: Value) -> Result<Bytes, VmError> {
+    digest(&digest::SHA512, data)
+}
+
+/// Compute the HMAC-SHA1 of the given data.
+fn hmac_sha1(key: Value, data: Value) -> Result<Bytes, VmError> {
+    hmac(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, key, data)
+}
+
+/// Compute the HMAC-SHA256 of the given data.
+fn hmac_sha256(key: Value, data: Value) -> Result<Bytes, VmError> {
+    hmac(hmac::HMAC_SHA256, key, data)
+}
+
+/// Compute the HMAC-SHA512 of the given data.
+fn hmac_sha512(key: Value, data: Value) -> Result<Bytes, VmError> {
+    hmac(hmac::HMAC_SHA512, key, data)
+}
+
+/// Compare two strings or byte strings in time which only depends on their
+/// lengths and not their contents, which is required when comparing secrets
+/// like signatures.
+fn constant_time_eq(a: Value, b: Value) -> Result<bool, VmError> {
+    let a = data_of(&a)?;
+    let b = data_of(&b)?;
+
+    if a.len() != b.len() {
+        return Ok(false);
+    }
+
+    let diff = a.iter().zip(&b).fold(0u8, |diff, (a, b)| diff | (a ^ b));
+    Ok(std::hint::black_box(diff) == 0)
+}
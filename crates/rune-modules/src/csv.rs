@@ -0,0 +1,350 @@
+//! The native `csv` module for the [Rune Language].
+//!
+//! [Rune Language]: https://rune-rs.github.io
+//!
+//! ## Usage
+//!
+//! Add the following to your `Cargo.toml`:
+//!
+//! ```toml
+//! rune-modules = { version = "0.11.0", features = ["csv"] }
+//! ```
+//!
+//! Install it into your context:
+//!
+//! ```rust
+//! # fn main() -> rune::Result<()> {
+//! let mut context = rune::Context::with_default_modules()?;
+//! context.install(&rune_modules::csv::module(true)?)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Use it in Rune:
+//!
+//! ```rust,ignore
+//! use csv::{Reader, Writer};
+//!
+//! fn main() {
+//!     let data = "name;age\nAlice;42\n\"Bob; Jr.\";7\n";
+//!     let writer = Writer::new().delimiter('\t');
+//!
+//!     for person in Reader::new(data).delimiter(';').records() {
+//!         writer.write([person.name, person.age]);
+//!     }
+//!
+//!     println(writer.into_string());
+//! }
+//! ```
+//!
+//! Rows are parsed lazily as they are iterated over. `rows` produces every row
+//! as a vector of strings, while `records` treats the first row as a header
+//! and produces the remaining rows as objects keyed by it. Quoted fields may
+//! contain delimiters, newlines and escaped quotes (`""`), and empty lines are
+//! skipped. Malformed input causes iteration to fail with an error naming the
+//! offending line.
+//!
+//! The writer quotes fields when needed, and accepts strings, numbers, bools,
+//! chars and unit, which is written as an empty field.
+
+use rune::runtime::{Iterator, Object, ToValue as _, Value, VmError};
+use rune::{Any, ContextError, Module};
+
+/// Construct the `csv` module.
+pub fn module(_stdio: bool) -> Result<Module, ContextError> {
+    let mut module = Module::with_crate("csv");
+
+    module.ty::<Reader>()?;
+    module.function(&["Reader", "new"], Reader::new)?;
+    module.inst_fn("delimiter", Reader::delimiter)?;
+    module.inst_fn("rows", Reader::rows)?;
+    module.inst_fn("records", Reader::records)?;
+
+    module.ty::<Writer>()?;
+    module.function(&["Writer", "new"], Writer::new)?;
+    module.inst_fn("delimiter", Writer::delimiter)?;
+    module.inst_fn("write", Writer::write)?;
+    module.inst_fn("into_string", Writer::into_string)?;
+    Ok(module)
+}
+
+/// A reader of csv data, which is configured before iterating over its rows.
+#[derive(Any)]
+struct Reader {
+    data: String,
+    delimiter: char,
+}
+
+impl Reader {
+    /// Construct a reader over a string or bytes.
+    fn new(data: Value) -> Result<Self, VmError> {
+        let data = match data {
+            Value::String(string) => string.borrow_ref()?.clone(),
+            Value::StaticString(string) => string.as_str().to_owned(),
+            Value::Bytes(bytes) => String::from_utf8(bytes.borrow_ref()?.to_vec())
+                .map_err(|_| VmError::panic("csv data is not valid utf-8"))?,
+            actual => return Err(VmError::expected::<String>(actual.type_info()?)),
+        };
+
+        Ok(Self {
+            data,
+            delimiter: ',',
+        })
+    }
+
+    /// Set the delimiter separating fields, which defaults to `,`.
+    fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Iterate over all rows as vectors of strings.
+    fn rows(self) -> Iterator {
+        Iterator::from("csv::Rows", Rows::new(self))
+    }
+
+    /// Iterate over all rows after the header as objects keyed by the header.
+    fn records(self) -> Iterator {
+        let records = Records {
+            rows: Rows::new(self),
+            headers: None,
+        };
+
+        Iterator::from("csv::Records", records)
+    }
+}
+
+/// The rows being parsed out of csv data.
+struct Rows {
+    data: String,
+    delimiter: char,
+    pos: usize,
+    /// The line being parsed, starting at 1.
+    line: usize,
+    /// The line the last row started on.
+    row_line: usize,
+}
+
+impl Rows {
+    fn new(reader: Reader) -> Self {
+        Self {
+            data: reader.data,
+            delimiter: reader.delimiter,
+            pos: 0,
+            line: 1,
+            row_line: 1,
+        }
+    }
+
+    fn next_row(&mut self) -> Result<Option<Vec<String>>, VmError> {
+        loop {
+            let input = &self.data[self.pos..];
+
+            if input.is_empty() {
+                return Ok(None);
+            }
+
+            if let Some(n) = ["\n", "\r\n"].iter().find_map(|nl| input.strip_prefix(nl)) {
+                self.pos = self.data.len() - n.len();
+                self.line += 1;
+                continue;
+            }
+
+            self.row_line = self.line;
+
+            let (row, consumed) =
+                parse_row(input, self.delimiter, &mut self.line).map_err(VmError::panic)?;
+
+            self.pos += consumed;
+            return Ok(Some(row));
+        }
+    }
+}
+
+impl rune::runtime::IteratorTrait for Rows {
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, None)
+    }
+
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        match self.next_row()? {
+            Some(row) => Ok(Some(row.to_value()?)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// The rows of csv data after the header, keyed by the header.
+struct Records {
+    rows: Rows,
+    headers: Option<Vec<String>>,
+}
+
+impl rune::runtime::IteratorTrait for Records {
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, None)
+    }
+
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        if self.headers.is_none() {
+            self.headers = self.rows.next_row()?;
+        }
+
+        let headers = match &self.headers {
+            Some(headers) => headers,
+            None => return Ok(None),
+        };
+
+        let row = match self.rows.next_row()? {
+            Some(row) => row,
+            None => return Ok(None),
+        };
+
+        if row.len() != headers.len() {
+            return Err(VmError::panic(format!(
+                "row on line {} has {} fields, but the header has {}",
+                self.rows.row_line,
+                row.len(),
+                headers.len()
+            )));
+        }
+
+        let mut object = Object::with_capacity(row.len());
+
+        for (key, field) in headers.iter().zip(row) {
+            object.insert(key.clone(), Value::from(field));
+        }
+
+        Ok(Some(Value::from(object)))
+    }
+}
+
+/// Parse a single row from the start of the input, returning its fields and
+/// the number of bytes consumed including the terminating newline.
+fn parse_row(input: &str, delimiter: char, line: &mut usize) -> Result<(Vec<String>, usize), String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = input.char_indices().peekable();
+    // If we're inside of a quoted field.
+    let mut quoted = false;
+    // If the current field was quoted, in which case it has to end after the
+    // closing quote.
+    let mut was_quoted = false;
+    let start = *line;
+
+    while let Some((i, c)) = chars.next() {
+        if quoted {
+            match c {
+                '"' if matches!(chars.peek(), Some((_, '"'))) => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => quoted = false,
+                c => {
+                    if c == '\n' {
+                        *line += 1;
+                    }
+
+                    field.push(c);
+                }
+            }
+
+            continue;
+        }
+
+        match c {
+            c if c == delimiter => {
+                fields.push(std::mem::take(&mut field));
+                was_quoted = false;
+            }
+            '\n' => {
+                *line += 1;
+                fields.push(field);
+                return Ok((fields, i + 1));
+            }
+            '\r' if matches!(chars.peek(), Some((_, '\n'))) => {}
+            '"' if field.is_empty() && !was_quoted => {
+                quoted = true;
+                was_quoted = true;
+            }
+            c if was_quoted => {
+                return Err(format!(
+                    "unexpected `{}` after a quoted field on line {}",
+                    c.escape_debug(),
+                    line
+                ));
+            }
+            c => field.push(c),
+        }
+    }
+
+    if quoted {
+        return Err(format!("unterminated quoted field on line {}", start));
+    }
+
+    fields.push(field);
+    Ok((fields, input.len()))
+}
+
+/// A writer of csv data, collecting the rows written into a string.
+#[derive(Any)]
+struct Writer {
+    out: String,
+    delimiter: char,
+}
+
+impl Writer {
+    fn new() -> Self {
+        Self {
+            out: String::new(),
+            delimiter: ',',
+        }
+    }
+
+    /// Set the delimiter separating fields, which defaults to `,`.
+    fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Write a row of fields.
+    fn write(&mut self, row: Vec<Value>) -> Result<(), VmError> {
+        for (n, value) in row.into_iter().enumerate() {
+            if n != 0 {
+                self.out.push(self.delimiter);
+            }
+
+            let field = match value {
+                Value::String(string) => string.borrow_ref()?.clone(),
+                Value::StaticString(string) => string.as_str().to_owned(),
+                Value::Integer(integer) => integer.to_string(),
+                Value::Float(float) => float.to_string(),
+                Value::Bool(boolean) => boolean.to_string(),
+                Value::Char(c) => c.to_string(),
+                Value::Byte(byte) => byte.to_string(),
+                Value::Unit => String::new(),
+                actual => return Err(VmError::expected::<String>(actual.type_info()?)),
+            };
+
+            let needs_quotes = field
+                .chars()
+                .any(|c| c == self.delimiter || matches!(c, '"' | '\n' | '\r'));
+
+            if needs_quotes {
+                self.out.push('"');
+                self.out.push_str(&field.replace('"', "\"\""));
+                self.out.push('"');
+            } else {
+                self.out.push_str(&field);
+            }
+        }
+
+        self.out.push('\n');
+        Ok(())
+    }
+
+    /// Get the data which was written.
+    fn into_string(self) -> String {
+        self.out
+    }
+}
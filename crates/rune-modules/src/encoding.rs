@@ -0,0 +1,149 @@
+//! The native `encoding` module for the [Rune Language].
+//!
+//! [Rune Language]: https://rune-rs.github.io
+//!
+//! ## Usage
+//!
+//! Add the following to your `Cargo.toml`:
+//!
+//! ```toml
+//! rune-modules = { version = "0.11.0", features = ["encoding"] }
+//! ```
+//!
+//! Install it into your context:
+//!
+//! ```rust
+//! # fn main() -> rune::Result<()> {
+//! let mut context = rune::Context::with_default_modules()?;
+//! context.install(&rune_modules::encoding::module(true)?)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Use it in Rune:
+//!
+//! ```rust,ignore
+//! use encoding::{base64, hex};
+//!
+//! fn main() {
+//!     let token = base64::encode_url(b"\xfb\xff binary");
+//!     let data = base64::decode_url(token)?;
+//!     println(hex::encode(data));
+//! }
+//! ```
+//!
+//! Encoders accept both strings and bytes, and produce strings. Decoders
+//! produce bytes, or an error if the input isn't validly encoded.
+//!
+//! `base64::encode` uses the standard alphabet with padding, while
+//! `base64::encode_url` uses the url-safe alphabet without padding as used in
+//! for example JSON Web Tokens. Both decoders accept input with or without
+//! padding. `hex::encode` produces lowercase digits, and `hex::decode` accepts
+//! both cases.
+
+use base64::alphabet;
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig};
+use base64::engine::DecodePaddingMode;
+use base64::Engine as _;
+use rune::runtime::{Bytes, Value, VmError};
+use rune::{ContextError, Module};
+
+const STANDARD: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+const URL_SAFE: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::URL_SAFE,
+    GeneralPurposeConfig::new()
+        .with_encode_padding(false)
+        .with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Construct the `encoding` module.
+pub fn module(_stdio: bool) -> Result<Module, ContextError> {
+    let mut module = Module::with_crate("encoding");
+    module.function(&["base64", "encode"], base64_encode)?;
+    module.function(&["base64", "decode"], base64_decode)?;
+    module.function(&["base64", "encode_url"], base64_encode_url)?;
+    module.function(&["base64", "decode_url"], base64_decode_url)?;
+    module.function(&["hex", "encode"], hex_encode)?;
+    module.function(&["hex", "decode"], hex_decode)?;
+    Ok(module)
+}
+
+/// Coerce a string or bytes into the data to encode.
+fn data_of(value: &Value) -> Result<Vec<u8>, VmError> {
+    Ok(match value {
+        Value::Bytes(bytes) => bytes.borrow_ref()?.to_vec(),
+        Value::String(string) => string.borrow_ref()?.as_bytes().to_vec(),
+        Value::StaticString(string) => string.as_bytes().to_vec(),
+        actual => return Err(VmError::expected::<Bytes>(actual.type_info()?)),
+    })
+}
+
+/// Encode data as standard base64.
+fn base64_encode(data: Value) -> Result<String, VmError> {
+    Ok(STANDARD.encode(data_of(&data)?))
+}
+
+/// Decode standard base64.
+fn base64_decode(string: &str) -> rune::Result<Bytes> {
+    Ok(Bytes::from_vec(STANDARD.decode(string)?))
+}
+
+/// Encode data as url-safe base64.
+fn base64_encode_url(data: Value) -> Result<String, VmError> {
+    Ok(URL_SAFE.encode(data_of(&data)?))
+}
+
+/// Decode url-safe base64.
+fn base64_decode_url(string: &str) -> rune::Result<Bytes> {
+    Ok(Bytes::from_vec(URL_SAFE.decode(string)?))
+}
+
+/// Encode data as lowercase hex.
+fn hex_encode(data: Value) -> Result<String, VmError> {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+    let data = data_of(&data)?;
+    let mut out = String::with_capacity(data.len() * 2);
+
+    for b in data {
+        out.push(char::from(DIGITS[usize::from(b >> 4)]));
+        out.push(char::from(DIGITS[usize::from(b & 0xf)]));
+    }
+
+    Ok(out)
+}
+
+/// Decode hex in either case.
+fn hex_decode(string: &str) -> rune::Result<Bytes> {
+    fn digit(c: u8) -> rune::Result<u8> {
+        Ok(match c {
+            b'0'..=b'9' => c - b'0',
+            b'a'..=b'f' => c - b'a' + 10,
+            b'A'..=b'F' => c - b'A' + 10,
+            _ => {
+                return Err(rune::Error::msg(format!(
+                    "invalid hex digit `{}`",
+                    char::from(c).escape_debug()
+                )))
+            }
+        })
+    }
+
+    let bytes = string.as_bytes();
+
+    if !bytes.len().is_multiple_of(2) {
+        return Err(rune::Error::msg("hex input has an odd number of digits"));
+    }
+
+    let mut out = Vec::with_capacity(bytes.len() / 2);
+
+    for pair in bytes.chunks_exact(2) {
+        out.push(digit(pair[0])? << 4 | digit(pair[1])?);
+    }
+
+    Ok(Bytes::from_vec(out))
+}
@@ -0,0 +1,156 @@
+//! The native `env` module for the [Rune Language].
+//!
+//! [Rune Language]: https://rune-rs.github.io
+//!
+//! ## Usage
+//!
+//! Add the following to your `Cargo.toml`:
+//!
+//! ```toml
+//! rune-modules = { version = "0.11.0", features = ["env"] }
+//! ```
+//!
+//! Install it into your context:
+//!
+//! ```rust
+//! # fn main() -> rune::Result<()> {
+//! let mut context = rune::Context::with_default_modules()?;
+//! context.install(&rune_modules::env::module(true)?)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Use it in Rune:
+//!
+//! ```rust,ignore
+//! fn main() {
+//!     let level = env::var("LOG_LEVEL").unwrap_or("info");
+//!
+//!     for arg in env::args() {
+//!         println(`${level}: ${arg}`);
+//!     }
+//! }
+//! ```
+//!
+//! The arguments returned by `env::args` are the ones the embedder provided
+//! to the virtual machine through [Args], not the arguments of the host
+//! process.
+//!
+//! The module requires the
+//! [Environment][rune::runtime::Capability::Environment] capability.
+
+use rune::runtime::{Capability, Extensions, Object, Value};
+use rune::{ContextError, Module};
+use std::io;
+
+/// Construct the `env` module.
+pub fn module(_stdio: bool) -> Result<Module, ContextError> {
+    let mut module = Module::with_crate("env");
+    module.require(Capability::Environment);
+    module.function(&["var"], var)?;
+    module.function(&["vars"], vars)?;
+    module.function(&["set_var"], set_var)?;
+    module.function(&["remove_var"], remove_var)?;
+    module.function(&["current_dir"], current_dir)?;
+    module.function(&["set_current_dir"], set_current_dir)?;
+    module.function(&["args"], args)?;
+    Ok(module)
+}
+
+/// The command-line arguments of a script, which are returned by `env::args`.
+///
+/// These are provided by inserting them into the
+/// [extensions][rune::Vm::extensions_mut] of the virtual machine running the
+/// script. If none are provided, `env::args` returns an empty vector.
+///
+/// # Examples
+///
+/// ```
+/// use rune::{Context, FromValue, Vm};
+/// use rune_modules::env::Args;
+/// use std::sync::Arc;
+///
+/// # fn main() -> rune::Result<()> {
+/// let mut context = Context::with_default_modules()?;
+/// context.install(&rune_modules::env::module(true)?)?;
+///
+/// let mut sources = rune::sources! {
+///     entry => {
+///         pub fn main() {
+///             env::args()
+///         }
+///     }
+/// };
+///
+/// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+/// let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+/// vm.extensions_mut().insert(Args::new(["--verbose", "input.txt"]));
+///
+/// let args = Vec::<String>::from_value(vm.call(&["main"], ())?)?;
+/// assert_eq!(args, ["--verbose", "input.txt"]);
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Args {
+    args: Vec<String>,
+}
+
+impl Args {
+    /// Construct a collection of arguments.
+    pub fn new<I>(args: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        Self {
+            args: args.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Get the arguments.
+    pub fn as_slice(&self) -> &[String] {
+        &self.args
+    }
+}
+
+/// Get the value of an environment variable, if it's set and valid unicode.
+fn var(key: &str) -> Option<String> {
+    std::env::var(key).ok()
+}
+
+/// Get all environment variables which are valid unicode.
+fn vars() -> Object {
+    let mut object = Object::new();
+
+    for (key, value) in std::env::vars_os() {
+        if let (Ok(key), Ok(value)) = (key.into_string(), value.into_string()) {
+            object.insert(key, Value::from(value));
+        }
+    }
+
+    object
+}
+
+fn set_var(key: &str, value: &str) {
+    std::env::set_var(key, value);
+}
+
+fn remove_var(key: &str) {
+    std::env::remove_var(key);
+}
+
+fn current_dir() -> io::Result<String> {
+    Ok(std::env::current_dir()?.to_string_lossy().into_owned())
+}
+
+fn set_current_dir(path: &str) -> io::Result<()> {
+    std::env::set_current_dir(path)
+}
+
+/// Get the arguments the embedder provided to the script.
+fn args() -> Vec<String> {
+    Extensions::current()
+        .get::<Args>()
+        .map(|args| args.args.clone())
+        .unwrap_or_default()
+}
@@ -28,18 +28,194 @@
 //!     println(`{file}`);
 //! }
 //! ```
+//!
+//! Every function is also available in a blocking variant under
+//! `fs::blocking`, which doesn't need to be awaited:
+//!
+//! ```rust,ignore
+//! fn main() {
+//!     fs::blocking::create_dir_all("out")?;
+//!
+//!     for path in fs::blocking::read_dir(".")? {
+//!         if fs::blocking::metadata(path)?.is_file() {
+//!             fs::blocking::append("out/files.txt", `${path}\n`)?;
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! The module requires the [Filesystem][rune::runtime::Capability::Filesystem]
+//! capability.
 
+use rune::runtime::Capability;
+use rune::{Any, ContextError, Module};
 use std::io;
+use std::path::Path;
 use tokio::fs;
-use rune::{Module, ContextError};
+use tokio::io::AsyncWriteExt as _;
 
 /// Construct the `fs` module.
 pub fn module(_stdio: bool) -> Result<Module, ContextError> {
     let mut module = Module::with_crate("fs");
+    module.require(Capability::Filesystem);
+
+    module.ty::<Metadata>()?;
+    module.inst_fn("is_file", Metadata::is_file)?;
+    module.inst_fn("is_dir", Metadata::is_dir)?;
+    module.inst_fn("len", Metadata::len)?;
+    module.inst_fn("is_readonly", Metadata::is_readonly)?;
+
     module.async_function(&["read_to_string"], read_to_string)?;
+    module.async_function(&["write"], write)?;
+    module.async_function(&["append"], append)?;
+    module.async_function(&["metadata"], metadata)?;
+    module.async_function(&["read_dir"], read_dir)?;
+    module.async_function(&["create_dir_all"], create_dir_all)?;
+    module.async_function(&["remove"], remove)?;
+
+    module.function(&["blocking", "read_to_string"], blocking::read_to_string)?;
+    module.function(&["blocking", "write"], blocking::write)?;
+    module.function(&["blocking", "append"], blocking::append)?;
+    module.function(&["blocking", "metadata"], blocking::metadata)?;
+    module.function(&["blocking", "read_dir"], blocking::read_dir)?;
+    module.function(&["blocking", "create_dir_all"], blocking::create_dir_all)?;
+    module.function(&["blocking", "remove"], blocking::remove)?;
     Ok(module)
 }
 
+/// Metadata about a file or directory.
+#[derive(Debug, Any)]
+struct Metadata {
+    inner: std::fs::Metadata,
+}
+
+impl Metadata {
+    fn is_file(&self) -> bool {
+        self.inner.is_file()
+    }
+
+    fn is_dir(&self) -> bool {
+        self.inner.is_dir()
+    }
+
+    fn len(&self) -> u64 {
+        self.inner.len()
+    }
+
+    fn is_readonly(&self) -> bool {
+        self.inner.permissions().readonly()
+    }
+}
+
+impl From<std::fs::Metadata> for Metadata {
+    fn from(inner: std::fs::Metadata) -> Self {
+        Self { inner }
+    }
+}
+
+/// Convert a path into a string which can be handed to a script.
+fn path_to_string(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
+}
+
 async fn read_to_string(path: &str) -> io::Result<String> {
     fs::read_to_string(path).await
 }
+
+async fn write(path: &str, contents: &str) -> io::Result<()> {
+    fs::write(path, contents).await
+}
+
+async fn append(path: &str, contents: &str) -> io::Result<()> {
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await?;
+
+    file.write_all(contents.as_bytes()).await?;
+    file.flush().await
+}
+
+async fn metadata(path: &str) -> io::Result<Metadata> {
+    Ok(Metadata::from(fs::metadata(path).await?))
+}
+
+async fn read_dir(path: &str) -> io::Result<Vec<String>> {
+    let mut dir = fs::read_dir(path).await?;
+    let mut paths = Vec::new();
+
+    while let Some(entry) = dir.next_entry().await? {
+        paths.push(path_to_string(&entry.path()));
+    }
+
+    paths.sort();
+    Ok(paths)
+}
+
+async fn create_dir_all(path: &str) -> io::Result<()> {
+    fs::create_dir_all(path).await
+}
+
+/// Remove a file, or a directory together with all of its contents.
+///
+/// Symbolic links are removed without following them.
+async fn remove(path: &str) -> io::Result<()> {
+    if fs::symlink_metadata(path).await?.is_dir() {
+        fs::remove_dir_all(path).await
+    } else {
+        fs::remove_file(path).await
+    }
+}
+
+/// Blocking variants of the filesystem functions.
+mod blocking {
+    use super::{path_to_string, Metadata};
+    use std::fs;
+    use std::io;
+    use std::io::Write as _;
+
+    pub(super) fn read_to_string(path: &str) -> io::Result<String> {
+        fs::read_to_string(path)
+    }
+
+    pub(super) fn write(path: &str, contents: &str) -> io::Result<()> {
+        fs::write(path, contents)
+    }
+
+    pub(super) fn append(path: &str, contents: &str) -> io::Result<()> {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+
+        file.write_all(contents.as_bytes())
+    }
+
+    pub(super) fn metadata(path: &str) -> io::Result<Metadata> {
+        Ok(Metadata::from(fs::metadata(path)?))
+    }
+
+    pub(super) fn read_dir(path: &str) -> io::Result<Vec<String>> {
+        let mut paths = Vec::new();
+
+        for entry in fs::read_dir(path)? {
+            paths.push(path_to_string(&entry?.path()));
+        }
+
+        paths.sort();
+        Ok(paths)
+    }
+
+    pub(super) fn create_dir_all(path: &str) -> io::Result<()> {
+        fs::create_dir_all(path)
+    }
+
+    pub(super) fn remove(path: &str) -> io::Result<()> {
+        if fs::symlink_metadata(path)?.is_dir() {
+            fs::remove_dir_all(path)
+        } else {
+            fs::remove_file(path)
+        }
+    }
+}
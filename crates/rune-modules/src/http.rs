@@ -49,15 +49,38 @@
 //!     dbg(response);
 //! }
 //! ```
+//!
+//! Requests can be customized before they're sent:
+//!
+//! ```rust,ignore
+//! use http;
+//!
+//! async fn main() {
+//!     let client = http::Client::new();
+//!
+//!     let response = client.put("https://postman-echo.com/put").await
+//!         .header("authorization", "Bearer token")
+//!         .query(#{"page": 2})
+//!         .json(#{"hello": "world"})
+//!         .timeout(2.5)
+//!         .send().await?;
+//!
+//!     if response.status().is_success() {
+//!         dbg(response.header("content-type"));
+//!     }
+//! }
+//! ```
 
 use rune::{Any, Module, Value, ContextError};
-use rune::runtime::{Bytes, Protocol};
+use rune::runtime::{Bytes, Capability, Object, Protocol, VmError};
 use std::fmt;
 use std::fmt::Write;
+use std::time::Duration;
 
 /// Construct the `http` module.
 pub fn module(_stdio: bool) -> Result<Module, ContextError> {
     let mut module = Module::with_crate("http");
+    module.require(Capability::Network);
 
     module.ty::<Client>()?;
     module.ty::<Response>()?;
@@ -70,15 +93,33 @@ pub fn module(_stdio: bool) -> Result<Module, ContextError> {
 
     module.async_inst_fn("get", Client::get)?;
     module.async_inst_fn("post", Client::post)?;
+    module.async_inst_fn("put", Client::put)?;
+    module.async_inst_fn("patch", Client::patch)?;
+    module.async_inst_fn("delete", Client::delete)?;
+    module.async_inst_fn("head", Client::head)?;
 
     module.async_inst_fn("text", Response::text)?;
     module.async_inst_fn("json", Response::json)?;
+    module.async_inst_fn("bytes", Response::bytes)?;
     module.inst_fn("status", Response::status)?;
+    module.inst_fn("header", Response::header)?;
+    module.inst_fn("headers", Response::headers)?;
 
     module.async_inst_fn("send", RequestBuilder::send)?;
     module.inst_fn("header", RequestBuilder::header)?;
+    module.inst_fn("query", RequestBuilder::query)?;
+    module.inst_fn("timeout", RequestBuilder::timeout)?;
+    module.inst_fn("json", RequestBuilder::json)?;
+    module.inst_fn("form", RequestBuilder::form)?;
+    module.inst_fn("body_string", RequestBuilder::body_string)?;
     module.async_inst_fn("body_bytes", RequestBuilder::body_bytes)?;
 
+    module.inst_fn("as_u16", StatusCode::as_u16)?;
+    module.inst_fn("is_success", StatusCode::is_success)?;
+    module.inst_fn("is_redirection", StatusCode::is_redirection)?;
+    module.inst_fn("is_client_error", StatusCode::is_client_error)?;
+    module.inst_fn("is_server_error", StatusCode::is_server_error)?;
+
     module.inst_fn(Protocol::STRING_DISPLAY, Error::display)?;
     module.inst_fn(Protocol::STRING_DISPLAY, StatusCode::display)?;
     Ok(module)
@@ -120,6 +161,26 @@ impl StatusCode {
     fn display(&self, buf: &mut String) -> fmt::Result {
         write!(buf, "{}", self.inner)
     }
+
+    fn as_u16(&self) -> u16 {
+        self.inner.as_u16()
+    }
+
+    fn is_success(&self) -> bool {
+        self.inner.is_success()
+    }
+
+    fn is_redirection(&self) -> bool {
+        self.inner.is_redirection()
+    }
+
+    fn is_client_error(&self) -> bool {
+        self.inner.is_client_error()
+    }
+
+    fn is_server_error(&self) -> bool {
+        self.inner.is_server_error()
+    }
 }
 
 impl Response {
@@ -133,12 +194,37 @@ impl Response {
         Ok(text)
     }
 
+    async fn bytes(self) -> Result<Bytes, Error> {
+        let bytes = self.response.bytes().await?;
+        Ok(Bytes::from_vec(bytes.to_vec()))
+    }
+
     /// Get the status code of the response.
     fn status(&self) -> StatusCode {
         let inner = self.response.status();
 
         StatusCode { inner }
     }
+
+    /// Get the value of a header in the response, if it's present and valid
+    /// unicode.
+    fn header(&self, key: &str) -> Option<String> {
+        let value = self.response.headers().get(key)?;
+        Some(value.to_str().ok()?.to_owned())
+    }
+
+    /// Get all headers in the response which are valid unicode.
+    fn headers(&self) -> Object {
+        let mut object = Object::new();
+
+        for (key, value) in self.response.headers() {
+            if let Ok(value) = value.to_str() {
+                object.insert(key.as_str().to_owned(), Value::from(value.to_owned()));
+            }
+        }
+
+        object
+    }
 }
 
 #[derive(Debug, Any)]
@@ -160,6 +246,56 @@ impl RequestBuilder {
         }
     }
 
+    /// Append query parameters from an object to the URL of the request.
+    fn query(self, query: &Object) -> Result<Self, VmError> {
+        let query = form_pairs(query)?;
+
+        Ok(Self {
+            request: self.request.query(&query),
+        })
+    }
+
+    /// Set a timeout in seconds for the request, from when it starts
+    /// connecting until the response body has been read.
+    fn timeout(self, secs: Value) -> Result<Self, VmError> {
+        let secs = match secs {
+            Value::Integer(secs) => secs as f64,
+            Value::Float(secs) => secs,
+            actual => return Err(VmError::expected::<f64>(actual.type_info()?)),
+        };
+
+        if !secs.is_finite() || secs < 0.0 {
+            return Err(VmError::panic("timeout must be a positive number of seconds"));
+        }
+
+        Ok(Self {
+            request: self.request.timeout(Duration::from_secs_f64(secs)),
+        })
+    }
+
+    /// Set the request body to the given value serialized as JSON.
+    fn json(self, value: Value) -> Self {
+        Self {
+            request: self.request.json(&value),
+        }
+    }
+
+    /// Set the request body to the given object as URL encoded form data.
+    fn form(self, form: &Object) -> Result<Self, VmError> {
+        let form = form_pairs(form)?;
+
+        Ok(Self {
+            request: self.request.form(&form),
+        })
+    }
+
+    /// Set the request body from a string.
+    fn body_string(self, body: &str) -> Self {
+        Self {
+            request: self.request.body(body.to_owned()),
+        }
+    }
+
     /// Set the request body from bytes.
     async fn body_bytes(self, bytes: Bytes) -> Result<Self, Error> {
         let bytes = bytes.into_vec();
@@ -188,6 +324,50 @@ impl Client {
         let request = self.client.post(url);
         Ok(RequestBuilder { request })
     }
+
+    /// Construct a builder to PUT to the given URL.
+    async fn put(&self, url: &str) -> Result<RequestBuilder, Error> {
+        let request = self.client.put(url);
+        Ok(RequestBuilder { request })
+    }
+
+    /// Construct a builder to PATCH the given URL.
+    async fn patch(&self, url: &str) -> Result<RequestBuilder, Error> {
+        let request = self.client.patch(url);
+        Ok(RequestBuilder { request })
+    }
+
+    /// Construct a builder to DELETE the given URL.
+    async fn delete(&self, url: &str) -> Result<RequestBuilder, Error> {
+        let request = self.client.delete(url);
+        Ok(RequestBuilder { request })
+    }
+
+    /// Construct a builder to HEAD the given URL.
+    async fn head(&self, url: &str) -> Result<RequestBuilder, Error> {
+        let request = self.client.head(url);
+        Ok(RequestBuilder { request })
+    }
+}
+
+/// Convert an object into key-value pairs for query parameters or forms.
+fn form_pairs(object: &Object) -> Result<Vec<(String, String)>, VmError> {
+    let mut pairs = Vec::with_capacity(object.len());
+
+    for (key, value) in object {
+        let value = match value {
+            Value::String(string) => string.borrow_ref()?.clone(),
+            Value::StaticString(string) => string.as_str().to_owned(),
+            Value::Integer(integer) => integer.to_string(),
+            Value::Float(float) => float.to_string(),
+            Value::Bool(boolean) => boolean.to_string(),
+            actual => return Err(VmError::expected::<String>(actual.type_info()?)),
+        };
+
+        pairs.push((key.clone(), value));
+    }
+
+    Ok(pairs)
 }
 
 /// Shorthand for generating a get request.
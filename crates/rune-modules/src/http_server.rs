@@ -0,0 +1,346 @@
+//! The native `http::server` module for the [Rune Language].
+//!
+//! [Rune Language]: https://rune-rs.github.io
+//!
+//! ## Usage
+//!
+//! Add the following to your `Cargo.toml`:
+//!
+//! ```toml
+//! rune-modules = { version = "0.11.0", features = ["http-server"] }
+//! ```
+//!
+//! Install it into your context:
+//!
+//! ```rust
+//! # fn main() -> rune::Result<()> {
+//! let mut context = rune::Context::with_default_modules()?;
+//! context.install(&rune_modules::http_server::module(true)?)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Use it in Rune:
+//!
+//! ```rust,ignore
+//! use http::server::{Response, Server};
+//!
+//! async fn main() {
+//!     let server = Server::new();
+//!
+//!     server.route("/hello", |req| {
+//!         `Hello ${req.query("name").unwrap_or("world")}!`
+//!     });
+//!
+//!     server.route("/webhook", |req| {
+//!         dbg(req.text());
+//!         Response::new(202).header("x-handled", "yes")
+//!     });
+//!
+//!     server.listen("127.0.0.1:8080").await?;
+//! }
+//! ```
+//!
+//! Handlers are called with a `Request`, and can return a `Response`, a
+//! string, bytes or nothing at all, in which case the response is empty. They
+//! can also be `async`. A handler returning an `Err` or failing responds with
+//! `500 Internal Server Error`, and requests to paths without a handler respond
+//! with `404 Not Found`.
+//!
+//! Instead of having the script listen, the host can drive the server which a
+//! script set up through [Server::serve].
+//!
+//! The module requires the [Network][rune::runtime::Capability::Network]
+//! capability.
+
+use futures_util::future::{self, Either};
+use futures_util::stream::{FuturesUnordered, StreamExt as _};
+use hyper::server::conn::Http;
+use hyper::service::service_fn;
+use hyper::{Body, StatusCode};
+use rune::runtime::{Bytes, Capability, Function, Value, VmError};
+use rune::{Any, ContextError, Module};
+use std::cell::RefCell;
+use std::convert::Infallible;
+use std::future::Future;
+use std::io;
+use std::rc::Rc;
+use tokio::net::TcpListener;
+
+/// Construct the `http::server` module.
+pub fn module(_stdio: bool) -> Result<Module, ContextError> {
+    let mut module = Module::with_crate_item("http", &["server"]);
+    module.require(Capability::Network);
+
+    module.ty::<Server>()?;
+    module.function(&["Server", "new"], Server::new)?;
+    module.inst_fn("route", Server::route)?;
+    module.async_inst_fn("listen", Server::listen)?;
+
+    module.ty::<Request>()?;
+    module.inst_fn("method", Request::method)?;
+    module.inst_fn("path", Request::path)?;
+    module.inst_fn("query", Request::query)?;
+    module.inst_fn("header", Request::header)?;
+    module.inst_fn("body", Request::body)?;
+    module.inst_fn("text", Request::text)?;
+
+    module.ty::<Response>()?;
+    module.function(&["Response", "new"], Response::new)?;
+    module.inst_fn("header", Response::header)?;
+    module.inst_fn("body", Response::body)?;
+    Ok(module)
+}
+
+/// The handlers registered by a script, by path.
+type Routes = Vec<(String, Rc<Function>)>;
+
+/// A server dispatching requests to the handlers registered by a script.
+#[derive(Any, Clone, Default)]
+pub struct Server {
+    routes: Rc<RefCell<Routes>>,
+}
+
+impl Server {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a handler for the given path, replacing any existing handler
+    /// for it.
+    fn route(&self, path: &str, handler: Function) {
+        let mut routes = self.routes.borrow_mut();
+        let handler = Rc::new(handler);
+
+        match routes.iter_mut().find(|(p, _)| p == path) {
+            Some(route) => route.1 = handler,
+            None => routes.push((path.to_owned(), handler)),
+        }
+    }
+
+    /// Bind to the given address and serve requests until an error occurs.
+    fn listen(&self, addr: &str) -> impl Future<Output = io::Result<()>> {
+        let server = self.clone();
+        let addr = addr.to_owned();
+
+        async move {
+            let listener = TcpListener::bind(addr).await?;
+            server.serve(listener).await
+        }
+    }
+
+    /// Serve requests from the given listener until an error occurs.
+    ///
+    /// Handlers are called on the current thread, so the returned future has
+    /// to be polled without being sent to another thread, for example through
+    /// a [tokio::task::LocalSet] or a current-thread runtime.
+    pub async fn serve(&self, listener: TcpListener) -> io::Result<()> {
+        let mut connections = FuturesUnordered::new();
+
+        loop {
+            let accepted = if connections.is_empty() {
+                listener.accept().await
+            } else {
+                let accept = Box::pin(listener.accept());
+
+                match future::select(accept, connections.next()).await {
+                    Either::Left((accepted, _)) => accepted,
+                    Either::Right(..) => continue,
+                }
+            };
+
+            let (stream, _) = accepted?;
+            let server = self.clone();
+
+            let service = service_fn(move |request| {
+                let server = server.clone();
+                async move { Ok::<_, Infallible>(server.handle(request).await) }
+            });
+
+            let mut http = Http::new().with_executor(LocalExec);
+            http.http1_only(true);
+            let connection = http.serve_connection(stream, service);
+
+            // Errors in individual connections are reported to the client,
+            // and shouldn't take down the server.
+            connections.push(async move {
+                let _ = connection.await;
+            });
+        }
+    }
+
+    /// Dispatch a request to its handler.
+    async fn handle(&self, request: hyper::Request<Body>) -> hyper::Response<Body> {
+        let handler = self
+            .routes
+            .borrow()
+            .iter()
+            .find(|(path, _)| path == request.uri().path())
+            .map(|(_, handler)| handler.clone());
+
+        let handler = match handler {
+            Some(handler) => handler,
+            None => return status_response(StatusCode::NOT_FOUND, String::new()),
+        };
+
+        let (parts, body) = request.into_parts();
+
+        let body = match hyper::body::to_bytes(body).await {
+            Ok(body) => body,
+            Err(error) => return status_response(StatusCode::BAD_REQUEST, error.to_string()),
+        };
+
+        let request = Request {
+            parts,
+            body: body.to_vec(),
+        };
+
+        match call_handler(&handler, request).await {
+            Ok(response) => response.into_hyper(),
+            Err(error) => status_response(StatusCode::INTERNAL_SERVER_ERROR, error.to_string()),
+        }
+    }
+}
+
+/// Call a handler, awaiting its result if it's async.
+async fn call_handler(handler: &Function, request: Request) -> Result<Response, VmError> {
+    let value = handler.call::<_, Value>((request,))?;
+
+    let value = match value {
+        Value::Future(future) => future.take()?.await?,
+        value => value,
+    };
+
+    Response::from_handler(value)
+}
+
+/// An executor for connections, which is only used for HTTP/2 and therefore
+/// never called.
+#[derive(Clone, Copy)]
+struct LocalExec;
+
+impl<F> hyper::rt::Executor<F> for LocalExec
+where
+    F: Future + 'static,
+{
+    fn execute(&self, future: F) {
+        tokio::task::spawn_local(future);
+    }
+}
+
+fn status_response(status: StatusCode, body: String) -> hyper::Response<Body> {
+    let mut response = hyper::Response::new(Body::from(body));
+    *response.status_mut() = status;
+    response
+}
+
+/// A request received by the server.
+#[derive(Any)]
+struct Request {
+    parts: hyper::http::request::Parts,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn method(&self) -> String {
+        self.parts.method.to_string()
+    }
+
+    fn path(&self) -> String {
+        self.parts.uri.path().to_owned()
+    }
+
+    /// Get the first query parameter with the given name, if present.
+    fn query(&self, name: &str) -> Option<String> {
+        let query = self.parts.uri.query()?;
+
+        form_urlencoded::parse(query.as_bytes())
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    }
+
+    /// Get the value of a header, if it's present and valid unicode.
+    fn header(&self, name: &str) -> Option<String> {
+        let value = self.parts.headers.get(name)?;
+        Some(value.to_str().ok()?.to_owned())
+    }
+
+    fn body(&self) -> Bytes {
+        Bytes::from_vec(self.body.clone())
+    }
+
+    /// Get the body as a string, replacing invalid unicode.
+    fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+/// A response to be sent by the server.
+#[derive(Any)]
+struct Response {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn new(status: u16) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    /// Add a header to the response.
+    fn header(mut self, key: &str, value: &str) -> Self {
+        self.headers.push((key.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Set the body of the response from a string or bytes.
+    fn body(mut self, body: Value) -> Result<Self, VmError> {
+        self.body = match body {
+            Value::String(string) => string.borrow_ref()?.as_bytes().to_vec(),
+            Value::StaticString(string) => string.as_bytes().to_vec(),
+            Value::Bytes(bytes) => bytes.borrow_ref()?.to_vec(),
+            actual => return Err(VmError::expected::<String>(actual.type_info()?)),
+        };
+
+        Ok(self)
+    }
+
+    /// Convert the value returned by a handler into a response.
+    fn from_handler(value: Value) -> Result<Self, VmError> {
+        Ok(match value {
+            Value::Unit => Self::new(204),
+            Value::String(..) | Value::StaticString(..) => Self::new(200)
+                .header("content-type", "text/plain; charset=utf-8")
+                .body(value)?,
+            Value::Bytes(..) => Self::new(200)
+                .header("content-type", "application/octet-stream")
+                .body(value)?,
+            Value::Result(result) => match result.take()? {
+                Ok(value) => Self::from_handler(value)?,
+                Err(error) => {
+                    return Err(VmError::panic(format!("handler failed: {:?}", error)));
+                }
+            },
+            Value::Any(any) if any.borrow_ref()?.is::<Self>() => any.take_downcast::<Self>()?,
+            actual => return Err(VmError::expected::<Self>(actual.type_info()?)),
+        })
+    }
+
+    fn into_hyper(self) -> hyper::Response<Body> {
+        let mut builder = hyper::Response::builder().status(self.status);
+
+        for (key, value) in &self.headers {
+            builder = builder.header(key.as_str(), value.as_str());
+        }
+
+        match builder.body(Body::from(self.body)) {
+            Ok(response) => response,
+            Err(error) => status_response(StatusCode::INTERNAL_SERVER_ERROR, error.to_string()),
+        }
+    }
+}
@@ -26,10 +26,19 @@
 //! use json;
 //!
 //! fn main() {
-//!     let data = json::from_string("{\"key\": 42}");
-//!     dbg(data);
+//!     let data = json::from_string("{\"key\": 42, \"list\": [1, 2.5, null]}")?;
+//!     data.key += 1;
+//!
+//!     println(json::to_string(data)?);
+//!     println(json::to_string_pretty(data)?);
 //! }
 //! ```
+//!
+//! JSON objects are converted into objects, arrays into vectors, `null` into
+//! unit and numbers into integers where possible and floats otherwise. When
+//! converting into JSON, structs are converted into objects, tuples into
+//! arrays and enum variants into objects keyed by the name of the variant.
+//! Values which can't be represented, like functions, result in an error.
 
 use rune::{ContextError, Module};
 use rune::runtime::{Bytes, Value};
@@ -40,7 +49,9 @@ pub fn module(_stdio: bool) -> Result<Module, ContextError> {
     module.function(&["from_bytes"], from_bytes)?;
     module.function(&["from_string"], from_string)?;
     module.function(&["to_string"], to_string)?;
+    module.function(&["to_string_pretty"], to_string_pretty)?;
     module.function(&["to_bytes"], to_bytes)?;
+    module.function(&["to_bytes_pretty"], to_bytes_pretty)?;
     Ok(module)
 }
 
@@ -58,8 +69,19 @@ fn to_string(value: Value) -> rune::Result<String> {
     Ok(serde_json::to_string(&value)?)
 }
 
+/// Convert any value to an indented json string.
+fn to_string_pretty(value: Value) -> rune::Result<String> {
+    Ok(serde_json::to_string_pretty(&value)?)
+}
+
 /// Convert any value to json bytes.
 fn to_bytes(value: Value) -> rune::Result<Bytes> {
     let bytes = serde_json::to_vec(&value)?;
     Ok(Bytes::from_vec(bytes))
 }
+
+/// Convert any value to indented json bytes.
+fn to_bytes_pretty(value: Value) -> rune::Result<Bytes> {
+    let bytes = serde_json::to_vec_pretty(&value)?;
+    Ok(Bytes::from_vec(bytes))
+}
@@ -45,53 +45,92 @@
 //! [Rune Language]: https://rune-rs.github.io
 //!
 //! See each module for documentation:
+//! * [cli]
 //! * [core]
+//! * [crypto]
+//! * [csv]
+//! * [encoding]
+//! * [env]
 //! * [experiments]
 //! * [fmt]
 //! * [fs]
 //! * [http]
+//! * [http_server]
 //! * [io]
 //! * [json]
+//! * [log]
 //! * [macros]
+//! * [net]
+//! * [path]
 //! * [process]
 //! * [rand]
 //! * [signal]
+//! * [sqlite]
+//! * [template]
 //! * [test]
 //! * [time]
 //! * [toml]
+//! * [websocket]
+//! * [yaml]
 //!
 //! ## Features
 //!
+//! * `cli` for the [cli module][cli]
 //! * `core` for the [core module][toml]
+//! * `crypto` for the [crypto module][crypto]
+//! * `csv` for the [csv module][csv]
+//! * `encoding` for the [encoding module][encoding]
+//! * `env` for the [env module][env]
 //! * `experiments` for the [experiments module][experiments]
 //! * `fmt` for the [fmt module][fmt]
 //! * `fs` for the [fs module][fs]
 //! * `full` includes all modules.
 //! * `http` for the [http module][http]
+//! * `http-server` for the [http server module][http_server]
 //! * `io` for the [io module][io]
 //! * `json` for the [json module][json]
+//! * `log` for the [log module][log]
 //! * `macros` for the [macros module][macros]
+//! * `net` for the [net module][net]
+//! * `path` for the [path module][path]
 //! * `process` for the [process module][process]
 //! * `rand` for the [rand module][rand]
 //! * `signal` for the [signal module][signal]
+//! * `sqlite` for the [sqlite module][sqlite]
+//! * `template` for the [template module][template]
 //! * `test` for the [test module][test]
 //! * `time` for the [time module][time]
 //! * `toml` for the [toml module][toml]
+//! * `websocket` for the [websocket module][websocket]
+//! * `yaml` for the [yaml module][yaml]
 //!
+//! [cli]: https://docs.rs/rune-modules/0/rune_modules/cli/
 //! [core]: https://docs.rs/rune-modules/0/rune_modules/core/
+//! [crypto]: https://docs.rs/rune-modules/0/rune_modules/crypto/
+//! [csv]: https://docs.rs/rune-modules/0/rune_modules/csv/
+//! [encoding]: https://docs.rs/rune-modules/0/rune_modules/encoding/
+//! [env]: https://docs.rs/rune-modules/0/rune_modules/env/
 //! [experiments]: https://docs.rs/rune-modules/0/rune_modules/experiments/
 //! [fmt]: https://docs.rs/rune-modules/0/rune_modules/fmt/
 //! [fs]: https://docs.rs/rune-modules/0/rune_modules/fs/
 //! [http]: https://docs.rs/rune-modules/0/rune_modules/http/
+//! [http_server]: https://docs.rs/rune-modules/0/rune_modules/http_server/
 //! [io]: https://docs.rs/rune-modules/0/rune_modules/io/
 //! [json]: https://docs.rs/rune-modules/0/rune_modules/json/
+//! [log]: https://docs.rs/rune-modules/0/rune_modules/log/
 //! [macros]: https://docs.rs/rune-modules/0/rune_modules/macros/
+//! [net]: https://docs.rs/rune-modules/0/rune_modules/net/
+//! [path]: https://docs.rs/rune-modules/0/rune_modules/path/
 //! [process]: https://docs.rs/rune-modules/0/rune_modules/process/
 //! [rand]: https://docs.rs/rune-modules/0/rune_modules/rand/
 //! [signal]: https://docs.rs/rune-modules/0/rune_modules/signal/
+//! [sqlite]: https://docs.rs/rune-modules/0/rune_modules/sqlite/
+//! [template]: https://docs.rs/rune-modules/0/rune_modules/template/
 //! [test]: https://docs.rs/rune-modules/0/rune_modules/test/
 //! [time]: https://docs.rs/rune-modules/0/rune_modules/time/
 //! [toml]: https://docs.rs/rune-modules/0/rune_modules/toml/
+//! [websocket]: https://docs.rs/rune-modules/0/rune_modules/websocket/
+//! [yaml]: https://docs.rs/rune-modules/0/rune_modules/yaml/
 
 // Note: The above links to docs.rs are needed because cargo-readme does not
 // support intra-doc links (yet):
@@ -117,13 +156,29 @@ macro_rules! modules {
         /// based on the [default rune
         /// context](rune::Context::with_default_modules).
         pub fn with_config(stdio: bool) -> Result<rune::Context, rune::ContextError> {
-            #[allow(unused_mut)]
+            with_capabilities(stdio, rune::runtime::Capabilities::all())
+        }
+
+        /// Construct a default rune context restricted to the given
+        /// capabilities, with all enabled modules which only require
+        /// capabilities in the set.
+        ///
+        /// See [Capabilities][rune::runtime::Capabilities] for more.
+        pub fn with_capabilities(
+            stdio: bool,
+            capabilities: rune::runtime::Capabilities,
+        ) -> Result<rune::Context, rune::ContextError> {
             let mut context = rune::Context::with_config(stdio)?;
+            context.set_capabilities(capabilities);
 
             $(
                 #[cfg(feature = $name)]
                 {
-                    context.install(&self::$ident::module(stdio)?)?;
+                    let module = self::$ident::module(stdio)?;
+
+                    if capabilities.contains_all(module.capabilities()) {
+                        context.install(&module)?;
+                    }
                 }
             )*
 
@@ -138,17 +193,30 @@ macro_rules! modules {
 }
 
 modules! {
+    cli, "cli",
     core, "core",
+    crypto, "crypto",
+    csv, "csv",
+    encoding, "encoding",
+    env, "env",
     fmt, "fmt",
     fs, "fs",
     http, "http",
+    http_server, "http-server",
     io, "io",
     json, "json",
+    log, "log",
     macros, "macros",
+    net, "net",
+    path, "path",
     process, "process",
     rand, "rand",
     signal, "signal",
+    sqlite, "sqlite",
+    template, "template",
     test, "test",
     time, "time",
     toml, "toml",
+    websocket, "websocket",
+    yaml, "yaml",
 }
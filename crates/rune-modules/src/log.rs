@@ -0,0 +1,106 @@
+//! The native `log` module for the [Rune Language].
+//!
+//! [Rune Language]: https://rune-rs.github.io
+//!
+//! ## Usage
+//!
+//! Add the following to your `Cargo.toml`:
+//!
+//! ```toml
+//! rune-modules = { version = "0.11.0", features = ["log"] }
+//! ```
+//!
+//! Install it into your context:
+//!
+//! ```rust
+//! # fn main() -> rune::Result<()> {
+//! let mut context = rune::Context::with_default_modules()?;
+//! context.install(&rune_modules::log::module(true)?)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Use it in Rune:
+//!
+//! ```rust,ignore
+//! fn main() {
+//!     log::info!("processing {} items", 42);
+//!     log::warn!("disk is {}% full", 93);
+//!     log::error!("giving up");
+//! }
+//! ```
+//!
+//! The `trace!`, `debug!`, `info!`, `warn!` and `error!` macros take the same
+//! arguments as `format!`, and emit [tracing] events with the `rune::script`
+//! target. Every event has a `message`, and the `file` and `line` of the
+//! script which logged it. Hosts using the [log] crate instead receive the
+//! events as log records, as long as no tracing subscriber has been set.
+//!
+//! The `log::log(level, message)` function logs a message with a level
+//! determined at runtime, which is one of `"trace"`, `"debug"`, `"info"`,
+//! `"warn"` or `"error"`.
+//!
+//! [tracing]: https://docs.rs/tracing
+//! [log]: https://docs.rs/log
+
+use rune::macros::{quote, FormatArgs, MacroContext, TokenStream};
+use rune::parse::Parser;
+use rune::runtime::VmError;
+use rune::{ContextError, Module};
+
+/// The target of the events emitted by scripts.
+const TARGET: &str = "rune::script";
+
+/// Construct the `log` module.
+pub fn module(_stdio: bool) -> Result<Module, ContextError> {
+    let mut module = Module::with_crate("log");
+
+    for level in ["trace", "debug", "info", "warn", "error"] {
+        module.macro_(&[level], move |ctx, stream| log_macro(ctx, stream, level))?;
+    }
+
+    module.function(&["log"], |level: &str, message: &str| {
+        log(level, message, "", 0)
+    })?;
+    module.function(&["__log"], log)?;
+    Ok(module)
+}
+
+/// Implementation for the level macros, which attaches the location of the
+/// macro call.
+fn log_macro(
+    ctx: &mut MacroContext<'_>,
+    stream: &TokenStream,
+    level: &str,
+) -> rune::Result<TokenStream> {
+    let mut p = Parser::from_token_stream(stream, ctx.stream_span());
+    let args = p.parse_all::<FormatArgs>()?;
+    let expanded = args.expand(ctx)?;
+    let level = ctx.lit(level);
+
+    Ok(quote!(log::__log(#level, #expanded, #[builtin] file!(), #[builtin] line!())).into_token_stream(ctx))
+}
+
+/// Emit an event, where an empty file means that the location is unknown.
+fn log(level: &str, message: &str, file: &str, line: i64) -> Result<(), VmError> {
+    macro_rules! event {
+        ($level:ident) => {
+            if file.is_empty() {
+                tracing::$level!(target: TARGET, "{}", message)
+            } else {
+                tracing::$level!(target: TARGET, file, line, "{}", message)
+            }
+        };
+    }
+
+    match level {
+        "trace" => event!(trace),
+        "debug" => event!(debug),
+        "info" => event!(info),
+        "warn" => event!(warn),
+        "error" => event!(error),
+        level => return Err(VmError::panic(format!("unsupported log level `{}`", level))),
+    }
+
+    Ok(())
+}
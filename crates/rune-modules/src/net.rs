@@ -0,0 +1,289 @@
+//! The native `net` module for the [Rune Language].
+//!
+//! [Rune Language]: https://rune-rs.github.io
+//!
+//! ## Usage
+//!
+//! Add the following to your `Cargo.toml`:
+//!
+//! ```toml
+//! rune-modules = { version = "0.11.0", features = ["net"] }
+//! ```
+//!
+//! Install it into your context:
+//!
+//! ```rust
+//! # fn main() -> rune::Result<()> {
+//! let mut context = rune::Context::with_default_modules()?;
+//! context.install(&rune_modules::net::module(true)?)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Use it in Rune:
+//!
+//! ```rust,ignore
+//! use net::TcpListener;
+//!
+//! async fn main() {
+//!     let listener = TcpListener::bind("127.0.0.1:8080").await?;
+//!
+//!     loop {
+//!         let (stream, addr) = listener.accept().await?;
+//!         let request = stream.read(1024).await?;
+//!         stream.write_all(request).await?;
+//!     }
+//! }
+//! ```
+//!
+//! Data can be written both as strings and as bytes, and is always read as
+//! bytes.
+//!
+//! The module requires the [Network][rune::runtime::Capability::Network]
+//! capability.
+
+use rune::runtime::{Bytes, Capability, Value, VmError};
+use rune::{Any, ContextError, Module};
+use std::future::Future;
+use std::io;
+use std::sync::Arc;
+use tokio::net;
+
+/// Construct the `net` module.
+pub fn module(_stdio: bool) -> Result<Module, ContextError> {
+    let mut module = Module::with_crate("net");
+    module.require(Capability::Network);
+
+    module.ty::<TcpStream>()?;
+    module.async_function(&["TcpStream", "connect"], TcpStream::connect)?;
+    module.async_inst_fn("read", TcpStream::read)?;
+    module.async_inst_fn("read_to_end", TcpStream::read_to_end)?;
+    module.async_inst_fn("write_all", TcpStream::write_all)?;
+    module.inst_fn("local_addr", TcpStream::local_addr)?;
+    module.inst_fn("peer_addr", TcpStream::peer_addr)?;
+
+    module.ty::<TcpListener>()?;
+    module.async_function(&["TcpListener", "bind"], TcpListener::bind)?;
+    module.async_inst_fn("accept", TcpListener::accept)?;
+    module.inst_fn("local_addr", TcpListener::local_addr)?;
+
+    module.ty::<UdpSocket>()?;
+    module.async_function(&["UdpSocket", "bind"], UdpSocket::bind)?;
+    module.async_inst_fn("connect", UdpSocket::connect)?;
+    module.async_inst_fn("send", UdpSocket::send)?;
+    module.async_inst_fn("send_to", UdpSocket::send_to)?;
+    module.async_inst_fn("recv", UdpSocket::recv)?;
+    module.async_inst_fn("recv_from", UdpSocket::recv_from)?;
+    module.inst_fn("local_addr", UdpSocket::local_addr)?;
+    Ok(module)
+}
+
+/// Coerce a string or bytes into the data to send.
+fn data_of(value: &Value) -> Result<Vec<u8>, VmError> {
+    Ok(match value {
+        Value::Bytes(bytes) => bytes.borrow_ref()?.to_vec(),
+        Value::String(string) => string.borrow_ref()?.as_bytes().to_vec(),
+        Value::StaticString(string) => string.as_bytes().to_vec(),
+        actual => return Err(VmError::expected::<Bytes>(actual.type_info()?)),
+    })
+}
+
+/// A TCP connection.
+///
+/// The stream is shared with the futures returned by its methods, since the
+/// script only lends it out while the method is being called.
+#[derive(Debug, Any)]
+struct TcpStream {
+    inner: Arc<net::TcpStream>,
+}
+
+impl TcpStream {
+    /// Connect to the given address.
+    async fn connect(addr: &str) -> io::Result<Self> {
+        Ok(Self {
+            inner: Arc::new(net::TcpStream::connect(addr).await?),
+        })
+    }
+
+    /// Read at most `max` bytes. An empty read means the connection was
+    /// closed by the peer.
+    fn read(&self, max: usize) -> impl Future<Output = io::Result<Bytes>> {
+        let inner = self.inner.clone();
+
+        async move {
+            let mut buf = vec![0; max];
+
+            loop {
+                inner.readable().await?;
+
+                match inner.try_read(&mut buf) {
+                    Ok(n) => {
+                        buf.truncate(n);
+                        return Ok(Bytes::from_vec(buf));
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+    }
+
+    /// Read until the connection is closed by the peer.
+    fn read_to_end(&self) -> impl Future<Output = io::Result<Bytes>> {
+        let inner = self.inner.clone();
+
+        async move {
+            let mut out = Vec::new();
+            let mut buf = [0; 4096];
+
+            loop {
+                inner.readable().await?;
+
+                match inner.try_read(&mut buf) {
+                    Ok(0) => return Ok(Bytes::from_vec(out)),
+                    Ok(n) => out.extend_from_slice(&buf[..n]),
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+    }
+
+    /// Write all of the given data.
+    fn write_all(&self, data: Value) -> impl Future<Output = Result<io::Result<()>, VmError>> {
+        let inner = self.inner.clone();
+        let data = data_of(&data);
+
+        async move {
+            let data = data?;
+            let mut written = 0;
+
+            while written < data.len() {
+                if let Err(e) = inner.writable().await {
+                    return Ok(Err(e));
+                }
+
+                match inner.try_write(&data[written..]) {
+                    Ok(n) => written += n,
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => continue,
+                    Err(e) => return Ok(Err(e)),
+                }
+            }
+
+            Ok(Ok(()))
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<String> {
+        Ok(self.inner.local_addr()?.to_string())
+    }
+
+    fn peer_addr(&self) -> io::Result<String> {
+        Ok(self.inner.peer_addr()?.to_string())
+    }
+}
+
+/// A TCP server, listening for connections.
+#[derive(Debug, Any)]
+struct TcpListener {
+    inner: Arc<net::TcpListener>,
+}
+
+impl TcpListener {
+    /// Bind to the given address.
+    async fn bind(addr: &str) -> io::Result<Self> {
+        Ok(Self {
+            inner: Arc::new(net::TcpListener::bind(addr).await?),
+        })
+    }
+
+    /// Accept a new connection, returning it together with the address of
+    /// the peer.
+    fn accept(&self) -> impl Future<Output = io::Result<(TcpStream, String)>> {
+        let inner = self.inner.clone();
+
+        async move {
+            let (stream, addr) = inner.accept().await?;
+
+            let stream = TcpStream {
+                inner: Arc::new(stream),
+            };
+
+            Ok((stream, addr.to_string()))
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<String> {
+        Ok(self.inner.local_addr()?.to_string())
+    }
+}
+
+/// A UDP socket.
+#[derive(Debug, Any)]
+struct UdpSocket {
+    inner: Arc<net::UdpSocket>,
+}
+
+impl UdpSocket {
+    /// Bind to the given address.
+    async fn bind(addr: &str) -> io::Result<Self> {
+        Ok(Self {
+            inner: Arc::new(net::UdpSocket::bind(addr).await?),
+        })
+    }
+
+    /// Connect to the given address, which is used by `send` and `recv`.
+    fn connect(&self, addr: &str) -> impl Future<Output = io::Result<()>> {
+        let inner = self.inner.clone();
+        let addr = addr.to_owned();
+        async move { inner.connect(addr).await }
+    }
+
+    /// Send data to the connected address.
+    fn send(&self, data: Value) -> impl Future<Output = Result<io::Result<usize>, VmError>> {
+        let inner = self.inner.clone();
+        let data = data_of(&data);
+        async move { Ok(inner.send(&data?).await) }
+    }
+
+    /// Send data to the given address.
+    fn send_to(
+        &self,
+        data: Value,
+        addr: &str,
+    ) -> impl Future<Output = Result<io::Result<usize>, VmError>> {
+        let inner = self.inner.clone();
+        let data = data_of(&data);
+        let addr = addr.to_owned();
+        async move { Ok(inner.send_to(&data?, addr).await) }
+    }
+
+    /// Receive a datagram of at most `max` bytes from the connected address.
+    fn recv(&self, max: usize) -> impl Future<Output = io::Result<Bytes>> {
+        let inner = self.inner.clone();
+
+        async move {
+            let mut buf = vec![0; max];
+            let n = inner.recv(&mut buf).await?;
+            buf.truncate(n);
+            Ok(Bytes::from_vec(buf))
+        }
+    }
+
+    /// Receive a datagram of at most `max` bytes, returning it together with
+    /// the address it was sent from.
+    fn recv_from(&self, max: usize) -> impl Future<Output = io::Result<(Bytes, String)>> {
+        let inner = self.inner.clone();
+
+        async move {
+            let mut buf = vec![0; max];
+            let (n, addr) = inner.recv_from(&mut buf).await?;
+            buf.truncate(n);
+            Ok((Bytes::from_vec(buf), addr.to_string()))
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<String> {
+        Ok(self.inner.local_addr()?.to_string())
+    }
+}
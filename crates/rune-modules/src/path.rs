@@ -0,0 +1,290 @@
+//! The native `path` module for the [Rune Language].
+//!
+//! [Rune Language]: https://rune-rs.github.io
+//!
+//! ## Usage
+//!
+//! Add the following to your `Cargo.toml`:
+//!
+//! ```toml
+//! rune-modules = { version = "0.11.0", features = ["path"] }
+//! ```
+//!
+//! Install it into your context:
+//!
+//! ```rust
+//! # fn main() -> rune::Result<()> {
+//! let mut context = rune::Context::with_default_modules()?;
+//! context.install(&rune_modules::path::module(true)?)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Use it in Rune:
+//!
+//! ```rust,ignore
+//! fn main() {
+//!     for source in path::glob("src/**/*.rn") {
+//!         let out = path::join("target", path::with_extension(source, "txt"));
+//!         println(`${source} -> ${path::normalize(out)}`);
+//!     }
+//! }
+//! ```
+//!
+//! Paths are passed around as strings and are manipulated lexically, using
+//! the separator of the host platform. Only `path::exists` and `path::glob`
+//! look at the filesystem, but since they do the module requires the
+//! [Filesystem][rune::runtime::Capability::Filesystem] capability.
+
+use rune::runtime::Capability;
+use rune::{ContextError, Module};
+use std::collections::BTreeSet;
+use std::path::{Component, Path, PathBuf};
+
+/// Construct the `path` module.
+pub fn module(_stdio: bool) -> Result<Module, ContextError> {
+    let mut module = Module::with_crate("path");
+    module.require(Capability::Filesystem);
+    module.function(&["join"], join)?;
+    module.function(&["parent"], parent)?;
+    module.function(&["file_name"], file_name)?;
+    module.function(&["file_stem"], file_stem)?;
+    module.function(&["extension"], extension)?;
+    module.function(&["with_file_name"], with_file_name)?;
+    module.function(&["with_extension"], with_extension)?;
+    module.function(&["components"], components)?;
+    module.function(&["is_absolute"], is_absolute)?;
+    module.function(&["is_relative"], is_relative)?;
+    module.function(&["normalize"], normalize)?;
+    module.function(&["exists"], exists)?;
+    module.function(&["glob"], glob)?;
+    Ok(module)
+}
+
+/// Convert a path into a string which can be handed to a script.
+fn path_to_string(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
+}
+
+/// Join two paths. If `other` is absolute it replaces `path`.
+fn join(path: &str, other: &str) -> String {
+    path_to_string(&Path::new(path).join(other))
+}
+
+fn parent(path: &str) -> Option<String> {
+    Path::new(path).parent().map(path_to_string)
+}
+
+fn file_name(path: &str) -> Option<String> {
+    Some(Path::new(path).file_name()?.to_string_lossy().into_owned())
+}
+
+/// The file name without its extension.
+fn file_stem(path: &str) -> Option<String> {
+    Some(Path::new(path).file_stem()?.to_string_lossy().into_owned())
+}
+
+fn extension(path: &str) -> Option<String> {
+    Some(Path::new(path).extension()?.to_string_lossy().into_owned())
+}
+
+fn with_file_name(path: &str, file_name: &str) -> String {
+    path_to_string(&Path::new(path).with_file_name(file_name))
+}
+
+/// Replace the extension of a path, or remove it if `extension` is empty.
+fn with_extension(path: &str, extension: &str) -> String {
+    path_to_string(&Path::new(path).with_extension(extension))
+}
+
+fn components(path: &str) -> Vec<String> {
+    Path::new(path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect()
+}
+
+fn is_absolute(path: &str) -> bool {
+    Path::new(path).is_absolute()
+}
+
+fn is_relative(path: &str) -> bool {
+    Path::new(path).is_relative()
+}
+
+/// Lexically remove `.` components and resolve `..` components against the
+/// component preceding them, without looking at the filesystem.
+///
+/// Leading `..` components of relative paths are preserved, while ones
+/// following the root are dropped.
+fn normalize(path: &str) -> String {
+    let mut out = Vec::new();
+
+    for component in Path::new(path).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match out.last() {
+                Some(Component::Normal(..)) => {
+                    out.pop();
+                }
+                Some(Component::RootDir | Component::Prefix(..)) => {}
+                _ => out.push(component),
+            },
+            component => out.push(component),
+        }
+    }
+
+    if out.is_empty() {
+        return String::from(".");
+    }
+
+    path_to_string(&out.iter().collect::<PathBuf>())
+}
+
+fn exists(path: &str) -> bool {
+    Path::new(path).symlink_metadata().is_ok()
+}
+
+/// Find all paths matching a pattern, sorted.
+///
+/// Each component of the pattern can contain `*` to match any sequence of
+/// characters, `?` to match any single character and `[...]` to match one
+/// character out of a set, like `[abc]`, `[a-z]` or `[!0-9]`. A component
+/// which is exactly `**` matches any number of directories. Wildcards don't
+/// match file names starting with a `.` unless the pattern does too.
+///
+/// Directories which can't be read are skipped.
+fn glob(pattern: &str) -> Vec<String> {
+    let parts = Path::new(pattern)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+
+    let mut out = BTreeSet::new();
+    walk(PathBuf::new(), &parts, &mut out);
+    out.into_iter().collect()
+}
+
+/// Walk the directory at `prefix` matching the remaining components of a
+/// pattern.
+fn walk(prefix: PathBuf, parts: &[String], out: &mut BTreeSet<String>) {
+    let (part, rest) = match parts.split_first() {
+        Some(split) => split,
+        None => {
+            out.insert(path_to_string(&prefix));
+            return;
+        }
+    };
+
+    if !part.contains(['*', '?', '[']) {
+        let path = prefix.join(part);
+
+        if rest.is_empty() {
+            if path.symlink_metadata().is_ok() {
+                out.insert(path_to_string(&path));
+            }
+        } else if path.is_dir() {
+            walk(path, rest, out);
+        }
+
+        return;
+    }
+
+    if part == "**" {
+        walk(prefix.clone(), rest, out);
+    }
+
+    let dir = if prefix.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        prefix.as_path()
+    };
+
+    let entries = match dir.read_dir() {
+        Ok(entries) => entries,
+        Err(..) => return,
+    };
+
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let path = prefix.join(&name);
+
+        if part == "**" {
+            if !name.starts_with('.') && path.is_dir() {
+                walk(path, parts, out);
+            }
+
+            continue;
+        }
+
+        if name.starts_with('.') && !part.starts_with('.') {
+            continue;
+        }
+
+        let pattern = part.chars().collect::<Vec<_>>();
+        let name = name.chars().collect::<Vec<_>>();
+
+        if !matches(&pattern, &name) {
+            continue;
+        }
+
+        if rest.is_empty() {
+            out.insert(path_to_string(&path));
+        } else if path.is_dir() {
+            walk(path, rest, out);
+        }
+    }
+}
+
+/// Test if a single path component matches a wildcard pattern.
+fn matches(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|n| matches(rest, &name[n..])),
+        Some(('?', rest)) => !name.is_empty() && matches(rest, &name[1..]),
+        Some(('[', rest)) => {
+            let (c, name) = match name.split_first() {
+                Some(split) => split,
+                None => return false,
+            };
+
+            match class(rest, *c) {
+                Some((true, rest)) => matches(rest, name),
+                Some((false, _)) => false,
+                // NB: an unterminated class is matched literally.
+                None => *c == '[' && matches(rest, name),
+            }
+        }
+        Some((p, rest)) => name.first() == Some(p) && matches(rest, &name[1..]),
+    }
+}
+
+/// Match a character against the character class at the start of `pattern`
+/// (after the opening `[`), returning if it matched and the rest of the
+/// pattern.
+fn class(pattern: &[char], c: char) -> Option<(bool, &[char])> {
+    let (negated, mut pattern) = match pattern.split_first() {
+        Some(('!', rest)) => (true, rest),
+        _ => (false, pattern),
+    };
+
+    let mut matched = false;
+    let mut first = true;
+
+    loop {
+        match pattern {
+            [']', rest @ ..] if !first => return Some((matched != negated, rest)),
+            [a, '-', b, rest @ ..] if *b != ']' => {
+                matched |= (*a..=*b).contains(&c);
+                pattern = rest;
+            }
+            [a, rest @ ..] => {
+                matched |= *a == c;
+                pattern = rest;
+            }
+            [] => return None,
+        }
+
+        first = false;
+    }
+}
@@ -30,16 +30,42 @@
 //!     command.run().await;
 //! }
 //! ```
+//!
+//! Commands can be configured with an environment and a working directory,
+//! and their output can be captured:
+//!
+//! ```rust,ignore
+//! use process::Command;
+//!
+//! async fn main() {
+//!     let command = Command::new("git");
+//!     command.args(["status", "--short"]);
+//!     command.current_dir("repo");
+//!     command.env("GIT_PAGER", "cat");
+//!
+//!     let output = command.output().await?;
+//!
+//!     if !output.status.success() {
+//!         panic(`git failed: ${output.status}`);
+//!     }
+//! }
+//! ```
+//!
+//! The module requires the [Process][rune::runtime::Capability::Process]
+//! capability.
 
 use rune::{Any, Module, ContextError};
-use rune::runtime::{Bytes, Shared, Value, VmError, Protocol};
+use rune::runtime::{Bytes, Capability, Shared, Value, VmError, Protocol};
 use std::fmt;
+use std::future::Future;
 use std::io;
+use std::process::Stdio;
 use tokio::process;
 
 /// Construct the `process` module.
 pub fn module(_stdio: bool) -> Result<Module, ContextError> {
     let mut module = Module::with_crate("process");
+    module.require(Capability::Process);
     module.ty::<Command>()?;
     module.ty::<Child>()?;
     module.ty::<ExitStatus>()?;
@@ -49,9 +75,18 @@ pub fn module(_stdio: bool) -> Result<Module, ContextError> {
     module.inst_fn("spawn", Command::spawn)?;
     module.inst_fn("arg", Command::arg)?;
     module.inst_fn("args", Command::args)?;
+    module.inst_fn("env", Command::env)?;
+    module.inst_fn("env_remove", Command::env_remove)?;
+    module.inst_fn("env_clear", Command::env_clear)?;
+    module.inst_fn("current_dir", Command::current_dir)?;
+    module.async_inst_fn("status", Command::status)?;
+    module.async_inst_fn("output", Command::output)?;
+    module.inst_fn("id", Child::id)?;
+    module.async_inst_fn("wait", Child::wait)?;
     module.async_inst_fn("wait_with_output", Child::wait_with_output)?;
     module.inst_fn(Protocol::STRING_DISPLAY, ExitStatus::display)?;
     module.inst_fn("code", ExitStatus::code)?;
+    module.inst_fn("success", ExitStatus::success)?;
     Ok(module)
 }
 
@@ -92,12 +127,57 @@ impl Command {
         self.inner.arg(arg);
     }
 
+    /// Set an environment variable for the command.
+    fn env(&mut self, key: &str, value: &str) {
+        self.inner.env(key, value);
+    }
+
+    /// Remove an environment variable for the command.
+    fn env_remove(&mut self, key: &str) {
+        self.inner.env_remove(key);
+    }
+
+    /// Clear the environment the command inherits.
+    fn env_clear(&mut self) {
+        self.inner.env_clear();
+    }
+
+    /// Set the working directory of the command.
+    fn current_dir(&mut self, dir: &str) {
+        self.inner.current_dir(dir);
+    }
+
     /// Spawn the command.
     fn spawn(mut self) -> io::Result<Child> {
         Ok(Child {
             inner: Some(self.inner.spawn()?),
         })
     }
+
+    /// Run the command to completion with inherited stdio, returning its exit
+    /// status.
+    fn status(&mut self) -> impl Future<Output = io::Result<ExitStatus>> {
+        // NB: the command is only accessible while the function is being
+        // called, so the child is spawned before the future is returned.
+        let child = self.inner.spawn();
+
+        async move {
+            let status = child?.wait().await?;
+            Ok(ExitStatus { status })
+        }
+    }
+
+    /// Run the command to completion, capturing its stdout and stderr.
+    fn output(&mut self) -> impl Future<Output = io::Result<Output>> {
+        self.inner.stdout(Stdio::piped());
+        self.inner.stderr(Stdio::piped());
+        let child = self.inner.spawn();
+
+        async move {
+            let output = child?.wait_with_output().await?;
+            Ok(Output::from(output))
+        }
+    }
 }
 
 #[derive(Any)]
@@ -110,6 +190,25 @@ struct Child {
 }
 
 impl Child {
+    /// Get the operating system identifier of the child process, if it's
+    /// still running.
+    fn id(&self) -> Option<u32> {
+        self.inner.as_ref()?.id()
+    }
+
+    /// Returns a future that will resolve to the exit status of the child
+    /// process.
+    async fn wait(self) -> Result<io::Result<ExitStatus>, VmError> {
+        let mut inner = match self.inner {
+            Some(inner) => inner,
+            None => {
+                return Err(VmError::panic("already completed"));
+            }
+        };
+
+        Ok(inner.wait().await.map(|status| ExitStatus { status }))
+    }
+
     // Returns a future that will resolve to an Output, containing the exit
     // status, stdout, and stderr of the child process.
     async fn wait_with_output(self) -> Result<io::Result<Output>, VmError> {
@@ -125,11 +224,7 @@ impl Child {
             Err(error) => return Ok(Err(error)),
         };
 
-        Ok(Ok(Output {
-            status: ExitStatus { status: output.status },
-            stdout: Shared::new(Bytes::from_vec(output.stdout)),
-            stderr: Shared::new(Bytes::from_vec(output.stderr)),
-        }))
+        Ok(Ok(Output::from(output)))
     }
 }
 
@@ -143,6 +238,18 @@ struct Output {
     stderr: Shared<Bytes>,
 }
 
+impl From<std::process::Output> for Output {
+    fn from(output: std::process::Output) -> Self {
+        Self {
+            status: ExitStatus {
+                status: output.status,
+            },
+            stdout: Shared::new(Bytes::from_vec(output.stdout)),
+            stderr: Shared::new(Bytes::from_vec(output.stderr)),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Any)]
 struct ExitStatus {
     status: std::process::ExitStatus,
@@ -157,4 +264,8 @@ impl ExitStatus {
     fn code(&self) -> Option<i32> {
         self.status.code()
     }
+
+    fn success(&self) -> bool {
+        self.status.success()
+    }
 }
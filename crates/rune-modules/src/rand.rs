@@ -32,14 +32,38 @@
 //!     println(`Random int between -100 and 100: {rand_int_range}`);
 //! }
 //! ```
+//!
+//! For reproducible randomness, use an explicitly seeded `Rng`:
+//!
+//! ```rust,ignore
+//! fn main() {
+//!     let rng = rand::Rng::with_seed(42);
+//!     let deck = ["ace", "king", "queen", "jack"];
+//!     rng.shuffle(deck);
+//!
+//!     let roll = rng.int_range(1, 7);
+//!     let chance = rng.float();
+//!     let card = rng.choose(deck);
+//! }
+//! ```
+//!
+//! The same functions are also available at the top level of the module, like
+//! `rand::float()` and `rand::shuffle(deck)`, using a new generator for each
+//! call.
+//!
+//! If the virtual machine is [deterministic], random number generators which
+//! aren't explicitly seeded are seeded from it.
+//!
+//! [deterministic]: rune::runtime::Determinism
 
-use nanorand::Rng;
+use nanorand::Rng as _;
 use rune::{Any, ContextError, Module};
-use rune::runtime::Value;
+use rune::runtime::{Capability, Determinism, Value, Vec, VmError};
 
 /// Construct the `rand` module.
 pub fn module(_stdio: bool) -> Result<Module, ContextError> {
     let mut module = Module::with_crate("rand");
+    module.require(Capability::Random);
 
     module.ty::<WyRand>()?;
     module.function(&["WyRand", "new"], WyRand::new)?;
@@ -53,8 +77,26 @@ pub fn module(_stdio: bool) -> Result<Module, ContextError> {
     module.inst_fn("int", Pcg64::int)?;
     module.inst_fn("int_range", Pcg64::int_range)?;
 
+    module.ty::<Rng>()?;
+    module.function(&["Rng", "new"], Rng::new)?;
+    module.function(&["Rng", "with_seed"], Rng::with_seed)?;
+    module.inst_fn("int", Rng::int)?;
+    module.inst_fn("int_range", Rng::int_range)?;
+    module.inst_fn("float", Rng::float)?;
+    module.inst_fn("float_range", Rng::float_range)?;
+    module.inst_fn("bool", Rng::bool)?;
+    module.inst_fn("shuffle", Rng::shuffle)?;
+    module.inst_fn("choose", Rng::choose)?;
+
     module.function(&["int"], int)?;
     module.function(&["int_range"], int_range)?;
+    module.function(&["float"], || Rng::new().float())?;
+    module.function(&["float_range"], |lower, upper| {
+        Rng::new().float_range(lower, upper)
+    })?;
+    module.function(&["bool"], || Rng::new().bool())?;
+    module.function(&["shuffle"], |vec: &mut Vec| Rng::new().shuffle(vec))?;
+    module.function(&["choose"], |vec: &Vec| Rng::new().choose(vec))?;
 
     Ok(module)
 }
@@ -67,9 +109,7 @@ struct WyRand {
 impl WyRand {
     /// Create a new RNG instance.
     fn new() -> Self {
-        Self {
-            inner: nanorand::WyRand::new(),
-        }
+        Self { inner: wyrand() }
     }
 
     /// Create a new RNG instance, using a custom seed.
@@ -98,9 +138,15 @@ struct Pcg64 {
 impl Pcg64 {
     /// Create a new RNG instance.
     fn new() -> Self {
-        Self {
-            inner: nanorand::Pcg64::new(),
-        }
+        let inner = match Determinism::current() {
+            Some(determinism) => {
+                let seed = ((determinism.next_u64() as u128) << 64) | determinism.next_u64() as u128;
+                nanorand::Pcg64::new_seed(seed)
+            }
+            None => nanorand::Pcg64::new(),
+        };
+
+        Self { inner }
     }
 
     /// Create a new RNG instance, using a custom seed.
@@ -121,21 +167,113 @@ impl Pcg64 {
     }
 }
 
+/// A random number generator, which can be explicitly seeded to produce the
+/// same sequence of numbers every time.
+#[derive(Any)]
+struct Rng {
+    inner: nanorand::WyRand,
+}
+
+impl Rng {
+    /// Create a generator, which is seeded from the virtual machine if it's
+    /// deterministic and from entropy otherwise.
+    fn new() -> Self {
+        Self { inner: wyrand() }
+    }
+
+    /// Create a generator from the given seed.
+    fn with_seed(seed: i64) -> Self {
+        Self {
+            inner: nanorand::WyRand::new_seed(seed as u64),
+        }
+    }
+
+    /// Generate a random integer.
+    fn int(&mut self) -> i64 {
+        self.inner.generate::<u64>() as i64
+    }
+
+    /// Generate a random integer in the range `lower..upper`.
+    fn int_range(&mut self, lower: i64, upper: i64) -> Result<i64, VmError> {
+        if lower >= upper {
+            return Err(VmError::panic(format!(
+                "empty range {}..{}",
+                lower, upper
+            )));
+        }
+
+        let span = upper.wrapping_sub(lower) as u64;
+        Ok(lower.wrapping_add(self.inner.generate_range(0..span) as i64))
+    }
+
+    /// Generate a random float in the range `0.0..1.0`.
+    fn float(&mut self) -> f64 {
+        // Use the 53 most significant bits, which is the precision of a float.
+        (self.inner.generate::<u64>() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Generate a random float in the range `lower..upper`.
+    fn float_range(&mut self, lower: f64, upper: f64) -> Result<f64, VmError> {
+        if lower >= upper || lower.is_nan() || !(upper - lower).is_finite() {
+            return Err(VmError::panic(format!(
+                "invalid range {}..{}",
+                lower, upper
+            )));
+        }
+
+        Ok(lower + self.float() * (upper - lower))
+    }
+
+    /// Generate a random bool.
+    fn bool(&mut self) -> bool {
+        self.inner.generate::<u64>() & 1 == 1
+    }
+
+    /// Shuffle a vector in place.
+    fn shuffle(&mut self, vec: &mut Vec) {
+        // Fisher-Yates, which makes every permutation equally likely.
+        for n in (1..vec.len()).rev() {
+            let m = self.inner.generate_range(0..=n);
+            vec.swap(n, m);
+        }
+    }
+
+    /// Choose a random element of a vector, or `None` if it's empty.
+    fn choose(&mut self, vec: &Vec) -> Option<Value> {
+        if vec.is_empty() {
+            return None;
+        }
+
+        let n = self.inner.generate_range(0..vec.len());
+        vec.get(n).cloned()
+    }
+}
+
 fn int() -> rune::Result<Value> {
     Ok(Value::Integer(
-        nanorand::WyRand::new().generate::<u64>() as i64
+        wyrand().generate::<u64>() as i64
     ))
 }
 
 fn int_range(lower: i64, upper: i64) -> rune::Result<Value> {
     Ok(Value::Integer(
-        nanorand::WyRand::new().generate_range(0..(upper - lower) as u64) as i64 + lower,
+        wyrand().generate_range(0..(upper - lower) as u64) as i64 + lower,
     ))
 }
 
+/// Construct a generator which is seeded from the virtual machine if it's
+/// deterministic.
+fn wyrand() -> nanorand::WyRand {
+    match Determinism::current() {
+        Some(determinism) => nanorand::WyRand::new_seed(determinism.next_u64()),
+        None => nanorand::WyRand::new(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{int, int_range};
+    use super::{int, int_range, Rng};
+    use rune::runtime::{Value, Vec};
 
     #[test]
     fn test_range_is_exclusive() {
@@ -164,4 +302,52 @@ mod tests {
         assert!(any_positive);
         assert!(any_negative);
     }
+
+    #[test]
+    fn test_rng_ranges() {
+        let mut rng = Rng::with_seed(1);
+
+        for _ in 0..100 {
+            assert!((-3..3).contains(&rng.int_range(-3, 3).unwrap()));
+            assert!((0.0..1.0).contains(&rng.float()));
+            assert!((2.0..2.5).contains(&rng.float_range(2.0, 2.5).unwrap()));
+        }
+
+        assert_eq!(rng.int_range(i64::MIN, i64::MIN + 1).unwrap(), i64::MIN);
+        assert!(rng.int_range(1, 1).is_err());
+        assert!(rng.float_range(1.0, f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_rng_with_seed_is_reproducible() {
+        let shuffled = |seed| {
+            let mut rng = Rng::with_seed(seed);
+            let mut vec = Vec::from((0..10i64).map(Value::from).collect::<std::vec::Vec<_>>());
+            rng.shuffle(&mut vec);
+
+            vec.iter()
+                .map(|v| v.clone().into_integer().unwrap())
+                .collect::<std::vec::Vec<_>>()
+        };
+
+        assert_eq!(shuffled(7), shuffled(7));
+        assert_ne!(shuffled(7), shuffled(8));
+
+        let mut sorted = shuffled(7);
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..10).collect::<std::vec::Vec<_>>());
+    }
+
+    #[test]
+    fn test_choose() {
+        let mut rng = Rng::with_seed(3);
+        assert!(rng.choose(&Vec::new()).is_none());
+
+        let vec = Vec::from(vec![Value::from(1i64), Value::from(2i64)]);
+
+        for _ in 0..10 {
+            let n = rng.choose(&vec).unwrap().into_integer().unwrap();
+            assert!(n == 1 || n == 2);
+        }
+    }
 }
@@ -31,10 +31,12 @@
 
 use tokio::signal;
 use rune::{Module, ContextError};
+use rune::runtime::Capability;
 
 /// Construct the `signal` module.
 pub fn module(_stdio: bool) -> Result<Module, ContextError> {
     let mut module = Module::with_crate("signal");
+    module.require(Capability::Process);
     module.async_function(&["ctrl_c"], signal::ctrl_c)?;
     Ok(module)
 }
@@ -0,0 +1,366 @@
+//! The native `sqlite` module for the [Rune Language].
+//!
+//! [Rune Language]: https://rune-rs.github.io
+//!
+//! ## Usage
+//!
+//! Add the following to your `Cargo.toml`:
+//!
+//! ```toml
+//! rune-modules = { version = "0.11.0", features = ["sqlite"] }
+//! ```
+//!
+//! Install it into your context:
+//!
+//! ```rust
+//! # fn main() -> rune::Result<()> {
+//! let mut context = rune::Context::with_default_modules()?;
+//! context.install(&rune_modules::sqlite::module(true)?)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Use it in Rune:
+//!
+//! ```rust,ignore
+//! use sqlite::Connection;
+//!
+//! fn main() {
+//!     let db = Connection::open("people.db")?;
+//!     db.execute_batch("CREATE TABLE IF NOT EXISTS people (name TEXT, age INTEGER)")?;
+//!
+//!     let insert = db.prepare("INSERT INTO people (name, age) VALUES (?, ?)")?;
+//!     insert.execute(["ann", 42])?;
+//!     insert.execute(["bob", 17])?;
+//!
+//!     for person in db.query("SELECT * FROM people WHERE age > :age", #{ age: 18 })? {
+//!         println(`${person.name} is ${person.age}`);
+//!     }
+//! }
+//! ```
+//!
+//! Parameters are given as a vector or tuple for positional parameters like
+//! `?`, as an object for named parameters like `:name`, or as unit if there are
+//! none. Unit and `None` bind as `NULL`, bools as integers and bytes as blobs.
+//! Queried rows are objects keyed by column name, where `NULL` is unit, text is
+//! a string and blobs are bytes.
+//!
+//! Every row of a query is fetched from the database before it returns, so
+//! errors raised while fetching rows are returned as a result just like errors
+//! raised by the query itself. Every named parameter of a statement has to be
+//! given a value, just like every positional parameter.
+//!
+//! SQLite is an embedded database and the driver has no asynchronous
+//! interface, so calls are made synchronously and don't need to be awaited.
+//! Prepared statements are kept in a cache on the connection, so preparing the
+//! same statement again is cheap.
+//!
+//! The module requires the [Filesystem][rune::runtime::Capability::Filesystem]
+//! capability.
+
+use rune::runtime::{Bytes, Capability, Iterator, Object, Protocol, Value, VmError};
+use rune::{Any, ContextError, Module};
+use rusqlite::types::{Value as SqlValue, ValueRef};
+use std::fmt;
+use std::fmt::Write as _;
+use std::rc::Rc;
+
+/// Construct the `sqlite` module.
+pub fn module(_stdio: bool) -> Result<Module, ContextError> {
+    let mut module = Module::with_crate("sqlite");
+    module.require(Capability::Filesystem);
+
+    module.ty::<Connection>()?;
+    module.function(&["Connection", "open"], Connection::open)?;
+    module.function(
+        &["Connection", "open_in_memory"],
+        Connection::open_in_memory,
+    )?;
+    module.inst_fn("execute", Connection::execute)?;
+    module.inst_fn("execute_batch", Connection::execute_batch)?;
+    module.inst_fn("query", Connection::query)?;
+    module.inst_fn("query_row", Connection::query_row)?;
+    module.inst_fn("prepare", Connection::prepare)?;
+    module.inst_fn("last_insert_rowid", Connection::last_insert_rowid)?;
+
+    module.ty::<Statement>()?;
+    module.inst_fn("execute", Statement::execute)?;
+    module.inst_fn("query", Statement::query)?;
+    module.inst_fn("query_row", Statement::query_row)?;
+
+    module.ty::<Error>()?;
+    module.inst_fn(Protocol::STRING_DISPLAY, Error::display)?;
+    Ok(module)
+}
+
+/// A connection to a database.
+#[derive(Any)]
+struct Connection {
+    inner: Rc<rusqlite::Connection>,
+}
+
+impl Connection {
+    /// Open the database at the given path, creating it if it doesn't exist.
+    fn open(path: &str) -> Result<Self, Error> {
+        Ok(Self {
+            inner: Rc::new(rusqlite::Connection::open(path)?),
+        })
+    }
+
+    /// Open a new database in memory.
+    fn open_in_memory() -> Result<Self, Error> {
+        Ok(Self {
+            inner: Rc::new(rusqlite::Connection::open_in_memory()?),
+        })
+    }
+
+    /// Execute a single statement, returning the number of rows changed.
+    fn execute(&self, sql: &str, params: Value) -> Result<Result<usize, Error>, VmError> {
+        self.statement(sql).execute(params)
+    }
+
+    /// Execute any number of statements separated by semicolons, without
+    /// parameters.
+    fn execute_batch(&self, sql: &str) -> Result<(), Error> {
+        Ok(self.inner.execute_batch(sql)?)
+    }
+
+    /// Run a query, producing an iterator over its rows.
+    fn query(&self, sql: &str, params: Value) -> Result<Result<Iterator, Error>, VmError> {
+        self.statement(sql).query(params)
+    }
+
+    /// Run a query, producing its first row if there is one.
+    fn query_row(
+        &self,
+        sql: &str,
+        params: Value,
+    ) -> Result<Result<Option<Object>, Error>, VmError> {
+        self.statement(sql).query_row(params)
+    }
+
+    /// Prepare a statement which can be executed any number of times.
+    fn prepare(&self, sql: &str) -> Result<Statement, Error> {
+        self.inner.prepare_cached(sql)?;
+        Ok(self.statement(sql))
+    }
+
+    /// The row id of the last successful insert.
+    fn last_insert_rowid(&self) -> i64 {
+        self.inner.last_insert_rowid()
+    }
+
+    fn statement(&self, sql: &str) -> Statement {
+        Statement {
+            connection: self.inner.clone(),
+            sql: sql.to_owned(),
+        }
+    }
+}
+
+/// A prepared statement.
+#[derive(Any)]
+struct Statement {
+    connection: Rc<rusqlite::Connection>,
+    sql: String,
+}
+
+impl Statement {
+    /// Execute the statement, returning the number of rows changed.
+    fn execute(&self, params: Value) -> Result<Result<usize, Error>, VmError> {
+        let params = Params::from_value(params)?;
+        Ok(self.run(&params).map_err(Error::from))
+    }
+
+    /// Run the statement as a query, producing an iterator over its rows.
+    fn query(&self, params: Value) -> Result<Result<Iterator, Error>, VmError> {
+        let rows = match self.rows(params, None)? {
+            Ok(rows) => rows,
+            Err(error) => return Ok(Err(error)),
+        };
+
+        Ok(Ok(Iterator::from_double_ended(
+            "sqlite::Rows",
+            rows.into_iter(),
+        )))
+    }
+
+    /// Run the statement as a query, producing its first row if there is one.
+    fn query_row(&self, params: Value) -> Result<Result<Option<Object>, Error>, VmError> {
+        Ok(self
+            .rows(params, Some(1))?
+            .map(|rows| rows.into_iter().next()))
+    }
+
+    /// Collect up to `limit` rows of the query.
+    fn rows(
+        &self,
+        params: Value,
+        limit: Option<usize>,
+    ) -> Result<Result<Vec<Object>, Error>, VmError> {
+        let params = Params::from_value(params)?;
+        Ok(self.collect(&params, limit).map_err(Error::from))
+    }
+
+    fn run(&self, params: &Params) -> rusqlite::Result<usize> {
+        let mut statement = self.connection.prepare_cached(&self.sql)?;
+        params.bind(&mut statement)?;
+        statement.raw_execute()
+    }
+
+    fn collect(&self, params: &Params, limit: Option<usize>) -> rusqlite::Result<Vec<Object>> {
+        let mut statement = self.connection.prepare_cached(&self.sql)?;
+        params.bind(&mut statement)?;
+
+        let columns = statement
+            .column_names()
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+
+        let mut rows = statement.raw_query();
+        let mut out = Vec::new();
+
+        while !matches!(limit, Some(limit) if out.len() >= limit) {
+            let row = match rows.next()? {
+                Some(row) => row,
+                None => break,
+            };
+
+            let mut object = Object::with_capacity(columns.len());
+
+            for (index, column) in columns.iter().enumerate() {
+                let value = match row.get_ref(index)? {
+                    ValueRef::Null => Value::Unit,
+                    ValueRef::Integer(integer) => Value::Integer(integer),
+                    ValueRef::Real(float) => Value::Float(float),
+                    ValueRef::Text(text) => Value::from(String::from_utf8_lossy(text).into_owned()),
+                    ValueRef::Blob(blob) => Value::from(Bytes::from_vec(blob.to_vec())),
+                };
+
+                object.insert(column.clone(), value);
+            }
+
+            out.push(object);
+        }
+
+        Ok(out)
+    }
+}
+
+/// The parameters bound to a statement.
+enum Params {
+    Positional(Vec<SqlValue>),
+    Named(Vec<(String, SqlValue)>),
+}
+
+impl Params {
+    fn from_value(value: Value) -> Result<Self, VmError> {
+        Ok(match value {
+            Value::Unit => Self::Positional(Vec::new()),
+            Value::Vec(vec) => Self::Positional(Self::values(&vec.borrow_ref()?)?),
+            Value::Tuple(tuple) => Self::Positional(Self::values(&tuple.borrow_ref()?)?),
+            Value::Object(object) => {
+                let mut params = Vec::new();
+
+                for (name, value) in object.borrow_ref()?.iter() {
+                    let name = if name.starts_with([':', '@', '$']) {
+                        name.clone()
+                    } else {
+                        format!(":{}", name)
+                    };
+
+                    params.push((name, Self::value(value.clone())?));
+                }
+
+                Self::Named(params)
+            }
+            actual => return Err(VmError::expected::<rune::runtime::Vec>(actual.type_info()?)),
+        })
+    }
+
+    fn values(values: &[Value]) -> Result<Vec<SqlValue>, VmError> {
+        values.iter().cloned().map(Self::value).collect()
+    }
+
+    /// Convert a value into an SQL value.
+    fn value(value: Value) -> Result<SqlValue, VmError> {
+        Ok(match value {
+            Value::Unit => SqlValue::Null,
+            Value::Bool(boolean) => SqlValue::Integer(boolean as i64),
+            Value::Byte(byte) => SqlValue::Integer(byte as i64),
+            Value::Integer(integer) => SqlValue::Integer(integer),
+            Value::Float(float) => SqlValue::Real(float),
+            Value::Char(c) => SqlValue::Text(c.to_string()),
+            Value::String(string) => SqlValue::Text(string.borrow_ref()?.clone()),
+            Value::StaticString(string) => SqlValue::Text(string.as_str().to_owned()),
+            Value::Bytes(bytes) => SqlValue::Blob(bytes.borrow_ref()?.to_vec()),
+            Value::Option(option) => match option.borrow_ref()?.clone() {
+                Some(value) => Self::value(value)?,
+                None => SqlValue::Null,
+            },
+            actual => return Err(VmError::expected::<String>(actual.type_info()?)),
+        })
+    }
+
+    /// Bind the parameters to a statement. Statements taken from the cache
+    /// have no parameters bound.
+    fn bind(&self, statement: &mut rusqlite::Statement<'_>) -> rusqlite::Result<()> {
+        match self {
+            Self::Positional(values) => {
+                let expected = statement.parameter_count();
+
+                if values.len() != expected {
+                    return Err(rusqlite::Error::InvalidParameterCount(
+                        values.len(),
+                        expected,
+                    ));
+                }
+
+                for (index, value) in values.iter().enumerate() {
+                    statement.raw_bind_parameter(index + 1, value)?;
+                }
+            }
+            Self::Named(values) => {
+                let expected = statement.parameter_count();
+                let mut bound = vec![false; expected];
+
+                for (name, value) in values {
+                    match statement.parameter_index(name)? {
+                        Some(index) => {
+                            statement.raw_bind_parameter(index, value)?;
+                            bound[index - 1] = true;
+                        }
+                        None => return Err(rusqlite::Error::InvalidParameterName(name.clone())),
+                    }
+                }
+
+                let count = bound.iter().filter(|bound| **bound).count();
+
+                if count != expected {
+                    return Err(rusqlite::Error::InvalidParameterCount(count, expected));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// An error raised by the database.
+#[derive(Debug, Any)]
+struct Error {
+    inner: rusqlite::Error,
+}
+
+impl From<rusqlite::Error> for Error {
+    fn from(inner: rusqlite::Error) -> Self {
+        Self { inner }
+    }
+}
+
+impl Error {
+    fn display(&self, buf: &mut String) -> fmt::Result {
+        write!(buf, "{}", self.inner)
+    }
+}
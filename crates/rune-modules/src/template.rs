@@ -0,0 +1,739 @@
+//! The native `template` module for the [Rune Language].
+//!
+//! [Rune Language]: https://rune-rs.github.io
+//!
+//! ## Usage
+//!
+//! Add the following to your `Cargo.toml`:
+//!
+//! ```toml
+//! rune-modules = { version = "0.11.0", features = ["template"] }
+//! ```
+//!
+//! Install it into your context:
+//!
+//! ```rust
+//! # fn main() -> rune::Result<()> {
+//! let mut context = rune::Context::with_default_modules()?;
+//! context.install(&rune_modules::template::module(true)?)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Use it in Rune:
+//!
+//! ```rust,ignore
+//! use template::Template;
+//!
+//! fn main() {
+//!     let template = Template::new(`
+//! [server]
+//! name = "{{ name }}"
+//! {% for key, value in options %}
+//! {{ key }} = {{ value }}
+//! {% endfor %}
+//! `)?;
+//!
+//!     println(template.render(#{ name: "rune", options: #{ port: 8080 } })?);
+//! }
+//! ```
+//!
+//! Templates support the following syntax:
+//!
+//! * `{{ path }}` substitutes a value, where a path is a variable optionally
+//!   followed by fields or indexes like `user.name` or `items.0`. Strings,
+//!   numbers, bools and chars are rendered as-is, unit and `None` are rendered
+//!   as nothing and `Some` is rendered as its content. A value can be passed
+//!   through the filters `escape` (for HTML), `upper`, `lower` and `trim`, like
+//!   `{{ user.name | trim | escape }}`.
+//! * `{% if path %}`, `{% elif path %}`, `{% else %}` and `{% endif %}` render
+//!   conditionally. Conditions can be negated with `not`, and `false`, unit,
+//!   `None`, zero and empty strings and collections are false.
+//! * `{% for item in path %}` ... `{% endfor %}` loops over a vector or tuple,
+//!   and `{% for key, value in path %}` loops over an object or over the
+//!   indexes and values of a vector. An `{% else %}` branch is rendered if
+//!   there is nothing to loop over, and inside of the loop `loop.index`,
+//!   `loop.first` and `loop.last` are available.
+//! * `{# ... #}` is a comment.
+//!
+//! Variables are looked up in the object or struct being rendered, and
+//! referencing one which doesn't exist is an error. Tags other than
+//! substitutions which are the only thing on a line remove the whole line from
+//! the output, so that they can be used to structure templates without leaving
+//! empty lines behind.
+
+use rune::runtime::{Object, Protocol, Value, VmError};
+use rune::{Any, ContextError, Module};
+use std::fmt;
+use std::fmt::Write as _;
+
+/// Construct the `template` module.
+pub fn module(_stdio: bool) -> Result<Module, ContextError> {
+    let mut module = Module::with_crate("template");
+
+    module.ty::<Template>()?;
+    module.function(&["Template", "new"], Template::new)?;
+    module.inst_fn("render", Template::render)?;
+    module.function(&["render"], render)?;
+
+    module.ty::<Error>()?;
+    module.inst_fn(Protocol::STRING_DISPLAY, Error::display)?;
+    Ok(module)
+}
+
+/// Compile and render a template in one go.
+fn render(source: &str, data: Value) -> Result<Result<String, Error>, VmError> {
+    match Template::new(source) {
+        Ok(template) => template.render(data),
+        Err(error) => Ok(Err(error)),
+    }
+}
+
+/// A compiled template, which can be rendered any number of times.
+#[derive(Debug, Clone, Any)]
+struct Template {
+    nodes: Vec<Node>,
+}
+
+impl Template {
+    /// Compile a template.
+    fn new(source: &str) -> Result<Self, Error> {
+        let mut tokens = lex(source)?.into_iter();
+        let (nodes, _) = parse(&mut tokens, &[])?;
+        Ok(Self { nodes })
+    }
+
+    /// Render the template with the variables in the given object or struct.
+    fn render(&self, data: Value) -> Result<Result<String, Error>, VmError> {
+        let mut renderer = Renderer {
+            root: data,
+            scopes: Vec::new(),
+            out: String::new(),
+        };
+
+        if let Err(error) = renderer.nodes(&self.nodes)? {
+            return Ok(Err(error));
+        }
+
+        Ok(Ok(renderer.out))
+    }
+}
+
+enum Token {
+    Text(String),
+    /// The content of a `{{ .. }}` tag.
+    Expr(String, usize),
+    /// The content of a `{% .. %}` tag.
+    Block(String, usize),
+}
+
+/// Split a template into text and tags.
+fn lex(source: &str) -> Result<Vec<Token>, Error> {
+    let mut tokens = Vec::new();
+    let mut text = String::new();
+    let mut rest = source;
+    let mut line = 1;
+    // If nothing but text has been seen on the current line.
+    let mut at_line_start = true;
+
+    loop {
+        let start = match ["{{", "{%", "{#"]
+            .iter()
+            .filter_map(|open| rest.find(open))
+            .min()
+        {
+            Some(start) => start,
+            None => {
+                text.push_str(rest);
+                break;
+            }
+        };
+
+        text.push_str(&rest[..start]);
+        line += rest[..start].matches('\n').count();
+
+        let open = &rest[start..start + 2];
+
+        let close = match open {
+            "{{" => "}}",
+            "{%" => "%}",
+            _ => "#}",
+        };
+
+        let end = match rest[start + 2..].find(close) {
+            Some(end) => start + 2 + end,
+            None => return Err(Error::new(format!("unclosed `{}` on line {}", open, line))),
+        };
+
+        let content = rest[start + 2..end].trim().to_owned();
+        let tag_line = line;
+        line += rest[start..end].matches('\n').count();
+        rest = &rest[end + 2..];
+
+        if open != "{{" {
+            let line_start = text.rfind('\n').map(|n| n + 1);
+            let before = &text[line_start.unwrap_or(0)..];
+            let after = &rest[..rest.find('\n').unwrap_or(rest.len())];
+
+            let standalone = (line_start.is_some() || at_line_start)
+                && before.chars().all(|c| c == ' ' || c == '\t')
+                && after.trim().is_empty();
+
+            if standalone {
+                text.truncate(line_start.unwrap_or(0));
+
+                rest = match rest.find('\n') {
+                    Some(n) => {
+                        line += 1;
+                        &rest[n + 1..]
+                    }
+                    None => "",
+                };
+            }
+
+            at_line_start = standalone;
+        } else {
+            at_line_start = false;
+        }
+
+        if !text.is_empty() {
+            tokens.push(Token::Text(std::mem::take(&mut text)));
+        }
+
+        match open {
+            "{{" => tokens.push(Token::Expr(content, tag_line)),
+            "{%" => tokens.push(Token::Block(content, tag_line)),
+            _ => {}
+        }
+    }
+
+    if !text.is_empty() {
+        tokens.push(Token::Text(text));
+    }
+
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Text(String),
+    Expr {
+        path: Path,
+        filters: Vec<Filter>,
+    },
+    If {
+        branches: Vec<(Cond, Vec<Node>)>,
+        fallback: Vec<Node>,
+    },
+    For {
+        key: Option<String>,
+        value: String,
+        path: Path,
+        body: Vec<Node>,
+        fallback: Vec<Node>,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Filter {
+    Escape,
+    Upper,
+    Lower,
+    Trim,
+}
+
+#[derive(Debug, Clone)]
+struct Cond {
+    negate: bool,
+    path: Path,
+}
+
+/// A variable followed by any number of fields or indexes.
+#[derive(Debug, Clone)]
+struct Path {
+    segments: Vec<String>,
+    line: usize,
+}
+
+impl Path {
+    fn parse(source: &str, line: usize) -> Result<Self, Error> {
+        let segments = source
+            .split('.')
+            .map(|segment| segment.trim().to_owned())
+            .collect::<Vec<_>>();
+
+        if segments.iter().any(|segment| !is_ident(segment)) {
+            return Err(Error::new(format!(
+                "invalid expression `{}` on line {}",
+                source, line
+            )));
+        }
+
+        Ok(Self { segments, line })
+    }
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.segments.join("."))
+    }
+}
+
+fn is_ident(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// The tag which ended a block.
+struct End {
+    keyword: String,
+    args: String,
+    line: usize,
+}
+
+/// Parse nodes until one of the given `ends` is encountered, which is returned
+/// if found.
+fn parse(
+    tokens: &mut std::vec::IntoIter<Token>,
+    ends: &[&str],
+) -> Result<(Vec<Node>, Option<End>), Error> {
+    let mut nodes = Vec::new();
+
+    while let Some(token) = tokens.next() {
+        let (content, line) = match token {
+            Token::Text(text) => {
+                nodes.push(Node::Text(text));
+                continue;
+            }
+            Token::Expr(content, line) => {
+                let mut parts = content.split('|');
+                let path = Path::parse(parts.next().unwrap_or_default(), line)?;
+                let mut filters = Vec::new();
+
+                for filter in parts {
+                    filters.push(match filter.trim() {
+                        "escape" => Filter::Escape,
+                        "upper" => Filter::Upper,
+                        "lower" => Filter::Lower,
+                        "trim" => Filter::Trim,
+                        filter => {
+                            return Err(Error::new(format!(
+                                "unknown filter `{}` on line {}",
+                                filter, line
+                            )))
+                        }
+                    });
+                }
+
+                nodes.push(Node::Expr { path, filters });
+                continue;
+            }
+            Token::Block(content, line) => (content, line),
+        };
+
+        let (keyword, args) = match content.split_once(char::is_whitespace) {
+            Some((keyword, args)) => (keyword.to_owned(), args.trim().to_owned()),
+            None => (content, String::new()),
+        };
+
+        if ends.contains(&keyword.as_str()) {
+            if matches!(keyword.as_str(), "else" | "endif" | "endfor") && !args.is_empty() {
+                return Err(Error::new(format!(
+                    "unexpected `{} {}` on line {}",
+                    keyword, args, line
+                )));
+            }
+
+            return Ok((
+                nodes,
+                Some(End {
+                    keyword,
+                    args,
+                    line,
+                }),
+            ));
+        }
+
+        match keyword.as_str() {
+            "if" => {
+                let mut branches = Vec::new();
+                let mut cond = Cond::parse(&args, line)?;
+                let mut fallback = Vec::new();
+
+                loop {
+                    let (body, end) = parse(tokens, &["elif", "else", "endif"])?;
+                    branches.push((cond, body));
+
+                    let end = match end {
+                        Some(end) => end,
+                        None => return Err(unclosed("if", line)),
+                    };
+
+                    match end.keyword.as_str() {
+                        "elif" => {
+                            cond = Cond::parse(&end.args, end.line)?;
+                        }
+                        "else" => {
+                            let (body, end) = parse(tokens, &["endif"])?;
+
+                            if end.is_none() {
+                                return Err(unclosed("if", line));
+                            }
+
+                            fallback = body;
+                            break;
+                        }
+                        _ => break,
+                    }
+                }
+
+                nodes.push(Node::If { branches, fallback });
+            }
+            "for" => {
+                let (vars, iterable) = match args.split_once(" in ") {
+                    Some((vars, iterable)) => (vars, iterable),
+                    None => {
+                        return Err(Error::new(format!(
+                            "expected `for <name> in <path>` on line {}",
+                            line
+                        )))
+                    }
+                };
+
+                let vars = vars.split(',').map(str::trim).collect::<Vec<_>>();
+
+                let (key, value) = match vars.as_slice() {
+                    [value] if is_ident(value) => (None, value.to_string()),
+                    [key, value] if is_ident(key) && is_ident(value) => {
+                        (Some(key.to_string()), value.to_string())
+                    }
+                    _ => {
+                        return Err(Error::new(format!(
+                            "invalid loop variables `{}` on line {}",
+                            vars.join(", "),
+                            line
+                        )))
+                    }
+                };
+
+                let path = Path::parse(iterable, line)?;
+                let (body, end) = parse(tokens, &["else", "endfor"])?;
+
+                let fallback = match end {
+                    Some(end) if end.keyword == "else" => match parse(tokens, &["endfor"])? {
+                        (fallback, Some(..)) => fallback,
+                        (_, None) => return Err(unclosed("for", line)),
+                    },
+                    Some(..) => Vec::new(),
+                    None => return Err(unclosed("for", line)),
+                };
+
+                nodes.push(Node::For {
+                    key,
+                    value,
+                    path,
+                    body,
+                    fallback,
+                });
+            }
+            _ => {
+                let tag = if args.is_empty() {
+                    keyword
+                } else {
+                    format!("{} {}", keyword, args)
+                };
+
+                return Err(Error::new(format!("unexpected `{}` on line {}", tag, line)));
+            }
+        }
+    }
+
+    Ok((nodes, None))
+}
+
+fn unclosed(keyword: &str, line: usize) -> Error {
+    Error::new(format!("unclosed `{}` on line {}", keyword, line))
+}
+
+impl Cond {
+    fn parse(source: &str, line: usize) -> Result<Self, Error> {
+        match source.strip_prefix("not ") {
+            Some(path) => Ok(Self {
+                negate: true,
+                path: Path::parse(path, line)?,
+            }),
+            None => Ok(Self {
+                negate: false,
+                path: Path::parse(source, line)?,
+            }),
+        }
+    }
+}
+
+struct Renderer {
+    root: Value,
+    /// Loop variables, innermost last.
+    scopes: Vec<(String, Value)>,
+    out: String,
+}
+
+impl Renderer {
+    fn nodes(&mut self, nodes: &[Node]) -> Result<Result<(), Error>, VmError> {
+        for node in nodes {
+            if let Err(error) = self.node(node)? {
+                return Ok(Err(error));
+            }
+        }
+
+        Ok(Ok(()))
+    }
+
+    fn node(&mut self, node: &Node) -> Result<Result<(), Error>, VmError> {
+        match node {
+            Node::Text(text) => {
+                self.out.push_str(text);
+            }
+            Node::Expr { path, filters } => {
+                let value = match self.lookup(path)? {
+                    Ok(value) => value,
+                    Err(error) => return Ok(Err(error)),
+                };
+
+                let mut string = match display(value)? {
+                    Some(string) => string,
+                    None => {
+                        return Ok(Err(Error::new(format!(
+                            "`{}` can't be rendered on line {}",
+                            path, path.line
+                        ))))
+                    }
+                };
+
+                for filter in filters {
+                    string = match filter {
+                        Filter::Escape => escape(&string),
+                        Filter::Upper => string.to_uppercase(),
+                        Filter::Lower => string.to_lowercase(),
+                        Filter::Trim => string.trim().to_owned(),
+                    };
+                }
+
+                self.out.push_str(&string);
+            }
+            Node::If { branches, fallback } => {
+                for (cond, body) in branches {
+                    let value = match self.lookup(&cond.path)? {
+                        Ok(value) => value,
+                        Err(error) => return Ok(Err(error)),
+                    };
+
+                    if truthy(&value)? != cond.negate {
+                        return self.nodes(body);
+                    }
+                }
+
+                return self.nodes(fallback);
+            }
+            Node::For {
+                key,
+                value,
+                path,
+                body,
+                fallback,
+            } => {
+                let iterable = match self.lookup(path)? {
+                    Ok(iterable) => iterable,
+                    Err(error) => return Ok(Err(error)),
+                };
+
+                let items = match items(iterable, key.is_some())? {
+                    Some(items) => items,
+                    None => {
+                        return Ok(Err(Error::new(format!(
+                            "`{}` can't be looped over on line {}",
+                            path, path.line
+                        ))))
+                    }
+                };
+
+                if items.is_empty() {
+                    return self.nodes(fallback);
+                }
+
+                let len = items.len();
+
+                for (index, (k, v)) in items.into_iter().enumerate() {
+                    let mut state = Object::new();
+                    state.insert(String::from("index"), Value::from(index as i64));
+                    state.insert(String::from("first"), Value::from(index == 0));
+                    state.insert(String::from("last"), Value::from(index + 1 == len));
+
+                    let depth = self.scopes.len();
+                    self.scopes.push((String::from("loop"), Value::from(state)));
+
+                    if let Some(key) = key {
+                        self.scopes.push((key.clone(), k));
+                    }
+
+                    self.scopes.push((value.clone(), v));
+                    let result = self.nodes(body)?;
+                    self.scopes.truncate(depth);
+
+                    if let Err(error) = result {
+                        return Ok(Err(error));
+                    }
+                }
+            }
+        }
+
+        Ok(Ok(()))
+    }
+
+    /// Look up the value of a path.
+    fn lookup(&self, path: &Path) -> Result<Result<Value, Error>, VmError> {
+        let mut segments = path.segments.iter();
+        let first = segments.next().map(String::as_str).unwrap_or_default();
+
+        let scope = self
+            .scopes
+            .iter()
+            .rev()
+            .find(|(name, _)| name == first)
+            .map(|(_, value)| value.clone());
+
+        let mut value = match scope {
+            Some(value) => value,
+            None => match field(&self.root, first)? {
+                Some(value) => value,
+                None => {
+                    return Ok(Err(Error::new(format!(
+                        "undefined variable `{}` on line {}",
+                        first, path.line
+                    ))))
+                }
+            },
+        };
+
+        for segment in segments {
+            value = match field(&value, segment)? {
+                Some(value) => value,
+                None => {
+                    return Ok(Err(Error::new(format!(
+                        "`{}` is undefined on line {}",
+                        path, path.line
+                    ))))
+                }
+            };
+        }
+
+        Ok(Ok(value))
+    }
+}
+
+/// Get a field of an object or struct, or an index of a vector or tuple.
+fn field(value: &Value, name: &str) -> Result<Option<Value>, VmError> {
+    let index = || name.parse::<usize>().ok();
+
+    Ok(match value {
+        Value::Object(object) => object.borrow_ref()?.get(name).cloned(),
+        Value::Struct(st) => st.borrow_ref()?.data().get(name).cloned(),
+        Value::Vec(vec) => index().and_then(|n| vec.borrow_ref().ok()?.get(n).cloned()),
+        Value::Tuple(tuple) => index().and_then(|n| tuple.borrow_ref().ok()?.get(n).cloned()),
+        Value::TupleStruct(st) => {
+            index().and_then(|n| st.borrow_ref().ok()?.data().get(n).cloned())
+        }
+        _ => None,
+    })
+}
+
+/// Convert a value into the string it's rendered as, if it can be rendered.
+fn display(value: Value) -> Result<Option<String>, VmError> {
+    Ok(Some(match value {
+        Value::String(string) => string.borrow_ref()?.clone(),
+        Value::StaticString(string) => string.as_str().to_owned(),
+        Value::Integer(integer) => integer.to_string(),
+        Value::Float(float) => float.to_string(),
+        Value::Bool(boolean) => boolean.to_string(),
+        Value::Char(c) => c.to_string(),
+        Value::Byte(byte) => byte.to_string(),
+        Value::Unit => String::new(),
+        Value::Option(option) => match option.borrow_ref()?.clone() {
+            Some(value) => return display(value),
+            None => String::new(),
+        },
+        _ => return Ok(None),
+    }))
+}
+
+/// Test if a value is considered true by a condition.
+fn truthy(value: &Value) -> Result<bool, VmError> {
+    Ok(match value {
+        Value::Unit => false,
+        Value::Bool(boolean) => *boolean,
+        Value::Integer(integer) => *integer != 0,
+        Value::Float(float) => *float != 0.0,
+        Value::String(string) => !string.borrow_ref()?.is_empty(),
+        Value::StaticString(string) => !string.as_str().is_empty(),
+        Value::Vec(vec) => !vec.borrow_ref()?.is_empty(),
+        Value::Tuple(tuple) => !tuple.borrow_ref()?.is_empty(),
+        Value::Object(object) => !object.borrow_ref()?.is_empty(),
+        Value::Option(option) => option.borrow_ref()?.is_some(),
+        _ => true,
+    })
+}
+
+/// Collect the items to loop over, as pairs of keys and values.
+fn items(value: Value, keyed: bool) -> Result<Option<Vec<(Value, Value)>>, VmError> {
+    let indexed = |values: &[Value]| {
+        values
+            .iter()
+            .enumerate()
+            .map(|(n, value)| (Value::from(n as i64), value.clone()))
+            .collect()
+    };
+
+    Ok(Some(match value {
+        Value::Vec(vec) => indexed(&vec.borrow_ref()?),
+        Value::Tuple(tuple) => indexed(&tuple.borrow_ref()?),
+        Value::Object(object) if keyed => object
+            .borrow_ref()?
+            .iter()
+            .map(|(key, value)| (Value::from(key.clone()), value.clone()))
+            .collect(),
+        _ => return Ok(None),
+    }))
+}
+
+/// Escape a string for use in HTML.
+fn escape(string: &str) -> String {
+    let mut out = String::with_capacity(string.len());
+
+    for c in string.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+/// An error raised when a template is invalid, or when it can't be rendered
+/// with the given data.
+#[derive(Debug, Clone, Any)]
+struct Error {
+    message: String,
+}
+
+impl Error {
+    fn new(message: String) -> Self {
+        Self { message }
+    }
+
+    fn display(&self, buf: &mut String) -> fmt::Result {
+        write!(buf, "{}", self.message)
+    }
+}
@@ -23,39 +23,594 @@
 //! Use it in Rune:
 //!
 //! ```rust,ignore
-//! use time;
+//! use time::{DateTime, Duration, Instant};
 //!
-//! fn main() {
-//!     time::sleep(time::Duration::from_secs(10)).await;
-//!     println("Message after 10 seconds!");
+//! async fn main() {
+//!     let start = Instant::now();
+//!     time::sleep(Duration::from_millis(1500)).await;
+//!     println(`Slept for ${start.elapsed()}`);
+//!
+//!     let deadline = time::now() + Duration::from_secs(3600);
+//!     println(`Deadline at ${deadline.format("%Y-%m-%d %H:%M")?}`);
+//!
+//!     let date = DateTime::parse_rfc3339("2021-11-25T12:00:00+01:00")?;
+//!     println(`Released on a weekday ${date.weekday()}, ${date.to_utc()}`);
 //! }
 //! ```
+//!
+//! Sleeping and intervals are driven by the executor running the virtual
+//! machine, so they don't block its thread:
+//!
+//! ```rust,ignore
+//! async fn main() {
+//!     let interval = time::interval(time::Duration::from_secs(1));
+//!
+//!     loop {
+//!         interval.tick().await;
+//!         println("Tick!");
+//!     }
+//! }
+//! ```
+//!
+//! A future can be given a deadline with `time::timeout`, which produces
+//! `Err` with an `Elapsed` error if it doesn't complete in time. It lives here
+//! next to `sleep` rather than in `std::future` with `join_all` and `race`,
+//! since it needs the timer that this module is built on:
+//!
+//! ```rust,ignore
+//! async fn main() {
+//!     match time::timeout(fetch(), time::Duration::from_secs(5)).await {
+//!         Ok(response) => println(`Got ${response}`),
+//!         Err(error) => println(`Gave up: ${error}`),
+//!     }
+//! }
+//! ```
+//!
+//! Date-times are displayed in RFC 3339 format, and can be parsed and
+//! formatted with strftime-like patterns which support `%Y`, `%y`, `%m`, `%d`,
+//! `%e`, `%H`, `%I`, `%M`, `%S`, `%f`, `%p`, `%j`, `%a`, `%A`, `%b`, `%B`,
+//! `%z`, `%F`, `%T`, `%D` and `%%`. Durations and date-times can be compared
+//! with `==` and `cmp` and sorted, and date-times support adding and
+//! subtracting durations.
+//!
+//! If the virtual machine is [deterministic], time is read from its virtual
+//! clock instead of the system: `time::now` counts from the unix epoch,
+//! instants measure virtual time, and sleeping and waiting for the next tick
+//! of an interval advance the virtual clock and complete immediately. A
+//! timeout likewise elapses immediately unless its future completes the
+//! first time it's polled.
+//!
+//! [deterministic]: rune::runtime::Determinism
 
+use ::time::format_description::well_known::Rfc3339;
+use ::time::{Date, OffsetDateTime, PrimitiveDateTime, UtcOffset};
+use rune::runtime::{Capability, Determinism, Protocol, Shared, Value, VmError};
 use rune::{Any, ContextError, Module};
+use std::cmp::Ordering;
+use std::fmt::{self, Write as _};
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::Mutex;
 
 /// Construct the `time` module.
 pub fn module(_stdio: bool) -> Result<Module, ContextError> {
     let mut module = Module::with_crate("time");
+    module.require(Capability::Time);
+
+    module.ty::<Duration>()?;
     module.function(&["Duration", "from_secs"], Duration::from_secs)?;
+    module.function(&["Duration", "from_millis"], Duration::from_millis)?;
+    module.function(&["Duration", "from_secs_f64"], Duration::from_secs_f64)?;
+    module.inst_fn("as_secs", Duration::as_secs)?;
+    module.inst_fn("as_millis", Duration::as_millis)?;
+    module.inst_fn("as_secs_f64", Duration::as_secs_f64)?;
+    module.inst_fn(Protocol::ADD, Duration::add)?;
+    module.inst_fn(Protocol::SUB, Duration::sub)?;
+    module.inst_fn(Protocol::EQ, Duration::eq)?;
+    module.inst_fn(Protocol::CMP, Duration::cmp)?;
+    module.inst_fn(Protocol::STRING_DISPLAY, Duration::display)?;
+
+    module.ty::<Instant>()?;
+    module.function(&["Instant", "now"], Instant::now)?;
+    module.inst_fn("elapsed", Instant::elapsed)?;
+    module.inst_fn("duration_since", Instant::duration_since)?;
+
+    module.ty::<DateTime>()?;
+    module.function(&["now"], DateTime::now)?;
+    module.function(&["DateTime", "now"], DateTime::now)?;
+    module.function(&["DateTime", "from_unix"], DateTime::from_unix)?;
+    module.function(&["DateTime", "parse_rfc3339"], DateTime::parse_rfc3339)?;
+    module.function(&["DateTime", "parse"], DateTime::parse)?;
+    module.inst_fn("to_rfc3339", DateTime::to_rfc3339)?;
+    module.inst_fn("format", DateTime::format)?;
+    module.inst_fn("unix_timestamp", DateTime::unix_timestamp)?;
+    module.inst_fn("to_utc", DateTime::to_utc)?;
+    module.inst_fn("year", DateTime::year)?;
+    module.inst_fn("month", DateTime::month)?;
+    module.inst_fn("day", DateTime::day)?;
+    module.inst_fn("hour", DateTime::hour)?;
+    module.inst_fn("minute", DateTime::minute)?;
+    module.inst_fn("second", DateTime::second)?;
+    module.inst_fn("nanosecond", DateTime::nanosecond)?;
+    module.inst_fn("weekday", DateTime::weekday)?;
+    module.inst_fn("duration_since", DateTime::duration_since)?;
+    module.inst_fn(Protocol::ADD, DateTime::add)?;
+    module.inst_fn(Protocol::SUB, DateTime::sub)?;
+    module.inst_fn(Protocol::EQ, DateTime::eq)?;
+    module.inst_fn(Protocol::CMP, DateTime::cmp)?;
+    module.inst_fn(Protocol::STRING_DISPLAY, DateTime::display)?;
+
+    module.ty::<Interval>()?;
+    module.function(&["interval"], Interval::new)?;
+    module.async_inst_fn("tick", Interval::tick)?;
+    module.inst_fn("period", Interval::period)?;
+
+    module.ty::<Elapsed>()?;
+    module.inst_fn(Protocol::STRING_DISPLAY, Elapsed::display)?;
+
     module.async_function(&["sleep"], sleep)?;
+    module.async_function(&["timeout"], timeout)?;
     Ok(module)
 }
 
+/// The time of the virtual clock, if the virtual machine is deterministic.
+fn virtual_now() -> Option<std::time::Duration> {
+    Determinism::current().map(|determinism| determinism.now())
+}
+
+/// A span of time.
 #[derive(Debug, Clone, Copy, Any)]
 struct Duration {
-    inner: tokio::time::Duration,
+    inner: std::time::Duration,
 }
 
 impl Duration {
     /// Construct a duration from seconds.
     fn from_secs(secs: u64) -> Self {
         Self {
-            inner: tokio::time::Duration::from_secs(secs),
+            inner: std::time::Duration::from_secs(secs),
+        }
+    }
+
+    /// Construct a duration from milliseconds.
+    fn from_millis(millis: u64) -> Self {
+        Self {
+            inner: std::time::Duration::from_millis(millis),
+        }
+    }
+
+    /// Construct a duration from fractional seconds.
+    fn from_secs_f64(secs: f64) -> Result<Self, VmError> {
+        let inner = std::time::Duration::try_from_secs_f64(secs)
+            .map_err(|_| VmError::panic(format!("invalid duration of {} seconds", secs)))?;
+
+        Ok(Self { inner })
+    }
+
+    fn as_secs(&self) -> u64 {
+        self.inner.as_secs()
+    }
+
+    fn as_millis(&self) -> u64 {
+        u64::try_from(self.inner.as_millis()).unwrap_or(u64::MAX)
+    }
+
+    fn as_secs_f64(&self) -> f64 {
+        self.inner.as_secs_f64()
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        Self {
+            inner: self.inner.saturating_add(other.inner),
+        }
+    }
+
+    /// Subtract a duration, saturating at zero.
+    fn sub(&self, other: &Self) -> Self {
+        Self {
+            inner: self.inner.saturating_sub(other.inner),
+        }
+    }
+
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.inner.cmp(&other.inner)
+    }
+
+    fn display(&self, buf: &mut String) -> fmt::Result {
+        write!(buf, "{:?}", self.inner)
+    }
+}
+
+/// A point in time used for measuring elapsed time.
+#[derive(Debug, Clone, Copy, Any)]
+struct Instant {
+    inner: Clock,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Clock {
+    System(std::time::Instant),
+    /// A point in time on the virtual clock of a deterministic virtual
+    /// machine.
+    Virtual(std::time::Duration),
+}
+
+impl Instant {
+    fn now() -> Self {
+        let inner = match virtual_now() {
+            Some(now) => Clock::Virtual(now),
+            None => Clock::System(std::time::Instant::now()),
+        };
+
+        Self { inner }
+    }
+
+    /// The time elapsed since the instant was created.
+    fn elapsed(&self) -> Duration {
+        Self::now().duration_since(self)
+    }
+
+    /// The time elapsed from another instant to this one, saturating at zero
+    /// if the other instant is later.
+    fn duration_since(&self, earlier: &Self) -> Duration {
+        let inner = match (self.inner, earlier.inner) {
+            (Clock::System(a), Clock::System(b)) => a.saturating_duration_since(b),
+            (Clock::Virtual(a), Clock::Virtual(b)) => a.saturating_sub(b),
+            // Instants from different clocks can't be compared.
+            _ => std::time::Duration::ZERO,
+        };
+
+        Duration { inner }
+    }
+}
+
+/// A date and time with an offset from UTC.
+#[derive(Debug, Clone, Any)]
+struct DateTime {
+    inner: OffsetDateTime,
+}
+
+impl DateTime {
+    /// The current date and time in UTC.
+    fn now() -> Result<Self, VmError> {
+        let inner = match virtual_now() {
+            Some(now) => checked_add(OffsetDateTime::UNIX_EPOCH, now)?,
+            None => OffsetDateTime::now_utc(),
+        };
+
+        Ok(Self { inner })
+    }
+
+    /// Construct a date-time in UTC from seconds since the unix epoch.
+    fn from_unix(timestamp: i64) -> rune::Result<Self> {
+        Ok(Self {
+            inner: OffsetDateTime::from_unix_timestamp(timestamp)?,
+        })
+    }
+
+    fn parse_rfc3339(input: &str) -> rune::Result<Self> {
+        Ok(Self {
+            inner: OffsetDateTime::parse(input, &Rfc3339)?,
+        })
+    }
+
+    /// Parse a date-time using a strftime-like pattern.
+    ///
+    /// Date-times without an offset are assumed to be in UTC, and dates
+    /// without a time are at midnight.
+    fn parse(input: &str, pattern: &str) -> rune::Result<Self> {
+        let description = format_description(pattern)?;
+        let items = ::time::format_description::parse_borrowed::<1>(&description)?;
+
+        let error = match OffsetDateTime::parse(input, &items) {
+            Ok(inner) => return Ok(Self { inner }),
+            Err(error) => error,
+        };
+
+        let inner = if let Ok(date_time) = PrimitiveDateTime::parse(input, &items) {
+            date_time.assume_utc()
+        } else if let Ok(date) = Date::parse(input, &items) {
+            date.midnight().assume_utc()
+        } else {
+            return Err(error.into());
+        };
+
+        Ok(Self { inner })
+    }
+
+    fn to_rfc3339(&self) -> rune::Result<String> {
+        Ok(self.inner.format(&Rfc3339)?)
+    }
+
+    /// Format the date-time using a strftime-like pattern.
+    fn format(&self, pattern: &str) -> rune::Result<String> {
+        let description = format_description(pattern)?;
+        let items = ::time::format_description::parse_borrowed::<1>(&description)?;
+        Ok(self.inner.format(&items)?)
+    }
+
+    fn unix_timestamp(&self) -> i64 {
+        self.inner.unix_timestamp()
+    }
+
+    /// Convert the date-time to UTC.
+    fn to_utc(&self) -> Self {
+        Self {
+            inner: self.inner.to_offset(UtcOffset::UTC),
         }
     }
+
+    fn year(&self) -> i64 {
+        i64::from(self.inner.year())
+    }
+
+    fn month(&self) -> i64 {
+        i64::from(u8::from(self.inner.month()))
+    }
+
+    fn day(&self) -> i64 {
+        i64::from(self.inner.day())
+    }
+
+    fn hour(&self) -> i64 {
+        i64::from(self.inner.hour())
+    }
+
+    fn minute(&self) -> i64 {
+        i64::from(self.inner.minute())
+    }
+
+    fn second(&self) -> i64 {
+        i64::from(self.inner.second())
+    }
+
+    fn nanosecond(&self) -> i64 {
+        i64::from(self.inner.nanosecond())
+    }
+
+    /// The day of the week, from 1 for Monday to 7 for Sunday.
+    fn weekday(&self) -> i64 {
+        i64::from(self.inner.weekday().number_from_monday())
+    }
+
+    /// The time elapsed from another date-time to this one, saturating at zero
+    /// if the other date-time is later.
+    fn duration_since(&self, earlier: &Self) -> Duration {
+        let inner = std::time::Duration::try_from(self.inner - earlier.inner).unwrap_or_default();
+        Duration { inner }
+    }
+
+    fn add(&self, duration: &Duration) -> Result<Self, VmError> {
+        Ok(Self {
+            inner: checked_add(self.inner, duration.inner)?,
+        })
+    }
+
+    fn sub(&self, duration: &Duration) -> Result<Self, VmError> {
+        let inner = ::time::Duration::try_from(duration.inner)
+            .ok()
+            .and_then(|duration| self.inner.checked_sub(duration))
+            .ok_or_else(|| VmError::panic("date-time out of range"))?;
+
+        Ok(Self { inner })
+    }
+
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.inner.cmp(&other.inner)
+    }
+
+    fn display(&self, buf: &mut String) -> fmt::Result {
+        let string = self.inner.format(&Rfc3339).map_err(|_| fmt::Error)?;
+        buf.push_str(&string);
+        Ok(())
+    }
+}
+
+fn checked_add(
+    date_time: OffsetDateTime,
+    duration: std::time::Duration,
+) -> Result<OffsetDateTime, VmError> {
+    ::time::Duration::try_from(duration)
+        .ok()
+        .and_then(|duration| date_time.checked_add(duration))
+        .ok_or_else(|| VmError::panic("date-time out of range"))
 }
 
-/// Convert any value to a json string.
-async fn sleep(duration: &Duration) {
-    tokio::time::sleep(duration.inner).await;
+/// Translate a strftime-like pattern into a format description.
+fn format_description(pattern: &str) -> rune::Result<String> {
+    let mut out = String::new();
+    let mut chars = pattern.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '%' => {}
+            '[' => {
+                out.push_str("[[");
+                continue;
+            }
+            c => {
+                out.push(c);
+                continue;
+            }
+        }
+
+        let component = match chars.next() {
+            Some('Y') => "[year]",
+            Some('y') => "[year repr:last_two]",
+            Some('m') => "[month]",
+            Some('d') => "[day]",
+            Some('e') => "[day padding:space]",
+            Some('H') => "[hour]",
+            Some('I') => "[hour repr:12]",
+            Some('M') => "[minute]",
+            Some('S') => "[second]",
+            Some('f') => "[subsecond]",
+            Some('p') => "[period]",
+            Some('j') => "[ordinal]",
+            Some('a') => "[weekday repr:short]",
+            Some('A') => "[weekday]",
+            Some('b') => "[month repr:short]",
+            Some('B') => "[month repr:long]",
+            Some('z') => "[offset_hour sign:mandatory][offset_minute]",
+            Some('F') => "[year]-[month]-[day]",
+            Some('T') => "[hour]:[minute]:[second]",
+            Some('D') => "[month]/[day]/[year repr:last_two]",
+            Some('%') => "%",
+            Some(c) => {
+                return Err(rune::Error::msg(format!(
+                    "unsupported specifier `%{}` in pattern",
+                    c
+                )))
+            }
+            None => return Err(rune::Error::msg("incomplete specifier at end of pattern")),
+        };
+
+        out.push_str(component);
+    }
+
+    Ok(out)
+}
+
+/// A timer which ticks periodically.
+#[derive(Debug, Clone, Any)]
+struct Interval {
+    period: std::time::Duration,
+    /// The deterministic environment the interval was created in.
+    determinism: Option<Determinism>,
+    state: Arc<Mutex<IntervalState>>,
+}
+
+#[derive(Debug, Default)]
+struct IntervalState {
+    /// The timer, which is created on the first tick.
+    system: Option<tokio::time::Interval>,
+    /// The time of the next tick on the virtual clock, if the interval has
+    /// ticked on it.
+    next: Option<std::time::Duration>,
+}
+
+impl Interval {
+    /// Construct an interval which first ticks immediately, and then once
+    /// every period.
+    fn new(period: &Duration) -> Result<Self, VmError> {
+        if period.inner.is_zero() {
+            return Err(VmError::panic("interval period must be non-zero"));
+        }
+
+        Ok(Self {
+            period: period.inner,
+            determinism: Determinism::current(),
+            state: Arc::new(Mutex::new(IntervalState::default())),
+        })
+    }
+
+    /// Wait for the next tick, returning the instant it was scheduled for.
+    ///
+    /// Ticks which were missed because the script was busy complete
+    /// immediately, so that the interval catches up.
+    fn tick(&self) -> impl Future<Output = Instant> {
+        let this = self.clone();
+
+        async move {
+            let mut state = this.state.lock().await;
+
+            if let Some(determinism) = &this.determinism {
+                let now = determinism.now();
+
+                let next = state.next.unwrap_or(now);
+
+                if next > now {
+                    determinism.advance(next - now);
+                }
+
+                state.next = Some(next.saturating_add(this.period));
+
+                return Instant {
+                    inner: Clock::Virtual(next),
+                };
+            }
+
+            // NB: the timer has to be created lazily, since it can only be
+            // created while a runtime is available.
+            let period = this.period;
+            let interval = state
+                .system
+                .get_or_insert_with(|| tokio::time::interval(period));
+
+            Instant {
+                inner: Clock::System(interval.tick().await.into_std()),
+            }
+        }
+    }
+
+    fn period(&self) -> Duration {
+        Duration { inner: self.period }
+    }
+}
+
+/// Sleep for the given duration.
+fn sleep(duration: &Duration) -> impl Future<Output = ()> {
+    // NB: the virtual machine is only accessible while the function is being
+    // called, not once the future is polled.
+    let determinism = Determinism::current();
+    let duration = duration.inner;
+
+    async move {
+        match determinism {
+            Some(determinism) => determinism.advance(duration),
+            None => tokio::time::sleep(duration).await,
+        }
+    }
+}
+
+/// The error produced when a timeout elapses.
+#[derive(Debug, Clone, Copy, Any)]
+struct Elapsed;
+
+impl Elapsed {
+    fn display(&self, s: &mut String) -> fmt::Result {
+        write!(s, "deadline has elapsed")
+    }
+}
+
+/// Wait for a future to complete within the given duration.
+fn timeout(
+    future: Shared<rune::runtime::Future>,
+    duration: &Duration,
+) -> impl Future<Output = Result<Result<Value, Elapsed>, VmError>> {
+    let determinism = Determinism::current();
+    let duration = duration.inner;
+
+    async move {
+        let mut future = future.into_mut()?;
+
+        match determinism {
+            Some(determinism) => {
+                let polled = std::future::poll_fn(|cx| {
+                    std::task::Poll::Ready(std::pin::Pin::new(&mut *future).poll(cx))
+                })
+                .await;
+
+                match polled {
+                    std::task::Poll::Ready(value) => Ok(Ok(value?)),
+                    std::task::Poll::Pending => {
+                        determinism.advance(duration);
+                        Ok(Err(Elapsed))
+                    }
+                }
+            }
+            None => match tokio::time::timeout(duration, &mut *future).await {
+                Ok(value) => Ok(Ok(value?)),
+                Err(..) => Ok(Err(Elapsed)),
+            },
+        }
+    }
 }
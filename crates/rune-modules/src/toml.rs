@@ -26,10 +26,19 @@
 //! use toml;
 //!
 //! fn main() {
-//!     let data = toml::from_string("[hello]\nworld = 42");
-//!     dbg(data);
+//!     let config = toml::from_string("[server]\nport = 8080")?;
+//!     config.server.port += 1;
+//!     config.name = "rune";
+//!
+//!     println(toml::to_string(config)?);
+//!     println(toml::to_string_pretty(config)?);
 //! }
 //! ```
+//!
+//! Values are converted the same way as by the `json` module, except that the
+//! document being serialized has to be an object, and that TOML has no
+//! representation for unit. Values in an object are emitted before nested
+//! objects, since TOML requires tables to come last.
 
 use rune::{ContextError, Module};
 use rune::runtime::{Bytes, Value};
@@ -40,6 +49,7 @@ pub fn module(_stdio: bool) -> Result<Module, ContextError> {
     module.function(&["from_bytes"], from_bytes)?;
     module.function(&["from_string"], from_string)?;
     module.function(&["to_string"], to_string)?;
+    module.function(&["to_string_pretty"], to_string_pretty)?;
     module.function(&["to_bytes"], to_bytes)?;
     Ok(module)
 }
@@ -53,13 +63,32 @@ fn from_string(string: &str) -> rune::Result<Value> {
     Ok(toml::from_str(string)?)
 }
 
+/// Convert a value into a toml document.
+///
+/// This goes through [toml::Value], which takes care of emitting tables after
+/// all other values regardless of the order of the fields of an object.
+fn to_document(value: Value) -> rune::Result<toml::Value> {
+    let document = toml::Value::try_from(&value)?;
+
+    if !document.is_table() {
+        return Err(rune::Error::msg("a toml document must be an object"));
+    }
+
+    Ok(document)
+}
+
 /// Convert any value to a toml string.
 fn to_string(value: Value) -> rune::Result<String> {
-    Ok(toml::to_string(&value)?)
+    Ok(toml::to_string(&to_document(value)?)?)
+}
+
+/// Convert any value to an indented toml string.
+fn to_string_pretty(value: Value) -> rune::Result<String> {
+    Ok(toml::to_string_pretty(&to_document(value)?)?)
 }
 
 /// Convert any value to toml bytes.
 fn to_bytes(value: Value) -> rune::Result<Bytes> {
-    let bytes = toml::to_vec(&value)?;
+    let bytes = toml::to_vec(&to_document(value)?)?;
     Ok(Bytes::from_vec(bytes))
 }
@@ -0,0 +1,487 @@
+//! The native `websocket` module for the [Rune Language].
+//!
+//! [Rune Language]: https://rune-rs.github.io
+//!
+//! ## Usage
+//!
+//! Add the following to your `Cargo.toml`:
+//!
+//! ```toml
+//! rune-modules = { version = "0.11.0", features = ["websocket"] }
+//! ```
+//!
+//! Install it into your context:
+//!
+//! ```rust
+//! # fn main() -> rune::Result<()> {
+//! let mut context = rune::Context::with_default_modules()?;
+//! context.install(&rune_modules::websocket::module(true)?)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Use it in Rune:
+//!
+//! ```rust,ignore
+//! async fn main() {
+//!     let socket = websocket::connect("wss://echo.websocket.events").await?;
+//!     socket.send("hello").await?;
+//!
+//!     while let Some(message) = socket.recv().await? {
+//!         dbg(message);
+//!     }
+//!
+//!     socket.close().await?;
+//! }
+//! ```
+//!
+//! Strings are sent as text messages and bytes as binary messages, and
+//! received messages are returned the same way. `recv` returns `None` once the
+//! connection has been closed, so the messages of a connection can be consumed
+//! as a stream with a `while let` loop. Pings are answered automatically.
+//!
+//! Both `ws://` and `wss://` urls are supported.
+//!
+//! The module requires the [Network][rune::runtime::Capability::Network]
+//! capability.
+
+use base64::Engine as _;
+use ring::rand::SecureRandom as _;
+use rune::runtime::{Bytes, Capability, Value, VmError};
+use rune::{Any, ContextError, Module};
+use std::future::Future;
+use std::io;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt as _, AsyncRead, AsyncReadExt as _, AsyncWrite, AsyncWriteExt as _};
+use tokio::io::{BufReader, ReadHalf, WriteHalf};
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+
+/// Construct the `websocket` module.
+pub fn module(_stdio: bool) -> Result<Module, ContextError> {
+    let mut module = Module::with_crate("websocket");
+    module.require(Capability::Network);
+
+    module.ty::<WebSocket>()?;
+    module.async_function(&["connect"], connect)?;
+    module.async_inst_fn("send", WebSocket::send)?;
+    module.async_inst_fn("recv", WebSocket::recv)?;
+    module.async_inst_fn("close", WebSocket::close)?;
+    Ok(module)
+}
+
+/// The GUID used to derive the accept key of the handshake.
+const GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// The largest message which will be received.
+const MAX_MESSAGE_SIZE: u64 = 64 << 20;
+
+const OP_CONTINUATION: u8 = 0x0;
+const OP_TEXT: u8 = 0x1;
+const OP_BINARY: u8 = 0x2;
+const OP_CLOSE: u8 = 0x8;
+const OP_PING: u8 = 0x9;
+const OP_PONG: u8 = 0xa;
+
+/// A connection which is either plain or encrypted.
+trait Io: AsyncRead + AsyncWrite + Send + Unpin {}
+
+impl<T> Io for T where T: AsyncRead + AsyncWrite + Send + Unpin {}
+
+type Stream = BufReader<Box<dyn Io>>;
+
+fn invalid_data<E>(error: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+fn random<const N: usize>() -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+
+    ring::rand::SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| io::Error::other("failed to generate random bytes"))?;
+
+    Ok(bytes)
+}
+
+/// Connect to the given `ws://` or `wss://` url.
+async fn connect(url: &str) -> io::Result<WebSocket> {
+    let url = url::Url::parse(url).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+    let tls = match url.scheme() {
+        "ws" => false,
+        "wss" => true,
+        scheme => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unsupported scheme `{}`", scheme),
+            ))
+        }
+    };
+
+    let host = url
+        .host_str()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "url is missing a host"))?;
+
+    let port = url.port_or_known_default().unwrap_or(if tls { 443 } else { 80 });
+    let tcp = TcpStream::connect((host, port)).await?;
+
+    let io: Box<dyn Io> = if tls {
+        Box::new(connect_tls(host, tcp).await?)
+    } else {
+        Box::new(tcp)
+    };
+
+    let mut stream = BufReader::new(io);
+    let key = base64::engine::general_purpose::STANDARD.encode(random::<16>()?);
+
+    let mut target = url.path().to_owned();
+
+    if let Some(query) = url.query() {
+        target.push('?');
+        target.push_str(query);
+    }
+
+    let host_header = match url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_owned(),
+    };
+
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Key: {}\r\nSec-WebSocket-Version: 13\r\n\r\n",
+        target, host_header, key
+    );
+
+    stream.write_all(request.as_bytes()).await?;
+    stream.flush().await?;
+    handshake(&mut stream, &key).await?;
+
+    let (reader, writer) = tokio::io::split(stream);
+
+    Ok(WebSocket {
+        inner: Arc::new(Inner {
+            reader: Mutex::new(reader),
+            writer: Mutex::new(Writer {
+                half: writer,
+                closed: false,
+            }),
+        }),
+    })
+}
+
+async fn connect_tls(host: &str, tcp: TcpStream) -> io::Result<impl Io> {
+    let mut roots = rustls::RootCertStore::empty();
+
+    roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|anchor| {
+        rustls::OwnedTrustAnchor::from_subject_spki_name_constraints(
+            anchor.subject,
+            anchor.spki,
+            anchor.name_constraints,
+        )
+    }));
+
+    let config = rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+
+    let name = rustls::ServerName::try_from(host)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+    let connector = tokio_rustls::TlsConnector::from(Arc::new(config));
+    connector.connect(name, tcp).await
+}
+
+/// Read the response to the opening handshake and verify that the server
+/// accepted the upgrade.
+async fn handshake(stream: &mut Stream, key: &str) -> io::Result<()> {
+    let mut line = String::new();
+    stream.read_line(&mut line).await?;
+
+    let status = line.split_whitespace().nth(1);
+
+    if status != Some("101") {
+        return Err(invalid_data(format!(
+            "server refused the upgrade: {}",
+            line.trim_end()
+        )));
+    }
+
+    let digest = ring::digest::digest(
+        &ring::digest::SHA1_FOR_LEGACY_USE_ONLY,
+        format!("{}{}", key, GUID).as_bytes(),
+    );
+
+    let expected = base64::engine::general_purpose::STANDARD.encode(digest.as_ref());
+    let mut accepted = false;
+
+    loop {
+        line.clear();
+
+        if stream.read_line(&mut line).await? == 0 {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+        }
+
+        let line = line.trim_end();
+
+        if line.is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("sec-websocket-accept") {
+                accepted = value.trim() == expected;
+            }
+        }
+    }
+
+    if !accepted {
+        return Err(invalid_data("server sent an invalid accept key"));
+    }
+
+    Ok(())
+}
+
+/// Coerce a string or bytes into the opcode and payload of a message.
+fn message_of(value: Value) -> Result<(u8, Vec<u8>), VmError> {
+    Ok(match value {
+        Value::String(string) => (OP_TEXT, string.borrow_ref()?.as_bytes().to_vec()),
+        Value::StaticString(string) => (OP_TEXT, string.as_bytes().to_vec()),
+        Value::Bytes(bytes) => (OP_BINARY, bytes.borrow_ref()?.to_vec()),
+        actual => return Err(VmError::expected::<Bytes>(actual.type_info()?)),
+    })
+}
+
+/// A single frame read from the server.
+struct Frame {
+    fin: bool,
+    opcode: u8,
+    payload: Vec<u8>,
+}
+
+async fn read_frame(reader: &mut ReadHalf<Stream>) -> io::Result<Frame> {
+    let mut head = [0; 2];
+    reader.read_exact(&mut head).await?;
+
+    let fin = head[0] & 0x80 != 0;
+    let opcode = head[0] & 0x0f;
+    let masked = head[1] & 0x80 != 0;
+
+    let len = match head[1] & 0x7f {
+        126 => u64::from(reader.read_u16().await?),
+        127 => reader.read_u64().await?,
+        len => u64::from(len),
+    };
+
+    if len > MAX_MESSAGE_SIZE {
+        return Err(invalid_data("frame is too large"));
+    }
+
+    let mut mask = [0; 4];
+
+    if masked {
+        reader.read_exact(&mut mask).await?;
+    }
+
+    let mut payload = vec![0; len as usize];
+    reader.read_exact(&mut payload).await?;
+
+    if masked {
+        apply_mask(&mut payload, mask);
+    }
+
+    Ok(Frame {
+        fin,
+        opcode,
+        payload,
+    })
+}
+
+fn apply_mask(payload: &mut [u8], mask: [u8; 4]) {
+    for (i, b) in payload.iter_mut().enumerate() {
+        *b ^= mask[i % 4];
+    }
+}
+
+/// The writing half of a connection.
+struct Writer {
+    half: WriteHalf<Stream>,
+    /// If a close frame has been sent.
+    closed: bool,
+}
+
+impl Writer {
+    /// Write a single, final frame. Frames sent by a client are always masked.
+    async fn write_frame(&mut self, opcode: u8, payload: &[u8]) -> io::Result<()> {
+        if self.closed {
+            return Err(io::Error::new(
+                io::ErrorKind::NotConnected,
+                "connection is closed",
+            ));
+        }
+
+        let mut frame = Vec::with_capacity(payload.len() + 14);
+        frame.push(0x80 | opcode);
+
+        match payload.len() {
+            len if len < 126 => frame.push(0x80 | len as u8),
+            len if len <= usize::from(u16::MAX) => {
+                frame.push(0x80 | 126);
+                frame.extend_from_slice(&(len as u16).to_be_bytes());
+            }
+            len => {
+                frame.push(0x80 | 127);
+                frame.extend_from_slice(&(len as u64).to_be_bytes());
+            }
+        }
+
+        let mask = random::<4>()?;
+        frame.extend_from_slice(&mask);
+
+        let start = frame.len();
+        frame.extend_from_slice(payload);
+        apply_mask(&mut frame[start..], mask);
+
+        self.half.write_all(&frame).await?;
+        self.half.flush().await?;
+
+        if opcode == OP_CLOSE {
+            self.closed = true;
+        }
+
+        Ok(())
+    }
+}
+
+struct Inner {
+    reader: Mutex<ReadHalf<Stream>>,
+    writer: Mutex<Writer>,
+}
+
+/// A WebSocket connection.
+///
+/// The connection is shared with the futures returned by its methods, since
+/// the script only lends it out while the method is being called.
+#[derive(Any)]
+struct WebSocket {
+    inner: Arc<Inner>,
+}
+
+impl WebSocket {
+    /// Send a string as a text message, or bytes as a binary message.
+    fn send(&self, message: Value) -> impl Future<Output = Result<io::Result<()>, VmError>> {
+        let inner = self.inner.clone();
+
+        let message = message_of(message);
+
+        async move {
+            let (opcode, payload) = message?;
+            let mut writer = inner.writer.lock().await;
+            Ok(writer.write_frame(opcode, &payload).await)
+        }
+    }
+
+    /// Receive the next message, or `None` if the connection was closed.
+    fn recv(&self) -> impl Future<Output = io::Result<Option<Value>>> {
+        let inner = self.inner.clone();
+
+        async move {
+            let mut reader = inner.reader.lock().await;
+            let mut message = None::<(u8, Vec<u8>)>;
+
+            loop {
+                let frame = match read_frame(&mut reader).await {
+                    Ok(frame) => frame,
+                    Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+                    Err(e) => return Err(e),
+                };
+
+                match frame.opcode {
+                    OP_CLOSE => {
+                        let mut writer = inner.writer.lock().await;
+
+                        if !writer.closed {
+                            // Echo the status code back, as required when
+                            // responding to a close.
+                            let code = frame.payload.get(..2).unwrap_or_default();
+                            writer.write_frame(OP_CLOSE, code).await?;
+                        }
+
+                        return Ok(None);
+                    }
+                    OP_PING => {
+                        let mut writer = inner.writer.lock().await;
+
+                        if !writer.closed {
+                            writer.write_frame(OP_PONG, &frame.payload).await?;
+                        }
+
+                        continue;
+                    }
+                    OP_PONG => continue,
+                    OP_CONTINUATION => match &mut message {
+                        Some((_, data)) => {
+                            if (data.len() + frame.payload.len()) as u64 > MAX_MESSAGE_SIZE {
+                                return Err(invalid_data("message is too large"));
+                            }
+
+                            data.extend_from_slice(&frame.payload);
+                        }
+                        None => return Err(invalid_data("unexpected continuation frame")),
+                    },
+                    OP_TEXT | OP_BINARY if message.is_none() => {
+                        message = Some((frame.opcode, frame.payload));
+                    }
+                    OP_TEXT | OP_BINARY => {
+                        return Err(invalid_data("expected a continuation frame"));
+                    }
+                    opcode => {
+                        return Err(invalid_data(format!("unsupported opcode {:#x}", opcode)));
+                    }
+                }
+
+                if frame.fin {
+                    if let Some((opcode, data)) = message.take() {
+                        return Ok(Some(match opcode {
+                            OP_TEXT => Value::from(String::from_utf8(data).map_err(invalid_data)?),
+                            _ => Value::from(Bytes::from_vec(data)),
+                        }));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Close the connection, waiting for the server to acknowledge it.
+    fn close(&self) -> impl Future<Output = io::Result<()>> {
+        let inner = self.inner.clone();
+
+        async move {
+            {
+                let mut writer = inner.writer.lock().await;
+
+                if writer.closed {
+                    return Ok(());
+                }
+
+                writer.write_frame(OP_CLOSE, &1000u16.to_be_bytes()).await?;
+            }
+
+            let mut reader = inner.reader.lock().await;
+
+            loop {
+                match read_frame(&mut reader).await {
+                    Ok(frame) if frame.opcode == OP_CLOSE => break,
+                    Ok(..) => continue,
+                    Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+                    Err(e) => return Err(e),
+                }
+            }
+
+            inner.writer.lock().await.half.shutdown().await
+        }
+    }
+}
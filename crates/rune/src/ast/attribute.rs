@@ -1,6 +1,6 @@
 use crate::ast::prelude::*;
 
-/// Attribute like `#[derive(Debug)]`
+/// Attribute like `#[derive(Debug, Serialize)]`
 ///
 /// # Examples
 ///
@@ -13,7 +13,7 @@ use crate::ast::prelude::*;
 /// testing::roundtrip::<ast::Attribute>("#![cfg(all(feature = \"potato\"))]");
 /// testing::roundtrip::<ast::Attribute>("#[x+1]");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct Attribute {
     /// The `#` character
@@ -93,7 +93,7 @@ impl IntoExpectation for Attribute {
 }
 
 /// Whether or not the attribute is an outer `#!` or inner `#` attribute
-#[derive(Debug, Clone, Copy, PartialEq, Eq, ToTokens)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ToTokens, Serialize, Walk)]
 #[non_exhaustive]
 pub enum AttrStyle {
     /// `#`
@@ -165,7 +165,7 @@ mod tests {
             r#"#[foo = a::Fred {"a": #{ "b": 2 } } ]"#,
             "#[bar()]",
             "#[bar(baz)]",
-            "#[derive(Debug, PartialEq, PartialOrd)]",
+            "#[derive(Debug, PartialEq, PartialOrd, Serialize)]",
             "#[tracing::instrument(skip(non_debug))]",
             "#[zanzibar(a = \"z\", both = false, sasquatch::herring)]",
             r#"#[doc = "multiline \
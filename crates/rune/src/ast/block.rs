@@ -31,11 +31,12 @@ use crate::ast::prelude::*;
 ///
 /// assert_eq!(block.statements.len(), 3);
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Opaque)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Opaque, Serialize, Walk)]
 #[non_exhaustive]
 pub struct Block {
     /// The unique identifier for the block expression.
     #[rune(id)]
+    #[serde(skip)]
     pub(crate) id: Id,
     /// The close brace.
     pub open: T!['{'],
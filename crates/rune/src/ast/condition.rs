@@ -10,7 +10,7 @@ use crate::ast::prelude::*;
 /// testing::roundtrip::<ast::Condition>("true");
 /// testing::roundtrip::<ast::Condition>("let [a, ..] = v");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub enum Condition {
     /// A regular expression.
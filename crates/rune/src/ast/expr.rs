@@ -3,7 +3,7 @@ use std::mem::take;
 use std::ops;
 
 /// Indicator that an expression should be parsed with an eager brace.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize)]
 pub(crate) struct EagerBrace(bool);
 
 /// Indicates that an expression should be parsed with eager braces.
@@ -22,7 +22,7 @@ impl ops::Deref for EagerBrace {
 }
 
 /// Indicator that an expression should be parsed as an eager binary expression.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize)]
 pub(crate) struct EagerBinary(bool);
 
 /// Indicates that an expression should be parsed as a binary expression.
@@ -42,7 +42,7 @@ impl ops::Deref for EagerBinary {
 /// Indicates if an expression can be called. By default, this depends on if the
 /// expression is a block expression (no) or not (yes). This allows the caller
 /// to contextually override that behavior.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize)]
 pub(crate) struct Callable(bool);
 
 /// Indicates that an expression should be treated as if it could be callable.
@@ -62,7 +62,7 @@ impl ops::Deref for Callable {
 }
 
 /// A rune expression.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub enum Expr {
     /// An path expression.
@@ -1,7 +1,7 @@
 use crate::ast::prelude::*;
 
 /// An assign expression `a = b`.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct ExprAssign {
     /// Attributes associated with the assign expression.
@@ -11,7 +11,7 @@ use crate::ast::prelude::*;
 /// testing::roundtrip::<ast::Expr>("self.await");
 /// testing::roundtrip::<ast::Expr>("test.await");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct ExprAwait {
     /// Attributes associated with expression.
@@ -11,7 +11,7 @@ use std::fmt;
 /// testing::roundtrip::<ast::ExprBinary>("42 + b");
 /// testing::roundtrip::<ast::ExprBinary>("b << 10");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct ExprBinary {
     /// Attributes associated with the binary expression.
@@ -28,7 +28,7 @@ pub struct ExprBinary {
 expr_parse!(Binary, ExprBinary, "binary expression");
 
 /// A binary operation.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ToTokens, Spanned)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub enum BinOp {
     /// Addition `a + b`.
@@ -99,7 +99,7 @@ pub enum BinOp {
 
 impl BinOp {
     /// Test if operator is an assign operator.
-    pub(crate) fn is_assign(&self) -> bool {
+    pub fn is_assign(&self) -> bool {
         match self {
             Self::AddAssign(..) => true,
             Self::SubAssign(..) => true,
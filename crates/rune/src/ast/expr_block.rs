@@ -17,7 +17,7 @@ use crate::ast::prelude::*;
 /// assert_eq!(expr.block.statements.len(), 1);
 /// assert_eq!(expr.attributes.len(), 1);
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned, Serialize, Walk)]
 #[rune(parse = "meta_only")]
 #[non_exhaustive]
 pub struct ExprBlock {
@@ -9,7 +9,7 @@ use crate::ast::prelude::*;
 /// testing::roundtrip::<ast::ExprBreak>("break 42");
 /// testing::roundtrip::<ast::ExprBreak>("#[attr] break 42");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned, Serialize, Walk)]
 #[rune(parse = "meta_only")]
 #[non_exhaustive]
 pub struct ExprBreak {
@@ -26,7 +26,7 @@ pub struct ExprBreak {
 expr_parse!(Break, ExprBreak, "break expression");
 
 /// Things that we can break on.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 #[allow(clippy::large_enum_variant)]
 pub enum ExprBreakValue {
@@ -10,11 +10,12 @@ use crate::ast::prelude::*;
 /// testing::roundtrip::<ast::ExprCall>("test()");
 /// testing::roundtrip::<ast::ExprCall>("(foo::bar)()");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Opaque)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Opaque, Serialize, Walk)]
 #[non_exhaustive]
 pub struct ExprCall {
     /// Opaque identifier related with call.
     #[rune(id)]
+    #[serde(skip)]
     pub(crate) id: Id,
     /// Attributes associated with expression.
     #[rune(iter)]
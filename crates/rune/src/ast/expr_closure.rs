@@ -19,12 +19,13 @@ use crate::ast::prelude::*;
 /// let expr = testing::roundtrip::<ast::ExprClosure>("#[retry(n=3)] async || 43");
 /// assert_eq!(expr.attributes.len(), 1);
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned, Opaque)]
+#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned, Opaque, Serialize, Walk)]
 #[rune(parse = "meta_only")]
 #[non_exhaustive]
 pub struct ExprClosure {
     /// Opaque identifier for the closure.
     #[rune(id)]
+    #[serde(skip)]
     pub(crate) id: Id,
     /// The attributes for the async closure
     #[rune(iter, meta)]
@@ -54,13 +55,16 @@ impl ExprClosure {
 
 expr_parse!(Closure, ExprClosure, "closure expression");
 
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens)]
+/// The arguments of a closure.
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Serialize, Walk)]
 #[non_exhaustive]
 pub enum ExprClosureArgs {
+    /// Closure has no arguments, like `||`.
     Empty {
         /// The `||` token.
         token: T![||],
     },
+    /// Closure has a list of arguments, like `|a, b|`.
     List {
         /// The opening pipe for the argument group.
         open: T![|],
@@ -8,7 +8,7 @@ use crate::ast::prelude::*;
 /// testing::roundtrip::<ast::ExprContinue>("continue");
 /// testing::roundtrip::<ast::ExprContinue>("continue 'foo");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned, Serialize, Walk)]
 #[rune(parse = "meta_only")]
 #[non_exhaustive]
 pub struct ExprContinue {
@@ -11,7 +11,7 @@ use crate::ast::prelude::*;
 /// // Note: tuple accesses must be disambiguated.
 /// testing::roundtrip::<ast::ExprFieldAccess>("(foo.0).1");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct ExprFieldAccess {
     /// Attributes associated with expression.
@@ -28,7 +28,7 @@ pub struct ExprFieldAccess {
 expr_parse!(FieldAccess, ExprFieldAccess, "field access expression");
 
 /// The field being accessed.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub enum ExprField {
     /// An identifier.
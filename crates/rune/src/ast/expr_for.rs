@@ -12,7 +12,7 @@ use crate::ast::prelude::*;
 /// testing::roundtrip::<ast::ExprFor>("'label: for i in x {}");
 /// testing::roundtrip::<ast::ExprFor>("#[attr] 'label: for i in x {}");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct ExprFor {
     /// The attributes of the `for` loop
@@ -10,7 +10,7 @@ use crate::ast::prelude::*;
 /// testing::roundtrip::<ast::ExprGroup>("(for i in x {})");
 /// testing::roundtrip::<ast::ExprGroup>("(1 + 2)");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct ExprGroup {
     /// Attributes associated with expression.
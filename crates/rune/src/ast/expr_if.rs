@@ -13,7 +13,7 @@ use crate::ast::prelude::*;
 /// testing::roundtrip::<ast::ExprIf>("if let v = v {  }");
 /// testing::roundtrip::<ast::ExprIf>("#[attr] if 1 {} else {}");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned, Serialize, Walk)]
 #[rune(parse = "meta_only")]
 #[non_exhaustive]
 pub struct ExprIf {
@@ -37,7 +37,7 @@ pub struct ExprIf {
 expr_parse!(If, ExprIf, "if expression");
 
 /// An else branch of an if expression.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Parse, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Parse, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct ExprElseIf {
     /// The `else` token.
@@ -57,7 +57,7 @@ impl Peek for ExprElseIf {
 }
 
 /// An else branch of an if expression.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Parse, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Parse, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct ExprElse {
     /// The `else` token.
@@ -1,7 +1,7 @@
 use crate::ast::prelude::*;
 
 /// An index get operation `<target>[<index>]`.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct ExprIndex {
     /// Attributes associated with expression.
@@ -10,7 +10,7 @@ use crate::ast::prelude::*;
 /// testing::roundtrip::<ast::ExprLet>("let x = 1");
 /// testing::roundtrip::<ast::ExprLet>("#[attr] let a = f()");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct ExprLet {
     /// The attributes for the let expression
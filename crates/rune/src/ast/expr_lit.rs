@@ -12,7 +12,7 @@ use crate::ast::prelude::*;
 /// testing::roundtrip::<ast::ExprLit>("\"test\"");
 /// testing::roundtrip::<ast::ExprLit>("#[attr] 42");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned, Serialize, Walk)]
 #[rune(parse = "meta_only")]
 #[non_exhaustive]
 pub struct ExprLit {
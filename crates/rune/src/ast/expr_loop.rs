@@ -12,7 +12,7 @@ use crate::ast::prelude::*;
 /// testing::roundtrip::<ast::ExprLoop>("'label: loop {1;}");
 /// testing::roundtrip::<ast::ExprLoop>("#[attr] 'label: loop {x();}");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned, Serialize, Walk)]
 #[rune(parse = "meta_only")]
 #[non_exhaustive]
 pub struct ExprLoop {
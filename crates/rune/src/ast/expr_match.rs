@@ -11,7 +11,7 @@ use crate::ast::prelude::*;
 /// let expr = testing::roundtrip::<ast::ExprMatch>("#[jit(always)] match 0 { _ => 1, }");
 /// assert_eq!(expr.attributes.len(), 1);
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct ExprMatch {
     /// The attributes for the match expression
@@ -77,7 +77,7 @@ expr_parse!(Match, ExprMatch, "match expression");
 ///
 /// testing::roundtrip::<ast::ExprMatchBranch>("1 => { foo }");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Parse, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Parse, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct ExprMatchBranch {
     /// The pattern to match.
@@ -12,7 +12,7 @@ use std::borrow::Cow;
 /// testing::roundtrip::<ast::ExprObject>("#{\"foo\": 42}");
 /// testing::roundtrip::<ast::ExprObject>("#{\"foo\": 42,}");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct ExprObject {
     /// Attributes associated with object.
@@ -36,7 +36,7 @@ impl Peek for ExprObject {
 }
 
 /// A literal object identifier.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub enum ObjectIdent {
     /// An anonymous object.
@@ -65,7 +65,7 @@ impl Parse for ObjectIdent {
 /// testing::roundtrip::<ast::FieldAssign>("\"foo\": 42");
 /// testing::roundtrip::<ast::FieldAssign>("\"foo\": 42");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct FieldAssign {
     /// The key of the field.
@@ -92,7 +92,7 @@ impl Parse for FieldAssign {
 }
 
 /// Possible literal object keys.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub enum ObjectKey {
     /// A literal string (with escapes).
@@ -9,7 +9,7 @@ use crate::ast::prelude::*;
 /// testing::roundtrip::<ast::ExprRange>("0..=42");
 /// testing::roundtrip::<ast::ExprRange>("0..=a + 2");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct ExprRange {
     /// Attributes associated with the assign expression.
@@ -26,7 +26,7 @@ pub struct ExprRange {
 }
 
 /// The limits of the specified range.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub enum ExprRangeLimits {
     /// Half-open range expression.
@@ -9,7 +9,7 @@ use crate::ast::prelude::*;
 /// testing::roundtrip::<ast::ExprReturn>("return 42");
 /// testing::roundtrip::<ast::ExprReturn>("#[attr] return 42");
 /// ```
-#[derive(Debug, Clone, Parse, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, Parse, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[rune(parse = "meta_only")]
 #[non_exhaustive]
 pub struct ExprReturn {
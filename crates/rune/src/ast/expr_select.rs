@@ -21,7 +21,7 @@ use crate::ast::prelude::*;
 /// assert!(matches!(select.branches.get(2), Some(&(ast::ExprSelectBranch::Pat(..), None))));
 /// assert!(matches!(select.branches.get(3), Some(&(ast::ExprSelectBranch::Default(..), None))));
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct ExprSelect {
     /// The attributes of the `select`
@@ -74,7 +74,7 @@ impl ExprSelect {
 expr_parse!(Select, ExprSelect, "select expression");
 
 /// A single selection branch.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 #[allow(clippy::large_enum_variant)]
 pub enum ExprSelectBranch {
@@ -105,7 +105,7 @@ impl Parse for ExprSelectBranch {
 }
 
 /// A single selection branch.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Parse, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Parse, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct ExprSelectPatBranch {
     /// The identifier to bind the result to.
@@ -121,7 +121,7 @@ pub struct ExprSelectPatBranch {
 }
 
 /// A single selection branch.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Parse, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Parse, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct ExprDefaultBranch {
     /// The `default` keyword.
@@ -8,7 +8,7 @@ use crate::ast::prelude::*;
 /// testing::roundtrip::<ast::ExprTry>("42?");
 /// testing::roundtrip::<ast::ExprTry>("foo()?");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct ExprTry {
     /// Attributes associated with expression.
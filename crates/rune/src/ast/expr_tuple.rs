@@ -13,7 +13,7 @@ use crate::ast::prelude::*;
 /// testing::roundtrip::<ast::ExprTuple>("(1, 2,)");
 /// testing::roundtrip::<ast::ExprTuple>("(1, 2, foo())");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct ExprTuple {
     /// Attributes associated with tuple.
@@ -15,7 +15,7 @@ use std::fmt;
 ///     a: 42,
 /// }");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct ExprUnary {
     /// Attributes associated with expression.
@@ -50,7 +50,7 @@ impl ExprUnary {
 expr_parse!(Unary, ExprUnary, "try expression");
 
 /// A unary operation.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 pub enum UnOp {
     /// Not `!<thing>`.
     Not(ast::Bang),
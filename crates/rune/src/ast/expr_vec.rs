@@ -11,7 +11,7 @@ use crate::ast::prelude::*;
 /// testing::roundtrip::<ast::ExprVec>("[1, 2,]");
 /// testing::roundtrip::<ast::ExprVec>("[1, 2, foo()]");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct ExprVec {
     /// Attributes associated with vector.
@@ -11,7 +11,7 @@ use crate::ast::prelude::*;
 /// testing::roundtrip::<ast::ExprWhile>("'label: while x {}");
 /// testing::roundtrip::<ast::ExprWhile>("#[attr] 'label: while x {}");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned, Serialize, Walk)]
 #[rune(parse = "meta_only")]
 #[non_exhaustive]
 pub struct ExprWhile {
@@ -11,7 +11,7 @@ use crate::ast::prelude::*;
 /// testing::roundtrip::<ast::ExprYield>("yield 42");
 /// testing::roundtrip::<ast::ExprYield>("#[attr] yield 42");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned, Serialize, Walk)]
 #[rune(parse = "meta_only")]
 #[non_exhaustive]
 pub struct ExprYield {
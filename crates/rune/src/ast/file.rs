@@ -72,7 +72,7 @@ use crate::ast::prelude::*;
 /// assert!(file.shebang.is_some());
 /// # Ok(()) }
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Serialize, Walk)]
 #[non_exhaustive]
 pub struct File {
     /// Top-level shebang.
@@ -151,7 +151,7 @@ impl Parse for File {
 }
 
 /// The shebang of a file.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Walk)]
 #[non_exhaustive]
 pub struct Shebang {
     /// The span of the shebang.
@@ -11,7 +11,7 @@ use crate::ast::prelude::*;
 /// testing::roundtrip::<ast::FnArg>("_");
 /// testing::roundtrip::<ast::FnArg>("abc");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub enum FnArg {
     /// The `self` parameter.
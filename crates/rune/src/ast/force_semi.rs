@@ -1,7 +1,7 @@
 use crate::ast::prelude::*;
 
 /// Helper to force an expression to have a specific semi-colon policy.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Walk)]
 #[non_exhaustive]
 pub struct ForceSemi {
     /// The span of the whole wrapping expression.
@@ -3,7 +3,7 @@ use crate::ast::prelude::*;
 macro_rules! grouped {
     ($(#[$meta:meta])* $name:ident { $field:ident, $open:ty, $close:ty }) => {
         $(#[$meta])*
-        #[derive(Debug, Clone, PartialEq, Eq, Spanned, ToTokens)]
+        #[derive(Debug, Clone, PartialEq, Eq, Spanned, ToTokens, Serialize, Walk)]
         #[non_exhaustive]
         pub struct $name<T, S> {
             /// The open parenthesis.
@@ -27,7 +27,7 @@ use crate::ast::prelude::*;
 /// testing::roundtrip::<ast::Ident>("foo");
 /// testing::roundtrip::<ast::Ident>("a42");
 /// ```
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Spanned)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct Ident {
     /// The kind of the identifier.
@@ -1,7 +1,7 @@
 use crate::ast::prelude::*;
 
 /// A declaration.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub enum Item {
     /// A use declaration.
@@ -9,12 +9,13 @@ use crate::ast::prelude::*;
 ///
 /// testing::roundtrip::<ast::ItemConst>("const value = #{}");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned, Opaque)]
+#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned, Opaque, Serialize, Walk)]
 #[rune(parse = "meta_only")]
 #[non_exhaustive]
 pub struct ItemConst {
     /// Opaque identifier for the constant.
     #[rune(id)]
+    #[serde(skip)]
     pub(crate) id: Id,
     /// The *inner* attributes that are applied to the const declaration.
     #[rune(iter, meta)]
@@ -12,7 +12,7 @@ use crate::ast::prelude::*;
 /// testing::roundtrip::<ast::ItemEnum>("#[repr(Rune)] enum Foo { Bar(a), Baz(b), #[default_value = \"zombie\"] Empty() }");
 /// testing::roundtrip::<ast::ItemEnum>("pub enum Color { Blue, Red, Green }");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned, Serialize, Walk)]
 #[rune(parse = "meta_only")]
 #[non_exhaustive]
 pub struct ItemEnum {
@@ -33,11 +33,12 @@ pub struct ItemEnum {
 item_parse!(Enum, ItemEnum, "enum item");
 
 /// An enum variant.
-#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned, Opaque)]
+#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned, Opaque, Serialize, Walk)]
 #[non_exhaustive]
 pub struct ItemVariant {
     /// Opaque identifier of variant.
     #[rune(id)]
+    #[serde(skip)]
     pub(crate) id: Id,
     /// The attributes associated with the variant.
     #[rune(iter)]
@@ -50,7 +51,7 @@ pub struct ItemVariant {
 }
 
 /// An item body declaration.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, OptionSpanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, OptionSpanned, Serialize, Walk)]
 #[non_exhaustive]
 pub enum ItemVariantBody {
     /// An empty enum body.
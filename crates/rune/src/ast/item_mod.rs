@@ -16,12 +16,13 @@ use crate::ast::prelude::*;
 /// assert_eq!(item.attributes.len(), 0);
 /// assert!(matches!(item.body, ast::ItemModBody::InlineBody(..)));
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned, Opaque)]
+#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned, Opaque, Serialize, Walk)]
 #[rune(parse = "meta_only")]
 #[non_exhaustive]
 pub struct ItemMod {
     /// The id of the module item.
     #[rune(id)]
+    #[serde(skip)]
     pub(crate) id: Id,
     /// The *inner* attributes are applied to the module  `#[cfg(test)] mod tests {  }`
     #[rune(iter, meta)]
@@ -51,7 +52,7 @@ impl ItemMod {
 item_parse!(Mod, ItemMod, "mod item");
 
 /// An item body.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub enum ItemModBody {
     /// An empty body terminated by a semicolon.
@@ -70,7 +71,7 @@ impl Parse for ItemModBody {
 }
 
 /// A module declaration.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Parse, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Parse, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct ItemInlineBody {
     /// The open brace.
@@ -13,12 +13,13 @@ use crate::ast::prelude::*;
 /// testing::roundtrip::<ast::ItemStruct>("struct Foo { #[default_value = 1] a, b, c }");
 /// testing::roundtrip::<ast::ItemStruct>("#[alpha] struct Foo ( #[default_value = \"x\" ] a, b, c )");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned, Opaque)]
+#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned, Opaque, Serialize, Walk)]
 #[rune(parse = "meta_only")]
 #[non_exhaustive]
 pub struct ItemStruct {
     /// Opaque identifier of the struct.
     #[rune(id)]
+    #[serde(skip)]
     pub(crate) id: Id,
     /// The attributes for the struct
     #[rune(iter, meta)]
@@ -45,7 +46,7 @@ impl ItemStruct {
 item_parse!(Struct, ItemStruct, "struct item");
 
 /// AST for a struct body.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, OptionSpanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, OptionSpanned, Serialize, Walk)]
 #[non_exhaustive]
 pub enum ItemStructBody {
     /// An empty struct declaration.
@@ -111,7 +112,7 @@ impl Parse for ItemStructBody {
 /// testing::roundtrip::<ast::Field>("a");
 /// testing::roundtrip::<ast::Field>("#[x] a");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Parse, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Parse, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct Field {
     /// Attributes associated with field.
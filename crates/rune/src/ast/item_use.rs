@@ -13,7 +13,7 @@ use crate::ast::prelude::*;
 /// testing::roundtrip::<ast::ItemUse>("#[macro_use] use foo::bar::baz");
 /// testing::roundtrip::<ast::ItemUse>("#[macro_use] pub(crate) use foo::bar::baz");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned, Serialize, Walk)]
 #[rune(parse = "meta_only")]
 #[non_exhaustive]
 pub struct ItemUse {
@@ -44,7 +44,7 @@ item_parse!(Use, ItemUse, "use item");
 /// testing::roundtrip::<ast::ItemUsePath>("{*, bar::*}");
 /// testing::roundtrip::<ast::ItemUsePath>("::{*, bar::*}");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct ItemUsePath {
     /// Global prefix.
@@ -61,7 +61,7 @@ pub struct ItemUsePath {
 }
 
 /// A use component.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub enum ItemUseSegment {
     /// A path segment.
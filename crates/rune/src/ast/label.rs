@@ -27,7 +27,7 @@ use crate::ast::prelude::*;
 /// testing::roundtrip::<ast::Label>("'foo");
 /// testing::roundtrip::<ast::Label>("'barify42");
 /// ```
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Spanned)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct Label {
     /// The token of the label.
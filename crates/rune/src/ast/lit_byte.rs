@@ -1,7 +1,7 @@
 use crate::ast::prelude::*;
 
 /// A byte literal.
-#[derive(Debug, Clone, PartialEq, Eq, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct LitByte {
     /// The span corresponding to the literal.
@@ -10,12 +10,13 @@ use crate::ast::prelude::*;
 /// testing::roundtrip::<ast::MacroCall>("foo!()");
 /// testing::roundtrip::<ast::MacroCall>("::bar::foo!(question to life)");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Opaque)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Opaque, Serialize, Walk)]
 #[non_exhaustive]
 pub struct MacroCall {
     /// Opaque identifier for macro call. Use to store reference to internally
     /// expanded macros.
     #[rune(id)]
+    #[serde(skip)]
     pub(crate) id: Id,
     /// Attributes associated with macro call.
     #[rune(iter)]
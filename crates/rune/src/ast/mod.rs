@@ -54,6 +54,7 @@
 
 use crate::macros::{MacroContext, ToTokens, TokenStream};
 use crate::parse::{Parse, ParseError, Parser, Peek};
+use serde::Serialize;
 
 #[macro_use]
 /// Generated modules.
@@ -155,8 +156,9 @@ mod stmt;
 mod token;
 pub(super) mod utils;
 mod vis;
+mod visit;
 
-pub use self::attribute::Attribute;
+pub use self::attribute::{AttrStyle, Attribute};
 pub use self::block::Block;
 pub use self::condition::Condition;
 pub use self::expr::Expr;
@@ -166,7 +168,7 @@ pub use self::expr_binary::{BinOp, ExprBinary};
 pub use self::expr_block::ExprBlock;
 pub use self::expr_break::{ExprBreak, ExprBreakValue};
 pub use self::expr_call::ExprCall;
-pub use self::expr_closure::ExprClosure;
+pub use self::expr_closure::{ExprClosure, ExprClosureArgs};
 pub use self::expr_continue::ExprContinue;
 pub use self::expr_empty::ExprEmpty;
 pub use self::expr_field_access::{ExprField, ExprFieldAccess};
@@ -181,7 +183,7 @@ pub use self::expr_match::{ExprMatch, ExprMatchBranch};
 pub use self::expr_object::{ExprObject, FieldAssign, ObjectIdent, ObjectKey};
 pub use self::expr_range::{ExprRange, ExprRangeLimits};
 pub use self::expr_return::ExprReturn;
-pub use self::expr_select::{ExprSelect, ExprSelectBranch};
+pub use self::expr_select::{ExprDefaultBranch, ExprSelect, ExprSelectBranch, ExprSelectPatBranch};
 pub use self::expr_try::ExprTry;
 pub use self::expr_tuple::ExprTuple;
 pub use self::expr_unary::{ExprUnary, UnOp};
@@ -198,7 +200,7 @@ pub use self::item_const::ItemConst;
 pub use self::item_enum::{ItemEnum, ItemVariant, ItemVariantBody};
 pub use self::item_fn::ItemFn;
 pub use self::item_impl::ItemImpl;
-pub use self::item_mod::{ItemMod, ItemModBody};
+pub use self::item_mod::{ItemInlineBody, ItemMod, ItemModBody};
 pub use self::item_struct::{Field, ItemStruct, ItemStructBody};
 pub use self::item_use::{ItemUse, ItemUsePath, ItemUseSegment};
 pub use self::label::Label;
@@ -211,7 +213,9 @@ pub use self::lit_number::LitNumber;
 pub use self::lit_str::LitStr;
 pub use self::local::Local;
 pub use self::macro_call::MacroCall;
-pub use self::pat::{Pat, PatBinding, PatLit, PatObject, PatPath, PatTuple, PatVec};
+pub use self::pat::{
+    Pat, PatBinding, PatIgnore, PatLit, PatObject, PatPath, PatRest, PatTuple, PatVec,
+};
 pub use self::path::{Path, PathKind, PathSegment, PathSegmentExpr};
 pub use self::span::{ByteIndex, Span};
 pub use self::spanned::{OptionSpanned, Spanned};
@@ -222,13 +226,14 @@ pub use self::token::{
     BuiltIn, CopySource, Delimiter, LitSource, Number, NumberBase, NumberSource, NumberText,
     StrSource, StrText, Token,
 };
-pub use self::vis::Visibility;
+pub use self::vis::{Visibility, VisibilityIn, VisibilityRestrict};
+pub use self::visit::{Visit, VisitMut, Walk};
 
 macro_rules! decl_tokens {
     ($(($parser:ident, $name:expr, $doc:expr, $($kind:tt)*),)*) => {
         $(
             #[doc = $doc]
-            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
             pub struct $parser {
                 /// Associated token.
                 pub span: Span,
@@ -262,6 +267,8 @@ macro_rules! decl_tokens {
                     stream.push(Token { span: self.span, kind: $($kind)* });
                 }
             }
+
+            impl Walk for $parser {}
         )*
     }
 }
@@ -278,7 +285,7 @@ decl_tokens! {
 }
 
 /// The composite `is not` operation.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ToTokens, Spanned)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct IsNot {
     /// The `is` token.
@@ -1,7 +1,7 @@
 use crate::ast::prelude::*;
 
 /// A pattern match.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub enum Pat {
     /// An ignored binding `_`.
@@ -184,7 +184,7 @@ impl Peek for Pat {
 }
 
 /// A literal pattern.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct PatLit {
     /// Attributes associated with the pattern.
@@ -195,7 +195,7 @@ pub struct PatLit {
 }
 
 /// The rest pattern `..` and associated attributes.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct PatRest {
     /// Attribute associated with the rest pattern.
@@ -206,7 +206,7 @@ pub struct PatRest {
 }
 
 /// An array pattern.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct PatVec {
     /// Attributes associated with the vector pattern.
@@ -217,7 +217,7 @@ pub struct PatVec {
 }
 
 /// A tuple pattern.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct PatTuple {
     /// Attributes associated with the object pattern.
@@ -231,7 +231,7 @@ pub struct PatTuple {
 }
 
 /// An object pattern.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct PatObject {
     /// Attributes associated with the object pattern.
@@ -244,7 +244,7 @@ pub struct PatObject {
 }
 
 /// An object item.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Parse)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Parse, Serialize, Walk)]
 #[non_exhaustive]
 pub struct PatBinding {
     /// Attributes associate with the binding.
@@ -259,7 +259,7 @@ pub struct PatBinding {
 }
 
 /// A tuple pattern.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct PatPath {
     /// Attributes associate with the path.
@@ -270,7 +270,7 @@ pub struct PatPath {
 }
 
 /// A ignore pattern.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct PatIgnore {
     /// Attributes associate with the path.
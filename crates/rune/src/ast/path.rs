@@ -15,11 +15,12 @@ use crate::ast::prelude::*;
 /// testing::roundtrip::<ast::Path>("HashMap::<Foo, Bar>");
 /// testing::roundtrip::<ast::Path>("super::HashMap::<Foo, Bar>");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned, Opaque)]
+#[derive(Debug, Clone, PartialEq, Eq, Parse, ToTokens, Spanned, Opaque, Serialize, Walk)]
 #[non_exhaustive]
 pub struct Path {
     /// Opaque id associated with path.
     #[rune(id)]
+    #[serde(skip)]
     pub(crate) id: Id,
     /// The optional leading colon `::` indicating global scope.
     #[rune(iter)]
@@ -52,7 +53,7 @@ impl Path {
     ///
     /// This is only allowed if there are no other path components
     /// and the path segment is not `Crate` or `Super`.
-    pub(crate) fn try_as_ident(&self) -> Option<&ast::Ident> {
+    pub fn try_as_ident(&self) -> Option<&ast::Ident> {
         if self.rest.is_empty() && self.trailing.is_none() && self.global.is_none() {
             self.first.try_as_ident()
         } else {
@@ -187,7 +188,7 @@ impl<'a> Resolve<'a> for Path {
 }
 
 /// An identified path kind.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 #[non_exhaustive]
 pub enum PathKind {
     /// A path that is the `self` value.
@@ -197,7 +198,7 @@ pub enum PathKind {
 }
 
 /// Part of a `::` separated path.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub enum PathSegment {
     /// A path segment that contains `Self`.
@@ -274,7 +275,7 @@ impl Peek for PathSegment {
 }
 
 /// Used to parse an expression without supporting an immediate binary expression.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct PathSegmentExpr {
     /// The expression that makes up the path segment.
@@ -2,10 +2,11 @@
 
 pub(crate) use crate::ast;
 pub(crate) use crate::ast::utils;
-pub(crate) use crate::ast::{OptionSpanned, Span, Spanned};
+pub(crate) use crate::ast::{OptionSpanned, Span, Spanned, Walk};
 pub(crate) use crate::macros::{MacroContext, SyntheticKind, ToTokens, TokenStream};
 pub(crate) use crate::parse::Opaque;
 pub(crate) use crate::parse::{
     Expectation, Id, IntoExpectation, Parse, ParseError, ParseErrorKind, Parser, Peek, Peeker,
     Resolve, ResolveContext, ResolveError, ResolveErrorKind,
 };
+pub(crate) use serde::Serialize;
@@ -9,7 +9,7 @@ use std::mem::take;
 /// testing::roundtrip::<ast::Stmt>("let x = 1;");
 /// testing::roundtrip::<ast::Stmt>("#[attr] let a = f();");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 #[allow(clippy::large_enum_variant)]
 pub enum Stmt {
@@ -94,7 +94,7 @@ impl Parse for Stmt {
 }
 
 /// Parsing an item or an expression.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 #[non_exhaustive]
 #[allow(clippy::large_enum_variant)]
 pub enum ItemOrExpr {
@@ -148,7 +148,7 @@ impl Parse for ItemOrExpr {
 }
 
 /// Key used to stort a statement into its processing order.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 #[non_exhaustive]
 pub enum StmtSortKey {
     /// USe statements, that should be processed first.
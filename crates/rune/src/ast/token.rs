@@ -2,10 +2,11 @@ use crate::ast::{Kind, Span, Spanned};
 use crate::macros::{MacroContext, SyntheticId, ToTokens, TokenStream};
 use crate::parse::{Expectation, IntoExpectation, ParseError, ParseErrorKind};
 use crate::SourceId;
+use serde::Serialize;
 use std::fmt;
 
 /// A single token encountered during parsing.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
 #[non_exhaustive]
 pub struct Token {
     /// The span of the token.
@@ -263,7 +264,7 @@ impl fmt::Display for Number {
 }
 
 /// The kind of a number literal.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
 #[non_exhaustive]
 pub enum NumberBase {
     /// A decimal number literal, like `3.14`.
@@ -291,7 +292,7 @@ impl fmt::Display for NumberBase {
 ///
 /// This is necessary to synthesize identifiers in the lexer since there's not
 /// storage available, nor is the identifier reflected in the source.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
 #[non_exhaustive]
 pub enum BuiltIn {
     /// `template`.
@@ -323,7 +324,7 @@ impl fmt::Display for BuiltIn {
 }
 
 /// The kind of the identifier.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
 #[non_exhaustive]
 pub enum LitSource {
     /// The identifier is from the source text.
@@ -337,7 +338,7 @@ pub enum LitSource {
 /// The source of the literal string. This need to be treated separately from
 /// [LitSource] because it might encompass special things like quoting and
 /// escaping.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
 #[non_exhaustive]
 pub enum StrSource {
     /// The literal string source is from the source text.
@@ -347,7 +348,7 @@ pub enum StrSource {
 }
 
 /// Configuration for a literal string.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
 #[non_exhaustive]
 pub struct StrText {
     /// The source of the text.
@@ -359,7 +360,7 @@ pub struct StrText {
 }
 
 /// The source of a number.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
 #[non_exhaustive]
 pub enum NumberSource {
     /// The number is from the source text (and need to be parsed while it's
@@ -370,7 +371,7 @@ pub enum NumberSource {
 }
 
 /// The source of an item that implements Copy.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
 #[non_exhaustive]
 pub enum CopySource<T>
 where
@@ -384,7 +385,7 @@ where
 }
 
 /// Configuration of a text number.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
 #[non_exhaustive]
 pub struct NumberText {
     /// The source of the text.
@@ -396,7 +397,7 @@ pub struct NumberText {
 }
 
 /// A delimiter, `{`, `{`, or `[`.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
 #[non_exhaustive]
 pub enum Delimiter {
     /// A parenthesis delimiter `(` and `)`.
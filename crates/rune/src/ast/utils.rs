@@ -1,10 +1,11 @@
 use crate::ast;
 use crate::parse::ResolveErrorKind;
+use serde::Serialize;
 use std::iter::Peekable;
 use std::ops;
 
 /// Indicates if we are parsing template escapes.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize)]
 pub(crate) struct WithTemplate(pub(super) bool);
 
 impl ops::Deref for WithTemplate {
@@ -16,7 +17,7 @@ impl ops::Deref for WithTemplate {
 }
 
 /// Indicates if we are parsing line continuations or not.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize)]
 pub(super) struct WithLineCont(pub(super) bool);
 
 impl ops::Deref for WithLineCont {
@@ -1,7 +1,7 @@
 use crate::ast::prelude::*;
 
 /// Visibility level restricted to some path: pub(self) or pub(super) or pub(crate) or pub(in some::module).
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, OptionSpanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, OptionSpanned, Serialize, Walk)]
 #[non_exhaustive]
 pub enum Visibility {
     /// An inherited visibility level, this usually means private.
@@ -118,7 +118,7 @@ impl Parse for Visibility {
 }
 
 /// A `in path` restriction to visibility.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct VisibilityIn {
     /// The `in` keyword.
@@ -128,7 +128,7 @@ pub struct VisibilityIn {
 }
 
 /// A restriction to visibility.
-#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned)]
+#[derive(Debug, Clone, PartialEq, Eq, ToTokens, Spanned, Serialize, Walk)]
 #[non_exhaustive]
 pub struct VisibilityRestrict<T> {
     /// `pub` keyword.
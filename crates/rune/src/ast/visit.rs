@@ -0,0 +1,319 @@
+use crate::ast;
+use crate::macros::TokenStream;
+pub use rune_macros::Walk;
+
+/// A syntax tree node which can be walked by a [Visit] or [VisitMut]
+/// implementation.
+///
+/// [accept][Walk::accept] dispatches to the visitor method corresponding to
+/// the node, while [walk][Walk::walk] visits each child of the node in the
+/// order in which they appear in the source.
+pub trait Walk {
+    /// Accept the given visitor, calling the visitor method which corresponds
+    /// to this node.
+    fn accept<V>(&self, visitor: &mut V)
+    where
+        V: ?Sized + Visit,
+    {
+        self.walk(visitor);
+    }
+
+    /// Visit each child of this node.
+    #[allow(unused_variables)]
+    fn walk<V>(&self, visitor: &mut V)
+    where
+        V: ?Sized + Visit,
+    {
+    }
+
+    /// Accept the given mutable visitor, calling the visitor method which
+    /// corresponds to this node.
+    fn accept_mut<V>(&mut self, visitor: &mut V)
+    where
+        V: ?Sized + VisitMut,
+    {
+        self.walk_mut(visitor);
+    }
+
+    /// Mutably visit each child of this node.
+    #[allow(unused_variables)]
+    fn walk_mut<V>(&mut self, visitor: &mut V)
+    where
+        V: ?Sized + VisitMut,
+    {
+    }
+}
+
+macro_rules! decl_visit {
+    ($(($visit:ident, $visit_mut:ident, $ty:ident),)*) => {
+        /// A visitor over a syntax tree.
+        ///
+        /// Every method has a default implementation which walks the children
+        /// of the node, so implementors only need to override the nodes they
+        /// are interested in. To keep descending from an overriden method,
+        /// call [Walk::walk] on the node.
+        ///
+        /// ```
+        /// use rune::ast::{self, Visit, Walk};
+        /// use rune::SourceId;
+        ///
+        /// #[derive(Default)]
+        /// struct CountCalls(usize);
+        ///
+        /// impl Visit for CountCalls {
+        ///     fn visit_expr_call(&mut self, node: &ast::ExprCall) {
+        ///         self.0 += 1;
+        ///         node.walk(self);
+        ///     }
+        /// }
+        ///
+        /// # fn main() -> rune::Result<()> {
+        /// let file = rune::parse::parse_all::<ast::File>(
+        ///     "fn main() { foo(bar(1), 2) }",
+        ///     SourceId::empty(),
+        ///     false,
+        /// )?;
+        ///
+        /// let mut visitor = CountCalls::default();
+        /// file.accept(&mut visitor);
+        /// assert_eq!(visitor.0, 2);
+        /// # Ok(()) }
+        /// ```
+        pub trait Visit {
+            $(
+                #[doc = concat!("Visit a [", stringify!($ty), "][ast::", stringify!($ty), "].")]
+                fn $visit(&mut self, node: &ast::$ty) {
+                    Walk::walk(node, self);
+                }
+            )*
+        }
+
+        /// A visitor over a syntax tree which can modify the nodes it visits.
+        ///
+        /// See [Visit] for details.
+        pub trait VisitMut {
+            $(
+                #[doc = concat!("Mutably visit a [", stringify!($ty), "][ast::", stringify!($ty), "].")]
+                fn $visit_mut(&mut self, node: &mut ast::$ty) {
+                    Walk::walk_mut(node, self);
+                }
+            )*
+        }
+    }
+}
+
+decl_visit! {
+    (visit_attribute, visit_attribute_mut, Attribute),
+    (visit_attr_style, visit_attr_style_mut, AttrStyle),
+    (visit_bin_op, visit_bin_op_mut, BinOp),
+    (visit_block, visit_block_mut, Block),
+    (visit_condition, visit_condition_mut, Condition),
+    (visit_expr, visit_expr_mut, Expr),
+    (visit_expr_assign, visit_expr_assign_mut, ExprAssign),
+    (visit_expr_await, visit_expr_await_mut, ExprAwait),
+    (visit_expr_binary, visit_expr_binary_mut, ExprBinary),
+    (visit_expr_block, visit_expr_block_mut, ExprBlock),
+    (visit_expr_break, visit_expr_break_mut, ExprBreak),
+    (visit_expr_break_value, visit_expr_break_value_mut, ExprBreakValue),
+    (visit_expr_call, visit_expr_call_mut, ExprCall),
+    (visit_expr_closure, visit_expr_closure_mut, ExprClosure),
+    (visit_expr_closure_args, visit_expr_closure_args_mut, ExprClosureArgs),
+    (visit_expr_continue, visit_expr_continue_mut, ExprContinue),
+    (visit_expr_default_branch, visit_expr_default_branch_mut, ExprDefaultBranch),
+    (visit_expr_else, visit_expr_else_mut, ExprElse),
+    (visit_expr_else_if, visit_expr_else_if_mut, ExprElseIf),
+    (visit_expr_empty, visit_expr_empty_mut, ExprEmpty),
+    (visit_expr_field, visit_expr_field_mut, ExprField),
+    (visit_expr_field_access, visit_expr_field_access_mut, ExprFieldAccess),
+    (visit_expr_for, visit_expr_for_mut, ExprFor),
+    (visit_expr_group, visit_expr_group_mut, ExprGroup),
+    (visit_expr_if, visit_expr_if_mut, ExprIf),
+    (visit_expr_index, visit_expr_index_mut, ExprIndex),
+    (visit_expr_let, visit_expr_let_mut, ExprLet),
+    (visit_expr_lit, visit_expr_lit_mut, ExprLit),
+    (visit_expr_loop, visit_expr_loop_mut, ExprLoop),
+    (visit_expr_match, visit_expr_match_mut, ExprMatch),
+    (visit_expr_match_branch, visit_expr_match_branch_mut, ExprMatchBranch),
+    (visit_expr_object, visit_expr_object_mut, ExprObject),
+    (visit_expr_range, visit_expr_range_mut, ExprRange),
+    (visit_expr_range_limits, visit_expr_range_limits_mut, ExprRangeLimits),
+    (visit_expr_return, visit_expr_return_mut, ExprReturn),
+    (visit_expr_select, visit_expr_select_mut, ExprSelect),
+    (visit_expr_select_branch, visit_expr_select_branch_mut, ExprSelectBranch),
+    (visit_expr_select_pat_branch, visit_expr_select_pat_branch_mut, ExprSelectPatBranch),
+    (visit_expr_try, visit_expr_try_mut, ExprTry),
+    (visit_expr_tuple, visit_expr_tuple_mut, ExprTuple),
+    (visit_expr_unary, visit_expr_unary_mut, ExprUnary),
+    (visit_expr_vec, visit_expr_vec_mut, ExprVec),
+    (visit_expr_while, visit_expr_while_mut, ExprWhile),
+    (visit_expr_yield, visit_expr_yield_mut, ExprYield),
+    (visit_field, visit_field_mut, Field),
+    (visit_field_assign, visit_field_assign_mut, FieldAssign),
+    (visit_file, visit_file_mut, File),
+    (visit_fn_arg, visit_fn_arg_mut, FnArg),
+    (visit_force_semi, visit_force_semi_mut, ForceSemi),
+    (visit_ident, visit_ident_mut, Ident),
+    (visit_is_not, visit_is_not_mut, IsNot),
+    (visit_item, visit_item_mut, Item),
+    (visit_item_const, visit_item_const_mut, ItemConst),
+    (visit_item_enum, visit_item_enum_mut, ItemEnum),
+    (visit_item_fn, visit_item_fn_mut, ItemFn),
+    (visit_item_impl, visit_item_impl_mut, ItemImpl),
+    (visit_item_inline_body, visit_item_inline_body_mut, ItemInlineBody),
+    (visit_item_mod, visit_item_mod_mut, ItemMod),
+    (visit_item_mod_body, visit_item_mod_body_mut, ItemModBody),
+    (visit_item_struct, visit_item_struct_mut, ItemStruct),
+    (visit_item_struct_body, visit_item_struct_body_mut, ItemStructBody),
+    (visit_item_use, visit_item_use_mut, ItemUse),
+    (visit_item_use_path, visit_item_use_path_mut, ItemUsePath),
+    (visit_item_use_segment, visit_item_use_segment_mut, ItemUseSegment),
+    (visit_item_variant, visit_item_variant_mut, ItemVariant),
+    (visit_item_variant_body, visit_item_variant_body_mut, ItemVariantBody),
+    (visit_label, visit_label_mut, Label),
+    (visit_lit, visit_lit_mut, Lit),
+    (visit_lit_bool, visit_lit_bool_mut, LitBool),
+    (visit_lit_byte, visit_lit_byte_mut, LitByte),
+    (visit_lit_byte_str, visit_lit_byte_str_mut, LitByteStr),
+    (visit_lit_char, visit_lit_char_mut, LitChar),
+    (visit_lit_number, visit_lit_number_mut, LitNumber),
+    (visit_lit_str, visit_lit_str_mut, LitStr),
+    (visit_local, visit_local_mut, Local),
+    (visit_macro_call, visit_macro_call_mut, MacroCall),
+    (visit_object_ident, visit_object_ident_mut, ObjectIdent),
+    (visit_object_key, visit_object_key_mut, ObjectKey),
+    (visit_pat, visit_pat_mut, Pat),
+    (visit_pat_binding, visit_pat_binding_mut, PatBinding),
+    (visit_pat_ignore, visit_pat_ignore_mut, PatIgnore),
+    (visit_pat_lit, visit_pat_lit_mut, PatLit),
+    (visit_pat_object, visit_pat_object_mut, PatObject),
+    (visit_pat_path, visit_pat_path_mut, PatPath),
+    (visit_pat_rest, visit_pat_rest_mut, PatRest),
+    (visit_pat_tuple, visit_pat_tuple_mut, PatTuple),
+    (visit_pat_vec, visit_pat_vec_mut, PatVec),
+    (visit_path, visit_path_mut, Path),
+    (visit_path_segment, visit_path_segment_mut, PathSegment),
+    (visit_path_segment_expr, visit_path_segment_expr_mut, PathSegmentExpr),
+    (visit_shebang, visit_shebang_mut, Shebang),
+    (visit_stmt, visit_stmt_mut, Stmt),
+    (visit_un_op, visit_un_op_mut, UnOp),
+    (visit_visibility, visit_visibility_mut, Visibility),
+    (visit_visibility_in, visit_visibility_in_mut, VisibilityIn),
+}
+
+impl<T> Walk for Box<T>
+where
+    T: Walk,
+{
+    fn accept<V>(&self, visitor: &mut V)
+    where
+        V: ?Sized + Visit,
+    {
+        Walk::accept(&**self, visitor);
+    }
+
+    fn accept_mut<V>(&mut self, visitor: &mut V)
+    where
+        V: ?Sized + VisitMut,
+    {
+        Walk::accept_mut(&mut **self, visitor);
+    }
+}
+
+impl<T> Walk for Option<T>
+where
+    T: Walk,
+{
+    fn walk<V>(&self, visitor: &mut V)
+    where
+        V: ?Sized + Visit,
+    {
+        if let Some(value) = self {
+            value.accept(visitor);
+        }
+    }
+
+    fn walk_mut<V>(&mut self, visitor: &mut V)
+    where
+        V: ?Sized + VisitMut,
+    {
+        if let Some(value) = self {
+            value.accept_mut(visitor);
+        }
+    }
+}
+
+impl<T> Walk for Vec<T>
+where
+    T: Walk,
+{
+    fn walk<V>(&self, visitor: &mut V)
+    where
+        V: ?Sized + Visit,
+    {
+        for value in self {
+            value.accept(visitor);
+        }
+    }
+
+    fn walk_mut<V>(&mut self, visitor: &mut V)
+    where
+        V: ?Sized + VisitMut,
+    {
+        for value in self {
+            value.accept_mut(visitor);
+        }
+    }
+}
+
+impl<A, B> Walk for (A, B)
+where
+    A: Walk,
+    B: Walk,
+{
+    fn walk<V>(&self, visitor: &mut V)
+    where
+        V: ?Sized + Visit,
+    {
+        self.0.accept(visitor);
+        self.1.accept(visitor);
+    }
+
+    fn walk_mut<V>(&mut self, visitor: &mut V)
+    where
+        V: ?Sized + VisitMut,
+    {
+        self.0.accept_mut(visitor);
+        self.1.accept_mut(visitor);
+    }
+}
+
+impl<A, B, C> Walk for (A, B, C)
+where
+    A: Walk,
+    B: Walk,
+    C: Walk,
+{
+    fn walk<V>(&self, visitor: &mut V)
+    where
+        V: ?Sized + Visit,
+    {
+        self.0.accept(visitor);
+        self.1.accept(visitor);
+        self.2.accept(visitor);
+    }
+
+    fn walk_mut<V>(&mut self, visitor: &mut V)
+    where
+        V: ?Sized + VisitMut,
+    {
+        self.0.accept_mut(visitor);
+        self.1.accept_mut(visitor);
+        self.2.accept_mut(visitor);
+    }
+}
+
+impl Walk for ast::Span {}
+impl Walk for ast::Token {}
+impl Walk for ast::LitSource {}
+impl Walk for bool {}
+impl Walk for TokenStream {}
@@ -163,6 +163,8 @@ impl<'a> Build<'a> {
             }
         };
 
+        unit.peephole(options.peephole);
+
         let mut default_visitor;
 
         let visitor = match self.visitor.take() {
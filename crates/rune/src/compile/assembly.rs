@@ -15,6 +15,7 @@ pub(crate) enum AssemblyInst {
     JumpIfBranch { branch: i64, label: Label },
     PopAndJumpIfNot { count: usize, label: Label },
     IterNext { offset: usize, label: Label },
+    TailCall { args: usize, label: Label },
     Raw { raw: Inst },
 }
 
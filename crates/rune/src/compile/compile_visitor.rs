@@ -1,9 +1,13 @@
 use crate::ast::Span;
-use crate::compile::MetaRef;
+use crate::compile::{Item, Location, MetaRef};
+use crate::runtime::Inst;
 use crate::SourceId;
 
 /// A visitor that will be called for every language item compiled.
 pub trait CompileVisitor {
+    /// Called when an item has been indexed, before any of it is compiled.
+    fn visit_item_indexed(&mut self, _location: Location, _item: &Item) {}
+
     /// Called when a meta item is registered.
     fn register_meta(&mut self, _meta: MetaRef<'_>) {}
 
@@ -15,6 +19,27 @@ pub trait CompileVisitor {
 
     /// Visit something that is a module.
     fn visit_mod(&mut self, _source_id: SourceId, _span: Span) {}
+
+    /// Visit the stringified output of a macro call at the given span.
+    ///
+    /// This is only called if [Options::emit_expansions] is enabled.
+    ///
+    /// [Options::emit_expansions]: crate::Options::emit_expansions
+    fn visit_macro_expansion(&mut self, _source_id: SourceId, _span: Span, _expanded: &str) {}
+
+    /// Visit an instruction which has been emitted into the unit, together with
+    /// the span it was compiled from.
+    fn visit_instruction(&mut self, _source_id: SourceId, _span: Span, _inst: &Inst) {}
+
+    /// Called when a function has been compiled, after each of its instructions
+    /// has been passed to [CompileVisitor::visit_instruction].
+    fn visit_function_compiled(
+        &mut self,
+        _location: Location,
+        _item: &Item,
+        _instructions: &[Inst],
+    ) {
+    }
 }
 
 /// A [CompileVisitor] which does nothing.
@@ -1,14 +1,15 @@
 use crate::collections::{HashMap, HashSet};
 use crate::compile::module::{
-    AssocFn, AssocKey, AssocKind, Function, InternalEnum, Macro, Module, ModuleFn, Type,
+    AssocFn, AssocKey, AssocKind, Docs, Function, InternalEnum, Macro, Module, ModuleFn, Type,
     TypeSpecification, UnitType,
 };
 use crate::compile::{
     ComponentRef, IntoComponent, Item, Meta, Names, PrivMeta, PrivMetaKind, StructMeta, TupleMeta,
+    DEFAULT_PRELUDE,
 };
 use crate::runtime::{
-    ConstValue, FunctionHandler, MacroHandler, Protocol, RuntimeContext, StaticType, TypeCheck,
-    TypeInfo, TypeOf, VmError,
+    Capabilities, Capability, ConstValue, FunctionHandler, MacroHandler, Protocol, RuntimeContext,
+    StaticType, TypeCheck, TypeInfo, TypeOf, VmError,
 };
 use crate::{Hash, InstFnKind};
 use std::fmt;
@@ -57,6 +58,8 @@ pub enum ContextError {
     MissingInstance { instance_type: TypeInfo },
     #[error("error when converting to constant value: {error}")]
     ValueError { error: VmError },
+    #[error("module `{item}` requires the missing capability `{capability}`")]
+    MissingCapability { item: Item, capability: Capability },
 }
 
 /// Information on a specific type.
@@ -172,12 +175,16 @@ pub struct Context {
     has_default_modules: bool,
     /// Item metadata in the context.
     meta: HashMap<Item, PrivMeta>,
-    /// Registered native function handlers.
-    functions: HashMap<Hash, Arc<FunctionHandler>>,
+    /// Registered native function handlers, shared with the runtime contexts
+    /// constructed from this context.
+    functions: Arc<HashMap<Hash, Arc<FunctionHandler>>>,
     /// Registered native macro handlers.
     macros: HashMap<Hash, Arc<MacroHandler>>,
-    /// Information on functions.
-    functions_info: HashMap<Hash, ContextSignature>,
+    /// Information on functions, shared with the runtime contexts constructed
+    /// from this context.
+    functions_info: Arc<HashMap<Hash, ContextSignature>>,
+    /// Documentation for functions.
+    docs: HashMap<Hash, Docs>,
     /// Registered types.
     types: HashMap<Hash, ContextTypeInfo>,
     /// Reverse lookup for types.
@@ -189,7 +196,10 @@ pub struct Context {
     /// Registered crates.
     crates: HashSet<Box<str>>,
     /// Constants visible in this context
-    constants: HashMap<Hash, ConstValue>,
+    constants: Arc<HashMap<Hash, ConstValue>>,
+    /// Capabilities which installed modules are allowed to require, or `None`
+    /// if they are unrestricted.
+    capabilities: Option<Capabilities>,
 }
 
 impl Context {
@@ -219,6 +229,7 @@ impl Context {
         this.install(&crate::modules::cmp::module()?)?;
         this.install(&crate::modules::collections::module()?)?;
         this.install(&crate::modules::core::module()?)?;
+        this.install(&crate::modules::error::module()?)?;
         this.install(&crate::modules::float::module()?)?;
         this.install(&crate::modules::fmt::module()?)?;
         this.install(&crate::modules::future::module()?)?;
@@ -227,12 +238,15 @@ impl Context {
         this.install(&crate::modules::io::module(stdio)?)?;
         this.install(&crate::modules::iter::module()?)?;
         this.install(&crate::modules::mem::module()?)?;
+        this.install(&crate::modules::num::module()?)?;
         this.install(&crate::modules::object::module()?)?;
         this.install(&crate::modules::ops::module()?)?;
         this.install(&crate::modules::option::module()?)?;
         this.install(&crate::modules::result::module()?)?;
         this.install(&crate::modules::stream::module()?)?;
         this.install(&crate::modules::string::module()?)?;
+        this.install(&crate::modules::sync::module()?)?;
+        this.install(&crate::modules::sync::channel::module()?)?;
         this.install(&crate::modules::vec::module()?)?;
         this.has_default_modules = true;
         Ok(this)
@@ -245,8 +259,9 @@ impl Context {
 
     /// Construct a runtime context used when executing the virtual machine.
     ///
-    /// This is not a cheap operation, since it requires cloning things out of
-    /// the build-time [Context] which are necessary at runtime.
+    /// This is a cheap operation, since the function and constant tables are
+    /// shared between the build-time [Context] and the runtime contexts
+    /// constructed from it until the context is modified again.
     ///
     /// ```
     /// use rune::{Context, Vm, Unit};
@@ -262,7 +277,29 @@ impl Context {
     /// # Ok(()) }
     /// ```
     pub fn runtime(&self) -> RuntimeContext {
-        RuntimeContext::new(self.functions.clone(), self.constants.clone())
+        RuntimeContext::new(
+            self.functions.clone(),
+            self.constants.clone(),
+            self.functions_info.clone(),
+        )
+    }
+
+    /// Restrict the capabilities which modules installed into the context are
+    /// allowed to require.
+    ///
+    /// Installing a module which requires a capability outside of the given
+    /// set fails with [ContextError::MissingCapability]. Modules which have
+    /// already been installed are not affected.
+    ///
+    /// See [Capabilities] for more.
+    pub fn set_capabilities(&mut self, capabilities: Capabilities) {
+        self.capabilities = Some(capabilities);
+    }
+
+    /// The capabilities which modules installed into the context are allowed
+    /// to require.
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities.unwrap_or_else(Capabilities::all)
     }
 
     /// Install the specified module.
@@ -271,6 +308,19 @@ impl Context {
     /// and ensures that they are compatible with the overall context, like
     /// ensuring that a given type is only declared once.
     pub fn install(&mut self, module: &Module) -> Result<(), ContextError> {
+        let capabilities = self.capabilities();
+
+        if let Some(capability) = module
+            .capabilities()
+            .iter()
+            .find(|capability| !capabilities.contains(*capability))
+        {
+            return Err(ContextError::MissingCapability {
+                item: module.item.clone(),
+                capability,
+            });
+        }
+
         if let Some(ComponentRef::Crate(name)) = module.item.first() {
             self.crates.insert(name.into());
         }
@@ -300,7 +350,7 @@ impl Context {
         }
 
         for (key, inst) in &module.associated_functions {
-            self.install_associated_function(key, inst)?;
+            self.install_associated_function(module, key, inst)?;
         }
 
         Ok(())
@@ -326,13 +376,25 @@ impl Context {
         })
     }
 
+    /// Get the documentation for the function with the given hash, if it was
+    /// registered with any.
+    pub fn docs(&self, hash: Hash) -> Option<&Docs> {
+        self.docs.get(&hash)
+    }
+
+    /// Look up the item of the function or type with the given hash.
+    pub fn item_for_hash(&self, hash: Hash) -> Option<&Item> {
+        if let Some(ContextSignature::Function { item, .. }) = self.functions_info.get(&hash) {
+            return Some(item);
+        }
+
+        Some(&self.types.get(&hash)?.item)
+    }
+
     /// Iterate over known child components of the given name.
-    pub(crate) fn iter_components<'a, I: 'a>(
-        &'a self,
-        iter: I,
-    ) -> impl Iterator<Item = ComponentRef<'a>> + 'a
+    pub fn iter_components<'a, I>(&'a self, iter: I) -> impl Iterator<Item = ComponentRef<'a>> + 'a
     where
-        I: IntoIterator,
+        I: 'a + IntoIterator,
         I::Item: IntoComponent,
     {
         self.names.iter_components(iter)
@@ -364,8 +426,33 @@ impl Context {
         Some(ty.type_check)
     }
 
+    /// Test if the given name is visible in scripts without having to import
+    /// it, either because it's a root item of the context or because it's
+    /// part of the default prelude.
+    pub fn contains_name(&self, name: &str) -> bool {
+        if self.crates.contains(name) || self.names.contains_prefix([name]) {
+            return true;
+        }
+
+        self.iter_prelude().any(|(local, _)| local == name)
+    }
+
+    /// Iterate over the names which are available in every script through the
+    /// prelude, together with the item they refer to.
+    pub fn iter_prelude(&self) -> impl Iterator<Item = (&'static str, Item)> {
+        let prelude = if self.has_default_modules {
+            DEFAULT_PRELUDE
+        } else {
+            &[]
+        };
+
+        prelude
+            .iter()
+            .map(|(local, path)| (*local, Item::with_crate_item("std", *path)))
+    }
+
     /// Check if context contains the given crate.
-    pub(crate) fn contains_crate(&self, name: &str) -> bool {
+    pub fn contains_crate(&self, name: &str) -> bool {
         self.crates.contains(name)
     }
 
@@ -439,7 +526,7 @@ impl Context {
             return Err(ContextError::ConflictingTypeHash { hash, existing });
         }
 
-        self.constants.insert(
+        Arc::make_mut(&mut self.constants).insert(
             Hash::instance_function(info.type_hash, Protocol::INTO_TYPE_NAME),
             ConstValue::String(info.item.to_string()),
         );
@@ -472,19 +559,24 @@ impl Context {
             args: f.args,
         };
 
-        if let Some(old) = self.functions_info.insert(hash, signature) {
+        if let Some(old) = Arc::make_mut(&mut self.functions_info).insert(hash, signature) {
             return Err(ContextError::ConflictingFunction {
                 signature: old,
                 hash,
             });
         }
 
-        self.constants.insert(
+        Arc::make_mut(&mut self.constants).insert(
             Hash::instance_function(hash, Protocol::INTO_TYPE_NAME),
             ConstValue::String(item.to_string()),
         );
 
-        self.functions.insert(hash, f.handler.clone());
+        Arc::make_mut(&mut self.functions).insert(hash, guard(module, &f.handler));
+
+        if !f.docs.is_empty() {
+            self.docs.insert(hash, f.docs);
+        }
+
         self.meta.insert(
             item.clone(),
             PrivMeta {
@@ -527,11 +619,15 @@ impl Context {
     ) -> Result<(), ContextError> {
         let item = module.item.join(item);
 
+        if self.meta.contains_key(&item) {
+            return Err(ContextError::ConflictingConstantName { name: item });
+        }
+
         self.names.insert(&item);
 
         let hash = Hash::type_hash(&item);
 
-        self.constants.insert(hash, v.clone());
+        Arc::make_mut(&mut self.constants).insert(hash, v.clone());
 
         self.meta.insert(
             item.clone(),
@@ -548,6 +644,7 @@ impl Context {
 
     fn install_associated_function(
         &mut self,
+        module: &Module,
         key: &AssocKey,
         assoc: &AssocFn,
     ) -> Result<(), ContextError> {
@@ -577,14 +674,18 @@ impl Context {
             self_type_info: info.type_info.clone(),
         };
 
-        if let Some(old) = self.functions_info.insert(hash, signature) {
+        if let Some(old) = Arc::make_mut(&mut self.functions_info).insert(hash, signature) {
             return Err(ContextError::ConflictingFunction {
                 signature: old,
                 hash,
             });
         }
 
-        self.functions.insert(hash, assoc.handler.clone());
+        Arc::make_mut(&mut self.functions).insert(hash, guard(module, &assoc.handler));
+
+        if !assoc.docs.is_empty() {
+            self.docs.insert(hash, assoc.docs);
+        }
 
         // If the associated function is a named instance function - register it
         // under the name of the item it corresponds to unless it's a field
@@ -598,7 +699,7 @@ impl Context {
             let type_hash = Hash::type_hash(&item);
             let hash = type_hash.with_parameters(key.parameters);
 
-            self.constants.insert(
+            Arc::make_mut(&mut self.constants).insert(
                 Hash::instance_function(hash, Protocol::INTO_TYPE_NAME),
                 ConstValue::String(item.to_string()),
             );
@@ -609,7 +710,11 @@ impl Context {
                 args: assoc.args,
             };
 
-            if let Some(old) = self.functions_info.insert(hash, signature) {
+            if !assoc.docs.is_empty() {
+                self.docs.insert(hash, assoc.docs);
+            }
+
+            if let Some(old) = Arc::make_mut(&mut self.functions_info).insert(hash, signature) {
                 return Err(ContextError::ConflictingFunction {
                     signature: old,
                     hash,
@@ -631,7 +736,7 @@ impl Context {
                 );
             }
 
-            self.functions.insert(hash, assoc.handler.clone());
+            Arc::make_mut(&mut self.functions).insert(hash, assoc.handler.clone());
         }
 
         Ok(())
@@ -726,13 +831,13 @@ impl Context {
                 args: Some(variant.args),
             };
 
-            if let Some(old) = self.functions_info.insert(hash, signature) {
+            if let Some(old) = Arc::make_mut(&mut self.functions_info).insert(hash, signature) {
                 return Err(ContextError::ConflictingFunction {
                     signature: old,
                     hash,
                 });
             }
-            self.functions.insert(hash, variant.constructor.clone());
+            Arc::make_mut(&mut self.functions).insert(hash, variant.constructor.clone());
         }
 
         Ok(())
@@ -777,7 +882,7 @@ impl Context {
         let constructor: Arc<FunctionHandler> =
             Arc::new(move |stack, args| constructor.fn_call(stack, args));
 
-        self.constants.insert(
+        Arc::make_mut(&mut self.constants).insert(
             Hash::instance_function(type_hash, Protocol::INTO_TYPE_NAME),
             ConstValue::String(item.to_string()),
         );
@@ -788,17 +893,34 @@ impl Context {
             args: Some(args),
         };
 
-        if let Some(old) = self.functions_info.insert(hash, signature) {
+        if let Some(old) = Arc::make_mut(&mut self.functions_info).insert(hash, signature) {
             return Err(ContextError::ConflictingFunction {
                 signature: old,
                 hash,
             });
         }
-        self.functions.insert(hash, constructor);
+        Arc::make_mut(&mut self.functions).insert(hash, constructor);
         Ok(())
     }
 }
 
+/// Wrap the handler of a function in a module which requires capabilities, so
+/// that it checks that the calling virtual machine has them.
+fn guard(module: &Module, handler: &Arc<FunctionHandler>) -> Arc<FunctionHandler> {
+    let capabilities = module.capabilities;
+
+    if capabilities.is_empty() {
+        return handler.clone();
+    }
+
+    let handler = handler.clone();
+
+    Arc::new(move |stack, args| {
+        capabilities.check()?;
+        handler(stack, args)
+    })
+}
+
 impl fmt::Debug for Context {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "Context")
@@ -2,7 +2,7 @@ use crate::ast;
 use crate::ast::Spanned;
 use crate::compile::ir;
 use crate::compile::{IrError, IrValue};
-use crate::parse::Resolve;
+use crate::parse::{Opaque, Resolve};
 use crate::query::{BuiltInMacro, BuiltInTemplate, Query};
 use crate::runtime::{Bytes, Shared};
 
@@ -77,6 +77,7 @@ pub(crate) fn expr(ast: &ast::Expr, c: &mut IrCompiler<'_>) -> Result<ir::Ir, Ir
         ast::Expr::If(e) => ir::Ir::new(e.span(), expr_if(e, c)?),
         ast::Expr::Loop(e) => ir::Ir::new(e.span(), expr_loop(e, c)?),
         ast::Expr::While(e) => ir::Ir::new(e.span(), expr_while(e, c)?),
+        ast::Expr::For(e) => ir::Ir::new(e.span(), expr_for(e, c)?),
         ast::Expr::Lit(e) => expr_lit(e, c)?,
         ast::Expr::Block(e) => expr_block(e, c)?,
         ast::Expr::Path(e) => path(e, c)?,
@@ -84,6 +85,16 @@ pub(crate) fn expr(ast: &ast::Expr, c: &mut IrCompiler<'_>) -> Result<ir::Ir, Ir
         ast::Expr::Break(expr_break) => {
             ir::Ir::new(expr_break, ir::IrBreak::compile_ast(expr_break, c)?)
         }
+        ast::Expr::Continue(e) => ir::Ir::new(
+            e.span(),
+            ir::IrContinue {
+                span: e.span(),
+                label: match &e.label {
+                    Some(label) => Some(c.resolve(label)?.into()),
+                    None => None,
+                },
+            },
+        ),
         ast::Expr::MacroCall(macro_call) => {
             let internal_macro = c.q.builtin_macro_for(&*macro_call)?;
 
@@ -139,12 +150,22 @@ fn expr_call(ast: &ast::ExprCall, c: &mut IrCompiler<'_>) -> Result<ir::IrCall,
     }
 
     if let ast::Expr::Path(path) = &*ast.expr {
+        // NB: paths which have been indexed can be resolved from the module
+        // they're used in once the call is evaluated.
+        if path.id().is_set() {
+            return Ok(ir::IrCall {
+                span,
+                target: ir::IrCallTarget::Path(Box::new(path.clone())),
+                args,
+            });
+        }
+
         if let Some(ident) = path.try_as_ident() {
             let target = c.resolve(ident)?;
 
             return Ok(ir::IrCall {
                 span,
-                target: target.into(),
+                target: ir::IrCallTarget::Name(target.into()),
                 args,
             });
         }
@@ -188,11 +209,13 @@ fn expr_binary(ast: &ast::ExprBinary, c: &mut IrCompiler<'_>) -> Result<ir::Ir,
         ast::BinOp::Sub(..) => ir::IrBinaryOp::Sub,
         ast::BinOp::Mul(..) => ir::IrBinaryOp::Mul,
         ast::BinOp::Div(..) => ir::IrBinaryOp::Div,
+        ast::BinOp::Rem(..) => ir::IrBinaryOp::Rem,
         ast::BinOp::Shl(..) => ir::IrBinaryOp::Shl,
         ast::BinOp::Shr(..) => ir::IrBinaryOp::Shr,
         ast::BinOp::Lt(..) => ir::IrBinaryOp::Lt,
         ast::BinOp::Lte(..) => ir::IrBinaryOp::Lte,
         ast::BinOp::Eq(..) => ir::IrBinaryOp::Eq,
+        ast::BinOp::Neq(..) => ir::IrBinaryOp::Neq,
         ast::BinOp::Gt(..) => ir::IrBinaryOp::Gt,
         ast::BinOp::Gte(..) => ir::IrBinaryOp::Gte,
         _ => return Err(IrError::msg(&ast.op, "op not supported yet")),
@@ -390,6 +413,10 @@ fn path(ast: &ast::Path, c: &mut IrCompiler<'_>) -> Result<ir::Ir, IrError> {
         return Ok(ir::Ir::new(span, <Box<str>>::from(name)));
     }
 
+    if ast.id().is_set() {
+        return Ok(ir::Ir::new(span, Box::new(ast.clone())));
+    }
+
     Err(IrError::msg(span, "not supported yet"))
 }
 
@@ -486,3 +513,37 @@ fn expr_loop(ast: &ast::ExprLoop, c: &mut IrCompiler<'_>) -> Result<ir::IrLoop,
         body: block(&ast.body, c)?,
     })
 }
+
+fn expr_for(ast: &ast::ExprFor, c: &mut IrCompiler<'_>) -> Result<ir::IrFor, IrError> {
+    let iter = match &*ast.iter {
+        ast::Expr::Range(range) => {
+            let from = match &range.from {
+                Some(from) => Box::new(expr(from, c)?),
+                None => return Err(IrError::msg(range, "range must have a start")),
+            };
+
+            let to = match &range.to {
+                Some(to) => Some(Box::new(expr(to, c)?)),
+                None => None,
+            };
+
+            ir::IrForIter::Range {
+                from,
+                to,
+                inclusive: matches!(range.limits, ast::ExprRangeLimits::Closed(..)),
+            }
+        }
+        iter => ir::IrForIter::Ir(Box::new(expr(iter, c)?)),
+    };
+
+    Ok(ir::IrFor {
+        span: ast.span(),
+        label: match &ast.label {
+            Some((label, _)) => Some(c.resolve(label)?.into()),
+            None => None,
+        },
+        binding: ir::IrPat::compile_ast(&ast.binding, c)?,
+        iter,
+        body: block(&ast.body, c)?,
+    })
+}
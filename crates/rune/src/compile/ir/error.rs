@@ -1,5 +1,5 @@
 use crate::ast::{Spanned, SpannedError};
-use crate::compile::{IrValue, Meta};
+use crate::compile::{CompileError, CompileErrorKind, IrValue, Meta};
 use crate::parse::{ResolveError, ResolveErrorKind};
 use crate::query::{QueryError, QueryErrorKind};
 use crate::runtime::{AccessError, TypeInfo, TypeOf};
@@ -16,6 +16,7 @@ error! {
     impl From<ResolveError>;
     impl From<QueryError>;
     impl From<ScopeError>;
+    impl From<CompileError>;
 }
 
 impl IrError {
@@ -86,6 +87,14 @@ pub enum IrErrorKind {
         #[from]
         error: ResolveErrorKind,
     },
+    /// A compile error raised while resolving a path.
+    #[error("{error}")]
+    CompileError {
+        /// The source error.
+        #[source]
+        #[from]
+        error: Box<CompileErrorKind>,
+    },
     /// Encountered an expression that is not supported as a constant
     /// expression.
     #[error("expected a constant expression")]
@@ -140,6 +149,9 @@ pub enum IrErrorKind {
     /// Error raised when trying to use a break outside of a loop.
     #[error("break outside of supported loop")]
     BreakOutsideOfLoop,
+    /// Error raised when trying to use a continue outside of a loop.
+    #[error("continue outside of supported loop")]
+    ContinueOutsideOfLoop,
     #[error("function not found")]
     FnNotFound,
     #[error("argument count mismatch, got {actual} but expected {expected}")]
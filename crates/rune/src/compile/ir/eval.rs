@@ -3,7 +3,10 @@ use crate::collections::HashMap;
 use crate::compile::ir;
 use crate::compile::ir::{IrError, IrInterpreter, IrValue};
 use crate::query::Used;
+use crate::runtime as rt;
 use crate::runtime::Shared;
+use crate::shared::ScopeGuard;
+use num::BigInt;
 use std::convert::TryFrom;
 use std::fmt::Write;
 
@@ -22,6 +25,8 @@ pub enum IrEvalOutcome {
     Error(IrError),
     /// Break until the next loop, or the optional label.
     Break(Span, IrEvalBreak),
+    /// Continue the next loop, or the loop with the optional label.
+    Continue(Span, Option<Box<str>>),
 }
 
 impl IrEvalOutcome {
@@ -73,7 +78,8 @@ fn eval_ir_binary(
     interp: &mut IrInterpreter<'_>,
     used: Used,
 ) -> Result<IrValue, IrEvalOutcome> {
-    use std::ops::{Add, Mul, Shl, Shr, Sub};
+    use num::Zero as _;
+    use std::ops::{Add, Mul, Rem, Shl, Shr, Sub};
 
     let span = ir.span();
     interp.budget.take(span)?;
@@ -98,6 +104,13 @@ fn eval_ir_binary(
                     .ok_or_else(|| IrError::msg(span, "division by zero"))?;
                 return Ok(IrValue::Integer(number));
             }
+            ir::IrBinaryOp::Rem => {
+                if b.is_zero() {
+                    return Err(IrError::msg(span, "division by zero").into());
+                }
+
+                return Ok(IrValue::Integer(a.rem(&b)));
+            }
             ir::IrBinaryOp::Shl => {
                 let b = u32::try_from(b)
                     .map_err(|_| IrError::msg(&ir.rhs, "cannot be converted to shift operand"))?;
@@ -115,6 +128,7 @@ fn eval_ir_binary(
             ir::IrBinaryOp::Lt => return Ok(IrValue::Bool(a < b)),
             ir::IrBinaryOp::Lte => return Ok(IrValue::Bool(a <= b)),
             ir::IrBinaryOp::Eq => return Ok(IrValue::Bool(a == b)),
+            ir::IrBinaryOp::Neq => return Ok(IrValue::Bool(a != b)),
             ir::IrBinaryOp::Gt => return Ok(IrValue::Bool(a > b)),
             ir::IrBinaryOp::Gte => return Ok(IrValue::Bool(a >= b)),
         },
@@ -125,9 +139,11 @@ fn eval_ir_binary(
                 ir::IrBinaryOp::Sub => return Ok(IrValue::Float(a - b)),
                 ir::IrBinaryOp::Mul => return Ok(IrValue::Float(a * b)),
                 ir::IrBinaryOp::Div => return Ok(IrValue::Float(a / b)),
+                ir::IrBinaryOp::Rem => return Ok(IrValue::Float(a % b)),
                 ir::IrBinaryOp::Lt => return Ok(IrValue::Bool(a < b)),
                 ir::IrBinaryOp::Lte => return Ok(IrValue::Bool(a <= b)),
                 ir::IrBinaryOp::Eq => return Ok(IrValue::Bool(a == b)),
+                ir::IrBinaryOp::Neq => return Ok(IrValue::Bool(a != b)),
                 ir::IrBinaryOp::Gt => return Ok(IrValue::Bool(a > b)),
                 ir::IrBinaryOp::Gte => return Ok(IrValue::Bool(a >= b)),
                 _ => (),
@@ -229,6 +245,53 @@ fn eval_ir_decl(
     Ok(IrValue::Unit)
 }
 
+/// How to proceed after the body of a loop has been evaluated.
+enum IrLoopControl {
+    /// Proceed with the next iteration.
+    Next,
+    /// Break out of the loop.
+    Break,
+    /// Break out of the loop with a value.
+    BreakValue(Span, IrValue),
+}
+
+/// Evaluate the body of a loop, handling any `break` or `continue` which
+/// targets the loop.
+fn eval_ir_loop_body(
+    label: Option<&str>,
+    body: &ir::IrScope,
+    guard: &ScopeGuard,
+    interp: &mut IrInterpreter<'_>,
+    used: Used,
+) -> Result<IrLoopControl, IrEvalOutcome> {
+    let outcome = match eval_ir_scope(body, interp, used) {
+        Ok(..) => return Ok(IrLoopControl::Next),
+        Err(outcome) => outcome,
+    };
+
+    // NB: the body didn't complete, so any scopes it pushed are still around.
+    interp.scopes.unwind(guard);
+
+    match outcome {
+        IrEvalOutcome::Break(span, b) => match b {
+            IrEvalBreak::Inherent => Ok(IrLoopControl::Break),
+            IrEvalBreak::Label(l) => {
+                if label == Some(&*l) {
+                    return Ok(IrLoopControl::Break);
+                }
+
+                Err(IrEvalOutcome::Break(span, IrEvalBreak::Label(l)))
+            }
+            IrEvalBreak::Value(value) => Ok(IrLoopControl::BreakValue(span, value)),
+        },
+        IrEvalOutcome::Continue(span, l) => match l {
+            Some(l) if label != Some(&*l) => Err(IrEvalOutcome::Continue(span, Some(l))),
+            _ => Ok(IrLoopControl::Next),
+        },
+        outcome => Err(outcome),
+    }
+}
+
 fn eval_ir_loop(
     ir: &ir::IrLoop,
     interp: &mut IrInterpreter<'_>,
@@ -250,32 +313,121 @@ fn eval_ir_loop(
             }
         }
 
-        match eval_ir_scope(&ir.body, interp, used) {
-            Ok(..) => (),
-            Err(outcome) => match outcome {
-                IrEvalOutcome::Break(span, b) => match b {
-                    IrEvalBreak::Inherent => break,
-                    IrEvalBreak::Label(l) => {
-                        if ir.label.as_ref() == Some(&l) {
-                            break;
-                        }
-
-                        return Err(IrEvalOutcome::Break(span, IrEvalBreak::Label(l)));
-                    }
-                    IrEvalBreak::Value(value) => {
-                        if ir.condition.is_none() {
-                            return Ok(value);
-                        }
-
-                        return Err(IrEvalOutcome::from(IrError::msg(
-                            span,
-                            "break with value is not supported for unconditional loops",
-                        )));
-                    }
+        match eval_ir_loop_body(ir.label.as_deref(), &ir.body, &guard, interp, used)? {
+            IrLoopControl::Next => (),
+            IrLoopControl::Break => break,
+            IrLoopControl::BreakValue(span, value) => {
+                if ir.condition.is_none() {
+                    interp.scopes.pop(ir, guard)?;
+                    return Ok(value);
+                }
+
+                return Err(IrEvalOutcome::from(IrError::msg(
+                    span,
+                    "break with value is not supported for unconditional loops",
+                )));
+            }
+        }
+    }
+
+    interp.scopes.pop(ir, guard)?;
+    Ok(IrValue::Unit)
+}
+
+/// The values produced by a for loop.
+enum IrForValues {
+    /// A range of integers with an optional end.
+    Range(BigInt, Option<BigInt>),
+    /// The values of a collection.
+    Values(std::vec::IntoIter<IrValue>),
+}
+
+impl Iterator for IrForValues {
+    type Item = IrValue;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Range(next, end) => {
+                if matches!(end, Some(end) if *next >= *end) {
+                    return None;
+                }
+
+                let value = next.clone();
+                *next += 1;
+                Some(IrValue::Integer(value))
+            }
+            Self::Values(values) => values.next(),
+        }
+    }
+}
+
+fn eval_ir_for_values(
+    iter: &ir::IrForIter,
+    interp: &mut IrInterpreter<'_>,
+    used: Used,
+) -> Result<IrForValues, IrEvalOutcome> {
+    match iter {
+        ir::IrForIter::Range {
+            from,
+            to,
+            inclusive,
+        } => {
+            let from = match eval_ir(from, interp, used)? {
+                IrValue::Integer(n) => n,
+                actual => return Err(IrError::expected::<_, i64>(&**from, &actual).into()),
+            };
+
+            let to = match to {
+                Some(to) => match eval_ir(to, interp, used)? {
+                    IrValue::Integer(n) if *inclusive => Some(n + 1),
+                    IrValue::Integer(n) => Some(n),
+                    actual => return Err(IrError::expected::<_, i64>(&**to, &actual).into()),
                 },
-                outcome => return Err(outcome),
-            },
-        };
+                None => None,
+            };
+
+            Ok(IrForValues::Range(from, to))
+        }
+        ir::IrForIter::Ir(ir) => {
+            let values = match eval_ir(ir, interp, used)? {
+                IrValue::Vec(vec) => vec.borrow_ref().map_err(IrError::access(&**ir))?.clone(),
+                IrValue::Tuple(tuple) => {
+                    tuple.borrow_ref().map_err(IrError::access(&**ir))?.to_vec()
+                }
+                actual => return Err(IrError::expected::<_, rt::Vec>(&**ir, &actual).into()),
+            };
+
+            Ok(IrForValues::Values(values.into_iter()))
+        }
+    }
+}
+
+fn eval_ir_for(
+    ir: &ir::IrFor,
+    interp: &mut IrInterpreter<'_>,
+    used: Used,
+) -> Result<IrValue, IrEvalOutcome> {
+    let span = ir.span();
+    interp.budget.take(span)?;
+
+    let values = eval_ir_for_values(&ir.iter, interp, used)?;
+    let guard = interp.scopes.push();
+
+    for value in values {
+        interp.budget.take(span)?;
+        interp.scopes.clear_current(span)?;
+        ir.binding.matches(interp, value, span)?;
+
+        match eval_ir_loop_body(ir.label.as_deref(), &ir.body, &guard, interp, used)? {
+            IrLoopControl::Next => (),
+            IrLoopControl::Break => break,
+            IrLoopControl::BreakValue(span, ..) => {
+                return Err(IrEvalOutcome::from(IrError::msg(
+                    span,
+                    "break with value is not supported for for loops",
+                )));
+            }
+        }
     }
 
     interp.scopes.pop(ir, guard)?;
@@ -417,10 +569,13 @@ pub(crate) fn eval_ir(
         ir::IrKind::Template(ir) => eval_ir_template(ir, interp, used),
         ir::IrKind::Name(name) => Ok(interp.resolve_var(ir.span(), name.as_ref(), used)?),
         ir::IrKind::Target(target) => Ok(interp.scopes.get_target(target)?),
+        ir::IrKind::Path(path) => Ok(interp.resolve_path(path, used)?),
         ir::IrKind::Value(value) => Ok(value.clone()),
         ir::IrKind::Branches(ir) => eval_ir_branches(ir, interp, used),
         ir::IrKind::Loop(ir) => eval_ir_loop(ir, interp, used),
+        ir::IrKind::For(ir) => eval_ir_for(ir, interp, used),
         ir::IrKind::Break(ir) => Err(ir.as_outcome(interp, used)),
+        ir::IrKind::Continue(ir) => Err(ir.as_outcome(interp)),
         ir::IrKind::Vec(ir) => eval_ir_vec(ir, interp, used),
         ir::IrKind::Tuple(ir) => eval_ir_tuple(ir, interp, used),
         ir::IrKind::Object(ir) => eval_ir_object(ir, interp, used),
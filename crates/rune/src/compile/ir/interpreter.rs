@@ -1,6 +1,8 @@
+use crate::ast;
 use crate::ast::{Span, Spanned};
 use crate::compile::ir;
 use crate::compile::{IrError, IrErrorKind, IrEvalOutcome, IrValue, Item, ModMeta, PrivMetaKind};
+use crate::parse::Id;
 use crate::query::{Query, Used};
 use crate::runtime::{ConstValue, Object, Tuple};
 
@@ -47,6 +49,9 @@ impl IrInterpreter<'_> {
                 IrEvalOutcome::Break(span, _) => {
                     return Err(IrError::new(span, IrErrorKind::BreakOutsideOfLoop))
                 }
+                IrEvalOutcome::Continue(span, _) => {
+                    return Err(IrError::new(span, IrErrorKind::ContinueOutsideOfLoop))
+                }
             },
         };
 
@@ -74,7 +79,47 @@ impl IrInterpreter<'_> {
                 IrEvalOutcome::Break(span, _) => {
                     Err(IrError::new(span, IrErrorKind::BreakOutsideOfLoop))
                 }
+                IrEvalOutcome::Continue(span, _) => {
+                    Err(IrError::new(span, IrErrorKind::ContinueOutsideOfLoop))
+                }
+            },
+        }
+    }
+
+    /// Resolve the constant value referenced by the given path, which might be
+    /// declared in another module.
+    pub(crate) fn resolve_path(
+        &mut self,
+        path: &ast::Path,
+        used: Used,
+    ) -> Result<IrValue, IrError> {
+        let span = path.span();
+        let context = self.q.context;
+        let named = self.q.convert_path(context, path)?;
+
+        if let Some(const_value) = self.q.consts.get(&named.item) {
+            return Ok(IrValue::from_const(const_value));
+        }
+
+        let meta = match self.q.query_meta(span, &named.item, used)? {
+            Some(meta) => Some(meta),
+            None => context.lookup_meta(&named.item),
+        };
+
+        match meta {
+            Some(meta) => match &meta.kind {
+                PrivMetaKind::Const { const_value, .. } => Ok(IrValue::from_const(const_value)),
+                _ => Err(IrError::new(
+                    span,
+                    IrErrorKind::UnsupportedMeta { meta: meta.info() },
+                )),
             },
+            None => Err(IrError::new(
+                span,
+                IrErrorKind::MissingConst {
+                    name: named.item.to_string().into(),
+                },
+            )),
         }
     }
 
@@ -101,7 +146,12 @@ impl IrInterpreter<'_> {
                 return Ok(IrValue::from_const(const_value));
             }
 
-            if let Some(meta) = self.q.query_meta(spanned, &item, used)? {
+            let meta = match self.q.query_meta(spanned, &item, used)? {
+                Some(meta) => Some(meta),
+                None => self.q.context.lookup_meta(&item),
+            };
+
+            if let Some(meta) = meta {
                 match &meta.kind {
                     PrivMetaKind::Const { const_value, .. } => {
                         return Ok(IrValue::from_const(const_value));
@@ -135,10 +185,12 @@ impl IrInterpreter<'_> {
         }
     }
 
+    /// Call the constant function identified by `target` with the given
+    /// arguments.
     pub(crate) fn call_const_fn<S>(
         &mut self,
         spanned: S,
-        target: &str,
+        target: &ir::IrCallTarget,
         args: Vec<IrValue>,
         used: Used,
     ) -> Result<IrValue, IrError>
@@ -146,30 +198,26 @@ impl IrInterpreter<'_> {
         S: Copy + Spanned,
     {
         let span = spanned.span();
-        let mut base = self.item.clone();
-
-        let id = loop {
-            let item = base.extended(target);
 
-            if let Some(meta) = self.q.query_meta(span, &item, used)? {
-                match &meta.kind {
-                    PrivMetaKind::ConstFn { id, .. } => {
-                        break *id;
-                    }
-                    _ => {
-                        return Err(IrError::new(
-                            span,
-                            IrErrorKind::UnsupportedMeta { meta: meta.info() },
-                        ));
-                    }
+        let id = match target {
+            ir::IrCallTarget::Name(name) => self.const_fn_by_name(span, name, used)?,
+            ir::IrCallTarget::Path(path) => {
+                let context = self.q.context;
+                let named = self.q.convert_path(context, path)?;
+
+                match self.q.query_meta(span, &named.item, used)? {
+                    Some(meta) => match &meta.kind {
+                        PrivMetaKind::ConstFn { id, .. } => *id,
+                        _ => {
+                            return Err(IrError::new(
+                                span,
+                                IrErrorKind::UnsupportedMeta { meta: meta.info() },
+                            ));
+                        }
+                    },
+                    None => return Err(IrError::new(spanned, IrErrorKind::FnNotFound)),
                 }
             }
-
-            if base.is_empty() {
-                return Err(IrError::new(spanned, IrErrorKind::FnNotFound));
-            }
-
-            base.pop();
         };
 
         let const_fn = self.q.const_fn_for((spanned.span(), id))?;
@@ -184,16 +232,54 @@ impl IrInterpreter<'_> {
             ));
         }
 
-        let guard = self.scopes.isolate();
+        // NB: the body of the function is evaluated in the item it's declared
+        // in, so that it can refer to items in its own module.
+        let mut interpreter = IrInterpreter {
+            budget: std::mem::take(&mut self.budget),
+            scopes: Default::default(),
+            module: &const_fn.item.module,
+            item: &const_fn.item.item,
+            q: self.q.borrow(),
+        };
 
         for (name, value) in const_fn.ir_fn.args.iter().zip(args) {
-            self.scopes.decl(&**name, value, spanned)?;
+            interpreter.scopes.decl(name, value, spanned)?;
         }
 
-        let value = self.eval_value(&const_fn.ir_fn.ir, used)?;
-        self.scopes.pop(spanned, guard)?;
+        let value = interpreter.eval_value(&const_fn.ir_fn.ir, used)?;
+        self.budget = interpreter.budget;
         Ok(value)
     }
+
+    /// Look up a constant function by name, starting from the current item
+    /// and moving outwards.
+    fn const_fn_by_name(&mut self, span: Span, name: &str, used: Used) -> Result<Id, IrError> {
+        let mut base = self.item.clone();
+
+        loop {
+            let item = base.extended(name);
+
+            if let Some(meta) = self.q.query_meta(span, &item, used)? {
+                match &meta.kind {
+                    PrivMetaKind::ConstFn { id, .. } => {
+                        return Ok(*id);
+                    }
+                    _ => {
+                        return Err(IrError::new(
+                            span,
+                            IrErrorKind::UnsupportedMeta { meta: meta.info() },
+                        ));
+                    }
+                }
+            }
+
+            if base.is_empty() {
+                return Err(IrError::new(span, IrErrorKind::FnNotFound));
+            }
+
+            base.pop();
+        }
+    }
 }
 
 impl IrScopes {
@@ -369,10 +455,17 @@ impl IrScopes {
 }
 
 /// A budget dictating the number of evaluations the compiler is allowed to do.
+#[derive(Debug, Clone)]
 pub(crate) struct IrBudget {
     budget: usize,
 }
 
+impl Default for IrBudget {
+    fn default() -> Self {
+        Self::new(1_000_000)
+    }
+}
+
 impl IrBudget {
     /// Construct a new constant evaluation budget with the given constraint.
     pub(crate) fn new(budget: usize) -> Self {
@@ -45,7 +45,7 @@ impl IrEval for ast::Expr {
         let ir = compile::expr(self, &mut ctx.c)?;
 
         let mut ir_interpreter = IrInterpreter {
-            budget: IrBudget::new(1_000_000),
+            budget: ctx.c.q.const_budget(),
             scopes: Default::default(),
             module: &ctx.item.module,
             item: &ctx.item.item,
@@ -142,14 +142,20 @@ decl_kind! {
         /// A local name. Could either be a local variable or a reference to
         /// something else, like another const declaration.
         Target(IrTarget),
+        /// A path to a constant, which might be declared in another module.
+        Path(Box<ast::Path>),
         /// A constant value.
         Value(IrValue),
         /// A sequence of conditional branches.
         Branches(IrBranches),
         /// A loop.
         Loop(IrLoop),
+        /// A for loop.
+        For(IrFor),
         /// A break to the given target.
         Break(IrBreak),
+        /// A continue to the given target.
+        Continue(IrContinue),
         /// Constructing a vector.
         Vec(IrVec),
         /// Constructing a tuple.
@@ -369,6 +375,38 @@ pub struct IrLoop {
     pub(crate) body: IrScope,
 }
 
+/// A for loop over a range or a collection.
+#[derive(Debug, Clone, Spanned)]
+pub struct IrFor {
+    /// The span of the loop.
+    #[rune(span)]
+    pub(crate) span: Span,
+    /// The label of the loop.
+    pub(crate) label: Option<Box<str>>,
+    /// The pattern every value is bound to.
+    pub(crate) binding: IrPat,
+    /// The values being iterated over.
+    pub(crate) iter: IrForIter,
+    /// The body of the loop.
+    pub(crate) body: IrScope,
+}
+
+/// The values a for loop iterates over.
+#[derive(Debug, Clone)]
+pub enum IrForIter {
+    /// A range of integers, like `0..10` or `0..=10`.
+    Range {
+        /// The start of the range.
+        from: Box<Ir>,
+        /// The optional end of the range.
+        to: Option<Box<Ir>>,
+        /// If the end of the range is inclusive.
+        inclusive: bool,
+    },
+    /// An expression evaluating to a vector or a tuple.
+    Ir(Box<Ir>),
+}
+
 /// A break operation.
 #[derive(Debug, Clone, Spanned)]
 pub struct IrBreak {
@@ -417,6 +455,27 @@ impl IrBreak {
     }
 }
 
+/// A continue operation.
+#[derive(Debug, Clone, Spanned)]
+pub struct IrContinue {
+    /// The span of the continue.
+    #[rune(span)]
+    pub(crate) span: Span,
+    /// The label of the loop to continue.
+    pub(crate) label: Option<Box<str>>,
+}
+
+impl IrContinue {
+    /// Evaluate the continue into an [IrEvalOutcome].
+    fn as_outcome(&self, interp: &mut IrInterpreter<'_>) -> IrEvalOutcome {
+        if let Err(e) = interp.budget.take(self) {
+            return e.into();
+        }
+
+        IrEvalOutcome::Continue(self.span, self.label.clone())
+    }
+}
+
 /// The kind of a break expression.
 #[derive(Debug, Clone)]
 pub enum IrBreakKind {
@@ -455,11 +514,23 @@ pub struct IrCall {
     #[rune(span)]
     pub(crate) span: Span,
     /// The target of the call.
-    pub(crate) target: Box<str>,
+    pub(crate) target: IrCallTarget,
     /// Arguments to the call.
     pub(crate) args: Vec<Ir>,
 }
 
+/// The function being called by an [IrCall].
+#[derive(Debug, Clone)]
+pub enum IrCallTarget {
+    /// A function called by name, which is looked up starting from the item
+    /// being evaluated.
+    Name(Box<str>),
+    /// A function called by path, which is resolved like any other path in the
+    /// module it's used in, allowing for calls to `const fn` items in other
+    /// modules.
+    Path(Box<ast::Path>),
+}
+
 /// Vector expression.
 #[derive(Debug, Clone, Spanned)]
 pub struct IrVec {
@@ -481,6 +552,8 @@ pub enum IrBinaryOp {
     Mul,
     /// Division `/`.
     Div,
+    /// Remainder `%`.
+    Rem,
     /// `<<`.
     Shl,
     /// `>>`.
@@ -491,6 +564,8 @@ pub enum IrBinaryOp {
     Lte,
     /// `==`,
     Eq,
+    /// `!=`,
+    Neq,
     /// `>`,
     Gt,
     /// `>=`,
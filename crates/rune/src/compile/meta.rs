@@ -140,6 +140,10 @@ pub struct SourceMeta {
 pub(crate) struct CaptureMeta {
     /// Identity of the captured variable.
     pub(crate) ident: Box<str>,
+    /// The constant value of the captured variable, if it's known to hold a
+    /// copyable constant at the point where it's captured. Such captures don't
+    /// need to be stored in the environment of the closure.
+    pub(crate) constant: Option<ConstValue>,
 }
 
 /// Metadata about a compiled unit.
@@ -7,10 +7,11 @@ use crate::ast;
 use crate::ast::{Span, Spanned};
 use crate::macros::Storage;
 use crate::parse::Resolve;
-use crate::query::{Build, BuildEntry, Query};
+use crate::query::{Build, BuildEntry, Query, QueryInner};
+use crate::runtime::Call;
 use crate::shared::{Consts, Gen};
 use crate::worker::{LoadFileKind, Task, Worker};
-use crate::{Diagnostics, Sources};
+use crate::{Diagnostics, Hash, Sources};
 
 mod assembly;
 pub(crate) use self::assembly::{Assembly, AssemblyInst};
@@ -37,9 +38,13 @@ pub use self::item::{Component, ComponentRef, IntoComponent, Item};
 mod source_loader;
 pub use self::source_loader::{FileSourceLoader, SourceLoader};
 
+mod peephole;
+
+mod tail_calls;
+
 mod unit_builder;
 pub use self::unit_builder::LinkerError;
-pub(crate) use self::unit_builder::UnitBuilder;
+pub(crate) use self::unit_builder::{UnitBuilder, DEFAULT_PRELUDE};
 
 mod v1;
 
@@ -56,7 +61,9 @@ pub(crate) use self::meta::{
 pub use self::meta::{Meta, MetaKind, MetaRef, SourceMeta};
 
 mod module;
-pub use self::module::{AssocType, InstallWith, Module};
+#[doc(hidden)]
+pub use self::module::FunctionMetaKind;
+pub use self::module::{AssocType, Docs, FunctionMeta, FunctionMetaData, InstallWith, Module};
 
 mod named;
 pub use self::named::Named;
@@ -84,7 +91,7 @@ pub(crate) fn compile(
     let gen = Gen::new();
     let mut consts = Consts::default();
     let mut storage = Storage::default();
-    let mut inner = Default::default();
+    let mut inner = QueryInner::new(options);
 
     // The worker queue.
     let mut worker = Worker::new(
@@ -167,11 +174,13 @@ impl CompileBuildEntry<'_> {
     fn compiler1<'a>(
         &'a mut self,
         location: Location,
+        item: &'a ItemMeta,
         span: Span,
         asm: &'a mut Assembly,
     ) -> self::v1::Assembler<'a> {
         self::v1::Assembler {
             source_id: location.source_id,
+            item,
             context: self.context,
             q: self.q.borrow(),
             asm,
@@ -180,7 +189,24 @@ impl CompileBuildEntry<'_> {
             loops: self::v1::Loops::new(),
             options: self.options,
             diagnostics: self.diagnostics,
+            unfoldable: Default::default(),
+        }
+    }
+
+    /// Report a function which was just added to the unit at the given
+    /// instruction offset to the compile visitor.
+    fn visit_function_compiled(&mut self, location: Location, item: &Item, offset: usize) {
+        let (instructions, debug) = self.q.unit.instructions_since(offset);
+
+        for (inst, debug) in instructions.iter().zip(debug) {
+            self.q
+                .visitor
+                .visit_instruction(debug.source_id, debug.span, inst);
         }
+
+        self.q
+            .visitor
+            .visit_function_compiled(location, item, instructions);
     }
 
     fn compile(mut self, entry: BuildEntry) -> Result<(), CompileError> {
@@ -203,12 +229,17 @@ impl CompileBuildEntry<'_> {
                 let span = f.ast.span();
                 let count = f.ast.args.len();
 
-                let mut c = self.compiler1(location, span, &mut asm);
+                let mut c = self.compiler1(location, &item, span, &mut asm);
                 assemble::fn_from_item_fn(&f.ast, &mut c, false)?;
 
+                if let Call::Immediate = f.call {
+                    tail_calls::rewrite(&mut asm, Hash::type_hash(&item.item), count);
+                }
+
                 if used.is_unused() {
                     self.diagnostics.not_used(location.source_id, span, None);
                 } else {
+                    let offset = self.q.unit.instruction_offset();
                     self.q.unit.new_function(
                         location,
                         item.item.clone(),
@@ -217,6 +248,8 @@ impl CompileBuildEntry<'_> {
                         f.call,
                         args,
                     )?;
+
+                    self.visit_function_compiled(location, &item.item, offset);
                 }
             }
             Build::InstanceFunction(f) => {
@@ -228,8 +261,11 @@ impl CompileBuildEntry<'_> {
                 let span = f.ast.span();
                 let count = f.ast.args.len();
 
-                let mut c = self.compiler1(location, span, &mut asm);
-                let meta = c.lookup_meta(f.instance_span, &f.impl_item)?;
+                let mut c = self.compiler1(location, &item, span, &mut asm);
+                // NB: the impl path is resolved like any other path, so that
+                // it can extend imported and native types.
+                let named = c.q.convert_path(c.context, &f.impl_path)?;
+                let meta = c.lookup_meta(f.instance_span, &named.item)?;
 
                 let type_hash = meta.type_hash_of().ok_or_else(|| {
                     CompileError::expected_meta(span, meta.info(), "instance function")
@@ -242,6 +278,7 @@ impl CompileBuildEntry<'_> {
                 } else {
                     let name = f.ast.name.resolve(resolve_context!(self.q))?;
 
+                    let offset = self.q.unit.instruction_offset();
                     self.q.unit.new_instance_function(
                         location,
                         item.item.clone(),
@@ -252,6 +289,8 @@ impl CompileBuildEntry<'_> {
                         f.call,
                         args,
                     )?;
+
+                    self.visit_function_compiled(location, &item.item, offset);
                 }
             }
             Build::Closure(closure) => {
@@ -264,13 +303,14 @@ impl CompileBuildEntry<'_> {
                     closure.ast.args.as_slice().iter().map(|(a, _)| a),
                 )?;
 
-                let mut c = self.compiler1(location, span, &mut asm);
+                let mut c = self.compiler1(location, &item, span, &mut asm);
                 assemble::closure_from_expr_closure(&closure.ast, &mut c, &closure.captures)?;
 
                 if used.is_unused() {
                     c.diagnostics
                         .not_used(location.source_id, location.span, None);
                 } else {
+                    let offset = self.q.unit.instruction_offset();
                     self.q.unit.new_function(
                         location,
                         item.item.clone(),
@@ -279,6 +319,8 @@ impl CompileBuildEntry<'_> {
                         closure.call,
                         args,
                     )?;
+
+                    self.visit_function_compiled(location, &item.item, offset);
                 }
             }
             Build::AsyncBlock(b) => {
@@ -287,13 +329,14 @@ impl CompileBuildEntry<'_> {
                 let args = b.captures.len();
                 let span = b.ast.span();
 
-                let mut c = self.compiler1(location, span, &mut asm);
+                let mut c = self.compiler1(location, &item, span, &mut asm);
                 assemble::closure_from_block(&b.ast, &mut c, &b.captures)?;
 
                 if used.is_unused() {
                     self.diagnostics
                         .not_used(location.source_id, location.span, None);
                 } else {
+                    let offset = self.q.unit.instruction_offset();
                     self.q.unit.new_function(
                         location,
                         item.item.clone(),
@@ -302,6 +345,8 @@ impl CompileBuildEntry<'_> {
                         b.call,
                         Default::default(),
                     )?;
+
+                    self.visit_function_compiled(location, &item.item, offset);
                 }
             }
             Build::Unused => {
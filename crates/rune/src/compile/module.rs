@@ -7,8 +7,9 @@ use crate::collections::{HashMap, HashSet};
 use crate::compile::{ContextError, IntoComponent, Item, Named};
 use crate::macros::{MacroContext, TokenStream};
 use crate::runtime::{
-    ConstValue, FromValue, FunctionHandler, Future, GeneratorState, MacroHandler, Protocol, Stack,
-    StaticType, ToValue, TypeCheck, TypeInfo, TypeOf, UnsafeFromValue, Value, VmError, VmErrorKind,
+    Capabilities, Capability, ConstValue, FromValue, FunctionHandler, Future, GeneratorState,
+    MacroHandler, Protocol, Stack, StaticType, ToValue, TypeCheck, TypeInfo, TypeOf,
+    UnsafeFromValue, Value, VmError, VmErrorKind,
 };
 use crate::{Hash, InstFnInfo, InstFnKind, InstFnName};
 use std::future;
@@ -143,6 +144,7 @@ pub(crate) struct AssocFn {
     pub(crate) args: Option<usize>,
     pub(crate) type_info: TypeInfo,
     pub(crate) name: InstFnKind,
+    pub(crate) docs: Docs,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -156,6 +158,144 @@ pub(crate) struct AssocKey {
 pub(crate) struct ModuleFn {
     pub(crate) handler: Arc<FunctionHandler>,
     pub(crate) args: Option<usize>,
+    pub(crate) docs: Docs,
+}
+
+/// Documentation for a native function, as captured by the
+/// [function][crate::function] attribute macro.
+#[derive(Debug, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct Docs {
+    /// The names of the arguments of the function, if known.
+    pub args: Option<&'static [&'static str]>,
+    /// The lines of the doc comment of the function.
+    pub lines: &'static [&'static str],
+}
+
+impl Docs {
+    /// Test if there is no documentation.
+    pub fn is_empty(&self) -> bool {
+        self.args.is_none() && self.lines.is_empty()
+    }
+}
+
+/// A function which produces [FunctionMetaData], as generated by the
+/// [function][crate::function] attribute macro.
+///
+/// Register it with [Module::function_meta].
+pub type FunctionMeta = fn() -> FunctionMetaData;
+
+/// Metadata on a native function, as produced by the
+/// [function][crate::function] attribute macro.
+pub struct FunctionMetaData {
+    kind: FunctionMetaKind,
+    name: &'static str,
+    item: Option<&'static [&'static str]>,
+    docs: Docs,
+}
+
+impl FunctionMetaData {
+    #[doc(hidden)]
+    pub fn new(
+        kind: FunctionMetaKind,
+        name: &'static str,
+        args: &'static [&'static str],
+        lines: &'static [&'static str],
+    ) -> Self {
+        Self {
+            kind,
+            name,
+            item: None,
+            docs: Docs {
+                args: Some(args),
+                lines,
+            },
+        }
+    }
+
+    /// Register a free function under the given item instead of its name,
+    /// which is used for associated functions.
+    #[doc(hidden)]
+    pub fn with_item(self, item: &'static [&'static str]) -> Self {
+        Self {
+            item: Some(item),
+            ..self
+        }
+    }
+
+    /// The name of the function.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The documentation of the function.
+    pub fn docs(&self) -> &Docs {
+        &self.docs
+    }
+}
+
+/// The kind of a function described by [FunctionMetaData].
+#[doc(hidden)]
+pub enum FunctionMetaKind {
+    /// A free function.
+    Function {
+        handler: Arc<FunctionHandler>,
+        args: usize,
+    },
+    /// An instance function.
+    Instance {
+        handler: Arc<FunctionHandler>,
+        ty: AssocType,
+        args: usize,
+    },
+}
+
+impl FunctionMetaKind {
+    #[doc(hidden)]
+    pub fn function<Func, Args>(f: Func) -> Self
+    where
+        Func: Function<Args>,
+    {
+        Self::Function {
+            handler: Arc::new(move |stack, args| f.fn_call(stack, args)),
+            args: Func::args(),
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn async_function<Func, Args>(f: Func) -> Self
+    where
+        Func: AsyncFunction<Args>,
+    {
+        Self::Function {
+            handler: Arc::new(move |stack, args| f.fn_call(stack, args)),
+            args: Func::args(),
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn instance<Func, Args>(f: Func) -> Self
+    where
+        Func: InstFn<Args>,
+    {
+        Self::Instance {
+            handler: Arc::new(move |stack, args| f.fn_call(stack, args)),
+            ty: Func::ty(),
+            args: Func::args(),
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn async_instance<Func, Args>(f: Func) -> Self
+    where
+        Func: AsyncInstFn<Args>,
+    {
+        Self::Instance {
+            handler: Arc::new(move |stack, args| f.fn_call(stack, args)),
+            ty: Func::ty(),
+            args: Func::args(),
+        }
+    }
 }
 
 pub(crate) struct Macro {
@@ -184,6 +324,8 @@ pub struct Module {
     pub(crate) unit_type: Option<UnitType>,
     /// Registered generator state type.
     pub(crate) internal_enums: Vec<InternalEnum>,
+    /// Capabilities required by the functions in the module.
+    pub(crate) capabilities: Capabilities,
 }
 
 impl Module {
@@ -225,9 +367,42 @@ impl Module {
             unit_type: None,
             internal_enums: Vec::new(),
             constants: Default::default(),
+            capabilities: Capabilities::none(),
         }
     }
 
+    /// Declare that the functions in this module require the given
+    /// capability.
+    ///
+    /// A [Context][crate::compile::Context] which doesn't have the capability
+    /// refuses to install the module, and a [Vm][crate::Vm] which doesn't have
+    /// it raises an error when one of the functions is called. See
+    /// [Capabilities] for more.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::{Context, Module};
+    /// use rune::runtime::{Capabilities, Capability};
+    ///
+    /// # fn main() -> rune::Result<()> {
+    /// let mut module = Module::with_crate("fs");
+    /// module.require(Capability::Filesystem);
+    ///
+    /// let mut context = Context::new();
+    /// context.set_capabilities(Capabilities::none());
+    /// assert!(context.install(&module).is_err());
+    /// # Ok(()) }
+    /// ```
+    pub fn require(&mut self, capability: Capability) {
+        self.capabilities = self.capabilities.with(capability);
+    }
+
+    /// The capabilities required by the functions in this module.
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
     /// Register a type. Registering a type is mandatory in order to register
     /// instance functions using that type.
     ///
@@ -503,6 +678,7 @@ impl Module {
             ModuleFn {
                 handler: Arc::new(move |stack, args| f.fn_call(stack, args)),
                 args: Some(Func::args()),
+                docs: Docs::default(),
             },
         );
 
@@ -511,16 +687,43 @@ impl Module {
 
     /// Register a constant value, at a crate, module or associated level.
     ///
+    /// Constants can be any value which can be converted into a
+    /// [ConstValue], like numbers, strings, vectors and objects. Scripts
+    /// access them through their path, and they can be used when evaluating
+    /// other constants at compile time.
+    ///
     /// # Examples
     ///
     /// ```
+    /// use rune::{FromValue, Vm};
+    /// use std::sync::Arc;
     ///
     /// # fn main() -> rune::Result<()> {
-    /// let mut module = rune::Module::default();
+    /// let mut module = rune::Module::with_item(&["math"]);
     ///
     /// module.constant(&["TEN"], 10)?; // a global TEN value
     /// module.constant(&["MyType", "TEN"], 10)?; // looks like an associated value
+    /// module.constant(&["PI"], 3.14159)?;
     ///
+    /// let mut context = rune::Context::new();
+    /// context.install(&module)?;
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         const TAU = math::PI * 2.0;
+    ///
+    ///         pub fn main() {
+    ///             (TAU, math::MyType::TEN)
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    /// let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+    ///
+    /// let (tau, ten) = <(f64, i64)>::from_value(vm.call(&["main"], ())?)?;
+    /// assert_eq!(tau, 6.28318);
+    /// assert_eq!(ten, 10);
     /// # Ok(()) }
     /// ```
     pub fn constant<N, V>(&mut self, name: N, value: V) -> Result<(), ContextError>
@@ -602,6 +805,141 @@ impl Module {
             ModuleFn {
                 handler: Arc::new(move |stack, args| f.fn_call(stack, args)),
                 args: Some(Func::args()),
+                docs: Docs::default(),
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Register a function which is passed a reference to the given shared
+    /// state before its arguments.
+    ///
+    /// This gives scripts controlled access to mutable host state, like an
+    /// `Arc<Mutex<T>>`, which the embedder holds on to as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::{Context, Module, Vm};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// #[derive(Default)]
+    /// struct Counter {
+    ///     total: i64,
+    /// }
+    ///
+    /// # fn main() -> rune::Result<()> {
+    /// let counter = Arc::new(Mutex::new(Counter::default()));
+    ///
+    /// let mut module = Module::with_item(&["counter"]);
+    ///
+    /// module.function_with_state(
+    ///     &["add"],
+    ///     counter.clone(),
+    ///     |counter: &Mutex<Counter>, n: i64| {
+    ///         counter.lock().unwrap().total += n;
+    ///     },
+    /// )?;
+    ///
+    /// let mut context = Context::with_default_modules()?;
+    /// context.install(&module)?;
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub fn main() {
+    ///             counter::add(10);
+    ///             counter::add(32);
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    /// let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+    /// vm.call(&["main"], ())?;
+    ///
+    /// assert_eq!(counter.lock().unwrap().total, 42);
+    /// # Ok(()) }
+    /// ```
+    pub fn function_with_state<Func, State, Args, N>(
+        &mut self,
+        name: N,
+        state: Arc<State>,
+        f: Func,
+    ) -> Result<(), ContextError>
+    where
+        Func: StateFunction<State, Args>,
+        State: 'static + Send + Sync,
+        N: IntoIterator,
+        N::Item: IntoComponent,
+    {
+        let name = Item::with_item(name);
+
+        if self.functions.contains_key(&name) {
+            return Err(ContextError::ConflictingFunctionName { name });
+        }
+
+        self.functions.insert(
+            name,
+            ModuleFn {
+                handler: Arc::new(move |stack, args| f.fn_call(&state, stack, args)),
+                args: Some(Func::args()),
+                docs: Docs::default(),
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Register an async function which is passed a clone of the given shared
+    /// state before its arguments.
+    ///
+    /// Since the future returned by the function can outlive the call, it's
+    /// passed the [Arc] itself rather than a reference.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// # fn main() -> rune::Result<()> {
+    /// let log = Arc::new(Mutex::new(Vec::<String>::new()));
+    ///
+    /// let mut module = rune::Module::with_item(&["log"]);
+    ///
+    /// module.async_function_with_state(
+    ///     &["write"],
+    ///     log,
+    ///     |log: Arc<Mutex<Vec<String>>>, line: String| async move {
+    ///         log.lock().unwrap().push(line);
+    ///     },
+    /// )?;
+    /// # Ok(()) }
+    /// ```
+    pub fn async_function_with_state<Func, State, Args, N>(
+        &mut self,
+        name: N,
+        state: Arc<State>,
+        f: Func,
+    ) -> Result<(), ContextError>
+    where
+        Func: AsyncStateFunction<State, Args>,
+        State: 'static + Send + Sync,
+        N: IntoIterator,
+        N::Item: IntoComponent,
+    {
+        let name = Item::with_item(name);
+
+        if self.functions.contains_key(&name) {
+            return Err(ContextError::ConflictingFunctionName { name });
+        }
+
+        self.functions.insert(
+            name,
+            ModuleFn {
+                handler: Arc::new(move |stack, args| f.fn_call(&state, stack, args)),
+                args: Some(Func::args()),
+                docs: Docs::default(),
             },
         );
 
@@ -627,12 +965,89 @@ impl Module {
             ModuleFn {
                 handler: Arc::new(move |stack, args| f(stack, args)),
                 args: None,
+                docs: Docs::default(),
             },
         );
 
         Ok(())
     }
 
+    /// Register a function annotated with the [function][crate::function]
+    /// attribute macro, using the name, arguments and documentation it
+    /// captured.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::Any;
+    ///
+    /// #[derive(Any)]
+    /// struct Counter {
+    ///     value: i64,
+    /// }
+    ///
+    /// impl Counter {
+    ///     /// Get the current value of the counter.
+    ///     #[rune::function]
+    ///     fn get(&self) -> i64 {
+    ///         self.value
+    ///     }
+    /// }
+    ///
+    /// /// Add ten to the given value.
+    /// #[rune::function]
+    /// fn add_ten(value: i64) -> i64 {
+    ///     value + 10
+    /// }
+    ///
+    /// # fn main() -> rune::Result<()> {
+    /// let mut module = rune::Module::default();
+    /// module.ty::<Counter>()?;
+    /// module.function_meta(add_ten)?;
+    /// module.function_meta(Counter::get)?;
+    /// # Ok(()) }
+    /// ```
+    pub fn function_meta(&mut self, meta: FunctionMeta) -> Result<(), ContextError> {
+        let FunctionMetaData {
+            kind,
+            name,
+            item,
+            docs,
+        } = meta();
+
+        match kind {
+            FunctionMetaKind::Function { handler, args } => {
+                let name = match item {
+                    Some(item) => Item::with_item(item),
+                    None => Item::with_item(&[name]),
+                };
+
+                if self.functions.contains_key(&name) {
+                    return Err(ContextError::ConflictingFunctionName { name });
+                }
+
+                self.functions.insert(
+                    name,
+                    ModuleFn {
+                        handler,
+                        args: Some(args),
+                        docs,
+                    },
+                );
+
+                Ok(())
+            }
+            FunctionMetaKind::Instance { handler, ty, args } => self.assoc_fn(
+                name.info(),
+                handler,
+                ty,
+                Some(args),
+                AssocKind::Instance,
+                docs,
+            ),
+        }
+    }
+
     /// Register an instance function.
     ///
     /// # Examples
@@ -677,7 +1092,14 @@ impl Module {
         let handler: Arc<FunctionHandler> = Arc::new(move |stack, args| f.fn_call(stack, args));
         let ty = Func::ty();
         let args = Some(Func::args());
-        self.assoc_fn(name, handler, ty, args, AssocKind::Instance)
+        self.assoc_fn(
+            name,
+            handler,
+            ty,
+            args,
+            AssocKind::Instance,
+            Docs::default(),
+        )
     }
 
     /// Install a protocol function that interacts with the given field.
@@ -695,7 +1117,14 @@ impl Module {
         let handler: Arc<FunctionHandler> = Arc::new(move |stack, args| f.fn_call(stack, args));
         let ty = Func::ty();
         let args = Some(Func::args());
-        self.assoc_fn(name, handler, ty, args, AssocKind::FieldFn(protocol))
+        self.assoc_fn(
+            name,
+            handler,
+            ty,
+            args,
+            AssocKind::FieldFn(protocol),
+            Docs::default(),
+        )
     }
 
     /// Register an instance function.
@@ -734,7 +1163,14 @@ impl Module {
         let handler: Arc<FunctionHandler> = Arc::new(move |stack, args| f.fn_call(stack, args));
         let ty = Func::ty();
         let args = Some(Func::args());
-        self.assoc_fn(name, handler, ty, args, AssocKind::Instance)
+        self.assoc_fn(
+            name,
+            handler,
+            ty,
+            args,
+            AssocKind::Instance,
+            Docs::default(),
+        )
     }
 
     /// Install an associated function.
@@ -745,6 +1181,7 @@ impl Module {
         ty: AssocType,
         args: Option<usize>,
         kind: AssocKind,
+        docs: Docs,
     ) -> Result<(), ContextError> {
         let key = AssocKey {
             type_hash: ty.hash,
@@ -775,6 +1212,7 @@ impl Module {
             args,
             type_info: ty.type_info,
             name: name.kind,
+            docs,
         };
 
         self.associated_functions.insert(key, assoc_fn);
@@ -806,6 +1244,32 @@ pub trait AsyncFunction<Args>: 'static + Send + Sync {
     fn fn_call(&self, stack: &mut Stack, args: usize) -> Result<(), VmError>;
 }
 
+/// Trait used to provide the [function_with_state][Module::function_with_state]
+/// function.
+pub trait StateFunction<State, Args>: 'static + Send + Sync {
+    /// The return type of the function.
+    type Return;
+
+    /// Get the number of arguments.
+    fn args() -> usize;
+
+    /// Perform the vm call.
+    fn fn_call(&self, state: &State, stack: &mut Stack, args: usize) -> Result<(), VmError>;
+}
+
+/// Trait used to provide the
+/// [async_function_with_state][Module::async_function_with_state] function.
+pub trait AsyncStateFunction<State, Args>: 'static + Send + Sync {
+    /// The return type of the function.
+    type Return;
+
+    /// Get the number of arguments.
+    fn args() -> usize;
+
+    /// Perform the vm call.
+    fn fn_call(&self, state: &Arc<State>, stack: &mut Stack, args: usize) -> Result<(), VmError>;
+}
+
 /// Trait used to provide the [inst_fn][Module::inst_fn] function.
 pub trait InstFn<Args>: 'static + Send + Sync {
     /// The type of the instance.
@@ -935,6 +1399,85 @@ macro_rules! impl_register {
             }
         }
 
+        impl<Func, State, Return, $($ty,)*> StateFunction<State, ($($ty,)*)> for Func
+        where
+            Func: 'static + Send + Sync + Fn(&State, $($ty,)*) -> Return,
+            Return: ToValue,
+            $($ty: UnsafeFromValue,)*
+        {
+            type Return = Return;
+
+            fn args() -> usize {
+                $count
+            }
+
+            fn fn_call(&self, state: &State, stack: &mut Stack, args: usize) -> Result<(), VmError> {
+                impl_register!{@check-args $count, args}
+
+                #[allow(unused_mut)]
+                let mut it = stack.drain($count)?;
+                $(let $var = it.next().unwrap();)*
+                drop(it);
+
+                // Safety: We hold a reference to the stack, so we can
+                // guarantee that it won't be modified.
+                #[allow(unused)]
+                let ret = unsafe {
+                    impl_register!{@unsafe-vars $count, $($ty, $var, $num,)*}
+                    let ret = self(state, $(<$ty>::unsafe_coerce($var.0),)*);
+                    impl_register!{@drop-stack-guards $($var),*}
+                    ret
+                };
+
+                impl_register!{@return stack, ret, Return}
+                Ok(())
+            }
+        }
+
+        impl<Func, State, Return, $($ty,)*> AsyncStateFunction<State, ($($ty,)*)> for Func
+        where
+            Func: 'static + Send + Sync + Fn(Arc<State>, $($ty,)*) -> Return,
+            Return: 'static + future::Future,
+            Return::Output: ToValue,
+            $($ty: 'static + UnsafeFromValue,)*
+        {
+            type Return = Return;
+
+            fn args() -> usize {
+                $count
+            }
+
+            fn fn_call(&self, state: &Arc<State>, stack: &mut Stack, args: usize) -> Result<(), VmError> {
+                impl_register!{@check-args $count, args}
+
+                #[allow(unused_mut)]
+                let mut it = stack.drain($count)?;
+                $(let $var = it.next().unwrap();)*
+                drop(it);
+
+                // Safety: Future is owned and will only be called within the
+                // context of the virtual machine, which will provide
+                // exclusive thread-local access to itself while the future is
+                // being polled.
+                #[allow(unused_unsafe)]
+                let ret = unsafe {
+                    impl_register!{@unsafe-vars $count, $($ty, $var, $num,)*}
+
+                    let fut = self(state.clone(), $(<$ty>::unsafe_coerce($var.0),)*);
+
+                    Future::new(async move {
+                        let output = fut.await;
+                        impl_register!{@drop-stack-guards $($var),*}
+                        let value = output.to_value()?;
+                        Ok(value)
+                    })
+                };
+
+                impl_register!{@return stack, ret, Return}
+                Ok(())
+            }
+        }
+
         impl<Func, Return, Instance, $($ty,)*> InstFn<(Instance, $($ty,)*)> for Func
         where
             Func: 'static + Send + Sync + Fn(Instance $(, $ty)*) -> Return,
@@ -22,6 +22,23 @@ pub struct Options {
     pub(crate) macros: bool,
     /// Support (experimental) bytecode caching.
     pub bytecode: bool,
+    /// Report the output of every expanded macro to the compile visitor.
+    pub(crate) emit_expansions: bool,
+    /// Run the peephole optimizer over the instructions of every function.
+    pub(crate) peephole: bool,
+    /// Fold constant expressions in function bodies at compile time.
+    pub(crate) fold_constants: bool,
+    /// The number of operations which can be spent folding a single
+    /// expression.
+    pub(crate) fold_budget: usize,
+    /// Inline small functions at their call sites.
+    pub(crate) inline: bool,
+    /// The largest number of expressions a function can consist of to be
+    /// inlined.
+    pub(crate) inline_threshold: usize,
+    /// The number of operations which can be spent evaluating a single
+    /// constant item or constant function call.
+    pub(crate) const_budget: usize,
 
     /// Compile for and enable test features
     pub cfg_test: bool,
@@ -55,6 +72,48 @@ impl Options {
             Some("bytecode") => {
                 self.bytecode = it.next() != Some("false");
             }
+            Some("emit-expansions") => {
+                self.emit_expansions = it.next() != Some("false");
+            }
+            Some("peephole") => {
+                self.peephole = it.next() != Some("false");
+            }
+            Some("fold-constants") => {
+                self.fold_constants = it.next() != Some("false");
+            }
+            Some("fold-budget") => {
+                self.fold_budget = match it.next().and_then(|n| n.parse().ok()) {
+                    Some(budget) => budget,
+                    None => {
+                        return Err(ParseOptionError {
+                            option: option.into(),
+                        });
+                    }
+                };
+            }
+            Some("inline") => {
+                self.inline = it.next() != Some("false");
+            }
+            Some("inline-threshold") => {
+                self.inline_threshold = match it.next().and_then(|n| n.parse().ok()) {
+                    Some(threshold) => threshold,
+                    None => {
+                        return Err(ParseOptionError {
+                            option: option.into(),
+                        });
+                    }
+                };
+            }
+            Some("const-budget") => {
+                self.const_budget = match it.next().and_then(|n| n.parse().ok()) {
+                    Some(budget) => budget,
+                    None => {
+                        return Err(ParseOptionError {
+                            option: option.into(),
+                        });
+                    }
+                };
+            }
             Some("test") => {
                 self.cfg_test = it.next() != Some("false");
             }
@@ -98,6 +157,61 @@ impl Options {
         self.bytecode = enabled;
     }
 
+    /// Set if the output of every macro expansion should be reported to
+    /// [CompileVisitor::visit_macro_expansion]. Defaults to `false`.
+    ///
+    /// [CompileVisitor::visit_macro_expansion]: crate::compile::CompileVisitor::visit_macro_expansion
+    pub fn emit_expansions(&mut self, enabled: bool) {
+        self.emit_expansions = enabled;
+    }
+
+    /// Set if the peephole optimizer should run over the instructions of every
+    /// function. Defaults to `true`.
+    pub fn peephole(&mut self, enabled: bool) {
+        self.peephole = enabled;
+    }
+
+    /// Set if constant expressions in function bodies, like `60 * 60 * 24`,
+    /// should be folded into a single value at compile time. Defaults to
+    /// `true`.
+    ///
+    /// Expressions which would fail at runtime, like integer overflow or
+    /// division by zero, are left to be evaluated at runtime.
+    pub fn fold_constants(&mut self, enabled: bool) {
+        self.fold_constants = enabled;
+    }
+
+    /// Set the number of operations which can be spent folding a single
+    /// constant expression. Defaults to `1000`.
+    pub fn fold_budget(&mut self, budget: usize) {
+        self.fold_budget = budget;
+    }
+
+    /// Set if calls to small functions should be inlined at their call sites.
+    /// Defaults to `false`.
+    ///
+    /// Only functions whose body is a single expression operating on its
+    /// arguments and literals, like `fn area(w, h) { w * h }`, are inlined.
+    pub fn inline(&mut self, enabled: bool) {
+        self.inline = enabled;
+    }
+
+    /// Set the largest number of expressions a function can consist of to be
+    /// inlined. Defaults to `8`.
+    pub fn inline_threshold(&mut self, threshold: usize) {
+        self.inline_threshold = threshold;
+    }
+
+    /// Set the number of operations which can be spent evaluating a single
+    /// constant item, like `const TABLE = ...;`, or a call to a `const fn`.
+    /// Defaults to `1000000`.
+    ///
+    /// Loops in constant contexts spend from this budget on every iteration, so
+    /// it needs to be raised to compute large tables at compile time.
+    pub fn const_budget(&mut self, budget: usize) {
+        self.const_budget = budget;
+    }
+
     /// Memoize the instance function in a loop. Defaults to `false`.
     pub fn memoize_instance_fn(&mut self, enabled: bool) {
         self.memoize_instance_fn = enabled;
@@ -112,6 +226,13 @@ impl Default for Options {
             debug_info: true,
             macros: true,
             bytecode: false,
+            emit_expansions: false,
+            peephole: true,
+            fold_constants: true,
+            fold_budget: 1000,
+            inline: false,
+            inline_threshold: 8,
+            const_budget: 1_000_000,
             cfg_test: false,
             v2: false,
         }
@@ -0,0 +1,214 @@
+//! A peephole optimizer which runs over the instructions of a single function
+//! before they're added to the unit.
+//!
+//! Since this runs before labels have been translated into offsets,
+//! instructions can be removed freely as long as the labels are moved along
+//! with them.
+
+use crate::collections::HashSet;
+use crate::compile::{Assembly, AssemblyInst};
+use crate::runtime::{Inst, Label};
+
+/// The maximum number of jumps to follow when threading a jump, which guards
+/// against jumps which form a cycle.
+const MAX_THREADING: usize = 16;
+
+/// Optimize the given assembly until it no longer changes.
+pub(crate) fn optimize(asm: &mut Assembly) {
+    loop {
+        let mut changed = thread_jumps(asm);
+        changed |= remove(asm, jumps_to_next);
+        changed |= remove(asm, dead_code);
+        changed |= remove(asm, push_pop);
+
+        if !changed {
+            break;
+        }
+    }
+}
+
+/// Retarget jumps which lead to an unconditional jump to wherever that jump
+/// leads.
+fn thread_jumps(asm: &mut Assembly) -> bool {
+    let mut changed = false;
+
+    for n in 0..asm.instructions.len() {
+        let mut label = match label(&asm.instructions[n].0) {
+            Some(label) => label,
+            None => continue,
+        };
+
+        for _ in 0..MAX_THREADING {
+            match target(asm, label) {
+                Some(AssemblyInst::Jump { label: next }) if *next != label => {
+                    label = *next;
+                }
+                _ => break,
+            }
+        }
+
+        if let Some(current) = label_mut(&mut asm.instructions[n].0) {
+            if *current != label {
+                *current = label;
+                changed = true;
+            }
+        }
+    }
+
+    changed
+}
+
+/// Remove unconditional jumps to the next instruction.
+fn jumps_to_next(asm: &Assembly, _: &HashSet<usize>, keep: &mut [bool]) {
+    for (n, (inst, _)) in asm.instructions.iter().enumerate() {
+        if let AssemblyInst::Jump { label } = inst {
+            if asm.labels.get(label) == Some(&(n + 1)) {
+                keep[n] = false;
+            }
+        }
+    }
+}
+
+/// Remove instructions following an unconditional jump, tail call, return, or panic
+/// which can't be jumped to.
+fn dead_code(asm: &Assembly, targets: &HashSet<usize>, keep: &mut [bool]) {
+    let mut dead = false;
+
+    for (n, (inst, _)) in asm.instructions.iter().enumerate() {
+        if targets.contains(&n) {
+            dead = false;
+        }
+
+        if dead {
+            keep[n] = false;
+            continue;
+        }
+
+        dead = matches!(
+            inst,
+            AssemblyInst::Jump { .. }
+                | AssemblyInst::TailCall { .. }
+                | AssemblyInst::Raw {
+                    raw: Inst::Return { .. } | Inst::ReturnUnit | Inst::Panic { .. },
+                }
+        );
+    }
+}
+
+/// Remove values which are pushed to the stack only to be immediately popped.
+fn push_pop(asm: &Assembly, targets: &HashSet<usize>, keep: &mut [bool]) {
+    let mut n = 0;
+
+    while n + 1 < asm.instructions.len() {
+        let pure = matches!(
+            &asm.instructions[n].0,
+            AssemblyInst::Raw {
+                raw: Inst::Push { .. }
+                    | Inst::Copy { .. }
+                    | Inst::Dup
+                    | Inst::String { .. }
+                    | Inst::Bytes { .. }
+                    | Inst::StaticTuple { .. },
+            }
+        );
+
+        if pure && !targets.contains(&(n + 1)) {
+            if let AssemblyInst::Raw { raw: Inst::Pop } = &asm.instructions[n + 1].0 {
+                keep[n] = false;
+                keep[n + 1] = false;
+                n += 2;
+                continue;
+            }
+        }
+
+        n += 1;
+    }
+}
+
+/// Run a pass which marks instructions to remove, and remove them while
+/// moving labels and comments along with the instructions that remain.
+fn remove(asm: &mut Assembly, pass: fn(&Assembly, &HashSet<usize>, &mut [bool])) -> bool {
+    let targets = asm
+        .instructions
+        .iter()
+        .filter_map(|(inst, _)| asm.labels.get(&label(inst)?).copied())
+        .collect::<HashSet<_>>();
+
+    let mut keep = vec![true; asm.instructions.len()];
+    pass(asm, &targets, &mut keep);
+
+    if keep.iter().all(|keep| *keep) {
+        return false;
+    }
+
+    // The new position of every instruction, and the end of the assembly.
+    let mut positions = Vec::with_capacity(keep.len() + 1);
+    let mut position = 0;
+
+    for keep in &keep {
+        positions.push(position);
+
+        if *keep {
+            position += 1;
+        }
+    }
+
+    positions.push(position);
+
+    for offset in asm.labels.values_mut() {
+        *offset = positions[*offset];
+    }
+
+    asm.labels_rev = asm
+        .labels_rev
+        .drain()
+        .map(|(offset, label)| (positions[offset], label))
+        .collect();
+
+    asm.comments = asm
+        .comments
+        .drain()
+        .filter(|(offset, _)| keep.get(*offset).copied().unwrap_or(true))
+        .map(|(offset, comments)| (positions[offset], comments))
+        .collect();
+
+    let mut keep = keep.into_iter();
+    asm.instructions.retain(|_| keep.next().unwrap_or(true));
+    true
+}
+
+/// The instruction a label points to.
+fn target(asm: &Assembly, label: Label) -> Option<&AssemblyInst> {
+    let offset = *asm.labels.get(&label)?;
+    Some(&asm.instructions.get(offset)?.0)
+}
+
+/// The label an instruction jumps to.
+fn label(inst: &AssemblyInst) -> Option<Label> {
+    match *inst {
+        AssemblyInst::Jump { label }
+        | AssemblyInst::JumpIf { label }
+        | AssemblyInst::JumpIfOrPop { label }
+        | AssemblyInst::JumpIfNotOrPop { label }
+        | AssemblyInst::JumpIfBranch { label, .. }
+        | AssemblyInst::PopAndJumpIfNot { label, .. }
+        | AssemblyInst::IterNext { label, .. }
+        | AssemblyInst::TailCall { label, .. } => Some(label),
+        AssemblyInst::Raw { .. } => None,
+    }
+}
+
+/// Access the label an instruction jumps to mutably.
+fn label_mut(inst: &mut AssemblyInst) -> Option<&mut Label> {
+    match inst {
+        AssemblyInst::Jump { label }
+        | AssemblyInst::JumpIf { label }
+        | AssemblyInst::JumpIfOrPop { label }
+        | AssemblyInst::JumpIfNotOrPop { label }
+        | AssemblyInst::JumpIfBranch { label, .. }
+        | AssemblyInst::PopAndJumpIfNot { label, .. }
+        | AssemblyInst::IterNext { label, .. }
+        | AssemblyInst::TailCall { label, .. } => Some(label),
+        AssemblyInst::Raw { .. } => None,
+    }
+}
@@ -0,0 +1,77 @@
+//! Rewrite calls a function makes to itself in tail position into jumps back
+//! to the start of the function which reuse its call frame.
+//!
+//! A call is in tail position if the only thing which happens to its result is
+//! that it's returned, possibly after jumping around or cleaning up locals.
+
+use crate::compile::{Assembly, AssemblyInst};
+use crate::runtime::{Inst, InstAddress, Label};
+use crate::Hash;
+
+/// The maximum number of instructions to follow when looking for the return
+/// which makes a call a tail call.
+const MAX_CONTINUATION: usize = 16;
+
+/// Rewrite tail calls to the function identified by `hash` taking `args`
+/// arguments, which has been assembled into `asm`.
+pub(crate) fn rewrite(asm: &mut Assembly, hash: Hash, args: usize) {
+    let mut entry = None;
+
+    for n in 0..asm.instructions.len() {
+        match &asm.instructions[n].0 {
+            AssemblyInst::Raw {
+                raw:
+                    Inst::Call {
+                        hash: call,
+                        args: count,
+                    },
+            } if *call == hash && *count == args => {}
+            _ => continue,
+        }
+
+        if !returns(asm, n + 1) {
+            continue;
+        }
+
+        let label = *entry.get_or_insert_with(|| entry_label(asm));
+        asm.instructions[n].0 = AssemblyInst::TailCall { args, label };
+    }
+}
+
+/// Test if the instructions starting at `n` return the value on top of the
+/// stack without doing anything else with it.
+fn returns(asm: &Assembly, mut n: usize) -> bool {
+    for _ in 0..MAX_CONTINUATION {
+        match asm.instructions.get(n).map(|(inst, _)| inst) {
+            Some(AssemblyInst::Jump { label }) => match asm.labels.get(label) {
+                Some(offset) => n = *offset,
+                None => return false,
+            },
+            Some(AssemblyInst::Raw {
+                raw: Inst::Clean { .. },
+            }) => n += 1,
+            Some(AssemblyInst::Raw {
+                raw:
+                    Inst::Return {
+                        address: InstAddress::Top,
+                        ..
+                    },
+            }) => return true,
+            _ => return false,
+        }
+    }
+
+    false
+}
+
+/// Get the label at the start of the function, or construct one.
+fn entry_label(asm: &mut Assembly) -> Label {
+    if let Some(label) = asm.labels_rev.get(&0) {
+        return *label;
+    }
+
+    let label = asm.new_label("entry");
+    asm.labels.insert(label, 0);
+    asm.labels_rev.insert(0, label);
+    label
+}
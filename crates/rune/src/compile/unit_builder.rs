@@ -5,12 +5,14 @@
 
 use crate::ast::Span;
 use crate::collections::HashMap;
+use crate::compile::peephole;
 use crate::compile::{
     Assembly, AssemblyInst, CompileError, CompileErrorKind, IntoComponent, Item, Location,
     PrivMeta, PrivMetaKind,
 };
 use crate::query::{QueryError, QueryErrorKind};
 use crate::runtime::debug::{DebugArgs, DebugSignature};
+use crate::runtime::link;
 use crate::runtime::{
     Call, ConstValue, DebugInfo, DebugInst, Inst, Label, Protocol, Rtti, StaticString, Unit,
     UnitFn, VariantRtti,
@@ -31,6 +33,40 @@ pub enum LinkerError {
     },
 }
 
+/// The default prelude, mapping names available in every script to the item
+/// in the `std` crate they refer to.
+pub(crate) const DEFAULT_PRELUDE: &[(&str, &[&str])] = &[
+    ("assert_eq", &["test", "assert_eq"]),
+    ("assert", &["test", "assert"]),
+    ("bool", &["bool"]),
+    ("byte", &["byte"]),
+    ("char", &["char"]),
+    ("dbg", &["io", "dbg"]),
+    ("drop", &["mem", "drop"]),
+    ("Err", &["result", "Result", "Err"]),
+    ("file", &["macros", "builtin", "file"]),
+    ("float", &["float"]),
+    ("format", &["fmt", "format"]),
+    ("globals", &["globals"]),
+    ("int", &["int"]),
+    ("is_readable", &["is_readable"]),
+    ("is_writable", &["is_writable"]),
+    ("line", &["macros", "builtin", "line"]),
+    ("None", &["option", "Option", "None"]),
+    ("Object", &["object", "Object"]),
+    ("Ok", &["result", "Result", "Ok"]),
+    ("Option", &["option", "Option"]),
+    ("panic", &["panic"]),
+    ("print", &["io", "print"]),
+    ("println", &["io", "println"]),
+    ("Result", &["result", "Result"]),
+    ("Some", &["option", "Option", "Some"]),
+    ("String", &["string", "String"]),
+    ("stringify", &["stringify"]),
+    ("unit", &["unit"]),
+    ("Vec", &["vec", "Vec"]),
+];
+
 /// Instructions from a single source file.
 #[derive(Debug, Default)]
 pub(crate) struct UnitBuilder {
@@ -61,6 +97,10 @@ pub(crate) struct UnitBuilder {
     static_object_keys: Vec<Box<[String]>>,
     /// Used to detect duplicates in the collection of static object keys.
     static_object_keys_rev: HashMap<Hash, usize>,
+    /// Constant tuples.
+    static_tuples: Vec<Box<[ConstValue]>>,
+    /// Reverse lookup for constant tuples.
+    static_tuples_rev: HashMap<Hash, usize>,
     /// Runtime type information for types.
     rtti: HashMap<Hash, Arc<Rtti>>,
     /// Runtime type information for variants.
@@ -73,6 +113,8 @@ pub(crate) struct UnitBuilder {
     debug: Option<Box<DebugInfo>>,
     /// Constant values
     constants: HashMap<Hash, ConstValue>,
+    /// Run the peephole optimizer over added assemblies.
+    peephole: bool,
 }
 
 impl UnitBuilder {
@@ -80,38 +122,18 @@ impl UnitBuilder {
     pub(crate) fn with_default_prelude() -> Self {
         let mut this = Self::default();
 
-        this.add_prelude("assert_eq", &["test", "assert_eq"]);
-        this.add_prelude("assert", &["test", "assert"]);
-        this.add_prelude("bool", &["bool"]);
-        this.add_prelude("byte", &["byte"]);
-        this.add_prelude("char", &["char"]);
-        this.add_prelude("dbg", &["io", "dbg"]);
-        this.add_prelude("drop", &["mem", "drop"]);
-        this.add_prelude("Err", &["result", "Result", "Err"]);
-        this.add_prelude("file", &["macros", "builtin", "file"]);
-        this.add_prelude("float", &["float"]);
-        this.add_prelude("format", &["fmt", "format"]);
-        this.add_prelude("int", &["int"]);
-        this.add_prelude("is_readable", &["is_readable"]);
-        this.add_prelude("is_writable", &["is_writable"]);
-        this.add_prelude("line", &["macros", "builtin", "line"]);
-        this.add_prelude("None", &["option", "Option", "None"]);
-        this.add_prelude("Object", &["object", "Object"]);
-        this.add_prelude("Ok", &["result", "Result", "Ok"]);
-        this.add_prelude("Option", &["option", "Option"]);
-        this.add_prelude("panic", &["panic"]);
-        this.add_prelude("print", &["io", "print"]);
-        this.add_prelude("println", &["io", "println"]);
-        this.add_prelude("Result", &["result", "Result"]);
-        this.add_prelude("Some", &["option", "Option", "Some"]);
-        this.add_prelude("String", &["string", "String"]);
-        this.add_prelude("stringify", &["stringify"]);
-        this.add_prelude("unit", &["unit"]);
-        this.add_prelude("Vec", &["vec", "Vec"]);
+        for (local, path) in DEFAULT_PRELUDE {
+            this.add_prelude(local, *path);
+        }
 
         this
     }
 
+    /// Set if the peephole optimizer should run over added assemblies.
+    pub(crate) fn peephole(&mut self, enabled: bool) {
+        self.peephole = enabled;
+    }
+
     /// Clone the prelude.
     pub(crate) fn prelude(&self) -> &HashMap<Box<str>, Item> {
         &self.prelude
@@ -157,12 +179,15 @@ impl UnitBuilder {
             ));
         }
 
+        link::specialize_calls(&mut self.instructions, &self.functions);
+
         Ok(Unit::new(
             self.instructions,
             self.functions,
             self.static_strings,
             self.static_bytes,
             self.static_object_keys,
+            self.static_tuples,
             self.rtti,
             self.variant_rtti,
             self.debug,
@@ -179,8 +204,7 @@ impl UnitBuilder {
         span: Span,
         current: &str,
     ) -> Result<usize, CompileError> {
-        let current = StaticString::new(current);
-        let hash = current.hash();
+        let hash = Hash::of(current);
 
         if let Some(existing_slot) = self.static_string_rev.get(&hash).copied() {
             let existing = self.static_strings.get(existing_slot).ok_or_else(|| {
@@ -193,12 +217,12 @@ impl UnitBuilder {
                 )
             })?;
 
-            if ***existing != *current {
+            if existing.as_str() != current {
                 return Err(CompileError::new(
                     span,
                     CompileErrorKind::StaticStringHashConflict {
                         hash,
-                        current: (*current).clone(),
+                        current: current.to_owned(),
                         existing: (***existing).clone(),
                     },
                 ));
@@ -208,7 +232,8 @@ impl UnitBuilder {
         }
 
         let new_slot = self.static_strings.len();
-        self.static_strings.push(Arc::new(current));
+        self.static_strings
+            .push(Arc::new(StaticString::new(current)));
         self.static_string_rev.insert(hash, new_slot);
         Ok(new_slot)
     }
@@ -314,8 +339,31 @@ impl UnitBuilder {
         Ok(new_slot)
     }
 
+    /// Insert a constant tuple and return its associated slot that can later
+    /// be looked up through [lookup_tuple][Unit::lookup_tuple].
+    ///
+    /// Only uses up space if the tuple is unique.
+    pub(crate) fn new_static_tuple(&mut self, current: &[ConstValue]) -> usize {
+        let hash = Hash::of(ConstKey(current));
+
+        if let Some(existing_slot) = self.static_tuples_rev.get(&hash).copied() {
+            if let Some(existing) = self.static_tuples.get(existing_slot) {
+                if &existing[..] == current {
+                    return existing_slot;
+                }
+            }
+        }
+
+        let new_slot = self.static_tuples.len();
+        self.static_tuples.push(current.into());
+        self.static_tuples_rev.entry(hash).or_insert(new_slot);
+        new_slot
+    }
+
     /// Declare a new struct.
     pub(crate) fn insert_meta(&mut self, span: Span, meta: &PrivMeta) -> Result<(), QueryError> {
+        self.insert_debug_item(Hash::type_hash(&meta.item.item), &meta.item.item);
+
         // TODO: Can someone deduplicate this?
         match &meta.kind {
             PrivMetaKind::Unknown { .. } => {
@@ -543,6 +591,24 @@ impl UnitBuilder {
         Ok(())
     }
 
+    /// The offset at which the next instruction will be emitted.
+    pub(crate) fn instruction_offset(&self) -> usize {
+        self.instructions.len()
+    }
+
+    /// Access the instructions emitted since the given offset, together with
+    /// their debug information.
+    pub(crate) fn instructions_since(&self, offset: usize) -> (&[Inst], &[DebugInst]) {
+        let instructions = self.instructions.get(offset..).unwrap_or_default();
+
+        let debug = match &self.debug {
+            Some(debug) => debug.instructions.get(offset..).unwrap_or_default(),
+            None => &[],
+        };
+
+        (instructions, debug)
+    }
+
     /// Construct a new empty assembly associated with the current unit.
     pub(crate) fn new_assembly(&self, location: Location) -> Assembly {
         Assembly::new(location, self.label_count)
@@ -685,15 +751,32 @@ impl UnitBuilder {
             .insert(local.into(), Item::with_crate_item("std", path));
     }
 
+    /// Register the item a hash corresponds to, so that it can be looked up
+    /// through [DebugInfo::item_for_hash].
+    pub(crate) fn insert_debug_item(&mut self, hash: Hash, item: &Item) {
+        self.debug_info_mut()
+            .hash_to_item
+            .entry(hash)
+            .or_insert_with(|| item.clone());
+    }
+
     /// Insert and access debug information.
     fn debug_info_mut(&mut self) -> &mut DebugInfo {
         self.debug.get_or_insert_with(Default::default)
     }
 
     /// Translate the given assembly into instructions.
-    fn add_assembly(&mut self, location: Location, assembly: Assembly) -> Result<(), CompileError> {
+    fn add_assembly(
+        &mut self,
+        location: Location,
+        mut assembly: Assembly,
+    ) -> Result<(), CompileError> {
         self.label_count = assembly.label_count;
 
+        if self.peephole {
+            peephole::optimize(&mut assembly);
+        }
+
         self.required_functions.extend(assembly.required_functions);
 
         for (pos, (inst, span)) in assembly.instructions.into_iter().enumerate() {
@@ -738,6 +821,11 @@ impl UnitBuilder {
                     let jump = translate_offset(span, pos, label, &assembly.labels)?;
                     self.instructions.push(Inst::IterNext { offset, jump });
                 }
+                AssemblyInst::TailCall { args, label } => {
+                    comment = Some(format!("label:{}", label).into());
+                    let offset = translate_offset(span, pos, label, &assembly.labels)?;
+                    self.instructions.push(Inst::TailCall { args, offset });
+                }
                 AssemblyInst::Raw { raw } => {
                     self.instructions.push(raw);
                 }
@@ -787,3 +875,49 @@ impl UnitBuilder {
         }
     }
 }
+
+/// Hashes constant values for the purpose of deduplicating them.
+struct ConstKey<'a>(&'a [ConstValue]);
+
+impl std::hash::Hash for ConstKey<'_> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.len().hash(state);
+
+        for value in self.0 {
+            hash_const(value, state);
+        }
+
+        fn hash_const<H: std::hash::Hasher>(value: &ConstValue, state: &mut H) {
+            std::mem::discriminant(value).hash(state);
+
+            match value {
+                ConstValue::Unit => (),
+                ConstValue::Byte(b) => b.hash(state),
+                ConstValue::Char(c) => c.hash(state),
+                ConstValue::Bool(b) => b.hash(state),
+                ConstValue::Integer(n) => n.hash(state),
+                ConstValue::Float(n) => n.to_bits().hash(state),
+                ConstValue::String(s) => s.hash(state),
+                ConstValue::StaticString(s) => s.as_str().hash(state),
+                ConstValue::Bytes(b) => b.hash(state),
+                ConstValue::Vec(values) => ConstKey(values).hash(state),
+                ConstValue::Tuple(values) => ConstKey(values).hash(state),
+                ConstValue::Object(object) => {
+                    let mut entries = object.iter().collect::<Vec<_>>();
+                    entries.sort_by_key(|e| e.0);
+                    entries.len().hash(state);
+
+                    for (key, value) in entries {
+                        key.hash(state);
+                        hash_const(value, state);
+                    }
+                }
+                ConstValue::Option(option) => {
+                    if let Some(value) = option {
+                        hash_const(value, state);
+                    }
+                }
+            }
+        }
+    }
+}
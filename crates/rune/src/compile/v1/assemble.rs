@@ -3,11 +3,12 @@ use crate::ast::{Span, Spanned};
 use crate::collections::{HashMap, HashSet};
 use crate::compile::v1::{Assembler, Loop, Needs, Scope, Var};
 use crate::compile::{
-    CaptureMeta, CompileError, CompileErrorKind, CompileResult, Item, PrivMeta, PrivMetaKind,
+    ir, CaptureMeta, CompileError, CompileErrorKind, CompileResult, IrBudget, IrInterpreter,
+    IrValue, Item, PrivMeta, PrivMetaKind,
 };
 use crate::hash::ParametersBuilder;
 use crate::parse::{Id, ParseErrorKind, Resolve};
-use crate::query::{BuiltInFormat, BuiltInTemplate, Named};
+use crate::query::{BuiltInFormat, BuiltInTemplate, Named, QueryInlineFn, Used};
 use crate::runtime::{
     ConstValue, Inst, InstAddress, InstAssignOp, InstOp, InstRangeLimits, InstTarget, InstValue,
     InstVariant, Label, PanicReason, Protocol, TypeCheck,
@@ -15,6 +16,7 @@ use crate::runtime::{
 use crate::Hash;
 use rune_macros::__instrument_ast as instrument;
 use std::convert::TryFrom;
+use std::sync::Arc;
 
 /// `self` variable.
 const SELF: &str = "self";
@@ -88,6 +90,9 @@ fn meta(
     needs: Needs,
     named: Named<'_>,
 ) -> CompileResult<()> {
+    c.q.unit
+        .insert_debug_item(Hash::type_hash(&meta.item.item), &meta.item.item);
+
     if let Needs::Value = needs {
         match &meta.kind {
             PrivMetaKind::UnitStruct { empty, .. } => {
@@ -1091,11 +1096,8 @@ fn const_(
             c.asm.push(Inst::Vec { count: vec.len() }, span);
         }
         ConstValue::Tuple(tuple) => {
-            for value in tuple.iter() {
-                const_(span, c, value, Needs::Value)?;
-            }
-
-            c.asm.push(Inst::Tuple { count: tuple.len() }, span);
+            let slot = c.q.unit.new_static_tuple(tuple);
+            c.asm.push(Inst::StaticTuple { slot }, span);
         }
         ConstValue::Object(object) => {
             let mut entries = object.iter().collect::<Vec<_>>();
@@ -1290,6 +1292,13 @@ fn expr_binary(ast: &ast::ExprBinary, c: &mut Assembler<'_>, needs: Needs) -> Co
         return Ok(Asm::top(span));
     }
 
+    if needs.value() {
+        if let Some(value) = fold_constant(span, c, |interp| fold_binary(ast, interp)) {
+            const_(span, c, &value, needs)?;
+            return Ok(Asm::top(span));
+        }
+    }
+
     let guard = c.scopes.push_child(span)?;
 
     // NB: need to declare these as anonymous local variables so that they
@@ -1762,6 +1771,7 @@ fn convert_expr_call(ast: &ast::ExprCall, c: &mut Assembler<'_>) -> CompileResul
                 hash
             };
 
+            c.q.unit.insert_debug_item(hash, &meta.item.item);
             return Ok(Call::Meta { meta, hash });
         }
         ast::Expr::FieldAccess(ast::ExprFieldAccess {
@@ -1771,6 +1781,7 @@ fn convert_expr_call(ast: &ast::ExprCall, c: &mut Assembler<'_>) -> CompileResul
             if let Some((ident, generics)) = path.try_as_ident_generics() {
                 let ident = ident.resolve(resolve_context!(c.q))?;
                 let hash = Hash::instance_fn_name(ident);
+                let item = Item::with_item([ident]);
 
                 let hash = if let Some(generics) = generics {
                     let parameters = generics_parameters(generics, c)?;
@@ -1779,6 +1790,7 @@ fn convert_expr_call(ast: &ast::ExprCall, c: &mut Assembler<'_>) -> CompileResul
                     hash
                 };
 
+                c.q.unit.insert_debug_item(hash, &item);
                 return Ok(Call::Instance { hash });
             }
         }
@@ -1826,13 +1838,35 @@ fn expr_call(ast: &ast::ExprCall, c: &mut Assembler<'_>, needs: Needs) -> Compil
             c.scopes.undecl_anon(span, ast.args.len() + 1)?;
         }
         Call::Meta { meta, hash } => {
+            let inline_fn = inline_fn_for(c, &meta, hash, args);
+
             for (e, _) in &ast.args {
                 expr(e, c, Needs::Value)?.apply(c)?;
                 c.scopes.decl_anon(span)?;
             }
 
-            c.asm
-                .push_with_comment(Inst::Call { hash, args }, span, meta.info().to_string());
+            if let Some(inline_fn) = inline_fn {
+                let offset = c.scopes.total_var_count(span)? - args;
+                let guard = c.scopes.push_child(span)?;
+
+                for (n, name) in inline_fn.args.iter().enumerate() {
+                    c.scopes.bind_var(name, offset + n, span)?;
+                }
+
+                expr(&inline_fn.body, c, Needs::Value)?.apply(c)?;
+                c.scopes.pop(guard, span)?;
+
+                if args > 0 {
+                    c.asm.push_with_comment(
+                        Inst::Clean { count: args },
+                        span,
+                        format!("inlined {}", meta.info()),
+                    );
+                }
+            } else {
+                c.asm
+                    .push_with_comment(Inst::Call { hash, args }, span, meta.info().to_string());
+            }
 
             c.scopes.undecl_anon(span, args)?;
         }
@@ -1864,6 +1898,33 @@ fn expr_call(ast: &ast::ExprCall, c: &mut Assembler<'_>, needs: Needs) -> Compil
     Ok(Asm::top(span))
 }
 
+/// Get the function to inline in place of a call to the given function, if
+/// inlining is enabled and the function is small enough.
+fn inline_fn_for(
+    c: &Assembler<'_>,
+    meta: &PrivMeta,
+    hash: Hash,
+    args: usize,
+) -> Option<Arc<QueryInlineFn>> {
+    if !c.options.inline {
+        return None;
+    }
+
+    let inline_fn = c.q.inline_fn_for(&meta.item.item)?;
+
+    // NB: calls with generics or the wrong number of arguments are left to
+    // the virtual machine so that it can report the appropriate error.
+    if hash != Hash::type_hash(&meta.item.item)
+        || inline_fn.args.len() != args
+        || inline_fn.source_id != c.source_id
+        || inline_fn.size > c.options.inline_threshold
+    {
+        return None;
+    }
+
+    Some(inline_fn)
+}
+
 /// Assemble the body of a closure function.
 #[instrument]
 pub(crate) fn closure_from_expr_closure(
@@ -1887,10 +1948,19 @@ pub(crate) fn closure_from_expr_closure(
         }
     }
 
-    if !captures.is_empty() {
+    if captures.iter().any(|capture| capture.constant.is_none()) {
         c.asm.push(Inst::PushTuple, span);
 
-        for capture in captures {
+        for capture in captures.iter().filter(|capture| capture.constant.is_none()) {
+            c.scopes.new_var(&capture.ident, span)?;
+        }
+    }
+
+    // NB: constant captures aren't stored in the environment, so they're
+    // materialized when the closure is called instead.
+    for capture in captures {
+        if let Some(value) = &capture.constant {
+            const_(span, c, value, Needs::Value)?;
             c.scopes.new_var(&capture.ident, span)?;
         }
     }
@@ -1940,7 +2010,21 @@ fn expr_closure(ast: &ast::ExprClosure, c: &mut Assembler<'_>, needs: Needs) ->
 
     tracing::trace!("captures: {} => {:?}", item.item, captures);
 
-    if captures.is_empty() {
+    // Constant captures still consume moved variables, so that they can't be
+    // used after they've been moved into the closure.
+    if do_move {
+        for capture in captures.iter().filter(|capture| capture.constant.is_some()) {
+            c.scopes
+                .take_var(c.q.visitor, &capture.ident, c.source_id, span)?;
+        }
+    }
+
+    let environment = captures
+        .iter()
+        .filter(|capture| capture.constant.is_none())
+        .collect::<Vec<_>>();
+
+    if environment.is_empty() {
         // NB: if closure doesn't capture the environment it acts like a regular
         // function. No need to store and load the environment.
         c.asm.push_with_comment(
@@ -1950,7 +2034,7 @@ fn expr_closure(ast: &ast::ExprClosure, c: &mut Assembler<'_>, needs: Needs) ->
         );
     } else {
         // Construct a closure environment.
-        for capture in captures {
+        for capture in &environment {
             if do_move {
                 let var = c
                     .scopes
@@ -1967,7 +2051,7 @@ fn expr_closure(ast: &ast::ExprClosure, c: &mut Assembler<'_>, needs: Needs) ->
         c.asm.push_with_comment(
             Inst::Closure {
                 hash,
-                count: captures.len(),
+                count: environment.len(),
             },
             span,
             format!("closure `{}`", item.item),
@@ -2675,6 +2759,19 @@ fn path(ast: &ast::Path, c: &mut Assembler<'_>, needs: Needs) -> CompileResult<A
         return Ok(Asm::top(span));
     }
 
+    if let Some(name) = global_name(ast, c)? {
+        if needs.value() {
+            let slot = c.q.unit.new_static_string(span, &name)?;
+            c.asm.push_with_comment(
+                Inst::LoadGlobal { slot },
+                span,
+                format!("global `{}`", name),
+            );
+        }
+
+        return Ok(Asm::top(span));
+    }
+
     if let (Needs::Value, Some(local)) = (needs, named.as_local()) {
         // light heuristics, treat it as a type error in case the
         // first character is uppercase.
@@ -2696,6 +2793,23 @@ fn path(ast: &ast::Path, c: &mut Assembler<'_>, needs: Needs) -> CompileResult<A
     ))
 }
 
+/// Get the name of the global variable a path refers to, if it's of the form
+/// `global::<name>` and doesn't refer to an item.
+fn global_name(ast: &ast::Path, c: &Assembler<'_>) -> CompileResult<Option<String>> {
+    let (first, rest) = match (&ast.global, &ast.first, &ast.rest[..]) {
+        (None, ast::PathSegment::Ident(first), [(_, ast::PathSegment::Ident(rest))]) => {
+            (first, rest)
+        }
+        _ => return Ok(None),
+    };
+
+    if first.resolve(resolve_context!(c.q))? != "global" {
+        return Ok(None);
+    }
+
+    Ok(Some(rest.resolve(resolve_context!(c.q))?.to_owned()))
+}
+
 /// Assemble a range expression.
 #[instrument]
 fn expr_range(ast: &ast::ExprRange, c: &mut Assembler<'_>, needs: Needs) -> CompileResult<Asm> {
@@ -3000,6 +3114,13 @@ fn expr_unary(ast: &ast::ExprUnary, c: &mut Assembler<'_>, needs: Needs) -> Comp
         return Err(CompileError::new(ast, CompileErrorKind::UnsupportedRef));
     }
 
+    if needs.value() {
+        if let Some(value) = fold_constant(span, c, |interp| fold_unary(ast, interp)) {
+            const_(span, c, &value, needs)?;
+            return Ok(Asm::top(span));
+        }
+    }
+
     if let (ast::UnOp::Neg(..), ast::Expr::Lit(expr_lit)) = (ast.op, &*ast.expr) {
         if let ast::Lit::Number(n) = &expr_lit.lit {
             match n.resolve(resolve_context!(c.q))? {
@@ -3054,6 +3175,172 @@ fn expr_unary(ast: &ast::ExprUnary, c: &mut Assembler<'_>, needs: Needs) -> Comp
     Ok(Asm::top(span))
 }
 
+/// Try to fold an expression which only operates over numeric literals into a
+/// constant value using the constant evaluator.
+///
+/// Returns `None` if folding is disabled, the expression isn't constant, or the
+/// budget was exceeded. Expressions which would error at runtime are left for
+/// the virtual machine to evaluate, so that the same error is raised.
+fn fold_constant<F>(span: Span, c: &mut Assembler<'_>, fold: F) -> Option<ConstValue>
+where
+    F: FnOnce(&mut Folder<'_, '_>) -> Option<IrValue>,
+{
+    if !c.options.fold_constants {
+        return None;
+    }
+
+    let item = c.item;
+
+    let mut folder = Folder {
+        interp: IrInterpreter {
+            budget: IrBudget::new(c.options.fold_budget),
+            scopes: Default::default(),
+            module: &item.module,
+            item: &item.item,
+            q: c.q.borrow(),
+        },
+        unfoldable: &mut c.unfoldable,
+        exhausted: false,
+    };
+
+    fold(&mut folder)?.into_const(span).ok()
+}
+
+/// The state of folding a single expression.
+///
+/// Every operand is folded even if a previous one failed, so that a single
+/// pass marks every subexpression which can't be folded. Assembling the
+/// operands of an expression which didn't fold then doesn't fold them again.
+struct Folder<'a, 'q> {
+    interp: IrInterpreter<'q>,
+    unfoldable: &'a mut HashSet<usize>,
+    /// Set once the budget has been exceeded. Expressions which fail after
+    /// this aren't marked, since they might fold with a budget of their own.
+    exhausted: bool,
+}
+
+impl Folder<'_, '_> {
+    /// Take from the budget.
+    fn take(&mut self, spanned: impl Spanned) -> Option<()> {
+        if self.interp.budget.take(spanned).is_err() {
+            self.exhausted = true;
+            return None;
+        }
+
+        Some(())
+    }
+
+    /// Fold the expression at the given address, marking it if it fails.
+    fn fold<T, F>(&mut self, ast: &T, fold: F) -> Option<IrValue>
+    where
+        F: FnOnce(&mut Self) -> Option<IrValue>,
+    {
+        let key = ast as *const T as usize;
+
+        if self.unfoldable.contains(&key) {
+            return None;
+        }
+
+        let value = fold(self);
+
+        if value.is_none() && !self.exhausted {
+            self.unfoldable.insert(key);
+        }
+
+        value
+    }
+}
+
+/// Fold the given expression.
+fn fold_expr(ast: &ast::Expr, f: &mut Folder<'_, '_>) -> Option<IrValue> {
+    match ast {
+        ast::Expr::Lit(expr_lit) => {
+            f.take(expr_lit)?;
+
+            let value = match &expr_lit.lit {
+                ast::Lit::Number(n) => match n.resolve(resolve_context!(f.interp.q)).ok()? {
+                    ast::Number::Integer(n) => IrValue::Integer(n),
+                    ast::Number::Float(n) => IrValue::Float(n),
+                },
+                _ => return None,
+            };
+
+            fold_checked(value)
+        }
+        ast::Expr::Group(expr_group) => fold_expr(&expr_group.expr, f),
+        ast::Expr::Unary(expr_unary) => fold_unary(expr_unary, f),
+        ast::Expr::Binary(expr_binary) => fold_binary(expr_binary, f),
+        _ => None,
+    }
+}
+
+/// Fold a unary expression.
+fn fold_unary(ast: &ast::ExprUnary, f: &mut Folder<'_, '_>) -> Option<IrValue> {
+    f.fold(ast, |f| {
+        f.take(ast)?;
+
+        let value = match (ast.op, fold_expr(&ast.expr, f)?) {
+            (ast::UnOp::Neg(..), IrValue::Integer(n)) => IrValue::Integer(-n),
+            (ast::UnOp::Neg(..), IrValue::Float(n)) => IrValue::Float(-n),
+            _ => return None,
+        };
+
+        fold_checked(value)
+    })
+}
+
+/// Fold a binary expression.
+fn fold_binary(ast: &ast::ExprBinary, f: &mut Folder<'_, '_>) -> Option<IrValue> {
+    f.fold(ast, |f| {
+        let span = ast.span();
+        f.take(span)?;
+
+        // NB: shifts are left out since their operands are interpreted
+        // differently by the virtual machine.
+        let op = match ast.op {
+            ast::BinOp::Add(..) => ir::IrBinaryOp::Add,
+            ast::BinOp::Sub(..) => ir::IrBinaryOp::Sub,
+            ast::BinOp::Mul(..) => ir::IrBinaryOp::Mul,
+            ast::BinOp::Div(..) => ir::IrBinaryOp::Div,
+            ast::BinOp::Rem(..) => ir::IrBinaryOp::Rem,
+            ast::BinOp::Lt(..) => ir::IrBinaryOp::Lt,
+            ast::BinOp::Lte(..) => ir::IrBinaryOp::Lte,
+            ast::BinOp::Eq(..) => ir::IrBinaryOp::Eq,
+            ast::BinOp::Neq(..) => ir::IrBinaryOp::Neq,
+            ast::BinOp::Gt(..) => ir::IrBinaryOp::Gt,
+            ast::BinOp::Gte(..) => ir::IrBinaryOp::Gte,
+            _ => return None,
+        };
+
+        let lhs = fold_expr(&ast.lhs, f);
+        let rhs = fold_expr(&ast.rhs, f);
+
+        let ir = ir::Ir::new(
+            span,
+            ir::IrBinary {
+                span,
+                op,
+                lhs: Box::new(ir::Ir::new(span, lhs?)),
+                rhs: Box::new(ir::Ir::new(span, rhs?)),
+            },
+        );
+
+        fold_checked(f.interp.eval_value(&ir, Used::Used).ok()?)
+    })
+}
+
+/// Integers are arbitrary precision during constant evaluation, so any value
+/// which doesn't fit in a runtime integer would have overflowed at runtime.
+fn fold_checked(value: IrValue) -> Option<IrValue> {
+    use num::ToPrimitive as _;
+
+    if let IrValue::Integer(n) = &value {
+        n.to_i64()?;
+    }
+
+    Some(value)
+}
+
 /// Assemble a literal vector.
 #[instrument]
 fn expr_vec(ast: &ast::ExprVec, c: &mut Assembler<'_>, needs: Needs) -> CompileResult<Asm> {
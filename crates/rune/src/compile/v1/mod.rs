@@ -1,8 +1,9 @@
 use crate::ast;
 use crate::ast::{Span, Spanned};
+use crate::collections::HashSet;
 use crate::compile::{
-    ir, Assembly, CompileError, CompileErrorKind, CompileResult, IrBudget, IrCompiler,
-    IrInterpreter, Item, ItemMeta, Options, PrivMeta,
+    ir, Assembly, CompileError, CompileErrorKind, CompileResult, IrCompiler, IrInterpreter, Item,
+    ItemMeta, Options, PrivMeta,
 };
 use crate::query::{Named, Query, QueryConstFn, Used};
 use crate::runtime::{ConstValue, Inst};
@@ -35,6 +36,8 @@ impl Needs {
 pub(crate) struct Assembler<'a> {
     /// The source id of the source.
     pub(crate) source_id: SourceId,
+    /// The item being compiled.
+    pub(crate) item: &'a ItemMeta,
     /// The context we are compiling for.
     pub(crate) context: &'a Context,
     /// Query system to compile required items.
@@ -51,6 +54,10 @@ pub(crate) struct Assembler<'a> {
     pub(crate) options: &'a Options,
     /// Compilation warnings.
     pub(crate) diagnostics: &'a mut Diagnostics,
+    /// Binary and unary expressions, by address, which are known to not fold
+    /// into a constant. This avoids folding them again when their operands
+    /// are assembled.
+    pub(crate) unfoldable: HashSet<usize>,
 }
 
 impl<'a> Assembler<'a> {
@@ -184,7 +191,7 @@ impl<'a> Assembler<'a> {
         }
 
         let mut interpreter = IrInterpreter {
-            budget: IrBudget::new(1_000_000),
+            budget: self.q.const_budget(),
             scopes: Default::default(),
             module: &from.module,
             item: &from.item,
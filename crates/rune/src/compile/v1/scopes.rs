@@ -122,6 +122,19 @@ impl Scope {
         offset
     }
 
+    /// Bind a name to a variable which has already been declared at the given
+    /// offset.
+    fn bind_var(&mut self, name: &str, offset: usize, span: Span) {
+        self.locals.insert(
+            name.to_owned(),
+            Var {
+                offset,
+                span,
+                moved_at: None,
+            },
+        );
+    }
+
     /// Declare an anonymous variable.
     ///
     /// This is used if cleanup is required in the middle of an expression.
@@ -292,6 +305,13 @@ impl Scopes {
         Ok(self.last_mut(span)?.decl_var(name, span))
     }
 
+    /// Bind a name to a variable which has already been declared at the given
+    /// offset.
+    pub(crate) fn bind_var(&mut self, name: &str, offset: usize, span: Span) -> CompileResult<()> {
+        self.last_mut(span)?.bind_var(name, offset, span);
+        Ok(())
+    }
+
     /// Declare an anonymous variable.
     pub(crate) fn decl_anon(&mut self, span: Span) -> CompileResult<usize> {
         Ok(self.last_mut(span)?.decl_anon(span))
@@ -6,8 +6,8 @@ use crate::diagnostics::{
 };
 use crate::parse::ResolveErrorKind;
 use crate::query::QueryErrorKind;
-use crate::runtime::{Unit, VmError, VmErrorKind};
-use crate::{Source, Diagnostics, SourceId, Sources};
+use crate::runtime::{DebugInfo, Unit, VmError, VmErrorKind};
+use crate::{Hash, Source, Diagnostics, SourceId, Sources};
 use crate::ast::{Span, Spanned};
 use std::convert::TryInto;
 use std::error::Error;
@@ -162,6 +162,39 @@ impl VmError {
                     ],
                 )
             }
+            VmErrorKind::MissingFunction { hash } => {
+                labels.push(
+                    d::Label::primary(source_id, span.range())
+                        .with_message("in this function call".to_string()),
+                );
+
+                missing(debug_info, "function", *hash)
+            }
+            VmErrorKind::MissingInstanceFunction { hash, instance } => {
+                labels.push(
+                    d::Label::primary(source_id, span.range())
+                        .with_message("in this function call".to_string()),
+                );
+
+                let (reason, notes) = missing(debug_info, "instance function", *hash);
+                (format!("{} for `{}`", reason, instance), notes)
+            }
+            VmErrorKind::MissingConst { hash } => {
+                labels.push(
+                    d::Label::primary(source_id, span.range())
+                        .with_message("in this expression".to_string()),
+                );
+
+                missing(debug_info, "constant", *hash)
+            }
+            VmErrorKind::MissingRtti { hash } | VmErrorKind::MissingVariantRtti { hash } => {
+                labels.push(
+                    d::Label::primary(source_id, span.range())
+                        .with_message("in this expression".to_string()),
+                );
+
+                missing(debug_info, "runtime information for type", *hash)
+            }
             e => {
                 labels.push(
                     d::Label::primary(source_id, span.range())
@@ -226,6 +259,18 @@ impl VmError {
     }
 }
 
+/// Describe something which is missing by hash, using its item if the debug
+/// information knows about it.
+fn missing(debug_info: &DebugInfo, what: &str, hash: Hash) -> (String, Vec<String>) {
+    match debug_info.item_for_hash(hash) {
+        Some(item) => (
+            format!("missing {} `{}`", what, item),
+            vec![format!("hash is `{}`", hash)],
+        ),
+        None => (format!("missing {} with hash `{}`", what, hash), vec![]),
+    }
+}
+
 impl FatalDiagnostic {
     /// Generate formatted diagnostics capable of referencing source lines and
     /// hints.
@@ -258,33 +303,43 @@ impl Unit {
     {
         let mut first_function = true;
 
-        for (n, inst) in self.iter_instructions().enumerate() {
-            let debug = self.debug_info().and_then(|d| d.instruction_at(n));
-
-            if let Some((hash, signature)) = self.debug_info().and_then(|d| d.function_at(n)) {
+        for record in self.instructions() {
+            if let Some(function) = &record.function {
                 if !std::mem::take(&mut first_function) {
                     writeln!(out)?;
                 }
 
-                writeln!(out, "fn {} ({}):", signature, hash)?;
+                match function.signature {
+                    Some(signature) => writeln!(out, "fn {} ({}):", signature, function.hash)?,
+                    None => writeln!(out, "fn {}:", function.hash)?,
+                }
             }
 
             if with_source {
-                if let Some((source, span)) =
-                    debug.and_then(|d| sources.get(d.source_id).map(|s| (s, d.span)))
+                if let Some((source, span)) = record
+                    .source_id
+                    .and_then(|id| sources.get(id))
+                    .zip(record.span)
                 {
                     source.emit_source_line(out, span)?;
                 }
             }
 
-            if let Some(label) = debug.and_then(|d| d.label.as_ref()) {
+            if let Some(label) = record.label {
                 writeln!(out, "{}:", label)?;
             }
 
-            write!(out, "  {:04} = {}", n, inst)?;
+            write!(out, "  {:04} = {}", record.ip, record.inst)?;
+
+            if let Some(jump) = record.jump {
+                write!(out, " -> {:04}", jump)?;
+            }
 
-            if let Some(comment) = debug.and_then(|d| d.comment.as_ref()) {
-                write!(out, " // {}", comment)?;
+            match (record.comment, &record.constant) {
+                (Some(comment), Some(constant)) => write!(out, " // {} ({})", comment, constant)?,
+                (Some(comment), None) => write!(out, " // {}", comment)?,
+                (None, Some(constant)) => write!(out, " // {}", constant)?,
+                (None, None) => (),
             }
 
             writeln!(out)?;
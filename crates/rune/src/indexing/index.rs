@@ -16,7 +16,7 @@ use crate::query::{
     Function, Indexed, IndexedEntry, InstanceFunction, Query, Used,
 };
 use crate::runtime::format;
-use crate::runtime::Call;
+use crate::runtime::{Call, ConstValue};
 use crate::shared::Items;
 use crate::worker::{Import, ImportKind, LoadFileKind, Task};
 use crate::{Context, Diagnostics, Hash, SourceId};
@@ -58,6 +58,8 @@ pub(crate) struct Indexer<'a> {
     pub(crate) mod_item: Arc<ModMeta>,
     /// Set if we are inside of an impl self.
     pub(crate) impl_item: Option<Arc<Item>>,
+    /// The path of the impl we are inside of.
+    pub(crate) impl_path: Option<Arc<ast::Path>>,
     /// Source loader to use.
     pub(crate) source_loader: &'a mut dyn SourceLoader,
     /// Indicates if indexer is nested privately inside of another item, and if
@@ -724,13 +726,13 @@ fn item_fn(ast: &mut ast::ItemFn, idx: &mut Indexer<'_>) -> CompileResult<()> {
             ));
         }
 
-        let impl_item = idx.impl_item.as_ref().ok_or_else(|| {
+        let impl_path = idx.impl_path.as_ref().ok_or_else(|| {
             CompileError::new(span, CompileErrorKind::InstanceFunctionOutsideImpl)
         })?;
 
         let f = InstanceFunction {
             ast: fun.ast,
-            impl_item: impl_item.clone(),
+            impl_path: impl_path.clone(),
             instance_span: span,
             call: fun.call,
         };
@@ -937,9 +939,53 @@ fn local(ast: &mut ast::Local, idx: &mut Indexer<'_>) -> CompileResult<()> {
     // declaration and use that instead of capturing from the outside.
     expr(&mut ast.expr, idx, IS_USED)?;
     pat(&mut ast.pat, idx, NOT_USED)?;
+
+    // Keep track of variables declared with a copyable constant, so that
+    // closures capturing them don't need to store them in their environment.
+    if let ast::Pat::PatPath(pat) = &ast.pat {
+        if let Some(ident) = pat.path.try_as_ident() {
+            if let Some(value) = copyable_constant(&ast.expr, idx)? {
+                let ident = ident.resolve(resolve_context!(idx.q))?;
+                idx.scopes.mark_constant(ident, value);
+            }
+        }
+    }
+
     Ok(())
 }
 
+/// Get the value of an expression if it's a constant which is cheap to copy.
+fn copyable_constant(ast: &ast::Expr, idx: &Indexer<'_>) -> CompileResult<Option<ConstValue>> {
+    use num::ToPrimitive;
+
+    let (lit, neg) = match ast {
+        ast::Expr::Lit(expr) if expr.attributes.is_empty() => (&expr.lit, false),
+        ast::Expr::Unary(expr) if matches!(expr.op, ast::UnOp::Neg(..)) => match &*expr.expr {
+            ast::Expr::Lit(lit) if lit.attributes.is_empty() => (&lit.lit, true),
+            _ => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+
+    Ok(match (lit, neg) {
+        (ast::Lit::Bool(lit), false) => Some(ConstValue::Bool(lit.value)),
+        (ast::Lit::Byte(lit), false) => {
+            Some(ConstValue::Byte(lit.resolve(resolve_context!(idx.q))?))
+        }
+        (ast::Lit::Char(lit), false) => {
+            Some(ConstValue::Char(lit.resolve(resolve_context!(idx.q))?))
+        }
+        (ast::Lit::Number(lit), neg) => match lit.resolve(resolve_context!(idx.q))? {
+            ast::Number::Float(n) => Some(ConstValue::Float(if neg { -n } else { n })),
+            ast::Number::Integer(n) => {
+                let n = if neg { -n } else { n };
+                n.to_i64().map(ConstValue::Integer)
+            }
+        },
+        _ => None,
+    })
+}
+
 #[instrument]
 fn expr_let(ast: &mut ast::ExprLet, idx: &mut Indexer<'_>) -> CompileResult<()> {
     pat(&mut ast.pat, idx, NOT_USED)?;
@@ -1180,6 +1226,7 @@ fn expr_if(ast: &mut ast::ExprIf, idx: &mut Indexer<'_>) -> CompileResult<()> {
 fn expr_assign(ast: &mut ast::ExprAssign, idx: &mut Indexer<'_>) -> CompileResult<()> {
     expr(&mut ast.lhs, idx, IS_USED)?;
     expr(&mut ast.rhs, idx, IS_USED)?;
+    mark_assign(&ast.lhs, idx)?;
     Ok(())
 }
 
@@ -1187,6 +1234,24 @@ fn expr_assign(ast: &mut ast::ExprAssign, idx: &mut Indexer<'_>) -> CompileResul
 fn expr_binary(ast: &mut ast::ExprBinary, idx: &mut Indexer<'_>) -> CompileResult<()> {
     expr(&mut ast.lhs, idx, IS_USED)?;
     expr(&mut ast.rhs, idx, IS_USED)?;
+
+    if ast.op.is_assign() {
+        mark_assign(&ast.lhs, idx)?;
+    }
+
+    Ok(())
+}
+
+/// Mark that the variable being assigned to by the given expression, if any, no
+/// longer holds the constant it was declared with.
+fn mark_assign(ast: &ast::Expr, idx: &mut Indexer<'_>) -> CompileResult<()> {
+    if let ast::Expr::Path(path) = ast {
+        if let Some(ident) = path.try_as_ident() {
+            let ident = ident.resolve(resolve_context!(idx.q))?;
+            idx.scopes.mark_assign(ident);
+        }
+    }
+
     Ok(())
 }
 
@@ -1334,6 +1399,13 @@ fn item_impl(ast: &mut ast::ItemImpl, idx: &mut Indexer<'_>) -> CompileResult<()
         ));
     }
 
+    // NB: the path of the impl is resolved in the scope surrounding it, which
+    // is needed to extend types which are defined elsewhere.
+    let id = idx
+        .q
+        .insert_path(&idx.mod_item, idx.impl_item.as_ref(), &idx.items.item());
+    ast.path.id.set(id);
+
     for path_segment in ast.path.as_components() {
         let ident_segment = path_segment
             .try_as_ident()
@@ -1344,12 +1416,14 @@ fn item_impl(ast: &mut ast::ItemImpl, idx: &mut Indexer<'_>) -> CompileResult<()
 
     let new = Arc::new(idx.items.item().clone());
     let old = std::mem::replace(&mut idx.impl_item, Some(new));
+    let old_path = idx.impl_path.replace(Arc::new(ast.path.clone()));
 
     for i in &mut ast.functions {
         item_fn(i, idx)?;
     }
 
     idx.impl_item = old;
+    idx.impl_path = old_path;
     Ok(())
 }
 
@@ -1516,7 +1590,7 @@ fn path_segment(ast: &mut ast::PathSegment, idx: &mut Indexer<'_>) -> CompileRes
 
 #[instrument]
 fn expr_while(ast: &mut ast::ExprWhile, idx: &mut Indexer<'_>) -> CompileResult<()> {
-    let _guard = idx.scopes.push_scope();
+    let _guard = idx.scopes.push_loop();
     condition(&mut ast.condition, idx)?;
     block(&mut ast.body, idx)?;
     Ok(())
@@ -1524,7 +1598,7 @@ fn expr_while(ast: &mut ast::ExprWhile, idx: &mut Indexer<'_>) -> CompileResult<
 
 #[instrument]
 fn expr_loop(ast: &mut ast::ExprLoop, idx: &mut Indexer<'_>) -> CompileResult<()> {
-    let _guard = idx.scopes.push_scope();
+    let _guard = idx.scopes.push_loop();
     block(&mut ast.body, idx)?;
     Ok(())
 }
@@ -1534,7 +1608,7 @@ fn expr_for(ast: &mut ast::ExprFor, idx: &mut Indexer<'_>) -> CompileResult<()>
     // NB: creating the iterator is evaluated in the parent scope.
     expr(&mut ast.iter, idx, IS_USED)?;
 
-    let _guard = idx.scopes.push_scope();
+    let _guard = idx.scopes.push_loop();
     pat(&mut ast.binding, idx, NOT_USED)?;
     block(&mut ast.body, idx)?;
     Ok(())
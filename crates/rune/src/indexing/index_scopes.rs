@@ -3,6 +3,7 @@
 use crate::ast::Span;
 use crate::collections::{HashMap, HashSet};
 use crate::compile::{CaptureMeta, CompileError, CompileErrorKind};
+use crate::runtime::ConstValue;
 use std::cell::RefCell;
 use std::rc::Rc;
 
@@ -80,13 +81,25 @@ impl Drop for IndexScopeGuard {
     }
 }
 
+#[derive(Debug, Clone)]
+struct IndexLocal {
+    #[allow(dead_code)]
+    span: Span,
+    /// The constant value the variable was declared with, as long as it hasn't
+    /// been assigned to since.
+    constant: Option<ConstValue>,
+}
+
 #[derive(Debug, Clone)]
 struct IndexScope {
     /// Unique identifier assigned to every scope to ensure that it matches the
     /// hierarchy at each point where the scope guard is consumed so that we
     /// can correctly detect programming bugs.
     id: usize,
-    locals: HashMap<String, Span>,
+    locals: HashMap<String, IndexLocal>,
+    /// If the scope is the body of a loop, which can be evaluated multiple
+    /// times.
+    is_loop: bool,
 }
 
 impl IndexScope {
@@ -95,6 +108,7 @@ impl IndexScope {
         Self {
             id,
             locals: HashMap::new(),
+            is_loop: false,
         }
     }
 }
@@ -184,6 +198,14 @@ impl IndexScopeLevel {
             IndexScopeLevel::IndexFunction(fun) => &fun.scope,
         }
     }
+
+    fn scope_mut(&mut self) -> &mut IndexScope {
+        match self {
+            IndexScopeLevel::IndexScope(scope) => scope,
+            IndexScopeLevel::IndexClosure(closure) => &mut closure.scope,
+            IndexScopeLevel::IndexFunction(fun) => &mut fun.scope,
+        }
+    }
 }
 
 /// An indexing scope.
@@ -218,10 +240,49 @@ impl IndexScopes {
             IndexScopeLevel::IndexFunction(fun) => &mut fun.scope,
         };
 
-        scope.locals.insert(var.to_owned(), span);
+        scope.locals.insert(
+            var.to_owned(),
+            IndexLocal {
+                span,
+                constant: None,
+            },
+        );
+
         Ok(())
     }
 
+    /// Mark that the given variable which was just declared in the last scope
+    /// holds a constant value.
+    pub(crate) fn mark_constant(&mut self, var: &str, value: ConstValue) {
+        let mut levels = self.levels.borrow_mut();
+
+        if let Some(local) = levels
+            .last_mut()
+            .and_then(|level| level.scope_mut().locals.get_mut(var))
+        {
+            local.constant = Some(value);
+        }
+    }
+
+    /// Mark that the given variable is assigned to, meaning that it no longer
+    /// holds the constant value it was declared with.
+    pub(crate) fn mark_assign(&mut self, var: &str) {
+        let mut levels = self.levels.borrow_mut();
+
+        for level in levels.iter_mut().rev() {
+            let term = matches!(level, IndexScopeLevel::IndexFunction(..));
+
+            if let Some(local) = level.scope_mut().locals.get_mut(var) {
+                local.constant = None;
+                break;
+            }
+
+            if term {
+                break;
+            }
+        }
+    }
+
     /// Mark that the given variable is used.
     pub(crate) fn mark_use(&mut self, var: &str) {
         let mut levels = self.levels.borrow_mut();
@@ -232,6 +293,12 @@ impl IndexScopes {
         let mut found = false;
         let mut closures = smallvec::SmallVec::<[_; 8]>::new();
 
+        // The constant value of the variable. This is only known if it's
+        // declared in the same function, and the closure capturing it isn't
+        // created in a loop that the variable is declared outside of.
+        let mut constant = None;
+        let mut in_loop = false;
+
         for scope in iter {
             let (scope, closure, term) = match scope {
                 IndexScopeLevel::IndexScope(scope) => (scope, None, false),
@@ -244,11 +311,17 @@ impl IndexScopes {
             };
 
             // Found a local variable - nothing more to do!
-            if scope.locals.get(var).is_some() {
+            if let Some(local) = scope.locals.get(var) {
+                if !in_loop {
+                    constant = local.constant.clone();
+                }
+
                 found = true;
                 break;
             }
 
+            in_loop |= scope.is_loop;
+
             if let Some((existing, captures)) = closure {
                 if existing.contains(var) {
                     found = true;
@@ -265,7 +338,10 @@ impl IndexScopes {
         if found {
             for (existing, captures) in closures {
                 existing.insert(var.into());
-                captures.push(CaptureMeta { ident: var.into() });
+                captures.push(CaptureMeta {
+                    ident: var.into(),
+                    constant: constant.clone(),
+                });
             }
         }
     }
@@ -371,6 +447,22 @@ impl IndexScopes {
         }
     }
 
+    /// Push a new scope for the body of a loop.
+    pub(crate) fn push_loop(&mut self) -> IndexScopeGuard {
+        let id = self.id();
+        let mut levels = self.levels.borrow_mut();
+
+        let mut scope = IndexScope::new(id);
+        scope.is_loop = true;
+        levels.push(IndexScopeLevel::IndexScope(scope));
+
+        IndexScopeGuard {
+            id,
+            levels: self.levels.clone(),
+            consumed: false,
+        }
+    }
+
     /// Allocate the next scope id.
     fn id(&mut self) -> usize {
         let next = self.id;
@@ -211,6 +211,8 @@ cfg_emit! {
 mod any;
 pub use self::any::Any;
 
+pub use rune_macros::{function, module};
+
 mod build;
 pub use self::build::{prepare, Build, BuildError};
 
@@ -235,8 +237,13 @@ pub mod parse;
 
 pub mod query;
 
+mod reload;
+pub use self::reload::{ReloadError, Reloader};
+
 pub mod runtime;
-pub use self::runtime::{FromValue, ToValue, Unit, Value, Vm};
+pub use self::runtime::{from_value, to_value, FromValue, ToValue, Unit, Value, Vm};
+
+pub mod semantic;
 
 mod shared;
 
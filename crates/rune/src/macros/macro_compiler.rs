@@ -7,6 +7,7 @@ use crate::macros::MacroContext;
 use crate::parse::{Parse, ParseError, Parser};
 use crate::query::Query;
 use crate::{Context, Hash};
+use std::fmt::Write;
 use std::sync::Arc;
 
 pub(crate) struct MacroCompiler<'a> {
@@ -49,6 +50,8 @@ impl MacroCompiler<'_> {
 
         let input_stream = &macro_call.stream;
 
+        let mut expanded = None;
+
         // SAFETY: Macro context only needs to live for the duration of the
         // `handler` call.
         let result = {
@@ -59,9 +62,29 @@ impl MacroCompiler<'_> {
                 q: self.query.borrow(),
             };
 
-            handler(&mut macro_context, input_stream)
+            let result = handler(&mut macro_context, input_stream);
+
+            if self.options.emit_expansions {
+                if let Ok(output) = &result {
+                    let mut string = String::new();
+
+                    // NB: streams containing marker tokens can't be
+                    // stringified, so they are simply not reported.
+                    if write!(string, "{}", macro_context.stringify(output)).is_ok() {
+                        expanded = Some(string);
+                    }
+                }
+            }
+
+            result
         };
 
+        if let Some(expanded) = &expanded {
+            self.query
+                .visitor
+                .visit_macro_expansion(self.item.location.source_id, span, expanded);
+        }
+
         let token_stream = match result {
             Ok(output) => output,
             Err(error) => {
@@ -9,7 +9,7 @@ use crate::macros::{IntoLit, Storage, ToTokens, TokenStream};
 use crate::parse::{Parse, ParseError, ParseErrorKind, Resolve, ResolveError};
 use crate::query::Query;
 use crate::shared::{Consts, Gen};
-use crate::{Source, SourceId, Sources};
+use crate::{Context, Source, SourceId, Sources};
 use std::fmt;
 use std::sync::Arc;
 
@@ -40,6 +40,7 @@ impl<'a> MacroContext<'a> {
     where
         F: FnOnce(&mut MacroContext<'_>) -> O,
     {
+        let context = Context::default();
         let mut unit = UnitBuilder::default();
         let gen = Gen::default();
         let mut consts = Consts::default();
@@ -49,6 +50,7 @@ impl<'a> MacroContext<'a> {
         let mut inner = Default::default();
 
         let mut query = Query::new(
+            &context,
             &mut unit,
             &mut consts,
             &mut storage,
@@ -1,9 +1,11 @@
 use crate::ast;
 use crate::collections::HashMap;
+use serde::Serialize;
 use std::fmt;
 
 /// A synthetic identifier which can be used to reference something in storage.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(transparent)]
 pub struct SyntheticId(usize);
 
 impl fmt::Display for SyntheticId {
@@ -1,11 +1,13 @@
 use crate::ast;
 use crate::ast::{OptionSpanned, Span};
 use crate::macros::MacroContext;
+use serde::Serialize;
 use std::fmt;
 use std::slice;
 
 /// A token stream.
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
+#[serde(transparent)]
 pub struct TokenStream {
     stream: Vec<ast::Token>,
 }
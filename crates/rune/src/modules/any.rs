@@ -1,29 +1,107 @@
 //! `std::any` module.
 
-use crate::runtime::{Protocol, Value};
+use crate::compile::Item;
+use crate::runtime::{
+    EnvProtocolCaller, Function, Protocol, Value, VariantData, VmError, VmErrorKind,
+};
+use crate::Hash;
 use crate::{Any, ContextError, Module};
-use std::any::TypeId as StdTypeId;
 use std::fmt;
 use std::fmt::Write;
 
 #[derive(Any, Debug)]
 #[rune(module = "crate")]
 #[repr(transparent)]
-struct TypeId(StdTypeId);
+struct TypeId(Hash);
 
 fn type_id_of_val(item: Value) -> TypeId {
-    unsafe { std::mem::transmute(item.type_hash().expect("no type known for item!")) }
+    TypeId(item.type_hash().expect("no type known for item!"))
 }
 
 fn format_type_id(item: &TypeId, buf: &mut String) -> fmt::Result {
     write!(buf, "{:?}", item.0)
 }
 
+/// Get the names of the fields of an object, a struct or a struct variant,
+/// sorted by name.
+///
+/// Returns `None` for values which don't have named fields.
+fn fields(value: Value) -> Result<Option<Vec<String>>, VmError> {
+    let mut fields = match value {
+        Value::Object(object) => object.borrow_ref()?.keys().cloned().collect::<Vec<_>>(),
+        Value::Struct(st) => st.borrow_ref()?.data().keys().cloned().collect(),
+        Value::Variant(variant) => match variant.borrow_ref()?.data() {
+            VariantData::Struct(object) => object.keys().cloned().collect(),
+            _ => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+
+    fields.sort();
+    Ok(Some(fields))
+}
+
+/// Test if the type of the value has a function implementing the protocol
+/// with the given name, like `string_display`, `+` or the name of a custom
+/// protocol.
+///
+/// Operations which are built into the virtual machine, like adding two
+/// integers, are not reported.
+fn has_protocol(value: Value, name: &str) -> Result<bool, VmError> {
+    EnvProtocolCaller.has_hash_fn(Protocol::hash_of_name(name), &value)
+}
+
+/// Call the protocol with the given name on the value, passing the given
+/// arguments after it.
+fn protocol_call(value: Value, name: &str, args: Vec<Value>) -> Result<Value, VmError> {
+    let protocol = Protocol::hash_of_name(name);
+
+    if !EnvProtocolCaller.has_hash_fn(protocol, &value)? {
+        return Err(VmError::from(VmErrorKind::MissingInstanceFunction {
+            hash: Hash::instance_function(value.type_hash()?, protocol),
+            instance: value.type_info()?,
+        }));
+    }
+
+    EnvProtocolCaller.call_hash_fn(protocol, value, args)
+}
+
+/// Look up a function by its path, like `foo::bar` for a function in the
+/// running unit or `std::any::type_name_of` for a native function.
+///
+/// Script functions are only compiled if they are used or public at the root
+/// of the unit, so private functions which are never referenced can't be
+/// found.
+fn function(path: &str) -> Result<Option<Function>, VmError> {
+    let components = path.split("::").collect::<Vec<_>>();
+
+    crate::runtime::env::with(|context, unit| {
+        let hash = Hash::type_hash(&Item::with_item(&components));
+
+        if let Some(function) = Function::lookup(context, unit, hash)? {
+            return Ok(Some(function));
+        }
+
+        let (first, rest) = match components.split_first() {
+            Some(split) => split,
+            None => return Ok(None),
+        };
+
+        let hash = Hash::type_hash(&Item::with_crate_item(first, rest));
+        Function::lookup(context, unit, hash)
+    })
+}
+
 /// Construct the `std::any` module.
 pub fn module() -> Result<Module, ContextError> {
     let mut module = Module::with_crate_item("std", &["any"]);
 
     module.function(&["type_name_of_val"], Value::into_type_name)?;
+    module.function(&["type_name_of"], Value::into_type_name)?;
+    module.function(&["fields"], fields)?;
+    module.function(&["has_protocol"], has_protocol)?;
+    module.function(&["protocol_call"], protocol_call)?;
+    module.function(&["function"], function)?;
 
     module.ty::<TypeId>()?;
     module.function(&["TypeId", "of_val"], type_id_of_val)?;
@@ -1,29 +1,269 @@
 //! `std::bytes` module.
 
-use crate::runtime::Bytes;
-use crate::{ContextError, Module};
+use crate::runtime::{Bytes, Iterator, Protocol};
+use crate::{Any, ContextError, Module};
+use std::string::FromUtf8Error;
 
 /// Construct the `std::bytes` module.
 pub fn module() -> Result<Module, ContextError> {
     let mut module = Module::with_crate_item("std", &["bytes"]);
 
     module.ty::<Bytes>()?;
+    module.ty::<Reader>()?;
+    module.ty::<FromUtf8Error>()?;
+
     module.function(&["Bytes", "new"], Bytes::new)?;
     module.function(&["Bytes", "with_capacity"], Bytes::with_capacity)?;
     module.function(&["Bytes", "from_vec"], Bytes::from_vec)?;
+    module.function(&["Bytes", "from_str"], from_str)?;
+    module.function(&["Bytes", "from_hex"], from_hex)?;
 
     module.inst_fn("into_vec", Bytes::into_vec)?;
     module.inst_fn("extend", Bytes::extend)?;
     module.inst_fn("extend_str", Bytes::extend_str)?;
+    module.inst_fn("push", push)?;
     module.inst_fn("pop", Bytes::pop)?;
     module.inst_fn("last", Bytes::last)?;
+    module.inst_fn("get", get)?;
+    module.inst_fn("slice", slice)?;
+    module.inst_fn("iter", iter)?;
+    module.inst_fn("concat", concat)?;
+    module.inst_fn("find", find)?;
+    module.inst_fn("contains", contains)?;
+    module.inst_fn("starts_with", starts_with)?;
+    module.inst_fn("ends_with", ends_with)?;
+    module.inst_fn("to_string", to_string)?;
+    module.inst_fn("to_string_lossy", to_string_lossy)?;
+    module.inst_fn("to_hex", to_hex)?;
+    module.inst_fn("reader", Reader::new)?;
 
     module.inst_fn("len", Bytes::len)?;
+    module.inst_fn("is_empty", Bytes::is_empty)?;
     module.inst_fn("capacity", Bytes::capacity)?;
     module.inst_fn("clear", Bytes::clear)?;
     module.inst_fn("reserve", Bytes::reserve)?;
     module.inst_fn("reserve_exact", Bytes::reserve_exact)?;
     module.inst_fn("clone", Bytes::clone)?;
     module.inst_fn("shrink_to_fit", Bytes::shrink_to_fit)?;
+    module.inst_fn(Protocol::ADD, concat)?;
+    module.inst_fn(Protocol::INTO_ITER, iter)?;
+
+    module.inst_fn("position", Reader::position)?;
+    module.inst_fn("remaining", Reader::remaining)?;
+    module.inst_fn("seek", Reader::seek)?;
+    module.inst_fn("skip", Reader::skip)?;
+    module.inst_fn("read_bytes", Reader::read_bytes)?;
+    module.inst_fn("read_u8", Reader::read_u8)?;
+    module.inst_fn("read_i8", Reader::read_i8)?;
+    module.inst_fn("read_u16_le", Reader::read_u16_le)?;
+    module.inst_fn("read_u16_be", Reader::read_u16_be)?;
+    module.inst_fn("read_i16_le", Reader::read_i16_le)?;
+    module.inst_fn("read_i16_be", Reader::read_i16_be)?;
+    module.inst_fn("read_u32_le", Reader::read_u32_le)?;
+    module.inst_fn("read_u32_be", Reader::read_u32_be)?;
+    module.inst_fn("read_i32_le", Reader::read_i32_le)?;
+    module.inst_fn("read_i32_be", Reader::read_i32_be)?;
+    module.inst_fn("read_i64_le", Reader::read_i64_le)?;
+    module.inst_fn("read_i64_be", Reader::read_i64_be)?;
+    module.inst_fn("read_f32_le", Reader::read_f32_le)?;
+    module.inst_fn("read_f32_be", Reader::read_f32_be)?;
+    module.inst_fn("read_f64_le", Reader::read_f64_le)?;
+    module.inst_fn("read_f64_be", Reader::read_f64_be)?;
     Ok(module)
 }
+
+crate::__internal_impl_any!(FromUtf8Error);
+
+/// Construct bytes from the UTF-8 encoding of a string.
+fn from_str(s: &str) -> Bytes {
+    Bytes::from_vec(s.as_bytes().to_vec())
+}
+
+/// Decode bytes from hex in either case, returning `None` if the string isn't
+/// valid hex.
+fn from_hex(s: &str) -> Option<Bytes> {
+    fn digit(c: u8) -> Option<u8> {
+        Some(match c {
+            b'0'..=b'9' => c - b'0',
+            b'a'..=b'f' => c - b'a' + 10,
+            b'A'..=b'F' => c - b'A' + 10,
+            _ => return None,
+        })
+    }
+
+    let s = s.as_bytes();
+
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+
+    let mut out = Vec::with_capacity(s.len() / 2);
+
+    for pair in s.chunks_exact(2) {
+        out.push(digit(pair[0])? << 4 | digit(pair[1])?);
+    }
+
+    Some(Bytes::from_vec(out))
+}
+
+/// Encode bytes as lowercase hex.
+fn to_hex(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+    let mut out = String::with_capacity(bytes.len() * 2);
+
+    for &b in bytes {
+        out.push(char::from(DIGITS[usize::from(b >> 4)]));
+        out.push(char::from(DIGITS[usize::from(b & 0xf)]));
+    }
+
+    out
+}
+
+fn push(bytes: &mut Bytes, byte: u8) {
+    bytes.bytes.push(byte);
+}
+
+fn get(bytes: &[u8], index: usize) -> Option<u8> {
+    bytes.get(index).copied()
+}
+
+/// Copy out the bytes in the range `start..end`, returning `None` if it's out
+/// of bounds.
+fn slice(bytes: &[u8], start: usize, end: usize) -> Option<Bytes> {
+    Some(Bytes::from_vec(bytes.get(start..end)?.to_vec()))
+}
+
+fn iter(bytes: &Bytes) -> Iterator {
+    Iterator::from_double_ended("std::bytes::Iter", bytes.clone().into_vec().into_iter())
+}
+
+/// Concatenate two byte collections into a new one.
+fn concat(a: &[u8], b: &[u8]) -> Bytes {
+    let mut out = Vec::with_capacity(a.len() + b.len());
+    out.extend_from_slice(a);
+    out.extend_from_slice(b);
+    Bytes::from_vec(out)
+}
+
+/// Find the index of the first occurrence of the needle.
+fn find(bytes: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(0);
+    }
+
+    bytes
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn contains(bytes: &[u8], needle: &[u8]) -> bool {
+    find(bytes, needle).is_some()
+}
+
+fn starts_with(bytes: &[u8], prefix: &[u8]) -> bool {
+    bytes.starts_with(prefix)
+}
+
+fn ends_with(bytes: &[u8], suffix: &[u8]) -> bool {
+    bytes.ends_with(suffix)
+}
+
+fn to_string(bytes: &[u8]) -> Result<String, FromUtf8Error> {
+    String::from_utf8(bytes.to_vec())
+}
+
+fn to_string_lossy(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+/// A cursor reading binary data from a copy of some bytes.
+///
+/// Reads return `None` without advancing if there isn't enough data left.
+#[derive(Any, Clone)]
+#[rune(module = "crate")]
+struct Reader {
+    bytes: Vec<u8>,
+    position: usize,
+}
+
+macro_rules! read {
+    ($($name:ident => $ty:ty, $from:ident, $out:ty;)*) => {
+        $(
+            fn $name(&mut self) -> Option<$out> {
+                Some(<$out>::from(<$ty>::$from(self.take()?)))
+            }
+        )*
+    };
+}
+
+impl Reader {
+    fn new(bytes: &[u8]) -> Self {
+        Self {
+            bytes: bytes.to_vec(),
+            position: 0,
+        }
+    }
+
+    /// The current position of the reader.
+    fn position(&self) -> usize {
+        self.position
+    }
+
+    /// The number of bytes left to read.
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.position
+    }
+
+    /// Move to the given position, returning `false` if it's out of bounds.
+    fn seek(&mut self, position: usize) -> bool {
+        if position > self.bytes.len() {
+            return false;
+        }
+
+        self.position = position;
+        true
+    }
+
+    /// Skip over the given number of bytes, returning `false` if there aren't
+    /// enough left.
+    fn skip(&mut self, n: usize) -> bool {
+        match self.position.checked_add(n) {
+            Some(position) => self.seek(position),
+            None => false,
+        }
+    }
+
+    fn read_bytes(&mut self, n: usize) -> Option<Bytes> {
+        let end = self.position.checked_add(n)?;
+        let bytes = self.bytes.get(self.position..end)?.to_vec();
+        self.position = end;
+        Some(Bytes::from_vec(bytes))
+    }
+
+    fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let end = self.position.checked_add(N)?;
+        let array = <[u8; N]>::try_from(self.bytes.get(self.position..end)?).ok()?;
+        self.position = end;
+        Some(array)
+    }
+
+    read! {
+        read_u8 => u8, from_le_bytes, i64;
+        read_i8 => i8, from_le_bytes, i64;
+        read_u16_le => u16, from_le_bytes, i64;
+        read_u16_be => u16, from_be_bytes, i64;
+        read_i16_le => i16, from_le_bytes, i64;
+        read_i16_be => i16, from_be_bytes, i64;
+        read_u32_le => u32, from_le_bytes, i64;
+        read_u32_be => u32, from_be_bytes, i64;
+        read_i32_le => i32, from_le_bytes, i64;
+        read_i32_be => i32, from_be_bytes, i64;
+        read_i64_le => i64, from_le_bytes, i64;
+        read_i64_be => i64, from_be_bytes, i64;
+        read_f32_le => f32, from_le_bytes, f64;
+        read_f32_be => f32, from_be_bytes, f64;
+        read_f64_le => f64, from_le_bytes, f64;
+        read_f64_be => f64, from_be_bytes, f64;
+    }
+}
@@ -1,12 +1,28 @@
 //! The `std::cmp` module.
 
+use crate::runtime::Protocol;
 use crate::{ContextError, Module};
+use std::cmp::Ordering;
 
 /// Construct the `std::cmp` module.
 pub fn module() -> Result<Module, ContextError> {
     let mut module = Module::with_crate_item("std", &["cmp"]);
 
-    module.ty::<std::cmp::Ordering>()?;
+    module.ty::<Ordering>()?;
+
+    module.inst_fn("reverse", |this: &Ordering| this.reverse())?;
+    module.inst_fn("then", |this: &Ordering, other: &Ordering| {
+        this.then(*other)
+    })?;
+    module.inst_fn("is_eq", |this: &Ordering| this.is_eq())?;
+    module.inst_fn("is_ne", |this: &Ordering| this.is_ne())?;
+    module.inst_fn("is_lt", |this: &Ordering| this.is_lt())?;
+    module.inst_fn("is_gt", |this: &Ordering| this.is_gt())?;
+    module.inst_fn("is_le", |this: &Ordering| this.is_le())?;
+    module.inst_fn("is_ge", |this: &Ordering| this.is_ge())?;
+    module.inst_fn(Protocol::EQ, |this: &Ordering, other: &Ordering| {
+        this == other
+    })?;
 
     Ok(module)
 }
@@ -1,19 +1,25 @@
 //! `std::collections` module.
 
-use crate::runtime::{Iterator, IteratorTrait, Key, Protocol, Ref, Value, VmError, VmErrorKind};
+use crate::runtime::{
+    Determinism, Iterator, IteratorTrait, Key, Protocol, Ref, Value, VmError, VmErrorKind,
+};
 use crate::{Any, ContextError, Module};
+use std::collections::BTreeSet;
 use std::fmt;
+use std::vec;
 
 #[derive(Any, Clone)]
 #[rune(module = "crate")]
 struct HashMap {
     map: crate::collections::HashMap<Key, Value>,
+    custom: CustomKeys,
 }
 
 impl HashMap {
     fn new() -> Self {
         Self {
             map: crate::collections::HashMap::new(),
+            custom: CustomKeys::default(),
         }
     }
 
@@ -25,8 +31,8 @@ impl HashMap {
         let mut it = value.into_iter()?;
 
         while let Some(value) = it.next()? {
-            let (key, value) = <(Key, Value)>::from_value(value)?;
-            self.map.insert(key, value);
+            let (key, value) = <(Value, Value)>::from_value(value)?;
+            self.insert(key, value)?;
         }
 
         Ok(())
@@ -34,19 +40,26 @@ impl HashMap {
 
     #[inline]
     fn iter(&self) -> Iterator {
-        let iter = self.map.clone().into_iter();
-        Iterator::from("std::collections::map::Iter", iter)
+        let iter = collect(self.map.clone(), |(key, _)| key)
+            .map(|(key, value)| (self.custom.value(key), value));
+        Iterator::from(
+            "std::collections::map::Iter",
+            iter.collect::<Vec<_>>().into_iter(),
+        )
     }
 
     #[inline]
     fn keys(&self) -> Iterator {
-        let iter = self.map.keys().cloned().collect::<Vec<_>>().into_iter();
-        Iterator::from("std::collections::map::Keys", iter)
+        let iter = collect(self.map.keys().cloned(), |key| key).map(|key| self.custom.value(key));
+        Iterator::from(
+            "std::collections::map::Keys",
+            iter.collect::<Vec<_>>().into_iter(),
+        )
     }
 
     #[inline]
     fn values(&self) -> Iterator {
-        let iter = self.map.values().cloned().collect::<Vec<_>>().into_iter();
+        let iter = collect(self.map.clone(), |(key, _)| key).map(|(_, value)| value);
         Iterator::from("std::collections::map::Values", iter)
     }
 
@@ -56,8 +69,9 @@ impl HashMap {
     }
 
     #[inline]
-    fn insert(&mut self, key: Key, value: Value) -> Option<Value> {
-        self.map.insert(key, value)
+    fn insert(&mut self, key: Value, value: Value) -> Result<Option<Value>, VmError> {
+        let key = self.custom.insert(key)?;
+        Ok(self.map.insert(key, value))
     }
 
     #[inline]
@@ -91,12 +105,175 @@ impl HashMap {
 
     #[inline]
     fn clear(&mut self) {
-        self.map.clear()
+        self.map.clear();
+        self.custom.clear();
     }
 
     #[inline]
     fn remove(&mut self, key: Key) {
         self.map.remove(&key);
+        self.custom.remove(&key);
+    }
+
+    #[inline]
+    fn string_debug(&self, s: &mut String) -> fmt::Result {
+        use std::fmt::Write;
+
+        if Determinism::current().is_none() {
+            return write!(s, "{:?}", self.map);
+        }
+
+        write!(
+            s,
+            "{:?}",
+            self.map
+                .iter()
+                .collect::<std::collections::BTreeMap<_, _>>()
+        )
+    }
+}
+
+#[derive(Any, Clone, Default)]
+#[rune(module = "crate")]
+struct BTreeMap {
+    map: std::collections::BTreeMap<Key, Value>,
+    custom: CustomKeys,
+}
+
+impl BTreeMap {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Extend this map from an iterator.
+    #[inline]
+    fn extend(&mut self, value: Value) -> Result<(), VmError> {
+        use crate::runtime::FromValue;
+
+        let mut it = value.into_iter()?;
+
+        while let Some(value) = it.next()? {
+            let (key, value) = <(Value, Value)>::from_value(value)?;
+            self.insert(key, value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Iterate over the entries of the map, ordered by key.
+    #[inline]
+    fn iter(&self) -> Iterator {
+        let iter = self
+            .map
+            .iter()
+            .map(|(key, value)| (self.custom.value(key.clone()), value.clone()));
+
+        Iterator::from(
+            "std::collections::btree_map::Iter",
+            iter.collect::<Vec<_>>().into_iter(),
+        )
+    }
+
+    #[inline]
+    fn keys(&self) -> Iterator {
+        let iter = self.map.keys().map(|key| self.custom.value(key.clone()));
+
+        Iterator::from(
+            "std::collections::btree_map::Keys",
+            iter.collect::<Vec<_>>().into_iter(),
+        )
+    }
+
+    #[inline]
+    fn values(&self) -> Iterator {
+        let iter = self.map.values().cloned().collect::<Vec<_>>().into_iter();
+        Iterator::from("std::collections::btree_map::Values", iter)
+    }
+
+    #[inline]
+    fn contains_key(&self, key: Key) -> bool {
+        self.map.contains_key(&key)
+    }
+
+    #[inline]
+    fn insert(&mut self, key: Value, value: Value) -> Result<Option<Value>, VmError> {
+        let key = self.custom.insert(key)?;
+        Ok(self.map.insert(key, value))
+    }
+
+    #[inline]
+    fn get(&self, key: Key) -> Option<Value> {
+        self.map.get(&key).cloned()
+    }
+
+    #[inline]
+    fn fallible_get(&self, key: Key) -> Result<Value, VmError> {
+        use crate::runtime::TypeOf;
+
+        let value = self.map.get(&key).ok_or_else(|| {
+            VmError::from(VmErrorKind::MissingIndexKey {
+                target: Self::type_info(),
+                index: key,
+            })
+        })?;
+
+        Ok(value.clone())
+    }
+
+    /// Get the entry with the smallest key.
+    #[inline]
+    fn first(&self) -> Option<(Value, Value)> {
+        let (key, value) = self.map.iter().next()?;
+        Some((self.custom.value(key.clone()), value.clone()))
+    }
+
+    /// Get the entry with the largest key.
+    #[inline]
+    fn last(&self) -> Option<(Value, Value)> {
+        let (key, value) = self.map.iter().next_back()?;
+        Some((self.custom.value(key.clone()), value.clone()))
+    }
+
+    /// Remove and return the entry with the smallest key.
+    #[inline]
+    fn pop_first(&mut self) -> Option<(Value, Value)> {
+        let key = self.map.keys().next()?.clone();
+        self.remove_entry(key)
+    }
+
+    /// Remove and return the entry with the largest key.
+    #[inline]
+    fn pop_last(&mut self) -> Option<(Value, Value)> {
+        let key = self.map.keys().next_back()?.clone();
+        self.remove_entry(key)
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.map.clear();
+        self.custom.clear();
+    }
+
+    #[inline]
+    fn remove(&mut self, key: Key) -> Option<Value> {
+        Some(self.remove_entry(key)?.1)
+    }
+
+    fn remove_entry(&mut self, key: Key) -> Option<(Value, Value)> {
+        let value = self.map.remove(&key)?;
+        let key_value = self.custom.value(key.clone());
+        self.custom.remove(&key);
+        Some((key_value, value))
     }
 
     #[inline]
@@ -104,18 +281,35 @@ impl HashMap {
         use std::fmt::Write;
         write!(s, "{:?}", self.map)
     }
+
+    #[inline]
+    fn eq(&self, other: &Self) -> Result<bool, VmError> {
+        if self.map.len() != other.map.len() {
+            return Ok(false);
+        }
+
+        for ((a_key, a), (b_key, b)) in self.map.iter().zip(&other.map) {
+            if a_key != b_key || !Value::value_eq(a, b)? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
 }
 
 #[derive(Any, Clone)]
 #[rune(module = "crate")]
 struct HashSet {
     set: crate::collections::HashSet<Key>,
+    custom: CustomKeys,
 }
 
 impl HashSet {
     fn new() -> Self {
         Self {
             set: crate::collections::HashSet::new(),
+            custom: CustomKeys::default(),
         }
     }
 
@@ -125,8 +319,7 @@ impl HashSet {
         let mut it = value.into_iter()?;
 
         while let Some(value) = it.next()? {
-            let key = Key::from_value(&value)?;
-            self.set.insert(key);
+            self.insert(value)?;
         }
 
         Ok(())
@@ -134,13 +327,25 @@ impl HashSet {
 
     #[inline]
     fn iter(&self) -> Iterator {
-        let iter = self.set.clone().into_iter();
-        Iterator::from("std::collections::set::Iter", iter)
+        Iterator::from(
+            "std::collections::set::Iter",
+            self.entries().map(|(_, value)| value),
+        )
+    }
+
+    /// Collect the keys in the set together with the values they were
+    /// inserted as.
+    fn entries(&self) -> vec::IntoIter<(Key, Value)> {
+        collect(self.set.iter(), |key| *key)
+            .map(|key| (key.clone(), self.custom.value(key.clone())))
+            .collect::<Vec<_>>()
+            .into_iter()
     }
 
     #[inline]
-    fn insert(&mut self, key: Key) -> bool {
-        self.set.insert(key)
+    fn insert(&mut self, key: Value) -> Result<bool, VmError> {
+        let key = self.custom.insert(key)?;
+        Ok(self.set.insert(key))
     }
 
     #[inline]
@@ -160,12 +365,29 @@ impl HashSet {
 
     #[inline]
     fn clear(&mut self) {
-        self.set.clear()
+        self.set.clear();
+        self.custom.clear();
     }
 
     #[inline]
-    fn remove(&mut self, key: Key) {
-        self.set.remove(&key);
+    fn remove(&mut self, key: Key) -> bool {
+        self.custom.remove(&key);
+        self.set.remove(&key)
+    }
+
+    #[inline]
+    fn is_subset(&self, other: &Self) -> bool {
+        self.set.is_subset(&other.set)
+    }
+
+    #[inline]
+    fn is_superset(&self, other: &Self) -> bool {
+        self.set.is_superset(&other.set)
+    }
+
+    #[inline]
+    fn is_disjoint(&self, other: &Self) -> bool {
+        self.set.is_disjoint(&other.set)
     }
 
     #[inline]
@@ -173,7 +395,7 @@ impl HashSet {
         Iterator::from(
             "std::collections::set::Difference",
             Difference {
-                this: self.set.clone().into_iter(),
+                this: self.entries(),
                 other: Some(other),
             },
         )
@@ -184,12 +406,12 @@ impl HashSet {
         // use shortest iterator as driver for intersections
         let intersection = if zelf.len() <= other.len() {
             Intersection {
-                this: zelf.set.clone().into_iter(),
+                this: zelf.entries(),
                 other: Some(other),
             }
         } else {
             Intersection {
-                this: other.set.clone().into_iter(),
+                this: other.entries(),
                 other: Some(zelf),
             }
         };
@@ -213,7 +435,12 @@ impl HashSet {
     #[inline]
     fn string_debug(&self, s: &mut String) -> fmt::Result {
         use std::fmt::Write;
-        write!(s, "{:?}", self.set)
+
+        if Determinism::current().is_none() {
+            return write!(s, "{:?}", self.set);
+        }
+
+        write!(s, "{:?}", self.set.iter().collect::<BTreeSet<_>>())
     }
 
     #[inline]
@@ -224,7 +451,7 @@ impl HashSet {
 
 struct Intersection<I>
 where
-    I: std::iter::Iterator<Item = Key>,
+    I: std::iter::Iterator<Item = (Key, Value)>,
 {
     this: I,
     other: Option<Ref<HashSet>>,
@@ -232,18 +459,18 @@ where
 
 impl<I> std::iter::Iterator for Intersection<I>
 where
-    I: std::iter::Iterator<Item = Key>,
+    I: std::iter::Iterator<Item = (Key, Value)>,
 {
-    type Item = Key;
+    type Item = Value;
     fn next(&mut self) -> Option<Self::Item> {
         let other = self.other.take()?;
 
         loop {
-            let item = self.this.next()?;
+            let (key, value) = self.this.next()?;
 
-            if other.set.contains(&item) {
+            if other.set.contains(&key) {
                 self.other = Some(other);
-                return Some(item);
+                return Some(value);
             }
         }
     }
@@ -257,7 +484,7 @@ where
 
 struct Difference<I>
 where
-    I: std::iter::Iterator<Item = Key>,
+    I: std::iter::Iterator<Item = (Key, Value)>,
 {
     this: I,
     other: Option<Ref<HashSet>>,
@@ -265,19 +492,19 @@ where
 
 impl<I> std::iter::Iterator for Difference<I>
 where
-    I: std::iter::Iterator<Item = Key>,
+    I: std::iter::Iterator<Item = (Key, Value)>,
 {
-    type Item = Key;
+    type Item = Value;
 
     fn next(&mut self) -> Option<Self::Item> {
         let other = self.other.take()?;
 
         loop {
-            let item = self.this.next()?;
+            let (key, value) = self.this.next()?;
 
-            if !other.set.contains(&item) {
+            if !other.set.contains(&key) {
                 self.other = Some(other);
-                return Some(item);
+                return Some(value);
             }
         }
     }
@@ -289,6 +516,42 @@ where
     }
 }
 
+/// The values of keys which are hashed through their [Protocol::HASH]
+/// function, so that the original values can be handed back when iterating.
+#[derive(Clone, Default)]
+struct CustomKeys {
+    values: crate::collections::HashMap<Key, Value>,
+}
+
+impl CustomKeys {
+    /// Convert a value into a key, remembering the value if it's a custom key.
+    fn insert(&mut self, value: Value) -> Result<Key, VmError> {
+        let key = Key::from_value(&value)?;
+
+        if let Key::Custom(..) = &key {
+            self.values.entry(key.clone()).or_insert(value);
+        }
+
+        Ok(key)
+    }
+
+    /// Get the value of the given key.
+    fn value(&self, key: Key) -> Value {
+        match self.values.get(&key) {
+            Some(value) => value.clone(),
+            None => key.into_value(),
+        }
+    }
+
+    fn remove(&mut self, key: &Key) {
+        self.values.remove(key);
+    }
+
+    fn clear(&mut self) {
+        self.values.clear();
+    }
+}
+
 struct Union {
     iter: Iterator,
 }
@@ -337,7 +600,7 @@ impl VecDeque {
     }
 
     fn rotate_right(&mut self, mid: usize) {
-        self.inner.rotate_left(mid);
+        self.inner.rotate_right(mid);
     }
 
     fn push_front(&mut self, v: Value) {
@@ -356,37 +619,69 @@ impl VecDeque {
         self.inner.pop_back()
     }
 
-    fn remove(&mut self, index: usize) {
-        self.inner.remove(index);
+    fn front(&self) -> Option<Value> {
+        self.inner.front().cloned()
+    }
+
+    fn back(&self) -> Option<Value> {
+        self.inner.back().cloned()
+    }
+
+    fn remove(&mut self, index: usize) -> Option<Value> {
+        self.inner.remove(index)
     }
 
     fn reserve(&mut self, index: usize) {
         self.inner.reserve(index);
     }
 
-    fn len(&mut self) -> usize {
+    fn len(&self) -> usize {
         self.inner.len()
     }
 
+    fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    fn clear(&mut self) {
+        self.inner.clear();
+    }
+
+    /// Test if the deque contains a value equal to the given one.
+    fn contains(&self, value: Value) -> Result<bool, VmError> {
+        for v in &self.inner {
+            if Value::value_eq(v, &value)? {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
     fn get(&self, index: usize) -> Result<Value, VmError> {
-        if index > self.inner.len() {
-            return Err(VmError::from(VmErrorKind::OutOfRange {
-                index: index.into(),
-                len: self.inner.len().into(),
-            }));
+        match self.inner.get(index) {
+            Some(value) => Ok(value.clone()),
+            None => Err(self.out_of_range(index)),
         }
-        Ok(self.inner[index].clone())
     }
 
     fn set(&mut self, index: usize, value: Value) -> Result<(), VmError> {
-        if index > self.inner.len() {
-            return Err(VmError::from(VmErrorKind::OutOfRange {
-                index: index.into(),
-                len: self.inner.len().into(),
-            }));
+        let error = self.out_of_range(index);
+
+        match self.inner.get_mut(index) {
+            Some(slot) => {
+                *slot = value;
+                Ok(())
+            }
+            None => Err(error),
         }
-        self.inner[index] = value;
-        Ok(())
+    }
+
+    fn out_of_range(&self, index: usize) -> VmError {
+        VmError::from(VmErrorKind::OutOfRange {
+            index: index.into(),
+            len: self.inner.len().into(),
+        })
     }
 
     fn insert(&mut self, index: usize, value: Value) {
@@ -404,6 +699,21 @@ impl VecDeque {
         use std::fmt::Write;
         write!(s, "{:?}", self.inner)
     }
+
+    #[inline]
+    fn eq(&self, other: &Self) -> Result<bool, VmError> {
+        if self.inner.len() != other.inner.len() {
+            return Ok(false);
+        }
+
+        for (a, b) in self.inner.iter().zip(&other.inner) {
+            if !Value::value_eq(a, b)? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
 }
 
 /// The `std::collections` module.
@@ -429,6 +739,31 @@ pub fn module() -> Result<Module, ContextError> {
     module.inst_fn(Protocol::INDEX_GET, HashMap::fallible_get)?;
     module.inst_fn(Protocol::STRING_DEBUG, HashMap::string_debug)?;
 
+    module.ty::<BTreeMap>()?;
+    module.function(&["BTreeMap", "new"], BTreeMap::new)?;
+    module.function(&["BTreeMap", "from"], btreemap_from)?;
+    module.inst_fn("clear", BTreeMap::clear)?;
+    module.inst_fn("clone", BTreeMap::clone)?;
+    module.inst_fn("contains_key", BTreeMap::contains_key)?;
+    module.inst_fn("extend", BTreeMap::extend)?;
+    module.inst_fn("first", BTreeMap::first)?;
+    module.inst_fn("get", BTreeMap::get)?;
+    module.inst_fn("insert", BTreeMap::insert)?;
+    module.inst_fn("is_empty", BTreeMap::is_empty)?;
+    module.inst_fn("iter", BTreeMap::iter)?;
+    module.inst_fn("keys", BTreeMap::keys)?;
+    module.inst_fn("last", BTreeMap::last)?;
+    module.inst_fn("len", BTreeMap::len)?;
+    module.inst_fn("pop_first", BTreeMap::pop_first)?;
+    module.inst_fn("pop_last", BTreeMap::pop_last)?;
+    module.inst_fn("remove", BTreeMap::remove)?;
+    module.inst_fn("values", BTreeMap::values)?;
+    module.inst_fn(Protocol::INTO_ITER, BTreeMap::iter)?;
+    module.inst_fn(Protocol::INDEX_SET, BTreeMap::insert)?;
+    module.inst_fn(Protocol::INDEX_GET, BTreeMap::fallible_get)?;
+    module.inst_fn(Protocol::STRING_DEBUG, BTreeMap::string_debug)?;
+    module.inst_fn(Protocol::EQ, BTreeMap::eq)?;
+
     module.ty::<HashSet>()?;
     module.function(&["HashSet", "new"], HashSet::new)?;
     module.function(&["HashSet", "from"], hashset_from)?;
@@ -439,7 +774,10 @@ pub fn module() -> Result<Module, ContextError> {
     module.inst_fn("extend", HashSet::extend)?;
     module.inst_fn("insert", HashSet::insert)?;
     module.inst_fn("intersection", HashSet::intersection)?;
+    module.inst_fn("is_disjoint", HashSet::is_disjoint)?;
     module.inst_fn("is_empty", HashSet::is_empty)?;
+    module.inst_fn("is_subset", HashSet::is_subset)?;
+    module.inst_fn("is_superset", HashSet::is_superset)?;
     module.inst_fn("iter", HashSet::iter)?;
     module.inst_fn("len", HashSet::len)?;
     module.inst_fn("remove", HashSet::remove)?;
@@ -453,8 +791,14 @@ pub fn module() -> Result<Module, ContextError> {
     module.function(&["VecDeque", "with_capacity"], VecDeque::with_capacity)?;
     module.function(&["VecDeque", "from"], vecdeque_from)?;
 
+    module.inst_fn("back", VecDeque::back)?;
+    module.inst_fn("clear", VecDeque::clear)?;
+    module.inst_fn("clone", VecDeque::clone)?;
+    module.inst_fn("contains", VecDeque::contains)?;
     module.inst_fn("extend", VecDeque::extend)?;
+    module.inst_fn("front", VecDeque::front)?;
     module.inst_fn("insert", VecDeque::insert)?;
+    module.inst_fn("is_empty", VecDeque::is_empty)?;
     module.inst_fn("iter", VecDeque::iter)?;
     module.inst_fn("len", VecDeque::len)?;
     module.inst_fn("pop_back", VecDeque::pop_back)?;
@@ -469,6 +813,7 @@ pub fn module() -> Result<Module, ContextError> {
     module.inst_fn(Protocol::INDEX_SET, VecDeque::set)?;
     module.inst_fn(Protocol::INTO_ITER, VecDeque::iter)?;
     module.inst_fn(Protocol::STRING_DEBUG, VecDeque::string_debug)?;
+    module.inst_fn(Protocol::EQ, VecDeque::eq)?;
 
     Ok(module)
 }
@@ -480,13 +825,19 @@ fn hashmap_from(value: Value) -> Result<HashMap, VmError> {
     let mut it = value.into_iter()?;
 
     while let Some(value) = it.next()? {
-        let (key, value) = <(Key, Value)>::from_value(value)?;
-        map.insert(key, value);
+        let (key, value) = <(Value, Value)>::from_value(value)?;
+        map.insert(key, value)?;
     }
 
     Ok(map)
 }
 
+fn btreemap_from(value: Value) -> Result<BTreeMap, VmError> {
+    let mut map = BTreeMap::new();
+    map.extend(value)?;
+    Ok(map)
+}
+
 fn vecdeque_from(value: Value) -> Result<VecDeque, VmError> {
     let mut cont = VecDeque::new();
     let mut it = value.into_iter()?;
@@ -503,8 +854,25 @@ fn hashset_from(value: Value) -> Result<HashSet, VmError> {
     let mut it = value.into_iter()?;
 
     while let Some(value) = it.next()? {
-        set.insert(Key::from_value(&value)?);
+        set.insert(value)?;
     }
 
     Ok(set)
 }
+
+/// Collect the items of a hash collection.
+///
+/// Hash collections iterate in an order which differs between processes, so
+/// the items are sorted by key if the virtual machine is deterministic.
+fn collect<T, K>(items: impl IntoIterator<Item = T>, key: impl Fn(&T) -> &K) -> vec::IntoIter<T>
+where
+    K: Ord,
+{
+    let mut items = items.into_iter().collect::<Vec<_>>();
+
+    if Determinism::current().is_some() {
+        items.sort_by(|a, b| key(a).cmp(key(b)));
+    }
+
+    items.into_iter()
+}
@@ -1,6 +1,6 @@
 //! The core `std` module.
 
-use crate::runtime::{Panic, Value};
+use crate::runtime::{Object, Panic, Shared, Value};
 use crate::{ContextError, Module};
 
 /// Construct the `std` module.
@@ -17,6 +17,7 @@ pub fn module() -> Result<Module, ContextError> {
     module.function(&["panic"], panic_impl)?;
     module.function(&["is_readable"], is_readable)?;
     module.function(&["is_writable"], is_writable)?;
+    module.function(&["globals"], globals)?;
     Ok(module)
 }
 
@@ -24,6 +25,11 @@ fn panic_impl(m: &str) -> Result<(), Panic> {
     Err(Panic::custom(m.to_owned()))
 }
 
+/// Get the object holding the global variables of the virtual machine.
+fn globals() -> Shared<Object> {
+    crate::runtime::env::globals().unwrap_or_else(|| Shared::tracked(Object::new()))
+}
+
 fn is_readable(value: Value) -> bool {
     match value {
         Value::Any(any) => any.is_readable(),
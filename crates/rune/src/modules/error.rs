@@ -0,0 +1,46 @@
+//! The `std::error` module.
+
+use crate::runtime::Protocol;
+use crate::{ContextError, Module};
+use std::fmt;
+use std::fmt::Write as _;
+
+/// Construct the `std::error` module.
+///
+/// This provides `std::error::Error`, which is how errors returned from
+/// native functions as [anyhow::Error] are represented in scripts.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::with_crate_item("std", &["error"]);
+
+    module.ty::<anyhow::Error>()?;
+    module.function(&["Error", "new"], error_new)?;
+    module.inst_fn("message", |error: &anyhow::Error| error.to_string())?;
+    module.inst_fn("chain", error_chain)?;
+    module.inst_fn("root_cause", |error: &anyhow::Error| {
+        error.root_cause().to_string()
+    })?;
+    module.inst_fn("context", error_context)?;
+    module.inst_fn(Protocol::STRING_DISPLAY, format_error)?;
+    module.inst_fn(Protocol::STRING_DEBUG, format_error_debug)?;
+    Ok(module)
+}
+
+fn error_new(message: &str) -> anyhow::Error {
+    anyhow::Error::msg(message.to_owned())
+}
+
+fn error_chain(error: &anyhow::Error) -> Vec<String> {
+    error.chain().map(|cause| cause.to_string()).collect()
+}
+
+fn error_context(error: anyhow::Error, context: &str) -> anyhow::Error {
+    error.context(context.to_owned())
+}
+
+fn format_error(error: &anyhow::Error, buf: &mut String) -> fmt::Result {
+    write!(buf, "{}", error)
+}
+
+fn format_error_debug(error: &anyhow::Error, buf: &mut String) -> fmt::Result {
+    write!(buf, "{:?}", error)
+}
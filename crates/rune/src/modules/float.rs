@@ -1,9 +1,11 @@
 //! The `std::float` module.
 
+use crate::runtime::{Protocol, VmError};
 use crate::{ContextError, Module};
+use std::fmt::{self, Write as _};
 use std::num::ParseFloatError;
 
-/// Parse an integer.
+/// Parse a float, which is independent of the current locale.
 fn parse(s: &str) -> Result<f64, ParseFloatError> {
     str::parse::<f64>(s)
 }
@@ -13,6 +15,56 @@ fn to_integer(value: f64) -> i64 {
     value as i64
 }
 
+/// Format a float with exactly the given number of digits after the decimal
+/// point.
+fn to_fixed(value: f64, digits: usize) -> String {
+    format!("{:.*}", digits, value)
+}
+
+/// Format a float with the given number of significant digits, switching to
+/// exponential notation for very large or very small numbers.
+fn to_precision(value: f64, precision: usize) -> Result<String, VmError> {
+    if precision == 0 {
+        return Err(VmError::panic("precision must be greater than zero"));
+    }
+
+    if !value.is_finite() || value == 0.0 {
+        return Ok(format!("{:.*}", precision - 1, value));
+    }
+
+    // NB: the exponent is taken after rounding to the given precision, since
+    // rounding might carry over into another digit.
+    let exponential = format!("{:.*e}", precision - 1, value);
+
+    let exponent = match exponential.rsplit_once('e') {
+        Some((_, exponent)) => exponent.parse::<i64>().unwrap_or_default(),
+        None => 0,
+    };
+
+    if exponent < -6 || exponent >= precision as i64 {
+        return Ok(exponential);
+    }
+
+    let digits = (precision as i64 - 1 - exponent).max(0) as usize;
+    Ok(format!("{:.*}", digits, value))
+}
+
+/// Restrict a value to the given interval.
+fn clamp(value: f64, min: f64, max: f64) -> Result<f64, VmError> {
+    if min.is_nan() || max.is_nan() || min > max {
+        return Err(VmError::panic(format!(
+            "invalid clamp interval, expected {} <= {}",
+            min, max
+        )));
+    }
+
+    Ok(value.clamp(min, max))
+}
+
+fn parse_error_display(error: &ParseFloatError, s: &mut String) -> fmt::Result {
+    write!(s, "{}", error)
+}
+
 crate::__internal_impl_any!(ParseFloatError);
 
 /// Install the core package into the given functions namespace.
@@ -20,12 +72,23 @@ pub fn module() -> Result<Module, ContextError> {
     let mut module = Module::with_crate_item("std", &["float"]);
 
     module.ty::<ParseFloatError>()?;
+    module.inst_fn(Protocol::STRING_DISPLAY, parse_error_display)?;
     module.function(&["parse"], parse)?;
     module.inst_fn("max", f64::max)?;
     module.inst_fn("min", f64::min)?;
     module.inst_fn("abs", f64::abs)?;
     module.inst_fn("powf", f64::powf)?;
     module.inst_fn("powi", f64::powi)?;
+    module.inst_fn("floor", f64::floor)?;
+    module.inst_fn("ceil", f64::ceil)?;
+    module.inst_fn("round", f64::round)?;
+    module.inst_fn("trunc", f64::trunc)?;
+    module.inst_fn("clamp", clamp)?;
+    module.inst_fn("is_nan", f64::is_nan)?;
+    module.inst_fn("is_finite", f64::is_finite)?;
+    module.inst_fn("is_infinite", f64::is_infinite)?;
+    module.inst_fn("to_fixed", to_fixed)?;
+    module.inst_fn("to_precision", to_precision)?;
 
     module.inst_fn("to_integer", to_integer)?;
 
@@ -1,7 +1,11 @@
 //! The `std::future` module.
+//!
+//! Futures can't be given a deadline here, since that needs a timer which
+//! this crate doesn't provide. `time::timeout` in the `time` module of
+//! `rune-modules` does that instead.
 
 use crate::runtime::future::SelectFuture;
-use crate::runtime::{Future, Shared, Stack, Value, VmError, VmErrorKind};
+use crate::runtime::{Future, Shared, Stack, Suspend, Tuple, Value, VmError, VmErrorKind};
 use crate::{ContextError, Module};
 
 /// Construct the `std::future` module.
@@ -9,6 +13,10 @@ pub fn module() -> Result<Module, ContextError> {
     let mut module = Module::with_crate_item("std", &["future"]);
     module.ty::<Future>()?;
     module.raw_fn(&["join"], raw_join)?;
+    module.function(&["join_all"], join_all)?;
+    module.function(&["race"], race)?;
+    module.ty::<Suspend>()?;
+    module.function(&["suspend"], Suspend::new)?;
     Ok(module)
 }
 
@@ -55,16 +63,75 @@ async fn join(value: Value) -> Result<Value, VmError> {
 }
 
 /// The join implementation.
+///
+/// This either joins a single tuple or vector of futures, or all of its
+/// arguments if there are more than one of them or the only one is a future.
 fn raw_join(stack: &mut Stack, args: usize) -> Result<(), VmError> {
-    if args != 1 {
+    if args == 0 {
         return Err(VmError::from(VmErrorKind::BadArgumentCount {
             actual: args,
             expected: 1,
         }));
     }
 
-    let value = stack.pop()?;
-    let value = Value::Future(Shared::new(Future::new(join(value))));
+    let value = if args == 1 && !matches!(stack.last()?, Value::Future(..)) {
+        stack.pop()?
+    } else {
+        let values = stack.drain(args)?.collect::<Vec<_>>();
+        Value::from(Tuple::from(values))
+    };
+
+    let value = Value::Future(Shared::tracked(Future::new(join(value))));
     stack.push(value);
     Ok(())
 }
+
+/// Collect the futures produced by something that can be iterated over.
+fn collect_futures(value: Value) -> Result<Vec<Value>, VmError> {
+    let mut it = value.into_iter()?;
+    let mut values = Vec::new();
+
+    while let Some(value) = it.next()? {
+        values.push(value);
+    }
+
+    Ok(values)
+}
+
+/// Wait for all the futures produced by an iterator to complete, producing a
+/// vector of their outputs in order.
+fn join_all(value: Value) -> Result<Future, VmError> {
+    let values = collect_futures(value)?;
+
+    Ok(Future::new(async move {
+        try_join_impl(values.iter(), values.len(), Value::vec).await
+    }))
+}
+
+/// Wait for the first of the futures produced by an iterator to complete,
+/// producing its output and dropping the rest of them.
+fn race(value: Value) -> Result<Future, VmError> {
+    use futures_util::stream::StreamExt as _;
+
+    let values = collect_futures(value)?;
+
+    if values.is_empty() {
+        return Err(VmError::panic("cannot race an empty collection of futures"));
+    }
+
+    let mut futures = futures_util::stream::FuturesUnordered::new();
+
+    for (index, value) in values.into_iter().enumerate() {
+        let future = match value {
+            Value::Future(future) => future.into_mut()?,
+            value => return Err(VmError::bad_argument::<Future>(index, &value)?),
+        };
+
+        futures.push(SelectFuture::new(index, future));
+    }
+
+    Ok(Future::new(async move {
+        let (_, value) = futures.next().await.unwrap()?;
+        Ok(value)
+    }))
+}
@@ -1,6 +1,8 @@
 //! The `std::int` module.
 
+use crate::runtime::{VmError, VmErrorKind};
 use crate::{ContextError, Module};
+use std::cmp::Ordering;
 use std::num::ParseIntError;
 
 /// Construct the `std::int` module.
@@ -14,24 +16,42 @@ pub fn module() -> Result<Module, ContextError> {
 
     module.inst_fn("max", i64::max)?;
     module.inst_fn("min", i64::min)?;
-    module.inst_fn("abs", i64::abs)?;
-    module.inst_fn("pow", i64::pow)?;
+    module.inst_fn("abs", abs)?;
+    module.inst_fn("pow", pow)?;
+    module.inst_fn("cmp", cmp)?;
 
     module.inst_fn("checked_add", i64::checked_add)?;
     module.inst_fn("checked_sub", i64::checked_sub)?;
     module.inst_fn("checked_div", i64::checked_div)?;
     module.inst_fn("checked_mul", i64::checked_mul)?;
     module.inst_fn("checked_rem", i64::checked_rem)?;
+    module.inst_fn("checked_neg", i64::checked_neg)?;
+    module.inst_fn("checked_abs", i64::checked_abs)?;
+    module.inst_fn("checked_pow", i64::checked_pow)?;
 
     module.inst_fn("wrapping_add", i64::wrapping_add)?;
     module.inst_fn("wrapping_sub", i64::wrapping_sub)?;
     module.inst_fn("wrapping_div", i64::wrapping_div)?;
     module.inst_fn("wrapping_mul", i64::wrapping_mul)?;
     module.inst_fn("wrapping_rem", i64::wrapping_rem)?;
+    module.inst_fn("wrapping_neg", i64::wrapping_neg)?;
+    module.inst_fn("wrapping_abs", i64::wrapping_abs)?;
+    module.inst_fn("wrapping_pow", i64::wrapping_pow)?;
+
+    module.inst_fn("overflowing_add", i64::overflowing_add)?;
+    module.inst_fn("overflowing_sub", i64::overflowing_sub)?;
+    module.inst_fn("overflowing_div", i64::overflowing_div)?;
+    module.inst_fn("overflowing_mul", i64::overflowing_mul)?;
+    module.inst_fn("overflowing_rem", i64::overflowing_rem)?;
+    module.inst_fn("overflowing_neg", i64::overflowing_neg)?;
+    module.inst_fn("overflowing_abs", i64::overflowing_abs)?;
+    module.inst_fn("overflowing_pow", i64::overflowing_pow)?;
 
     module.inst_fn("saturating_add", i64::saturating_add)?;
     module.inst_fn("saturating_sub", i64::saturating_sub)?;
     module.inst_fn("saturating_mul", i64::saturating_mul)?;
+    module.inst_fn("saturating_div", i64::saturating_div)?;
+    module.inst_fn("saturating_neg", i64::saturating_neg)?;
     module.inst_fn("saturating_abs", i64::saturating_abs)?;
     module.inst_fn("saturating_pow", i64::saturating_pow)?;
 
@@ -43,6 +63,25 @@ fn parse(s: &str) -> Result<i64, ParseIntError> {
     str::parse::<i64>(s)
 }
 
+/// The absolute value of an integer, erroring on overflow.
+fn abs(value: i64) -> Result<i64, VmError> {
+    value
+        .checked_abs()
+        .ok_or_else(|| VmError::from(VmErrorKind::Overflow))
+}
+
+/// Raise an integer to the given power, erroring on overflow.
+fn pow(value: i64, exp: u32) -> Result<i64, VmError> {
+    value
+        .checked_pow(exp)
+        .ok_or_else(|| VmError::from(VmErrorKind::Overflow))
+}
+
+/// Compare two integers.
+fn cmp(this: i64, other: i64) -> Ordering {
+    this.cmp(&other)
+}
+
 /// Convert a whole number to float.
 fn to_float(value: i64) -> f64 {
     value as f64
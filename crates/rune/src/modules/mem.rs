@@ -1,16 +1,97 @@
 //! The `std::mem` module.
 
-use crate::runtime::{Value, VmError};
-use crate::{ContextError, Module};
+use crate::runtime::{
+    AnyObj, Bytes, Function, Future, Generator, GeneratorState, Iterator, Object, Range, Stream,
+    Struct, Tuple, TupleStruct, UnitStruct, Value, Variant, Vec, Vm, VmError,
+};
+use crate::{Any, ContextError, Module};
 
 /// Construct the `std` module.
 pub fn module() -> Result<Module, ContextError> {
     let mut module = Module::with_crate_item("std", &["mem"]);
+    module.ty::<Weak>()?;
     module.function(&["drop"], drop_impl)?;
+    module.function(&["weak"], Weak::new)?;
+    module.inst_fn("upgrade", Weak::upgrade)?;
+    module.inst_fn("is_dropped", Weak::is_dropped)?;
+    module.inst_fn("clone", Weak::clone)?;
     Ok(module)
 }
 
 fn drop_impl(value: Value) -> Result<(), VmError> {
-    value.take()?;
+    crate::runtime::finalize(&value)?;
+    // NB: the data taken out is moved into a new value, which has already
+    // been finalized.
+    crate::runtime::take_finalizer(&value.take()?);
     Ok(())
 }
+
+/// A weak reference to a value, which doesn't keep it alive.
+///
+/// Values which aren't reference counted, like integers, are held as is and
+/// are never dropped.
+#[derive(Any, Clone)]
+#[rune(module = "crate")]
+struct Weak {
+    inner: Inner,
+}
+
+macro_rules! weak {
+    ($($variant:ident => $ty:ty),* $(,)?) => {
+        #[derive(Clone)]
+        enum Inner {
+            Value(Value),
+            $($variant(crate::runtime::Weak<$ty>),)*
+        }
+
+        impl Weak {
+            /// Construct a weak reference to the given value.
+            fn new(value: Value) -> Self {
+                let inner = match &value {
+                    $(Value::$variant(shared) => Inner::$variant(shared.downgrade()),)*
+                    _ => Inner::Value(value),
+                };
+
+                Self { inner }
+            }
+
+            /// Get the value, unless it has already been dropped.
+            fn upgrade(&self) -> Option<Value> {
+                Some(match &self.inner {
+                    Inner::Value(value) => value.clone(),
+                    $(Inner::$variant(weak) => Value::$variant(weak.upgrade()?),)*
+                })
+            }
+
+            /// Test if the value has been dropped.
+            fn is_dropped(&self) -> bool {
+                match &self.inner {
+                    Inner::Value(..) => false,
+                    $(Inner::$variant(weak) => weak.is_dropped(),)*
+                }
+            }
+        }
+    };
+}
+
+weak! {
+    String => String,
+    Bytes => Bytes,
+    Vec => Vec,
+    Tuple => Tuple,
+    Object => Object,
+    Range => Range,
+    Future => Future,
+    Stream => Stream<Vm>,
+    Generator => Generator<Vm>,
+    GeneratorState => GeneratorState,
+    Option => Option<Value>,
+    Result => Result<Value, Value>,
+    UnitStruct => UnitStruct,
+    TupleStruct => TupleStruct,
+    Struct => Struct,
+    Variant => Variant,
+    Function => Function,
+    Iterator => Iterator,
+    Any => AnyObj,
+}
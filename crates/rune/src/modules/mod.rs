@@ -7,6 +7,7 @@ pub mod char;
 pub mod cmp;
 pub mod collections;
 pub mod core;
+pub mod error;
 pub mod float;
 pub mod fmt;
 pub mod future;
@@ -15,10 +16,12 @@ pub mod int;
 pub mod io;
 pub mod iter;
 pub mod mem;
+pub mod num;
 pub mod object;
 pub mod ops;
 pub mod option;
 pub mod result;
 pub mod stream;
 pub mod string;
+pub mod sync;
 pub mod vec;
@@ -0,0 +1,220 @@
+//! The `std::num` module.
+
+use crate::runtime::{FromValue, Protocol, Ref, Value, VmError, VmErrorKind};
+use crate::{Any, ContextError, Module};
+use num::{Integer as _, Signed as _, ToPrimitive as _, Zero as _};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Construct the `std::num` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::with_crate_item("std", &["num"]);
+
+    module.ty::<BigInt>()?;
+    module.function(&["BigInt", "new"], BigInt::new)?;
+    module.function(&["BigInt", "parse"], BigInt::parse)?;
+    module.function(&["BigInt", "parse_radix"], BigInt::parse_radix)?;
+
+    module.inst_fn("to_int", BigInt::to_int)?;
+    module.inst_fn("to_float", BigInt::to_float)?;
+    module.inst_fn("to_string_radix", BigInt::to_string_radix)?;
+    module.inst_fn("abs", BigInt::abs)?;
+    module.inst_fn("signum", BigInt::signum)?;
+    module.inst_fn("is_zero", BigInt::is_zero)?;
+    module.inst_fn("pow", BigInt::pow)?;
+    module.inst_fn("modpow", BigInt::modpow)?;
+    module.inst_fn("gcd", BigInt::gcd)?;
+    module.inst_fn("neg", BigInt::neg)?;
+    module.inst_fn("clone", BigInt::clone)?;
+    module.inst_fn("cmp", BigInt::cmp)?;
+
+    module.inst_fn(Protocol::ADD, BigInt::add)?;
+    module.inst_fn(Protocol::ADD_ASSIGN, BigInt::add_assign)?;
+    module.inst_fn(Protocol::SUB, BigInt::sub)?;
+    module.inst_fn(Protocol::SUB_ASSIGN, BigInt::sub_assign)?;
+    module.inst_fn(Protocol::MUL, BigInt::mul)?;
+    module.inst_fn(Protocol::MUL_ASSIGN, BigInt::mul_assign)?;
+    module.inst_fn(Protocol::DIV, BigInt::div)?;
+    module.inst_fn(Protocol::DIV_ASSIGN, BigInt::div_assign)?;
+    module.inst_fn(Protocol::REM, BigInt::rem)?;
+    module.inst_fn(Protocol::REM_ASSIGN, BigInt::rem_assign)?;
+    module.inst_fn(Protocol::EQ, BigInt::eq)?;
+    module.inst_fn(Protocol::STRING_DISPLAY, BigInt::string_display)?;
+    module.inst_fn(Protocol::STRING_DEBUG, BigInt::string_debug)?;
+    Ok(module)
+}
+
+/// An arbitrary-precision integer.
+///
+/// Arithmetic works with other big integers or with integers on the right
+/// hand side, so an integer has to be converted with `BigInt::new` to be used
+/// on the left hand side.
+#[derive(Any, Clone)]
+#[rune(module = "crate")]
+struct BigInt(num_bigint::BigInt);
+
+/// Coerce the right hand side of an operation into a big integer.
+fn operand(value: Value) -> Result<num_bigint::BigInt, VmError> {
+    Ok(match value {
+        Value::Integer(n) => num_bigint::BigInt::from(n),
+        value => Ref::<BigInt>::from_value(value)?.0.clone(),
+    })
+}
+
+/// Coerce a divisor, erroring if it's zero.
+fn divisor(value: Value) -> Result<num_bigint::BigInt, VmError> {
+    let value = operand(value)?;
+
+    if value.is_zero() {
+        return Err(VmError::from(VmErrorKind::DivideByZero));
+    }
+
+    Ok(value)
+}
+
+/// Check that a radix is supported.
+fn radix(radix: u32) -> Result<u32, VmError> {
+    if !(2..=36).contains(&radix) {
+        return Err(VmError::panic(format!(
+            "radix must be between 2 and 36, but was {}",
+            radix
+        )));
+    }
+
+    Ok(radix)
+}
+
+impl BigInt {
+    fn new(value: i64) -> Self {
+        Self(num_bigint::BigInt::from(value))
+    }
+
+    /// Parse a big integer in base 10, returning `None` if it's invalid.
+    fn parse(s: &str) -> Option<Self> {
+        s.parse().ok().map(Self)
+    }
+
+    /// Parse a big integer in the given base, returning `None` if it's
+    /// invalid.
+    fn parse_radix(s: &str, r: u32) -> Result<Option<Self>, VmError> {
+        Ok(num_bigint::BigInt::parse_bytes(s.as_bytes(), radix(r)?).map(Self))
+    }
+
+    /// Convert into an integer, returning `None` if it doesn't fit.
+    fn to_int(&self) -> Option<i64> {
+        self.0.to_i64()
+    }
+
+    /// Convert into the nearest float.
+    fn to_float(&self) -> f64 {
+        self.0.to_f64().unwrap_or(f64::NAN)
+    }
+
+    fn to_string_radix(&self, r: u32) -> Result<String, VmError> {
+        Ok(self.0.to_str_radix(radix(r)?))
+    }
+
+    fn abs(&self) -> Self {
+        Self(self.0.abs())
+    }
+
+    fn signum(&self) -> i64 {
+        match self.0.sign() {
+            num_bigint::Sign::Minus => -1,
+            num_bigint::Sign::NoSign => 0,
+            num_bigint::Sign::Plus => 1,
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    fn pow(&self, exp: u32) -> Self {
+        Self(self.0.pow(exp))
+    }
+
+    /// Raise to the given power modulo `modulus`, which is a lot faster than
+    /// computing the power first for large exponents.
+    fn modpow(&self, exp: Value, modulus: Value) -> Result<Self, VmError> {
+        let exp = operand(exp)?;
+
+        if exp.is_negative() {
+            return Err(VmError::panic("exponent must not be negative"));
+        }
+
+        Ok(Self(self.0.modpow(&exp, &divisor(modulus)?)))
+    }
+
+    /// The greatest common divisor.
+    fn gcd(&self, other: Value) -> Result<Self, VmError> {
+        Ok(Self(self.0.gcd(&operand(other)?)))
+    }
+
+    fn neg(&self) -> Self {
+        Self(-&self.0)
+    }
+
+    fn cmp(&self, other: Value) -> Result<Ordering, VmError> {
+        Ok(self.0.cmp(&operand(other)?))
+    }
+
+    fn eq(&self, other: Value) -> Result<bool, VmError> {
+        Ok(self.0 == operand(other)?)
+    }
+
+    fn add(&self, other: Value) -> Result<Self, VmError> {
+        Ok(Self(&self.0 + operand(other)?))
+    }
+
+    fn add_assign(&mut self, other: Value) -> Result<(), VmError> {
+        self.0 += operand(other)?;
+        Ok(())
+    }
+
+    fn sub(&self, other: Value) -> Result<Self, VmError> {
+        Ok(Self(&self.0 - operand(other)?))
+    }
+
+    fn sub_assign(&mut self, other: Value) -> Result<(), VmError> {
+        self.0 -= operand(other)?;
+        Ok(())
+    }
+
+    fn mul(&self, other: Value) -> Result<Self, VmError> {
+        Ok(Self(&self.0 * operand(other)?))
+    }
+
+    fn mul_assign(&mut self, other: Value) -> Result<(), VmError> {
+        self.0 *= operand(other)?;
+        Ok(())
+    }
+
+    fn div(&self, other: Value) -> Result<Self, VmError> {
+        Ok(Self(&self.0 / divisor(other)?))
+    }
+
+    fn div_assign(&mut self, other: Value) -> Result<(), VmError> {
+        self.0 /= divisor(other)?;
+        Ok(())
+    }
+
+    fn rem(&self, other: Value) -> Result<Self, VmError> {
+        Ok(Self(&self.0 % divisor(other)?))
+    }
+
+    fn rem_assign(&mut self, other: Value) -> Result<(), VmError> {
+        self.0 %= divisor(other)?;
+        Ok(())
+    }
+
+    fn string_display(&self, s: &mut String) -> fmt::Result {
+        use std::fmt::Write as _;
+        write!(s, "{}", self.0)
+    }
+
+    fn string_debug(&self, s: &mut String) -> fmt::Result {
+        use std::fmt::Write as _;
+        write!(s, "BigInt({})", self.0)
+    }
+}
@@ -1,6 +1,6 @@
 //! The `std::object` module.
 
-use crate::runtime::{Iterator, Object, Protocol, Value};
+use crate::runtime::{FromValue, Iterator, Object, Protocol, Value, VmError};
 use crate::{ContextError, Module};
 
 /// Construct the `std::object` module.
@@ -9,19 +9,41 @@ pub fn module() -> Result<Module, ContextError> {
 
     module.ty::<Object>()?;
 
+    module.function(&["Object", "new"], Object::new)?;
+    module.function(&["Object", "from_pairs"], from_pairs)?;
     module.inst_fn("len", Object::len)?;
+    module.inst_fn("is_empty", Object::is_empty)?;
     module.inst_fn("insert", Object::insert)?;
+    module.inst_fn("remove", remove)?;
     module.inst_fn("clear", Object::clear)?;
+    module.inst_fn("clone", clone)?;
     module.inst_fn("contains_key", contains_key)?;
     module.inst_fn("get", get)?;
+    module.inst_fn("get_or_insert", get_or_insert)?;
+    module.inst_fn("extend", extend)?;
+    module.inst_fn("merge", merge)?;
 
     module.inst_fn("iter", Object::into_iterator)?;
+    module.inst_fn("entries", Object::into_iterator)?;
     module.inst_fn(Protocol::INTO_ITER, Object::into_iterator)?;
     module.inst_fn("keys", keys)?;
     module.inst_fn("values", values)?;
     Ok(module)
 }
 
+/// Construct an object from an iterator of `(key, value)` pairs, where later
+/// pairs replace earlier ones with the same key.
+fn from_pairs(pairs: Value) -> Result<Object, VmError> {
+    let mut object = Object::new();
+    extend(&mut object, pairs)?;
+    Ok(object)
+}
+
+/// Clone an object, along with all the values it contains.
+fn clone(object: &Object) -> Result<Value, VmError> {
+    crate::runtime::deep_clone_object(object)
+}
+
 fn contains_key(object: &Object, key: &str) -> bool {
     object.contains_key(key)
 }
@@ -30,6 +52,43 @@ fn get(object: &Object, key: &str) -> Option<Value> {
     object.get(key).cloned()
 }
 
+/// Remove the value of the given key, returning it if it was present.
+fn remove(object: &mut Object, key: &str) -> Option<Value> {
+    object.remove(key)
+}
+
+/// Get the value of the given key, inserting the default value first if the
+/// key isn't present.
+fn get_or_insert(object: &mut Object, key: &str, default: Value) -> Value {
+    if let Some(value) = object.get(key) {
+        return value.clone();
+    }
+
+    object.insert(key.to_owned(), default.clone());
+    default
+}
+
+/// Insert every `(key, value)` pair of an iterator, which can also be another
+/// object, replacing the values of existing keys.
+fn extend(object: &mut Object, pairs: Value) -> Result<(), VmError> {
+    let mut it = pairs.into_iter()?;
+
+    while let Some(pair) = it.next()? {
+        let (key, value) = <(String, Value)>::from_value(pair)?;
+        object.insert(key, value);
+    }
+
+    Ok(())
+}
+
+/// Insert every entry of another object, replacing the values of existing
+/// keys.
+fn merge(object: &mut Object, other: &Object) {
+    for (key, value) in other {
+        object.insert(key.clone(), value.clone());
+    }
+}
+
 fn keys(object: &Object) -> Iterator {
     let iter = object.keys().cloned().collect::<Vec<_>>().into_iter();
     Iterator::from_double_ended("std::object::Keys", iter)
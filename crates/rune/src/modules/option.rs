@@ -33,12 +33,12 @@ fn unwrap_or_else_impl(this: &Option<Value>, default: Function) -> Result<Value,
 
 /// Transpose functions, translates an Option<Result<T, E>> into a `Result<Option<T>, E>`.
 fn transpose_impl(this: &Option<Value>) -> Result<Value, VmError> {
-    Ok(Value::from(Shared::new(match this.clone() {
+    Ok(Value::from(Shared::tracked(match this.clone() {
         Some(some) => match some.into_result()?.borrow_ref()?.clone() {
-            Ok(ok) => Ok(Value::from(Shared::new(Some(ok)))),
+            Ok(ok) => Ok(Value::from(Shared::tracked(Some(ok)))),
             Err(err) => Err(err),
         },
-        None => Ok(Value::from(Shared::new(None::<Value>))),
+        None => Ok(Value::from(Shared::tracked(None::<Value>))),
     })))
 }
 
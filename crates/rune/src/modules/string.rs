@@ -2,6 +2,7 @@
 
 use crate::runtime::{Bytes, Iterator, Protocol, Value, VmError, VmErrorKind};
 use crate::{Any, ContextError, Module};
+use unicode_segmentation::UnicodeSegmentation as _;
 
 /// Construct the `std::string` module.
 pub fn module() -> Result<Module, ContextError> {
@@ -35,6 +36,21 @@ pub fn module() -> Result<Module, ContextError> {
     module.inst_fn("split_str", string_split)?;
     module.inst_fn("is_empty", str::is_empty)?;
     module.inst_fn("chars", string_chars)?;
+    module.inst_fn("graphemes", string_graphemes)?;
+    module.inst_fn("char_count", string_char_count)?;
+    module.inst_fn("char_slice", string_char_slice)?;
+    module.inst_fn("split_whitespace", string_split_whitespace)?;
+    module.inst_fn("splitn", string_splitn)?;
+    module.inst_fn("trim_start", string_trim_start)?;
+    module.inst_fn("trim_start_matches", string_trim_start_matches)?;
+    module.inst_fn("trim_end_matches", string_trim_end_matches)?;
+    module.inst_fn("strip_prefix", string_strip_prefix)?;
+    module.inst_fn("strip_suffix", string_strip_suffix)?;
+    module.inst_fn("pad_start", string_pad_start)?;
+    module.inst_fn("pad_end", string_pad_end)?;
+    module.inst_fn("repeat", str::repeat)?;
+    module.inst_fn("to_lowercase", str::to_lowercase)?;
+    module.inst_fn("to_uppercase", str::to_uppercase)?;
     module.inst_fn(Protocol::ADD, add)?;
     module.inst_fn(Protocol::ADD_ASSIGN, String::push_str)?;
     module.inst_fn(Protocol::INDEX_GET, string_index_get)?;
@@ -96,6 +112,90 @@ fn string_split(this: &str, value: Value) -> Result<Iterator, VmError> {
     ))
 }
 
+/// A pattern to match in a string, which is either a string or a character.
+enum Pattern {
+    Str(String),
+    Char(char),
+}
+
+impl Pattern {
+    fn from_value(value: Value) -> Result<Self, VmError> {
+        Ok(match value {
+            Value::String(s) => Self::Str(s.borrow_ref()?.clone()),
+            Value::StaticString(s) => Self::Str(s.as_str().to_owned()),
+            Value::Char(c) => Self::Char(c),
+            value => return Err(VmError::bad_argument::<String>(0, &value)?),
+        })
+    }
+}
+
+fn string_splitn(this: &str, n: usize, value: Value) -> Result<Iterator, VmError> {
+    let parts = match Pattern::from_value(value)? {
+        Pattern::Str(pat) => this.splitn(n, pat.as_str()).map(String::from).collect(),
+        Pattern::Char(pat) => this.splitn(n, pat).map(String::from).collect::<Vec<_>>(),
+    };
+
+    Ok(Iterator::from_double_ended(
+        "std::str::SplitN",
+        parts.into_iter(),
+    ))
+}
+
+fn string_split_whitespace(this: &str) -> Iterator {
+    let parts = this
+        .split_whitespace()
+        .map(String::from)
+        .collect::<Vec<_>>();
+
+    Iterator::from_double_ended("std::str::SplitWhitespace", parts.into_iter())
+}
+
+fn string_trim_start(this: &str) -> String {
+    this.trim_start().to_owned()
+}
+
+fn string_trim_start_matches(this: &str, value: Value) -> Result<String, VmError> {
+    Ok(match Pattern::from_value(value)? {
+        Pattern::Str(pat) => this.trim_start_matches(pat.as_str()).to_owned(),
+        Pattern::Char(pat) => this.trim_start_matches(pat).to_owned(),
+    })
+}
+
+fn string_trim_end_matches(this: &str, value: Value) -> Result<String, VmError> {
+    Ok(match Pattern::from_value(value)? {
+        Pattern::Str(pat) => this.trim_end_matches(pat.as_str()).to_owned(),
+        Pattern::Char(pat) => this.trim_end_matches(pat).to_owned(),
+    })
+}
+
+fn string_strip_prefix(this: &str, prefix: &str) -> Option<String> {
+    this.strip_prefix(prefix).map(String::from)
+}
+
+fn string_strip_suffix(this: &str, suffix: &str) -> Option<String> {
+    this.strip_suffix(suffix).map(String::from)
+}
+
+/// Pad the start of a string with the given character until it's at least
+/// `width` characters long.
+fn string_pad_start(this: &str, width: usize, fill: char) -> String {
+    let missing = width.saturating_sub(this.chars().count());
+    let mut out = String::with_capacity(this.len() + missing * fill.len_utf8());
+    out.extend((0..missing).map(|_| fill));
+    out.push_str(this);
+    out
+}
+
+/// Pad the end of a string with the given character until it's at least
+/// `width` characters long.
+fn string_pad_end(this: &str, width: usize, fill: char) -> String {
+    let missing = width.saturating_sub(this.chars().count());
+    let mut out = String::with_capacity(this.len() + missing * fill.len_utf8());
+    out.push_str(this);
+    out.extend((0..missing).map(|_| fill));
+    out
+}
+
 fn string_trim(this: &str) -> String {
     this.trim().to_owned()
 }
@@ -125,6 +225,39 @@ fn string_chars(s: &str) -> Iterator {
     Iterator::from_double_ended("std::str::Chars", iter)
 }
 
+fn string_graphemes(s: &str) -> Iterator {
+    let iter = s
+        .graphemes(true)
+        .map(String::from)
+        .collect::<Vec<_>>()
+        .into_iter();
+    Iterator::from_double_ended("std::str::Graphemes", iter)
+}
+
+/// Count the number of characters in a string, as opposed to its length in
+/// bytes.
+fn string_char_count(s: &str) -> usize {
+    s.chars().count()
+}
+
+/// Slice a string using character rather than byte indexes, returning `None`
+/// if the range is out of bounds.
+fn string_char_slice(s: &str, start: usize, end: usize) -> Option<String> {
+    if start > end {
+        return None;
+    }
+
+    let mut indices = s.char_indices().map(|(index, _)| index).chain([s.len()]);
+    let start_byte = indices.nth(start)?;
+    let end_byte = if start == end {
+        start_byte
+    } else {
+        indices.nth(end - start - 1)?
+    };
+
+    Some(s[start_byte..end_byte].to_owned())
+}
+
 /// Get a specific string index.
 fn string_get(s: &str, key: Value) -> Result<Option<String>, VmError> {
     use crate::runtime::{FromValue, RangeLimits, TypeOf};
@@ -0,0 +1,198 @@
+//! The `std::sync::channel` module.
+//!
+//! Asynchronous multi-producer, single-consumer channels. Values sent over a
+//! channel are copied into a [SendValue], so either half can be held by
+//! native code running on another thread while the other half is held by a
+//! script.
+
+use crate::runtime::{SendValue, Value, VmError};
+use crate::{Any, ContextError, Module};
+use futures_channel::mpsc;
+use futures_util::future;
+use futures_util::stream::StreamExt as _;
+
+/// Construct the `std::sync::channel` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::with_crate_item("std", &["sync", "channel"]);
+    module.ty::<Sender>()?;
+    module.ty::<Receiver>()?;
+    module.function(&["bounded"], bounded)?;
+    module.function(&["unbounded"], unbounded)?;
+    module.async_inst_fn("send", send)?;
+    module.inst_fn("try_send", try_send)?;
+    module.inst_fn("is_closed", Sender::is_closed)?;
+    module.inst_fn("clone", Sender::clone)?;
+    module.async_inst_fn("recv", recv)?;
+    module.inst_fn("try_recv", try_recv)?;
+    module.inst_fn("close", Receiver::close)?;
+    Ok(module)
+}
+
+/// Construct a channel which can hold at most `capacity` values which have
+/// not yet been received, in addition to one value for every sender.
+///
+/// # Examples
+///
+/// ```
+/// use rune::modules::sync::channel;
+/// use rune::runtime::SendValue;
+///
+/// # #[tokio::main] async fn main() {
+/// let (tx, mut rx) = channel::bounded(1);
+/// tx.send(SendValue::Integer(1)).await.unwrap();
+/// assert!(matches!(rx.recv().await, Some(SendValue::Integer(1))));
+/// # }
+/// ```
+pub fn bounded(capacity: usize) -> (Sender, Receiver) {
+    let (tx, rx) = mpsc::channel(capacity);
+
+    let tx = Sender {
+        inner: SenderKind::Bounded(tx),
+    };
+
+    let rx = Receiver {
+        inner: ReceiverKind::Bounded(rx),
+    };
+
+    (tx, rx)
+}
+
+/// Construct a channel which can hold any number of values which have not
+/// yet been received.
+pub fn unbounded() -> (Sender, Receiver) {
+    let (tx, rx) = mpsc::unbounded();
+
+    let tx = Sender {
+        inner: SenderKind::Unbounded(tx),
+    };
+
+    let rx = Receiver {
+        inner: ReceiverKind::Unbounded(rx),
+    };
+
+    (tx, rx)
+}
+
+#[derive(Clone)]
+enum SenderKind {
+    Bounded(mpsc::Sender<SendValue>),
+    Unbounded(mpsc::UnboundedSender<SendValue>),
+}
+
+/// The sending half of a channel.
+///
+/// Senders can be cloned to send values to the same channel from multiple
+/// places.
+#[derive(Any, Clone)]
+#[rune(module = "crate")]
+pub struct Sender {
+    inner: SenderKind,
+}
+
+impl Sender {
+    /// Send a value over the channel, waiting until there is capacity for it.
+    ///
+    /// If the receiving half has been closed or dropped, the value is handed
+    /// back as an error.
+    pub async fn send(&self, value: SendValue) -> Result<(), SendValue> {
+        match &self.inner {
+            SenderKind::Bounded(tx) => {
+                // NB: a clone of the sender is used so that we don't need to
+                // hold onto a mutable reference while waiting.
+                let mut tx = tx.clone();
+
+                if future::poll_fn(|cx| tx.poll_ready(cx)).await.is_err() {
+                    return Err(value);
+                }
+
+                tx.try_send(value).map_err(|e| e.into_inner())
+            }
+            SenderKind::Unbounded(tx) => tx.unbounded_send(value).map_err(|e| e.into_inner()),
+        }
+    }
+
+    /// Try to send a value over the channel without waiting.
+    ///
+    /// If the channel is full or closed, the value is handed back as an
+    /// error.
+    pub fn try_send(&mut self, value: SendValue) -> Result<(), SendValue> {
+        match &mut self.inner {
+            SenderKind::Bounded(tx) => tx.try_send(value).map_err(|e| e.into_inner()),
+            SenderKind::Unbounded(tx) => tx.unbounded_send(value).map_err(|e| e.into_inner()),
+        }
+    }
+
+    /// Test if the receiving half of the channel has been closed or dropped.
+    pub fn is_closed(&self) -> bool {
+        match &self.inner {
+            SenderKind::Bounded(tx) => tx.is_closed(),
+            SenderKind::Unbounded(tx) => tx.is_closed(),
+        }
+    }
+}
+
+enum ReceiverKind {
+    Bounded(mpsc::Receiver<SendValue>),
+    Unbounded(mpsc::UnboundedReceiver<SendValue>),
+}
+
+/// The receiving half of a channel.
+#[derive(Any)]
+#[rune(module = "crate")]
+pub struct Receiver {
+    inner: ReceiverKind,
+}
+
+impl Receiver {
+    /// Receive the next value from the channel, waiting until one is
+    /// available.
+    ///
+    /// Returns `None` once all senders have been dropped and every value has
+    /// been received.
+    pub async fn recv(&mut self) -> Option<SendValue> {
+        match &mut self.inner {
+            ReceiverKind::Bounded(rx) => rx.next().await,
+            ReceiverKind::Unbounded(rx) => rx.next().await,
+        }
+    }
+
+    /// Try to receive a value from the channel without waiting.
+    ///
+    /// Returns `None` if no value is currently available.
+    pub fn try_recv(&mut self) -> Option<SendValue> {
+        let result = match &mut self.inner {
+            ReceiverKind::Bounded(rx) => rx.try_recv(),
+            ReceiverKind::Unbounded(rx) => rx.try_recv(),
+        };
+
+        result.ok()
+    }
+
+    /// Close the channel, preventing any further values from being sent.
+    ///
+    /// Values which have already been sent can still be received.
+    pub fn close(&mut self) {
+        match &mut self.inner {
+            ReceiverKind::Bounded(rx) => rx.close(),
+            ReceiverKind::Unbounded(rx) => rx.close(),
+        }
+    }
+}
+
+async fn send(this: &Sender, value: Value) -> Result<Result<(), Value>, VmError> {
+    let value = SendValue::from_value(&value)?;
+    Ok(this.send(value).await.map_err(SendValue::into_value))
+}
+
+fn try_send(this: &mut Sender, value: Value) -> Result<Result<(), Value>, VmError> {
+    let value = SendValue::from_value(&value)?;
+    Ok(this.try_send(value).map_err(SendValue::into_value))
+}
+
+async fn recv(this: &mut Receiver) -> Option<Value> {
+    this.recv().await.map(SendValue::into_value)
+}
+
+fn try_recv(this: &mut Receiver) -> Option<Value> {
+    this.try_recv().map(SendValue::into_value)
+}
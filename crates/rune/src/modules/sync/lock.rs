@@ -0,0 +1,270 @@
+//! Asynchronous locks for sharing mutable state between script tasks.
+//!
+//! Acquiring a lock returns a future, so a task waiting for a lock yields to
+//! other tasks instead of erroring on a contended borrow. Guards can be held
+//! across awaits and release their lock when they are dropped, either by going
+//! out of scope or through `std::mem::drop`.
+
+use crate::runtime::{Future, Value, VmError};
+use crate::Any;
+use futures_util::future;
+use std::cell::{Cell, RefCell};
+use std::mem;
+use std::rc::Rc;
+use std::task::{Poll, Waker};
+
+/// State shared between a lock, its guards and the tasks waiting on it.
+struct Inner {
+    value: RefCell<Value>,
+    /// The number of active readers.
+    readers: Cell<usize>,
+    /// If the lock is exclusively held.
+    writer: Cell<bool>,
+    waiters: RefCell<Vec<Waker>>,
+}
+
+impl Inner {
+    fn new(value: Value) -> Rc<Self> {
+        Rc::new(Self {
+            value: RefCell::new(value),
+            readers: Cell::new(0),
+            writer: Cell::new(false),
+            waiters: RefCell::new(Vec::new()),
+        })
+    }
+
+    fn try_read(&self) -> bool {
+        if self.writer.get() {
+            return false;
+        }
+
+        self.readers.set(self.readers.get() + 1);
+        true
+    }
+
+    fn try_write(&self) -> bool {
+        if self.writer.get() || self.readers.get() > 0 {
+            return false;
+        }
+
+        self.writer.set(true);
+        true
+    }
+
+    /// Wait until `acquire` succeeds, registering the current task to be
+    /// woken up whenever the lock is released.
+    async fn acquire(&self, acquire: fn(&Self) -> bool) {
+        future::poll_fn(|cx| {
+            if acquire(self) {
+                return Poll::Ready(());
+            }
+
+            self.waiters.borrow_mut().push(cx.waker().clone());
+            Poll::Pending
+        })
+        .await
+    }
+
+    fn wake(&self) {
+        for waker in mem::take(&mut *self.waiters.borrow_mut()) {
+            waker.wake();
+        }
+    }
+
+    fn release_read(&self) {
+        self.readers.set(self.readers.get() - 1);
+
+        if self.readers.get() == 0 {
+            self.wake();
+        }
+    }
+
+    fn release_write(&self) {
+        self.writer.set(false);
+        self.wake();
+    }
+
+    fn get(&self) -> Value {
+        self.value.borrow().clone()
+    }
+
+    fn replace(&self, value: Value) -> Value {
+        mem::replace(&mut *self.value.borrow_mut(), value)
+    }
+}
+
+/// A mutual exclusion lock protecting a value.
+///
+/// Cloning a mutex produces a new handle to the same lock.
+#[derive(Any, Clone)]
+#[rune(module = "crate")]
+pub(super) struct Mutex {
+    inner: Rc<Inner>,
+}
+
+impl Mutex {
+    pub(super) fn new(value: Value) -> Self {
+        Self {
+            inner: Inner::new(value),
+        }
+    }
+
+    /// Acquire the lock, waiting until it's available.
+    pub(super) fn lock(&self) -> Future {
+        let inner = self.inner.clone();
+
+        Future::new(async move {
+            inner.acquire(Inner::try_write).await;
+            Ok::<_, VmError>(MutexGuard { inner })
+        })
+    }
+
+    /// Try to acquire the lock, returning `None` if it's held elsewhere.
+    pub(super) fn try_lock(&self) -> Option<MutexGuard> {
+        if !self.inner.try_write() {
+            return None;
+        }
+
+        Some(MutexGuard {
+            inner: self.inner.clone(),
+        })
+    }
+
+    pub(super) fn is_locked(&self) -> bool {
+        self.inner.writer.get()
+    }
+}
+
+/// Exclusive access to the value protected by a [Mutex].
+#[derive(Any)]
+#[rune(module = "crate")]
+pub(super) struct MutexGuard {
+    inner: Rc<Inner>,
+}
+
+impl MutexGuard {
+    pub(super) fn get(&self) -> Value {
+        self.inner.get()
+    }
+
+    pub(super) fn set(&self, value: Value) {
+        self.inner.replace(value);
+    }
+
+    /// Replace the protected value, returning the old one.
+    pub(super) fn replace(&self, value: Value) -> Value {
+        self.inner.replace(value)
+    }
+}
+
+impl Drop for MutexGuard {
+    fn drop(&mut self) {
+        self.inner.release_write();
+    }
+}
+
+/// A lock protecting a value which allows any number of readers or a single
+/// writer at a time.
+///
+/// Cloning a lock produces a new handle to the same lock.
+#[derive(Any, Clone)]
+#[rune(module = "crate")]
+pub(super) struct RwLock {
+    inner: Rc<Inner>,
+}
+
+impl RwLock {
+    pub(super) fn new(value: Value) -> Self {
+        Self {
+            inner: Inner::new(value),
+        }
+    }
+
+    /// Acquire shared read access, waiting until there's no writer.
+    pub(super) fn read(&self) -> Future {
+        let inner = self.inner.clone();
+
+        Future::new(async move {
+            inner.acquire(Inner::try_read).await;
+            Ok::<_, VmError>(RwLockReadGuard { inner })
+        })
+    }
+
+    /// Acquire exclusive write access, waiting until there are no readers or
+    /// writers.
+    pub(super) fn write(&self) -> Future {
+        let inner = self.inner.clone();
+
+        Future::new(async move {
+            inner.acquire(Inner::try_write).await;
+            Ok::<_, VmError>(RwLockWriteGuard { inner })
+        })
+    }
+
+    pub(super) fn try_read(&self) -> Option<RwLockReadGuard> {
+        if !self.inner.try_read() {
+            return None;
+        }
+
+        Some(RwLockReadGuard {
+            inner: self.inner.clone(),
+        })
+    }
+
+    pub(super) fn try_write(&self) -> Option<RwLockWriteGuard> {
+        if !self.inner.try_write() {
+            return None;
+        }
+
+        Some(RwLockWriteGuard {
+            inner: self.inner.clone(),
+        })
+    }
+}
+
+/// Shared read access to the value protected by a [RwLock].
+#[derive(Any)]
+#[rune(module = "crate")]
+pub(super) struct RwLockReadGuard {
+    inner: Rc<Inner>,
+}
+
+impl RwLockReadGuard {
+    pub(super) fn get(&self) -> Value {
+        self.inner.get()
+    }
+}
+
+impl Drop for RwLockReadGuard {
+    fn drop(&mut self) {
+        self.inner.release_read();
+    }
+}
+
+/// Exclusive write access to the value protected by a [RwLock].
+#[derive(Any)]
+#[rune(module = "crate")]
+pub(super) struct RwLockWriteGuard {
+    inner: Rc<Inner>,
+}
+
+impl RwLockWriteGuard {
+    pub(super) fn get(&self) -> Value {
+        self.inner.get()
+    }
+
+    pub(super) fn set(&self, value: Value) {
+        self.inner.replace(value);
+    }
+
+    /// Replace the protected value, returning the old one.
+    pub(super) fn replace(&self, value: Value) -> Value {
+        self.inner.replace(value)
+    }
+}
+
+impl Drop for RwLockWriteGuard {
+    fn drop(&mut self) {
+        self.inner.release_write();
+    }
+}
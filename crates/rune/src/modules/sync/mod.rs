@@ -0,0 +1,38 @@
+//! The `std::sync` module.
+
+use crate::{ContextError, Module};
+
+pub mod channel;
+mod lock;
+
+/// Construct the `std::sync` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::with_crate_item("std", &["sync"]);
+
+    module.ty::<lock::Mutex>()?;
+    module.ty::<lock::MutexGuard>()?;
+    module.function(&["Mutex", "new"], lock::Mutex::new)?;
+    module.inst_fn("lock", lock::Mutex::lock)?;
+    module.inst_fn("try_lock", lock::Mutex::try_lock)?;
+    module.inst_fn("is_locked", lock::Mutex::is_locked)?;
+    module.inst_fn("clone", lock::Mutex::clone)?;
+
+    module.ty::<lock::RwLock>()?;
+    module.ty::<lock::RwLockReadGuard>()?;
+    module.ty::<lock::RwLockWriteGuard>()?;
+    module.function(&["RwLock", "new"], lock::RwLock::new)?;
+    module.inst_fn("read", lock::RwLock::read)?;
+    module.inst_fn("write", lock::RwLock::write)?;
+    module.inst_fn("try_read", lock::RwLock::try_read)?;
+    module.inst_fn("try_write", lock::RwLock::try_write)?;
+    module.inst_fn("clone", lock::RwLock::clone)?;
+
+    module.inst_fn("get", lock::MutexGuard::get)?;
+    module.inst_fn("set", lock::MutexGuard::set)?;
+    module.inst_fn("replace", lock::MutexGuard::replace)?;
+    module.inst_fn("get", lock::RwLockReadGuard::get)?;
+    module.inst_fn("get", lock::RwLockWriteGuard::get)?;
+    module.inst_fn("set", lock::RwLockWriteGuard::set)?;
+    module.inst_fn("replace", lock::RwLockWriteGuard::replace)?;
+    Ok(module)
+}
@@ -1,7 +1,8 @@
 //! The `std::vec` module.
 
-use crate::runtime::{Function, Protocol, TypeOf, Value, Vec};
+use crate::runtime::{Function, Protocol, TypeOf, Value, Vec, VmError};
 use crate::{ContextError, Module, Params};
+use std::cmp;
 
 /// Construct the `std::vec` module.
 pub fn module() -> Result<Module, ContextError> {
@@ -10,17 +11,28 @@ pub fn module() -> Result<Module, ContextError> {
     module.ty::<Vec>()?;
 
     module.function(&["Vec", "new"], Vec::new)?;
+    module.inst_fn("binary_search", Vec::binary_search)?;
     module.inst_fn("clear", Vec::clear)?;
-    module.inst_fn("clone", Vec::clone)?;
+    module.inst_fn("clone", clone)?;
+    module.inst_fn("concat", concat)?;
+    module.inst_fn("dedup", Vec::dedup)?;
     module.inst_fn("extend", Vec::extend)?;
     module.inst_fn("get", vec_get)?;
+    module.inst_fn("insert", Vec::insert)?;
     module.inst_fn("iter", Vec::into_iterator)?;
+    module.inst_fn("join", join)?;
     module.inst_fn("len", Vec::len)?;
     module.inst_fn("pop", Vec::pop)?;
     module.inst_fn("push", Vec::push)?;
     module.inst_fn("remove", Vec::remove)?;
+    module.inst_fn("resize", Vec::resize)?;
+    module.inst_fn("retain", retain)?;
+    module.inst_fn("sort", sort)?;
     module.inst_fn("sort_by", sort_by)?;
-    module.inst_fn("insert", Vec::insert)?;
+    module.inst_fn("sort_by_key", sort_by_key)?;
+    module.inst_fn("split_off", Vec::split_off)?;
+    module.inst_fn("swap_remove", Vec::swap_remove)?;
+    module.inst_fn("truncate", Vec::truncate)?;
     module.inst_fn(Protocol::INTO_ITER, Vec::into_iterator)?;
     module.inst_fn(Protocol::INDEX_SET, Vec::set)?;
 
@@ -38,14 +50,162 @@ fn sort_int(vec: &mut Vec) {
     });
 }
 
+/// Sort a vector, comparing values which aren't built-in types with their
+/// `cmp` function.
+fn sort(vec: &mut Vec) -> Result<(), VmError> {
+    let mut error = None;
+
+    vec.sort_by(|a, b| compare(a, b, &mut error));
+
+    match error {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+/// Sort a vector by the key returned by the given function, which is called
+/// once for each element. Elements with equal keys keep their relative order.
+fn sort_by_key(vec: &mut Vec, key: &Function) -> Result<(), VmError> {
+    let mut keys = std::vec::Vec::with_capacity(vec.len());
+
+    for value in vec.iter() {
+        keys.push(key.call::<_, Value>((value.clone(),))?);
+    }
+
+    let values = std::mem::replace(vec, Vec::new()).into_inner();
+    let mut keyed = keys.into_iter().zip(values).collect::<std::vec::Vec<_>>();
+
+    let mut error = None;
+    keyed.sort_by(|(a, _), (b, _)| compare(a, b, &mut error));
+    *vec = Vec::from(
+        keyed
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect::<std::vec::Vec<_>>(),
+    );
+
+    match error {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+/// Compare two values, storing the first error raised.
+fn compare(a: &Value, b: &Value, error: &mut Option<VmError>) -> cmp::Ordering {
+    if error.is_some() {
+        return cmp::Ordering::Equal;
+    }
+
+    match Value::value_cmp(a, b) {
+        Ok(ordering) => ordering,
+        Err(e) => {
+            *error = Some(e);
+            cmp::Ordering::Equal
+        }
+    }
+}
+
+/// Clone a vector, along with all the values it contains.
+fn clone(vec: &Vec) -> Result<Value, VmError> {
+    crate::runtime::deep_clone_vec(vec)
+}
+
+/// Retain only the elements for which the given predicate returns `true`.
+fn retain(vec: &mut Vec, predicate: &Function) -> Result<(), VmError> {
+    let values = std::mem::replace(vec, Vec::new()).into_inner();
+    let mut retained = std::vec::Vec::with_capacity(values.len());
+
+    for value in values {
+        if predicate.call::<_, bool>((value.clone(),))? {
+            retained.push(value);
+        }
+    }
+
+    *vec = Vec::from(retained);
+    Ok(())
+}
+
+/// Concatenate a vector of strings into a string, or a vector of vectors into
+/// a vector.
+fn concat(vec: &Vec) -> Result<Value, VmError> {
+    match vec.first() {
+        Some(Value::String(..) | Value::StaticString(..)) => join_strings(vec, ""),
+        _ => join_vecs(vec, None),
+    }
+}
+
+/// Join a vector of strings into a string with the given string separator, or
+/// a vector of vectors into a vector with the given value as separator.
+fn join(vec: &Vec, separator: Value) -> Result<Value, VmError> {
+    match &separator {
+        Value::String(separator) => join_strings(vec, separator.borrow_ref()?.as_str()),
+        Value::StaticString(separator) => join_strings(vec, separator.as_str()),
+        _ => join_vecs(vec, Some(separator)),
+    }
+}
+
+fn join_strings(vec: &Vec, separator: &str) -> Result<Value, VmError> {
+    let mut out = String::new();
+
+    for (index, value) in vec.iter().enumerate() {
+        if index > 0 {
+            out.push_str(separator);
+        }
+
+        match value {
+            Value::String(string) => out.push_str(string.borrow_ref()?.as_str()),
+            Value::StaticString(string) => out.push_str(string.as_str()),
+            actual => return Err(VmError::expected::<String>(actual.type_info()?)),
+        }
+    }
+
+    Ok(Value::from(out))
+}
+
+fn join_vecs(vec: &Vec, separator: Option<Value>) -> Result<Value, VmError> {
+    let mut out = std::vec::Vec::new();
+
+    for (index, value) in vec.iter().enumerate() {
+        if index > 0 {
+            out.extend(separator.iter().cloned());
+        }
+
+        match value {
+            Value::Vec(inner) => out.extend(inner.borrow_ref()?.iter().cloned()),
+            actual => return Err(VmError::expected::<Vec>(actual.type_info()?)),
+        }
+    }
+
+    Ok(Value::vec(out))
+}
+
 fn vec_get(vec: &Vec, index: usize) -> Option<Value> {
     vec.get(index).cloned()
 }
 
-fn sort_by(vec: &mut Vec, comparator: &Function) {
+/// Sort a vector with the given comparator function.
+///
+/// Like the other sorting functions this is a stable sort, so elements which
+/// compare as equal keep their relative order.
+fn sort_by(vec: &mut Vec, comparator: &Function) -> Result<(), VmError> {
+    let mut error = None;
+
     vec.sort_by(|a, b| {
-        comparator
-            .call::<_, std::cmp::Ordering>((a, b))
-            .unwrap_or(std::cmp::Ordering::Equal)
-    })
+        if error.is_some() {
+            return cmp::Ordering::Equal;
+        }
+
+        match comparator.call::<_, cmp::Ordering>((a, b)) {
+            Ok(ordering) => ordering,
+            Err(e) => {
+                error = Some(e);
+                cmp::Ordering::Equal
+            }
+        }
+    });
+
+    match error {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
 }
@@ -6,6 +6,28 @@ use std::collections::VecDeque;
 use std::fmt;
 
 /// Lexer for the rune language.
+///
+/// # Examples
+///
+/// ```
+/// use rune::ast;
+/// use rune::parse::Lexer;
+/// use rune::SourceId;
+///
+/// let mut lexer = Lexer::new("fn main() {}", SourceId::empty(), false);
+/// let mut kinds = Vec::new();
+///
+/// while let Some(token) = lexer.next()? {
+///     if !matches!(token.kind, ast::Kind::Whitespace) {
+///         kinds.push(token.kind);
+///     }
+/// }
+///
+/// assert_eq!(kinds.len(), 6);
+/// assert_eq!(kinds[0], ast::Kind::Fn);
+/// assert_eq!(kinds[5], ast::Kind::Close(ast::Delimiter::Brace));
+/// # Ok::<_, rune::parse::ParseError>(())
+/// ```
 #[derive(Debug)]
 pub struct Lexer<'a> {
     /// The source identifier of the lexed data.
@@ -22,7 +44,7 @@ pub struct Lexer<'a> {
 
 impl<'a> Lexer<'a> {
     /// Construct a new lexer over the given source.
-    pub(crate) fn new(source: &'a str, source_id: SourceId, shebang: bool) -> Self {
+    pub fn new(source: &'a str, source_id: SourceId, shebang: bool) -> Self {
         Self {
             iter: SourceIter::new(source),
             source_id,
@@ -472,7 +494,7 @@ impl<'a> Lexer<'a> {
 
     /// Consume the next token from the lexer.
     #[allow(clippy::should_implement_trait)]
-    pub(crate) fn next(&mut self) -> Result<Option<ast::Token>, ParseError> {
+    pub fn next(&mut self) -> Result<Option<ast::Token>, ParseError> {
         'outer: loop {
             if let Some(token) = self.buffer.pop_front() {
                 return Ok(Some(token));
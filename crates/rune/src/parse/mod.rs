@@ -14,7 +14,7 @@ mod resolve;
 pub use self::expectation::Expectation;
 pub(crate) use self::expectation::IntoExpectation;
 pub use self::id::{Id, NonZeroId};
-pub(crate) use self::lexer::{Lexer, LexerMode};
+pub use self::lexer::{Lexer, LexerMode};
 pub(crate) use self::opaque::Opaque;
 pub use self::parse::Parse;
 pub use self::parse_error::{ParseError, ParseErrorKind};
@@ -8,8 +8,8 @@ use crate::compile::ir;
 use crate::compile::{
     CaptureMeta, CompileError, CompileErrorKind, CompileVisitor, ComponentRef, EmptyMeta,
     ImportStep, IntoComponent, IrBudget, IrCompiler, IrInterpreter, Item, ItemMeta, Location,
-    ModMeta, Names, PrivMeta, PrivMetaKind, SourceMeta, StructMeta, TupleMeta, UnitBuilder,
-    Visibility,
+    ModMeta, Names, Options, PrivMeta, PrivMetaKind, SourceMeta, StructMeta, TupleMeta,
+    UnitBuilder, Visibility,
 };
 use crate::macros::Storage;
 use crate::parse::{Id, NonZeroId, Opaque, Resolve, ResolveContext};
@@ -114,6 +114,8 @@ pub(crate) struct QueryInner {
     indexed: HashMap<Item, Vec<IndexedEntry>>,
     /// Compiled constant functions.
     const_fns: HashMap<NonZeroId, Arc<QueryConstFn>>,
+    /// Functions which are small enough to be inlined at their call sites.
+    inline_fns: HashMap<Item, Arc<QueryInlineFn>>,
     /// Query paths.
     query_paths: HashMap<NonZeroId, Arc<QueryPath>>,
     /// The result of internally resolved macros.
@@ -128,9 +130,23 @@ pub(crate) struct QueryInner {
     names: Names,
     /// Modules and associated metadata.
     modules: HashMap<Item, Arc<ModMeta>>,
+    /// The budget to evaluate constant items and constant function calls with.
+    const_budget: IrBudget,
+}
+
+impl QueryInner {
+    /// Construct query state for compiling with the given options.
+    pub(crate) fn new(options: &Options) -> Self {
+        Self {
+            const_budget: IrBudget::new(options.const_budget),
+            ..Self::default()
+        }
+    }
 }
 
 pub(crate) struct Query<'a> {
+    /// The context of native modules being compiled against.
+    pub(crate) context: &'a Context,
     /// The current unit being built.
     pub(crate) unit: &'a mut UnitBuilder,
     /// Cache of constants that have been expanded.
@@ -150,6 +166,7 @@ pub(crate) struct Query<'a> {
 impl<'a> Query<'a> {
     /// Construct a new compilation context.
     pub(crate) fn new(
+        context: &'a Context,
         unit: &'a mut UnitBuilder,
         consts: &'a mut Consts,
         storage: &'a mut Storage,
@@ -159,6 +176,7 @@ impl<'a> Query<'a> {
         inner: &'a mut QueryInner,
     ) -> Self {
         Self {
+            context,
             unit,
             consts,
             storage,
@@ -169,9 +187,16 @@ impl<'a> Query<'a> {
         }
     }
 
+    /// Construct a fresh budget to evaluate a constant item or a constant
+    /// function call with.
+    pub(crate) fn const_budget(&self) -> IrBudget {
+        self.inner.const_budget.clone()
+    }
+
     /// Reborrow the query engine from a reference to `self`.
     pub(crate) fn borrow(&mut self) -> Query<'_> {
         Query {
+            context: self.context,
             unit: self.unit,
             consts: self.consts,
             storage: self.storage,
@@ -380,6 +405,12 @@ impl<'a> Query<'a> {
         id
     }
 
+    /// Get the inlinable function associated with the given item, if the
+    /// function is small enough to be inlined.
+    pub(crate) fn inline_fn_for(&self, item: &Item) -> Option<Arc<QueryInlineFn>> {
+        self.inner.inline_fns.get(item).cloned()
+    }
+
     /// Get the constant function associated with the opaque.
     pub(crate) fn const_fn_for<T>(&self, ast: T) -> Result<Arc<QueryConstFn>, QueryError>
     where
@@ -960,6 +991,14 @@ impl<'a> Query<'a> {
                 struct_into_item_decl(&query_item.item, st.ast.body, None, resolve_context!(self))?
             }
             Indexed::Function(f) => {
+                if let Some(inline_fn) =
+                    QueryInlineFn::new(&query_item, &f, resolve_context!(self))?
+                {
+                    self.inner
+                        .inline_fns
+                        .insert(query_item.item.clone(), Arc::new(inline_fn));
+                }
+
                 self.inner.queue.push_back(BuildEntry {
                     location: query_item.location,
                     item: query_item.clone(),
@@ -1009,7 +1048,7 @@ impl<'a> Query<'a> {
             }
             Indexed::Const(c) => {
                 let mut const_compiler = IrInterpreter {
-                    budget: IrBudget::new(1_000_000),
+                    budget: self.const_budget(),
                     scopes: Default::default(),
                     module: &c.module,
                     item: &query_item.item,
@@ -1105,6 +1144,8 @@ impl<'a> Query<'a> {
             visibility,
         });
 
+        self.visitor
+            .visit_item_indexed(query_item.location, &query_item.item);
         self.inner.items.insert(id, query_item.clone());
         Ok(query_item)
     }
@@ -1374,8 +1415,9 @@ pub(crate) struct Function {
 pub(crate) struct InstanceFunction {
     /// Ast for the instance function.
     pub(crate) ast: Box<ast::ItemFn>,
-    /// The item of the instance function.
-    pub(crate) impl_item: Arc<Item>,
+    /// The path of the impl, which resolves to the type the instance
+    /// function belongs to.
+    pub(crate) impl_path: Arc<ast::Path>,
     /// The span of the instance function.
     pub(crate) instance_span: Span,
     /// Calling convention of the instance function.
@@ -1481,6 +1523,129 @@ pub(crate) struct QueryConstFn {
     pub(crate) ir_fn: ir::IrFn,
 }
 
+/// A function whose body is a single pure expression over its arguments, which
+/// can be inlined at its call sites.
+#[derive(Debug)]
+pub(crate) struct QueryInlineFn {
+    /// The source the function is declared in.
+    pub(crate) source_id: SourceId,
+    /// The names of the arguments of the function.
+    pub(crate) args: Box<[Box<str>]>,
+    /// The expression making up the body of the function.
+    pub(crate) body: ast::Expr,
+    /// The number of nodes in the body of the function.
+    pub(crate) size: usize,
+}
+
+impl QueryInlineFn {
+    /// Construct an inlinable function out of the given function, if it can be
+    /// inlined.
+    fn new(
+        item: &ItemMeta,
+        f: &Function,
+        ctx: ResolveContext<'_>,
+    ) -> Result<Option<Self>, QueryError> {
+        if !matches!(f.call, Call::Immediate) {
+            return Ok(None);
+        }
+
+        let body = match f.ast.body.statements.as_slice() {
+            [ast::Stmt::Expr(body, None)] => body,
+            _ => return Ok(None),
+        };
+
+        let mut args = Vec::new();
+
+        for (arg, _) in &f.ast.args {
+            let ident = match arg {
+                ast::FnArg::Pat(ast::Pat::PatPath(pat)) => pat.path.try_as_ident(),
+                _ => None,
+            };
+
+            match ident {
+                Some(ident) => args.push(Box::<str>::from(ident.resolve(ctx)?)),
+                None => return Ok(None),
+            }
+        }
+
+        let size = match inline_size(body, &args, ctx)? {
+            Some(size) => size,
+            None => return Ok(None),
+        };
+
+        Ok(Some(Self {
+            source_id: item.location.source_id,
+            args: args.into(),
+            body: body.clone(),
+            size,
+        }))
+    }
+}
+
+/// Calculate the number of nodes in an expression which can be inlined, or
+/// `None` if the expression does anything else than operate on its arguments
+/// and literals.
+fn inline_size(
+    ast: &ast::Expr,
+    args: &[Box<str>],
+    ctx: ResolveContext<'_>,
+) -> Result<Option<usize>, QueryError> {
+    let size = match ast {
+        ast::Expr::Path(path) => {
+            let ident = match path.try_as_ident() {
+                Some(ident) => ident.resolve(ctx)?,
+                None => return Ok(None),
+            };
+
+            if !args.iter().any(|arg| **arg == *ident) {
+                return Ok(None);
+            }
+
+            1
+        }
+        ast::Expr::Lit(expr_lit) => match &expr_lit.lit {
+            ast::Lit::Bool(..) | ast::Lit::Byte(..) | ast::Lit::Char(..) | ast::Lit::Number(..) => {
+                1
+            }
+            _ => return Ok(None),
+        },
+        ast::Expr::Group(expr_group) => return inline_size(&expr_group.expr, args, ctx),
+        ast::Expr::Unary(expr_unary) => match expr_unary.op {
+            ast::UnOp::Not(..) | ast::UnOp::Neg(..) => {
+                match inline_size(&expr_unary.expr, args, ctx)? {
+                    Some(size) => size + 1,
+                    None => return Ok(None),
+                }
+            }
+            _ => return Ok(None),
+        },
+        ast::Expr::Binary(expr_binary) if !expr_binary.op.is_assign() => {
+            let lhs = inline_size(&expr_binary.lhs, args, ctx)?;
+            let rhs = inline_size(&expr_binary.rhs, args, ctx)?;
+
+            match (lhs, rhs) {
+                (Some(lhs), Some(rhs)) => lhs + rhs + 1,
+                _ => return Ok(None),
+            }
+        }
+        ast::Expr::FieldAccess(expr_field_access) => {
+            if let ast::ExprField::Path(path) = &expr_field_access.expr_field {
+                if path.try_as_ident().is_none() {
+                    return Ok(None);
+                }
+            }
+
+            match inline_size(&expr_field_access.expr, args, ctx)? {
+                Some(size) => size + 1,
+                None => return Ok(None),
+            }
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(size))
+}
+
 /// The result of calling [Query::convert_path].
 #[derive(Debug)]
 pub(crate) struct Named<'a> {
@@ -0,0 +1,160 @@
+//! Hot reloading of scripts into running virtual machines.
+
+use crate::runtime::Unit;
+use crate::{BuildError, Source, Sources, Vm};
+use std::fs;
+use std::io;
+use thiserror::Error;
+
+/// Error raised when reloading sources.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ReloadError {
+    /// Sources couldn't be read.
+    #[error("failed to read sources")]
+    Io(#[from] io::Error),
+    /// Sources failed to build, see the diagnostics the build was performed
+    /// with for details.
+    #[error("failed to build sources")]
+    Build(#[from] BuildError),
+}
+
+/// Keeps track of the sources a unit was built from, so that it can be
+/// rebuilt and reloaded into a running virtual machine when any of them
+/// change.
+///
+/// Sources which were loaded from a path are read again every time they're
+/// built, and so are any modules which are loaded while building.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rune::termcolor::{ColorChoice, StandardStream};
+/// use rune::{Context, Diagnostics, Reloader, Source, Sources, Vm};
+/// use std::path::Path;
+/// use std::sync::Arc;
+///
+/// # fn main() -> rune::Result<()> {
+/// let context = Context::with_default_modules()?;
+///
+/// let mut sources = Sources::new();
+/// sources.insert(Source::from_path(Path::new("script.rn"))?);
+///
+/// let mut reloader = Reloader::new(sources);
+/// let unit = reloader.build(|sources| rune::prepare(sources).with_context(&context).build())?;
+/// let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+///
+/// loop {
+///     let mut diagnostics = Diagnostics::new();
+///
+///     let result = reloader.reload(&mut vm, |sources| {
+///         rune::prepare(sources)
+///             .with_context(&context)
+///             .with_diagnostics(&mut diagnostics)
+///             .build()
+///     });
+///
+///     if !diagnostics.is_empty() {
+///         let mut writer = StandardStream::stderr(ColorChoice::Always);
+///         diagnostics.emit(&mut writer, reloader.sources())?;
+///     }
+///
+///     if let Err(error) = result {
+///         println!("failed to reload: {}", error);
+///     }
+///
+///     vm.call(&["update"], ())?;
+/// }
+/// # }
+/// ```
+pub struct Reloader {
+    /// The sources to build, as they were provided.
+    roots: Vec<Source>,
+    /// The sources which were last built, including any modules loaded while
+    /// building them.
+    sources: Sources,
+    /// If the sources have been built.
+    built: bool,
+}
+
+impl Reloader {
+    /// Construct a reloader for the given sources.
+    pub fn new(sources: Sources) -> Self {
+        Self {
+            roots: sources.iter().cloned().collect(),
+            sources: Sources::new(),
+            built: false,
+        }
+    }
+
+    /// Access the sources which were last built, which is what diagnostics
+    /// from that build refer to.
+    pub fn sources(&self) -> &Sources {
+        &self.sources
+    }
+
+    /// Build the sources with the given function, reading any sources which
+    /// were loaded from a path again.
+    pub fn build<F>(&mut self, build: F) -> Result<Unit, ReloadError>
+    where
+        F: FnOnce(&mut Sources) -> Result<Unit, BuildError>,
+    {
+        let mut sources = Sources::new();
+
+        for root in &mut self.roots {
+            if let Some(path) = root.path() {
+                let source = fs::read_to_string(path)?;
+                *root = Source::with_path(root.name(), source, Some(path));
+            }
+
+            sources.insert(root.clone());
+        }
+
+        self.sources = sources;
+        self.built = true;
+        Ok(build(&mut self.sources)?)
+    }
+
+    /// Test if any of the sources which were last built have changed on disk.
+    ///
+    /// This is always `true` if the sources haven't been built yet.
+    pub fn changed(&self) -> io::Result<bool> {
+        if !self.built {
+            return Ok(true);
+        }
+
+        for source in self.sources.iter() {
+            let path = match source.path() {
+                Some(path) => path,
+                None => continue,
+            };
+
+            match fs::read_to_string(path) {
+                Ok(current) if current == source.as_str() => {}
+                Ok(..) => return Ok(true),
+                Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(true),
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Rebuild the sources with the given function if any of them have
+    /// changed, and reload the virtual machine with the new unit.
+    ///
+    /// Returns `true` if the virtual machine was reloaded. See [Vm::reload]
+    /// for what happens to the state of the virtual machine.
+    pub fn reload<F>(&mut self, vm: &mut Vm, build: F) -> Result<bool, ReloadError>
+    where
+        F: FnOnce(&mut Sources) -> Result<Unit, BuildError>,
+    {
+        if !self.changed()? {
+            return Ok(false);
+        }
+
+        let unit = self.build(build)?;
+        vm.reload(&unit);
+        Ok(true)
+    }
+}
@@ -305,7 +305,8 @@ impl AnyObj {
     where
         T: Any,
     {
-        Hash::from_any::<T>() == self.type_hash()
+        // Safety: invariants are checked at construction time.
+        unsafe { (self.vtable.as_ptr)(self.data, any::TypeId::of::<T>()).is_some() }
     }
 
     /// Returns some reference to the boxed value if it is of type `T`, or
@@ -332,7 +333,9 @@ impl AnyObj {
     where
         T: Any,
     {
-        unsafe { (self.vtable.as_ptr)(self.data, Hash::from_any::<T>()).map(|v| &*(v as *const _)) }
+        unsafe {
+            (self.vtable.as_ptr)(self.data, any::TypeId::of::<T>()).map(|v| &*(v as *const _))
+        }
     }
 
     /// Returns some mutable reference to the boxed value if it is of type `T`, or
@@ -357,12 +360,12 @@ impl AnyObj {
         T: Any,
     {
         unsafe {
-            (self.vtable.as_ptr)(self.data, Hash::from_any::<T>()).map(|v| &mut *(v as *mut _))
+            (self.vtable.as_ptr)(self.data, any::TypeId::of::<T>()).map(|v| &mut *(v as *mut _))
         }
     }
 
     /// Attempt to perform a conversion to a raw pointer.
-    pub(crate) fn raw_as_ptr(&self, expected: Hash) -> Result<*const (), AnyObjError> {
+    pub(crate) fn raw_as_ptr(&self, expected: any::TypeId) -> Result<*const (), AnyObjError> {
         // Safety: invariants are checked at construction time.
         match unsafe { (self.vtable.as_ptr)(self.data, expected) } {
             Some(ptr) => Ok(ptr),
@@ -371,7 +374,7 @@ impl AnyObj {
     }
 
     /// Attempt to perform a conversion to a raw mutable pointer.
-    pub(crate) fn raw_as_mut(&mut self, expected: Hash) -> Result<*mut (), AnyObjError> {
+    pub(crate) fn raw_as_mut(&mut self, expected: any::TypeId) -> Result<*mut (), AnyObjError> {
         match self.vtable.kind {
             // Only owned and mutable pointers can be treated as mutable.
             AnyObjKind::Owned | AnyObjKind::MutPtr => (),
@@ -396,7 +399,7 @@ impl AnyObj {
     ///
     /// If the conversion is not possible, we return a reconstructed `Any` as
     /// the error variant.
-    pub(crate) fn raw_take(self, expected: Hash) -> Result<*mut (), (AnyObjError, Self)> {
+    pub(crate) fn raw_take(self, expected: any::TypeId) -> Result<*mut (), (AnyObjError, Self)> {
         match self.vtable.kind {
             // Only owned things can be taken.
             AnyObjKind::Owned => (),
@@ -464,7 +467,7 @@ impl Drop for AnyObj {
 pub type DropFn = unsafe fn(*const ());
 
 /// The signature of a pointer coercion function.
-pub type AsPtrFn = unsafe fn(this: *const (), expected: Hash) -> Option<*const ()>;
+pub type AsPtrFn = unsafe fn(this: *const (), expected: any::TypeId) -> Option<*const ()>;
 
 /// The signature of a descriptive type name function.
 pub type DebugFn = fn(&mut fmt::Formatter<'_>) -> fmt::Result;
@@ -496,7 +499,7 @@ pub struct AnyObjVtable {
     kind: AnyObjKind,
     /// The underlying drop implementation for the stored type.
     drop: DropFn,
-    /// Punt the inner pointer to the type corresponding to the type hash.
+    /// Punt the inner pointer to the type corresponding to the type id.
     as_ptr: AsPtrFn,
     /// Type information for diagnostics.
     debug: DebugFn,
@@ -510,22 +513,22 @@ unsafe fn drop_impl<T>(this: *const ()) {
     Box::from_raw(this as *mut () as *mut T);
 }
 
-fn as_ptr_impl<T>(this: *const (), expected: Hash) -> Option<*const ()>
+fn as_ptr_impl<T>(this: *const (), expected: any::TypeId) -> Option<*const ()>
 where
     T: Any,
 {
-    if expected == Hash::from_type_id(any::TypeId::of::<T>()) {
+    if expected == any::TypeId::of::<T>() {
         Some(this)
     } else {
         None
     }
 }
 
-fn as_ptr_deref_impl<T: Deref>(this: *const (), expected: Hash) -> Option<*const ()>
+fn as_ptr_deref_impl<T: Deref>(this: *const (), expected: any::TypeId) -> Option<*const ()>
 where
     T::Target: Any,
 {
-    if expected == Hash::from_type_id(any::TypeId::of::<T::Target>()) {
+    if expected == any::TypeId::of::<T::Target>() {
         let guard = this as *const T;
         unsafe { Some((*guard).deref() as *const _ as *const ()) }
     } else {
@@ -533,11 +536,11 @@ where
     }
 }
 
-fn as_ptr_deref_mut_impl<T: DerefMut>(this: *const (), expected: Hash) -> Option<*const ()>
+fn as_ptr_deref_mut_impl<T: DerefMut>(this: *const (), expected: any::TypeId) -> Option<*const ()>
 where
     T::Target: Any,
 {
-    if expected == Hash::from_type_id(any::TypeId::of::<T::Target>()) {
+    if expected == any::TypeId::of::<T::Target>() {
         let guard = this as *mut T;
         unsafe { Some((*guard).deref_mut() as *const _ as *const ()) }
     } else {
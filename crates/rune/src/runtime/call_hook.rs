@@ -0,0 +1,149 @@
+//! Interception of calls into native functions.
+
+use crate::compile::{ContextSignature, Item};
+use crate::runtime::{RuntimeContext, Value, VmError};
+use crate::{Hash, InstFnKind};
+use std::fmt;
+
+/// A hook which observes every call a virtual machine makes into a native
+/// function, and which can veto it.
+///
+/// This can be used for auditing, rate limiting or sandbox policies which are
+/// finer grained than [Capabilities][crate::runtime::Capabilities]. It's
+/// installed with [Vm::set_call_hook][crate::Vm::set_call_hook], and is
+/// implemented for closures.
+///
+/// Native functions which are called through a function value by another
+/// native function, like the function passed to `Iterator::map`, are observed
+/// as well.
+///
+/// # Examples
+///
+/// ```
+/// use rune::{Context, Vm};
+/// use rune::runtime::{NativeCall, VmError};
+/// use std::sync::{Arc, Mutex};
+///
+/// # fn main() -> rune::Result<()> {
+/// let context = Context::with_default_modules()?;
+///
+/// let mut sources = rune::sources! {
+///     entry => {
+///         pub fn main() {
+///             let values = [1, 2, 3];
+///             values.push(4);
+///             values.len()
+///         }
+///     }
+/// };
+///
+/// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+/// let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+///
+/// let calls = Arc::new(Mutex::new(Vec::new()));
+/// let log = calls.clone();
+///
+/// vm.set_call_hook(move |call: &NativeCall<'_>| {
+///     if let Some(item) = call.item() {
+///         log.lock().unwrap().push(item.to_string());
+///     }
+///
+///     Ok(())
+/// });
+///
+/// vm.call(&["main"], ())?;
+///
+/// assert_eq!(
+///     *calls.lock().unwrap(),
+///     ["::std::vec::Vec::push", "::std::vec::Vec::len"]
+/// );
+/// # Ok(()) }
+/// ```
+pub trait CallHook: Send + Sync {
+    /// Called before the described native function is called.
+    ///
+    /// Returning an error vetoes the call, and the error is raised in the
+    /// virtual machine instead.
+    fn call(&self, call: &NativeCall<'_>) -> Result<(), VmError>;
+}
+
+impl fmt::Debug for dyn CallHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CallHook")
+    }
+}
+
+impl<F> CallHook for F
+where
+    F: Send + Sync + Fn(&NativeCall<'_>) -> Result<(), VmError>,
+{
+    fn call(&self, call: &NativeCall<'_>) -> Result<(), VmError> {
+        self(call)
+    }
+}
+
+/// A call into a native function, as observed by a [CallHook].
+pub struct NativeCall<'a> {
+    context: &'a RuntimeContext,
+    hash: Hash,
+    args: &'a [Value],
+}
+
+impl<'a> NativeCall<'a> {
+    pub(crate) fn new(context: &'a RuntimeContext, hash: Hash, args: &'a [Value]) -> Self {
+        Self {
+            context,
+            hash,
+            args,
+        }
+    }
+
+    /// The hash of the function being called.
+    pub fn hash(&self) -> Hash {
+        self.hash
+    }
+
+    /// The signature of the function being called, if it's registered in the
+    /// context.
+    pub fn signature(&self) -> Option<&'a ContextSignature> {
+        self.context.signature(self.hash)
+    }
+
+    /// The item of the function being called, like `::std::vec::Vec::len`.
+    ///
+    /// For instance functions this is the item of the type, extended with the
+    /// name of the function or the protocol.
+    pub fn item(&self) -> Option<Item> {
+        match self.signature()? {
+            ContextSignature::Function { item, .. } => Some(item.clone()),
+            ContextSignature::Instance { item, name, .. } => Some(match name {
+                InstFnKind::Instance(name) => item.extended(&**name),
+                InstFnKind::Protocol(protocol) => item.extended(protocol.name),
+                InstFnKind::Hash(..) => item.clone(),
+            }),
+        }
+    }
+
+    /// The arguments of the call. For instance functions the first argument
+    /// is the instance.
+    pub fn args(&self) -> &'a [Value] {
+        self.args
+    }
+}
+
+/// Construct the span of a call into a native function, which is entered for
+/// the duration of the call.
+///
+/// The item of the function is only resolved if the span is enabled, so that
+/// this is cheap unless a subscriber is interested in it.
+pub(crate) fn native_span(context: &RuntimeContext, hash: Hash) -> tracing::Span {
+    let span = tracing::trace_span!("native_call", item = tracing::field::Empty, %hash);
+
+    if !span.is_disabled() {
+        if let Some(item) = NativeCall::new(context, hash, &[]).item() {
+            span.record("item", tracing::field::display(item));
+        }
+    }
+
+    span
+}
@@ -0,0 +1,189 @@
+//! Capabilities which native modules require, used to run untrusted scripts
+//! with least privilege.
+
+use crate::runtime::{VmError, VmErrorKind};
+use std::fmt;
+
+/// A capability which the functions of a native module can require, like
+/// access to the filesystem or the network.
+///
+/// A module declares the capabilities it requires through
+/// [Module::require][crate::Module::require].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Capability {
+    /// Reading and writing the filesystem.
+    Filesystem,
+    /// Connecting to and listening on the network.
+    Network,
+    /// Spawning and signaling processes.
+    Process,
+    /// Reading and modifying environment variables.
+    Environment,
+    /// Reading the system clock and sleeping.
+    Time,
+    /// Generating random numbers from the system.
+    Random,
+}
+
+impl Capability {
+    /// All known capabilities.
+    const ALL: [Capability; 6] = [
+        Capability::Filesystem,
+        Capability::Network,
+        Capability::Process,
+        Capability::Environment,
+        Capability::Time,
+        Capability::Random,
+    ];
+
+    /// The bit used for the capability in [Capabilities].
+    const fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Capability::Filesystem => "filesystem",
+            Capability::Network => "network",
+            Capability::Process => "process",
+            Capability::Environment => "environment",
+            Capability::Time => "time",
+            Capability::Random => "random",
+        };
+
+        f.write_str(name)
+    }
+}
+
+/// A set of [Capability] values.
+///
+/// A [Context][crate::Context] refuses to install modules which require
+/// capabilities it doesn't have, and a [Vm][crate::Vm] refuses to call native
+/// functions which require capabilities it doesn't have, which can be used to
+/// restrict individual virtual machines sharing a context. Both have every
+/// capability unless configured otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use rune::{Context, Module, Vm};
+/// use rune::runtime::{Capabilities, Capability, VmErrorKind};
+/// use std::sync::Arc;
+///
+/// # fn main() -> rune::Result<()> {
+/// let mut module = Module::with_crate("clock");
+/// module.require(Capability::Time);
+/// module.function(&["now"], || 42i64)?;
+///
+/// let mut context = Context::with_default_modules()?;
+/// context.install(&module)?;
+///
+/// let mut sources = rune::sources! {
+///     entry => {
+///         pub fn main() {
+///             clock::now()
+///         }
+///     }
+/// };
+///
+/// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+/// let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+/// vm.set_capabilities(Capabilities::all().without(Capability::Time));
+///
+/// let error = vm.call(&["main"], ()).unwrap_err();
+///
+/// assert!(matches!(
+///     error.into_unwound().0.into_kind(),
+///     VmErrorKind::MissingCapability { capability: Capability::Time }
+/// ));
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Capabilities {
+    bits: u8,
+}
+
+impl Capabilities {
+    /// The set containing every capability.
+    pub const fn all() -> Self {
+        let mut bits = 0;
+        let mut n = 0;
+
+        while n < Capability::ALL.len() {
+            bits |= Capability::ALL[n].bit();
+            n += 1;
+        }
+
+        Self { bits }
+    }
+
+    /// The empty set, which only allows native functions which don't require
+    /// any capability. This is the default.
+    pub const fn none() -> Self {
+        Self { bits: 0 }
+    }
+
+    /// Add the given capability to the set.
+    pub const fn with(self, capability: Capability) -> Self {
+        Self {
+            bits: self.bits | capability.bit(),
+        }
+    }
+
+    /// Remove the given capability from the set.
+    pub const fn without(self, capability: Capability) -> Self {
+        Self {
+            bits: self.bits & !capability.bit(),
+        }
+    }
+
+    /// Test if the set contains the given capability.
+    pub const fn contains(self, capability: Capability) -> bool {
+        self.bits & capability.bit() != 0
+    }
+
+    /// Test if the set contains every capability in the other set.
+    pub const fn contains_all(self, other: Capabilities) -> bool {
+        self.bits & other.bits == other.bits
+    }
+
+    /// Test if the set is empty.
+    pub const fn is_empty(self) -> bool {
+        self.bits == 0
+    }
+
+    /// Iterate over the capabilities in the set.
+    pub fn iter(self) -> impl Iterator<Item = Capability> {
+        Capability::ALL
+            .into_iter()
+            .filter(move |capability| self.contains(*capability))
+    }
+
+    /// Get the capabilities of the virtual machine which is currently
+    /// running.
+    ///
+    /// Native functions can use this to check for capabilities themselves,
+    /// but functions in modules which require a capability are only called if
+    /// the virtual machine has it. When no virtual machine is running, the
+    /// caller is trusted with every capability.
+    pub fn current() -> Self {
+        crate::runtime::env::capabilities()
+    }
+
+    /// Check that the virtual machine which is currently running has all the
+    /// capabilities in this set.
+    pub(crate) fn check(self) -> Result<(), VmError> {
+        let current = Self::current();
+
+        for capability in self.iter() {
+            if !current.contains(capability) {
+                return Err(VmError::from(VmErrorKind::MissingCapability { capability }));
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,308 @@
+//! Deep cloning of values, which clones everything a value refers to.
+//!
+//! References which are shared within the value being cloned are also shared
+//! within the clone, which means that cyclic values can be cloned.
+
+use crate::collections::HashMap;
+use crate::runtime::{
+    EnvProtocolCaller, GeneratorState, Object, Protocol, ProtocolCaller, Range, Shared, Struct,
+    Tuple, TupleStruct, UnitStruct, Value, Variant, VariantData, Vec, VmError, VmErrorKind,
+};
+
+/// Deeply clone the given value.
+///
+/// Script types and external types are cloned using their [Protocol::CLONE]
+/// function if they have one. Script types which don't are cloned field by
+/// field.
+pub(crate) fn deep_clone(value: &Value) -> Result<Value, VmError> {
+    Cloner::default().value(value)
+}
+
+/// Deeply clone the given vector.
+pub(crate) fn deep_clone_vec(vec: &Vec) -> Result<Value, VmError> {
+    Cloner::default().vec(vec)
+}
+
+/// Deeply clone the given object.
+pub(crate) fn deep_clone_object(object: &Object) -> Result<Value, VmError> {
+    Cloner::default().object(object)
+}
+
+#[derive(Default)]
+struct Cloner {
+    /// Clones of the values which have been visited, by the address of the
+    /// data being cloned.
+    seen: HashMap<usize, Value>,
+}
+
+impl Cloner {
+    fn value(&mut self, value: &Value) -> Result<Value, VmError> {
+        Ok(match value {
+            Value::Unit
+            | Value::Bool(..)
+            | Value::Byte(..)
+            | Value::Char(..)
+            | Value::Integer(..)
+            | Value::Float(..)
+            | Value::Type(..)
+            | Value::StaticString(..)
+            | Value::Function(..)
+            | Value::Format(..) => value.clone(),
+            Value::String(string) => {
+                let string = string.borrow_ref()?;
+
+                self.visit(&*string, || {
+                    Value::String(Shared::tracked(String::clone(&string)))
+                })
+            }
+            Value::Bytes(bytes) => {
+                let bytes = bytes.borrow_ref()?;
+                self.visit(&*bytes, || Value::Bytes(Shared::tracked(bytes.clone())))
+            }
+            Value::Vec(vec) => self.vec(&*vec.borrow_ref()?)?,
+            Value::Tuple(tuple) => {
+                let tuple = tuple.borrow_ref()?;
+
+                if let Some(value) = self.seen.get(&addr(&*tuple)) {
+                    return Ok(value.clone());
+                }
+
+                let target = Shared::tracked(placeholder_tuple(tuple.len()));
+                self.seen
+                    .insert(addr(&*tuple), Value::Tuple(target.clone()));
+                self.fill_tuple(&tuple, &mut *target.borrow_mut()?)?;
+                Value::Tuple(target)
+            }
+            Value::Object(object) => self.object(&*object.borrow_ref()?)?,
+            Value::Range(range) => {
+                let range = range.borrow_ref()?;
+
+                if let Some(value) = self.seen.get(&addr(&*range)) {
+                    return Ok(value.clone());
+                }
+
+                let target = Shared::tracked(Range::new(None, None, range.limits));
+                self.seen
+                    .insert(addr(&*range), Value::Range(target.clone()));
+                let start = self.option(range.start.as_ref())?;
+                let end = self.option(range.end.as_ref())?;
+                *target.borrow_mut()? = Range::new(start, end, range.limits);
+                Value::Range(target)
+            }
+            Value::GeneratorState(state) => {
+                let state = state.borrow_ref()?;
+
+                if let Some(value) = self.seen.get(&addr(&*state)) {
+                    return Ok(value.clone());
+                }
+
+                let target = Shared::tracked(GeneratorState::Complete(Value::Unit));
+                self.seen
+                    .insert(addr(&*state), Value::GeneratorState(target.clone()));
+
+                let state = match &*state {
+                    GeneratorState::Yielded(value) => GeneratorState::Yielded(self.value(value)?),
+                    GeneratorState::Complete(value) => GeneratorState::Complete(self.value(value)?),
+                };
+
+                *target.borrow_mut()? = state;
+                Value::GeneratorState(target)
+            }
+            Value::Option(option) => {
+                let option = option.borrow_ref()?;
+
+                if let Some(value) = self.seen.get(&addr(&*option)) {
+                    return Ok(value.clone());
+                }
+
+                let target = Shared::tracked(None);
+                self.seen
+                    .insert(addr(&*option), Value::Option(target.clone()));
+                let option = self.option(option.as_ref())?;
+                *target.borrow_mut()? = option;
+                Value::Option(target)
+            }
+            Value::Result(result) => {
+                let result = result.borrow_ref()?;
+
+                if let Some(value) = self.seen.get(&addr(&*result)) {
+                    return Ok(value.clone());
+                }
+
+                let target = Shared::tracked(Ok(Value::Unit));
+                self.seen
+                    .insert(addr(&*result), Value::Result(target.clone()));
+
+                let result = match &*result {
+                    Ok(value) => Ok(self.value(value)?),
+                    Err(value) => Err(self.value(value)?),
+                };
+
+                *target.borrow_mut()? = result;
+                Value::Result(target)
+            }
+            Value::UnitStruct(..)
+            | Value::TupleStruct(..)
+            | Value::Struct(..)
+            | Value::Variant(..)
+            | Value::Any(..)
+                if EnvProtocolCaller.has_protocol_fn(Protocol::CLONE, value)? =>
+            {
+                EnvProtocolCaller.call_protocol_fn(Protocol::CLONE, value.clone(), ())?
+            }
+            Value::UnitStruct(unit) => {
+                let unit = unit.borrow_ref()?;
+
+                self.visit(&*unit, || {
+                    Value::UnitStruct(Shared::tracked(UnitStruct {
+                        rtti: unit.rtti.clone(),
+                    }))
+                })
+            }
+            Value::TupleStruct(tuple) => {
+                let tuple = tuple.borrow_ref()?;
+
+                if let Some(value) = self.seen.get(&addr(&*tuple)) {
+                    return Ok(value.clone());
+                }
+
+                let target = Shared::tracked(TupleStruct {
+                    rtti: tuple.rtti.clone(),
+                    data: placeholder_tuple(tuple.data.len()),
+                });
+
+                self.seen
+                    .insert(addr(&*tuple), Value::TupleStruct(target.clone()));
+                self.fill_tuple(&tuple.data, &mut target.borrow_mut()?.data)?;
+                Value::TupleStruct(target)
+            }
+            Value::Struct(st) => {
+                let st = st.borrow_ref()?;
+
+                if let Some(value) = self.seen.get(&addr(&*st)) {
+                    return Ok(value.clone());
+                }
+
+                let target = Shared::tracked(Struct {
+                    rtti: st.rtti.clone(),
+                    data: Object::with_capacity(st.data.len()),
+                });
+
+                self.seen.insert(addr(&*st), Value::Struct(target.clone()));
+                self.fill_object(&st.data, &mut target.borrow_mut()?.data)?;
+                Value::Struct(target)
+            }
+            Value::Variant(variant) => {
+                let variant = variant.borrow_ref()?;
+
+                if let Some(value) = self.seen.get(&addr(&*variant)) {
+                    return Ok(value.clone());
+                }
+
+                let data = match &variant.data {
+                    VariantData::Unit => VariantData::Unit,
+                    VariantData::Tuple(tuple) => VariantData::Tuple(placeholder_tuple(tuple.len())),
+                    VariantData::Struct(object) => {
+                        VariantData::Struct(Object::with_capacity(object.len()))
+                    }
+                };
+
+                let target = Shared::tracked(Variant {
+                    rtti: variant.rtti.clone(),
+                    data,
+                });
+
+                self.seen
+                    .insert(addr(&*variant), Value::Variant(target.clone()));
+
+                match (&variant.data, &mut target.borrow_mut()?.data) {
+                    (VariantData::Tuple(tuple), VariantData::Tuple(output)) => {
+                        self.fill_tuple(tuple, output)?;
+                    }
+                    (VariantData::Struct(object), VariantData::Struct(output)) => {
+                        self.fill_object(object, output)?;
+                    }
+                    _ => (),
+                }
+
+                Value::Variant(target)
+            }
+            Value::Future(..)
+            | Value::Stream(..)
+            | Value::Generator(..)
+            | Value::Iterator(..)
+            | Value::Any(..) => {
+                return Err(VmError::from(VmErrorKind::UnsupportedUnaryOperation {
+                    op: "clone",
+                    operand: value.type_info()?,
+                }))
+            }
+        })
+    }
+
+    fn vec(&mut self, vec: &Vec) -> Result<Value, VmError> {
+        if let Some(value) = self.seen.get(&addr(vec)) {
+            return Ok(value.clone());
+        }
+
+        let target = Shared::tracked(Vec::with_capacity(vec.len()));
+        self.seen.insert(addr(vec), Value::Vec(target.clone()));
+
+        for value in vec.iter() {
+            let value = self.value(value)?;
+            target.borrow_mut()?.push(value);
+        }
+
+        Ok(Value::Vec(target))
+    }
+
+    fn object(&mut self, object: &Object) -> Result<Value, VmError> {
+        if let Some(value) = self.seen.get(&addr(object)) {
+            return Ok(value.clone());
+        }
+
+        let target = Shared::tracked(Object::with_capacity(object.len()));
+        self.seen
+            .insert(addr(object), Value::Object(target.clone()));
+        self.fill_object(object, &mut *target.borrow_mut()?)?;
+        Ok(Value::Object(target))
+    }
+
+    /// Clone a value without any references to other values.
+    fn visit<T>(&mut self, data: &T, clone: impl FnOnce() -> Value) -> Value {
+        self.seen.entry(addr(data)).or_insert_with(clone).clone()
+    }
+
+    fn option(&mut self, value: Option<&Value>) -> Result<Option<Value>, VmError> {
+        match value {
+            Some(value) => Ok(Some(self.value(value)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn fill_tuple(&mut self, tuple: &Tuple, output: &mut Tuple) -> Result<(), VmError> {
+        for (value, output) in tuple.iter().zip(output.iter_mut()) {
+            *output = self.value(value)?;
+        }
+
+        Ok(())
+    }
+
+    fn fill_object(&mut self, object: &Object, output: &mut Object) -> Result<(), VmError> {
+        for (key, value) in object {
+            output.insert(key.clone(), self.value(value)?);
+        }
+
+        Ok(())
+    }
+}
+
+/// Construct a tuple of the given length to fill in.
+fn placeholder_tuple(len: usize) -> Tuple {
+    Tuple::from(vec![Value::Unit; len])
+}
+
+/// The address of the data being cloned.
+fn addr<T>(data: &T) -> usize {
+    data as *const T as usize
+}
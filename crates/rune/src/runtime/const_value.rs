@@ -8,7 +8,7 @@ use std::sync::Arc;
 use std::vec;
 
 /// A constant value.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub enum ConstValue {
     /// A constant unit.
     Unit,
@@ -52,11 +52,11 @@ impl ConstValue {
             Self::Bool(b) => Value::Bool(b),
             Self::Integer(n) => Value::Integer(n),
             Self::Float(n) => Value::Float(n),
-            Self::String(s) => Value::String(Shared::new(s)),
+            Self::String(s) => Value::String(Shared::tracked(s)),
             Self::StaticString(s) => Value::StaticString(s),
-            Self::Bytes(b) => Value::Bytes(Shared::new(b)),
+            Self::Bytes(b) => Value::Bytes(Shared::tracked(b)),
             Self::Option(option) => {
-                Value::Option(Shared::new(option.map(|some| some.into_value())))
+                Value::Option(Shared::tracked(option.map(|some| some.into_value())))
             }
             Self::Vec(vec) => {
                 let mut v = Vec::with_capacity(vec.len());
@@ -65,7 +65,7 @@ impl ConstValue {
                     v.push(value.into_value());
                 }
 
-                Value::Vec(Shared::new(v))
+                Value::Vec(Shared::tracked(v))
             }
             Self::Tuple(tuple) => {
                 let mut t = vec::Vec::with_capacity(tuple.len());
@@ -74,7 +74,7 @@ impl ConstValue {
                     t.push(value.into_value());
                 }
 
-                Value::Tuple(Shared::new(Tuple::from(t)))
+                Value::Tuple(Shared::tracked(Tuple::from(t)))
             }
             Self::Object(object) => {
                 let mut o = Object::with_capacity(object.len());
@@ -83,7 +83,7 @@ impl ConstValue {
                     o.insert(key, value.into_value());
                 }
 
-                Value::Object(Shared::new(o))
+                Value::Object(Shared::tracked(o))
             }
         }
     }
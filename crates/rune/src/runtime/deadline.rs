@@ -0,0 +1,257 @@
+//! Wall-clock deadlines for virtual machines.
+
+use crate::runtime::{VmError, VmErrorKind};
+use pin_project::pin_project;
+use std::cmp::Ordering as CmpOrdering;
+use std::collections::BinaryHeap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, Weak};
+use std::task::{Context, Poll, Waker};
+use std::thread::{self, Thread};
+use std::time::Instant;
+
+/// The timer shared by every deadline, which is started the first time an
+/// execution with a deadline is suspended.
+static TIMER: Mutex<Option<Timer>> = Mutex::new(None);
+
+/// The deadline of a virtual machine and the virtual machines it spawns.
+///
+/// Expiration is detected by the virtual machine as it executes instructions,
+/// but while it's waiting on a future nothing is executed. So the first time
+/// an execution is suspended the deadline is handed to a timer thread shared
+/// by all deadlines, which wakes the task once the deadline has passed.
+#[derive(Debug)]
+pub(crate) struct Deadline {
+    at: Instant,
+    expired: AtomicBool,
+    /// If the deadline has been handed to the timer.
+    queued: AtomicBool,
+    /// The waker of the last task to be suspended.
+    waker: Mutex<Option<Waker>>,
+}
+
+impl Deadline {
+    pub(crate) fn new(at: Instant) -> Self {
+        Self {
+            at,
+            expired: AtomicBool::new(false),
+            queued: AtomicBool::new(false),
+            waker: Mutex::new(None),
+        }
+    }
+
+    /// When the deadline expires.
+    pub(crate) fn at(&self) -> Instant {
+        self.at
+    }
+
+    /// Test if the deadline has expired.
+    pub(crate) fn expired(&self) -> bool {
+        if self.expired.load(Ordering::Relaxed) {
+            return true;
+        }
+
+        if Instant::now() >= self.at {
+            self.expired.store(true, Ordering::Relaxed);
+            return true;
+        }
+
+        false
+    }
+
+    /// Check that the deadline hasn't expired.
+    pub(crate) fn check(&self) -> Result<(), VmError> {
+        if self.expired() {
+            return Err(VmError::from(VmErrorKind::Timeout));
+        }
+
+        Ok(())
+    }
+
+    /// Wrap a future so that it's cancelled once the deadline expires.
+    pub(crate) fn wrap<F>(self: &Arc<Self>, future: F) -> WithDeadline<F> {
+        WithDeadline {
+            deadline: self.clone(),
+            future,
+        }
+    }
+
+    /// Register the waker of a suspended task, which is woken once the
+    /// deadline expires.
+    fn register(self: &Arc<Self>, waker: &Waker) {
+        {
+            let mut current = self.lock_waker();
+
+            if !matches!(&*current, Some(w) if w.will_wake(waker)) {
+                *current = Some(waker.clone());
+            }
+        }
+
+        // NB: the timer might have fired before the waker was replaced.
+        if self.expired() {
+            waker.wake_by_ref();
+            return;
+        }
+
+        if self.queued.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        let mut timer = lock(&TIMER);
+
+        let timer = timer.get_or_insert_with(|| Timer {
+            queue: BinaryHeap::new(),
+            thread: None,
+        });
+
+        let earliest = !matches!(timer.queue.peek(), Some(entry) if entry.at <= self.at);
+
+        timer.queue.push(Entry {
+            at: self.at,
+            deadline: Arc::downgrade(self),
+        });
+
+        match &timer.thread {
+            // NB: the timer thread only has to be woken up if it's waiting
+            // for a later deadline.
+            Some(thread) => {
+                if earliest {
+                    thread.unpark();
+                }
+            }
+            None => {
+                let handle = thread::Builder::new()
+                    .name(String::from("rune-deadline"))
+                    .spawn(run_timer);
+
+                timer.thread = handle.ok().map(|handle| handle.thread().clone());
+            }
+        }
+    }
+
+    /// Wake the task waiting on the deadline, since it has expired.
+    fn wake(&self) {
+        self.expired.store(true, Ordering::Relaxed);
+
+        let waker = self.lock_waker().take();
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    fn lock_waker(&self) -> MutexGuard<'_, Option<Waker>> {
+        lock(&self.waker)
+    }
+}
+
+/// Deadlines which tasks are waiting on, ordered by when they expire.
+struct Timer {
+    queue: BinaryHeap<Entry>,
+    /// The timer thread, if it has been started.
+    thread: Option<Thread>,
+}
+
+/// A deadline in the queue of the timer.
+///
+/// The deadline is only weakly held, so that the timer doesn't keep it alive
+/// if the execution is dropped before it expires.
+struct Entry {
+    at: Instant,
+    deadline: Weak<Deadline>,
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.at == other.at
+    }
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        // NB: reversed, so that the heap pops the earliest deadline first.
+        other.at.cmp(&self.at)
+    }
+}
+
+/// Run the timer thread, waking tasks as their deadlines expire.
+fn run_timer() {
+    loop {
+        let mut expired = Vec::new();
+
+        let timeout = {
+            let mut timer = lock(&TIMER);
+
+            let timer = match &mut *timer {
+                Some(timer) => timer,
+                None => return,
+            };
+
+            let now = Instant::now();
+
+            while matches!(timer.queue.peek(), Some(entry) if entry.at <= now) {
+                if let Some(deadline) = timer.queue.pop().and_then(|e| e.deadline.upgrade()) {
+                    expired.push(deadline);
+                }
+            }
+
+            timer.queue.peek().map(|entry| entry.at - now)
+        };
+
+        // NB: tasks are woken without holding the lock, since waking them
+        // might register other deadlines.
+        for deadline in expired {
+            deadline.wake();
+        }
+
+        match timeout {
+            Some(timeout) => thread::park_timeout(timeout),
+            None => thread::park(),
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// A future which fails with [VmErrorKind::Timeout] once a deadline expires.
+#[pin_project]
+pub(crate) struct WithDeadline<F> {
+    deadline: Arc<Deadline>,
+    #[pin]
+    future: F,
+}
+
+impl<F, T> Future for WithDeadline<F>
+where
+    F: Future<Output = Result<T, VmError>>,
+{
+    type Output = Result<T, VmError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        if this.deadline.expired() {
+            return Poll::Ready(Err(VmError::from(VmErrorKind::Timeout)));
+        }
+
+        match this.future.poll(cx) {
+            Poll::Ready(output) => Poll::Ready(output),
+            Poll::Pending => {
+                this.deadline.register(cx.waker());
+                Poll::Pending
+            }
+        }
+    }
+}
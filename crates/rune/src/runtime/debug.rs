@@ -18,6 +18,9 @@ pub struct DebugInfo {
     pub functions: HashMap<Hash, DebugSignature>,
     /// Reverse lookup of a function.
     pub functions_rev: HashMap<usize, Hash>,
+    /// Reverse lookup of the items referenced by the unit, such as the
+    /// functions it calls and the types it constructs.
+    pub hash_to_item: HashMap<Hash, Item>,
 }
 
 impl DebugInfo {
@@ -26,12 +29,38 @@ impl DebugInfo {
         self.instructions.get(ip)
     }
 
+    /// Get the item corresponding to the given hash, if it's known.
+    ///
+    /// This can be used to report a human-readable path for anything the unit
+    /// refers to by hash, like a function which is missing at runtime.
+    pub fn item_for_hash(&self, hash: Hash) -> Option<&Item> {
+        if let Some(item) = self.hash_to_item.get(&hash) {
+            return Some(item);
+        }
+
+        Some(&self.functions.get(&hash)?.path)
+    }
+
     /// Get the function corresponding to the given instruction pointer.
     pub fn function_at(&self, ip: usize) -> Option<(Hash, &DebugSignature)> {
         let hash = *self.functions_rev.get(&ip)?;
         let signature = self.functions.get(&hash)?;
         Some((hash, signature))
     }
+
+    /// Get the function which contains the instruction at the given
+    /// instruction pointer.
+    pub fn function_containing(&self, ip: usize) -> Option<(Hash, &DebugSignature)> {
+        let hash = self
+            .functions_rev
+            .iter()
+            .filter(|(offset, _)| **offset <= ip)
+            .max_by_key(|(offset, _)| **offset)
+            .map(|(_, hash)| *hash)?;
+
+        let signature = self.functions.get(&hash)?;
+        Some((hash, signature))
+    }
 }
 
 /// Debug information for every instruction.
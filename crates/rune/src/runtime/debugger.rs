@@ -0,0 +1,345 @@
+//! Hooks for debugging the execution of a virtual machine.
+
+use crate::ast::Span;
+use crate::runtime::{CallFrame, DebugInst, Inst, Profiler, Stack, Unit, Vm};
+use crate::SourceId;
+use std::fmt;
+use std::io;
+
+/// What to do after a [DebugListener] has been notified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DebugAction {
+    /// Continue executing.
+    Continue,
+    /// Pause execution before the current instruction is executed.
+    ///
+    /// The paused execution can later be resumed with
+    /// [VmExecution::resume_debug][crate::runtime::VmExecution::resume_debug].
+    Pause,
+}
+
+/// The outcome of resuming an execution which is being debugged.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum DebugOutcome {
+    /// The execution was paused, either by a [DebugListener], a breakpoint,
+    /// or because it's single-stepping.
+    Paused,
+    /// The execution yielded the given value.
+    Yielded(crate::runtime::Value),
+    /// The execution completed with the given value.
+    Complete(crate::runtime::Value),
+}
+
+/// The state of the virtual machine at the instruction which is about to be
+/// executed.
+pub struct DebugContext<'a> {
+    vm: &'a Vm,
+    inst: &'a Inst,
+    debug: Option<&'a DebugInst>,
+}
+
+impl<'a> DebugContext<'a> {
+    /// The instruction pointer of the instruction about to be executed.
+    pub fn ip(&self) -> usize {
+        self.vm.ip()
+    }
+
+    /// The instruction about to be executed.
+    pub fn inst(&self) -> &'a Inst {
+        self.inst
+    }
+
+    /// The source the instruction was compiled from, if the unit has debug
+    /// information.
+    pub fn source_id(&self) -> Option<SourceId> {
+        Some(self.debug?.source_id)
+    }
+
+    /// The span the instruction was compiled from, if the unit has debug
+    /// information.
+    pub fn span(&self) -> Option<Span> {
+        Some(self.debug?.span)
+    }
+
+    /// The call depth, which is the number of call frames of the virtual
+    /// machine.
+    pub fn depth(&self) -> usize {
+        self.vm.call_frames().len()
+    }
+
+    /// The stack of the virtual machine.
+    pub fn stack(&self) -> &'a Stack {
+        self.vm.stack()
+    }
+
+    /// The call frames of the virtual machine.
+    pub fn call_frames(&self) -> &'a [CallFrame] {
+        self.vm.call_frames()
+    }
+
+    /// The unit being executed.
+    pub fn unit(&self) -> &'a Unit {
+        self.vm.unit()
+    }
+}
+
+/// A listener which is notified as a virtual machine executes, used to
+/// implement debuggers.
+///
+/// See [Vm::set_debug_listener].
+pub trait DebugListener: Send {
+    /// Called before each instruction is executed.
+    fn before_instruction(&mut self, _: &DebugContext<'_>) -> DebugAction {
+        DebugAction::Continue
+    }
+
+    /// Called when execution enters a breakpoint, before the first instruction
+    /// inside of it is executed.
+    ///
+    /// Pauses by default.
+    fn breakpoint(&mut self, _: &DebugContext<'_>) -> DebugAction {
+        DebugAction::Pause
+    }
+}
+
+/// A sink which is passed every instruction executed while tracing.
+///
+/// See [Vm::set_trace_sink].
+pub trait TraceSink: Send {
+    /// Called with the instruction which is about to be executed.
+    fn trace(&mut self, cx: &DebugContext<'_>);
+}
+
+impl<F> TraceSink for F
+where
+    F: Send + FnMut(&DebugContext<'_>),
+{
+    fn trace(&mut self, cx: &DebugContext<'_>) {
+        self(cx)
+    }
+}
+
+/// A [TraceSink] which writes one line per executed instruction to the
+/// wrapped writer.
+///
+/// Errors raised while writing are ignored.
+pub struct TraceWriter<W> {
+    out: W,
+}
+
+impl<W> TraceWriter<W> {
+    /// Construct a new trace writer.
+    pub fn new(out: W) -> Self {
+        Self { out }
+    }
+
+    /// Coerce into the wrapped writer.
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+impl<W> TraceSink for TraceWriter<W>
+where
+    W: Send + io::Write,
+{
+    fn trace(&mut self, cx: &DebugContext<'_>) {
+        let _ = write!(
+            self.out,
+            "{:04} depth={} stack={} {}",
+            cx.ip(),
+            cx.depth(),
+            cx.stack().len(),
+            cx.inst()
+        );
+
+        if let (Some(source_id), Some(span)) = (cx.source_id(), cx.span()) {
+            let _ = write!(self.out, " // {}:{}", source_id, span);
+        }
+
+        let _ = writeln!(self.out);
+    }
+}
+
+/// A breakpoint on a span of a source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Breakpoint {
+    source_id: SourceId,
+    span: Span,
+}
+
+impl Breakpoint {
+    /// Test if the given instruction is covered by the breakpoint.
+    fn contains(&self, debug: &DebugInst) -> bool {
+        let start = self.span.start.into_usize();
+        // NB: an empty span covers the instructions starting at it.
+        let end = self.span.end.into_usize().max(start + 1);
+        let at = debug.span.start.into_usize();
+        debug.source_id == self.source_id && start <= at && at < end
+    }
+}
+
+/// The debugging state of a virtual machine.
+#[derive(Default)]
+pub(crate) struct Debugger {
+    listener: Option<Box<dyn DebugListener>>,
+    sink: Option<Box<dyn TraceSink>>,
+    /// Pass executed instructions to the trace sink.
+    tracing: bool,
+    profiler: Option<Profiler>,
+    breakpoints: Vec<Breakpoint>,
+    /// Pause before every instruction.
+    stepping: bool,
+    /// The breakpoint which the last instruction was covered by.
+    current: Option<usize>,
+    /// Set when execution was paused, so that the instruction it was paused
+    /// at is executed once resumed.
+    resuming: bool,
+}
+
+impl fmt::Debug for Debugger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Debugger")
+            .field("breakpoints", &self.breakpoints)
+            .field("stepping", &self.stepping)
+            .field("tracing", &self.tracing)
+            .finish()
+    }
+}
+
+impl Debugger {
+    pub(crate) fn set_listener(&mut self, listener: Option<Box<dyn DebugListener>>) {
+        self.listener = listener;
+    }
+
+    pub(crate) fn set_trace_sink(&mut self, sink: Option<Box<dyn TraceSink>>) {
+        self.tracing = sink.is_some();
+        self.sink = sink;
+    }
+
+    pub(crate) fn set_tracing(&mut self, tracing: bool) {
+        self.tracing = tracing;
+    }
+
+    pub(crate) fn set_profiler(&mut self, profiler: Option<Profiler>) {
+        self.profiler = profiler;
+    }
+
+    pub(crate) fn add_breakpoint(&mut self, source_id: SourceId, span: Span) {
+        let breakpoint = Breakpoint { source_id, span };
+
+        if !self.breakpoints.contains(&breakpoint) {
+            self.breakpoints.push(breakpoint);
+        }
+    }
+
+    pub(crate) fn remove_breakpoint(&mut self, source_id: SourceId, span: Span) -> bool {
+        let len = self.breakpoints.len();
+        self.breakpoints
+            .retain(|b| b.source_id != source_id || b.span != span);
+        self.current = None;
+        self.breakpoints.len() != len
+    }
+
+    pub(crate) fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
+        self.current = None;
+    }
+
+    pub(crate) fn set_stepping(&mut self, stepping: bool) {
+        self.stepping = stepping;
+    }
+
+    /// Notify the debugger of the instruction about to be executed, returning
+    /// `true` if execution should be paused.
+    pub(crate) fn hook(&mut self, cx: &DebugContext<'_>) -> bool {
+        if std::mem::take(&mut self.resuming) {
+            self.executing(cx);
+            return false;
+        }
+
+        let mut pause = self.stepping;
+
+        if let Some(listener) = &mut self.listener {
+            pause |= listener.before_instruction(cx) == DebugAction::Pause;
+        }
+
+        let hit = match cx.debug {
+            Some(debug) => self.breakpoints.iter().position(|b| b.contains(debug)),
+            None => None,
+        };
+
+        if hit.is_some() && hit != self.current {
+            pause |= match &mut self.listener {
+                Some(listener) => listener.breakpoint(cx) == DebugAction::Pause,
+                None => true,
+            };
+        }
+
+        self.current = hit;
+        self.resuming = pause;
+
+        // NB: a paused instruction is traced once it's resumed.
+        if pause {
+            if let Some(profiler) = &self.profiler {
+                profiler.pause();
+            }
+        } else {
+            self.executing(cx);
+        }
+
+        pause
+    }
+
+    /// Notify the trace sink and profiler of an instruction which is about to
+    /// be executed.
+    fn executing(&mut self, cx: &DebugContext<'_>) {
+        if let Some(profiler) = &self.profiler {
+            profiler.record(cx);
+        }
+
+        if !self.tracing {
+            return;
+        }
+
+        if let Some(sink) = &mut self.sink {
+            sink.trace(cx);
+        }
+    }
+}
+
+impl Vm {
+    /// Notify the debugger attached to the virtual machine of the instruction
+    /// about to be executed, returning `true` if execution should be paused.
+    #[cold]
+    pub(crate) fn debug_hook(&self) -> bool {
+        let debugger = match &self.debugger {
+            Some(debugger) => debugger,
+            None => return false,
+        };
+
+        let inst = match self.unit().instruction_at(self.ip()) {
+            Some(inst) => inst,
+            None => return false,
+        };
+
+        let debug = self
+            .unit()
+            .debug_info()
+            .and_then(|debug| debug.instruction_at(self.ip()));
+
+        let cx = DebugContext {
+            vm: self,
+            inst,
+            debug,
+        };
+
+        let mut debugger = debugger
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        debugger.hook(&cx)
+    }
+}
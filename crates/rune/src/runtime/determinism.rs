@@ -0,0 +1,120 @@
+//! Deterministic execution of virtual machines.
+
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+/// The sources of nondeterminism of a deterministic virtual machine, which
+/// native functions consult instead of the system.
+///
+/// A deterministic virtual machine:
+/// * Seeds random number generators from a single seed.
+/// * Reads time from a virtual clock, which only advances when told to.
+/// * Iterates over hash maps and sets in a stable order.
+///
+/// So executing the same script with the same inputs and the same seed
+/// produces the same results, which can be used to replay executions or run
+/// simulations in lockstep.
+///
+/// The handle can be cloned, and virtual machines spawned to run generators,
+/// streams and async functions share it with the virtual machine they are
+/// spawned from. Native functions access it through [Determinism::current].
+///
+/// # Examples
+///
+/// ```
+/// use rune::{Context, FromValue, Vm};
+/// use rune::runtime::Determinism;
+/// use std::sync::Arc;
+///
+/// # fn main() -> rune::Result<()> {
+/// let context = Context::with_default_modules()?;
+///
+/// let mut sources = rune::sources! {
+///     entry => {
+///         use std::collections::HashMap;
+///
+///         pub fn main() {
+///             let map = HashMap::new();
+///
+///             for n in 0..100 {
+///                 map.insert(n, n);
+///             }
+///
+///             map.keys().collect::<Vec>()
+///         }
+///     }
+/// };
+///
+/// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+/// let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+/// vm.set_determinism(Some(Determinism::new(42)));
+///
+/// let keys = Vec::<i64>::from_value(vm.call(&["main"], ())?)?;
+/// assert_eq!(keys, (0..100).collect::<Vec<_>>());
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Determinism {
+    inner: Arc<Mutex<State>>,
+}
+
+#[derive(Debug)]
+struct State {
+    /// The state of the random number generator.
+    rng: u64,
+    /// The time of the virtual clock.
+    clock: Duration,
+}
+
+impl Determinism {
+    /// Construct a new deterministic environment from the given seed, with
+    /// its virtual clock at zero.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(State {
+                rng: seed,
+                clock: Duration::ZERO,
+            })),
+        }
+    }
+
+    /// Get the deterministic environment of the virtual machine which is
+    /// currently running, if it's deterministic.
+    ///
+    /// This is intended to be called by native functions, which should use it
+    /// instead of any source of nondeterminism when it's present.
+    pub fn current() -> Option<Self> {
+        crate::runtime::env::determinism()
+    }
+
+    /// Generate the next random number.
+    ///
+    /// This uses [SplitMix64], which produces the same sequence on every
+    /// platform.
+    ///
+    /// [SplitMix64]: https://prng.di.unimi.it/splitmix64.c
+    pub fn next_u64(&self) -> u64 {
+        let mut state = self.lock();
+        state.rng = state.rng.wrapping_add(0x9e3779b97f4a7c15);
+
+        let mut z = state.rng;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// The current time of the virtual clock.
+    pub fn now(&self) -> Duration {
+        self.lock().clock
+    }
+
+    /// Advance the virtual clock by the given duration.
+    pub fn advance(&self, duration: Duration) {
+        let mut state = self.lock();
+        state.clock = state.clock.saturating_add(duration);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -0,0 +1,167 @@
+//! Structured disassembly of units.
+
+use crate::ast::Span;
+use crate::collections::HashMap;
+use crate::runtime::debug::DebugSignature;
+use crate::runtime::verify::jump_offset;
+use crate::runtime::{ConstValue, DebugLabel, Inst, InstTarget, Unit, UnitFn};
+use crate::{Hash, SourceId};
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+/// A single instruction in a unit, together with everything that's known
+/// about it.
+///
+/// See [Unit::instructions].
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct InstRecord<'a> {
+    /// The offset of the instruction in the unit.
+    pub ip: usize,
+    /// The instruction.
+    pub inst: Inst,
+    /// The function which starts at this instruction, if any.
+    pub function: Option<InstFunction<'a>>,
+    /// The label of this instruction, if any.
+    pub label: Option<&'a DebugLabel>,
+    /// The source the instruction was compiled from, if known.
+    pub source_id: Option<SourceId>,
+    /// The span in the source the instruction was compiled from, if known.
+    pub span: Option<Span>,
+    /// The comment associated with the instruction, if any.
+    pub comment: Option<&'a str>,
+    /// The offset this instruction jumps to, if it performs a jump.
+    pub jump: Option<usize>,
+    /// The constant the instruction refers to, if any.
+    pub constant: Option<InstConstant<'a>>,
+}
+
+/// A function which starts at a given instruction.
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct InstFunction<'a> {
+    /// The hash of the function.
+    pub hash: Hash,
+    /// The signature of the function, if debug information is available.
+    #[serde(serialize_with = "serialize_signature")]
+    pub signature: Option<&'a DebugSignature>,
+}
+
+/// Signatures are serialized the way they're displayed, since their items
+/// are stored in an internal format.
+fn serialize_signature<S>(
+    signature: &Option<&DebugSignature>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match signature {
+        Some(signature) => serializer.collect_str(signature),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// A constant which is referenced by an instruction through one of the
+/// static slots in the unit.
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub enum InstConstant<'a> {
+    /// A static string.
+    String(&'a str),
+    /// A static byte string.
+    Bytes(&'a [u8]),
+    /// A set of static object keys.
+    ObjectKeys(&'a [String]),
+    /// A static tuple.
+    Tuple(&'a [ConstValue]),
+}
+
+impl fmt::Display for InstConstant<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::String(string) => write!(f, "{:?}", string),
+            Self::Bytes(bytes) => write!(f, "{:?}", bytes),
+            Self::ObjectKeys(keys) => write!(f, "{{{}}}", keys.join(", ")),
+            Self::Tuple(values) => write!(f, "{:?}", values),
+        }
+    }
+}
+
+/// Disassemble the given instructions of a unit.
+pub(crate) fn disassemble<'a>(
+    unit: &'a Unit,
+    instructions: &'a [Inst],
+) -> impl Iterator<Item = InstRecord<'a>> + 'a {
+    let debug = unit.debug_info();
+
+    let mut functions = HashMap::new();
+
+    match debug {
+        Some(debug) => {
+            for (offset, hash) in &debug.functions_rev {
+                functions.insert(*offset, *hash);
+            }
+        }
+        None => {
+            for (hash, f) in unit.iter_functions() {
+                if let UnitFn::Offset { offset, .. } = f {
+                    functions.insert(*offset, hash);
+                }
+            }
+        }
+    }
+
+    instructions.iter().enumerate().map(move |(ip, inst)| {
+        let inst = *inst;
+        let debug_inst = debug.and_then(|d| d.instruction_at(ip));
+
+        let function = functions.get(&ip).map(|hash| InstFunction {
+            hash: *hash,
+            signature: debug.and_then(|d| d.functions.get(hash)),
+        });
+
+        let jump = jump_offset(&inst)
+            .and_then(|offset| (ip as isize).checked_add(offset)?.checked_add(1))
+            .and_then(|target| usize::try_from(target).ok());
+
+        InstRecord {
+            ip,
+            inst,
+            function,
+            label: debug_inst.and_then(|d| d.label.as_ref()),
+            source_id: debug_inst.map(|d| d.source_id),
+            span: debug_inst.map(|d| d.span),
+            comment: debug_inst.and_then(|d| d.comment.as_deref()),
+            jump,
+            constant: constant(unit, &inst),
+        }
+    })
+}
+
+/// The constant referenced by an instruction.
+fn constant<'a>(unit: &'a Unit, inst: &Inst) -> Option<InstConstant<'a>> {
+    match *inst {
+        Inst::String { slot }
+        | Inst::EqStaticString { slot }
+        | Inst::ObjectIndexGet { slot }
+        | Inst::ObjectIndexSet { slot }
+        | Inst::ObjectIndexGetAt { slot, .. }
+        | Inst::LoadGlobal { slot }
+        | Inst::Assign {
+            target: InstTarget::Field(slot),
+            ..
+        } => Some(InstConstant::String(
+            unit.lookup_string(slot).ok()?.as_str(),
+        )),
+        Inst::Bytes { slot } => Some(InstConstant::Bytes(unit.lookup_bytes(slot).ok()?)),
+        Inst::StaticTuple { slot } => Some(InstConstant::Tuple(unit.lookup_tuple(slot).ok()?)),
+        Inst::Object { slot }
+        | Inst::Struct { slot, .. }
+        | Inst::StructVariant { slot, .. }
+        | Inst::MatchObject { slot, .. } => {
+            Some(InstConstant::ObjectKeys(unit.lookup_object_keys(slot)?))
+        }
+        _ => None,
+    }
+}
@@ -7,65 +7,244 @@
 //!
 //! See the corresponding function for documentation.
 
-use crate::runtime::{RuntimeContext, Unit, VmError, VmErrorKind};
-use std::cell::Cell;
-use std::ptr;
+use crate::runtime::finalize::DropErrors;
+use crate::runtime::gc::Collector;
+use crate::runtime::{
+    native_span, CallHook, Capabilities, Deadline, Determinism, Extensions, Fuel, Memory,
+    NativeCall, Object, RuntimeContext, Shared, StackLimits, Unit, Value, Vm, VmError, VmErrorKind,
+};
+use crate::Hash;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
 use std::sync::Arc;
 
-thread_local! { static ENV: Cell<Env> = Cell::new(Env::null()) }
+thread_local! { static ENV: RefCell<Option<Rc<Env>>> = RefCell::new(None) }
 
 /// Call the given closure with access to the checked environment.
 pub(crate) fn with<F, T>(c: F) -> Result<T, VmError>
 where
     F: FnOnce(&Arc<RuntimeContext>, &Arc<Unit>) -> Result<T, VmError>,
 {
-    let env = ENV.with(|env| env.get());
-    let Env { context, unit } = env;
+    let env = current().ok_or(VmErrorKind::MissingInterfaceEnvironment)?;
+    c(&env.context, &env.unit)
+}
+
+/// Get the fuel of the virtual machine currently executing, so that virtual
+/// machines spawned by native functions share it.
+pub(crate) fn fuel() -> Option<Arc<Fuel>> {
+    current()?.fuel.clone()
+}
+
+/// Get the memory of the virtual machine currently executing, which values
+/// allocated are charged to.
+pub(crate) fn memory() -> Option<Arc<Memory>> {
+    current()?.memory.clone()
+}
+
+/// Get the cycle collector of the virtual machine currently executing, which
+/// values allocated are tracked by.
+pub(crate) fn collector() -> Option<Rc<Collector>> {
+    current()?.collector.clone()
+}
+
+/// Get the memory and the cycle collector of the virtual machine currently
+/// executing, which values allocated are charged to and tracked by.
+pub(crate) fn allocator() -> (Option<Arc<Memory>>, Option<Rc<Collector>>) {
+    match current() {
+        Some(env) => (env.memory.clone(), env.collector.clone()),
+        None => (None, None),
+    }
+}
+
+/// Get the deadline of the virtual machine currently executing, so that
+/// virtual machines spawned by native functions share it.
+pub(crate) fn deadline() -> Option<Arc<Deadline>> {
+    current()?.deadline.clone()
+}
+
+/// Get the deterministic environment of the virtual machine currently
+/// executing, if it's deterministic.
+pub(crate) fn determinism() -> Option<Determinism> {
+    current()?.determinism.clone()
+}
+
+/// Get the capabilities of the virtual machine currently executing, or every
+/// capability if none is executing.
+pub(crate) fn capabilities() -> Capabilities {
+    match current() {
+        Some(env) => env.capabilities,
+        None => Capabilities::all(),
+    }
+}
+
+/// Get the call hook of the virtual machine currently executing, so that
+/// virtual machines spawned by native functions share it.
+pub(crate) fn call_hook() -> Option<Arc<dyn CallHook>> {
+    current()?.call_hook.clone()
+}
+
+/// Get the extensions of the virtual machine currently executing, or an empty
+/// map if none is executing.
+pub(crate) fn extensions() -> Extensions {
+    match current() {
+        Some(env) => env.extensions.clone(),
+        None => Extensions::new(),
+    }
+}
+
+/// Get the global variables of the virtual machine currently executing, if
+/// any are defined.
+pub(crate) fn globals() -> Option<Shared<Object>> {
+    current()?.globals.clone()
+}
+
+/// Prepare to call the native function with the given hash and arguments
+/// outside of the virtual machine currently executing, like through a
+/// [Function][crate::runtime::Function] called by a native function.
+///
+/// This consults the call hook of the virtual machine, if any, and returns the
+/// span to wrap the call in.
+pub(crate) fn enter_native(hash: Hash, args: &[Value]) -> Result<tracing::Span, VmError> {
+    let env = match current() {
+        Some(env) => env,
+        None => return Ok(tracing::Span::none()),
+    };
 
-    if context.is_null() || unit.is_null() {
-        return Err(VmError::from(VmErrorKind::MissingInterfaceEnvironment));
+    if let Some(hook) = &env.call_hook {
+        hook.call(&NativeCall::new(&env.context, hash, args))?;
     }
 
-    // Safety: context and unit can only be registered publicly through
-    // [Guard], which makes sure that they are live for the duration of
-    // the registration.
-    c(unsafe { &*context }, unsafe { &*unit })
+    Ok(native_span(&env.context, hash))
+}
+
+/// Record an error raised by a drop function on the virtual machine currently
+/// executing, since there's no caller to return it to.
+pub(crate) fn drop_error(error: VmError) {
+    if let Some(env) = current() {
+        env.drop_errors.push(error);
+    }
+}
+
+/// Record the call depth and the size of the stack of the virtual machine
+/// currently executing as it's about to call a native function.
+pub(crate) fn set_position(depth: usize, size: usize) {
+    ENV.with(|env| {
+        if let Some(env) = &*env.borrow() {
+            env.depth.set(depth);
+            env.size.set(size);
+        }
+    });
+}
+
+/// Get the stack limits of a virtual machine spawned by a native function,
+/// which are what remains of the limits of the virtual machine currently
+/// executing.
+///
+/// The native function counts as one call, which makes sure that recursion
+/// through native functions is caught as well.
+pub(crate) fn limits() -> Result<StackLimits, VmError> {
+    let env = match current() {
+        Some(env) => env,
+        None => return Ok(StackLimits::new()),
+    };
+
+    let depth = env.depth.get() + 1;
+    let size = env.size.get();
+    let limits = env.limits;
+
+    let exceeded = matches!(limits.call_depth, Some(limit) if depth > limit)
+        || matches!(limits.stack_size, Some(limit) if size > limit);
+
+    if exceeded {
+        return Err(VmError::from(VmErrorKind::StackOverflow { depth, size }));
+    }
+
+    Ok(StackLimits {
+        call_depth: limits.call_depth.map(|limit| limit - depth),
+        stack_size: limits.stack_size.map(|limit| limit - size),
+    })
+}
+
+/// Access the environment of the virtual machine currently executing.
+fn current() -> Option<Rc<Env>> {
+    ENV.with(|env| env.borrow().clone())
 }
 
 pub(crate) struct Guard {
-    old: Env,
+    env: Rc<Env>,
+    old: Option<Rc<Env>>,
 }
 
 impl Guard {
-    /// Construct a new environment guard with the given context and unit.
-    ///
-    /// # Safety
+    /// Construct a new environment guard for the given virtual machine.
     ///
-    /// The returned guard must be dropped before the pointed to elements are.
-    pub(crate) fn new(context: *const Arc<RuntimeContext>, unit: *const Arc<Unit>) -> Guard {
-        let old = ENV.with(|e| e.replace(Env { context, unit }));
+    /// Everything native functions can access is copied out of the virtual
+    /// machine, so the guard doesn't borrow it.
+    pub(crate) fn new(vm: &Vm) -> Guard {
+        let env = Rc::new(Env {
+            context: vm.context().clone(),
+            unit: vm.unit().clone(),
+            fuel: vm.fuel.clone(),
+            memory: vm.memory.clone(),
+            collector: vm.collector.clone(),
+            deadline: vm.deadline.clone(),
+            determinism: vm.determinism.clone(),
+            capabilities: vm.capabilities,
+            call_hook: vm.call_hook.clone(),
+            extensions: vm.extensions.clone(),
+            globals: vm.globals.clone(),
+            limits: vm.limits,
+            depth: Cell::new(vm.call_frames().len()),
+            size: Cell::new(vm.stack().len()),
+            drop_errors: DropErrors::new(),
+        });
 
-        Guard { old }
+        let old = ENV.with(|e| e.replace(Some(env.clone())));
+        Guard { env, old }
+    }
+
+    /// Restore the previous environment, moving errors raised by drop
+    /// functions over to `errors` unless another virtual machine was
+    /// executing.
+    pub(crate) fn exit(self, errors: &DropErrors) {
+        if self.old.is_none() {
+            errors.append(&self.env.drop_errors);
+        }
     }
 }
 
 impl Drop for Guard {
     fn drop(&mut self) {
-        ENV.with(|e| e.set(self.old));
+        ENV.with(|e| e.replace(self.old.clone()));
+
+        // NB: errors raised by drop functions while a virtual machine spawned
+        // by another one was running are handed over to the one that spawned
+        // it, since that's the one the caller has access to.
+        if let Some(old) = &self.old {
+            old.drop_errors.append(&self.env.drop_errors);
+        }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+/// Everything native functions can access from the virtual machine currently
+/// executing.
 struct Env {
-    context: *const Arc<RuntimeContext>,
-    unit: *const Arc<Unit>,
-}
-
-impl Env {
-    const fn null() -> Self {
-        Self {
-            context: ptr::null(),
-            unit: ptr::null(),
-        }
-    }
+    context: Arc<RuntimeContext>,
+    unit: Arc<Unit>,
+    fuel: Option<Arc<Fuel>>,
+    memory: Option<Arc<Memory>>,
+    collector: Option<Rc<Collector>>,
+    deadline: Option<Arc<Deadline>>,
+    determinism: Option<Determinism>,
+    capabilities: Capabilities,
+    call_hook: Option<Arc<dyn CallHook>>,
+    extensions: Extensions,
+    globals: Option<Shared<Object>>,
+    limits: StackLimits,
+    /// The call depth as of the last native function called.
+    depth: Cell<usize>,
+    /// The size of the stack as of the last native function called.
+    size: Cell<usize>,
+    /// Errors raised by drop functions while the virtual machine is executing.
+    drop_errors: DropErrors,
 }
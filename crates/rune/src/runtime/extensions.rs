@@ -0,0 +1,142 @@
+//! Ambient host data which native functions can access.
+
+use crate::collections::HashMap;
+use std::any::{Any, TypeId};
+use std::fmt;
+use std::sync::Arc;
+
+/// A map of values keyed by their type, which the host associates with a
+/// virtual machine and which native functions can access while they're being
+/// called.
+///
+/// This can be used to provide things like the context of the current
+/// request, a logger or a database pool to native functions without passing
+/// them through every script function. The extensions of a virtual machine are
+/// set up with [Vm::extensions_mut][crate::Vm::extensions_mut], and are shared
+/// with the virtual machines spawned to run closures, generators, streams and
+/// async functions.
+///
+/// Cloning the map is cheap, since its contents are reference counted and
+/// only copied when a clone is modified.
+///
+/// # Examples
+///
+/// ```
+/// use rune::{Context, FromValue, Module, Vm};
+/// use rune::runtime::Extensions;
+/// use std::sync::Arc;
+///
+/// struct User(String);
+///
+/// fn current_user() -> Option<String> {
+///     Extensions::current().get::<User>().map(|user| user.0.clone())
+/// }
+///
+/// # fn main() -> rune::Result<()> {
+/// let mut module = Module::with_crate("request");
+/// module.function(&["user"], current_user)?;
+///
+/// let mut context = Context::with_default_modules()?;
+/// context.install(&module)?;
+///
+/// let mut sources = rune::sources! {
+///     entry => {
+///         pub fn main() {
+///             request::user()
+///         }
+///     }
+/// };
+///
+/// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+/// let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+///
+/// assert_eq!(Option::<String>::from_value(vm.call(&["main"], ())?)?, None);
+///
+/// vm.extensions_mut().insert(User(String::from("john")));
+/// let output = Option::<String>::from_value(vm.call(&["main"], ())?)?;
+/// assert_eq!(output.as_deref(), Some("john"));
+/// # Ok(()) }
+/// ```
+#[derive(Default, Clone)]
+pub struct Extensions {
+    map: Option<Arc<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>>,
+}
+
+impl Extensions {
+    /// Construct an empty map of extensions.
+    pub const fn new() -> Self {
+        Self { map: None }
+    }
+
+    /// Get the extensions of the virtual machine which is currently running.
+    ///
+    /// This is empty when no virtual machine is running.
+    pub fn current() -> Self {
+        crate::runtime::env::extensions()
+    }
+
+    /// Insert a value, returning the value of the same type which it
+    /// replaced, if any.
+    pub fn insert<T>(&mut self, value: T) -> Option<Arc<T>>
+    where
+        T: Any + Send + Sync,
+    {
+        let map = Arc::make_mut(self.map.get_or_insert_with(Default::default));
+        let old = map.insert(TypeId::of::<T>(), Arc::new(value))?;
+        old.downcast().ok()
+    }
+
+    /// Get a reference to the value of the given type, if present.
+    pub fn get<T>(&self) -> Option<&T>
+    where
+        T: Any + Send + Sync,
+    {
+        self.map.as_ref()?.get(&TypeId::of::<T>())?.downcast_ref()
+    }
+
+    /// Remove the value of the given type, returning it if it was present.
+    pub fn remove<T>(&mut self) -> Option<Arc<T>>
+    where
+        T: Any + Send + Sync,
+    {
+        let map = self.map.as_mut()?;
+
+        if !map.contains_key(&TypeId::of::<T>()) {
+            return None;
+        }
+
+        let old = Arc::make_mut(map).remove(&TypeId::of::<T>())?;
+        old.downcast().ok()
+    }
+
+    /// Test if a value of the given type is present.
+    pub fn contains<T>(&self) -> bool
+    where
+        T: Any + Send + Sync,
+    {
+        self.get::<T>().is_some()
+    }
+
+    /// Get the number of values in the map.
+    pub fn len(&self) -> usize {
+        self.map.as_ref().map(|map| map.len()).unwrap_or_default()
+    }
+
+    /// Test if the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove all values from the map.
+    pub fn clear(&mut self) {
+        self.map = None;
+    }
+}
+
+impl fmt::Debug for Extensions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Extensions")
+            .field("len", &self.len())
+            .finish()
+    }
+}
@@ -0,0 +1,87 @@
+//! Calling the [Protocol::DROP] function of script types once the last
+//! strong reference to a value of the type goes away.
+
+use crate::runtime::{EnvProtocolCaller, Protocol, ProtocolCaller, Value, VmError};
+use std::cell::RefCell;
+use std::fmt;
+use std::mem;
+
+/// Call the drop function of the given value, if its type has one.
+///
+/// Returns `false` if there's no drop function, or if no virtual machine is
+/// running that the function can be looked up through.
+pub(crate) fn call_drop(value: Value) -> Result<bool, VmError> {
+    if !EnvProtocolCaller.has_protocol_fn(Protocol::DROP, &value)? {
+        return Ok(false);
+    }
+
+    EnvProtocolCaller.call_protocol_fn(Protocol::DROP, value, ())?;
+    Ok(true)
+}
+
+/// Call the drop function of the given value right away, making sure that
+/// it's not called again once the value goes away.
+pub(crate) fn finalize(value: &Value) -> Result<(), VmError> {
+    if take_finalizer(value) {
+        call_drop(value.clone())?;
+    }
+
+    Ok(())
+}
+
+/// Take the finalizer of the given value so that its drop function isn't
+/// called, returning `true` if it had one.
+pub(crate) fn take_finalizer(value: &Value) -> bool {
+    match value {
+        Value::UnitStruct(data) => data.take_finalizer(),
+        Value::TupleStruct(data) => data.take_finalizer(),
+        Value::Struct(data) => data.take_finalizer(),
+        Value::Variant(data) => data.take_finalizer(),
+        _ => false,
+    }
+}
+
+/// Errors raised by drop functions called while a virtual machine was running.
+///
+/// Errors belong to the virtual machine which recorded them, so cloning it
+/// doesn't clone them.
+#[derive(Default)]
+pub(crate) struct DropErrors {
+    errors: RefCell<Vec<VmError>>,
+}
+
+impl DropErrors {
+    pub(crate) const fn new() -> Self {
+        Self {
+            errors: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Record an error.
+    pub(crate) fn push(&self, error: VmError) {
+        self.errors.borrow_mut().push(error);
+    }
+
+    /// Move all errors recorded by `other` over to this.
+    pub(crate) fn append(&self, other: &Self) {
+        let mut errors = other.take();
+        self.errors.borrow_mut().append(&mut errors);
+    }
+
+    /// Take all errors recorded.
+    pub(crate) fn take(&self) -> Vec<VmError> {
+        mem::take(&mut *self.errors.borrow_mut())
+    }
+}
+
+impl Clone for DropErrors {
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for DropErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.errors.borrow().iter()).finish()
+    }
+}
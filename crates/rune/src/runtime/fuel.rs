@@ -0,0 +1,41 @@
+//! Fuel which limits the number of instructions a virtual machine executes.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Fuel shared between a virtual machine and the virtual machines it spawns.
+#[derive(Debug)]
+pub(crate) struct Fuel {
+    remaining: AtomicU64,
+}
+
+impl Fuel {
+    pub(crate) fn new(remaining: u64) -> Self {
+        Self {
+            remaining: AtomicU64::new(remaining),
+        }
+    }
+
+    /// Take one unit of fuel, returning `false` if there is none left.
+    #[inline]
+    pub(crate) fn take(&self) -> bool {
+        self.remaining
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |v| v.checked_sub(1))
+            .is_ok()
+    }
+
+    pub(crate) fn get(&self) -> u64 {
+        self.remaining.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set(&self, remaining: u64) {
+        self.remaining.store(remaining, Ordering::Relaxed);
+    }
+
+    pub(crate) fn add(&self, fuel: u64) {
+        let _ = self
+            .remaining
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |v| {
+                Some(v.saturating_add(fuel))
+            });
+    }
+}
@@ -1,12 +1,14 @@
+use crate::runtime::snapshot::{FunctionSnapshot, SnapshotErrorKind};
 use crate::runtime::{
-    Args, Call, ConstValue, FromValue, FunctionHandler, RawRef, Ref, Rtti, RuntimeContext, Shared,
-    Stack, Tuple, Unit, UnsafeFromValue, Value, VariantRtti, Vm, VmCall, VmError, VmErrorKind,
-    VmHalt,
+    catch_native, native_span, Args, Call, ConstValue, FromValue, FunctionHandler, RawRef, Ref,
+    Rtti, RuntimeContext, Shared, SnapshotError, Stack, Tuple, Unit, UnitFn, UnsafeFromValue,
+    Value, VariantRtti, Vm, VmCall, VmError, VmErrorKind, VmHalt,
 };
 use crate::shared::AssertSend;
 use crate::Hash;
 use std::fmt;
 use std::future::Future;
+use std::mem;
 use std::sync::Arc;
 
 /// A callable non-sync function.
@@ -130,6 +132,147 @@ impl Function {
         Self(FunctionImpl::from_tuple_variant(rtti, args))
     }
 
+    /// Look up the function with the given hash, first in the unit and then
+    /// in the context.
+    ///
+    /// Returns `None` if no such function exists.
+    pub(crate) fn lookup(
+        context: &Arc<RuntimeContext>,
+        unit: &Arc<Unit>,
+        hash: Hash,
+    ) -> Result<Option<Self>, VmError> {
+        let info = match unit.function(hash) {
+            Some(info) => info,
+            None => {
+                return Ok(context
+                    .function(hash)
+                    .map(|handler| Self::from_handler(handler.clone(), hash)));
+            }
+        };
+
+        let function = match info {
+            UnitFn::Offset { offset, call, args } => {
+                Self::from_offset(context.clone(), unit.clone(), offset, call, args, hash)
+            }
+            UnitFn::UnitStruct { hash } => {
+                let rtti = unit
+                    .lookup_rtti(hash)
+                    .ok_or(VmErrorKind::MissingRtti { hash })?;
+
+                Self::from_unit_struct(rtti.clone())
+            }
+            UnitFn::TupleStruct { hash, args } => {
+                let rtti = unit
+                    .lookup_rtti(hash)
+                    .ok_or(VmErrorKind::MissingRtti { hash })?;
+
+                Self::from_tuple_struct(rtti.clone(), args)
+            }
+            UnitFn::UnitVariant { hash } => {
+                let rtti = unit
+                    .lookup_variant_rtti(hash)
+                    .ok_or(VmErrorKind::MissingVariantRtti { hash })?;
+
+                Self::from_unit_variant(rtti.clone())
+            }
+            UnitFn::TupleVariant { hash, args } => {
+                let rtti = unit
+                    .lookup_variant_rtti(hash)
+                    .ok_or(VmErrorKind::MissingVariantRtti { hash })?;
+
+                Self::from_tuple_variant(rtti.clone(), args)
+            }
+        };
+
+        Ok(Some(function))
+    }
+
+    /// Access the environment captured by the function, which is empty unless
+    /// it's a closure.
+    pub(crate) fn environment(&self) -> &[Value] {
+        match &self.0.inner {
+            Inner::FnClosureOffset(closure) => &closure.environment,
+            _ => &[],
+        }
+    }
+
+    /// Take the environment captured by the function, leaving it empty.
+    pub(crate) fn take_environment(&mut self) -> Box<[Value]> {
+        match &mut self.0.inner {
+            Inner::FnClosureOffset(closure) => mem::take(&mut closure.environment),
+            _ => Box::default(),
+        }
+    }
+
+    /// Describe the function for a snapshot, encoding the environment of
+    /// closures with the given function.
+    ///
+    /// Only functions which belong to the given unit or to the context can be
+    /// described.
+    pub(crate) fn snapshot<V>(
+        &self,
+        unit: &Arc<Unit>,
+        mut encode: impl FnMut(&Value) -> Result<V, SnapshotError>,
+    ) -> Result<FunctionSnapshot<V>, SnapshotError> {
+        let fn_offset = |fn_offset: &FnOffset| {
+            if !Arc::ptr_eq(&fn_offset.unit, unit) {
+                return Err(SnapshotError::from(SnapshotErrorKind::ForeignUnit));
+            }
+
+            Ok((
+                fn_offset.offset,
+                fn_offset.call,
+                fn_offset.args,
+                fn_offset.hash,
+            ))
+        };
+
+        Ok(match &self.0.inner {
+            Inner::FnHandler(handler) => FunctionSnapshot::Handler { hash: handler.hash },
+            Inner::FnOffset(offset) => {
+                let (offset, call, args, hash) = fn_offset(offset)?;
+
+                FunctionSnapshot::Offset {
+                    offset,
+                    call,
+                    args,
+                    hash,
+                }
+            }
+            Inner::FnClosureOffset(closure) => {
+                let (offset, call, args, hash) = fn_offset(&closure.fn_offset)?;
+
+                let environment = closure
+                    .environment
+                    .iter()
+                    .map(&mut encode)
+                    .collect::<Result<_, _>>()?;
+
+                FunctionSnapshot::Closure {
+                    offset,
+                    call,
+                    args,
+                    environment,
+                    hash,
+                }
+            }
+            Inner::FnUnitStruct(func) => FunctionSnapshot::UnitStruct {
+                hash: func.rtti.hash,
+            },
+            Inner::FnTupleStruct(func) => FunctionSnapshot::TupleStruct {
+                hash: func.rtti.hash,
+                args: func.args,
+            },
+            Inner::FnUnitVariant(func) => FunctionSnapshot::UnitVariant {
+                hash: func.rtti.hash,
+            },
+            Inner::FnTupleVariant(func) => FunctionSnapshot::TupleVariant {
+                hash: func.rtti.hash,
+                args: func.args,
+            },
+        })
+    }
+
     /// Type [Hash][struct@Hash] of the underlying function.
     ///
     /// # Examples
@@ -377,7 +520,10 @@ where
                 let arg_count = args.count();
                 let mut stack = Stack::with_capacity(arg_count);
                 args.into_stack(&mut stack)?;
-                (handler.handler)(&mut stack, arg_count)?;
+                let span =
+                    crate::runtime::env::enter_native(handler.hash, stack.peek_n(arg_count)?)?;
+                let _span = span.entered();
+                catch_native(|| (handler.handler)(&mut stack, arg_count))?;
                 stack.pop()?
             }
             Inner::FnOffset(fn_offset) => fn_offset.call(args, ())?,
@@ -443,7 +589,10 @@ where
     pub(crate) fn call_with_vm(&self, vm: &mut Vm, args: usize) -> Result<Option<VmHalt>, VmError> {
         let reason = match &self.inner {
             Inner::FnHandler(handler) => {
-                (handler.handler)(vm.stack_mut(), args)?;
+                vm.enter_native(handler.hash, args)?;
+                let _span = native_span(vm.context(), handler.hash).entered();
+                let stack = vm.stack_mut();
+                catch_native(|| (handler.handler)(stack, args))?;
                 None
             }
             Inner::FnOffset(fn_offset) => {
@@ -721,6 +870,16 @@ impl FnOffset {
         let mut vm = Vm::new(self.context.clone(), self.unit.clone());
 
         vm.set_ip(self.offset);
+        vm.fuel = crate::runtime::env::fuel();
+        vm.memory = crate::runtime::env::memory();
+        vm.limits = crate::runtime::env::limits()?;
+        vm.deadline = crate::runtime::env::deadline();
+        vm.determinism = crate::runtime::env::determinism();
+        vm.capabilities = crate::runtime::env::capabilities();
+        vm.call_hook = crate::runtime::env::call_hook();
+        vm.extensions = crate::runtime::env::extensions();
+        vm.globals = crate::runtime::env::globals();
+        vm.collector = crate::runtime::env::collector();
         args.into_stack(vm.stack_mut())?;
         extra.into_stack(vm.stack_mut())?;
 
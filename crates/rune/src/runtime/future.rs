@@ -1,6 +1,7 @@
 use crate::compile::{InstallWith, Named};
 use crate::runtime::{
-    FromValue, Mut, RawMut, RawRef, RawStr, Ref, Shared, ToValue, UnsafeFromValue, Value, VmError,
+    catch_native, FromValue, Mut, RawMut, RawRef, RawStr, Ref, Shared, ToValue, UnsafeFromValue,
+    Value, Vm, VmError,
 };
 use pin_project::pin_project;
 use std::fmt;
@@ -16,6 +17,9 @@ type DynFuture = dyn future::Future<Output = Result<Value, VmError>> + 'static;
 /// the virtual machine that created it.
 pub struct Future {
     future: Option<Pin<Box<DynFuture>>>,
+    /// The virtual machine of a call to an async function which hasn't been
+    /// polled yet. Awaiting it runs it as part of the awaiting execution.
+    vm: Option<Box<Vm>>,
 }
 
 impl Future {
@@ -30,14 +34,29 @@ impl Future {
                 let value = future.await?;
                 value.to_value()
             })),
+            vm: None,
         }
     }
 
+    /// Construct a future which runs the given virtual machine to completion.
+    pub(crate) fn from_vm(vm: Vm) -> Self {
+        Self {
+            future: None,
+            vm: Some(Box::new(vm)),
+        }
+    }
+
+    /// Take the virtual machine of the future if it hasn't been polled yet, so
+    /// that it can be run as part of an execution instead.
+    pub(crate) fn take_vm(&mut self) -> Option<Vm> {
+        Some(*self.vm.take()?)
+    }
+
     /// Check if future is completed.
     ///
     /// This will prevent it from being used in a select expression.
     pub fn is_completed(&self) -> bool {
-        self.future.is_none()
+        self.future.is_none() && self.vm.is_none()
     }
 }
 
@@ -46,11 +65,17 @@ impl future::Future for Future {
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<Value, VmError>> {
         let this = self.get_mut();
+
+        if let Some(vm) = this.vm.take() {
+            this.future = Some(Box::pin(vm.async_complete()));
+        }
+
         let mut future = this.future.take().expect("futures can only be polled once");
 
-        match future.as_mut().poll(cx) {
-            Poll::Ready(result) => Poll::Ready(result),
-            Poll::Pending => {
+        match catch_native(|| Ok(future.as_mut().poll(cx))) {
+            Err(error) => Poll::Ready(Err(error)),
+            Ok(Poll::Ready(result)) => Poll::Ready(result),
+            Ok(Poll::Pending) => {
                 this.future = Some(future);
                 Poll::Pending
             }
@@ -61,7 +86,7 @@ impl future::Future for Future {
 impl fmt::Debug for Future {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt.debug_struct("Future")
-            .field("is_completed", &self.future.is_none())
+            .field("is_completed", &self.is_completed())
             .finish()
     }
 }
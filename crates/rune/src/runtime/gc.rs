@@ -0,0 +1,344 @@
+//! Collection of reference cycles among shared values.
+//!
+//! Shared values are reference counted, so values which refer to each other
+//! are never freed on their own. Values allocated by a virtual machine with
+//! cycle collection enabled are tracked by a [Collector], which can find the
+//! ones that are only kept alive by references from other tracked values
+//! through trial deletion and break them apart.
+
+use crate::collections::HashMap;
+use crate::runtime::shared::{RawShared, RawSharedVTable};
+use crate::runtime::{
+    Function, GeneratorState, Object, Range, Struct, Tuple, TupleStruct, Value, Variant,
+    VariantData, Vec,
+};
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::fmt;
+use std::mem;
+use std::vec;
+
+/// Values which can hold onto other values, and can therefore be part of a
+/// reference cycle.
+pub(crate) trait Trace: 'static {
+    /// Visit every value held directly.
+    fn trace(&self, visit: &mut dyn FnMut(&Value));
+
+    /// Move every value held directly out into `out`, breaking any cycle
+    /// passing through this value.
+    fn clear(&mut self, out: &mut vec::Vec<Value>);
+}
+
+/// Get the vtable used to trace a shared value of the given type, if it's a
+/// type that can hold onto other values.
+pub(crate) fn vtable<T>() -> Option<&'static RawSharedVTable>
+where
+    T: 'static,
+{
+    macro_rules! traced {
+        ($($ty:ty),* $(,)?) => {
+            $(
+                if TypeId::of::<T>() == TypeId::of::<$ty>() {
+                    return Some(RawShared::vtable::<$ty>());
+                }
+            )*
+        };
+    }
+
+    traced! {
+        Vec,
+        Tuple,
+        Object,
+        Range,
+        Option<Value>,
+        Result<Value, Value>,
+        GeneratorState,
+        TupleStruct,
+        Struct,
+        Variant,
+        Function,
+    };
+
+    None
+}
+
+/// Tracks the shared values allocated by a virtual machine, so that reference
+/// cycles among them can be collected.
+#[derive(Default)]
+pub(crate) struct Collector {
+    tracked: RefCell<HashMap<usize, RawShared>>,
+}
+
+impl Collector {
+    /// Start tracking the given shared value.
+    pub(crate) fn track(&self, raw: RawShared) {
+        self.tracked.borrow_mut().insert(raw.addr(), raw);
+    }
+
+    /// Stop tracking the shared value at the given address, since it's being
+    /// freed.
+    pub(crate) fn untrack(&self, addr: usize) {
+        self.tracked.borrow_mut().remove(&addr);
+    }
+
+    /// The number of values currently tracked.
+    pub(crate) fn len(&self) -> usize {
+        self.tracked.borrow().len()
+    }
+
+    /// Free every tracked value which is only reachable through other tracked
+    /// values, returning the number of values freed.
+    pub(crate) fn collect(&self) -> usize {
+        let entries = self
+            .tracked
+            .borrow()
+            .values()
+            .copied()
+            .collect::<vec::Vec<_>>();
+
+        // NB: a strong reference is held to every entry for the duration of
+        // the collection, so that nothing is freed while values are being
+        // cleared below.
+        for entry in &entries {
+            // Safety: tracked values are untracked before they're freed.
+            unsafe { entry.inc() };
+        }
+
+        let index = entries
+            .iter()
+            .enumerate()
+            .map(|(n, entry)| (entry.addr(), n))
+            .collect::<HashMap<_, _>>();
+
+        let mut internal = vec![0usize; entries.len()];
+        let mut edges = vec![vec::Vec::new(); entries.len()];
+        let mut live = vec![false; entries.len()];
+
+        for (n, entry) in entries.iter().enumerate() {
+            let mut visit = |value: &Value| {
+                if let Some(to) = addr(value).and_then(|addr| index.get(&addr)) {
+                    internal[*to] += 1;
+                    edges[n].push(*to);
+                }
+            };
+
+            // Values which can't be accessed right now are assumed to be live,
+            // and so is everything they refer to.
+            //
+            // Safety: we hold a strong reference to the value.
+            if !unsafe { entry.trace(&mut visit) } {
+                live[n] = true;
+            }
+        }
+
+        let mut queue = vec::Vec::new();
+
+        for (n, entry) in entries.iter().enumerate() {
+            // NB: one reference is the one we're holding ourselves.
+            //
+            // Safety: we hold a strong reference to the value.
+            let external = unsafe { entry.count() } - 1;
+
+            if live[n] || external > internal[n] {
+                live[n] = true;
+                queue.push(n);
+            }
+        }
+
+        while let Some(n) = queue.pop() {
+            for &to in &edges[n] {
+                if !mem::replace(&mut live[to], true) {
+                    queue.push(to);
+                }
+            }
+        }
+
+        let mut garbage = 0;
+        let mut cleared = vec::Vec::new();
+
+        for (n, entry) in entries.iter().enumerate() {
+            if live[n] {
+                continue;
+            }
+
+            // NB: drop functions aren't called for garbage, since it would be
+            // called with values which are being torn apart.
+            //
+            // Safety: we hold a strong reference to the value.
+            unsafe { entry.take_finalizer() };
+        }
+
+        for (n, entry) in entries.iter().enumerate() {
+            // Safety: we hold a strong reference to the value.
+            if !live[n] && unsafe { entry.clear(&mut cleared) } {
+                garbage += 1;
+            }
+        }
+
+        // NB: the values held by garbage are dropped once they've all been
+        // cleared, since dropping them might run arbitrary code.
+        drop(cleared);
+
+        for entry in entries {
+            // Safety: this releases the reference acquired above, which frees
+            // the garbage since nothing else refers to it anymore.
+            unsafe { entry.dec() };
+        }
+
+        garbage
+    }
+}
+
+impl fmt::Debug for Collector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Collector")
+            .field("tracked", &self.len())
+            .finish()
+    }
+}
+
+/// Get the address of the shared value held by the given value, if any.
+fn addr(value: &Value) -> Option<usize> {
+    Some(match value {
+        Value::Vec(vec) => vec.addr(),
+        Value::Tuple(tuple) => tuple.addr(),
+        Value::Object(object) => object.addr(),
+        Value::Range(range) => range.addr(),
+        Value::Option(option) => option.addr(),
+        Value::Result(result) => result.addr(),
+        Value::GeneratorState(state) => state.addr(),
+        Value::TupleStruct(data) => data.addr(),
+        Value::Struct(data) => data.addr(),
+        Value::Variant(variant) => variant.addr(),
+        Value::Function(function) => function.addr(),
+        _ => return None,
+    })
+}
+
+impl Trace for Vec {
+    fn trace(&self, visit: &mut dyn FnMut(&Value)) {
+        self.iter().for_each(visit);
+    }
+
+    fn clear(&mut self, out: &mut vec::Vec<Value>) {
+        out.extend(mem::replace(self, Vec::new()).into_inner());
+    }
+}
+
+impl Trace for Tuple {
+    fn trace(&self, visit: &mut dyn FnMut(&Value)) {
+        self.iter().for_each(visit);
+    }
+
+    fn clear(&mut self, out: &mut vec::Vec<Value>) {
+        let tuple = mem::replace(self, Tuple::from(vec::Vec::new()));
+        out.extend(vec::Vec::from(tuple.into_inner()));
+    }
+}
+
+impl Trace for Object {
+    fn trace(&self, visit: &mut dyn FnMut(&Value)) {
+        self.values().for_each(visit);
+    }
+
+    fn clear(&mut self, out: &mut vec::Vec<Value>) {
+        out.extend(mem::take(self).into_inner().into_values());
+    }
+}
+
+impl Trace for Range {
+    fn trace(&self, visit: &mut dyn FnMut(&Value)) {
+        self.start.iter().chain(self.end.iter()).for_each(visit);
+    }
+
+    fn clear(&mut self, out: &mut vec::Vec<Value>) {
+        out.extend(self.start.take());
+        out.extend(self.end.take());
+    }
+}
+
+impl Trace for Option<Value> {
+    fn trace(&self, visit: &mut dyn FnMut(&Value)) {
+        self.iter().for_each(visit);
+    }
+
+    fn clear(&mut self, out: &mut vec::Vec<Value>) {
+        out.extend(self.take());
+    }
+}
+
+impl Trace for Result<Value, Value> {
+    fn trace(&self, visit: &mut dyn FnMut(&Value)) {
+        match self {
+            Ok(value) | Err(value) => visit(value),
+        }
+    }
+
+    fn clear(&mut self, out: &mut vec::Vec<Value>) {
+        match mem::replace(self, Ok(Value::Unit)) {
+            Ok(value) | Err(value) => out.push(value),
+        }
+    }
+}
+
+impl Trace for GeneratorState {
+    fn trace(&self, visit: &mut dyn FnMut(&Value)) {
+        match self {
+            GeneratorState::Yielded(value) | GeneratorState::Complete(value) => visit(value),
+        }
+    }
+
+    fn clear(&mut self, out: &mut vec::Vec<Value>) {
+        match mem::replace(self, GeneratorState::Complete(Value::Unit)) {
+            GeneratorState::Yielded(value) | GeneratorState::Complete(value) => out.push(value),
+        }
+    }
+}
+
+impl Trace for TupleStruct {
+    fn trace(&self, visit: &mut dyn FnMut(&Value)) {
+        Trace::trace(&self.data, visit);
+    }
+
+    fn clear(&mut self, out: &mut vec::Vec<Value>) {
+        Trace::clear(&mut self.data, out);
+    }
+}
+
+impl Trace for Struct {
+    fn trace(&self, visit: &mut dyn FnMut(&Value)) {
+        Trace::trace(&self.data, visit);
+    }
+
+    fn clear(&mut self, out: &mut vec::Vec<Value>) {
+        Trace::clear(&mut self.data, out);
+    }
+}
+
+impl Trace for Variant {
+    fn trace(&self, visit: &mut dyn FnMut(&Value)) {
+        match &self.data {
+            VariantData::Unit => (),
+            VariantData::Tuple(tuple) => Trace::trace(tuple, visit),
+            VariantData::Struct(object) => Trace::trace(object, visit),
+        }
+    }
+
+    fn clear(&mut self, out: &mut vec::Vec<Value>) {
+        match &mut self.data {
+            VariantData::Unit => (),
+            VariantData::Tuple(tuple) => Trace::clear(tuple, out),
+            VariantData::Struct(object) => Trace::clear(object, out),
+        }
+    }
+}
+
+impl Trace for Function {
+    fn trace(&self, visit: &mut dyn FnMut(&Value)) {
+        self.environment().iter().for_each(visit);
+    }
+
+    fn clear(&mut self, out: &mut vec::Vec<Value>) {
+        out.extend(vec::Vec::from(self.take_environment()));
+    }
+}
@@ -11,7 +11,7 @@ pub struct Generator<T>
 where
     T: AsMut<Vm>,
 {
-    execution: Option<VmExecution<T>>,
+    pub(crate) execution: Option<VmExecution<T>>,
 }
 
 impl<T> Generator<T>
@@ -81,6 +81,38 @@ impl Generator<Vm> {
     }
 }
 
+/// Consume the generator as a Rust iterator over the values it yields.
+///
+/// ```
+/// use rune::{FromValue, Vm};
+/// use std::sync::Arc;
+///
+/// # fn main() -> rune::Result<()> {
+/// let mut sources = rune::sources! {
+///     entry => {
+///         pub fn main() {
+///             yield 1;
+///             yield 2;
+///             yield 3;
+///             yield 4;
+///         }
+///     }
+/// };
+///
+/// let unit = rune::prepare(&mut sources).build()?;
+///
+/// let mut vm = Vm::without_runtime(Arc::new(unit));
+/// let generator = vm.execute(&["main"], ())?.into_generator()?.into_owned();
+///
+/// let values = generator
+///     .into_iter()
+///     .map(|value| i64::from_value(value?))
+///     .filter(|value| !matches!(value, Ok(n) if n % 2 == 0))
+///     .collect::<Result<Vec<_>, _>>()?;
+///
+/// assert_eq!(values, [1, 3]);
+/// # Ok(()) }
+/// ```
 impl IntoIterator for Generator<Vm> {
     type Item = Result<Value, VmError>;
     type IntoIter = GeneratorIterator;
@@ -90,6 +122,10 @@ impl IntoIterator for Generator<Vm> {
     }
 }
 
+/// An iterator over the values yielded by a [Generator].
+///
+/// The iterator ends when the generator completes, or after it has produced
+/// an error.
 pub struct GeneratorIterator {
     generator: Generator<Vm>,
 }
@@ -98,7 +134,25 @@ impl std::iter::Iterator for GeneratorIterator {
     type Item = Result<Value, VmError>;
 
     fn next(&mut self) -> Option<Result<Value, VmError>> {
-        self.generator.next().transpose()
+        self.generator.execution.as_ref()?;
+
+        let result = self.generator.next();
+
+        if result.is_err() {
+            self.generator.execution = None;
+        }
+
+        result.transpose()
+    }
+}
+
+impl std::iter::FusedIterator for GeneratorIterator {}
+
+impl fmt::Debug for GeneratorIterator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GeneratorIterator")
+            .field("generator", &self.generator)
+            .finish()
     }
 }
 
@@ -1,4 +1,4 @@
-use crate::runtime::{Stack, UnsafeToValue, VmError};
+use crate::runtime::{Stack, UnsafeToValue, Value, VmError};
 
 /// Trait for converting arguments onto the stack.
 ///
@@ -55,3 +55,19 @@ macro_rules! impl_into_args {
 }
 
 repeat_macro!(impl_into_args);
+
+impl GuardedArgs for Vec<Value> {
+    type Guard = ();
+
+    unsafe fn unsafe_into_stack(self, stack: &mut Stack) -> Result<Self::Guard, VmError> {
+        for value in self {
+            stack.push(value);
+        }
+
+        Ok(())
+    }
+
+    fn count(&self) -> usize {
+        self.len()
+    }
+}
@@ -0,0 +1,100 @@
+//! Caches of the functions which instance function calls and field accesses
+//! resolve to, so that repeatedly executing the same instruction with the same
+//! type of value doesn't have to look the function up again.
+
+use crate::runtime::{Call, FunctionHandler};
+use crate::Hash;
+use std::fmt;
+use std::sync::Arc;
+
+/// The number of instructions which can be cached at the same time.
+/// Instructions are assigned an entry by their instruction pointer.
+const SIZE: usize = 256;
+
+/// The number of lookups to perform before the cache is allocated, so that
+/// short-lived virtual machines don't have to pay for it.
+const WARMUP: usize = 16;
+
+/// The function an instruction resolved to.
+#[derive(Clone)]
+pub(crate) enum Cached {
+    /// A function in the unit.
+    Offset {
+        offset: usize,
+        call: Call,
+        args: usize,
+    },
+    /// A native function.
+    Handler(Arc<FunctionHandler>),
+}
+
+#[derive(Clone)]
+struct Entry {
+    ip: usize,
+    type_hash: Hash,
+    cached: Cached,
+}
+
+/// A cache of resolved functions, keyed by the instruction pointer and the
+/// type of the value the function was resolved for.
+///
+/// Each instruction only caches the last type it was executed with.
+#[derive(Clone)]
+pub(crate) struct InlineCache {
+    lookups: usize,
+    entries: Option<Box<[Option<Entry>]>>,
+}
+
+impl InlineCache {
+    /// Construct a new empty cache.
+    pub(crate) const fn new() -> Self {
+        Self {
+            lookups: 0,
+            entries: None,
+        }
+    }
+
+    /// Get the function cached for the given instruction and type.
+    #[inline]
+    pub(crate) fn get(&mut self, ip: usize, type_hash: Hash) -> Option<&Cached> {
+        let entries = match &self.entries {
+            Some(entries) => entries,
+            None => {
+                self.lookups += 1;
+                return None;
+            }
+        };
+
+        match &entries[ip % SIZE] {
+            Some(entry) if entry.ip == ip && entry.type_hash == type_hash => Some(&entry.cached),
+            _ => None,
+        }
+    }
+
+    /// Cache the function resolved for the given instruction and type.
+    pub(crate) fn insert(&mut self, ip: usize, type_hash: Hash, cached: Cached) {
+        if self.entries.is_none() {
+            if self.lookups < WARMUP {
+                return;
+            }
+
+            self.entries = Some(vec![None; SIZE].into_boxed_slice());
+        }
+
+        if let Some(entries) = &mut self.entries {
+            entries[ip % SIZE] = Some(Entry {
+                ip,
+                type_hash,
+                cached,
+            });
+        }
+    }
+}
+
+impl fmt::Debug for InlineCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InlineCache")
+            .field("enabled", &self.entries.is_some())
+            .finish()
+    }
+}
@@ -1,4 +1,4 @@
-use crate::runtime::{FormatSpec, Value};
+use crate::runtime::{Call, FormatSpec, Value};
 use crate::Hash;
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -127,6 +127,32 @@ pub enum Inst {
         /// The number of arguments expected on the stack for this call.
         args: usize,
     },
+    /// Perform a function call to a function in the unit whose offset has
+    /// already been resolved.
+    ///
+    /// This is what [Inst::Call] is specialized into when the unit is built
+    /// and the function being called is defined in the same unit, which avoids
+    /// looking up the function by hash at runtime.
+    CallOffset {
+        /// The offset of the function to call.
+        offset: usize,
+        /// The calling convention of the function.
+        call: Call,
+        /// The number of arguments expected on the stack for this call.
+        args: usize,
+    },
+    /// Perform a call a function makes to itself in tail position, by
+    /// replacing the arguments of the current call frame with the last `args`
+    /// number of entries and jumping back to the start of the function.
+    ///
+    /// Everything else in the current call frame is discarded, so the call
+    /// stack doesn't grow no matter how deep the recursion is.
+    TailCall {
+        /// The number of arguments expected on the stack for this call.
+        args: usize,
+        /// The offset to jump to, which is the start of the function.
+        offset: isize,
+    },
     /// Perform a instance function call.
     ///
     /// The instance being called on should be on top of the stack, followed by
@@ -322,6 +348,18 @@ pub enum Inst {
         /// The hash of the function to push.
         hash: Hash,
     },
+    /// Load the global variable of the virtual machine with the name in the
+    /// given static string slot and push it onto the stack.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// => <value>
+    /// ```
+    LoadGlobal {
+        /// The static string slot of the name of the global.
+        slot: usize,
+    },
     /// Push a value onto the stack.
     ///
     /// # Operation
@@ -702,6 +740,17 @@ pub enum Inst {
         /// The static byte string slot to load the string from.
         slot: usize,
     },
+    /// Load a constant tuple from a static tuple slot.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// => <tuple>
+    /// ```
+    StaticTuple {
+        /// The static tuple slot to load the tuple from.
+        slot: usize,
+    },
     /// Pop the given number of values from the stack, and concatenate a string
     /// from them.
     ///
@@ -1005,6 +1054,16 @@ impl fmt::Display for Inst {
             Self::Call { hash, args } => {
                 write!(fmt, "call hash={}, args={}", hash, args)?;
             }
+            Self::CallOffset { offset, call, args } => {
+                write!(
+                    fmt,
+                    "call-offset offset={}, call={}, args={}",
+                    offset, call, args
+                )?;
+            }
+            Self::TailCall { args, offset } => {
+                write!(fmt, "tail-call args={}, offset={}", args, offset)?;
+            }
             Self::CallInstance { hash, args } => {
                 write!(fmt, "call-instance hash={}, args={}", hash, args)?;
             }
@@ -1050,6 +1109,9 @@ impl fmt::Display for Inst {
             Self::LoadFn { hash } => {
                 write!(fmt, "load-fn hash={}", hash)?;
             }
+            Self::LoadGlobal { slot } => {
+                write!(fmt, "load-global slot={}", slot)?;
+            }
             Self::Push { value } => {
                 write!(fmt, "push value={}", value)?;
             }
@@ -1147,6 +1209,9 @@ impl fmt::Display for Inst {
             Self::Bytes { slot } => {
                 write!(fmt, "bytes slot={}", slot)?;
             }
+            Self::StaticTuple { slot } => {
+                write!(fmt, "static-tuple slot={}", slot)?;
+            }
             Self::StringConcat { len, size_hint } => {
                 write!(fmt, "string-concat len={}, size_hint={}", len, size_hint)?;
             }
@@ -4,6 +4,8 @@ use crate::runtime::{
     VmError, VmErrorKind,
 };
 use crate::InstallWith;
+use std::cmp;
+use std::collections::VecDeque;
 use std::fmt;
 use std::iter;
 use std::vec;
@@ -267,6 +269,172 @@ impl Iterator {
         }
     }
 
+    /// Find the minimum element according to the given comparator, where the
+    /// first one is returned if several elements are equally minimum.
+    pub fn min_by(mut self, comparator: Function) -> Result<Option<Value>, VmError> {
+        let mut min = match self.next()? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+
+        while let Some(value) = self.next()? {
+            if comparator.call::<_, cmp::Ordering>((value.clone(), min.clone()))?
+                == cmp::Ordering::Less
+            {
+                min = value;
+            }
+        }
+
+        Ok(Some(min))
+    }
+
+    /// Find the maximum element according to the given comparator, where the
+    /// last one is returned if several elements are equally maximum.
+    pub fn max_by(mut self, comparator: Function) -> Result<Option<Value>, VmError> {
+        let mut max = match self.next()? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+
+        while let Some(value) = self.next()? {
+            if comparator.call::<_, cmp::Ordering>((value.clone(), max.clone()))?
+                != cmp::Ordering::Less
+            {
+                max = value;
+            }
+        }
+
+        Ok(Some(max))
+    }
+
+    /// Zip this iterator with another, producing pairs of their values until
+    /// either of them is exhausted.
+    pub fn zip(self, other: Value) -> Result<Self, VmError> {
+        let zip = Zip {
+            a: self,
+            b: other.into_iter()?,
+        };
+
+        Ok(Self::from("std::iter::Zip", zip))
+    }
+
+    /// Produce the first element and then every `step`th element after it.
+    pub fn step_by(self, step: usize) -> Result<Self, VmError> {
+        if step == 0 {
+            return Err(VmError::panic("step must be greater than zero"));
+        }
+
+        let step_by = StepBy {
+            iter: self,
+            step,
+            first: true,
+        };
+
+        Ok(Self::from("std::iter::StepBy", step_by))
+    }
+
+    /// Produce vectors of `size` consecutive elements, where the last vector
+    /// holds the remaining elements and might be shorter.
+    pub fn chunks(self, size: usize) -> Result<Self, VmError> {
+        if size == 0 {
+            return Err(VmError::panic("chunk size must be greater than zero"));
+        }
+
+        Ok(Self::from("std::iter::Chunks", Chunks { iter: self, size }))
+    }
+
+    /// Produce vectors of every `size` consecutive elements, overlapping each
+    /// other.
+    pub fn windows(self, size: usize) -> Result<Self, VmError> {
+        if size == 0 {
+            return Err(VmError::panic("window size must be greater than zero"));
+        }
+
+        let windows = Windows {
+            iter: self,
+            size,
+            window: VecDeque::with_capacity(size),
+        };
+
+        Ok(Self::from("std::iter::Windows", windows))
+    }
+
+    /// Produce the running accumulation of the iterator, which starts at the
+    /// initial value and is passed to the function together with each
+    /// element to produce the next one.
+    pub fn scan(self, initial: Value, f: Function) -> Self {
+        let scan = Scan {
+            iter: self,
+            state: initial,
+            f,
+        };
+
+        Self::from("std::iter::Scan", scan)
+    }
+
+    /// Produce elements as long as they match the given predicate.
+    pub fn take_while(self, predicate: Function) -> Self {
+        let take_while = TakeWhile {
+            iter: Some(self),
+            predicate,
+        };
+
+        Self::from("std::iter::TakeWhile", take_while)
+    }
+
+    /// Skip over elements as long as they match the given predicate, and
+    /// produce all elements after that.
+    pub fn skip_while(self, predicate: Function) -> Self {
+        let skip_while = SkipWhile {
+            iter: self,
+            predicate: Some(predicate),
+        };
+
+        Self::from("std::iter::SkipWhile", skip_while)
+    }
+
+    /// Skip over elements which are equal to the element before them.
+    pub fn dedup(self) -> Self {
+        let dedup = Dedup {
+            iter: self,
+            last: None,
+        };
+
+        Self::from("std::iter::Dedup", dedup)
+    }
+
+    /// Group consecutive elements for which the given function returns equal
+    /// keys, producing `(key, elements)` pairs.
+    pub fn group_by(self, key: Function) -> Self {
+        let group_by = GroupBy {
+            iter: self,
+            key,
+            next: None,
+        };
+
+        Self::from("std::iter::GroupBy", group_by)
+    }
+
+    /// Split the elements into the ones which match the given predicate and
+    /// the ones which don't.
+    pub fn partition(
+        mut self,
+        predicate: Function,
+    ) -> Result<(vec::Vec<Value>, vec::Vec<Value>), VmError> {
+        let mut matching = vec::Vec::new();
+        let mut rest = vec::Vec::new();
+
+        while let Some(value) = self.next()? {
+            if predicate.call::<_, bool>((value.clone(),))? {
+                matching.push(value);
+            } else {
+                rest.push(value);
+            }
+        }
+
+        Ok((matching, rest))
+    }
+
     /// Count the number of elements remaining in the iterator.
     pub fn count(&mut self) -> Result<usize, VmError> {
         let mut c = 0;
@@ -1198,3 +1366,288 @@ where
         }
     }
 }
+
+struct Zip {
+    a: Iterator,
+    b: Iterator,
+}
+
+impl IteratorTrait for Zip {
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a_lower, a_upper) = self.a.size_hint();
+        let (b_lower, b_upper) = self.b.size_hint();
+
+        let upper = match (a_upper, b_upper) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+
+        (a_lower.min(b_lower), upper)
+    }
+
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        let a = match self.a.next()? {
+            Some(a) => a,
+            None => return Ok(None),
+        };
+
+        let b = match self.b.next()? {
+            Some(b) => b,
+            None => return Ok(None),
+        };
+
+        Ok(Some((a, b).to_value()?))
+    }
+}
+
+struct StepBy {
+    iter: Iterator,
+    step: usize,
+    first: bool,
+}
+
+impl IteratorTrait for StepBy {
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let step = |n: usize| {
+            if self.first {
+                n.div_ceil(self.step)
+            } else {
+                n / self.step
+            }
+        };
+
+        let (lower, upper) = self.iter.size_hint();
+        (step(lower), upper.map(step))
+    }
+
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        if !self.first {
+            for _ in 1..self.step {
+                if self.iter.next()?.is_none() {
+                    return Ok(None);
+                }
+            }
+        }
+
+        self.first = false;
+        self.iter.next()
+    }
+}
+
+struct Chunks {
+    iter: Iterator,
+    size: usize,
+}
+
+impl IteratorTrait for Chunks {
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let chunks = |n: usize| n.div_ceil(self.size);
+        let (lower, upper) = self.iter.size_hint();
+        (chunks(lower), upper.map(chunks))
+    }
+
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        let mut chunk = vec::Vec::with_capacity(self.size);
+
+        while chunk.len() < self.size {
+            match self.iter.next()? {
+                Some(value) => chunk.push(value),
+                None => break,
+            }
+        }
+
+        if chunk.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(chunk.to_value()?))
+    }
+}
+
+struct Windows {
+    iter: Iterator,
+    size: usize,
+    window: VecDeque<Value>,
+}
+
+impl IteratorTrait for Windows {
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let missing = self.size - self.window.len().max(1) + 1;
+        let windows = |n: usize| (n + 1).saturating_sub(missing);
+        let (lower, upper) = self.iter.size_hint();
+        (windows(lower), upper.map(windows))
+    }
+
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        if self.window.len() == self.size {
+            self.window.pop_front();
+        }
+
+        while self.window.len() < self.size {
+            match self.iter.next()? {
+                Some(value) => self.window.push_back(value),
+                None => return Ok(None),
+            }
+        }
+
+        Ok(Some(
+            self.window
+                .iter()
+                .cloned()
+                .collect::<vec::Vec<_>>()
+                .to_value()?,
+        ))
+    }
+}
+
+struct Scan {
+    iter: Iterator,
+    state: Value,
+    f: Function,
+}
+
+impl IteratorTrait for Scan {
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        let value = match self.iter.next()? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+
+        let state = std::mem::replace(&mut self.state, Value::Unit);
+        self.state = self.f.call::<_, Value>((state, value))?;
+        Ok(Some(self.state.clone()))
+    }
+}
+
+struct TakeWhile {
+    /// The iterator, which is cleared once an element doesn't match.
+    iter: Option<Iterator>,
+    predicate: Function,
+}
+
+impl IteratorTrait for TakeWhile {
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.iter {
+            Some(iter) => (0, iter.size_hint().1),
+            None => (0, Some(0)),
+        }
+    }
+
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        let value = match &mut self.iter {
+            Some(iter) => iter.next()?,
+            None => return Ok(None),
+        };
+
+        match value {
+            Some(value) if self.predicate.call::<_, bool>((value.clone(),))? => Ok(Some(value)),
+            _ => {
+                self.iter = None;
+                Ok(None)
+            }
+        }
+    }
+}
+
+struct SkipWhile {
+    iter: Iterator,
+    /// The predicate, which is cleared once an element doesn't match.
+    predicate: Option<Function>,
+}
+
+impl IteratorTrait for SkipWhile {
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.predicate {
+            Some(..) => (0, self.iter.size_hint().1),
+            None => self.iter.size_hint(),
+        }
+    }
+
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        if let Some(predicate) = self.predicate.take() {
+            while let Some(value) = self.iter.next()? {
+                if !predicate.call::<_, bool>((value.clone(),))? {
+                    return Ok(Some(value));
+                }
+            }
+
+            return Ok(None);
+        }
+
+        self.iter.next()
+    }
+}
+
+struct Dedup {
+    iter: Iterator,
+    last: Option<Value>,
+}
+
+impl IteratorTrait for Dedup {
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        (lower.min(1), upper)
+    }
+
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        while let Some(value) = self.iter.next()? {
+            if let Some(last) = &self.last {
+                if Value::value_eq(last, &value)? {
+                    continue;
+                }
+            }
+
+            self.last = Some(value.clone());
+            return Ok(Some(value));
+        }
+
+        Ok(None)
+    }
+}
+
+struct GroupBy {
+    iter: Iterator,
+    key: Function,
+    /// The key and element which starts the next group.
+    next: Option<(Value, Value)>,
+}
+
+impl IteratorTrait for GroupBy {
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        let pending = usize::from(self.next.is_some());
+        (
+            lower.saturating_add(pending).min(1),
+            upper.and_then(|upper| upper.checked_add(pending)),
+        )
+    }
+
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        let (key, first) = match self.next.take() {
+            Some(next) => next,
+            None => match self.iter.next()? {
+                Some(value) => (self.key.call::<_, Value>((value.clone(),))?, value),
+                None => return Ok(None),
+            },
+        };
+
+        let mut group = vec![first];
+
+        while let Some(value) = self.iter.next()? {
+            let next_key = self.key.call::<_, Value>((value.clone(),))?;
+
+            if !Value::value_eq(&key, &next_key)? {
+                self.next = Some((next_key, value));
+                break;
+            }
+
+            group.push(value);
+        }
+
+        Ok(Some((key, group).to_value()?))
+    }
+}
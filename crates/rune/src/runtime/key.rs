@@ -1,7 +1,8 @@
 use crate::runtime::{
-    Bytes, FromValue, Object, Shared, StaticString, ToValue, Tuple, TypeInfo, Value, Variant,
-    VariantData, VariantRtti, Vec, VmError, VmErrorKind,
+    Bytes, EnvProtocolCaller, FromValue, Object, Protocol, ProtocolCaller, Shared, StaticString,
+    ToValue, Tuple, TypeInfo, Value, Variant, VariantData, VariantRtti, Vec, VmError, VmErrorKind,
 };
+use crate::Hash;
 use serde::{de, ser};
 use std::cmp;
 use std::fmt;
@@ -34,6 +35,9 @@ pub enum Key {
     Option(Option<Box<Key>>),
     /// A variant.
     Variant(VariantKey),
+    /// A value which is keyed by what's returned from its [Protocol::HASH]
+    /// function.
+    Custom(CustomKey),
 }
 
 impl Key {
@@ -73,6 +77,10 @@ impl Key {
                 Self::Tuple(tuple_from_value(&*tuple)?)
             }
             Value::Variant(variant) => {
+                if let Some(key) = CustomKey::from_value(value)? {
+                    return Ok(Self::Custom(key));
+                }
+
                 let variant = variant.borrow_ref()?;
 
                 let data = match &variant.data {
@@ -88,11 +96,14 @@ impl Key {
                     data,
                 })
             }
-            value => {
-                return Err(VmError::from(VmErrorKind::KeyNotSupported {
-                    actual: value.type_info()?,
-                }))
-            }
+            value => match CustomKey::from_value(value)? {
+                Some(key) => Self::Custom(key),
+                None => {
+                    return Err(VmError::from(VmErrorKind::KeyNotSupported {
+                        actual: value.type_info()?,
+                    }))
+                }
+            },
         });
 
         fn tuple_from_value(tuple: &Tuple) -> Result<Box<[Key]>, VmError> {
@@ -129,12 +140,12 @@ impl Key {
             Self::Bool(b) => Value::Bool(b),
             Self::Integer(n) => Value::Integer(n),
             Self::String(s) => match s {
-                StringKey::String(s) => Value::String(Shared::new(String::from(s))),
+                StringKey::String(s) => Value::String(Shared::tracked(String::from(s))),
                 StringKey::StaticString(s) => Value::StaticString(s),
             },
-            Self::Bytes(b) => Value::Bytes(Shared::new(b)),
+            Self::Bytes(b) => Value::Bytes(Shared::tracked(b)),
             Self::Option(option) => {
-                Value::Option(Shared::new(option.map(|some| some.into_value())))
+                Value::Option(Shared::tracked(option.map(|some| some.into_value())))
             }
             Self::Vec(vec) => {
                 let mut v = Vec::with_capacity(vec.len());
@@ -143,9 +154,9 @@ impl Key {
                     v.push(value.into_value());
                 }
 
-                Value::Vec(Shared::new(v))
+                Value::Vec(Shared::tracked(v))
             }
-            Self::Tuple(tuple) => Value::Tuple(Shared::new(tuple_into_value(tuple))),
+            Self::Tuple(tuple) => Value::Tuple(Shared::tracked(tuple_into_value(tuple))),
             Self::Variant(variant) => {
                 let data = match variant.data {
                     VariantKeyData::Unit => VariantData::Unit,
@@ -153,11 +164,12 @@ impl Key {
                     VariantKeyData::Struct(st) => VariantData::Struct(struct_into_value(st)),
                 };
 
-                Value::Variant(Shared::new(Variant {
+                Value::Variant(Shared::tracked(Variant {
                     rtti: variant.rtti,
                     data,
                 }))
             }
+            Self::Custom(custom) => custom.key.into_value(),
         };
 
         fn tuple_into_value(data: Box<[Key]>) -> Tuple {
@@ -203,6 +215,7 @@ impl Key {
             Self::Tuple(..) => TypeInfo::StaticType(crate::runtime::TUPLE_TYPE),
             Self::Option(..) => TypeInfo::StaticType(crate::runtime::OPTION_TYPE),
             Self::Variant(variant) => TypeInfo::Variant(variant.rtti.clone()),
+            Self::Custom(custom) => custom.type_info.clone(),
         }
     }
 }
@@ -221,6 +234,7 @@ impl fmt::Debug for Key {
             Key::Tuple(tuple) => write!(f, "{:?}", tuple),
             Key::Option(opt) => write!(f, "{:?}", opt),
             Key::Variant(variant) => write!(f, "{:?}", variant),
+            Key::Custom(custom) => write!(f, "{:?}", custom),
         }
     }
 }
@@ -283,6 +297,7 @@ impl ser::Serialize for Key {
             }
             Self::Option(option) => <Option<Box<Key>>>::serialize(option, serializer),
             Self::Variant(..) => Err(ser::Error::custom("cannot serialize variants")),
+            Self::Custom(custom) => custom.key.serialize(serializer),
         }
     }
 }
@@ -556,3 +571,70 @@ pub enum VariantKeyData {
     /// An struct variant with a specific type hash.
     Struct(Box<[(Box<str>, Key)]>),
 }
+
+/// A value which is keyed by what's returned from its [Protocol::HASH]
+/// function.
+///
+/// Keys are compared and hashed by the type of the value and the key it
+/// returned, which is captured when the key is constructed. Since the value
+/// itself isn't stored, converting the key back into a value produces the
+/// returned key.
+#[derive(Clone)]
+pub struct CustomKey {
+    type_hash: Hash,
+    type_info: TypeInfo,
+    key: Box<Key>,
+}
+
+impl CustomKey {
+    /// Construct a custom key from the given value, returning `None` if its
+    /// type doesn't implement the [Protocol::HASH] function.
+    fn from_value(value: &Value) -> Result<Option<Self>, VmError> {
+        if !EnvProtocolCaller.has_protocol_fn(Protocol::HASH, value)? {
+            return Ok(None);
+        }
+
+        let key = EnvProtocolCaller.call_protocol_fn(Protocol::HASH, value.clone(), ())?;
+
+        Ok(Some(Self {
+            type_hash: value.type_hash()?,
+            type_info: value.type_info()?,
+            key: Box::new(Key::from_value(&key)?),
+        }))
+    }
+}
+
+impl fmt::Debug for CustomKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}({:?})", self.type_info, self.key)
+    }
+}
+
+impl cmp::PartialEq for CustomKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.type_hash == other.type_hash && self.key == other.key
+    }
+}
+
+impl cmp::Eq for CustomKey {}
+
+impl hash::Hash for CustomKey {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.type_hash.hash(state);
+        self.key.hash(state);
+    }
+}
+
+impl cmp::PartialOrd for CustomKey {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl cmp::Ord for CustomKey {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.type_hash
+            .cmp(&other.type_hash)
+            .then_with(|| self.key.cmp(&other.key))
+    }
+}
@@ -0,0 +1,151 @@
+//! Linking of units which were compiled separately.
+//!
+//! Linking appends the instructions and lookaside tables of one unit to those
+//! of another, relocating everything which refers to them by offset or slot.
+//! Since the instructions of the original unit don't move, anything which is
+//! currently executing it remains valid.
+
+use crate::collections::HashMap;
+use crate::runtime::{Inst, InstTarget, UnitFn};
+use crate::Hash;
+use thiserror::Error;
+
+/// An error raised when linking units.
+#[derive(Debug, Error)]
+#[error("{kind}")]
+pub struct LinkError {
+    kind: LinkErrorKind,
+}
+
+impl From<LinkErrorKind> for LinkError {
+    fn from(kind: LinkErrorKind) -> Self {
+        Self { kind }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum LinkErrorKind {
+    #[error("function with hash `{hash}` is defined in both units")]
+    ConflictingFunction { hash: Hash },
+    #[error("type with hash `{hash}` is defined in both units")]
+    ConflictingType { hash: Hash },
+    #[error("constant with hash `{hash}` is defined in both units")]
+    ConflictingConstant { hash: Hash },
+}
+
+/// The offsets at which the instructions and slots of a linked unit are
+/// placed.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Relocation {
+    /// The offset of the first instruction.
+    pub(crate) instructions: usize,
+    /// The offset of the first static string.
+    pub(crate) static_strings: usize,
+    /// The offset of the first static byte string.
+    pub(crate) static_bytes: usize,
+    /// The offset of the first set of static object keys.
+    pub(crate) static_object_keys: usize,
+    /// The offset of the first static tuple.
+    pub(crate) static_tuples: usize,
+}
+
+impl Relocation {
+    /// Relocate a single instruction.
+    ///
+    /// Jumps are relative, so only absolute offsets and slots need to change.
+    pub(crate) fn inst(&self, inst: Inst) -> Inst {
+        match inst {
+            Inst::CallOffset { offset, call, args } => Inst::CallOffset {
+                offset: offset + self.instructions,
+                call,
+                args,
+            },
+            Inst::String { slot } => Inst::String {
+                slot: slot + self.static_strings,
+            },
+            Inst::EqStaticString { slot } => Inst::EqStaticString {
+                slot: slot + self.static_strings,
+            },
+            Inst::ObjectIndexGet { slot } => Inst::ObjectIndexGet {
+                slot: slot + self.static_strings,
+            },
+            Inst::ObjectIndexSet { slot } => Inst::ObjectIndexSet {
+                slot: slot + self.static_strings,
+            },
+            Inst::ObjectIndexGetAt { offset, slot } => Inst::ObjectIndexGetAt {
+                offset,
+                slot: slot + self.static_strings,
+            },
+            Inst::LoadGlobal { slot } => Inst::LoadGlobal {
+                slot: slot + self.static_strings,
+            },
+            Inst::Assign {
+                target: InstTarget::Field(slot),
+                op,
+            } => Inst::Assign {
+                target: InstTarget::Field(slot + self.static_strings),
+                op,
+            },
+            Inst::Bytes { slot } => Inst::Bytes {
+                slot: slot + self.static_bytes,
+            },
+            Inst::StaticTuple { slot } => Inst::StaticTuple {
+                slot: slot + self.static_tuples,
+            },
+            Inst::Object { slot } => Inst::Object {
+                slot: slot + self.static_object_keys,
+            },
+            Inst::Struct { hash, slot } => Inst::Struct {
+                hash,
+                slot: slot + self.static_object_keys,
+            },
+            Inst::StructVariant { hash, slot } => Inst::StructVariant {
+                hash,
+                slot: slot + self.static_object_keys,
+            },
+            Inst::MatchObject { slot, exact } => Inst::MatchObject {
+                slot: slot + self.static_object_keys,
+                exact,
+            },
+            inst => inst,
+        }
+    }
+
+    /// Relocate a function.
+    pub(crate) fn function(&self, f: UnitFn) -> UnitFn {
+        match f {
+            UnitFn::Offset { offset, call, args } => UnitFn::Offset {
+                offset: offset + self.instructions,
+                call,
+                args,
+            },
+            f => f,
+        }
+    }
+}
+
+/// Specialize calls to functions defined in the unit into calls directly to
+/// their offset.
+///
+/// Calls with the wrong number of arguments are left alone, so that they are
+/// reported when they're executed.
+pub(crate) fn specialize_calls(instructions: &mut [Inst], functions: &HashMap<Hash, UnitFn>) {
+    for inst in instructions {
+        if let Inst::Call { hash, args } = *inst {
+            if let Some(UnitFn::Offset {
+                offset,
+                call,
+                args: expected,
+            }) = functions.get(&hash)
+            {
+                if args == *expected {
+                    *inst = Inst::CallOffset {
+                        offset: *offset,
+                        call: *call,
+                        args,
+                    };
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! Accounting of the memory allocated by a virtual machine.
+
+use crate::runtime::{Inst, InstTarget, Value, Vm, VmError, VmErrorKind};
+use std::cell::Cell;
+use std::mem;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// The memory allocated by a virtual machine and the virtual machines it
+/// spawns, and the limit it's held to.
+#[derive(Debug)]
+pub(crate) struct Memory {
+    limit: AtomicUsize,
+    used: AtomicUsize,
+}
+
+impl Memory {
+    pub(crate) fn new(limit: usize) -> Self {
+        Self {
+            limit: AtomicUsize::new(limit),
+            used: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn limit(&self) -> usize {
+        self.limit.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_limit(&self, limit: usize) {
+        self.limit.store(limit, Ordering::Relaxed);
+    }
+
+    pub(crate) fn used(&self) -> usize {
+        self.used.load(Ordering::Relaxed)
+    }
+
+    /// Check that the memory used is within the limit.
+    pub(crate) fn check(&self) -> Result<(), VmError> {
+        let used = self.used();
+        let limit = self.limit();
+
+        if used > limit {
+            return Err(VmError::from(VmErrorKind::MemoryLimitExceeded {
+                used,
+                limit,
+            }));
+        }
+
+        Ok(())
+    }
+
+    fn charge(&self, bytes: usize) {
+        self.used.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    fn release(&self, bytes: usize) {
+        let _ = self
+            .used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |v| {
+                Some(v.saturating_sub(bytes))
+            });
+    }
+}
+
+/// The memory charged for a single shared allocation, which is released once
+/// it's dropped.
+pub(crate) struct Charge {
+    memory: Cell<Option<Arc<Memory>>>,
+    bytes: Cell<usize>,
+}
+
+impl Charge {
+    /// Charge the given number of bytes to the given memory, if any.
+    pub(crate) fn new(memory: Option<Arc<Memory>>, bytes: usize) -> Self {
+        if let Some(memory) = &memory {
+            memory.charge(bytes);
+        }
+
+        Self {
+            bytes: Cell::new(if memory.is_some() { bytes } else { 0 }),
+            memory: Cell::new(memory),
+        }
+    }
+
+    /// Update the number of bytes charged.
+    ///
+    /// Allocations made outside of a limited virtual machine are charged to
+    /// the memory of the first virtual machine to resize them.
+    pub(crate) fn resize(&self, bytes: usize) {
+        let memory = match self.memory.take() {
+            Some(memory) => memory,
+            None => match crate::runtime::env::memory() {
+                Some(memory) => memory,
+                None => return,
+            },
+        };
+
+        let old = self.bytes.replace(bytes);
+
+        if bytes > old {
+            memory.charge(bytes - old);
+        } else {
+            memory.release(old - bytes);
+        }
+
+        self.memory.set(Some(memory));
+    }
+}
+
+impl Drop for Charge {
+    fn drop(&mut self) {
+        if let Some(memory) = self.memory.take() {
+            memory.release(self.bytes.get());
+        }
+    }
+}
+
+impl Value {
+    /// Charge the heap storage of the value to the memory of the virtual
+    /// machine which is currently running.
+    ///
+    /// Values which are currently borrowed exclusively are skipped.
+    pub(crate) fn charge_heap(&self) {
+        const VALUE: usize = mem::size_of::<Value>();
+
+        match self {
+            Value::String(string) => {
+                if let Ok(heap) = string.borrow_ref().map(|s| s.capacity()) {
+                    string.set_heap_size(heap);
+                }
+            }
+            Value::Bytes(bytes) => {
+                if let Ok(heap) = bytes.borrow_ref().map(|b| b.capacity()) {
+                    bytes.set_heap_size(heap);
+                }
+            }
+            Value::Vec(vec) => {
+                if let Ok(heap) = vec.borrow_ref().map(|v| v.capacity() * VALUE) {
+                    vec.set_heap_size(heap);
+                }
+            }
+            Value::Tuple(tuple) => {
+                if let Ok(heap) = tuple.borrow_ref().map(|t| t.len() * VALUE) {
+                    tuple.set_heap_size(heap);
+                }
+            }
+            Value::Object(object) => {
+                let heap = object.borrow_ref().map(|o| {
+                    o.keys()
+                        .map(|key| key.capacity() + mem::size_of::<(String, Value)>())
+                        .sum()
+                });
+
+                if let Ok(heap) = heap {
+                    object.set_heap_size(heap);
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+impl Vm {
+    /// Check the memory limit before executing the given instruction,
+    /// returning the values whose heap storage should be charged once it has
+    /// been executed.
+    ///
+    /// These are the arguments of calls and the targets of assignments, since
+    /// they can be grown in place.
+    #[cold]
+    pub(crate) fn memory_before(
+        &self,
+        memory: &Memory,
+        inst: &Inst,
+    ) -> Result<Vec<Value>, VmError> {
+        memory.check()?;
+
+        let args = match *inst {
+            Inst::Call { args, .. }
+            | Inst::CallOffset { args, .. }
+            | Inst::TailCall { args, .. } => args,
+            Inst::CallInstance { args, .. } => args + 1,
+            Inst::CallFn { args } => args + 1,
+            Inst::Assign {
+                target: InstTarget::Offset(offset),
+                ..
+            } => {
+                return Ok(self
+                    .stack()
+                    .at_offset(offset)
+                    .ok()
+                    .cloned()
+                    .into_iter()
+                    .collect());
+            }
+            _ => return Ok(Vec::new()),
+        };
+
+        let stack = self.stack();
+        let start = stack.stack_bottom().max(stack.len().saturating_sub(args));
+
+        Ok(stack
+            .get(start..)
+            .unwrap_or_default()
+            .iter()
+            .filter(|value| {
+                matches!(
+                    value,
+                    Value::String(..) | Value::Bytes(..) | Value::Vec(..) | Value::Object(..)
+                )
+            })
+            .cloned()
+            .collect())
+    }
+
+    /// Charge the heap storage of the given values and the value on top of
+    /// the stack once an instruction has been executed.
+    #[cold]
+    pub(crate) fn memory_after(&self, values: Vec<Value>) {
+        for value in &values {
+            value.charge_heap();
+        }
+
+        if let Ok(value) = self.stack().last() {
+            value.charge_heap();
+        }
+    }
+}
@@ -7,47 +7,70 @@ mod awaited;
 pub mod budget;
 mod bytes;
 mod call;
+mod call_hook;
+mod capabilities;
+mod clone;
 mod const_value;
+mod deadline;
 pub mod debug;
-mod env;
+mod debugger;
+mod determinism;
+mod disasm;
+pub(crate) mod env;
+mod extensions;
+mod finalize;
 pub mod format;
 mod from_value;
+mod fuel;
 mod function;
 pub(crate) mod future;
+mod gc;
 mod generator;
 mod generator_state;
 mod guarded_args;
+mod inline_cache;
 mod inst;
 mod iterator;
 mod key;
 mod label;
+pub(crate) mod link;
+mod memory;
 mod object;
 mod panic;
+mod preemption;
+mod profiler;
 mod protocol;
 mod protocol_caller;
 mod range;
 mod raw_str;
 mod runtime_context;
 mod select;
+mod send_value;
 mod shared;
+mod snapshot;
 mod stack;
+mod stack_trace;
 mod static_string;
 mod static_type;
 mod stream;
+mod suspend;
 mod to_value;
 mod tuple;
 mod type_info;
 mod type_of;
 mod unit;
 mod value;
+mod value_serde;
 mod variant;
 mod vec;
 mod vec_tuple;
+mod verify;
 mod vm;
 mod vm_call;
 mod vm_error;
 mod vm_execution;
 mod vm_halt;
+mod vm_metrics;
 
 pub(crate) use self::access::{Access, AccessKind};
 pub use self::access::{
@@ -58,13 +81,26 @@ pub use self::args::Args;
 pub(crate) use self::awaited::Awaited;
 pub use self::bytes::Bytes;
 pub use self::call::Call;
+pub(crate) use self::call_hook::native_span;
+pub use self::call_hook::{CallHook, NativeCall};
+pub use self::capabilities::{Capabilities, Capability};
+pub(crate) use self::clone::{deep_clone, deep_clone_object, deep_clone_vec};
 pub use self::const_value::ConstValue;
+pub(crate) use self::deadline::Deadline;
 pub use self::debug::{DebugInfo, DebugInst};
+pub use self::debugger::{
+    DebugAction, DebugContext, DebugListener, DebugOutcome, TraceSink, TraceWriter,
+};
+pub use self::determinism::Determinism;
+pub use self::disasm::{InstConstant, InstFunction, InstRecord};
+pub use self::extensions::Extensions;
+pub(crate) use self::finalize::{finalize, take_finalizer};
 pub use self::format::{Format, FormatSpec};
 pub use self::from_value::{FromValue, UnsafeFromValue};
+pub(crate) use self::fuel::Fuel;
 pub use self::function::{Function, SyncFunction};
 pub use self::future::Future;
-pub use self::generator::Generator;
+pub use self::generator::{Generator, GeneratorIterator};
 pub use self::generator_state::GeneratorState;
 pub use self::guarded_args::GuardedArgs;
 pub use self::inst::{
@@ -74,8 +110,13 @@ pub use self::inst::{
 pub use self::iterator::{Iterator, IteratorTrait};
 pub use self::key::Key;
 pub use self::label::{DebugLabel, Label};
+pub use self::link::LinkError;
+pub(crate) use self::memory::{Charge, Memory};
 pub use self::object::Object;
+pub(crate) use self::panic::catch_native;
 pub use self::panic::Panic;
+pub(crate) use self::preemption::{Preemption, YieldNow};
+pub use self::profiler::{Profile, ProfiledFunction, Profiler};
 pub use self::protocol::Protocol;
 pub(crate) use self::protocol_caller::{EnvProtocolCaller, ProtocolCaller};
 pub use self::range::{Range, RangeLimits};
@@ -83,8 +124,11 @@ pub use self::raw_str::RawStr;
 pub use self::runtime_context::RuntimeContext;
 pub(crate) use self::runtime_context::{FunctionHandler, MacroHandler};
 pub use self::select::Select;
-pub use self::shared::{Mut, RawMut, RawRef, Ref, Shared, SharedPointerGuard};
+pub use self::send_value::SendValue;
+pub use self::shared::{Mut, RawMut, RawRef, Ref, Shared, SharedPointerGuard, Weak};
+pub use self::snapshot::SnapshotError;
 pub use self::stack::{Stack, StackError};
+pub use self::stack_trace::{StackTrace, StackTraceFrame};
 pub use self::static_string::StaticString;
 pub use self::static_type::{
     StaticType, BOOL_TYPE, BYTES_TYPE, BYTE_TYPE, CHAR_TYPE, FLOAT_TYPE, FORMAT_TYPE,
@@ -93,18 +137,24 @@ pub use self::static_type::{
     UNIT_TYPE, VEC_TYPE,
 };
 pub use self::stream::Stream;
+pub use self::suspend::Suspend;
 pub use self::to_value::{ToValue, UnsafeToValue};
 pub use self::tuple::Tuple;
 pub use self::type_info::TypeInfo;
 pub use self::type_of::TypeOf;
 pub use self::unit::{Unit, UnitFn};
 pub use self::value::{Rtti, Struct, TupleStruct, UnitStruct, Value, VariantRtti};
+pub use self::value_serde::{from_value, to_value};
 pub use self::variant::{Variant, VariantData};
 pub use self::vec::Vec;
 pub use self::vec_tuple::VecTuple;
+pub use self::verify::VerifyError;
+pub(crate) use self::vm::StackLimits;
 pub use self::vm::{CallFrame, Vm};
 pub(crate) use self::vm_call::VmCall;
 pub use self::vm_error::{VmError, VmErrorKind, VmIntegerRepr};
 pub use self::vm_execution::{ExecutionState, VmExecution, VmSendExecution};
 pub(crate) use self::vm_halt::VmHalt;
 pub use self::vm_halt::VmHaltInfo;
+pub(crate) use self::vm_metrics::MetricsState;
+pub use self::vm_metrics::{FunctionMetrics, VmMetrics};
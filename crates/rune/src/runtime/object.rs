@@ -5,6 +5,7 @@ use crate::runtime::{
     VmError,
 };
 use crate::InstallWith;
+use serde::ser;
 use std::borrow;
 use std::cmp;
 use std::fmt;
@@ -272,6 +273,16 @@ impl fmt::Debug for Object {
     }
 }
 
+/// Objects are serialized as maps.
+impl ser::Serialize for Object {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.collect_map(&self.inner)
+    }
+}
+
 impl std::iter::FromIterator<(String, Value)> for Object {
     fn from_iter<T: IntoIterator<Item = (String, Value)>>(src: T) -> Self {
         Self {
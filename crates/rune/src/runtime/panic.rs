@@ -1,5 +1,6 @@
-use crate::runtime::PanicReason;
+use crate::runtime::{PanicReason, VmError, VmErrorKind};
 use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
 
 pub trait BoxedPanic: 'static + fmt::Display + fmt::Debug + Send + Sync {}
 impl<T> BoxedPanic for T where T: 'static + fmt::Display + fmt::Debug + Send + Sync {}
@@ -38,3 +39,22 @@ impl From<PanicReason> for Panic {
         }
     }
 }
+
+/// Run native code, converting a panic raised by it into an error so that it
+/// can't unwind through the virtual machine and abort the host.
+pub(crate) fn catch_native<T>(f: impl FnOnce() -> Result<T, VmError>) -> Result<T, VmError> {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(result) => result,
+        Err(payload) => {
+            let message = if let Some(message) = payload.downcast_ref::<&str>() {
+                (*message).to_owned()
+            } else if let Some(message) = payload.downcast_ref::<String>() {
+                message.clone()
+            } else {
+                String::from("Box<dyn Any>")
+            };
+
+            Err(VmError::from(VmErrorKind::NativePanic { message }))
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! Cooperative preemption of asynchronous executions.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Counts down the yield points executed by a virtual machine until it should
+/// return control to the executor.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Preemption {
+    interval: u32,
+    remaining: u32,
+}
+
+impl Preemption {
+    pub(crate) fn new(interval: u32) -> Self {
+        Self {
+            interval,
+            remaining: interval,
+        }
+    }
+
+    /// The number of yield points between each preemption.
+    pub(crate) fn interval(&self) -> u32 {
+        self.interval
+    }
+
+    /// Construct a fresh countdown with the same interval, for a virtual
+    /// machine being spawned.
+    pub(crate) fn spawn(&self) -> Self {
+        Self::new(self.interval)
+    }
+
+    /// Count a yield point, indicating with `true` if the virtual machine
+    /// should be preempted.
+    pub(crate) fn tick(&mut self) -> bool {
+        if self.remaining > 1 {
+            self.remaining -= 1;
+            return false;
+        }
+
+        self.remaining = self.interval;
+        true
+    }
+}
+
+/// A future which returns control to the executor once before completing.
+#[derive(Debug, Default)]
+pub(crate) struct YieldNow {
+    yielded: bool,
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.yielded {
+            return Poll::Ready(());
+        }
+
+        self.yielded = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
@@ -0,0 +1,309 @@
+//! An instrumenting profiler for script functions.
+
+use crate::collections::HashMap;
+use crate::runtime::{DebugContext, Unit};
+use crate::Hash;
+use std::io;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+/// A profiler which records the time spent in each script function.
+///
+/// The profiler is a cheap handle which can be cloned, attach it to a virtual
+/// machine with [Vm::set_profiler][crate::Vm::set_profiler] and collect what
+/// it has recorded with [Profiler::profile].
+///
+/// Functions are resolved through the debug information of the unit being
+/// executed, so time spent in a unit without debug information is recorded
+/// under an empty stack.
+///
+/// # Examples
+///
+/// ```
+/// use rune::{Context, Source, Sources, Vm};
+/// use rune::runtime::Profiler;
+/// use std::sync::Arc;
+///
+/// # fn main() -> rune::Result<()> {
+/// let mut sources = Sources::new();
+/// sources.insert(Source::new("entry", r#"
+/// fn fib(n) {
+///     if n <= 1 { n } else { fib(n - 1) + fib(n - 2) }
+/// }
+///
+/// pub fn main() {
+///     fib(10)
+/// }
+/// "#));
+///
+/// let unit = rune::prepare(&mut sources).build()?;
+/// let mut vm = Vm::without_runtime(Arc::new(unit));
+///
+/// let profiler = Profiler::new();
+/// vm.set_profiler(profiler.clone());
+/// vm.call(&["main"], ())?;
+///
+/// let profile = profiler.profile();
+/// let fib = profile.functions().iter().find(|f| f.name == "fib").unwrap();
+/// assert_eq!(fib.calls, 177);
+///
+/// let mut collapsed = Vec::new();
+/// profile.write_collapsed(&mut collapsed)?;
+/// assert!(String::from_utf8(collapsed)?.contains("main;fib;fib "));
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Profiler {
+    inner: Arc<Mutex<Recorder>>,
+}
+
+impl Profiler {
+    /// Construct a new profiler.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get a snapshot of what has been recorded so far.
+    pub fn profile(&self) -> Profile {
+        self.lock().profile()
+    }
+
+    /// Clear everything recorded so far.
+    pub fn reset(&self) {
+        *self.lock() = Recorder::default();
+    }
+
+    /// Record the instruction which is about to be executed.
+    pub(crate) fn record(&self, cx: &DebugContext<'_>) {
+        self.lock().record(cx);
+    }
+
+    /// Stop attributing time to the current function, since execution is
+    /// paused.
+    pub(crate) fn pause(&self) {
+        self.lock().pause();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Recorder> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// The recording state of a [Profiler].
+#[derive(Debug, Default)]
+struct Recorder {
+    /// When the last instruction was recorded.
+    last: Option<Instant>,
+    /// The function stack of the last instruction, outermost first.
+    stack: Vec<Hash>,
+    /// Scratch buffer used when building the stack of an instruction.
+    scratch: Vec<Hash>,
+    /// Time spent with the given stack.
+    samples: HashMap<Vec<Hash>, Duration>,
+    /// Number of times each function was called.
+    calls: HashMap<Hash, usize>,
+    /// Names of functions.
+    names: HashMap<Hash, String>,
+    /// Sorted function offsets of the unit last seen, keyed by its address.
+    offsets: Option<(usize, Vec<(usize, Hash)>)>,
+}
+
+impl Recorder {
+    fn record(&mut self, cx: &DebugContext<'_>) {
+        let now = Instant::now();
+        self.attribute(now);
+        self.last = Some(now);
+
+        let unit = cx.unit();
+        self.cache_offsets(unit);
+
+        let mut scratch = std::mem::take(&mut self.scratch);
+        scratch.clear();
+
+        let ips = cx.call_frames().iter().map(|frame| frame.ip());
+
+        for ip in ips.chain(std::iter::once(cx.ip())) {
+            if let Some(hash) = self.function_containing(ip) {
+                scratch.push(hash);
+            }
+        }
+
+        if scratch != self.stack {
+            if scratch.len() > self.stack.len() {
+                if let Some(hash) = scratch.last() {
+                    *self.calls.entry(*hash).or_default() += 1;
+                }
+            }
+
+            std::mem::swap(&mut scratch, &mut self.stack);
+        }
+
+        self.scratch = scratch;
+    }
+
+    fn pause(&mut self) {
+        self.attribute(Instant::now());
+        self.last = None;
+    }
+
+    /// Attribute the time since the last instruction to the current stack.
+    fn attribute(&mut self, now: Instant) {
+        let last = match self.last {
+            Some(last) => last,
+            None => return,
+        };
+
+        let elapsed = now.saturating_duration_since(last);
+
+        if let Some(duration) = self.samples.get_mut(&self.stack[..]) {
+            *duration += elapsed;
+        } else {
+            self.samples.insert(self.stack.clone(), elapsed);
+        }
+    }
+
+    fn cache_offsets(&mut self, unit: &Unit) {
+        let address = unit as *const Unit as usize;
+
+        if matches!(&self.offsets, Some((a, _)) if *a == address) {
+            return;
+        }
+
+        let mut offsets = Vec::new();
+
+        if let Some(debug_info) = unit.debug_info() {
+            for (offset, hash) in &debug_info.functions_rev {
+                offsets.push((*offset, *hash));
+
+                if let Some(signature) = debug_info.functions.get(hash) {
+                    self.names
+                        .entry(*hash)
+                        .or_insert_with(|| signature.path.to_string());
+                }
+            }
+        }
+
+        offsets.sort_unstable();
+        self.offsets = Some((address, offsets));
+    }
+
+    fn function_containing(&self, ip: usize) -> Option<Hash> {
+        let (_, offsets) = self.offsets.as_ref()?;
+
+        let index = match offsets.binary_search_by_key(&ip, |(offset, _)| *offset) {
+            Ok(index) => index,
+            Err(index) => index.checked_sub(1)?,
+        };
+
+        Some(offsets.get(index)?.1)
+    }
+
+    fn profile(&self) -> Profile {
+        let mut functions = HashMap::new();
+        let mut stacks = Vec::new();
+
+        for (stack, duration) in &self.samples {
+            if let Some(hash) = stack.last() {
+                self.function(&mut functions, *hash).self_time += *duration;
+            }
+
+            let mut seen = Vec::with_capacity(stack.len());
+
+            // NB: recursive calls only count once towards the total time.
+            for hash in stack {
+                if !seen.contains(hash) {
+                    seen.push(*hash);
+                    self.function(&mut functions, *hash).total_time += *duration;
+                }
+            }
+
+            let names = stack.iter().map(|hash| self.name(*hash)).collect();
+            stacks.push((names, *duration));
+        }
+
+        for hash in self.calls.keys() {
+            self.function(&mut functions, *hash);
+        }
+
+        let mut functions = functions.into_iter().map(|(_, f)| f).collect::<Vec<_>>();
+        functions.sort_by(|a, b| b.self_time.cmp(&a.self_time).then(a.name.cmp(&b.name)));
+        stacks.sort();
+
+        Profile { functions, stacks }
+    }
+
+    fn function<'a>(
+        &self,
+        functions: &'a mut HashMap<Hash, ProfiledFunction>,
+        hash: Hash,
+    ) -> &'a mut ProfiledFunction {
+        functions.entry(hash).or_insert_with(|| ProfiledFunction {
+            hash,
+            name: self.name(hash),
+            calls: self.calls.get(&hash).copied().unwrap_or_default(),
+            self_time: Duration::default(),
+            total_time: Duration::default(),
+        })
+    }
+
+    fn name(&self, hash: Hash) -> String {
+        match self.names.get(&hash) {
+            Some(name) => name.clone(),
+            None => hash.to_string(),
+        }
+    }
+}
+
+/// The time recorded for a single script function by a [Profiler].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ProfiledFunction {
+    /// The hash of the function.
+    pub hash: Hash,
+    /// The name of the function, as resolved through debug information. Falls
+    /// back to the hash of the function.
+    pub name: String,
+    /// The number of times the function was called.
+    pub calls: usize,
+    /// Time spent executing the function itself.
+    pub self_time: Duration,
+    /// Time spent executing the function and the functions it called.
+    pub total_time: Duration,
+}
+
+/// A snapshot of what a [Profiler] has recorded.
+#[derive(Debug, Clone)]
+pub struct Profile {
+    functions: Vec<ProfiledFunction>,
+    stacks: Vec<(Vec<String>, Duration)>,
+}
+
+impl Profile {
+    /// The profiled functions, ordered by the time spent in them.
+    pub fn functions(&self) -> &[ProfiledFunction] {
+        &self.functions
+    }
+
+    /// Write the profile as collapsed stacks, one line per unique stack
+    /// followed by the number of nanoseconds spent in it.
+    ///
+    /// This is the format expected by tools like [inferno] and the original
+    /// [flamegraph] scripts.
+    ///
+    /// [inferno]: https://github.com/jonhoo/inferno
+    /// [flamegraph]: https://github.com/brendangregg/FlameGraph
+    pub fn write_collapsed<O>(&self, out: &mut O) -> io::Result<()>
+    where
+        O: ?Sized + io::Write,
+    {
+        for (stack, duration) in &self.stacks {
+            if stack.is_empty() {
+                continue;
+            }
+
+            writeln!(out, "{} {}", stack.join(";"), duration.as_nanos())?;
+        }
+
+        Ok(())
+    }
+}
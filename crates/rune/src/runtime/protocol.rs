@@ -4,7 +4,14 @@ use std::cmp;
 use std::fmt;
 use std::hash;
 
+/// Seed for the hashes of custom protocols, so that they don't conflict with
+/// instance functions of the same name.
+const CUSTOM_PROTOCOL: u64 = 0x8e1d4fb8a1c0d5f3;
+
 /// A built in instance function.
+///
+/// Besides the protocols built into the virtual machine, embedders can define
+/// their own with [Protocol::custom].
 #[derive(Debug, Clone, Copy)]
 pub struct Protocol {
     /// The name of the builtin function.
@@ -76,9 +83,12 @@ impl hash::Hash for Protocol {
 
 impl Protocol {
     /// Check two types for equality.
+    ///
+    /// This has the same hash as an instance function named `eq`, so that
+    /// scripts can implement it.
     pub const EQ: Protocol = Protocol {
         name: "eq",
-        hash: Hash::new(0x418f5becbf885806),
+        hash: Hash::new(0xa952bb3d6c7651ff),
     };
 
     /// The function to access a field.
@@ -260,4 +270,182 @@ impl Protocol {
         name: "into_type_name",
         hash: Hash::new(0xbffd08b816c24682),
     };
+
+    /// Function called when the last strong reference to a value goes away,
+    /// or when it's explicitly dropped through `std::mem::drop`.
+    ///
+    /// This has the same hash as an instance function named `drop`, so that
+    /// scripts can implement it.
+    pub const DROP: Protocol = Protocol {
+        name: "drop",
+        hash: Hash::new(0x80bbcfdc4f9f1d43),
+    };
+
+    /// Function used to get the value a type is hashed and compared by when
+    /// it's used as a key, like in a `HashMap` or a `HashSet`.
+    ///
+    /// This has the same hash as an instance function named `hash`, so that
+    /// scripts can implement it.
+    pub const HASH: Protocol = Protocol {
+        name: "hash",
+        hash: Hash::new(0x3440d109c9dd0b5f),
+    };
+
+    /// Function used to order two values, returning an `Ordering`. This is
+    /// used when sorting.
+    ///
+    /// This has the same hash as an instance function named `cmp`, so that
+    /// scripts can implement it.
+    pub const CMP: Protocol = Protocol {
+        name: "cmp",
+        hash: Hash::new(0x3ab799784e414506),
+    };
+
+    /// Function used to clone a value. Values which don't implement it are
+    /// cloned deeply, cloning the values they contain.
+    ///
+    /// This has the same hash as an instance function named `clone`, so that
+    /// scripts can implement it.
+    pub const CLONE: Protocol = Protocol {
+        name: "clone",
+        hash: Hash::new(0xbece0d347b6ac4e9),
+    };
+
+    /// All built in protocols.
+    const ALL: &'static [Protocol] = &[
+        Protocol::EQ,
+        Protocol::GET,
+        Protocol::SET,
+        Protocol::INDEX_GET,
+        Protocol::INDEX_SET,
+        Protocol::ADD,
+        Protocol::ADD_ASSIGN,
+        Protocol::SUB,
+        Protocol::SUB_ASSIGN,
+        Protocol::MUL,
+        Protocol::MUL_ASSIGN,
+        Protocol::DIV,
+        Protocol::DIV_ASSIGN,
+        Protocol::REM,
+        Protocol::REM_ASSIGN,
+        Protocol::BIT_AND,
+        Protocol::BIT_AND_ASSIGN,
+        Protocol::BIT_XOR,
+        Protocol::BIT_XOR_ASSIGN,
+        Protocol::BIT_OR,
+        Protocol::BIT_OR_ASSIGN,
+        Protocol::SHL,
+        Protocol::SHL_ASSIGN,
+        Protocol::SHR,
+        Protocol::SHR_ASSIGN,
+        Protocol::STRING_DISPLAY,
+        Protocol::STRING_DEBUG,
+        Protocol::INTO_ITER,
+        Protocol::NEXT,
+        Protocol::INTO_FUTURE,
+        Protocol::INTO_TYPE_NAME,
+        Protocol::DROP,
+        Protocol::HASH,
+        Protocol::CMP,
+        Protocol::CLONE,
+    ];
+
+    /// Look up a built in protocol by its name, like `string_display` or `+`.
+    pub(crate) fn from_name(name: &str) -> Option<Protocol> {
+        Self::ALL.iter().copied().find(|p| p.name == name)
+    }
+
+    /// Define a custom protocol with the given name, which native types can
+    /// implement with [Module::inst_fn][crate::Module::inst_fn] just like the
+    /// built in ones.
+    ///
+    /// Custom protocols are invoked from native functions with
+    /// [Value::protocol_call][crate::Value::protocol_call], and from scripts
+    /// by name with `std::any::protocol_call`. The name shouldn't be the same
+    /// as one of the built in protocols, since those take precedence when
+    /// looking a protocol up by name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::{Any, Context, FromValue, Module, Value, Vm};
+    /// use rune::runtime::{Protocol, VmError};
+    /// use std::sync::Arc;
+    ///
+    /// const SERIALIZE: Protocol = Protocol::custom("serialize");
+    ///
+    /// #[derive(Any)]
+    /// struct Point {
+    ///     x: i64,
+    ///     y: i64,
+    /// }
+    ///
+    /// fn serialize(point: &Point) -> String {
+    ///     format!("{},{}", point.x, point.y)
+    /// }
+    ///
+    /// fn save(value: Value) -> Result<String, VmError> {
+    ///     let output = value.protocol_call(SERIALIZE, ())?;
+    ///     Ok(format!("saved {}", String::from_value(output)?))
+    /// }
+    ///
+    /// # fn main() -> rune::Result<()> {
+    /// let mut module = Module::with_crate("storage");
+    /// module.ty::<Point>()?;
+    /// module.function(&["point"], |x: i64, y: i64| Point { x, y })?;
+    /// module.function(&["save"], save)?;
+    /// module.inst_fn(SERIALIZE, serialize)?;
+    ///
+    /// let mut context = Context::with_default_modules()?;
+    /// context.install(&module)?;
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub fn main() {
+    ///             let point = storage::point(1, 2);
+    ///             (std::any::protocol_call(point, "serialize", []), storage::save(point))
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    /// let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+    ///
+    /// let output = <(String, String)>::from_value(vm.call(&["main"], ())?)?;
+    /// assert_eq!(output, (String::from("1,2"), String::from("saved 1,2")));
+    /// # Ok(()) }
+    /// ```
+    pub const fn custom(name: &'static str) -> Protocol {
+        Protocol {
+            name,
+            hash: Self::custom_hash(name),
+        }
+    }
+
+    /// Get the hash of the protocol with the given name, which is either one
+    /// of the built in protocols or a custom protocol.
+    pub(crate) fn hash_of_name(name: &str) -> Hash {
+        match Self::from_name(name) {
+            Some(protocol) => protocol.hash,
+            None => Self::custom_hash(name),
+        }
+    }
+
+    /// Calculate the hash of a custom protocol.
+    ///
+    /// This is a FNV-1a hash of the name, since it needs to be usable in
+    /// constant contexts.
+    const fn custom_hash(name: &str) -> Hash {
+        let bytes = name.as_bytes();
+        let mut hash = CUSTOM_PROTOCOL;
+        let mut n = 0;
+
+        while n < bytes.len() {
+            hash ^= bytes[n] as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+            n += 1;
+        }
+
+        Hash::new(hash)
+    }
 }
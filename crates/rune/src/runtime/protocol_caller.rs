@@ -1,4 +1,7 @@
-use crate::runtime::{GuardedArgs, Protocol, Stack, UnitFn, Value, Vm, VmError, VmErrorKind};
+use crate::runtime::{
+    catch_native, native_span, GuardedArgs, Protocol, Stack, UnitFn, Value, Vm, VmError,
+    VmErrorKind,
+};
 use crate::Hash;
 
 /// Trait used for integrating an instance function call.
@@ -19,10 +22,36 @@ pub(crate) trait ProtocolCaller {
 /// This allocates its own stack and virtual machine for the call.
 pub(crate) struct EnvProtocolCaller;
 
-impl ProtocolCaller for EnvProtocolCaller {
-    fn call_protocol_fn<A>(
-        self,
+impl EnvProtocolCaller {
+    /// Test if the given protocol function is implemented for the target.
+    ///
+    /// Returns `false` if no virtual machine is running that the function can
+    /// be looked up through.
+    pub(crate) fn has_protocol_fn(
+        &self,
         protocol: Protocol,
+        target: &Value,
+    ) -> Result<bool, VmError> {
+        self.has_hash_fn(protocol.hash, target)
+    }
+
+    /// Test if the protocol function with the given hash is implemented for
+    /// the target.
+    pub(crate) fn has_hash_fn(&self, protocol: Hash, target: &Value) -> Result<bool, VmError> {
+        let hash = Hash::instance_function(target.type_hash()?, protocol);
+
+        let found = crate::runtime::env::with(|context, unit| {
+            Ok(matches!(unit.function(hash), Some(UnitFn::Offset { .. }))
+                || context.function(hash).is_some())
+        });
+
+        Ok(found.unwrap_or(false))
+    }
+
+    /// Call the protocol function with the given hash.
+    pub(crate) fn call_hash_fn<A>(
+        self,
+        protocol: Hash,
         target: Value,
         args: A,
     ) -> Result<Value, VmError>
@@ -31,7 +60,7 @@ impl ProtocolCaller for EnvProtocolCaller {
     {
         return crate::runtime::env::with(|context, unit| {
             let count = args.count() + 1;
-            let hash = Hash::instance_function(target.type_hash()?, protocol.hash);
+            let hash = Hash::instance_function(target.type_hash()?, protocol);
 
             if let Some(UnitFn::Offset {
                 offset,
@@ -49,6 +78,14 @@ impl ProtocolCaller for EnvProtocolCaller {
 
                 let mut vm = Vm::with_stack(context.clone(), unit.clone(), stack);
                 vm.set_ip(offset);
+                vm.fuel = crate::runtime::env::fuel();
+                vm.memory = crate::runtime::env::memory();
+                vm.limits = crate::runtime::env::limits()?;
+                vm.deadline = crate::runtime::env::deadline();
+                vm.determinism = crate::runtime::env::determinism();
+                vm.extensions = crate::runtime::env::extensions();
+                vm.globals = crate::runtime::env::globals();
+                vm.collector = crate::runtime::env::collector();
                 return call.call_with_vm(vm);
             }
 
@@ -63,7 +100,8 @@ impl ProtocolCaller for EnvProtocolCaller {
             // Safety: We hold onto the guard until the vm has completed.
             let _guard = unsafe { args.unsafe_into_stack(&mut stack)? };
 
-            handler(&mut stack, count)?;
+            let _span = native_span(context, hash).entered();
+            catch_native(|| handler(&mut stack, count))?;
             Ok(stack.pop()?)
         });
 
@@ -81,6 +119,20 @@ impl ProtocolCaller for EnvProtocolCaller {
     }
 }
 
+impl ProtocolCaller for EnvProtocolCaller {
+    fn call_protocol_fn<A>(
+        self,
+        protocol: Protocol,
+        target: Value,
+        args: A,
+    ) -> Result<Value, VmError>
+    where
+        A: GuardedArgs,
+    {
+        self.call_hash_fn(protocol.hash, target, args)
+    }
+}
+
 impl ProtocolCaller for &mut Vm {
     fn call_protocol_fn<A>(
         self,
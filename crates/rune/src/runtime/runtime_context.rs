@@ -1,4 +1,5 @@
 use crate::collections::HashMap;
+use crate::compile::ContextSignature;
 use crate::macros::{MacroContext, TokenStream};
 use crate::runtime::{ConstValue, Stack, VmError};
 use crate::Hash;
@@ -18,22 +19,31 @@ pub(crate) type MacroHandler =
 /// * Declared functions.
 /// * Declared instance functions.
 /// * Built-in type checks.
+///
+/// The tables of the context are immutable and reference counted, so cloning
+/// it is cheap. Together with an `Arc<Unit>`, an `Arc<RuntimeContext>` is all
+/// that's needed to construct a [Vm][crate::Vm], which makes it cheap to
+/// construct one for every task or request.
 #[derive(Default, Clone)]
 pub struct RuntimeContext {
     /// Registered native function handlers.
-    functions: HashMap<Hash, Arc<FunctionHandler>>,
+    functions: Arc<HashMap<Hash, Arc<FunctionHandler>>>,
     /// Named constant values
-    constants: HashMap<Hash, ConstValue>,
+    constants: Arc<HashMap<Hash, ConstValue>>,
+    /// Signatures of the registered native functions.
+    signatures: Arc<HashMap<Hash, ContextSignature>>,
 }
 
 impl RuntimeContext {
     pub(crate) fn new(
-        functions: HashMap<Hash, Arc<FunctionHandler>>,
-        constants: HashMap<Hash, ConstValue>,
+        functions: Arc<HashMap<Hash, Arc<FunctionHandler>>>,
+        constants: Arc<HashMap<Hash, ConstValue>>,
+        signatures: Arc<HashMap<Hash, ContextSignature>>,
     ) -> Self {
         Self {
             functions,
             constants,
+            signatures,
         }
     }
 
@@ -42,6 +52,11 @@ impl RuntimeContext {
         self.functions.get(&hash)
     }
 
+    /// Lookup the signature of the given native function in the context.
+    pub fn signature(&self, hash: Hash) -> Option<&ContextSignature> {
+        self.signatures.get(&hash)
+    }
+
     /// Read a constant value from the unit.
     pub fn constant(&self, hash: Hash) -> Option<&ConstValue> {
         self.constants.get(&hash)
@@ -0,0 +1,287 @@
+use crate::collections::BTreeMap;
+use crate::runtime::{
+    Bytes, FromValue, Object, Range, RangeLimits, Rtti, Shared, StaticString, Struct, ToValue,
+    Tuple, TupleStruct, UnitStruct, Value, Variant, VariantData, VariantRtti, Vec, VmError,
+    VmErrorKind,
+};
+use crate::Hash;
+use std::sync::Arc;
+use std::vec;
+
+/// A deep copy of a value which is `Send + Sync`, and can be moved to another
+/// thread.
+///
+/// Values are reference counted without synchronization, so they can't be
+/// sent across threads directly. A value produced by a virtual machine on one
+/// thread can instead be copied into a `SendValue`, sent to another thread
+/// and converted back into a value there.
+///
+/// Values which are aliased are copied once for every reference to them, and
+/// values which can't be copied like functions, futures and external types
+/// can't be converted.
+///
+/// # Examples
+///
+/// ```
+/// use rune::{FromValue, Vm};
+/// use rune::runtime::SendValue;
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// # fn main() -> rune::Result<()> {
+/// let mut sources = rune::sources! {
+///     entry => {
+///         pub fn main() {
+///             #{ numbers: [1, 2, 3], name: "rune" }
+///         }
+///     }
+/// };
+///
+/// let unit = rune::prepare(&mut sources).build()?;
+/// let mut vm = Vm::without_runtime(Arc::new(unit));
+///
+/// let value = SendValue::from_value(&vm.call(&["main"], ())?)?;
+///
+/// let numbers = thread::spawn(move || -> rune::Result<Vec<i64>> {
+///     let object = rune::runtime::Object::from_value(value.into_value())?;
+///     Ok(Vec::<i64>::from_value(object.get("numbers").unwrap().clone())?)
+/// }).join().unwrap()?;
+///
+/// assert_eq!(numbers, vec![1, 2, 3]);
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum SendValue {
+    /// The unit value.
+    Unit,
+    /// A byte.
+    Byte(u8),
+    /// A character.
+    Char(char),
+    /// A boolean.
+    Bool(bool),
+    /// An integer.
+    Integer(i64),
+    /// A float.
+    Float(f64),
+    /// A type.
+    Type(Hash),
+    /// A string.
+    String(String),
+    /// A static string.
+    StaticString(Arc<StaticString>),
+    /// A byte string.
+    Bytes(Bytes),
+    /// A vector of values.
+    Vec(vec::Vec<SendValue>),
+    /// An anonymous tuple.
+    Tuple(Box<[SendValue]>),
+    /// An anonymous object.
+    Object(BTreeMap<String, SendValue>),
+    /// A range.
+    Range(Option<Box<SendValue>>, Option<Box<SendValue>>, RangeLimits),
+    /// An option.
+    Option(Option<Box<SendValue>>),
+    /// A result.
+    Result(Result<Box<SendValue>, Box<SendValue>>),
+    /// An empty struct.
+    UnitStruct(Arc<Rtti>),
+    /// A tuple struct.
+    TupleStruct(Arc<Rtti>, Box<[SendValue]>),
+    /// A struct with named fields.
+    Struct(Arc<Rtti>, BTreeMap<String, SendValue>),
+    /// An empty variant.
+    UnitVariant(Arc<VariantRtti>),
+    /// A tuple variant.
+    TupleVariant(Arc<VariantRtti>, Box<[SendValue]>),
+    /// A variant with named fields.
+    StructVariant(Arc<VariantRtti>, BTreeMap<String, SendValue>),
+}
+
+impl SendValue {
+    /// Copy a value into a sendable value.
+    pub fn from_value(value: &Value) -> Result<Self, VmError> {
+        return Ok(match value {
+            Value::Unit => Self::Unit,
+            Value::Byte(b) => Self::Byte(*b),
+            Value::Char(c) => Self::Char(*c),
+            Value::Bool(b) => Self::Bool(*b),
+            Value::Integer(n) => Self::Integer(*n),
+            Value::Float(n) => Self::Float(*n),
+            Value::Type(hash) => Self::Type(*hash),
+            Value::String(s) => Self::String(s.borrow_ref()?.clone()),
+            Value::StaticString(s) => Self::StaticString(s.clone()),
+            Value::Bytes(b) => Self::Bytes(b.borrow_ref()?.clone()),
+            Value::Vec(vec) => Self::Vec(values_from_value(&vec.borrow_ref()?)?.into_vec()),
+            Value::Tuple(tuple) => Self::Tuple(values_from_value(&tuple.borrow_ref()?)?),
+            Value::Object(object) => Self::Object(object_from_value(&*object.borrow_ref()?)?),
+            Value::Range(range) => {
+                let range = range.borrow_ref()?;
+                Self::Range(
+                    option_from_value(range.start.as_ref())?,
+                    option_from_value(range.end.as_ref())?,
+                    range.limits,
+                )
+            }
+            Value::Option(option) => {
+                Self::Option(option_from_value(option.borrow_ref()?.as_ref())?)
+            }
+            Value::Result(result) => Self::Result(match &*result.borrow_ref()? {
+                Ok(value) => Ok(Box::new(Self::from_value(value)?)),
+                Err(value) => Err(Box::new(Self::from_value(value)?)),
+            }),
+            Value::UnitStruct(data) => Self::UnitStruct(data.borrow_ref()?.rtti.clone()),
+            Value::TupleStruct(data) => {
+                let data = data.borrow_ref()?;
+                Self::TupleStruct(data.rtti.clone(), values_from_value(&data.data)?)
+            }
+            Value::Struct(data) => {
+                let data = data.borrow_ref()?;
+                Self::Struct(data.rtti.clone(), object_from_value(&data.data)?)
+            }
+            Value::Variant(variant) => {
+                let variant = variant.borrow_ref()?;
+                let rtti = variant.rtti.clone();
+
+                match &variant.data {
+                    VariantData::Unit => Self::UnitVariant(rtti),
+                    VariantData::Tuple(tuple) => {
+                        Self::TupleVariant(rtti, values_from_value(tuple)?)
+                    }
+                    VariantData::Struct(object) => {
+                        Self::StructVariant(rtti, object_from_value(object)?)
+                    }
+                }
+            }
+            value => {
+                return Err(VmError::from(VmErrorKind::SendNotSupported {
+                    actual: value.type_info()?,
+                }))
+            }
+        });
+
+        fn values_from_value(values: &[Value]) -> Result<Box<[SendValue]>, VmError> {
+            let mut output = vec::Vec::with_capacity(values.len());
+
+            for value in values {
+                output.push(SendValue::from_value(value)?);
+            }
+
+            Ok(output.into_boxed_slice())
+        }
+
+        fn object_from_value(object: &Object) -> Result<BTreeMap<String, SendValue>, VmError> {
+            let mut output = BTreeMap::new();
+
+            for (key, value) in object {
+                output.insert(key.clone(), SendValue::from_value(value)?);
+            }
+
+            Ok(output)
+        }
+
+        fn option_from_value(value: Option<&Value>) -> Result<Option<Box<SendValue>>, VmError> {
+            Ok(match value {
+                Some(value) => Some(Box::new(SendValue::from_value(value)?)),
+                None => None,
+            })
+        }
+    }
+
+    /// Convert into virtual machine value.
+    ///
+    /// We provide this associated method since a sendable value can be
+    /// converted into a value infallibly, which is not captured by the trait
+    /// otherwise.
+    pub fn into_value(self) -> Value {
+        return match self {
+            Self::Unit => Value::Unit,
+            Self::Byte(b) => Value::Byte(b),
+            Self::Char(c) => Value::Char(c),
+            Self::Bool(b) => Value::Bool(b),
+            Self::Integer(n) => Value::Integer(n),
+            Self::Float(n) => Value::Float(n),
+            Self::Type(hash) => Value::Type(hash),
+            Self::String(s) => Value::String(Shared::tracked(s)),
+            Self::StaticString(s) => Value::StaticString(s),
+            Self::Bytes(b) => Value::Bytes(Shared::tracked(b)),
+            Self::Vec(vec) => {
+                let mut v = Vec::with_capacity(vec.len());
+
+                for value in vec {
+                    v.push(value.into_value());
+                }
+
+                Value::Vec(Shared::tracked(v))
+            }
+            Self::Tuple(tuple) => Value::Tuple(Shared::tracked(tuple_into_value(tuple))),
+            Self::Object(object) => Value::Object(Shared::tracked(object_into_value(object))),
+            Self::Range(start, end, limits) => Value::Range(Shared::tracked(Range::new(
+                start.map(|value| value.into_value()),
+                end.map(|value| value.into_value()),
+                limits,
+            ))),
+            Self::Option(option) => {
+                Value::Option(Shared::tracked(option.map(|some| some.into_value())))
+            }
+            Self::Result(result) => Value::Result(Shared::tracked(match result {
+                Ok(value) => Ok(value.into_value()),
+                Err(value) => Err(value.into_value()),
+            })),
+            Self::UnitStruct(rtti) => Value::UnitStruct(Shared::tracked(UnitStruct { rtti })),
+            Self::TupleStruct(rtti, tuple) => Value::TupleStruct(Shared::tracked(TupleStruct {
+                rtti,
+                data: tuple_into_value(tuple),
+            })),
+            Self::Struct(rtti, object) => Value::Struct(Shared::tracked(Struct {
+                rtti,
+                data: object_into_value(object),
+            })),
+            Self::UnitVariant(rtti) => Value::Variant(Shared::tracked(Variant {
+                rtti,
+                data: VariantData::Unit,
+            })),
+            Self::TupleVariant(rtti, tuple) => Value::Variant(Shared::tracked(Variant {
+                rtti,
+                data: VariantData::Tuple(tuple_into_value(tuple)),
+            })),
+            Self::StructVariant(rtti, object) => Value::Variant(Shared::tracked(Variant {
+                rtti,
+                data: VariantData::Struct(object_into_value(object)),
+            })),
+        };
+
+        fn tuple_into_value(data: Box<[SendValue]>) -> Tuple {
+            let mut t = vec::Vec::with_capacity(data.len());
+
+            for value in vec::Vec::from(data) {
+                t.push(value.into_value());
+            }
+
+            Tuple::from(t)
+        }
+
+        fn object_into_value(data: BTreeMap<String, SendValue>) -> Object {
+            let mut object = Object::with_capacity(data.len());
+
+            for (key, value) in data {
+                object.insert(key, value.into_value());
+            }
+
+            object
+        }
+    }
+}
+
+impl FromValue for SendValue {
+    fn from_value(value: Value) -> Result<Self, VmError> {
+        SendValue::from_value(&value)
+    }
+}
+
+impl ToValue for SendValue {
+    fn to_value(self) -> Result<Value, VmError> {
+        Ok(SendValue::into_value(self))
+    }
+}
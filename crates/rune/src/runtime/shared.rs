@@ -1,17 +1,21 @@
+use crate::runtime::gc::{Collector, Trace};
 use crate::runtime::{
-    Access, AccessError, AccessKind, AnyObj, AnyObjError, BorrowMut, BorrowRef, RawAccessGuard,
+    Access, AccessError, AccessKind, AnyObj, AnyObjError, BorrowMut, BorrowRef, Charge,
+    RawAccessGuard, Struct, TupleStruct, UnitStruct, Value, Variant,
 };
-use crate::{Any, Hash};
-use std::any;
+use crate::Any;
+use std::any::{self, TypeId};
 use std::cell::{Cell, UnsafeCell};
 use std::fmt;
 use std::future::Future;
+use std::marker;
 use std::mem;
 use std::mem::ManuallyDrop;
 use std::ops;
 use std::pin::Pin;
 use std::process;
 use std::ptr;
+use std::rc::Rc;
 use std::task::{Context, Poll};
 
 /// A shared value.
@@ -25,6 +29,9 @@ impl<T> Shared<T> {
         let inner = Box::leak(Box::new(SharedBox {
             access: Access::new(false),
             count: Cell::new(1),
+            weak: Cell::new(1),
+            tracking: Cell::new(None),
+            finalizer: Cell::new(None),
             data: data.into(),
         }));
 
@@ -33,6 +40,77 @@ impl<T> Shared<T> {
         }
     }
 
+    /// Construct a new shared value which is tracked by the virtual machine
+    /// that is currently running.
+    ///
+    /// Its allocation is charged to the memory of the virtual machine if it's
+    /// limited, it's tracked by its cycle collector if enabled, and values of
+    /// script types have their [Protocol::DROP] function called once they go
+    /// away.
+    ///
+    /// [Protocol::DROP]: crate::runtime::Protocol::DROP
+    pub(crate) fn tracked(data: T) -> Self
+    where
+        T: 'static,
+    {
+        let this = Self::new(data);
+
+        // Safety: we just allocated the box.
+        let inner = unsafe { this.inner.as_ref() };
+        inner.finalizer.set(finalizer::<T>());
+
+        let (memory, collector) = crate::runtime::env::allocator();
+
+        let collector = match (crate::runtime::gc::vtable::<T>(), collector) {
+            (Some(vtable), Some(collector)) => {
+                collector.track(RawShared {
+                    ptr: this.inner.cast(),
+                    vtable,
+                });
+
+                Some(collector)
+            }
+            _ => None,
+        };
+
+        if memory.is_some() || collector.is_some() {
+            inner.tracking.set(Some(Box::new(Tracking {
+                charge: Charge::new(memory, mem::size_of::<SharedBox<T>>()),
+                collector,
+            })));
+        }
+
+        this
+    }
+
+    /// Update the number of heap bytes owned by the shared value, which is
+    /// charged to the memory of the virtual machine that is currently running
+    /// in addition to the allocation of the value itself.
+    pub(crate) fn set_heap_size(&self, heap: usize) {
+        // Safety: the inner box is live for as long as we hold a reference
+        // to it, and the tracking is only accessed through shared references.
+        let inner = unsafe { self.inner.as_ref() };
+
+        let tracking = match inner.tracking.take() {
+            Some(tracking) => tracking,
+            None => Box::new(Tracking {
+                charge: Charge::new(None, 0),
+                collector: None,
+            }),
+        };
+
+        tracking
+            .charge
+            .resize(mem::size_of::<SharedBox<T>>() + heap);
+        inner.tracking.set(Some(tracking));
+    }
+
+    /// Get the address of the shared box, which identifies the value among all
+    /// handles that refer to it.
+    pub(crate) fn addr(&self) -> usize {
+        self.inner.as_ptr() as usize
+    }
+
     /// Return a debug formatter, that when printed will display detailed
     /// diagnostics of this shared type.
     pub fn debug(&self) -> SharedDebug<'_, T> {
@@ -273,6 +351,39 @@ impl<T> Shared<T> {
 }
 
 impl<T: ?Sized> Shared<T> {
+    /// Construct a weak reference to the shared value, which doesn't keep it
+    /// alive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::runtime::Shared;
+    ///
+    /// let shared = Shared::new(vec![1, 2, 3]);
+    /// let weak = shared.downgrade();
+    ///
+    /// assert!(weak.upgrade().is_some());
+    /// drop(shared);
+    /// assert!(weak.upgrade().is_none());
+    /// ```
+    pub fn downgrade(&self) -> Weak<T> {
+        // Safety: the inner box is live for as long as we hold a reference to
+        // it.
+        unsafe {
+            SharedBox::inc_weak(self.inner.as_ptr());
+        }
+
+        Weak { inner: self.inner }
+    }
+
+    /// Take the finalizer of the shared value so that it's not called once
+    /// the value is dropped, returning `true` if there was one.
+    pub(crate) fn take_finalizer(&self) -> bool {
+        // Safety: the inner box is live for as long as we hold a reference to
+        // it.
+        unsafe { self.inner.as_ref().finalizer.take().is_some() }
+    }
+
     /// Get a reference to the interior value while checking for shared access.
     ///
     /// This prevents other exclusive accesses from being performed while the
@@ -442,6 +553,9 @@ impl Shared<AnyObj> {
         let inner = ptr::NonNull::from(Box::leak(Box::new(SharedBox {
             access: Access::new(true),
             count: Cell::new(2),
+            weak: Cell::new(1),
+            tracking: Cell::new(None),
+            finalizer: Cell::new(None),
             data: any.into(),
         })));
 
@@ -478,7 +592,7 @@ impl Shared<AnyObj> {
             // exclusive access (see above).
             let any = ptr::read(inner.data.get());
 
-            let expected = Hash::from_type_id(any::TypeId::of::<T>());
+            let expected = any::TypeId::of::<T>();
 
             let (e, any) = match any.raw_take(expected) {
                 Ok(value) => return Ok(*Box::from_raw(value as *mut T)),
@@ -519,7 +633,7 @@ impl Shared<AnyObj> {
         unsafe {
             let inner = self.inner.as_ref();
             let guard = inner.access.shared(AccessKind::Any)?;
-            let expected = Hash::from_type_id(any::TypeId::of::<T>());
+            let expected = any::TypeId::of::<T>();
 
             let data = match (*inner.data.get()).raw_as_ptr(expected) {
                 Ok(data) => data,
@@ -547,7 +661,7 @@ impl Shared<AnyObj> {
         unsafe {
             let inner = self.inner.as_ref();
             let guard = inner.access.exclusive(AccessKind::Any)?;
-            let expected = Hash::from_type_id(any::TypeId::of::<T>());
+            let expected = any::TypeId::of::<T>();
 
             let data = match (*inner.data.get()).raw_as_mut(expected) {
                 Ok(data) => data,
@@ -590,7 +704,7 @@ impl Shared<AnyObj> {
             let (data, guard) = {
                 let inner = self.inner.as_ref();
                 let guard = inner.access.shared(kind)?;
-                let expected = Hash::from_type_id(any::TypeId::of::<T>());
+                let expected = any::TypeId::of::<T>();
 
                 match (*inner.data.get()).raw_as_ptr(expected) {
                     Ok(data) => (data, guard),
@@ -642,7 +756,7 @@ impl Shared<AnyObj> {
             let (data, guard) = {
                 let inner = self.inner.as_ref();
                 let guard = inner.access.exclusive(kind)?;
-                let expected = Hash::from_type_id(any::TypeId::of::<T>());
+                let expected = any::TypeId::of::<T>();
 
                 match (*inner.data.get()).raw_as_mut(expected) {
                     Ok(data) => (data, guard),
@@ -709,6 +823,69 @@ where
     }
 }
 
+/// A weak reference to a shared value, constructed through
+/// [downgrade][Shared::downgrade].
+///
+/// Weak references don't keep the value alive, so they can be used to refer
+/// back to a value without creating a reference cycle.
+pub struct Weak<T: ?Sized> {
+    inner: ptr::NonNull<SharedBox<T>>,
+}
+
+impl<T: ?Sized> Weak<T> {
+    /// Get a strong reference to the shared value, unless it has already been
+    /// dropped.
+    pub fn upgrade(&self) -> Option<Shared<T>> {
+        // Safety: the inner box is live for as long as we hold a weak
+        // reference to it.
+        unsafe {
+            if self.inner.as_ref().count.get() == 0 {
+                return None;
+            }
+
+            SharedBox::inc(self.inner.as_ptr());
+        }
+
+        Some(Shared { inner: self.inner })
+    }
+
+    /// Test if the shared value has been dropped.
+    pub fn is_dropped(&self) -> bool {
+        // Safety: the inner box is live for as long as we hold a weak
+        // reference to it.
+        unsafe { self.inner.as_ref().count.get() == 0 }
+    }
+
+    /// Test if two weak references refer to the same shared value.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.inner.as_ptr() as *const () == other.inner.as_ptr() as *const ()
+    }
+}
+
+impl<T: ?Sized> Clone for Weak<T> {
+    fn clone(&self) -> Self {
+        unsafe {
+            SharedBox::inc_weak(self.inner.as_ptr());
+        }
+
+        Self { inner: self.inner }
+    }
+}
+
+impl<T: ?Sized> Drop for Weak<T> {
+    fn drop(&mut self) {
+        unsafe {
+            SharedBox::dec_weak(self.inner.as_ptr());
+        }
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for Weak<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "(Weak)")
+    }
+}
+
 /// A debug helper that prints detailed diagnostics on the type being debugged.
 ///
 /// Constructed using [debug][Shared::debug].
@@ -748,11 +925,29 @@ struct SharedBox<T: ?Sized> {
     access: Access,
     /// The number of strong references to the shared data.
     count: Cell<usize>,
+    /// The number of weak references to the shared box, plus one which is
+    /// held collectively by all strong references.
+    weak: Cell<usize>,
+    /// How the shared data is tracked by the virtual machine which allocated
+    /// it, if at all.
+    tracking: Cell<Option<Box<Tracking>>>,
+    /// Called once the last strong reference to the shared data is dropped,
+    /// unless it has already been called.
+    finalizer: Cell<Option<Finalizer>>,
     /// The value being held. Guarded by the `access` field to determine if it
     /// can be access shared or exclusively.
     data: UnsafeCell<T>,
 }
 
+/// The memory charged for a shared value and the cycle collector tracking it,
+/// which are kept out of line since most values aren't tracked.
+struct Tracking {
+    /// The memory charged for the shared data.
+    charge: Charge,
+    /// The cycle collector tracking the shared data, if any.
+    collector: Option<Rc<Collector>>,
+}
+
 impl<T: ?Sized> SharedBox<T> {
     /// Increment the reference count of the inner value.
     unsafe fn inc(this: *const Self) {
@@ -785,28 +980,276 @@ impl<T: ?Sized> SharedBox<T> {
             return false;
         }
 
-        let this = Box::from_raw(this);
+        if !(*this).access.is_taken() {
+            if let Some(finalize) = (*this).finalizer.take() {
+                // NB: The value is kept alive while it's being finalized, and
+                // is dropped as usual once that's done. Unless the finalizer
+                // holds onto it, in which case it simply lives on.
+                (*this).count.set(1);
+                finalize(ptr::NonNull::new_unchecked(this).cast());
+                return false;
+            }
+        }
 
-        if this.access.is_taken() {
-            // NB: This prevents the inner `T` from being dropped in case it
-            // has already been taken (as indicated by `is_taken`).
-            //
-            // If it has been taken, the shared box contains invalid memory.
-            drop(std::mem::transmute::<_, Box<SharedBox<ManuallyDrop<T>>>>(
-                this,
-            ));
-        } else {
+        if let Some(tracking) = (*this).tracking.take() {
+            if let Some(collector) = &tracking.collector {
+                collector.untrack(this as *const () as usize);
+            }
+        }
+
+        // NB: This prevents the inner `T` from being dropped in case it has
+        // already been taken (as indicated by `is_taken`).
+        //
+        // If it has been taken, the shared box contains invalid memory.
+        if !(*this).access.is_taken() {
             // NB: At the point of the final drop, no on else should be using
             // this.
             debug_assert!(
-                this.access.is_exclusive(),
+                (*this).access.is_exclusive(),
                 "expected exclusive, but was: {:?}",
-                this.access
+                (*this).access
             );
+
+            // NB: The data is dropped with the last strong reference, while
+            // the box itself is kept around until the last weak reference to
+            // it is dropped.
+            ptr::drop_in_place((*this).data.get());
         }
 
+        Self::dec_weak(this);
         true
     }
+
+    /// Increment the weak reference count of the shared box.
+    unsafe fn inc_weak(this: *const Self) {
+        let weak = (*this).weak.get();
+
+        if weak == 0 || weak == usize::MAX {
+            process::abort();
+        }
+
+        (*this).weak.set(weak + 1);
+    }
+
+    /// Decrement the weak reference count of the shared box, and free it if it
+    /// has reached zero.
+    ///
+    /// # Safety
+    ///
+    /// The data of the shared box must already have been dropped or taken
+    /// once the weak reference count reaches zero.
+    unsafe fn dec_weak(this: *mut Self) {
+        let weak = (*this).weak.get();
+
+        if weak == 0 {
+            process::abort();
+        }
+
+        let weak = weak - 1;
+        (*this).weak.set(weak);
+
+        if weak == 0 {
+            drop(Box::from_raw(this as *mut SharedBox<ManuallyDrop<T>>));
+        }
+    }
+}
+
+/// A type-erased shared value tracked by a cycle collector.
+#[derive(Clone, Copy)]
+pub(crate) struct RawShared {
+    ptr: ptr::NonNull<()>,
+    vtable: &'static RawSharedVTable,
+}
+
+impl RawShared {
+    /// Get the vtable for shared values of the given type.
+    pub(crate) fn vtable<T>() -> &'static RawSharedVTable
+    where
+        T: Trace,
+    {
+        &VTable::<T>::VTABLE
+    }
+
+    /// Get the address of the shared box.
+    pub(crate) fn addr(&self) -> usize {
+        self.ptr.as_ptr() as usize
+    }
+
+    /// Get the number of strong references to the shared value.
+    ///
+    /// # Safety
+    ///
+    /// The shared value must be live.
+    pub(crate) unsafe fn count(&self) -> usize {
+        (self.vtable.count)(self.ptr)
+    }
+
+    /// Visit every value held by the shared value, returning `false` if it
+    /// can't currently be accessed.
+    ///
+    /// # Safety
+    ///
+    /// The shared value must be live.
+    pub(crate) unsafe fn trace(&self, visit: &mut dyn FnMut(&Value)) -> bool {
+        (self.vtable.trace)(self.ptr, visit)
+    }
+
+    /// Move every value held by the shared value out into `out`, returning
+    /// `false` if it can't currently be accessed.
+    ///
+    /// # Safety
+    ///
+    /// The shared value must be live.
+    pub(crate) unsafe fn clear(&self, out: &mut Vec<Value>) -> bool {
+        (self.vtable.clear)(self.ptr, out)
+    }
+
+    /// Take the finalizer of the shared value, so that it's not called once
+    /// the value is dropped.
+    ///
+    /// # Safety
+    ///
+    /// The shared value must be live.
+    pub(crate) unsafe fn take_finalizer(&self) {
+        (self.vtable.take_finalizer)(self.ptr)
+    }
+
+    /// Increment the reference count of the shared value.
+    ///
+    /// # Safety
+    ///
+    /// The shared value must be live.
+    pub(crate) unsafe fn inc(&self) {
+        (self.vtable.inc)(self.ptr)
+    }
+
+    /// Decrement the reference count of the shared value, freeing it if it
+    /// reaches zero.
+    ///
+    /// # Safety
+    ///
+    /// The shared value must be live, and must not be used again if this
+    /// releases the last reference to it.
+    pub(crate) unsafe fn dec(&self) {
+        (self.vtable.dec)(self.ptr)
+    }
+}
+
+/// The vtable of a [RawShared].
+pub(crate) struct RawSharedVTable {
+    count: unsafe fn(ptr::NonNull<()>) -> usize,
+    trace: unsafe fn(ptr::NonNull<()>, &mut dyn FnMut(&Value)) -> bool,
+    clear: unsafe fn(ptr::NonNull<()>, &mut Vec<Value>) -> bool,
+    take_finalizer: unsafe fn(ptr::NonNull<()>),
+    inc: unsafe fn(ptr::NonNull<()>),
+    dec: unsafe fn(ptr::NonNull<()>),
+}
+
+struct VTable<T>(marker::PhantomData<T>);
+
+impl<T> VTable<T>
+where
+    T: Trace,
+{
+    const VTABLE: RawSharedVTable = RawSharedVTable {
+        count: count_impl::<T>,
+        trace: trace_impl::<T>,
+        clear: clear_impl::<T>,
+        take_finalizer: take_finalizer_impl::<T>,
+        inc: inc_impl::<T>,
+        dec: dec_impl::<T>,
+    };
+}
+
+unsafe fn count_impl<T>(ptr: ptr::NonNull<()>) -> usize {
+    ptr.cast::<SharedBox<T>>().as_ref().count.get()
+}
+
+unsafe fn trace_impl<T>(ptr: ptr::NonNull<()>, visit: &mut dyn FnMut(&Value)) -> bool
+where
+    T: Trace,
+{
+    let inner = ptr.cast::<SharedBox<T>>().as_ref();
+
+    let guard = match inner.access.shared(AccessKind::Any) {
+        Ok(guard) => guard,
+        Err(..) => return false,
+    };
+
+    (*inner.data.get()).trace(visit);
+    drop(guard);
+    true
+}
+
+unsafe fn clear_impl<T>(ptr: ptr::NonNull<()>, out: &mut Vec<Value>) -> bool
+where
+    T: Trace,
+{
+    let inner = ptr.cast::<SharedBox<T>>().as_ref();
+
+    let guard = match inner.access.exclusive(AccessKind::Any) {
+        Ok(guard) => guard,
+        Err(..) => return false,
+    };
+
+    (*inner.data.get()).clear(out);
+    drop(guard);
+    true
+}
+
+unsafe fn take_finalizer_impl<T>(ptr: ptr::NonNull<()>) {
+    ptr.cast::<SharedBox<T>>().as_ref().finalizer.take();
+}
+
+unsafe fn inc_impl<T>(ptr: ptr::NonNull<()>) {
+    SharedBox::inc(ptr.cast::<SharedBox<T>>().as_ptr());
+}
+
+unsafe fn dec_impl<T>(ptr: ptr::NonNull<()>) {
+    SharedBox::dec(ptr.cast::<SharedBox<T>>().as_ptr());
+}
+
+/// Finalizes a shared value which has been resurrected with a single strong
+/// reference.
+type Finalizer = unsafe fn(ptr::NonNull<()>);
+
+/// Get the finalizer for shared values of the given type, if any.
+///
+/// Values of script types have their [Protocol::DROP] function called once
+/// they go away.
+///
+/// [Protocol::DROP]: crate::runtime::Protocol::DROP
+fn finalizer<T>() -> Option<Finalizer>
+where
+    T: 'static,
+{
+    macro_rules! finalized {
+        ($($ty:ty),* $(,)?) => {
+            $(
+                if TypeId::of::<T>() == TypeId::of::<$ty>() {
+                    return Some(finalize_impl::<$ty>);
+                }
+            )*
+        };
+    }
+
+    finalized!(UnitStruct, TupleStruct, Struct, Variant);
+    return None;
+
+    unsafe fn finalize_impl<T>(ptr: ptr::NonNull<()>)
+    where
+        Value: From<Shared<T>>,
+    {
+        let value = Value::from(Shared {
+            inner: ptr.cast::<SharedBox<T>>(),
+        });
+
+        // NB: errors raised by drop functions have no caller to be returned
+        // to, so they're recorded on the virtual machine instead.
+        if let Err(error) = crate::runtime::finalize::call_drop(value) {
+            crate::runtime::env::drop_error(error);
+        }
+    }
 }
 
 type DropFn = unsafe fn(*const ());
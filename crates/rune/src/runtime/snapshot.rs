@@ -0,0 +1,770 @@
+//! Snapshots of suspended executions.
+//!
+//! A snapshot records the virtual machines of an execution together with every
+//! value reachable from their stacks. Shared values are recorded once in a heap
+//! table and referenced by index, so values which are aliased when the snapshot
+//! is taken are still aliased once it's restored.
+
+use crate::collections::HashMap;
+use crate::runtime::{
+    AccessError, Bytes, Call, CallFrame, ExecutionState, Function, Generator, GeneratorState,
+    Object, Range, RangeLimits, Rtti, RuntimeContext, Shared, Stack, StaticString, Stream, Struct,
+    Tuple, TupleStruct, Unit, UnitStruct, Value, Variant, VariantData, VariantRtti, Vec,
+    VerifyError, Vm, VmExecution,
+};
+use crate::Hash;
+use serde::{Deserialize, Serialize};
+use std::hash::Hasher as _;
+use std::sync::Arc;
+use thiserror::Error;
+use twox_hash::XxHash64;
+
+/// The version of the snapshot format.
+const VERSION: u32 = 1;
+
+/// An error raised when taking or restoring a snapshot.
+#[derive(Debug, Error)]
+#[error("{kind}")]
+pub struct SnapshotError {
+    kind: SnapshotErrorKind,
+}
+
+impl From<SnapshotErrorKind> for SnapshotError {
+    fn from(kind: SnapshotErrorKind) -> Self {
+        Self { kind }
+    }
+}
+
+impl From<AccessError> for SnapshotError {
+    fn from(error: AccessError) -> Self {
+        Self::from(SnapshotErrorKind::AccessError { error })
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum SnapshotErrorKind {
+    #[error("values of type `{type_info}` can't be snapshotted")]
+    Unsupported { type_info: String },
+    #[error("cyclic values can't be snapshotted")]
+    Cycle,
+    #[error("value is not accessible: {error}")]
+    AccessError {
+        #[source]
+        error: AccessError,
+    },
+    #[error("execution refers to a unit other than the one it's running")]
+    ForeignUnit,
+    #[error("unsupported snapshot version {version}")]
+    Version { version: u32 },
+    #[error("snapshot was taken against a different unit")]
+    UnitMismatch,
+    #[error("missing native function with hash `{hash}`")]
+    MissingFunction { hash: Hash },
+    #[error("missing type with hash `{hash}`")]
+    MissingType { hash: Hash },
+    #[error("malformed snapshot: {error}")]
+    Malformed {
+        #[source]
+        error: bincode::Error,
+    },
+    #[error("malformed snapshot: invalid value reference {index}")]
+    BadReference { index: usize },
+    #[error("malformed snapshot: stack bottom {stack_bottom} is out of bounds")]
+    BadStackBottom { stack_bottom: usize },
+    #[error("malformed snapshot: call frames are out of order")]
+    BadCallFrames,
+    #[error("malformed snapshot: instruction pointer {ip} is out of bounds")]
+    BadInstructionPointer { ip: usize },
+    #[error("unit failed verification: {error}")]
+    Verify {
+        #[source]
+        error: VerifyError,
+    },
+}
+
+/// The serialized form of a function.
+#[derive(Serialize, Deserialize)]
+pub(crate) enum FunctionSnapshot<V> {
+    Handler {
+        hash: Hash,
+    },
+    Offset {
+        offset: usize,
+        call: Call,
+        args: usize,
+        hash: Hash,
+    },
+    Closure {
+        offset: usize,
+        call: Call,
+        args: usize,
+        environment: std::vec::Vec<V>,
+        hash: Hash,
+    },
+    UnitStruct {
+        hash: Hash,
+    },
+    TupleStruct {
+        hash: Hash,
+        args: usize,
+    },
+    UnitVariant {
+        hash: Hash,
+    },
+    TupleVariant {
+        hash: Hash,
+        args: usize,
+    },
+}
+
+/// The serialized form of a value, where shared values refer to the heap.
+#[derive(Serialize, Deserialize)]
+enum ValueSnapshot {
+    Unit,
+    Bool(bool),
+    Byte(u8),
+    Char(char),
+    Integer(i64),
+    Float(f64),
+    Type(Hash),
+    StaticString(String),
+    Heap(usize),
+}
+
+/// The serialized form of a shared value.
+#[derive(Serialize, Deserialize)]
+enum HeapSnapshot {
+    String(String),
+    Bytes(std::vec::Vec<u8>),
+    Vec(std::vec::Vec<ValueSnapshot>),
+    Tuple(std::vec::Vec<ValueSnapshot>),
+    Object(std::vec::Vec<(String, ValueSnapshot)>),
+    Range {
+        start: Option<ValueSnapshot>,
+        end: Option<ValueSnapshot>,
+        closed: bool,
+    },
+    Option(Option<ValueSnapshot>),
+    Result(Result<ValueSnapshot, ValueSnapshot>),
+    GeneratorState(Result<ValueSnapshot, ValueSnapshot>),
+    UnitStruct(Hash),
+    TupleStruct(Hash, std::vec::Vec<ValueSnapshot>),
+    Struct(Hash, std::vec::Vec<(String, ValueSnapshot)>),
+    UnitVariant(Hash),
+    TupleVariant(Hash, std::vec::Vec<ValueSnapshot>),
+    StructVariant(Hash, std::vec::Vec<(String, ValueSnapshot)>),
+    Function(FunctionSnapshot<ValueSnapshot>),
+    Generator(Option<ExecutionSnapshot>),
+    Stream(Option<ExecutionSnapshot>),
+}
+
+#[derive(Serialize, Deserialize)]
+struct VmSnapshot {
+    ip: usize,
+    stack: std::vec::Vec<ValueSnapshot>,
+    stack_bottom: usize,
+    call_frames: std::vec::Vec<(usize, usize)>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ExecutionSnapshot {
+    head: VmSnapshot,
+    resumed: bool,
+    vms: std::vec::Vec<(VmSnapshot, bool)>,
+    interrupted: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    version: u32,
+    fingerprint: u64,
+    heap: std::vec::Vec<HeapSnapshot>,
+    execution: ExecutionSnapshot,
+}
+
+/// Take a snapshot of the given execution.
+pub(crate) fn take<T>(execution: &VmExecution<T>) -> Result<std::vec::Vec<u8>, SnapshotError>
+where
+    T: AsRef<Vm> + AsMut<Vm>,
+{
+    let unit = execution.head.as_ref().unit();
+
+    let mut encoder = Encoder {
+        unit,
+        heap: std::vec::Vec::new(),
+        slots: HashMap::new(),
+    };
+
+    let execution = encoder.execution(
+        execution.head.as_ref(),
+        execution.state,
+        &execution.vms,
+        execution.interrupted,
+    )?;
+
+    let snapshot = Snapshot {
+        version: VERSION,
+        fingerprint: fingerprint(unit),
+        heap: encoder
+            .heap
+            .into_iter()
+            .map(|data| data.ok_or(SnapshotErrorKind::Cycle))
+            .collect::<Result<_, _>>()?,
+        execution,
+    };
+
+    bincode::serialize(&snapshot)
+        .map_err(|error| SnapshotError::from(SnapshotErrorKind::Malformed { error }))
+}
+
+/// Restore an execution from the given snapshot.
+pub(crate) fn restore(
+    context: Arc<RuntimeContext>,
+    unit: Arc<Unit>,
+    bytes: &[u8],
+) -> Result<VmExecution<Vm>, SnapshotError> {
+    let snapshot: Snapshot = bincode::deserialize(bytes)
+        .map_err(|error| SnapshotError::from(SnapshotErrorKind::Malformed { error }))?;
+
+    if snapshot.version != VERSION {
+        return Err(SnapshotError::from(SnapshotErrorKind::Version {
+            version: snapshot.version,
+        }));
+    }
+
+    if snapshot.fingerprint != fingerprint(&unit) {
+        return Err(SnapshotError::from(SnapshotErrorKind::UnitMismatch));
+    }
+
+    // NB: the restored stacks are used as is, so the unit they're run against
+    // must not be trusted any more than the snapshot itself.
+    unit.verify()
+        .map_err(|error| SnapshotError::from(SnapshotErrorKind::Verify { error }))?;
+
+    let mut decoder = Decoder {
+        values: snapshot.heap.iter().map(|_| None).collect(),
+        heap: snapshot.heap.into_iter().map(Some).collect(),
+        context,
+        unit,
+    };
+
+    decoder.execution(snapshot.execution)
+}
+
+/// Fingerprint the instructions of a unit, to detect if a snapshot is
+/// restored against a unit other than the one it was taken from.
+fn fingerprint(unit: &Unit) -> u64 {
+    let mut hasher = XxHash64::default();
+
+    for inst in unit.iter_instructions() {
+        // NB: serializing an instruction into memory can't fail.
+        if let Ok(bytes) = bincode::serialize(&inst) {
+            hasher.write(&bytes);
+        }
+    }
+
+    hasher.finish()
+}
+
+struct Encoder<'a> {
+    unit: &'a Arc<Unit>,
+    /// Encoded shared values. A slot is empty while its value is being
+    /// encoded.
+    heap: std::vec::Vec<Option<HeapSnapshot>>,
+    /// Heap slots indexed by the address of the shared value.
+    slots: HashMap<usize, usize>,
+}
+
+impl Encoder<'_> {
+    fn execution(
+        &mut self,
+        head: &Vm,
+        state: ExecutionState,
+        vms: &[(Vm, ExecutionState)],
+        interrupted: bool,
+    ) -> Result<ExecutionSnapshot, SnapshotError> {
+        let head = self.vm(head)?;
+
+        let vms = vms
+            .iter()
+            .map(|(vm, state)| Ok((self.vm(vm)?, is_resumed(*state))))
+            .collect::<Result<_, SnapshotError>>()?;
+
+        Ok(ExecutionSnapshot {
+            head,
+            resumed: is_resumed(state),
+            vms,
+            interrupted,
+        })
+    }
+
+    fn vm(&mut self, vm: &Vm) -> Result<VmSnapshot, SnapshotError> {
+        if !Arc::ptr_eq(vm.unit(), self.unit) {
+            return Err(SnapshotError::from(SnapshotErrorKind::ForeignUnit));
+        }
+
+        let stack = vm
+            .stack()
+            .iter()
+            .map(|value| self.value(value))
+            .collect::<Result<_, _>>()?;
+
+        let call_frames = vm
+            .call_frames()
+            .iter()
+            .map(|frame| (frame.ip(), frame.stack_bottom()))
+            .collect();
+
+        Ok(VmSnapshot {
+            ip: vm.ip(),
+            stack,
+            stack_bottom: vm.stack().stack_bottom(),
+            call_frames,
+        })
+    }
+
+    fn value(&mut self, value: &Value) -> Result<ValueSnapshot, SnapshotError> {
+        Ok(match value {
+            Value::Unit => ValueSnapshot::Unit,
+            Value::Bool(b) => ValueSnapshot::Bool(*b),
+            Value::Byte(b) => ValueSnapshot::Byte(*b),
+            Value::Char(c) => ValueSnapshot::Char(*c),
+            Value::Integer(n) => ValueSnapshot::Integer(*n),
+            Value::Float(n) => ValueSnapshot::Float(*n),
+            Value::Type(hash) => ValueSnapshot::Type(*hash),
+            Value::StaticString(s) => ValueSnapshot::StaticString(s.as_str().to_owned()),
+            Value::String(s) => self.shared(s, |_, s| Ok(HeapSnapshot::String(s.clone())))?,
+            Value::Bytes(b) => self.shared(b, |_, b| Ok(HeapSnapshot::Bytes(b.to_vec())))?,
+            Value::Vec(vec) => self.shared(vec, |this, vec| {
+                Ok(HeapSnapshot::Vec(this.values(vec.iter())?))
+            })?,
+            Value::Tuple(tuple) => self.shared(tuple, |this, tuple| {
+                Ok(HeapSnapshot::Tuple(this.values(tuple.iter())?))
+            })?,
+            Value::Object(object) => self.shared(object, |this, object| {
+                Ok(HeapSnapshot::Object(this.object(object)?))
+            })?,
+            Value::Range(range) => self.shared(range, |this, range| {
+                Ok(HeapSnapshot::Range {
+                    start: range.start.as_ref().map(|v| this.value(v)).transpose()?,
+                    end: range.end.as_ref().map(|v| this.value(v)).transpose()?,
+                    closed: matches!(range.limits, RangeLimits::Closed),
+                })
+            })?,
+            Value::Option(option) => self.shared(option, |this, option| {
+                Ok(HeapSnapshot::Option(
+                    option.as_ref().map(|v| this.value(v)).transpose()?,
+                ))
+            })?,
+            Value::Result(result) => self.shared(result, |this, result| {
+                Ok(HeapSnapshot::Result(match result {
+                    Ok(value) => Ok(this.value(value)?),
+                    Err(value) => Err(this.value(value)?),
+                }))
+            })?,
+            Value::GeneratorState(state) => self.shared(state, |this, state| {
+                Ok(HeapSnapshot::GeneratorState(match state {
+                    GeneratorState::Yielded(value) => Ok(this.value(value)?),
+                    GeneratorState::Complete(value) => Err(this.value(value)?),
+                }))
+            })?,
+            Value::UnitStruct(data) => {
+                self.shared(data, |_, data| Ok(HeapSnapshot::UnitStruct(data.rtti.hash)))?
+            }
+            Value::TupleStruct(data) => self.shared(data, |this, data| {
+                Ok(HeapSnapshot::TupleStruct(
+                    data.rtti.hash,
+                    this.values(data.data.iter())?,
+                ))
+            })?,
+            Value::Struct(data) => self.shared(data, |this, data| {
+                Ok(HeapSnapshot::Struct(
+                    data.rtti.hash,
+                    this.object(&data.data)?,
+                ))
+            })?,
+            Value::Variant(variant) => self.shared(variant, |this, variant| {
+                let hash = variant.rtti.hash;
+
+                Ok(match &variant.data {
+                    VariantData::Unit => HeapSnapshot::UnitVariant(hash),
+                    VariantData::Tuple(tuple) => {
+                        HeapSnapshot::TupleVariant(hash, this.values(tuple.iter())?)
+                    }
+                    VariantData::Struct(object) => {
+                        HeapSnapshot::StructVariant(hash, this.object(object)?)
+                    }
+                })
+            })?,
+            Value::Function(function) => self.shared(function, |this, function| {
+                let unit = this.unit;
+                Ok(HeapSnapshot::Function(
+                    function.snapshot(unit, |value| this.value(value))?,
+                ))
+            })?,
+            Value::Generator(generator) => self.shared(generator, |this, generator| {
+                Ok(HeapSnapshot::Generator(
+                    generator
+                        .execution
+                        .as_ref()
+                        .map(|e| this.execution(&e.head, e.state, &e.vms, e.interrupted))
+                        .transpose()?,
+                ))
+            })?,
+            Value::Stream(stream) => self.shared(stream, |this, stream| {
+                Ok(HeapSnapshot::Stream(
+                    stream
+                        .execution
+                        .as_ref()
+                        .map(|e| this.execution(&e.head, e.state, &e.vms, e.interrupted))
+                        .transpose()?,
+                ))
+            })?,
+            Value::Future(..) | Value::Format(..) | Value::Iterator(..) | Value::Any(..) => {
+                let type_info = match value.type_info() {
+                    Ok(type_info) => type_info.to_string(),
+                    Err(..) => String::from("?"),
+                };
+
+                return Err(SnapshotError::from(SnapshotErrorKind::Unsupported {
+                    type_info,
+                }));
+            }
+        })
+    }
+
+    fn values<'v>(
+        &mut self,
+        values: impl IntoIterator<Item = &'v Value>,
+    ) -> Result<std::vec::Vec<ValueSnapshot>, SnapshotError> {
+        values.into_iter().map(|value| self.value(value)).collect()
+    }
+
+    fn object(
+        &mut self,
+        object: &Object,
+    ) -> Result<std::vec::Vec<(String, ValueSnapshot)>, SnapshotError> {
+        object
+            .iter()
+            .map(|(key, value)| Ok((key.clone(), self.value(value)?)))
+            .collect()
+    }
+
+    /// Encode a shared value into the heap, unless it's already been encoded.
+    fn shared<T>(
+        &mut self,
+        shared: &Shared<T>,
+        encode: impl FnOnce(&mut Self, &T) -> Result<HeapSnapshot, SnapshotError>,
+    ) -> Result<ValueSnapshot, SnapshotError> {
+        if let Some(index) = self.slots.get(&shared.addr()) {
+            if self.heap[*index].is_none() {
+                return Err(SnapshotError::from(SnapshotErrorKind::Cycle));
+            }
+
+            return Ok(ValueSnapshot::Heap(*index));
+        }
+
+        let index = self.heap.len();
+        self.heap.push(None);
+        self.slots.insert(shared.addr(), index);
+
+        let data = shared.borrow_ref()?;
+        self.heap[index] = Some(encode(self, &data)?);
+        Ok(ValueSnapshot::Heap(index))
+    }
+}
+
+struct Decoder {
+    context: Arc<RuntimeContext>,
+    unit: Arc<Unit>,
+    /// Shared values which haven't been decoded yet.
+    heap: std::vec::Vec<Option<HeapSnapshot>>,
+    /// Shared values which have been decoded.
+    values: std::vec::Vec<Option<Value>>,
+}
+
+impl Decoder {
+    fn execution(&mut self, snapshot: ExecutionSnapshot) -> Result<VmExecution<Vm>, SnapshotError> {
+        let head = self.vm(snapshot.head)?;
+
+        let vms = snapshot
+            .vms
+            .into_iter()
+            .map(|(vm, resumed)| Ok((self.vm(vm)?, execution_state(resumed))))
+            .collect::<Result<_, SnapshotError>>()?;
+
+        Ok(VmExecution {
+            head,
+            state: execution_state(snapshot.resumed),
+            vms,
+            interrupted: snapshot.interrupted,
+        })
+    }
+
+    fn vm(&mut self, snapshot: VmSnapshot) -> Result<Vm, SnapshotError> {
+        if snapshot.stack_bottom > snapshot.stack.len() {
+            return Err(SnapshotError::from(SnapshotErrorKind::BadStackBottom {
+                stack_bottom: snapshot.stack_bottom,
+            }));
+        }
+
+        // Every call frame records the stack bottom of its caller, so they
+        // can't decrease and can't be above the current stack bottom.
+        let mut stack_bottom = 0;
+
+        for &(ip, bottom) in &snapshot.call_frames {
+            self.check_ip(ip)?;
+
+            if bottom < stack_bottom {
+                return Err(SnapshotError::from(SnapshotErrorKind::BadCallFrames));
+            }
+
+            stack_bottom = bottom;
+        }
+
+        if stack_bottom > snapshot.stack_bottom {
+            return Err(SnapshotError::from(SnapshotErrorKind::BadCallFrames));
+        }
+
+        self.check_ip(snapshot.ip)?;
+
+        let stack = self.values(snapshot.stack)?;
+        let stack = Stack::from_parts(stack, snapshot.stack_bottom);
+
+        let mut vm = Vm::with_stack(self.context.clone(), self.unit.clone(), stack);
+        vm.set_ip(snapshot.ip);
+        vm.call_frames = snapshot
+            .call_frames
+            .into_iter()
+            .map(|(ip, stack_bottom)| CallFrame::new(ip, stack_bottom))
+            .collect();
+
+        Ok(vm)
+    }
+
+    fn check_ip(&self, ip: usize) -> Result<(), SnapshotError> {
+        if self.unit.instruction_at(ip).is_none() {
+            return Err(SnapshotError::from(
+                SnapshotErrorKind::BadInstructionPointer { ip },
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn value(&mut self, snapshot: ValueSnapshot) -> Result<Value, SnapshotError> {
+        Ok(match snapshot {
+            ValueSnapshot::Unit => Value::Unit,
+            ValueSnapshot::Bool(b) => Value::Bool(b),
+            ValueSnapshot::Byte(b) => Value::Byte(b),
+            ValueSnapshot::Char(c) => Value::Char(c),
+            ValueSnapshot::Integer(n) => Value::Integer(n),
+            ValueSnapshot::Float(n) => Value::Float(n),
+            ValueSnapshot::Type(hash) => Value::Type(hash),
+            ValueSnapshot::StaticString(s) => Value::StaticString(Arc::new(StaticString::new(s))),
+            ValueSnapshot::Heap(index) => self.shared(index)?,
+        })
+    }
+
+    fn values(
+        &mut self,
+        values: std::vec::Vec<ValueSnapshot>,
+    ) -> Result<std::vec::Vec<Value>, SnapshotError> {
+        values.into_iter().map(|value| self.value(value)).collect()
+    }
+
+    fn object(
+        &mut self,
+        entries: std::vec::Vec<(String, ValueSnapshot)>,
+    ) -> Result<Object, SnapshotError> {
+        let mut object = Object::with_capacity(entries.len());
+
+        for (key, value) in entries {
+            object.insert(key, self.value(value)?);
+        }
+
+        Ok(object)
+    }
+
+    /// Decode the shared value at the given heap index, unless it's already
+    /// been decoded.
+    fn shared(&mut self, index: usize) -> Result<Value, SnapshotError> {
+        if let Some(Some(value)) = self.values.get(index) {
+            return Ok(value.clone());
+        }
+
+        let snapshot = match self.heap.get_mut(index).and_then(Option::take) {
+            Some(snapshot) => snapshot,
+            None => {
+                return Err(SnapshotError::from(SnapshotErrorKind::BadReference {
+                    index,
+                }))
+            }
+        };
+
+        let value = match snapshot {
+            HeapSnapshot::String(s) => Value::String(Shared::tracked(s)),
+            HeapSnapshot::Bytes(bytes) => Value::Bytes(Shared::tracked(Bytes::from_vec(bytes))),
+            HeapSnapshot::Vec(values) => {
+                Value::Vec(Shared::tracked(Vec::from(self.values(values)?)))
+            }
+            HeapSnapshot::Tuple(values) => {
+                Value::Tuple(Shared::tracked(Tuple::from(self.values(values)?)))
+            }
+            HeapSnapshot::Object(entries) => Value::Object(Shared::tracked(self.object(entries)?)),
+            HeapSnapshot::Range { start, end, closed } => {
+                let start = start.map(|v| self.value(v)).transpose()?;
+                let end = end.map(|v| self.value(v)).transpose()?;
+
+                let limits = if closed {
+                    RangeLimits::Closed
+                } else {
+                    RangeLimits::HalfOpen
+                };
+
+                Value::Range(Shared::tracked(Range::new(start, end, limits)))
+            }
+            HeapSnapshot::Option(option) => {
+                Value::Option(Shared::tracked(option.map(|v| self.value(v)).transpose()?))
+            }
+            HeapSnapshot::Result(result) => Value::Result(Shared::tracked(match result {
+                Ok(value) => Ok(self.value(value)?),
+                Err(value) => Err(self.value(value)?),
+            })),
+            HeapSnapshot::GeneratorState(state) => {
+                Value::GeneratorState(Shared::tracked(match state {
+                    Ok(value) => GeneratorState::Yielded(self.value(value)?),
+                    Err(value) => GeneratorState::Complete(self.value(value)?),
+                }))
+            }
+            HeapSnapshot::UnitStruct(hash) => Value::UnitStruct(Shared::tracked(UnitStruct {
+                rtti: self.rtti(hash)?,
+            })),
+            HeapSnapshot::TupleStruct(hash, values) => {
+                Value::TupleStruct(Shared::tracked(TupleStruct {
+                    rtti: self.rtti(hash)?,
+                    data: Tuple::from(self.values(values)?),
+                }))
+            }
+            HeapSnapshot::Struct(hash, entries) => Value::Struct(Shared::tracked(Struct {
+                rtti: self.rtti(hash)?,
+                data: self.object(entries)?,
+            })),
+            HeapSnapshot::UnitVariant(hash) => self.variant(hash, VariantData::Unit)?,
+            HeapSnapshot::TupleVariant(hash, values) => {
+                let data = VariantData::Tuple(Tuple::from(self.values(values)?));
+                self.variant(hash, data)?
+            }
+            HeapSnapshot::StructVariant(hash, entries) => {
+                let data = VariantData::Struct(self.object(entries)?);
+                self.variant(hash, data)?
+            }
+            HeapSnapshot::Function(function) => {
+                Value::Function(Shared::tracked(self.function(function)?))
+            }
+            HeapSnapshot::Generator(execution) => {
+                let execution = execution.map(|e| self.execution(e)).transpose()?;
+                Value::Generator(Shared::tracked(Generator { execution }))
+            }
+            HeapSnapshot::Stream(execution) => {
+                let execution = execution.map(|e| self.execution(e)).transpose()?;
+                Value::Stream(Shared::tracked(Stream {
+                    execution,
+                    pending: None,
+                }))
+            }
+        };
+
+        self.values[index] = Some(value.clone());
+        Ok(value)
+    }
+
+    fn variant(&mut self, hash: Hash, data: VariantData) -> Result<Value, SnapshotError> {
+        let rtti = self.variant_rtti(hash)?;
+        Ok(Value::Variant(Shared::tracked(Variant { rtti, data })))
+    }
+
+    fn rtti(&self, hash: Hash) -> Result<Arc<Rtti>, SnapshotError> {
+        self.unit
+            .lookup_rtti(hash)
+            .cloned()
+            .ok_or_else(|| SnapshotError::from(SnapshotErrorKind::MissingType { hash }))
+    }
+
+    fn function(
+        &mut self,
+        snapshot: FunctionSnapshot<ValueSnapshot>,
+    ) -> Result<Function, SnapshotError> {
+        Ok(match snapshot {
+            FunctionSnapshot::Handler { hash } => match self.context.function(hash) {
+                Some(handler) => Function::from_handler(handler.clone(), hash),
+                None => {
+                    return Err(SnapshotError::from(SnapshotErrorKind::MissingFunction {
+                        hash,
+                    }))
+                }
+            },
+            FunctionSnapshot::Offset {
+                offset,
+                call,
+                args,
+                hash,
+            } => Function::from_offset(
+                self.context.clone(),
+                self.unit.clone(),
+                offset,
+                call,
+                args,
+                hash,
+            ),
+            FunctionSnapshot::Closure {
+                offset,
+                call,
+                args,
+                environment,
+                hash,
+            } => {
+                let environment = self.values(environment)?.into_boxed_slice();
+
+                Function::from_closure(
+                    self.context.clone(),
+                    self.unit.clone(),
+                    offset,
+                    call,
+                    args,
+                    environment,
+                    hash,
+                )
+            }
+            FunctionSnapshot::UnitStruct { hash } => Function::from_unit_struct(self.rtti(hash)?),
+            FunctionSnapshot::TupleStruct { hash, args } => {
+                Function::from_tuple_struct(self.rtti(hash)?, args)
+            }
+            FunctionSnapshot::UnitVariant { hash } => {
+                Function::from_unit_variant(self.variant_rtti(hash)?)
+            }
+            FunctionSnapshot::TupleVariant { hash, args } => {
+                Function::from_tuple_variant(self.variant_rtti(hash)?, args)
+            }
+        })
+    }
+
+    fn variant_rtti(&self, hash: Hash) -> Result<Arc<VariantRtti>, SnapshotError> {
+        self.unit
+            .lookup_variant_rtti(hash)
+            .cloned()
+            .ok_or_else(|| SnapshotError::from(SnapshotErrorKind::MissingType { hash }))
+    }
+}
+
+fn is_resumed(state: ExecutionState) -> bool {
+    matches!(state, ExecutionState::Resumed)
+}
+
+fn execution_state(resumed: bool) -> ExecutionState {
+    if resumed {
+        ExecutionState::Resumed
+    } else {
+        ExecutionState::Initial
+    }
+}
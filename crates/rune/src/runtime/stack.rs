@@ -222,6 +222,15 @@ impl Stack {
         self.stack.last().ok_or(StackError(()))
     }
 
+    /// Get the top `count` elements of the stack in the order that they were
+    /// pushed, from bottom to top.
+    pub(crate) fn peek_n(&self, count: usize) -> Result<&[Value], StackError> {
+        match self.stack.len().checked_sub(count) {
+            Some(start) if start >= self.stack_bottom => Ok(&self.stack[start..]),
+            _ => Err(StackError(())),
+        }
+    }
+
     /// Get the last position on the stack.
     #[inline]
     pub(crate) fn peek(&self) -> Option<&Value> {
@@ -242,6 +251,15 @@ impl Stack {
         self.stack_bottom
     }
 
+    /// Construct a stack from its values and the bottom of its current stack
+    /// frame.
+    pub(crate) fn from_parts(stack: Vec<Value>, stack_bottom: usize) -> Self {
+        Self {
+            stack,
+            stack_bottom,
+        }
+    }
+
     /// Access the value at the given frame offset.
     pub(crate) fn at_offset(&self, offset: usize) -> Result<&Value, StackError> {
         self.stack_bottom
@@ -296,12 +314,56 @@ impl Stack {
         })
     }
 
+    /// Access the operands of a binary operation without removing them from
+    /// the stack.
+    pub(crate) fn operands(
+        &self,
+        lhs: InstAddress,
+        rhs: InstAddress,
+    ) -> Result<(&Value, &Value), StackError> {
+        let (rhs, top) = match rhs {
+            InstAddress::Top => (self.at_offset_from_top(1)?, 2),
+            InstAddress::Offset(offset) => (self.at_offset(offset)?, 1),
+        };
+
+        let lhs = match lhs {
+            InstAddress::Top => self.at_offset_from_top(top)?,
+            InstAddress::Offset(offset) => self.at_offset(offset)?,
+        };
+
+        Ok((lhs, rhs))
+    }
+
+    /// Pop the operands of a binary operation which are addressed from the top
+    /// of the stack.
+    pub(crate) fn pop_operands(
+        &mut self,
+        lhs: InstAddress,
+        rhs: InstAddress,
+    ) -> Result<(), StackError> {
+        let count = usize::from(matches!(lhs, InstAddress::Top))
+            + usize::from(matches!(rhs, InstAddress::Top));
+        self.popn(count)
+    }
+
     /// Pop the given number of elements from the stack.
     pub(crate) fn popn(&mut self, count: usize) -> Result<(), StackError> {
         drop(self.drain(count)?);
         Ok(())
     }
 
+    /// Replace the current stack frame with the last `count` values on the
+    /// stack.
+    pub(crate) fn reframe(&mut self, count: usize) -> Result<(), StackError> {
+        match self.stack.len().checked_sub(count) {
+            Some(start) if start >= self.stack_bottom => {
+                drop(self.stack.drain(self.stack_bottom..start));
+                Ok(())
+            }
+            _ => Err(StackError(())),
+        }
+    }
+
     /// Pop a sequence of values from the stack.
     pub(crate) fn pop_sequence(&mut self, count: usize) -> Result<Vec<Value>, StackError> {
         Ok(self.drain(count)?.collect::<Vec<_>>())
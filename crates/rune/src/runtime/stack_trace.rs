@@ -0,0 +1,116 @@
+//! Stack traces of script functions, captured when a [VmError] is raised.
+//!
+//! [VmError]: crate::runtime::VmError
+
+use crate::ast::Span;
+use crate::compile::Item;
+use crate::runtime::{CallFrame, Unit};
+use crate::SourceId;
+use std::fmt;
+
+/// A single frame in a [StackTrace].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct StackTraceFrame {
+    /// The instruction pointer of the frame.
+    ///
+    /// For the innermost frame this is the instruction which raised the error,
+    /// for every other frame it's the call which is being executed.
+    pub ip: usize,
+    /// The item of the function the instruction belongs to, if the unit has
+    /// debug information.
+    pub item: Option<Item>,
+    /// The source the instruction was compiled from, if the unit has debug
+    /// information.
+    pub source_id: Option<SourceId>,
+    /// The span the instruction was compiled from, if the unit has debug
+    /// information.
+    pub span: Option<Span>,
+}
+
+impl StackTraceFrame {
+    fn new(unit: &Unit, ip: usize) -> Self {
+        let debug_info = unit.debug_info();
+
+        let item = debug_info
+            .and_then(|debug| debug.function_containing(ip))
+            .map(|(_, signature)| signature.path.clone());
+
+        let inst = debug_info.and_then(|debug| debug.instruction_at(ip));
+
+        Self {
+            ip,
+            item,
+            source_id: inst.map(|inst| inst.source_id),
+            span: inst.map(|inst| inst.span),
+        }
+    }
+}
+
+impl fmt::Display for StackTraceFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.item {
+            Some(item) => write!(f, "{}", item)?,
+            None => write!(f, "<unknown>")?,
+        }
+
+        write!(f, " (inst {}", self.ip)?;
+
+        if let (Some(source_id), Some(span)) = (self.source_id, self.span) {
+            write!(f, ", source {} at {}", source_id, span)?;
+        }
+
+        write!(f, ")")
+    }
+}
+
+/// The script functions being executed when an error was raised, with the
+/// innermost frame first.
+#[derive(Debug, Clone)]
+pub struct StackTrace {
+    frames: Vec<StackTraceFrame>,
+}
+
+impl StackTrace {
+    /// Construct a stack trace from the instruction pointer where an error was
+    /// raised and the call frames leading up to it.
+    pub(crate) fn new(unit: &Unit, ip: usize, frames: &[CallFrame]) -> Self {
+        let frames = std::iter::once(ip)
+            .chain(frames.iter().rev().map(CallFrame::ip))
+            .map(|ip| StackTraceFrame::new(unit, ip))
+            .collect();
+
+        Self { frames }
+    }
+
+    /// The frames of the stack trace, with the innermost frame first.
+    pub fn frames(&self) -> &[StackTraceFrame] {
+        &self.frames
+    }
+}
+
+impl fmt::Display for StackTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut frames = self.frames.iter().peekable();
+
+        while let Some(frame) = frames.next() {
+            writeln!(f)?;
+            write!(f, "    at {}", frame)?;
+
+            // NB: collapse frames from deep recursion, which would otherwise
+            // drown out the rest of the trace.
+            let mut repeated = 0;
+
+            while frames.next_if(|next| next.ip == frame.ip).is_some() {
+                repeated += 1;
+            }
+
+            if repeated > 0 {
+                writeln!(f)?;
+                write!(f, "    ... repeated {} more times", repeated)?;
+            }
+        }
+
+        Ok(())
+    }
+}
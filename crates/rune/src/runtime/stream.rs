@@ -4,13 +4,21 @@ use crate::runtime::{
     Vm, VmError, VmErrorKind, VmExecution,
 };
 use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// The in-flight resumption of a stream being polled.
+type PendingResume<T> =
+    Pin<Box<dyn Future<Output = (VmExecution<T>, Result<GeneratorState, VmError>)>>>;
 
 /// A stream with a stored virtual machine.
 pub struct Stream<T>
 where
     T: AsMut<Vm>,
 {
-    execution: Option<VmExecution<T>>,
+    pub(crate) execution: Option<VmExecution<T>>,
+    pub(crate) pending: Option<PendingResume<T>>,
 }
 
 impl<T> Stream<T>
@@ -21,6 +29,7 @@ where
     pub(crate) fn new(vm: T) -> Self {
         Self {
             execution: Some(VmExecution::new(vm)),
+            pending: None,
         }
     }
 
@@ -28,6 +37,7 @@ where
     pub(crate) fn from_execution(execution: VmExecution<T>) -> Self {
         Self {
             execution: Some(execution),
+            pending: None,
         }
     }
 
@@ -65,10 +75,93 @@ impl Stream<&mut Vm> {
     pub fn into_owned(self) -> Stream<Vm> {
         Stream {
             execution: self.execution.map(|e| e.into_owned()),
+            pending: None,
+        }
+    }
+}
+
+/// Consume the stream with the combinators of [futures_core::Stream].
+///
+/// While the stream is being polled its virtual machine is held by the
+/// in-flight resumption, so mixing polling with [Stream::next] or
+/// [Stream::resume] before a value has been produced results in an error.
+/// The stream ends when the execution completes, or after it has produced an
+/// error.
+///
+/// ```
+/// use futures_util::TryStreamExt;
+/// use rune::{FromValue, Vm};
+/// use std::sync::Arc;
+///
+/// # #[tokio::main] async fn main() -> rune::Result<()> {
+/// let mut sources = rune::sources! {
+///     entry => {
+///         pub async fn main() {
+///             yield 1;
+///             yield 2;
+///             yield 3;
+///         }
+///     }
+/// };
+///
+/// let unit = rune::prepare(&mut sources).build()?;
+///
+/// let mut vm = Vm::without_runtime(Arc::new(unit));
+/// let stream = vm.execute(&["main"], ())?.into_stream()?.into_owned();
+///
+/// let values = stream
+///     .and_then(|value| async move { i64::from_value(value) })
+///     .try_collect::<Vec<_>>()
+///     .await?;
+///
+/// assert_eq!(values, [1, 2, 3]);
+/// # Ok(()) }
+/// ```
+impl futures_core::Stream for Stream<Vm> {
+    type Item = Result<Value, VmError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Some(pending) = &mut this.pending {
+                let (execution, result) = futures_core::ready!(pending.as_mut().poll(cx));
+                this.pending = None;
+
+                return Poll::Ready(match result {
+                    Ok(GeneratorState::Yielded(value)) => {
+                        this.execution = Some(execution);
+                        Some(Ok(value))
+                    }
+                    Ok(GeneratorState::Complete(..)) => None,
+                    Err(error) => Some(Err(error)),
+                });
+            }
+
+            let mut execution = match this.execution.take() {
+                Some(execution) => execution,
+                None => return Poll::Ready(None),
+            };
+
+            this.pending = Some(Box::pin(async move {
+                let result = if execution.is_resumed() {
+                    execution.async_resume_with(Value::Unit).await
+                } else {
+                    execution.async_resume().await
+                };
+
+                (execution, result)
+            }));
         }
     }
 }
 
+impl futures_core::FusedStream for Stream<Vm> {
+    fn is_terminated(&self) -> bool {
+        self.execution.is_none() && self.pending.is_none()
+    }
+}
+
 impl<T> Named for Stream<T>
 where
     T: AsMut<Vm>,
@@ -84,7 +177,10 @@ where
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Stream")
-            .field("completed", &self.execution.is_none())
+            .field(
+                "completed",
+                &(self.execution.is_none() && self.pending.is_none()),
+            )
             .finish()
     }
 }
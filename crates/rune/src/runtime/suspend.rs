@@ -0,0 +1,96 @@
+//! Suspension points which hand control back to the host.
+
+use crate::runtime::Value;
+use crate::Any;
+
+/// A suspension point, which suspends the execution it's awaited in and hands
+/// the contained value to the host.
+///
+/// Native functions can return a suspension to let the host drive a script,
+/// like when the script needs input only the host can provide. Scripts can
+/// also construct one with `std::future::suspend`.
+///
+/// Awaiting a suspension makes [VmExecution::resume][crate::runtime::VmExecution::resume]
+/// return [GeneratorState::Yielded][crate::runtime::GeneratorState::Yielded]
+/// with the contained value, just like a `yield` does. The host then resumes
+/// the execution with a reply through
+/// [VmExecution::resume_with][crate::runtime::VmExecution::resume_with],
+/// which becomes the result of the `.await`. Resuming without a reply makes
+/// it the unit value.
+///
+/// Suspensions are also observed when they're awaited by async functions which
+/// the function being executed awaits, since those are run as part of the same
+/// execution. Futures which are polled by other means, like a `select` or
+/// `std::future::join`, run in their own virtual machine, and awaiting a
+/// suspension there is an error.
+///
+/// # Examples
+///
+/// ```
+/// use rune::{Context, FromValue, Module, Value, Vm};
+/// use rune::runtime::{GeneratorState, Suspend};
+/// use std::sync::Arc;
+///
+/// # fn main() -> rune::Result<()> {
+/// let mut module = Module::with_crate("prompt");
+/// module.function(&["ask"], |question: String| Suspend::new(Value::from(question)))?;
+///
+/// let mut context = Context::with_default_modules()?;
+/// context.install(&module)?;
+///
+/// let mut sources = rune::sources! {
+///     entry => {
+///         pub async fn main() {
+///             let name = prompt::ask("name").await;
+///             let age = prompt::ask("age").await;
+///             (name, age)
+///         }
+///     }
+/// };
+///
+/// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+/// let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+/// let mut execution = vm.execute(&["main"], ())?;
+///
+/// let mut state = execution.resume()?;
+///
+/// let output = loop {
+///     let question = match state {
+///         GeneratorState::Yielded(question) => String::from_value(question)?,
+///         GeneratorState::Complete(output) => break output,
+///     };
+///
+///     let reply = match question.as_str() {
+///         "name" => Value::from(String::from("john")),
+///         _ => Value::from(42i64),
+///     };
+///
+///     state = execution.resume_with(reply)?;
+/// };
+///
+/// let output = <(String, i64)>::from_value(output)?;
+/// assert_eq!(output, (String::from("john"), 42));
+/// # Ok(()) }
+/// ```
+#[derive(Any, Debug, Clone)]
+#[rune(module = "crate")]
+pub struct Suspend {
+    value: Value,
+}
+
+impl Suspend {
+    /// Construct a suspension point which hands the given value to the host.
+    pub fn new(value: Value) -> Self {
+        Self { value }
+    }
+
+    /// Get the value handed to the host.
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    /// Coerce into the value handed to the host.
+    pub fn into_value(self) -> Value {
+        self.value
+    }
+}
@@ -87,7 +87,7 @@ where
     T: ToValue,
 {
     fn to_value(self) -> Result<Value, VmError> {
-        Ok(Value::from(Shared::new(match self {
+        Ok(Value::from(Shared::tracked(match self {
             Some(some) => {
                 let value = some.to_value()?;
                 Some(value)
@@ -101,13 +101,13 @@ where
 
 impl ToValue for Box<str> {
     fn to_value(self) -> Result<Value, VmError> {
-        Ok(Value::from(Shared::new(self.to_string())))
+        Ok(Value::from(Shared::tracked(self.to_string())))
     }
 }
 
 impl ToValue for &str {
     fn to_value(self) -> Result<Value, VmError> {
-        Ok(Value::from(Shared::new(self.to_string())))
+        Ok(Value::from(Shared::tracked(self.to_string())))
     }
 }
 
@@ -146,11 +146,11 @@ where
         Ok(match self {
             Ok(ok) => {
                 let ok = ok.to_value()?;
-                Value::from(Shared::new(Ok(ok)))
+                Value::from(Shared::tracked(Ok(ok)))
             }
             Err(err) => {
                 let err = err.to_value()?;
-                Value::from(Shared::new(Err(err)))
+                Value::from(Shared::tracked(Err(err)))
             }
         })
     }
@@ -208,7 +208,7 @@ macro_rules! impl_map {
                     output.insert(key, value.to_value()?);
                 }
 
-                Ok(Value::from(Shared::new(output)))
+                Ok(Value::from(Shared::tracked(output)))
             }
         }
     };
@@ -3,11 +3,14 @@
 //! A unit consists of a sequence of instructions, and lookaside tables for
 //! metadata like function locations.
 
+use crate::ast::Span;
 use crate::collections::HashMap;
+use crate::runtime::link::{self, LinkErrorKind, Relocation};
 use crate::runtime::{
-    Call, ConstValue, DebugInfo, Inst, Rtti, StaticString, VariantRtti, VmError, VmErrorKind,
+    disasm, verify, Call, ConstValue, DebugInfo, DebugInst, Inst, InstRecord, LinkError, Rtti,
+    StaticString, VariantRtti, VerifyError, VmError, VmErrorKind,
 };
-use crate::Hash;
+use crate::{Hash, SourceId};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::sync::Arc;
@@ -30,6 +33,8 @@ pub struct Unit {
     ///
     /// All keys are sorted with the default string sort.
     static_object_keys: Vec<Box<[String]>>,
+    /// Constant tuples.
+    static_tuples: Vec<Box<[ConstValue]>>,
     /// Runtime information for types.
     rtti: HashMap<Hash, Arc<Rtti>>,
     /// Runtime information for variants.
@@ -49,6 +54,7 @@ impl Unit {
         static_strings: Vec<Arc<StaticString>>,
         static_bytes: Vec<Vec<u8>>,
         static_object_keys: Vec<Box<[String]>>,
+        static_tuples: Vec<Box<[ConstValue]>>,
         rtti: HashMap<Hash, Arc<Rtti>>,
         variant_rtti: HashMap<Hash, Arc<VariantRtti>>,
         debug: Option<Box<DebugInfo>>,
@@ -60,6 +66,7 @@ impl Unit {
             static_strings,
             static_bytes,
             static_object_keys,
+            static_tuples,
             rtti,
             variant_rtti,
             debug,
@@ -103,6 +110,33 @@ impl Unit {
         self.instructions.iter().copied()
     }
 
+    /// Iterate over all instructions in order, together with the functions,
+    /// labels, spans and constants associated with them.
+    ///
+    /// ```
+    /// use rune::runtime::{Inst, InstConstant};
+    /// use rune::{Context, Source, Sources};
+    ///
+    /// # fn main() -> rune::Result<()> {
+    /// let context = Context::with_default_modules()?;
+    ///
+    /// let mut sources = Sources::new();
+    /// sources.insert(Source::new("main", r#"pub fn main() { "Hello" }"#));
+    ///
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    ///
+    /// let record = unit
+    ///     .instructions()
+    ///     .find(|record| matches!(record.inst, Inst::String { .. }))
+    ///     .expect("missing string instruction");
+    ///
+    /// assert!(matches!(record.constant, Some(InstConstant::String("Hello"))));
+    /// # Ok(()) }
+    /// ```
+    pub fn instructions(&self) -> impl Iterator<Item = InstRecord<'_>> + '_ {
+        disasm::disassemble(self, &self.instructions)
+    }
+
     /// Iterate over dynamic functions.
     pub fn iter_functions(&self) -> impl Iterator<Item = (Hash, &UnitFn)> + '_ {
         self.functions.iter().map(|(h, f)| (*h, f))
@@ -130,6 +164,14 @@ impl Unit {
         self.static_object_keys.get(slot).map(|keys| &keys[..])
     }
 
+    /// Lookup the constant tuple by slot, if it exists.
+    pub fn lookup_tuple(&self, slot: usize) -> Result<&[ConstValue], VmError> {
+        Ok(self
+            .static_tuples
+            .get(slot)
+            .ok_or(VmErrorKind::MissingStaticTuple { slot })?)
+    }
+
     /// Lookup runt-time information for the given type hash.
     pub fn lookup_rtti(&self, hash: Hash) -> Option<&Arc<Rtti>> {
         self.rtti.get(&hash)
@@ -149,6 +191,212 @@ impl Unit {
     pub fn constant(&self, hash: Hash) -> Option<&ConstValue> {
         self.constants.get(&hash)
     }
+
+    /// Verify that the unit is well-formed.
+    ///
+    /// Units which are built by the compiler are always well-formed, but this
+    /// should be used before running a unit from an untrusted source, like
+    /// precompiled bytecode. It checks that jumps stay within the function
+    /// they belong to, that all referenced slots and function offsets exist,
+    /// and that every function uses the stack consistently.
+    pub fn verify(&self) -> Result<(), VerifyError> {
+        verify::verify(self, &self.instructions)
+    }
+
+    /// Link this unit together with another unit which was compiled
+    /// separately, producing a unit which contains both.
+    ///
+    /// The instructions of `other` are placed after the instructions of this
+    /// unit, so anything which refers to this unit by offset remains valid.
+    /// Calls by hash in either unit to functions defined in the other are
+    /// resolved into direct calls.
+    ///
+    /// It's an error for both units to define the same function, type or
+    /// constant.
+    pub fn link(&self, other: &Unit) -> Result<Unit, LinkError> {
+        let relocation = Relocation {
+            instructions: self.instructions.len(),
+            static_strings: self.static_strings.len(),
+            static_bytes: self.static_bytes.len(),
+            static_object_keys: self.static_object_keys.len(),
+            static_tuples: self.static_tuples.len(),
+        };
+
+        let mut unit = self.clone();
+
+        for (hash, f) in &other.functions {
+            if unit
+                .functions
+                .insert(*hash, relocation.function(*f))
+                .is_some()
+            {
+                return Err(LinkError::from(LinkErrorKind::ConflictingFunction {
+                    hash: *hash,
+                }));
+            }
+        }
+
+        for (hash, rtti) in &other.rtti {
+            if unit.rtti.insert(*hash, rtti.clone()).is_some() {
+                return Err(LinkError::from(LinkErrorKind::ConflictingType {
+                    hash: *hash,
+                }));
+            }
+        }
+
+        for (hash, rtti) in &other.variant_rtti {
+            if unit.variant_rtti.insert(*hash, rtti.clone()).is_some() {
+                return Err(LinkError::from(LinkErrorKind::ConflictingType {
+                    hash: *hash,
+                }));
+            }
+        }
+
+        for (hash, value) in &other.constants {
+            if unit.constants.insert(*hash, value.clone()).is_some() {
+                return Err(LinkError::from(LinkErrorKind::ConflictingConstant {
+                    hash: *hash,
+                }));
+            }
+        }
+
+        unit.instructions
+            .extend(other.instructions.iter().map(|inst| relocation.inst(*inst)));
+
+        unit.static_strings
+            .extend(other.static_strings.iter().cloned());
+        unit.static_bytes.extend(other.static_bytes.iter().cloned());
+        unit.static_object_keys
+            .extend(other.static_object_keys.iter().cloned());
+        unit.static_tuples
+            .extend(other.static_tuples.iter().cloned());
+
+        unit.debug = link_debug(
+            self.debug.as_deref(),
+            other.debug.as_deref(),
+            relocation.instructions,
+        );
+
+        link::specialize_calls(&mut unit.instructions, &unit.functions);
+        Ok(unit)
+    }
+
+    /// Reload this unit with a new version of it, producing a unit where every
+    /// function, type and constant is taken from `other`.
+    ///
+    /// The instructions of this unit are kept in place, so that functions which
+    /// are currently executing can run to completion. Calls they make to
+    /// functions which still exist with the same signature are redirected to
+    /// the new version of the function.
+    pub fn reload(&self, other: &Unit) -> Unit {
+        let relocation = Relocation {
+            instructions: self.instructions.len(),
+            static_strings: self.static_strings.len(),
+            static_bytes: self.static_bytes.len(),
+            static_object_keys: self.static_object_keys.len(),
+            static_tuples: self.static_tuples.len(),
+        };
+
+        let functions = other
+            .functions
+            .iter()
+            .map(|(hash, f)| (*hash, relocation.function(*f)))
+            .collect::<HashMap<_, _>>();
+
+        // The new offset of every function which keeps its signature.
+        let mut redirects = HashMap::new();
+
+        for (hash, f) in &self.functions {
+            if let (
+                UnitFn::Offset { offset, call, args },
+                Some(UnitFn::Offset {
+                    offset: new,
+                    call: new_call,
+                    args: new_args,
+                }),
+            ) = (f, functions.get(hash))
+            {
+                if call == new_call && args == new_args {
+                    redirects.insert(*offset, *new);
+                }
+            }
+        }
+
+        let mut instructions = self.instructions.clone();
+
+        for inst in &mut instructions {
+            if let Inst::CallOffset { offset, .. } = inst {
+                if let Some(new) = redirects.get(offset) {
+                    *offset = *new;
+                }
+            }
+        }
+
+        instructions.extend(other.instructions.iter().map(|inst| relocation.inst(*inst)));
+
+        Unit {
+            instructions,
+            functions,
+            static_strings: concat(&self.static_strings, &other.static_strings),
+            static_bytes: concat(&self.static_bytes, &other.static_bytes),
+            static_object_keys: concat(&self.static_object_keys, &other.static_object_keys),
+            static_tuples: concat(&self.static_tuples, &other.static_tuples),
+            rtti: other.rtti.clone(),
+            variant_rtti: other.variant_rtti.clone(),
+            debug: link_debug(
+                self.debug.as_deref(),
+                other.debug.as_deref(),
+                relocation.instructions,
+            ),
+            constants: other.constants.clone(),
+        }
+    }
+}
+
+/// Concatenate two tables.
+fn concat<T>(a: &[T], b: &[T]) -> Vec<T>
+where
+    T: Clone,
+{
+    a.iter().chain(b).cloned().collect()
+}
+
+/// Link the debug information of two units, where the instructions of the
+/// second unit are placed at `base`.
+fn link_debug(a: Option<&DebugInfo>, b: Option<&DebugInfo>, base: usize) -> Option<Box<DebugInfo>> {
+    if a.is_none() && b.is_none() {
+        return None;
+    }
+
+    let mut debug = a.cloned().unwrap_or_default();
+
+    // NB: debug information is looked up by instruction pointer, so
+    // instructions without it are padded out.
+    debug.instructions.resize(
+        base,
+        DebugInst::new(SourceId::empty(), Span::empty(), None, None),
+    );
+
+    if let Some(b) = b {
+        debug.instructions.extend(b.instructions.iter().cloned());
+        debug.functions.extend(
+            b.functions
+                .iter()
+                .map(|(hash, signature)| (*hash, signature.clone())),
+        );
+        debug.functions_rev.extend(
+            b.functions_rev
+                .iter()
+                .map(|(offset, hash)| (*offset + base, *hash)),
+        );
+        debug.hash_to_item.extend(
+            b.hash_to_item
+                .iter()
+                .map(|(hash, item)| (*hash, item.clone())),
+        );
+    }
+
+    Some(Box::new(debug))
 }
 
 /// The kind and necessary information on registered functions.
@@ -1,9 +1,9 @@
 use crate::compile::Item;
 use crate::runtime::{
     AccessKind, AnyObj, Bytes, ConstValue, EnvProtocolCaller, Format, FromValue, Function, Future,
-    Generator, GeneratorState, Iterator, Mut, Object, Protocol, ProtocolCaller, Range, RawMut,
-    RawRef, Ref, Shared, StaticString, Stream, ToValue, Tuple, TypeInfo, Variant, Vec, Vm, VmError,
-    VmErrorKind,
+    Generator, GeneratorState, GuardedArgs, Iterator, Mut, Object, Protocol, ProtocolCaller, Range,
+    RawMut, RawRef, Ref, Shared, StaticString, Stream, ToValue, Tuple, TypeInfo, Variant,
+    VariantData, Vec, Vm, VmError, VmErrorKind,
 };
 use crate::{Any, Hash};
 use serde::{de, ser, Deserialize, Serialize};
@@ -287,6 +287,23 @@ pub enum Value {
 }
 
 impl Value {
+    /// Call the given protocol on the value, like a custom protocol defined
+    /// with [Protocol::custom].
+    ///
+    /// You must use [Vm::with] to specify which virtual machine this function
+    /// is called inside.
+    ///
+    /// # Errors
+    ///
+    /// This function errors if called outside of a virtual machine, or if the
+    /// type of the value doesn't implement the protocol.
+    pub fn protocol_call<A>(&self, protocol: Protocol, args: A) -> Result<Value, VmError>
+    where
+        A: GuardedArgs,
+    {
+        EnvProtocolCaller.call_protocol_fn(protocol, self.clone(), args)
+    }
+
     /// Format the value using the [Protocol::STRING_DISPLAY] protocol.
     ///
     /// Requires a work buffer `buf` which will be used in case the value
@@ -338,7 +355,7 @@ impl Value {
                 return Ok(write!(s, "{:#04X}", byte));
             }
             value => {
-                let b = Shared::new(std::mem::take(s));
+                let b = Shared::tracked(std::mem::take(s));
 
                 let result = caller.call_protocol_fn(
                     Protocol::STRING_DISPLAY,
@@ -458,7 +475,7 @@ impl Value {
                 write!(s, "{:?}", value)
             }
             value => {
-                let b = Shared::new(std::mem::take(s));
+                let b = Shared::tracked(std::mem::take(s));
 
                 let result = caller.call_protocol_fn(
                     Protocol::STRING_DEBUG,
@@ -531,7 +548,7 @@ impl Value {
         };
 
         let value = EnvProtocolCaller.call_protocol_fn(Protocol::INTO_FUTURE, target, ())?;
-        Ok(Shared::new(Future::from_value(value)?))
+        Ok(Shared::tracked(Future::from_value(value)?))
     }
 
     /// Retrieves a human readable type name for the current value.
@@ -568,22 +585,22 @@ impl Value {
 
     /// Construct a vector.
     pub fn vec(vec: vec::Vec<Value>) -> Self {
-        Self::Vec(Shared::new(Vec::from(vec)))
+        Self::Vec(Shared::tracked(Vec::from(vec)))
     }
 
     /// Construct a tuple.
     pub fn tuple(vec: vec::Vec<Value>) -> Self {
-        Self::Tuple(Shared::new(Tuple::from(vec)))
+        Self::Tuple(Shared::tracked(Tuple::from(vec)))
     }
 
     /// Construct an empty.
     pub fn unit_struct(rtti: Arc<Rtti>) -> Self {
-        Self::UnitStruct(Shared::new(UnitStruct { rtti }))
+        Self::UnitStruct(Shared::tracked(UnitStruct { rtti }))
     }
 
     /// Construct a typed tuple.
     pub fn tuple_struct(rtti: Arc<Rtti>, vec: vec::Vec<Value>) -> Self {
-        Self::TupleStruct(Shared::new(TupleStruct {
+        Self::TupleStruct(Shared::tracked(TupleStruct {
             rtti,
             data: Tuple::from(vec),
         }))
@@ -591,12 +608,12 @@ impl Value {
 
     /// Construct an empty variant.
     pub fn unit_variant(rtti: Arc<VariantRtti>) -> Self {
-        Self::Variant(Shared::new(Variant::unit(rtti)))
+        Self::Variant(Shared::tracked(Variant::unit(rtti)))
     }
 
     /// Construct a tuple variant.
     pub fn tuple_variant(rtti: Arc<VariantRtti>, vec: vec::Vec<Value>) -> Self {
-        Self::Variant(Shared::new(Variant::tuple(rtti, Tuple::from(vec))))
+        Self::Variant(Shared::tracked(Variant::tuple(rtti, Tuple::from(vec))))
     }
 
     /// Take the interior value.
@@ -610,26 +627,26 @@ impl Value {
             Self::Float(value) => Self::Float(value),
             Self::Type(value) => Self::Type(value),
             Self::StaticString(value) => Self::StaticString(value),
-            Self::String(value) => Self::String(Shared::new(value.take()?)),
-            Self::Bytes(value) => Self::Bytes(Shared::new(value.take()?)),
-            Self::Vec(value) => Self::Vec(Shared::new(value.take()?)),
-            Self::Tuple(value) => Self::Tuple(Shared::new(value.take()?)),
-            Self::Object(value) => Self::Object(Shared::new(value.take()?)),
-            Self::Range(value) => Self::Range(Shared::new(value.take()?)),
-            Self::Future(value) => Self::Future(Shared::new(value.take()?)),
-            Self::Stream(value) => Self::Stream(Shared::new(value.take()?)),
-            Self::Generator(value) => Self::Generator(Shared::new(value.take()?)),
-            Self::GeneratorState(value) => Self::GeneratorState(Shared::new(value.take()?)),
-            Self::Option(value) => Self::Option(Shared::new(value.take()?)),
-            Self::Result(value) => Self::Result(Shared::new(value.take()?)),
-            Self::UnitStruct(value) => Self::UnitStruct(Shared::new(value.take()?)),
-            Self::TupleStruct(value) => Self::TupleStruct(Shared::new(value.take()?)),
-            Self::Struct(value) => Self::Struct(Shared::new(value.take()?)),
-            Self::Variant(value) => Self::Variant(Shared::new(value.take()?)),
-            Self::Function(value) => Self::Function(Shared::new(value.take()?)),
+            Self::String(value) => Self::String(Shared::tracked(value.take()?)),
+            Self::Bytes(value) => Self::Bytes(Shared::tracked(value.take()?)),
+            Self::Vec(value) => Self::Vec(Shared::tracked(value.take()?)),
+            Self::Tuple(value) => Self::Tuple(Shared::tracked(value.take()?)),
+            Self::Object(value) => Self::Object(Shared::tracked(value.take()?)),
+            Self::Range(value) => Self::Range(Shared::tracked(value.take()?)),
+            Self::Future(value) => Self::Future(Shared::tracked(value.take()?)),
+            Self::Stream(value) => Self::Stream(Shared::tracked(value.take()?)),
+            Self::Generator(value) => Self::Generator(Shared::tracked(value.take()?)),
+            Self::GeneratorState(value) => Self::GeneratorState(Shared::tracked(value.take()?)),
+            Self::Option(value) => Self::Option(Shared::tracked(value.take()?)),
+            Self::Result(value) => Self::Result(Shared::tracked(value.take()?)),
+            Self::UnitStruct(value) => Self::UnitStruct(Shared::tracked(value.take()?)),
+            Self::TupleStruct(value) => Self::TupleStruct(Shared::tracked(value.take()?)),
+            Self::Struct(value) => Self::Struct(Shared::tracked(value.take()?)),
+            Self::Variant(value) => Self::Variant(Shared::tracked(value.take()?)),
+            Self::Function(value) => Self::Function(Shared::tracked(value.take()?)),
             Self::Format(value) => Self::Format(value),
             Self::Iterator(value) => Self::Iterator(value),
-            Self::Any(value) => Self::Any(Shared::new(value.take()?)),
+            Self::Any(value) => Self::Any(Shared::tracked(value.take()?)),
         })
     }
 
@@ -824,6 +841,69 @@ impl Value {
         }
     }
 
+    /// Try to coerce value into an [anyhow::Error], like the error carried by
+    /// an `Err` which is returned from a script.
+    ///
+    /// Errors which originate from native functions are handed back as they
+    /// are, so they can be downcast to their original type. Strings are used
+    /// as the message of the error, and any other value is described by its
+    /// debug representation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::{Context, Module, Vm};
+    /// use std::sync::Arc;
+    ///
+    /// #[derive(Debug, thiserror::Error)]
+    /// #[error("not found")]
+    /// struct NotFound;
+    ///
+    /// # fn main() -> rune::Result<()> {
+    /// let mut module = Module::with_crate("db");
+    /// module.function(&["get"], || -> rune::Result<i64> { Err(NotFound.into()) })?;
+    ///
+    /// let mut context = Context::with_default_modules()?;
+    /// context.install(&module)?;
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub fn main() {
+    ///             match db::get() {
+    ///                 Ok(value) => Ok(value),
+    ///                 Err(error) => Err(error.context("loading user")),
+    ///             }
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    /// let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+    ///
+    /// let output = vm.call(&["main"], ())?.into_result()?.take()?;
+    /// let error = output.unwrap_err().into_error()?;
+    ///
+    /// assert_eq!(error.to_string(), "loading user");
+    /// assert!(error.downcast_ref::<NotFound>().is_some());
+    /// # Ok(()) }
+    /// ```
+    pub fn into_error(self) -> Result<anyhow::Error, VmError> {
+        Ok(match self {
+            Self::Any(any) => {
+                if any.borrow_ref()?.is::<anyhow::Error>() {
+                    any.take_downcast::<anyhow::Error>()?
+                } else if any.borrow_ref()?.is::<std::io::Error>() {
+                    anyhow::Error::from(any.take_downcast::<std::io::Error>()?)
+                } else {
+                    anyhow::Error::msg(format!("{:?}", Self::Any(any)))
+                }
+            }
+            Self::String(string) => anyhow::Error::msg(string.borrow_ref()?.clone()),
+            Self::StaticString(string) => anyhow::Error::msg(string.as_str().to_owned()),
+            value => anyhow::Error::msg(format!("{:?}", value)),
+        })
+    }
+
     /// Try to coerce value into an opaque value.
     #[inline]
     pub fn into_any(self) -> Result<Shared<AnyObj>, VmError> {
@@ -955,8 +1035,23 @@ impl Value {
     /// Optimized function to test if two value pointers are deeply equal to
     /// each other.
     ///
-    /// This is the basis for the eq operation (`==`).
+    /// This is the basis for the eq operation (`==`). Script types and external
+    /// types are compared using their [Protocol::EQ] function if they have
+    /// one, and script types which don't are compared field by field.
     pub(crate) fn value_ptr_eq(vm: &mut Vm, a: &Value, b: &Value) -> Result<bool, VmError> {
+        if let Self::UnitStruct(..)
+        | Self::TupleStruct(..)
+        | Self::Struct(..)
+        | Self::Variant(..)
+        | Self::Any(..) = a
+        {
+            if EnvProtocolCaller.has_protocol_fn(Protocol::EQ, a)? {
+                let value =
+                    EnvProtocolCaller.call_protocol_fn(Protocol::EQ, a.clone(), (b.clone(),))?;
+                return bool::from_value(value);
+            }
+        }
+
         match (a, b) {
             (Self::Unit, Self::Unit) => return Ok(true),
             (Self::Bool(a), Self::Bool(b)) => return Ok(a == b),
@@ -964,6 +1059,7 @@ impl Value {
             (Self::Char(a), Self::Char(b)) => return Ok(a == b),
             (Self::Integer(a), Self::Integer(b)) => return Ok(a == b),
             (Self::Float(a), Self::Float(b)) => return Ok(a == b),
+            (Self::Bytes(a), Self::Bytes(b)) => return Ok(*a.borrow_ref()? == *b.borrow_ref()?),
             (Self::Vec(a), Self::Vec(b)) => {
                 let a = a.borrow_ref()?;
                 let b = b.borrow_ref()?;
@@ -985,7 +1081,10 @@ impl Value {
                 return Range::value_ptr_eq(vm, &*a, &*b);
             }
             (Self::UnitStruct(a), Self::UnitStruct(b)) => {
-                if a.borrow_ref()?.rtti.hash == b.borrow_ref()?.rtti.hash {
+                let a = a.borrow_ref()?;
+                let b = b.borrow_ref()?;
+
+                if a.rtti.hash == b.rtti.hash {
                     // NB: don't get any future ideas, this must fall through to
                     // the VmError below since it's otherwise a comparison
                     // between two incompatible types.
@@ -1029,9 +1128,10 @@ impl Value {
                 let a = a.borrow_ref()?;
                 return Ok(*a == ***b);
             }
-            // fast string comparison: exact string slot.
+            // fast string comparison: static strings from the same slot are
+            // the same allocation.
             (Self::StaticString(a), Self::StaticString(b)) => {
-                return Ok(***a == ***b);
+                return Ok(Arc::ptr_eq(a, b) || ***a == ***b);
             }
             (Self::Option(a), Self::Option(b)) => match (&*a.borrow_ref()?, &*b.borrow_ref()?) {
                 (Some(a), Some(b)) => return Self::value_ptr_eq(vm, a, b),
@@ -1043,11 +1143,7 @@ impl Value {
                 (Err(a), Err(b)) => return Self::value_ptr_eq(vm, a, b),
                 _ => return Ok(false),
             },
-            (a, b) => {
-                if vm.call_instance_fn(a.clone(), Protocol::EQ, (b.clone(),))? {
-                    return bool::from_value(vm.stack_mut().pop()?);
-                }
-            }
+            _ => (),
         }
 
         Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
@@ -1056,6 +1152,84 @@ impl Value {
             rhs: b.type_info()?,
         }))
     }
+
+    /// Test if two values are deeply equal like [Value::value_ptr_eq], for
+    /// native functions which don't have access to the virtual machine.
+    pub(crate) fn value_eq(a: &Value, b: &Value) -> Result<bool, VmError> {
+        // NB: the virtual machine is only passed along by the comparison, so
+        // an empty one is sufficient.
+        let mut vm = Vm::without_runtime(Arc::default());
+        Self::value_ptr_eq(&mut vm, a, b)
+    }
+
+    /// Compare two values, using the [Protocol::CMP] function of the type if
+    /// it's not a built-in type.
+    ///
+    /// This is the basis for sorting.
+    pub(crate) fn value_cmp(a: &Value, b: &Value) -> Result<cmp::Ordering, VmError> {
+        let ordering = match (a, b) {
+            (Self::Unit, Self::Unit) => Some(cmp::Ordering::Equal),
+            (Self::Bool(a), Self::Bool(b)) => Some(a.cmp(b)),
+            (Self::Byte(a), Self::Byte(b)) => Some(a.cmp(b)),
+            (Self::Char(a), Self::Char(b)) => Some(a.cmp(b)),
+            (Self::Integer(a), Self::Integer(b)) => Some(a.cmp(b)),
+            (Self::Float(a), Self::Float(b)) => a.partial_cmp(b),
+            (Self::String(a), Self::String(b)) => Some(a.borrow_ref()?.cmp(&*b.borrow_ref()?)),
+            (Self::StaticString(a), Self::String(b)) => {
+                Some(a.as_str().cmp(b.borrow_ref()?.as_str()))
+            }
+            (Self::String(a), Self::StaticString(b)) => {
+                Some(a.borrow_ref()?.as_str().cmp(b.as_str()))
+            }
+            (Self::StaticString(a), Self::StaticString(b)) => Some(a.as_str().cmp(b.as_str())),
+            (Self::Bytes(a), Self::Bytes(b)) => Some(a.borrow_ref()?.cmp(&*b.borrow_ref()?)),
+            (Self::Vec(a), Self::Vec(b)) => {
+                return Self::slice_cmp(&a.borrow_ref()?, &b.borrow_ref()?);
+            }
+            (Self::Tuple(a), Self::Tuple(b)) => {
+                return Self::slice_cmp(&a.borrow_ref()?, &b.borrow_ref()?);
+            }
+            (Self::Option(a), Self::Option(b)) => match (&*a.borrow_ref()?, &*b.borrow_ref()?) {
+                (Some(a), Some(b)) => return Self::value_cmp(a, b),
+                (a, b) => Some(a.is_some().cmp(&b.is_some())),
+            },
+            (a, b) => {
+                if a.type_hash()? == b.type_hash()?
+                    && EnvProtocolCaller.has_protocol_fn(Protocol::CMP, a)?
+                {
+                    let ordering = EnvProtocolCaller.call_protocol_fn(
+                        Protocol::CMP,
+                        a.clone(),
+                        (b.clone(),),
+                    )?;
+                    return cmp::Ordering::from_value(ordering);
+                }
+
+                None
+            }
+        };
+
+        match ordering {
+            Some(ordering) => Ok(ordering),
+            None => Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
+                op: "cmp",
+                lhs: a.type_info()?,
+                rhs: b.type_info()?,
+            })),
+        }
+    }
+
+    /// Lexicographically compare two slices of values.
+    fn slice_cmp(a: &[Value], b: &[Value]) -> Result<cmp::Ordering, VmError> {
+        for (a, b) in a.iter().zip(b.iter()) {
+            match Self::value_cmp(a, b)? {
+                cmp::Ordering::Equal => (),
+                ordering => return Ok(ordering),
+            }
+        }
+
+        Ok(a.len().cmp(&b.len()))
+    }
 }
 
 impl fmt::Debug for Value {
@@ -1177,7 +1351,7 @@ where
     T: Any,
 {
     fn from(any: T) -> Self {
-        Self::Any(Shared::new(AnyObj::new(any)))
+        Self::Any(Shared::tracked(AnyObj::new(any)))
     }
 }
 
@@ -1219,12 +1393,16 @@ macro_rules! impl_from {
 
 macro_rules! impl_from_wrapper {
     ($($variant:ident => $wrapper:ident<$ty:ty>),* $(,)?) => {
+        impl_from_wrapper!($($variant => $wrapper<$ty>::new),*);
+    };
+
+    ($($variant:ident => $wrapper:ident<$ty:ty>::$new:ident),* $(,)?) => {
         impl_from!($($variant => $wrapper<$ty>),*);
 
         $(
             impl From<$ty> for Value {
                 fn from(value: $ty) -> Self {
-                    Self::$variant($wrapper::new(value))
+                    Self::$variant($wrapper::$new(value))
                 }
             }
 
@@ -1250,23 +1428,26 @@ impl_from! {
 impl_from_wrapper! {
     StaticString => Arc<StaticString>,
     Format => Box<Format>,
-    Iterator => Shared<Iterator>,
-    Bytes => Shared<Bytes>,
-    String => Shared<String>,
-    Vec => Shared<Vec>,
-    Tuple => Shared<Tuple>,
-    Object => Shared<Object>,
-    Range => Shared<Range>,
-    Future => Shared<Future>,
-    Stream => Shared<Stream<Vm>>,
-    Generator => Shared<Generator<Vm>>,
-    GeneratorState => Shared<GeneratorState>,
-    UnitStruct => Shared<UnitStruct>,
-    TupleStruct => Shared<TupleStruct>,
-    Struct => Shared<Struct>,
-    Variant => Shared<Variant>,
-    Function => Shared<Function>,
-    Any => Shared<AnyObj>,
+}
+
+impl_from_wrapper! {
+    Iterator => Shared<Iterator>::tracked,
+    Bytes => Shared<Bytes>::tracked,
+    String => Shared<String>::tracked,
+    Vec => Shared<Vec>::tracked,
+    Tuple => Shared<Tuple>::tracked,
+    Object => Shared<Object>::tracked,
+    Range => Shared<Range>::tracked,
+    Future => Shared<Future>::tracked,
+    Stream => Shared<Stream<Vm>>::tracked,
+    Generator => Shared<Generator<Vm>>::tracked,
+    GeneratorState => Shared<GeneratorState>::tracked,
+    UnitStruct => Shared<UnitStruct>::tracked,
+    TupleStruct => Shared<TupleStruct>::tracked,
+    Struct => Shared<Struct>::tracked,
+    Variant => Shared<Variant>::tracked,
+    Function => Shared<Function>::tracked,
+    Any => Shared<AnyObj>::tracked,
 }
 
 /// Deserialize implementation for value pointers.
@@ -1280,6 +1461,11 @@ impl<'de> de::Deserialize<'de> for Value {
 }
 
 /// Serialize implementation for value pointers.
+///
+/// Structs and objects are serialized as maps, tuples and tuple structs as
+/// sequences, and enum variants and results are externally tagged with the
+/// name of their variant. Values which have no data representation, like
+/// functions, futures or external objects, raise an error.
 impl ser::Serialize for Value {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -1326,23 +1512,52 @@ impl ser::Serialize for Value {
             }
             Value::Object(object) => {
                 let object = object.borrow_ref().map_err(ser::Error::custom)?;
-                let mut serializer = serializer.serialize_map(Some(object.len()))?;
-
-                for (key, value) in &*object {
-                    serializer.serialize_entry(key, value)?;
-                }
-
-                serializer.end()
+                object.serialize(serializer)
             }
             Value::Option(option) => {
                 let option = option.borrow_ref().map_err(ser::Error::custom)?;
                 <Option<Value>>::serialize(&*option, serializer)
             }
+            Value::Result(result) => {
+                let result = result.borrow_ref().map_err(ser::Error::custom)?;
+                let mut serializer = serializer.serialize_map(Some(1))?;
+
+                match &*result {
+                    Ok(value) => serializer.serialize_entry("Ok", value)?,
+                    Err(value) => serializer.serialize_entry("Err", value)?,
+                }
+
+                serializer.end()
+            }
             Value::UnitStruct(..) => serializer.serialize_unit(),
-            Value::TupleStruct(..) => Err(ser::Error::custom("cannot serialize tuple structs")),
-            Value::Struct(..) => Err(ser::Error::custom("cannot serialize objects structs")),
-            Value::Variant(..) => Err(ser::Error::custom("cannot serialize variants")),
-            Value::Result(..) => Err(ser::Error::custom("cannot serialize results")),
+            Value::TupleStruct(tuple) => {
+                let tuple = tuple.borrow_ref().map_err(ser::Error::custom)?;
+                <[Value]>::serialize(&tuple.data, serializer)
+            }
+            Value::Struct(object) => {
+                let object = object.borrow_ref().map_err(ser::Error::custom)?;
+                object.data.serialize(serializer)
+            }
+            // NB: variants are externally tagged with the name of the
+            // variant, the same way serde represents enums by default.
+            Value::Variant(variant) => {
+                let variant = variant.borrow_ref().map_err(ser::Error::custom)?;
+                let name = variant.rtti.item.iter().next_back_str().unwrap_or_default();
+
+                match &variant.data {
+                    VariantData::Unit => serializer.serialize_str(name),
+                    VariantData::Tuple(tuple) => {
+                        let mut serializer = serializer.serialize_map(Some(1))?;
+                        serializer.serialize_entry(name, &**tuple)?;
+                        serializer.end()
+                    }
+                    VariantData::Struct(object) => {
+                        let mut serializer = serializer.serialize_map(Some(1))?;
+                        serializer.serialize_entry(name, object)?;
+                        serializer.end()
+                    }
+                }
+            }
             Value::Type(..) => Err(ser::Error::custom("cannot serialize types")),
             Value::Future(..) => Err(ser::Error::custom("cannot serialize futures")),
             Value::Stream(..) => Err(ser::Error::custom("cannot serialize streams")),
@@ -1373,7 +1588,7 @@ impl<'de> de::Visitor<'de> for VmVisitor {
     where
         E: de::Error,
     {
-        Ok(Value::String(Shared::new(value.to_owned())))
+        Ok(Value::String(Shared::tracked(value.to_owned())))
     }
 
     #[inline]
@@ -1381,7 +1596,7 @@ impl<'de> de::Visitor<'de> for VmVisitor {
     where
         E: de::Error,
     {
-        Ok(Value::String(Shared::new(value)))
+        Ok(Value::String(Shared::tracked(value)))
     }
 
     #[inline]
@@ -1389,7 +1604,7 @@ impl<'de> de::Visitor<'de> for VmVisitor {
     where
         E: de::Error,
     {
-        Ok(Value::Bytes(Shared::new(Bytes::from_vec(v.to_vec()))))
+        Ok(Value::Bytes(Shared::tracked(Bytes::from_vec(v.to_vec()))))
     }
 
     #[inline]
@@ -1397,7 +1612,7 @@ impl<'de> de::Visitor<'de> for VmVisitor {
     where
         E: de::Error,
     {
-        Ok(Value::Bytes(Shared::new(Bytes::from_vec(v))))
+        Ok(Value::Bytes(Shared::tracked(Bytes::from_vec(v))))
     }
 
     #[inline]
@@ -1437,7 +1652,13 @@ impl<'de> de::Visitor<'de> for VmVisitor {
     where
         E: de::Error,
     {
-        Ok(Value::Integer(v as i64))
+        match i64::try_from(v) {
+            Ok(v) => Ok(Value::Integer(v)),
+            Err(..) => Err(E::invalid_value(
+                de::Unexpected::Other("128-bit integer"),
+                &self,
+            )),
+        }
     }
 
     #[inline]
@@ -1469,7 +1690,10 @@ impl<'de> de::Visitor<'de> for VmVisitor {
     where
         E: de::Error,
     {
-        Ok(Value::Integer(v as i64))
+        match i64::try_from(v) {
+            Ok(v) => Ok(Value::Integer(v)),
+            Err(..) => Err(E::invalid_value(de::Unexpected::Unsigned(v), &self)),
+        }
     }
 
     #[inline]
@@ -1477,7 +1701,29 @@ impl<'de> de::Visitor<'de> for VmVisitor {
     where
         E: de::Error,
     {
-        Ok(Value::Integer(v as i64))
+        match i64::try_from(v) {
+            Ok(v) => Ok(Value::Integer(v)),
+            Err(..) => Err(E::invalid_value(
+                de::Unexpected::Other("128-bit integer"),
+                &self,
+            )),
+        }
+    }
+
+    #[inline]
+    fn visit_f32<E>(self, v: f32) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Value::Float(v as f64))
+    }
+
+    #[inline]
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Value::Float(v))
     }
 
     #[inline]
@@ -1504,6 +1750,22 @@ impl<'de> de::Visitor<'de> for VmVisitor {
         Ok(Value::Unit)
     }
 
+    #[inline]
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        Value::deserialize(deserializer)
+    }
+
+    #[inline]
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        Value::deserialize(deserializer)
+    }
+
     #[inline]
     fn visit_seq<V>(self, mut visitor: V) -> Result<Self::Value, V::Error>
     where
@@ -1519,7 +1781,7 @@ impl<'de> de::Visitor<'de> for VmVisitor {
             vec.push(elem);
         }
 
-        Ok(Value::Vec(Shared::new(Vec::from(vec))))
+        Ok(Value::Vec(Shared::tracked(Vec::from(vec))))
     }
 
     #[inline]
@@ -1533,7 +1795,7 @@ impl<'de> de::Visitor<'de> for VmVisitor {
             object.insert(key, value);
         }
 
-        Ok(Value::Object(Shared::new(object)))
+        Ok(Value::Object(Shared::tracked(object)))
     }
 }
 
@@ -0,0 +1,728 @@
+//! Conversion between values and any type which implements serde's
+//! [Serialize] or [Deserialize].
+//!
+//! Values are represented the same way as they are when a [Value] is
+//! serialized, so structs become objects, sequences become vectors and enums
+//! are externally tagged with the name of their variant.
+
+use crate::runtime::{Bytes, Object, Shared, Tuple, Value, VariantData, Vec, VmError, VmErrorKind};
+use serde::de::{self, DeserializeOwned, IntoDeserializer};
+use serde::ser::{self, Serialize};
+use std::fmt;
+use std::vec;
+
+/// Convert any type which implements [Serialize] into a [Value].
+///
+/// # Examples
+///
+/// ```
+/// use rune::runtime::Object;
+/// use rune::FromValue;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Point {
+///     x: i64,
+///     y: i64,
+/// }
+///
+/// # fn main() -> rune::Result<()> {
+/// let value = rune::to_value(Point { x: 1, y: 2 })?;
+/// let object = Object::from_value(value)?;
+/// assert_eq!(object.len(), 2);
+/// # Ok(()) }
+/// ```
+pub fn to_value<T>(value: T) -> Result<Value, VmError>
+where
+    T: Serialize,
+{
+    value.serialize(ValueSerializer)
+}
+
+/// Convert a [Value] into any type which implements [Deserialize][de::Deserialize].
+///
+/// # Examples
+///
+/// ```
+/// use rune::{Context, Source, Sources, Vm};
+/// use serde::Deserialize;
+/// use std::sync::Arc;
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Point {
+///     x: i64,
+///     y: i64,
+/// }
+///
+/// # fn main() -> rune::Result<()> {
+/// let context = Context::with_default_modules()?;
+///
+/// let mut sources = Sources::new();
+/// sources.insert(Source::new("main", "pub fn main() { #{ x: 1, y: 2 } }"));
+///
+/// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+/// let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+///
+/// let point = rune::from_value::<Point>(vm.call(["main"], ())?)?;
+/// assert_eq!(point, Point { x: 1, y: 2 });
+/// # Ok(()) }
+/// ```
+pub fn from_value<T>(value: Value) -> Result<T, VmError>
+where
+    T: DeserializeOwned,
+{
+    T::deserialize(value)
+}
+
+impl ser::Error for VmError {
+    fn custom<T>(message: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        VmError::from(VmErrorKind::Serde {
+            message: message.to_string().into(),
+        })
+    }
+}
+
+impl de::Error for VmError {
+    fn custom<T>(message: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        VmError::from(VmErrorKind::Serde {
+            message: message.to_string().into(),
+        })
+    }
+}
+
+/// Serializer which produces a [Value].
+struct ValueSerializer;
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = VmError;
+    type SerializeSeq = SerializeVec;
+    type SerializeTuple = SerializeTuple;
+    type SerializeTupleStruct = SerializeTuple;
+    type SerializeTupleVariant = SerializeTupleVariant;
+    type SerializeMap = SerializeObject;
+    type SerializeStruct = SerializeObject;
+    type SerializeStructVariant = SerializeStructVariant;
+
+    fn serialize_bool(self, v: bool) -> Result<Value, VmError> {
+        Ok(Value::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value, VmError> {
+        Ok(Value::Integer(v as i64))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value, VmError> {
+        Ok(Value::Integer(v as i64))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value, VmError> {
+        Ok(Value::Integer(v as i64))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value, VmError> {
+        Ok(Value::Integer(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Value, VmError> {
+        integer(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value, VmError> {
+        Ok(Value::Integer(v as i64))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value, VmError> {
+        Ok(Value::Integer(v as i64))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value, VmError> {
+        Ok(Value::Integer(v as i64))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value, VmError> {
+        integer(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Value, VmError> {
+        integer(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value, VmError> {
+        Ok(Value::Float(v as f64))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value, VmError> {
+        Ok(Value::Float(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value, VmError> {
+        Ok(Value::Char(v))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value, VmError> {
+        Ok(Value::from(v.to_owned()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value, VmError> {
+        Ok(Value::from(Bytes::from_vec(v.to_vec())))
+    }
+
+    fn serialize_none(self) -> Result<Value, VmError> {
+        Ok(Value::from(Shared::tracked(None)))
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Value, VmError>
+    where
+        T: ?Sized + Serialize,
+    {
+        Ok(Value::from(Shared::tracked(Some(value.serialize(self)?))))
+    }
+
+    fn serialize_unit(self) -> Result<Value, VmError> {
+        Ok(Value::Unit)
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<Value, VmError> {
+        Ok(Value::Unit)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<Value, VmError> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, _: &'static str, value: &T) -> Result<Value, VmError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value, VmError>
+    where
+        T: ?Sized + Serialize,
+    {
+        let mut object = Object::with_capacity(1);
+        object.insert(variant.to_owned(), value.serialize(self)?);
+        Ok(Value::from(object))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeVec, VmError> {
+        Ok(SerializeVec {
+            vec: Vec::with_capacity(len.unwrap_or_default()),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeTuple, VmError> {
+        Ok(SerializeTuple {
+            values: vec::Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        len: usize,
+    ) -> Result<SerializeTuple, VmError> {
+        self.serialize_tuple(len)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeTupleVariant, VmError> {
+        Ok(SerializeTupleVariant {
+            variant,
+            values: vec::Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<SerializeObject, VmError> {
+        Ok(SerializeObject {
+            object: Object::new(),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, _: &'static str, len: usize) -> Result<SerializeObject, VmError> {
+        Ok(SerializeObject {
+            object: Object::with_capacity(len),
+            key: None,
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeStructVariant, VmError> {
+        Ok(SerializeStructVariant {
+            variant,
+            object: Object::with_capacity(len),
+        })
+    }
+}
+
+/// Convert a wide integer into a value, erroring if it's out of range.
+fn integer<T>(v: T) -> Result<Value, VmError>
+where
+    T: Copy + fmt::Display + TryInto<i64>,
+{
+    match v.try_into() {
+        Ok(v) => Ok(Value::Integer(v)),
+        Err(..) => Err(ser::Error::custom(format_args!(
+            "integer `{}` is out of range",
+            v
+        ))),
+    }
+}
+
+struct SerializeVec {
+    vec: Vec,
+}
+
+impl ser::SerializeSeq for SerializeVec {
+    type Ok = Value;
+    type Error = VmError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), VmError>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.vec.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, VmError> {
+        Ok(Value::from(self.vec))
+    }
+}
+
+struct SerializeTuple {
+    values: vec::Vec<Value>,
+}
+
+impl ser::SerializeTuple for SerializeTuple {
+    type Ok = Value;
+    type Error = VmError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), VmError>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.values.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, VmError> {
+        Ok(Value::from(Tuple::from(self.values)))
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeTuple {
+    type Ok = Value;
+    type Error = VmError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), VmError>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeTuple::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value, VmError> {
+        ser::SerializeTuple::end(self)
+    }
+}
+
+struct SerializeTupleVariant {
+    variant: &'static str,
+    values: vec::Vec<Value>,
+}
+
+impl ser::SerializeTupleVariant for SerializeTupleVariant {
+    type Ok = Value;
+    type Error = VmError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), VmError>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.values.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, VmError> {
+        let mut object = Object::with_capacity(1);
+        object.insert(
+            self.variant.to_owned(),
+            Value::from(Tuple::from(self.values)),
+        );
+        Ok(Value::from(object))
+    }
+}
+
+struct SerializeObject {
+    object: Object,
+    key: Option<String>,
+}
+
+impl ser::SerializeMap for SerializeObject {
+    type Ok = Value;
+    type Error = VmError;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), VmError>
+    where
+        T: ?Sized + Serialize,
+    {
+        let key = match key.serialize(ValueSerializer)? {
+            Value::String(string) => string.take()?,
+            Value::Char(c) => c.to_string(),
+            Value::Integer(integer) => integer.to_string(),
+            Value::Bool(b) => b.to_string(),
+            value => {
+                return Err(ser::Error::custom(format_args!(
+                    "object keys must be strings, but got `{}`",
+                    value.type_info()?
+                )))
+            }
+        };
+
+        self.key = Some(key);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), VmError>
+    where
+        T: ?Sized + Serialize,
+    {
+        let key = match self.key.take() {
+            Some(key) => key,
+            None => return Err(ser::Error::custom("value serialized before its key")),
+        };
+
+        self.object.insert(key, value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, VmError> {
+        Ok(Value::from(self.object))
+    }
+}
+
+impl ser::SerializeStruct for SerializeObject {
+    type Ok = Value;
+    type Error = VmError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), VmError>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.object
+            .insert(key.to_owned(), value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, VmError> {
+        Ok(Value::from(self.object))
+    }
+}
+
+struct SerializeStructVariant {
+    variant: &'static str,
+    object: Object,
+}
+
+impl ser::SerializeStructVariant for SerializeStructVariant {
+    type Ok = Value;
+    type Error = VmError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), VmError>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.object
+            .insert(key.to_owned(), value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, VmError> {
+        let mut object = Object::with_capacity(1);
+        object.insert(self.variant.to_owned(), Value::from(self.object));
+        Ok(Value::from(object))
+    }
+}
+
+impl<'de> IntoDeserializer<'de, VmError> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+/// Values can be deserialized into any type which implements
+/// [Deserialize][de::Deserialize], see [from_value].
+impl<'de> de::Deserializer<'de> for Value {
+    type Error = VmError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, VmError>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self {
+            Value::Unit => visitor.visit_unit(),
+            Value::Bool(b) => visitor.visit_bool(b),
+            Value::Byte(b) => visitor.visit_u8(b),
+            Value::Char(c) => visitor.visit_char(c),
+            Value::Integer(integer) => visitor.visit_i64(integer),
+            Value::Float(float) => visitor.visit_f64(float),
+            Value::StaticString(string) => visitor.visit_str(string.as_str()),
+            Value::String(string) => visitor.visit_string(string.borrow_ref()?.clone()),
+            Value::Bytes(bytes) => visitor.visit_byte_buf(bytes.borrow_ref()?.to_vec()),
+            Value::Vec(vec) => visit_seq(vec.borrow_ref()?.to_vec(), visitor),
+            Value::Tuple(tuple) => visit_seq(tuple.borrow_ref()?.to_vec(), visitor),
+            Value::Object(object) => visit_object(&*object.borrow_ref()?, visitor),
+            Value::Option(option) => match option.borrow_ref()?.clone() {
+                Some(value) => visitor.visit_some(value),
+                None => visitor.visit_none(),
+            },
+            Value::Result(result) => match result.borrow_ref()?.clone() {
+                Ok(value) => visit_tagged("Ok", value, visitor),
+                Err(value) => visit_tagged("Err", value, visitor),
+            },
+            Value::UnitStruct(..) => visitor.visit_unit(),
+            Value::TupleStruct(tuple) => visit_seq(tuple.borrow_ref()?.data.to_vec(), visitor),
+            Value::Struct(object) => visit_object(&object.borrow_ref()?.data, visitor),
+            Value::Variant(variant) => {
+                let variant = variant.borrow_ref()?;
+                let name = variant.rtti.item.iter().next_back_str().unwrap_or_default();
+
+                match &variant.data {
+                    VariantData::Unit => visitor.visit_str(name),
+                    VariantData::Tuple(tuple) => {
+                        visit_tagged(name, Value::from(tuple.clone()), visitor)
+                    }
+                    VariantData::Struct(object) => {
+                        visit_tagged(name, Value::from(object.clone()), visitor)
+                    }
+                }
+            }
+            value => Err(de::Error::custom(format_args!(
+                "cannot deserialize from `{}`",
+                value.type_info()?
+            ))),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, VmError>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self {
+            Value::Unit => visitor.visit_none(),
+            Value::Option(option) => match option.borrow_ref()?.clone() {
+                Some(value) => visitor.visit_some(value),
+                None => visitor.visit_none(),
+            },
+            value => visitor.visit_some(value),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(self, _: &'static str, visitor: V) -> Result<V::Value, VmError>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, VmError>
+    where
+        V: de::Visitor<'de>,
+    {
+        let (variant, value) = match self {
+            Value::StaticString(string) => (string.as_str().to_owned(), None),
+            Value::String(string) => (string.borrow_ref()?.clone(), None),
+            Value::Object(object) => {
+                let object = object.borrow_ref()?;
+                let mut it = object.iter();
+
+                match (it.next(), it.next()) {
+                    (Some((variant, value)), None) => (variant.clone(), Some(value.clone())),
+                    _ => {
+                        return Err(de::Error::invalid_length(
+                            object.len(),
+                            &"an object with a single key",
+                        ))
+                    }
+                }
+            }
+            Value::Variant(variant) => {
+                let variant = variant.borrow_ref()?;
+                let name = variant.rtti.item.iter().next_back_str().unwrap_or_default();
+
+                let value = match &variant.data {
+                    VariantData::Unit => None,
+                    VariantData::Tuple(tuple) => Some(Value::from(tuple.clone())),
+                    VariantData::Struct(object) => Some(Value::from(object.clone())),
+                };
+
+                (name.to_owned(), value)
+            }
+            value => {
+                return Err(de::Error::custom(format_args!(
+                    "expected a string or an object for an enum, but got `{}`",
+                    value.type_info()?
+                )))
+            }
+        };
+
+        visitor.visit_enum(EnumDeserializer { variant, value })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+fn visit_seq<'de, V>(values: vec::Vec<Value>, visitor: V) -> Result<V::Value, VmError>
+where
+    V: de::Visitor<'de>,
+{
+    let mut seq = de::value::SeqDeserializer::new(values.into_iter());
+    let value = visitor.visit_seq(&mut seq)?;
+    seq.end()?;
+    Ok(value)
+}
+
+fn visit_object<'de, V>(object: &Object, visitor: V) -> Result<V::Value, VmError>
+where
+    V: de::Visitor<'de>,
+{
+    let entries = object
+        .iter()
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect::<vec::Vec<_>>();
+
+    let mut map = de::value::MapDeserializer::new(entries.into_iter());
+    let value = visitor.visit_map(&mut map)?;
+    map.end()?;
+    Ok(value)
+}
+
+/// Visit a value which is externally tagged with the name of a variant.
+fn visit_tagged<'de, V>(tag: &str, value: Value, visitor: V) -> Result<V::Value, VmError>
+where
+    V: de::Visitor<'de>,
+{
+    let mut map = de::value::MapDeserializer::new(std::iter::once((tag.to_owned(), value)));
+    let value = visitor.visit_map(&mut map)?;
+    map.end()?;
+    Ok(value)
+}
+
+struct EnumDeserializer {
+    variant: String,
+    value: Option<Value>,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumDeserializer {
+    type Error = VmError;
+    type Variant = VariantDeserializer;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, VariantDeserializer), VmError>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let variant: de::value::StringDeserializer<VmError> = self.variant.into_deserializer();
+        let variant = seed.deserialize(variant)?;
+
+        Ok((variant, VariantDeserializer { value: self.value }))
+    }
+}
+
+struct VariantDeserializer {
+    value: Option<Value>,
+}
+
+impl<'de> de::VariantAccess<'de> for VariantDeserializer {
+    type Error = VmError;
+
+    fn unit_variant(self) -> Result<(), VmError> {
+        match self.value {
+            None | Some(Value::Unit) => Ok(()),
+            Some(value) => Err(de::Error::invalid_type(
+                de::Unexpected::Other(&value.type_info()?.to_string()),
+                &"unit variant",
+            )),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, VmError>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        match self.value {
+            Some(value) => seed.deserialize(value),
+            None => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"newtype variant",
+            )),
+        }
+    }
+
+    fn tuple_variant<V>(self, _: usize, visitor: V) -> Result<V::Value, VmError>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value {
+            Some(value) => de::Deserializer::deserialize_seq(value, visitor),
+            None => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"tuple variant",
+            )),
+        }
+    }
+
+    fn struct_variant<V>(self, _: &'static [&'static str], visitor: V) -> Result<V::Value, VmError>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value {
+            Some(value) => de::Deserializer::deserialize_map(value, visitor),
+            None => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"struct variant",
+            )),
+        }
+    }
+}
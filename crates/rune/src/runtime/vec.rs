@@ -73,13 +73,16 @@ impl Vec {
         self.inner.len()
     }
 
+    /// Returns the number of elements the dynamic vector can hold without
+    /// reallocating.
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
     /// Set by index
     pub fn set(&mut self, index: usize, value: Value) -> Result<(), VmError> {
         if index >= self.len() {
-            Err(VmError::from(VmErrorKind::OutOfRange {
-                index: index.into(),
-                len: self.len().into(),
-            }))
+            Err(self.out_of_range(index))
         } else {
             self.inner[index] = value;
             Ok(())
@@ -145,8 +148,106 @@ impl Vec {
 
     /// Inserts an element at position index within the vector, shifting all
     /// elements after it to the right.
-    pub fn insert(&mut self, index: usize, value: Value) {
+    ///
+    /// Errors if the index is greater than the length of the vector.
+    pub fn insert(&mut self, index: usize, value: Value) -> Result<(), VmError> {
+        if index > self.len() {
+            return Err(self.out_of_range(index));
+        }
+
         self.inner.insert(index, value);
+        Ok(())
+    }
+
+    /// Removes the element at the specified index and returns it, replacing it
+    /// with the last element of the vector.
+    ///
+    /// This doesn't preserve ordering, but is O(1).
+    pub fn swap_remove(&mut self, index: usize) -> Result<Value, VmError> {
+        if index >= self.len() {
+            return Err(self.out_of_range(index));
+        }
+
+        Ok(self.inner.swap_remove(index))
+    }
+
+    /// Shortens the vector to the given length, dropping the rest of the
+    /// elements. Has no effect if the vector is already shorter than that.
+    pub fn truncate(&mut self, len: usize) {
+        self.inner.truncate(len);
+    }
+
+    /// Resizes the vector to the given length, either by truncating it or by
+    /// filling it with the given value.
+    ///
+    /// Note that the value is filled in by reference the same way as an
+    /// assignment is, so a vector or an object would be shared among all new
+    /// elements.
+    pub fn resize(&mut self, len: usize, value: Value) {
+        self.inner.resize(len, value);
+    }
+
+    /// Splits the vector in two at the given index, returning the elements
+    /// from the index and onwards.
+    ///
+    /// Errors if the index is greater than the length of the vector.
+    pub fn split_off(&mut self, at: usize) -> Result<Self, VmError> {
+        if at > self.len() {
+            return Err(self.out_of_range(at));
+        }
+
+        Ok(Self::from(self.inner.split_off(at)))
+    }
+
+    /// Removes consecutive elements which are equal to each other.
+    pub fn dedup(&mut self) -> Result<(), VmError> {
+        let mut error = None;
+
+        self.inner.dedup_by(|a, b| {
+            if error.is_some() {
+                return false;
+            }
+
+            match Value::value_eq(a, b) {
+                Ok(equal) => equal,
+                Err(e) => {
+                    error = Some(e);
+                    false
+                }
+            }
+        });
+
+        match error {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Binary searches the sorted vector for the given value.
+    ///
+    /// Returns `Ok` with the index of a matching element, or `Err` with the
+    /// index where the value could be inserted to keep the vector sorted.
+    pub fn binary_search(&self, value: Value) -> Result<Result<usize, usize>, VmError> {
+        let mut error = None;
+
+        let result = self.inner.binary_search_by(|probe| {
+            if error.is_some() {
+                return cmp::Ordering::Equal;
+            }
+
+            match Value::value_cmp(probe, &value) {
+                Ok(ordering) => ordering,
+                Err(e) => {
+                    error = Some(e);
+                    cmp::Ordering::Equal
+                }
+            }
+        });
+
+        match error {
+            Some(error) => Err(error),
+            None => Ok(result),
+        }
     }
 
     /// Extend this vector with something that implements the into_iter
@@ -166,6 +267,14 @@ impl Vec {
         Iterator::from_double_ended("std::vec::Iter", self.clone().into_iter())
     }
 
+    /// Construct an error for the given index being out of range.
+    fn out_of_range(&self, index: usize) -> VmError {
+        VmError::from(VmErrorKind::OutOfRange {
+            index: index.into(),
+            len: self.len().into(),
+        })
+    }
+
     /// Compare two vectors for equality.
     pub(crate) fn value_ptr_eq(vm: &mut Vm, a: &Self, b: &Self) -> Result<bool, VmError> {
         if a.len() != b.len() {
@@ -341,6 +450,6 @@ where
             vec.push(value.to_value()?);
         }
 
-        Ok(Value::from(Shared::new(Vec::from(vec))))
+        Ok(Value::from(Shared::tracked(Vec::from(vec))))
     }
 }
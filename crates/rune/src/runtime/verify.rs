@@ -0,0 +1,674 @@
+//! Verification of units before they're run.
+//!
+//! Units which are built by the compiler are well-formed by construction, but
+//! units which are loaded from elsewhere, like precompiled bytecode, could
+//! contain anything. Verification checks that every function in a unit stays
+//! within its own instructions, only refers to slots and functions which exist,
+//! and uses the stack consistently, so that such a unit can't put the virtual
+//! machine into an unexpected state.
+
+use crate::collections::{HashMap, HashSet};
+use crate::runtime::{Call, Inst, InstAddress, InstTarget, InstVariant, Unit, UnitFn};
+use crate::Hash;
+use std::ops::Range;
+use thiserror::Error;
+
+/// An error raised when a unit fails verification.
+#[derive(Debug, Error)]
+#[error("{kind}")]
+pub struct VerifyError {
+    kind: VerifyErrorKind,
+}
+
+impl From<VerifyErrorKind> for VerifyError {
+    fn from(kind: VerifyErrorKind) -> Self {
+        Self { kind }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum VerifyErrorKind {
+    #[error("function `{hash}` has an offset {offset} outside of the unit")]
+    FunctionOutOfBounds { hash: Hash, offset: usize },
+    #[error("instruction at {ip} jumps outside of its function")]
+    JumpOutOfBounds { ip: usize },
+    #[error("instruction at {ip} calls offset {offset} which isn't a function")]
+    MissingCallOffset { ip: usize, offset: usize },
+    #[error("instruction at {ip} calls a function in a way which doesn't match its signature")]
+    CallMismatch { ip: usize },
+    #[error("instruction at {ip} refers to missing static string slot {slot}")]
+    MissingStaticString { ip: usize, slot: usize },
+    #[error("instruction at {ip} refers to missing static byte string slot {slot}")]
+    MissingStaticBytes { ip: usize, slot: usize },
+    #[error("instruction at {ip} refers to missing static tuple slot {slot}")]
+    MissingStaticTuple { ip: usize, slot: usize },
+    #[error("instruction at {ip} refers to missing static object keys slot {slot}")]
+    MissingStaticObjectKeys { ip: usize, slot: usize },
+    #[error("instruction at {ip} addresses stack offset {offset} which isn't in use")]
+    AddressOutOfBounds { ip: usize, offset: usize },
+    #[error("instruction at {ip} pops more values than there are on the stack")]
+    StackUnderflow { ip: usize },
+    #[error("instruction at {ip} is reached with both {a} and {b} values on the stack")]
+    StackMismatch { ip: usize, a: usize, b: usize },
+    #[error("instruction at {ip} continues past the end of its function")]
+    MissingReturn { ip: usize },
+}
+
+/// Verify the given unit.
+pub(crate) fn verify(unit: &Unit, instructions: &[Inst]) -> Result<(), VerifyError> {
+    let len = instructions.len();
+
+    // The number of values a closure keeps in its environment, which is
+    // unpacked onto the stack when it's called.
+    let mut environments = HashMap::<Hash, Option<usize>>::new();
+
+    for inst in instructions {
+        if let Inst::Closure { hash, count } = *inst {
+            let env = environments.entry(hash).or_insert(Some(count));
+
+            if *env != Some(count) {
+                *env = None;
+            }
+        }
+    }
+
+    let mut functions = HashMap::<usize, Function>::new();
+
+    for (hash, f) in unit.iter_functions() {
+        if let UnitFn::Offset { offset, call, args } = *f {
+            if offset >= len {
+                return Err(VerifyError::from(VerifyErrorKind::FunctionOutOfBounds {
+                    hash,
+                    offset,
+                }));
+            }
+
+            let env = match environments.get(&hash) {
+                None => Env::None,
+                Some(Some(count)) => Env::Some(*count),
+                Some(None) => Env::Unknown,
+            };
+
+            let f = Function {
+                call: Some(call),
+                args: Some(args),
+                env,
+            };
+
+            let existing = functions.entry(offset).or_insert(f);
+
+            if existing.call != f.call {
+                existing.call = None;
+            }
+
+            if existing.args != f.args {
+                existing.args = None;
+            }
+
+            if existing.env != f.env {
+                existing.env = Env::Unknown;
+            }
+        }
+    }
+
+    // Every function is assumed to extend up until the next one, and any
+    // instructions before the first function belong to a block of their own.
+    let mut starts = functions.keys().copied().collect::<HashSet<_>>();
+    starts.insert(0);
+    let mut starts = starts.into_iter().collect::<Vec<_>>();
+    starts.sort_unstable();
+
+    for (n, &start) in starts.iter().enumerate() {
+        let end = starts.get(n + 1).copied().unwrap_or(len);
+
+        for ip in start..end {
+            check_inst(unit, &functions, instructions, ip, start..end)?;
+        }
+
+        if let Some(f) = functions.get(&start) {
+            check_stack(unit, f, instructions, start..end)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Information on a function in the unit, which might be shared between
+/// multiple hashes. Fields are `None` if the hashes disagree.
+#[derive(Debug, Clone, Copy)]
+struct Function {
+    call: Option<Call>,
+    args: Option<usize>,
+    env: Env,
+}
+
+/// The environment of a function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Env {
+    /// The function isn't a closure with an environment.
+    None,
+    /// The function is a closure with an environment of the given size.
+    Some(usize),
+    /// The environment can't be determined.
+    Unknown,
+}
+
+impl Function {
+    /// The number of values on the stack when the function is entered.
+    fn entry(&self) -> Option<usize> {
+        match self.env {
+            Env::None => self.args,
+            Env::Some(..) => Some(self.args? + 1),
+            Env::Unknown => None,
+        }
+    }
+}
+
+/// Check the operands of the instruction at `ip`, which belongs to the
+/// function occupying `range`.
+fn check_inst(
+    unit: &Unit,
+    functions: &HashMap<usize, Function>,
+    instructions: &[Inst],
+    ip: usize,
+    range: Range<usize>,
+) -> Result<(), VerifyError> {
+    let inst = instructions[ip];
+
+    if let Some(offset) = jump_offset(&inst) {
+        jump(ip, offset, &range)?;
+    }
+
+    match inst {
+        Inst::CallOffset { offset, call, args } => {
+            let f = functions
+                .get(&offset)
+                .ok_or(VerifyErrorKind::MissingCallOffset { ip, offset })?;
+
+            if f.call != Some(call) || f.args != Some(args) || f.env != Env::None {
+                return Err(VerifyError::from(VerifyErrorKind::CallMismatch { ip }));
+            }
+        }
+        Inst::TailCall { args, offset } => {
+            let f = functions.get(&range.start);
+
+            if jump(ip, offset, &range)? != range.start
+                || !matches!(f, Some(f) if f.entry() == Some(args))
+            {
+                return Err(VerifyError::from(VerifyErrorKind::CallMismatch { ip }));
+            }
+        }
+        Inst::String { slot }
+        | Inst::EqStaticString { slot }
+        | Inst::ObjectIndexGet { slot }
+        | Inst::ObjectIndexSet { slot }
+        | Inst::ObjectIndexGetAt { slot, .. }
+        | Inst::LoadGlobal { slot }
+        | Inst::Assign {
+            target: InstTarget::Field(slot),
+            ..
+        } if unit.lookup_string(slot).is_err() => {
+            return Err(VerifyError::from(VerifyErrorKind::MissingStaticString {
+                ip,
+                slot,
+            }));
+        }
+        Inst::Bytes { slot } if unit.lookup_bytes(slot).is_err() => {
+            return Err(VerifyError::from(VerifyErrorKind::MissingStaticBytes {
+                ip,
+                slot,
+            }));
+        }
+        Inst::StaticTuple { slot } if unit.lookup_tuple(slot).is_err() => {
+            return Err(VerifyError::from(VerifyErrorKind::MissingStaticTuple {
+                ip,
+                slot,
+            }));
+        }
+        Inst::Object { slot }
+        | Inst::Struct { slot, .. }
+        | Inst::StructVariant { slot, .. }
+        | Inst::MatchObject { slot, .. }
+            if unit.lookup_object_keys(slot).is_none() =>
+        {
+            return Err(VerifyError::from(
+                VerifyErrorKind::MissingStaticObjectKeys { ip, slot },
+            ));
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// The relative jump performed by an instruction, if any.
+pub(crate) fn jump_offset(inst: &Inst) -> Option<isize> {
+    match *inst {
+        Inst::Jump { offset }
+        | Inst::JumpIf { offset }
+        | Inst::JumpIfOrPop { offset }
+        | Inst::JumpIfNotOrPop { offset }
+        | Inst::JumpIfBranch { offset, .. }
+        | Inst::PopAndJumpIfNot { offset, .. }
+        | Inst::TailCall { offset, .. }
+        | Inst::IterNext { jump: offset, .. } => Some(offset),
+        _ => None,
+    }
+}
+
+/// Calculate the target of a jump from `ip`, making sure it stays within
+/// `range`.
+fn jump(ip: usize, offset: isize, range: &Range<usize>) -> Result<usize, VerifyError> {
+    (ip as isize)
+        .checked_add(offset)
+        .and_then(|target| target.checked_add(1))
+        .and_then(|target| usize::try_from(target).ok())
+        .filter(|target| range.contains(target))
+        .ok_or_else(|| VerifyError::from(VerifyErrorKind::JumpOutOfBounds { ip }))
+}
+
+/// Check that the function occupying `range` uses the stack consistently, by
+/// following every path through it and keeping track of how many values are
+/// on the stack at each instruction.
+///
+/// Functions where the stack can't be determined statically, like closures
+/// with an unknown environment, are skipped.
+fn check_stack(
+    unit: &Unit,
+    f: &Function,
+    instructions: &[Inst],
+    range: Range<usize>,
+) -> Result<(), VerifyError> {
+    let entry = match f.entry() {
+        Some(entry) => entry,
+        None => return Ok(()),
+    };
+
+    let mut depths = vec![None; range.len()];
+    let mut queue = vec![(range.start, entry)];
+
+    while let Some((ip, depth)) = queue.pop() {
+        match depths[ip - range.start] {
+            Some(existing) if existing == depth => continue,
+            Some(existing) => {
+                return Err(VerifyError::from(VerifyErrorKind::StackMismatch {
+                    ip,
+                    a: existing,
+                    b: depth,
+                }));
+            }
+            None => depths[ip - range.start] = Some(depth),
+        }
+
+        let mut stack = Frame { ip, depth };
+        let inst = instructions[ip];
+
+        match inst {
+            Inst::Not
+            | Inst::Neg
+            | Inst::LoadInstanceFn { .. }
+            | Inst::TupleIndexGet { .. }
+            | Inst::ObjectIndexGet { .. }
+            | Inst::Await
+            | Inst::Format { .. }
+            | Inst::IsUnit
+            | Inst::EqByte { .. }
+            | Inst::EqCharacter { .. }
+            | Inst::EqInteger { .. }
+            | Inst::EqBool { .. }
+            | Inst::EqStaticString { .. }
+            | Inst::MatchType { .. }
+            | Inst::MatchSequence { .. }
+            | Inst::MatchObject { .. }
+            | Inst::Yield => {
+                stack.pop(1)?;
+                stack.push(1);
+            }
+            Inst::Closure { count, .. } => {
+                stack.pop(count)?;
+                stack.push(1);
+            }
+            Inst::Call { args, .. }
+            | Inst::CallOffset { args, .. }
+            | Inst::Vec { count: args }
+            | Inst::Tuple { count: args }
+            | Inst::StringConcat { len: args, .. } => {
+                stack.pop(args)?;
+                stack.push(1);
+            }
+            Inst::CallInstance { args, .. } | Inst::CallFn { args } => {
+                stack.pop(args)?;
+                stack.pop(1)?;
+                stack.push(1);
+            }
+            Inst::TailCall { args, .. } => {
+                stack.pop(args)?;
+                queue.push((range.start, args));
+                continue;
+            }
+            Inst::IndexGet { target, index } => {
+                stack.address(index)?;
+                stack.address(target)?;
+                stack.push(1);
+            }
+            Inst::TupleIndexSet { .. } | Inst::ObjectIndexSet { .. } => {
+                stack.pop(2)?;
+            }
+            Inst::TupleIndexGetAt { offset, .. }
+            | Inst::ObjectIndexGetAt { offset, .. }
+            | Inst::Copy { offset }
+            | Inst::Move { offset } => {
+                stack.offset(offset)?;
+                stack.push(1);
+            }
+            Inst::IndexSet => {
+                stack.pop(3)?;
+            }
+            Inst::Select { len } => {
+                stack.pop(len)?;
+                stack.push(2);
+            }
+            Inst::LoadFn { .. }
+            | Inst::LoadGlobal { .. }
+            | Inst::Push { .. }
+            | Inst::UnitStruct { .. }
+            | Inst::UnitVariant { .. }
+            | Inst::String { .. }
+            | Inst::Bytes { .. }
+            | Inst::StaticTuple { .. }
+            | Inst::YieldUnit
+            | Inst::Variant {
+                variant: InstVariant::None,
+            } => {
+                stack.push(1);
+            }
+            Inst::Variant { .. } => {
+                stack.pop(1)?;
+                stack.push(1);
+            }
+            Inst::Pop => {
+                stack.pop(1)?;
+            }
+            Inst::PopN { count } => {
+                stack.pop(count)?;
+            }
+            Inst::PopAndJumpIfNot { count, offset } => {
+                stack.pop(1)?;
+                let depth = stack.depth;
+                stack.pop(count)?;
+                queue.push((jump(ip, offset, &range)?, stack.depth));
+                stack.depth = depth;
+            }
+            Inst::Clean { count } => {
+                stack.pop(1)?;
+                stack.pop(count)?;
+                stack.push(1);
+            }
+            Inst::Drop { offset } => {
+                stack.offset(offset)?;
+            }
+            Inst::Dup => {
+                stack.pop(1)?;
+                stack.push(2);
+            }
+            Inst::Replace { offset } => {
+                stack.pop(1)?;
+                stack.offset(offset)?;
+            }
+            Inst::Return { address, clean } => {
+                stack.address(address)?;
+                stack.pop(clean)?;
+                continue;
+            }
+            Inst::ReturnUnit | Inst::Panic { .. } => {
+                continue;
+            }
+            Inst::Jump { offset } => {
+                queue.push((jump(ip, offset, &range)?, stack.depth));
+                continue;
+            }
+            Inst::JumpIf { offset } => {
+                stack.pop(1)?;
+                queue.push((jump(ip, offset, &range)?, stack.depth));
+            }
+            Inst::JumpIfOrPop { offset } | Inst::JumpIfNotOrPop { offset } => {
+                stack.pop(1)?;
+                queue.push((jump(ip, offset, &range)?, stack.depth + 1));
+            }
+            Inst::JumpIfBranch { offset, .. } => {
+                stack.pop(1)?;
+                queue.push((jump(ip, offset, &range)?, stack.depth));
+                stack.push(1);
+            }
+            Inst::Tuple1 { args } => stack.tuple(&args)?,
+            Inst::Tuple2 { args } => stack.tuple(&args)?,
+            Inst::Tuple3 { args } => stack.tuple(&args)?,
+            Inst::Tuple4 { args } => stack.tuple(&args)?,
+            Inst::PushTuple => match f.env {
+                Env::Some(count) => {
+                    stack.pop(1)?;
+                    stack.push(count);
+                }
+                _ => return Ok(()),
+            },
+            Inst::Object { slot }
+            | Inst::Struct { slot, .. }
+            | Inst::StructVariant { slot, .. } => {
+                // NB: slots have already been checked.
+                let keys = unit.lookup_object_keys(slot).map_or(0, |keys| keys.len());
+                stack.pop(keys)?;
+                stack.push(1);
+            }
+            Inst::Range { .. } => {
+                stack.pop(2)?;
+                stack.push(1);
+            }
+            Inst::Try {
+                address,
+                clean,
+                preserve,
+            } => {
+                stack.address(address)?;
+
+                // NB: the value is returned with the stack cleaned.
+                let depth = stack.depth;
+                stack.pop(clean)?;
+                stack.depth = depth;
+
+                if preserve {
+                    stack.push(1);
+                }
+            }
+            Inst::Op { a, b, .. } => {
+                stack.address(b)?;
+                stack.address(a)?;
+                stack.push(1);
+            }
+            Inst::Assign { target, .. } => {
+                stack.pop(1)?;
+
+                match target {
+                    InstTarget::Offset(offset) => stack.offset(offset)?,
+                    InstTarget::TupleField(..) | InstTarget::Field(..) => stack.pop(1)?,
+                }
+            }
+            Inst::IterNext { offset, jump: to } => {
+                stack.offset(offset)?;
+                queue.push((jump(ip, to, &range)?, stack.depth));
+            }
+        }
+
+        if ip + 1 >= range.end {
+            return Err(VerifyError::from(VerifyErrorKind::MissingReturn { ip }));
+        }
+
+        queue.push((ip + 1, stack.depth));
+    }
+
+    Ok(())
+}
+
+/// The call frame as seen by a single instruction.
+struct Frame {
+    ip: usize,
+    depth: usize,
+}
+
+impl Frame {
+    /// Pop `count` values.
+    fn pop(&mut self, count: usize) -> Result<(), VerifyError> {
+        self.depth = self
+            .depth
+            .checked_sub(count)
+            .ok_or(VerifyErrorKind::StackUnderflow { ip: self.ip })?;
+        Ok(())
+    }
+
+    /// Push `count` values.
+    fn push(&mut self, count: usize) {
+        self.depth += count;
+    }
+
+    /// Access the value at the given offset in the current call frame.
+    fn offset(&self, offset: usize) -> Result<(), VerifyError> {
+        if offset >= self.depth {
+            return Err(VerifyError::from(VerifyErrorKind::AddressOutOfBounds {
+                ip: self.ip,
+                offset,
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Access a value by address, which pops it if it's on top of the stack.
+    fn address(&mut self, address: InstAddress) -> Result<(), VerifyError> {
+        match address {
+            InstAddress::Top => self.pop(1),
+            InstAddress::Offset(offset) => self.offset(offset),
+        }
+    }
+
+    /// Construct a tuple out of the given addresses, which are accessed in
+    /// reverse.
+    fn tuple(&mut self, args: &[InstAddress]) -> Result<(), VerifyError> {
+        for address in args.iter().rev() {
+            self.address(*address)?;
+        }
+
+        self.push(1);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VerifyErrorKind;
+    use crate::collections::HashMap;
+    use crate::runtime::{Call, Inst, InstAddress, Unit, UnitFn};
+    use crate::Hash;
+
+    /// Construct and verify a unit with a single function taking `args`
+    /// arguments, made up of the given instructions.
+    fn verify(args: usize, instructions: Vec<Inst>) -> Result<(), VerifyErrorKind> {
+        let mut functions = HashMap::new();
+
+        functions.insert(
+            Hash::type_hash(["main"]),
+            UnitFn::Offset {
+                offset: 0,
+                call: Call::Immediate,
+                args,
+            },
+        );
+
+        let unit = Unit::new(
+            instructions,
+            functions,
+            Vec::new(),
+            Vec::new(),
+            Vec::new(),
+            Vec::new(),
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            HashMap::new(),
+        );
+
+        unit.verify().map_err(|error| error.kind)
+    }
+
+    #[test]
+    fn test_well_formed() {
+        let result = verify(
+            1,
+            vec![
+                Inst::Copy { offset: 0 },
+                Inst::JumpIf { offset: 1 },
+                Inst::Jump { offset: -3 },
+                Inst::Return {
+                    address: InstAddress::Offset(0),
+                    clean: 1,
+                },
+            ],
+        );
+
+        assert!(result.is_ok(), "{:?}", result);
+    }
+
+    #[test]
+    fn test_malformed() {
+        assert!(matches!(
+            verify(0, vec![Inst::Jump { offset: 1 }, Inst::ReturnUnit]),
+            Err(VerifyErrorKind::JumpOutOfBounds { ip: 0 })
+        ));
+
+        assert!(matches!(
+            verify(0, vec![Inst::Pop, Inst::ReturnUnit]),
+            Err(VerifyErrorKind::StackUnderflow { ip: 0 })
+        ));
+
+        assert!(matches!(
+            verify(1, vec![Inst::Copy { offset: 1 }, Inst::ReturnUnit]),
+            Err(VerifyErrorKind::AddressOutOfBounds { ip: 0, offset: 1 })
+        ));
+
+        assert!(matches!(
+            verify(0, vec![Inst::String { slot: 0 }, Inst::ReturnUnit]),
+            Err(VerifyErrorKind::MissingStaticString { ip: 0, slot: 0 })
+        ));
+
+        assert!(matches!(
+            verify(0, vec![Inst::unit()]),
+            Err(VerifyErrorKind::MissingReturn { ip: 0 })
+        ));
+
+        assert!(matches!(
+            verify(
+                0,
+                vec![
+                    Inst::CallOffset {
+                        offset: 1,
+                        call: Call::Immediate,
+                        args: 0,
+                    },
+                    Inst::ReturnUnit,
+                ],
+            ),
+            Err(VerifyErrorKind::MissingCallOffset { ip: 0, offset: 1 })
+        ));
+
+        assert!(matches!(
+            verify(
+                1,
+                vec![
+                    Inst::Copy { offset: 0 },
+                    Inst::JumpIf { offset: 1 },
+                    Inst::unit(),
+                    Inst::ReturnUnit,
+                ],
+            ),
+            Err(VerifyErrorKind::StackMismatch { ip: 3, .. })
+        ));
+    }
+}
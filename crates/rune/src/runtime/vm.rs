@@ -1,18 +1,33 @@
+use crate::ast::Span;
 use crate::runtime::budget;
+use crate::runtime::debugger::Debugger;
+use crate::runtime::finalize::DropErrors;
 use crate::runtime::future::SelectFuture;
+use crate::runtime::gc::Collector;
+use crate::runtime::inline_cache::{Cached, InlineCache};
 use crate::runtime::unit::UnitFn;
 use crate::runtime::{
-    Args, Awaited, BorrowMut, Bytes, Call, Format, FormatSpec, FromValue, Function, Future,
-    Generator, GuardedArgs, Inst, InstAddress, InstAssignOp, InstOp, InstRangeLimits, InstTarget,
-    InstValue, InstVariant, Object, Panic, Protocol, Range, RangeLimits, RuntimeContext, Select,
-    Shared, Stack, Stream, Struct, Tuple, TypeCheck, Unit, UnitStruct, Value, Variant, VariantData,
-    Vec, VmError, VmErrorKind, VmExecution, VmHalt, VmIntegerRepr, VmSendExecution,
+    catch_native, native_span, Args, Awaited, BorrowMut, Bytes, Call, CallHook, Capabilities,
+    ConstValue, Deadline, DebugListener, Determinism, Extensions, Format, FormatSpec, FromValue,
+    Fuel, Function, Future, Generator, GuardedArgs, Inst, InstAddress, InstAssignOp, InstOp,
+    InstRangeLimits, InstTarget, InstValue, InstVariant, LinkError, Memory, MetricsState,
+    NativeCall, Object, Panic, Preemption, Profiler, Protocol, Range, RangeLimits, RuntimeContext,
+    Select, SendValue, Shared, Stack, Stream, Struct, Suspend, ToValue, TraceSink, Tuple,
+    TypeCheck, Unit, UnitStruct, Value, Variant, VariantData, Vec, VmCall, VmError, VmErrorKind,
+    VmExecution, VmHalt, VmIntegerRepr, VmMetrics, VmSendExecution,
 };
-use crate::{Hash, IntoTypeHash};
+use crate::shared::AssertSend;
+use crate::{Hash, IntoTypeHash, SourceId};
 use std::fmt;
 use std::mem;
-use std::sync::Arc;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Instant;
 use std::vec;
+use tracing::Instrument as _;
+
+/// The number of instructions executed between checks of the deadline.
+const DEADLINE_INTERVAL: u32 = 256;
 
 enum TargetFallback<'a> {
     Value(Value, Value),
@@ -70,11 +85,45 @@ pub struct Vm {
     /// The current stack.
     stack: Stack,
     /// Frames relative to the stack.
-    call_frames: vec::Vec<CallFrame>,
+    pub(crate) call_frames: vec::Vec<CallFrame>,
+    /// The debugger attached to the virtual machine, if any.
+    pub(crate) debugger: Option<Arc<Mutex<Debugger>>>,
+    /// The metrics recorded by the virtual machine, if any.
+    pub(crate) metrics: Option<MetricsState>,
+    /// The fuel of the virtual machine, if it's limited.
+    pub(crate) fuel: Option<Arc<Fuel>>,
+    /// The memory allocated by the virtual machine, if it's limited.
+    pub(crate) memory: Option<Arc<Memory>>,
+    /// Limits on the call depth and the size of the stack.
+    pub(crate) limits: StackLimits,
+    /// The deadline of the virtual machine, if any.
+    pub(crate) deadline: Option<Arc<Deadline>>,
+    /// Cooperative preemption of the virtual machine, if enabled.
+    pub(crate) preemption: Option<Preemption>,
+    /// The deterministic environment of the virtual machine, if any.
+    pub(crate) determinism: Option<Determinism>,
+    /// The capabilities native functions called by the virtual machine are
+    /// allowed to use.
+    pub(crate) capabilities: Capabilities,
+    /// The hook consulted before calling native functions, if any.
+    pub(crate) call_hook: Option<Arc<dyn CallHook>>,
+    /// Ambient host data which native functions can access.
+    pub(crate) extensions: Extensions,
+    /// Global variables which scripts can read, if any are defined.
+    pub(crate) globals: Option<Shared<Object>>,
+    /// The cycle collector of the virtual machine, if enabled.
+    pub(crate) collector: Option<Rc<Collector>>,
+    /// Errors raised by drop functions while the virtual machine was running.
+    pub(crate) drop_errors: DropErrors,
+    /// Functions resolved by instance function calls and field accesses.
+    inline_cache: InlineCache,
 }
 
 impl Vm {
     /// Construct a new virtual machine.
+    ///
+    /// This is cheap, since the context and the unit are shared with other
+    /// virtual machines constructed from them.
     pub const fn new(context: Arc<RuntimeContext>, unit: Arc<Unit>) -> Self {
         Self::with_stack(context, unit, Stack::new())
     }
@@ -87,6 +136,21 @@ impl Vm {
             ip: 0,
             stack,
             call_frames: vec::Vec::new(),
+            debugger: None,
+            metrics: None,
+            fuel: None,
+            memory: None,
+            limits: StackLimits::new(),
+            deadline: None,
+            preemption: None,
+            determinism: None,
+            capabilities: Capabilities::all(),
+            call_hook: None,
+            extensions: Extensions::new(),
+            globals: None,
+            collector: None,
+            drop_errors: DropErrors::new(),
+            inline_cache: InlineCache::new(),
         }
     }
 
@@ -126,6 +190,588 @@ impl Vm {
         &mut self.stack
     }
 
+    /// Limit the number of instructions the virtual machine executes to the
+    /// given amount of fuel, or remove the limit with `None`.
+    ///
+    /// Each executed instruction consumes one unit of fuel. Once it runs out
+    /// execution stops with [VmErrorKind::OutOfFuel] before the next
+    /// instruction is executed. The execution can then be continued by adding
+    /// more fuel with [Vm::add_fuel] and resuming it.
+    ///
+    /// Fuel is shared with clones of the virtual machine and with the virtual
+    /// machines spawned to run generators, streams, async functions and calls
+    /// into other units, so that a script can't escape its limit.
+    ///
+    /// ```
+    /// use rune::{FromValue, Vm};
+    /// use rune::runtime::VmErrorKind;
+    /// use std::sync::Arc;
+    ///
+    /// # fn main() -> rune::Result<()> {
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub fn main() {
+    ///             let n = 0;
+    ///             while n < 100 { n += 1; }
+    ///             n
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).build()?;
+    /// let mut vm = Vm::without_runtime(Arc::new(unit));
+    /// vm.set_fuel(Some(100));
+    ///
+    /// let mut execution = vm.execute(&["main"], ())?;
+    /// let error = execution.complete().unwrap_err();
+    /// assert!(matches!(error.as_unwound().0, VmErrorKind::OutOfFuel));
+    ///
+    /// execution.vm_mut().add_fuel(10_000);
+    /// let value = execution.complete()?;
+    /// assert_eq!(i64::from_value(value)?, 100);
+    /// # Ok(()) }
+    /// ```
+    pub fn set_fuel(&mut self, fuel: Option<u64>) {
+        match (fuel, &self.fuel) {
+            (Some(fuel), Some(current)) => current.set(fuel),
+            (Some(fuel), None) => self.fuel = Some(Arc::new(Fuel::new(fuel))),
+            (None, _) => self.fuel = None,
+        }
+    }
+
+    /// Add fuel to the virtual machine.
+    ///
+    /// Does nothing unless fuel has been limited with [Vm::set_fuel].
+    pub fn add_fuel(&mut self, fuel: u64) {
+        if let Some(current) = &self.fuel {
+            current.add(fuel);
+        }
+    }
+
+    /// The amount of fuel remaining, or `None` if it's unlimited.
+    pub fn fuel(&self) -> Option<u64> {
+        Some(self.fuel.as_ref()?.get())
+    }
+
+    /// Limit the number of bytes the virtual machine is allowed to allocate,
+    /// or remove the limit with `None`.
+    ///
+    /// Allocations of strings, byte arrays, vectors, tuples, objects and
+    /// other shared values are counted while the virtual machine is running,
+    /// together with the storage they grow. Once the limit is exceeded the
+    /// next instruction fails with [VmErrorKind::MemoryLimitExceeded], which
+    /// can be caught as any other error. Memory is released as values are
+    /// dropped, so lowering the limit or freeing values allows execution to
+    /// be resumed.
+    ///
+    /// Virtual machines spawned to run generators, streams and async
+    /// functions share the limit with the virtual machine they are spawned
+    /// from.
+    ///
+    /// Accounting is approximate, it's intended to protect the host against
+    /// runaway scripts, not to precisely measure how much memory they use.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::{Context, Vm};
+    /// use rune::runtime::VmErrorKind;
+    /// use std::sync::Arc;
+    ///
+    /// # fn main() -> rune::Result<()> {
+    /// let context = Context::with_default_modules()?;
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub fn main() {
+    ///             let values = [];
+    ///             loop { values.push([1, 2, 3]); }
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    /// let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+    /// vm.set_memory_limit(Some(1 << 20));
+    ///
+    /// let error = vm.call(&["main"], ()).unwrap_err();
+    ///
+    /// assert!(matches!(
+    ///     error.as_unwound().0,
+    ///     VmErrorKind::MemoryLimitExceeded { .. }
+    /// ));
+    /// # Ok(()) }
+    /// ```
+    pub fn set_memory_limit(&mut self, limit: Option<usize>) {
+        match (limit, &self.memory) {
+            (Some(limit), Some(current)) => current.set_limit(limit),
+            (Some(limit), None) => self.memory = Some(Arc::new(Memory::new(limit))),
+            (None, _) => self.memory = None,
+        }
+    }
+
+    /// The memory limit of the virtual machine, or `None` if it's unlimited.
+    pub fn memory_limit(&self) -> Option<usize> {
+        Some(self.memory.as_ref()?.limit())
+    }
+
+    /// The number of bytes currently allocated by the virtual machine, or
+    /// `None` unless memory has been limited with [Vm::set_memory_limit].
+    pub fn memory_used(&self) -> Option<usize> {
+        Some(self.memory.as_ref()?.used())
+    }
+
+    /// Enable or disable collection of reference cycles.
+    ///
+    /// Values are reference counted, so values which refer to each other,
+    /// like an object which holds onto a closure that captures the object,
+    /// are never freed. With cycle collection enabled, vectors, tuples,
+    /// objects, structs, variants and closures allocated while the virtual
+    /// machine is running are tracked, so that cycles among them can be freed
+    /// with [Vm::collect_cycles].
+    ///
+    /// Virtual machines spawned to run generators, streams and async
+    /// functions share the collector with the virtual machine they are
+    /// spawned from.
+    ///
+    /// Values referenced by types which can't be traced, like external types
+    /// and generators, are conservatively assumed to be live.
+    pub fn set_cycle_collection(&mut self, enabled: bool) {
+        match (enabled, &self.collector) {
+            (true, None) => self.collector = Some(Rc::new(Collector::default())),
+            (true, Some(..)) => (),
+            (false, _) => self.collector = None,
+        }
+    }
+
+    /// Free reference cycles which can no longer be reached, returning the
+    /// number of values freed.
+    ///
+    /// This does nothing unless cycle collection has been enabled with
+    /// [Vm::set_cycle_collection]. It's cheapest to call between calls into
+    /// the virtual machine, like once every few calls in a long-running
+    /// embedding.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::Vm;
+    /// use std::sync::Arc;
+    ///
+    /// # fn main() -> rune::Result<()> {
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub fn main() {
+    ///             let a = #{};
+    ///             let b = #{ a };
+    ///             a.b = b;
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).build()?;
+    /// let mut vm = Vm::without_runtime(Arc::new(unit));
+    /// vm.set_cycle_collection(true);
+    ///
+    /// vm.call(&["main"], ())?;
+    /// assert_eq!(vm.collect_cycles(), 2);
+    /// assert_eq!(vm.collect_cycles(), 0);
+    /// # Ok(()) }
+    /// ```
+    pub fn collect_cycles(&self) -> usize {
+        match &self.collector {
+            Some(collector) => collector.collect(),
+            None => 0,
+        }
+    }
+
+    /// The number of values tracked for cycle collection, or `None` unless it
+    /// has been enabled with [Vm::set_cycle_collection].
+    pub fn tracked_values(&self) -> Option<usize> {
+        Some(self.collector.as_ref()?.len())
+    }
+
+    /// Take the errors raised by [Protocol::DROP] functions called while the
+    /// virtual machine was running.
+    ///
+    /// Drop functions are called when the last reference to a value goes
+    /// away, so there's no caller to return their errors to. Errors raised
+    /// while a virtual machine spawned by this one was running, like one
+    /// calling a closure from a native function, are recorded here as well.
+    pub fn take_drop_errors(&mut self) -> vec::Vec<VmError> {
+        self.drop_errors.take()
+    }
+
+    /// Limit the number of nested function calls, or remove the limit with
+    /// `None`.
+    ///
+    /// Calling a function once the limit has been reached fails with
+    /// [VmErrorKind::StackOverflow], which carries the stack trace of the
+    /// script through [VmError::stack_trace]. Native functions which call
+    /// back into the script count towards the limit as one additional call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::Vm;
+    /// use rune::runtime::VmErrorKind;
+    /// use std::sync::Arc;
+    ///
+    /// # fn main() -> rune::Result<()> {
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         fn recurse(n) {
+    ///             recurse(n + 1) + 1
+    ///         }
+    ///
+    ///         pub fn main() {
+    ///             recurse(0)
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).build()?;
+    /// let mut vm = Vm::without_runtime(Arc::new(unit));
+    /// vm.set_call_depth_limit(Some(100));
+    ///
+    /// let error = vm.call(&["main"], ()).unwrap_err();
+    /// assert!(matches!(error.as_unwound().0, VmErrorKind::StackOverflow { depth: 100, .. }));
+    /// assert_eq!(error.stack_trace().unwrap().frames().len(), 101);
+    /// # Ok(()) }
+    /// ```
+    pub fn set_call_depth_limit(&mut self, limit: Option<usize>) {
+        self.limits.call_depth = limit;
+    }
+
+    /// The call depth limit of the virtual machine, or `None` if it's
+    /// unlimited.
+    pub fn call_depth_limit(&self) -> Option<usize> {
+        self.limits.call_depth
+    }
+
+    /// Limit the number of values on the stack of the virtual machine, or
+    /// remove the limit with `None`.
+    ///
+    /// The limit is checked before each instruction is executed, so an
+    /// instruction which pushes several values might exceed it by a few. Once
+    /// exceeded, execution fails with [VmErrorKind::StackOverflow].
+    pub fn set_stack_size_limit(&mut self, limit: Option<usize>) {
+        self.limits.stack_size = limit;
+    }
+
+    /// The stack size limit of the virtual machine, or `None` if it's
+    /// unlimited.
+    pub fn stack_size_limit(&self) -> Option<usize> {
+        self.limits.stack_size
+    }
+
+    /// Set the wall-clock deadline of the virtual machine, or remove it with
+    /// `None`.
+    ///
+    /// Once the deadline has passed, execution fails with
+    /// [VmErrorKind::Timeout]. This is detected both while instructions are
+    /// being executed and while an asynchronous execution is waiting on a
+    /// future, in which case the future is dropped. Virtual machines spawned
+    /// to run generators, streams and async functions share the deadline.
+    ///
+    /// See [VmExecution::set_timeout] for setting a timeout relative to when
+    /// an execution is started.
+    pub fn set_deadline(&mut self, deadline: Option<Instant>) {
+        self.deadline = deadline.map(|at| Arc::new(Deadline::new(at)));
+    }
+
+    /// The deadline of the virtual machine, if any.
+    pub fn deadline(&self) -> Option<Instant> {
+        Some(self.deadline.as_ref()?.at())
+    }
+
+    /// Make asynchronous executions return control to the executor after the
+    /// given number of yield points, or disable preemption with `None`.
+    ///
+    /// Yield points are backward jumps, which are taken by every iteration
+    /// of a loop, and function calls. So a script which never awaits can't
+    /// starve other tasks running on the same thread. Synchronous executions
+    /// are never preempted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::{Context, FromValue, Vm};
+    /// use std::sync::Arc;
+    ///
+    /// # #[tokio::main] async fn main() -> rune::Result<()> {
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub async fn main() {
+    ///             let n = 0;
+    ///             while n < 10000 { n += 1; }
+    ///             n
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).build()?;
+    /// let mut vm = Vm::without_runtime(Arc::new(unit));
+    /// vm.set_preemption_interval(Some(100));
+    ///
+    /// let value = vm.async_call(&["main"], ()).await?;
+    /// assert_eq!(i64::from_value(value)?, 10000);
+    /// # Ok(()) }
+    /// ```
+    pub fn set_preemption_interval(&mut self, interval: Option<u32>) {
+        self.preemption = interval.map(|interval| Preemption::new(interval.max(1)));
+    }
+
+    /// The number of yield points between each preemption, or `None` if
+    /// preemption is disabled.
+    pub fn preemption_interval(&self) -> Option<u32> {
+        Some(self.preemption.as_ref()?.interval())
+    }
+
+    /// Make the virtual machine deterministic, or remove determinism with
+    /// `None`.
+    ///
+    /// See [Determinism] for what this entails.
+    pub fn set_determinism(&mut self, determinism: Option<Determinism>) {
+        self.determinism = determinism;
+    }
+
+    /// The deterministic environment of the virtual machine, if any.
+    pub fn determinism(&self) -> Option<&Determinism> {
+        self.determinism.as_ref()
+    }
+
+    /// Set the capabilities which native functions called by the virtual
+    /// machine are allowed to use.
+    ///
+    /// See [Capabilities] for what this entails.
+    pub fn set_capabilities(&mut self, capabilities: Capabilities) {
+        self.capabilities = capabilities;
+    }
+
+    /// The capabilities which native functions called by the virtual machine
+    /// are allowed to use.
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    /// Set the hook which is consulted before every call into a native
+    /// function, and which can veto it.
+    ///
+    /// The hook is shared with the virtual machines spawned to run generators,
+    /// streams and async functions. See [CallHook] for how to use.
+    pub fn set_call_hook<H>(&mut self, hook: H)
+    where
+        H: 'static + CallHook,
+    {
+        self.call_hook = Some(Arc::new(hook));
+    }
+
+    /// Clear the call hook of the virtual machine.
+    pub fn clear_call_hook(&mut self) {
+        self.call_hook = None;
+    }
+
+    /// The extensions of the virtual machine, which native functions called
+    /// by it can access through [Extensions::current].
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+
+    /// Mutable access to the extensions of the virtual machine.
+    ///
+    /// The extensions are shared with the virtual machines spawned to run
+    /// generators, streams and async functions. See [Extensions] for how to
+    /// use.
+    pub fn extensions_mut(&mut self) -> &mut Extensions {
+        &mut self.extensions
+    }
+
+    /// Define a global variable, which scripts read as `global::<name>` or
+    /// through the object returned by `globals()`.
+    ///
+    /// Globals are shared with the virtual machines spawned to run closures,
+    /// generators, streams and async functions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::{Context, FromValue, Vm};
+    /// use std::sync::Arc;
+    ///
+    /// # fn main() -> rune::Result<()> {
+    /// let context = Context::with_default_modules()?;
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub fn main() {
+    ///             (global::name, globals().age)
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    /// let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+    /// vm.set_global("name", "john")?;
+    /// vm.set_global("age", 42i64)?;
+    ///
+    /// let output = <(String, i64)>::from_value(vm.call(&["main"], ())?)?;
+    /// assert_eq!(output, (String::from("john"), 42));
+    /// # Ok(()) }
+    /// ```
+    pub fn set_global<V>(&mut self, name: &str, value: V) -> Result<(), VmError>
+    where
+        V: ToValue,
+    {
+        let value = value.to_value()?;
+        let globals = self
+            .globals
+            .get_or_insert_with(|| Shared::tracked(Object::new()));
+        globals.borrow_mut()?.insert(name.to_owned(), value);
+        Ok(())
+    }
+
+    /// Get the global variable with the given name, if it's defined.
+    pub fn global(&self, name: &str) -> Result<Option<Value>, VmError> {
+        match &self.globals {
+            Some(globals) => Ok(globals.borrow_ref()?.get(name).cloned()),
+            None => Ok(None),
+        }
+    }
+
+    /// Remove the global variable with the given name, returning its value
+    /// if it was defined.
+    pub fn remove_global(&mut self, name: &str) -> Result<Option<Value>, VmError> {
+        match &self.globals {
+            Some(globals) => Ok(globals.borrow_mut()?.remove(name)),
+            None => Ok(None),
+        }
+    }
+
+    /// Prepare to call the native function with the given hash, whose `args`
+    /// arguments are on the top of the stack.
+    ///
+    /// This records the position of the virtual machine in the environment
+    /// and consults the call hook, if any.
+    #[inline]
+    pub(crate) fn enter_native(&self, hash: Hash, args: usize) -> Result<(), VmError> {
+        crate::runtime::env::set_position(self.call_frames.len(), self.stack.len());
+
+        if let Some(hook) = &self.call_hook {
+            let args = self.stack.peek_n(args)?;
+            hook.call(&NativeCall::new(&self.context, hash, args))?;
+        }
+
+        Ok(())
+    }
+
+    /// Set the listener which is notified before each instruction is executed
+    /// and when a breakpoint is reached.
+    ///
+    /// The listener is shared with clones of the virtual machine and with the
+    /// virtual machines spawned to run generators, streams and async functions
+    /// from the same unit.
+    pub fn set_debug_listener<L>(&mut self, listener: L)
+    where
+        L: 'static + DebugListener,
+    {
+        self.debugger().set_listener(Some(Box::new(listener)));
+    }
+
+    /// Clear the debug listener of the virtual machine.
+    pub fn clear_debug_listener(&mut self) {
+        self.debugger().set_listener(None);
+    }
+
+    /// Add a breakpoint on the given span of a source.
+    ///
+    /// Execution is paused when it reaches the first instruction compiled from
+    /// inside of the span. Requires the unit to have debug information.
+    pub fn add_breakpoint(&mut self, source_id: SourceId, span: Span) {
+        self.debugger().add_breakpoint(source_id, span);
+    }
+
+    /// Remove the breakpoint on the given span of a source, returning `true`
+    /// if it existed.
+    pub fn remove_breakpoint(&mut self, source_id: SourceId, span: Span) -> bool {
+        self.debugger().remove_breakpoint(source_id, span)
+    }
+
+    /// Remove all breakpoints.
+    pub fn clear_breakpoints(&mut self) {
+        self.debugger().clear_breakpoints();
+    }
+
+    /// Enable or disable single-stepping, which pauses execution before every
+    /// instruction.
+    pub fn set_single_step(&mut self, single_step: bool) {
+        self.debugger().set_stepping(single_step);
+    }
+
+    /// Set the sink which is passed every executed instruction, and enable
+    /// tracing.
+    ///
+    /// Like the debug listener, the sink is shared with clones of the virtual
+    /// machine. See [TraceWriter][crate::runtime::TraceWriter] for a sink which
+    /// writes the trace to an [std::io::Write].
+    pub fn set_trace_sink<S>(&mut self, sink: S)
+    where
+        S: 'static + TraceSink,
+    {
+        self.debugger().set_trace_sink(Some(Box::new(sink)));
+    }
+
+    /// Clear the trace sink of the virtual machine.
+    pub fn clear_trace_sink(&mut self) {
+        self.debugger().set_trace_sink(None);
+    }
+
+    /// Enable or disable tracing to the configured trace sink.
+    ///
+    /// Tracing is enabled by default once a sink is set.
+    pub fn set_tracing(&mut self, tracing: bool) {
+        self.debugger().set_tracing(tracing);
+    }
+
+    /// Attach a profiler which records the time spent in each script function
+    /// executed by the virtual machine.
+    ///
+    /// See [Profiler] for how to use.
+    pub fn set_profiler(&mut self, profiler: Profiler) {
+        self.debugger().set_profiler(Some(profiler));
+    }
+
+    /// Detach the profiler of the virtual machine.
+    pub fn clear_profiler(&mut self) {
+        self.debugger().set_profiler(None);
+    }
+
+    /// Record call counts and durations of the functions executed by the
+    /// virtual machine into the given metrics.
+    ///
+    /// See [VmMetrics] for how to use.
+    pub fn set_metrics(&mut self, metrics: VmMetrics) {
+        self.metrics = Some(MetricsState::new(metrics, &self.unit));
+    }
+
+    /// Stop recording metrics.
+    pub fn clear_metrics(&mut self) {
+        self.metrics = None;
+    }
+
+    /// Get the metrics recorded by the virtual machine, if any.
+    pub fn metrics(&self) -> Option<&VmMetrics> {
+        Some(self.metrics.as_ref()?.metrics())
+    }
+
+    /// Access the debugger of the virtual machine, attaching one if needed.
+    fn debugger(&mut self) -> MutexGuard<'_, Debugger> {
+        self.debugger
+            .get_or_insert_with(Default::default)
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
     /// Access the context related to the virtual machine.
     #[inline]
     pub fn context(&self) -> &Arc<RuntimeContext> {
@@ -138,6 +784,60 @@ impl Vm {
         &self.unit
     }
 
+    /// Load an additional unit into the virtual machine by linking it with the
+    /// unit it's currently running, so that its functions can be called
+    /// alongside the functions which are already loaded.
+    ///
+    /// Functions which are already executing are unaffected, since loading a
+    /// unit leaves the instructions of the existing unit where they are.
+    ///
+    /// See [Unit::link] for the details of how units are linked. Units from
+    /// untrusted sources should be checked with [Unit::verify] first.
+    ///
+    /// ```
+    /// use rune::{Context, FromValue, Source, Sources, Vm};
+    /// use std::sync::Arc;
+    ///
+    /// # fn main() -> rune::Result<()> {
+    /// let context = Context::with_default_modules()?;
+    ///
+    /// let mut sources = Sources::new();
+    /// sources.insert(Source::new("host", "pub fn main() { 42 }"));
+    /// let host = rune::prepare(&mut sources).with_context(&context).build()?;
+    ///
+    /// let mut sources = Sources::new();
+    /// sources.insert(Source::new("plugin", "pub fn plugin() { \"Hello\" }"));
+    /// let plugin = rune::prepare(&mut sources).with_context(&context).build()?;
+    ///
+    /// let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(host));
+    /// vm.load_unit(&plugin)?;
+    ///
+    /// assert_eq!(i64::from_value(vm.call(["main"], ())?)?, 42);
+    /// assert_eq!(String::from_value(vm.call(["plugin"], ())?)?, "Hello");
+    /// # Ok(()) }
+    /// ```
+    pub fn load_unit(&mut self, unit: &Unit) -> Result<(), LinkError> {
+        self.unit = Arc::new(self.unit.link(unit)?);
+        self.inline_cache = InlineCache::new();
+        Ok(())
+    }
+
+    /// Reload the unit of the virtual machine with a new version of it, like
+    /// one which was built after its sources were changed.
+    ///
+    /// The state of the virtual machine is preserved, and execution which is
+    /// in progress, like a suspended generator, continues to run the code it
+    /// was started with. Calls it makes to functions which still have the
+    /// same signature go to their new version. See [Unit::reload] for details.
+    ///
+    /// The code of the previous unit is kept around for as long as the
+    /// virtual machine lives. See [Reloader][crate::Reloader] for keeping
+    /// track of sources which have changed.
+    pub fn reload(&mut self, unit: &Unit) {
+        self.unit = Arc::new(self.unit.reload(unit));
+        self.inline_cache = InlineCache::new();
+    }
+
     /// Access the current instruction pointer.
     #[inline]
     pub fn ip(&self) -> usize {
@@ -155,6 +855,10 @@ impl Vm {
         self.ip = 0;
         self.stack.clear();
         self.call_frames.clear();
+
+        if let Some(metrics) = &mut self.metrics {
+            metrics.clear();
+        }
     }
 
     /// Modify the current instruction pointer.
@@ -259,6 +963,12 @@ impl Vm {
     /// This is accomplished by preventing values escaping from being
     /// non-exclusively sent with the execution or escaping the execution. We
     /// only support encoding arguments which themselves are `Send`.
+    ///
+    /// Global variables are deep copied into the execution, so they have to
+    /// be values which can be converted into a [SendValue], and changes made
+    /// to them aren't visible outside of it.
+    ///
+    /// [SendValue]: crate::runtime::SendValue
     pub fn send_execute<A, N>(mut self, name: N, args: A) -> Result<VmSendExecution, VmError>
     where
         N: IntoTypeHash,
@@ -268,16 +978,137 @@ impl Vm {
         // being sent along with the virtual machine.
         self.stack.clear();
 
+        // Safety: the cycle collector is shared with the virtual machines this
+        // one was spawned from or has spawned, so the sent virtual machine
+        // gets a collector of its own.
+        if self.collector.is_some() {
+            self.collector = Some(Rc::new(Collector::default()));
+        }
+
         self.set_entrypoint(name, args.count())?;
-        args.into_stack(&mut self.stack)?;
+
+        // Safety: values allocated while a virtual machine is executing are
+        // tracked by its collector, which can't be sent. So values sent along
+        // are allocated in the environment of the sent virtual machine.
+        let guard = crate::runtime::env::Guard::new(&self);
+        let result = self.send_values(args);
+        guard.exit(&self.drop_errors);
+        result?;
+
         Ok(VmSendExecution(VmExecution::new(self)))
     }
 
+    /// Copy the global variables and push the arguments of a virtual machine
+    /// which is about to be sent.
+    fn send_values<A>(&mut self, args: A) -> Result<(), VmError>
+    where
+        A: Send + Args,
+    {
+        // Safety: the globals are shared with the virtual machines this one
+        // was spawned from or has spawned, so they're copied to make sure
+        // none of them are sent along with the virtual machine.
+        if let Some(globals) = self.globals.take() {
+            let mut copy = Object::new();
+
+            for (name, value) in globals.borrow_ref()?.iter() {
+                copy.insert(name.clone(), SendValue::from_value(value)?.into_value());
+            }
+
+            self.globals = Some(Shared::tracked(copy));
+        }
+
+        args.into_stack(&mut self.stack)
+    }
+
+    /// Package a call to the given function into a future which implements
+    /// [`Send`], allowing it to be spawned onto a thread pool like Tokio's
+    /// through [tokio::spawn].
+    ///
+    /// The call runs in a new virtual machine which shares the context, the
+    /// unit and the settings of this one, so many calls can run concurrently.
+    /// Like with [Vm::send_execute], arguments must be [`Send`], and the
+    /// produced value must be converted into a type which is [`Send`] before
+    /// it can leave the future.
+    ///
+    /// [tokio::spawn]: https://docs.rs/tokio/0/tokio/runtime/struct.Runtime.html#method.spawn
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::Vm;
+    /// use std::sync::Arc;
+    ///
+    /// # #[tokio::main] async fn main() -> rune::Result<()> {
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub async fn main(n) {
+    ///             n * 2
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).build()?;
+    /// let vm = Vm::without_runtime(Arc::new(unit));
+    ///
+    /// let tasks = (0..4u32)
+    ///     .map(|n| Ok(tokio::spawn(vm.send_call::<_, _, u32>(&["main"], (n,))?)))
+    ///     .collect::<rune::Result<Vec<_>>>()?;
+    ///
+    /// let mut output = Vec::new();
+    ///
+    /// for task in tasks {
+    ///     output.push(task.await??);
+    /// }
+    ///
+    /// assert_eq!(output, vec![0, 2, 4, 6]);
+    /// # Ok(()) }
+    /// ```
+    pub fn send_call<A, N, T>(
+        &self,
+        name: N,
+        args: A,
+    ) -> Result<impl std::future::Future<Output = Result<T, VmError>> + Send + 'static, VmError>
+    where
+        N: IntoTypeHash,
+        A: Send + Args,
+        T: Send + FromValue,
+    {
+        let mut vm = Vm::new(self.context.clone(), self.unit.clone());
+        vm.debugger = self.debugger.clone();
+        vm.metrics = self.metrics.as_ref().map(|metrics| metrics.spawn(None));
+        vm.fuel = self.fuel.clone();
+        vm.memory = self.memory.clone();
+        vm.limits = self.limits;
+        vm.deadline = self.deadline.clone();
+        vm.preemption = self.preemption.as_ref().map(Preemption::spawn);
+        vm.determinism = self.determinism.clone();
+        vm.capabilities = self.capabilities;
+        vm.call_hook = self.call_hook.clone();
+        vm.extensions = self.extensions.clone();
+        vm.globals = self.globals.clone();
+
+        let VmSendExecution(mut execution) = vm.send_execute(name, args)?;
+
+        let future = async move {
+            let value = execution.async_complete().await?;
+            T::from_value(value)
+        };
+
+        // Safety: the produced value is converted into a value which is
+        // `Send` before it leaves the future, so no values can escape from
+        // the virtual machine.
+        Ok(unsafe { AssertSend::new(future) })
+    }
+
     /// Call the given function immediately, returning the produced value.
     ///
     /// This function permits for using references since it doesn't defer its
     /// execution.
     ///
+    /// The call is wrapped in a `vm_call` [tracing] span at the debug level,
+    /// carrying the item of the called function. Every call into a native
+    /// function is wrapped in a `native_call` span at the trace level.
+    ///
     /// # Panics
     ///
     /// If any of the arguments passed in are references, and that references is
@@ -292,6 +1123,7 @@ impl Vm {
         N: IntoTypeHash,
         A: GuardedArgs,
     {
+        let _span = self.entry_span(name).entered();
         self.set_entrypoint(name, args.count())?;
 
         // Safety: We hold onto the guard until the vm has completed and
@@ -313,6 +1145,60 @@ impl Vm {
         Ok(value)
     }
 
+    /// Call the given function immediately like [Vm::call], but recover from
+    /// any panic raised during the call.
+    ///
+    /// Panics raised by native functions are always converted into errors.
+    /// This additionally covers panics raised by the host while passing
+    /// arguments, like when a reference passed in is still captured once the
+    /// call completes. If the call fails, the virtual machine is cleared so
+    /// that it can be used for further calls.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::{Context, FromValue, Module, Vm};
+    /// use rune::runtime::VmErrorKind;
+    /// use std::sync::Arc;
+    ///
+    /// # fn main() -> rune::Result<()> {
+    /// let mut module = Module::new();
+    /// module.function(&["boom"], || -> i64 { panic!("boom") })?;
+    ///
+    /// let mut context = Context::new();
+    /// context.install(&module)?;
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub fn explode() { boom() }
+    ///         pub fn main() { 42 }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    /// let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+    ///
+    /// let error = vm.call_with_recovery(&["explode"], ()).unwrap_err();
+    /// assert!(matches!(error.as_unwound().0, VmErrorKind::NativePanic { .. }));
+    ///
+    /// let value = vm.call_with_recovery(&["main"], ())?;
+    /// assert_eq!(i64::from_value(value)?, 42);
+    /// # Ok(()) }
+    /// ```
+    pub fn call_with_recovery<A, N>(&mut self, name: N, args: A) -> Result<Value, VmError>
+    where
+        N: IntoTypeHash,
+        A: GuardedArgs,
+    {
+        let result = catch_native(|| self.call(name, args));
+
+        if result.is_err() {
+            self.clear();
+        }
+
+        result
+    }
+
     /// Call the given function immediately asynchronously, returning the
     /// produced value.
     ///
@@ -333,7 +1219,8 @@ impl Vm {
         N: IntoTypeHash,
         A: GuardedArgs,
     {
-        self.set_entrypoint(name, args.count())?;
+        let span = self.entry_span(name);
+        span.in_scope(|| self.set_entrypoint(name, args.count()))?;
 
         // Safety: We hold onto the guard until the vm has completed and
         // `VmExecution` will clear the stack before this function returns.
@@ -344,7 +1231,10 @@ impl Vm {
             // Clearing the stack here on panics has safety implications - see
             // above.
             let vm = ClearStack(self);
-            VmExecution::new(&mut *vm.0).async_complete().await?
+            VmExecution::new(&mut *vm.0)
+                .async_complete()
+                .instrument(span)
+                .await?
         };
 
         // Note: this might panic if something in the vm is holding on to a
@@ -354,6 +1244,30 @@ impl Vm {
         Ok(value)
     }
 
+    /// Construct the span of a call into the unit through [Vm::call] or
+    /// [Vm::async_call], carrying the item of the called function if it's
+    /// known.
+    fn entry_span<N>(&self, name: N) -> tracing::Span
+    where
+        N: IntoTypeHash,
+    {
+        let hash = name.into_type_hash();
+        let span = tracing::debug_span!("vm_call", item = tracing::field::Empty, %hash);
+
+        if !span.is_disabled() {
+            let item = name.into_item().or_else(|| {
+                let debug = self.unit.debug_info()?;
+                Some(debug.functions.get(&hash)?.path.clone())
+            });
+
+            if let Some(item) = item {
+                span.record("item", tracing::field::display(item));
+            }
+        }
+
+        span
+    }
+
     /// Update the instruction pointer to match the function matching the given
     /// name and check that the number of argument matches.
     fn set_entrypoint<N>(&mut self, name: N, count: usize) -> Result<(), VmError>
@@ -389,6 +1303,12 @@ impl Vm {
         self.ip = offset;
         self.stack.clear();
         self.call_frames.clear();
+
+        if let Some(metrics) = &mut self.metrics {
+            metrics.clear();
+            metrics.enter(offset, 0);
+        }
+
         Ok(())
     }
 
@@ -425,7 +1345,15 @@ impl Vm {
         }
 
         if let Some(handler) = self.context.function(hash) {
-            handler(&mut self.stack, count)?;
+            self.enter_native(hash, count)?;
+            let _span = native_span(&self.context, hash).entered();
+            let stack = &mut self.stack;
+
+            match &self.metrics {
+                Some(metrics) => metrics.time(hash, || catch_native(|| handler(stack, count)))?,
+                None => catch_native(|| handler(stack, count))?,
+            }
+
             return Ok(true);
         }
 
@@ -434,35 +1362,90 @@ impl Vm {
         Ok(false)
     }
 
-    /// Helper to call a field function.
-    #[inline(always)]
-    fn call_field_fn<H, A>(
+    /// Helper to call a field function.
+    #[inline(always)]
+    fn call_field_fn<H, A>(
+        &mut self,
+        protocol: Protocol,
+        target: &Value,
+        hash: H,
+        args: A,
+    ) -> Result<bool, VmError>
+    where
+        H: IntoTypeHash,
+        A: Args,
+    {
+        let count = args.count() + 1;
+        self.stack.push(target.clone());
+        args.into_stack(&mut self.stack)?;
+
+        let hash = Hash::field_fn(protocol, target.type_hash()?, hash.into_type_hash());
+
+        let handler = match self.context.function(hash) {
+            Some(handler) => handler,
+            None => {
+                // NB: restore the stack
+                self.stack.popn(count)?;
+                return Ok(false);
+            }
+        };
+
+        self.enter_native(hash, count)?;
+
+        let _span = native_span(&self.context, hash).entered();
+        let stack = &mut self.stack;
+
+        match &self.metrics {
+            Some(metrics) => metrics.time(hash, || catch_native(|| handler(stack, count)))?,
+            None => catch_native(|| handler(stack, count))?,
+        }
+
+        Ok(true)
+    }
+
+    /// Helper to call a field function, caching the function which the
+    /// current instruction resolves to.
+    fn call_cached_field_fn<A>(
         &mut self,
         protocol: Protocol,
         target: &Value,
-        hash: H,
+        hash: Hash,
         args: A,
     ) -> Result<bool, VmError>
     where
-        H: IntoTypeHash,
         A: Args,
     {
+        let type_hash = target.type_hash()?;
+        let hash = Hash::field_fn(protocol, type_hash, hash);
+
+        let handler = match self.inline_cache.get(self.ip, type_hash) {
+            Some(Cached::Handler(handler)) => handler.clone(),
+            _ => {
+                let handler = match self.context.function(hash) {
+                    Some(handler) => handler.clone(),
+                    None => return Ok(false),
+                };
+
+                self.inline_cache
+                    .insert(self.ip, type_hash, Cached::Handler(handler.clone()));
+                handler
+            }
+        };
+
         let count = args.count() + 1;
         self.stack.push(target.clone());
         args.into_stack(&mut self.stack)?;
 
-        let hash = Hash::field_fn(protocol, target.type_hash()?, hash.into_type_hash());
+        self.enter_native(hash, count)?;
 
-        let handler = match self.context.function(hash) {
-            Some(handler) => handler,
-            None => {
-                // NB: restore the stack
-                self.stack.popn(count)?;
-                return Ok(false);
-            }
-        };
+        let _span = native_span(&self.context, hash).entered();
+        let stack = &mut self.stack;
+
+        match &self.metrics {
+            Some(metrics) => metrics.time(hash, || catch_native(|| handler(stack, count)))?,
+            None => catch_native(|| handler(stack, count))?,
+        }
 
-        handler(&mut self.stack, count)?;
         Ok(true)
     }
 
@@ -498,6 +1481,10 @@ impl Vm {
     /// This will cause the `args` number of elements on the stack to be
     /// associated and accessible to the new call frame.
     pub(crate) fn push_call_frame(&mut self, ip: usize, args: usize) -> Result<(), VmError> {
+        if matches!(self.limits.call_depth, Some(limit) if self.call_frames.len() >= limit) {
+            return Err(self.stack_overflow());
+        }
+
         let stack_top = self.stack.swap_stack_bottom(args)?;
 
         self.call_frames.push(CallFrame {
@@ -505,12 +1492,29 @@ impl Vm {
             stack_bottom: stack_top,
         });
 
+        if let Some(metrics) = &mut self.metrics {
+            metrics.enter(ip, self.call_frames.len());
+        }
+
         self.ip = ip.wrapping_sub(1);
         Ok(())
     }
 
+    /// Construct the error raised when the limits of the stack are exceeded.
+    #[cold]
+    fn stack_overflow(&self) -> VmError {
+        VmError::from(VmErrorKind::StackOverflow {
+            depth: self.call_frames.len(),
+            size: self.stack.len(),
+        })
+    }
+
     /// Pop a call frame and return it.
     fn pop_call_frame(&mut self) -> Result<bool, VmError> {
+        if let Some(metrics) = &mut self.metrics {
+            metrics.exit(self.call_frames.len());
+        }
+
         let frame = match self.call_frames.pop() {
             Some(frame) => frame,
             None => {
@@ -847,7 +1851,7 @@ impl Vm {
             target => {
                 let hash = index.hash();
 
-                if self.call_field_fn(Protocol::GET, target, hash, ())? {
+                if self.call_cached_field_fn(Protocol::GET, target, hash, ())? {
                     Some(self.stack.pop()?)
                 } else {
                     None
@@ -901,7 +1905,7 @@ impl Vm {
             target => {
                 let hash = field.hash();
 
-                if self.call_field_fn(Protocol::SET, target, hash, (value,))? {
+                if self.call_cached_field_fn(Protocol::SET, target, hash, (value,))? {
                     self.stack.pop()?;
                     Some(())
                 } else {
@@ -1033,6 +2037,19 @@ impl Vm {
         let stack = self.stack.drain(args)?.collect::<Stack>();
         let mut vm = Self::with_stack(self.context.clone(), self.unit.clone(), stack);
         vm.ip = offset;
+        vm.debugger = self.debugger.clone();
+        vm.metrics = self.metrics.as_ref().map(|m| m.spawn(None));
+        vm.fuel = self.fuel.clone();
+        vm.memory = self.memory.clone();
+        vm.limits = self.limits;
+        vm.deadline = self.deadline.clone();
+        vm.preemption = self.preemption.as_ref().map(Preemption::spawn);
+        vm.determinism = self.determinism.clone();
+        vm.capabilities = self.capabilities;
+        vm.call_hook = self.call_hook.clone();
+        vm.extensions = self.extensions.clone();
+        vm.globals = self.globals.clone();
+        vm.collector = self.collector.clone();
         self.stack.push(Generator::new(vm));
         Ok(())
     }
@@ -1042,6 +2059,19 @@ impl Vm {
         let stack = self.stack.drain(args)?.collect::<Stack>();
         let mut vm = Self::with_stack(self.context.clone(), self.unit.clone(), stack);
         vm.ip = offset;
+        vm.debugger = self.debugger.clone();
+        vm.metrics = self.metrics.as_ref().map(|m| m.spawn(None));
+        vm.fuel = self.fuel.clone();
+        vm.memory = self.memory.clone();
+        vm.limits = self.limits;
+        vm.deadline = self.deadline.clone();
+        vm.preemption = self.preemption.as_ref().map(Preemption::spawn);
+        vm.determinism = self.determinism.clone();
+        vm.capabilities = self.capabilities;
+        vm.call_hook = self.call_hook.clone();
+        vm.extensions = self.extensions.clone();
+        vm.globals = self.globals.clone();
+        vm.collector = self.collector.clone();
         self.stack.push(Stream::new(vm));
         Ok(())
     }
@@ -1051,7 +2081,20 @@ impl Vm {
         let stack = self.stack.drain(args)?.collect::<Stack>();
         let mut vm = Self::with_stack(self.context.clone(), self.unit.clone(), stack);
         vm.ip = offset;
-        self.stack.push(Future::new(vm.async_complete()));
+        vm.debugger = self.debugger.clone();
+        vm.metrics = self.metrics.as_ref().map(|m| m.spawn(Some(offset)));
+        vm.fuel = self.fuel.clone();
+        vm.memory = self.memory.clone();
+        vm.limits = self.limits;
+        vm.deadline = self.deadline.clone();
+        vm.preemption = self.preemption.as_ref().map(Preemption::spawn);
+        vm.determinism = self.determinism.clone();
+        vm.capabilities = self.capabilities;
+        vm.call_hook = self.call_hook.clone();
+        vm.extensions = self.extensions.clone();
+        vm.globals = self.globals.clone();
+        vm.collector = self.collector.clone();
+        self.stack.push(Future::from_vm(vm));
         Ok(())
     }
 
@@ -1150,47 +2193,73 @@ impl Vm {
         }
     }
 
-    /// Internal impl of a numeric operation.
-    fn internal_num(
+    /// Fast path for arithmetic and comparisons where both operands are
+    /// integers or both are floats, which avoids copying the operands and
+    /// dispatching to protocols.
+    ///
+    /// Returns `false` if the operation has to take the slow path.
+    #[inline]
+    fn try_fast_op(
         &mut self,
-        protocol: Protocol,
-        error: fn() -> VmErrorKind,
-        integer_op: fn(i64, i64) -> Option<i64>,
-        float_op: fn(f64, f64) -> f64,
+        op: InstOp,
         lhs: InstAddress,
         rhs: InstAddress,
-    ) -> Result<(), VmError> {
-        let rhs = self.stack.address(rhs)?;
-        let lhs = self.stack.address(lhs)?;
+    ) -> Result<bool, VmError> {
+        let value = match self.stack.operands(lhs, rhs)? {
+            (Value::Integer(lhs), Value::Integer(rhs)) => match fast_integer_op(op, *lhs, *rhs) {
+                Some(value) => value?,
+                None => return Ok(false),
+            },
+            (Value::Float(lhs), Value::Float(rhs)) => match fast_float_op(op, *lhs, *rhs) {
+                Some(value) => value,
+                None => return Ok(false),
+            },
+            _ => return Ok(false),
+        };
 
-        let (lhs, rhs) = match (lhs, rhs) {
-            (Value::Integer(lhs), Value::Integer(rhs)) => {
-                self.stack.push(integer_op(lhs, rhs).ok_or_else(error)?);
-                return Ok(());
-            }
-            (Value::Float(lhs), Value::Float(rhs)) => {
-                self.stack.push(float_op(lhs, rhs));
-                return Ok(());
+        self.stack.pop_operands(lhs, rhs)?;
+        self.stack.push(value);
+        Ok(true)
+    }
+
+    /// Fast path for indexing into a vector with an integer.
+    ///
+    /// Returns `false` if the index operation has to take the slow path.
+    #[inline]
+    fn try_fast_index_get(
+        &mut self,
+        target: InstAddress,
+        index: InstAddress,
+    ) -> Result<bool, VmError> {
+        use std::convert::TryFrom as _;
+
+        let value = match self.stack.operands(target, index)? {
+            (Value::Vec(vec), Value::Integer(index)) => {
+                let vec = vec.borrow_ref()?;
+
+                match usize::try_from(*index)
+                    .ok()
+                    .and_then(|index| vec.get(index))
+                {
+                    Some(value) => value.clone(),
+                    None => return Ok(false),
+                }
             }
-            (lhs, rhs) => (lhs, rhs),
+            _ => return Ok(false),
         };
 
-        if !self.call_instance_fn(lhs.clone(), protocol, (&rhs,))? {
-            return Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
-                op: protocol.name,
-                lhs: lhs.type_info()?,
-                rhs: rhs.type_info()?,
-            }));
-        }
-
-        Ok(())
+        self.stack.pop_operands(target, index)?;
+        self.stack.push(value);
+        Ok(true)
     }
 
     /// Internal impl of a numeric operation.
-    fn internal_infallible_bitwise(
+    fn internal_num(
         &mut self,
         protocol: Protocol,
-        integer_op: fn(i64, i64) -> i64,
+        error: fn() -> VmErrorKind,
+        integer_op: fn(i64, i64) -> Option<i64>,
+        float_op: fn(f64, f64) -> f64,
         lhs: InstAddress,
         rhs: InstAddress,
     ) -> Result<(), VmError> {
@@ -1199,7 +2268,11 @@ impl Vm {
 
         let (lhs, rhs) = match (lhs, rhs) {
             (Value::Integer(lhs), Value::Integer(rhs)) => {
-                self.stack.push(integer_op(lhs, rhs));
+                self.stack.push(integer_op(lhs, rhs).ok_or_else(error)?);
+                return Ok(());
+            }
+            (Value::Float(lhs), Value::Float(rhs)) => {
+                self.stack.push(float_op(lhs, rhs));
                 return Ok(());
             }
             (lhs, rhs) => (lhs, rhs),
@@ -1347,6 +2420,23 @@ impl Vm {
         value.into_shared_future()
     }
 
+    /// Suspend the execution if the value being awaited is a [Suspend],
+    /// replacing it with the value which is handed to the host.
+    #[cfg_attr(feature = "bench", inline(never))]
+    fn op_suspend(&mut self) -> Result<bool, VmError> {
+        let value = match self.stack.last()? {
+            Value::Any(any) if any.borrow_ref()?.is::<Suspend>() => {
+                any.downcast_borrow_ref::<Suspend>()?.value().clone()
+            }
+            _ => return Ok(false),
+        };
+
+        self.stack.pop()?;
+        self.stack.push(value);
+        self.advance();
+        Ok(true)
+    }
+
     #[cfg_attr(feature = "bench", inline(never))]
     fn op_select(&mut self, len: usize) -> Result<Option<Select>, VmError> {
         let futures = futures_util::stream::FuturesUnordered::new();
@@ -1458,6 +2548,14 @@ impl Vm {
         Ok(())
     }
 
+    /// Reuse the current call frame to call the current function again.
+    #[cfg_attr(feature = "bench", inline(never))]
+    fn op_tail_call(&mut self, args: usize, offset: isize) -> Result<(), VmError> {
+        self.stack.reframe(args)?;
+        self.modify_ip(offset)?;
+        Ok(())
+    }
+
     /// Perform a conditional jump operation.
     #[cfg_attr(feature = "bench", inline(never))]
     fn op_jump_if(&mut self, offset: isize) -> Result<(), VmError> {
@@ -1511,7 +2609,7 @@ impl Vm {
     #[cfg_attr(feature = "bench", inline(never))]
     fn op_vec(&mut self, count: usize) -> Result<(), VmError> {
         let vec = Vec::from(self.stack.pop_sequence(count)?);
-        self.stack.push(Shared::new(vec));
+        self.stack.push(Shared::tracked(vec));
         Ok(())
     }
 
@@ -1570,7 +2668,10 @@ impl Vm {
 
         let value = match value {
             Value::Float(value) => Value::from(-value),
-            Value::Integer(value) => Value::from(-value),
+            Value::Integer(value) => match value.checked_neg() {
+                Some(value) => Value::from(value),
+                None => return Err(VmError::from(VmErrorKind::Overflow)),
+            },
             other => {
                 let operand = other.type_info()?;
                 return Err(VmError::from(VmErrorKind::UnsupportedUnaryOperation {
@@ -1588,6 +2689,10 @@ impl Vm {
     fn op_op(&mut self, op: InstOp, lhs: InstAddress, rhs: InstAddress) -> Result<(), VmError> {
         use std::convert::TryFrom as _;
 
+        if self.try_fast_op(op, lhs, rhs)? {
+            return Ok(());
+        }
+
         match op {
             InstOp::Add => {
                 self.internal_num(
@@ -1679,7 +2784,13 @@ impl Vm {
                 )?;
             }
             InstOp::Shr => {
-                self.internal_infallible_bitwise(Protocol::SHR, std::ops::Shr::shr, lhs, rhs)?;
+                self.internal_bitwise(
+                    Protocol::SHR,
+                    || VmErrorKind::Overflow,
+                    |a, b| a.checked_shr(u32::try_from(b).ok()?),
+                    lhs,
+                    rhs,
+                )?;
             }
             InstOp::Gt => {
                 self.internal_boolean_ops(|a, b| a > b, |a, b| a > b, ">", lhs, rhs)?;
@@ -1804,10 +2915,11 @@ impl Vm {
                 )?;
             }
             InstAssignOp::Shr => {
-                self.internal_infallible_bitwise_assign(
+                self.internal_bitwise_assign(
                     target,
                     Protocol::SHR_ASSIGN,
-                    std::ops::ShrAssign::shr_assign,
+                    || VmErrorKind::Overflow,
+                    |a, b| a.checked_shr(u32::try_from(b).ok()?),
                 )?;
             }
         }
@@ -1927,6 +3039,10 @@ impl Vm {
     /// Perform an index get operation.
     #[cfg_attr(feature = "bench", inline(never))]
     fn op_index_get(&mut self, target: InstAddress, index: InstAddress) -> Result<(), VmError> {
+        if self.try_fast_index_get(target, index)? {
+            return Ok(());
+        }
+
         let index = self.stack.address(index)?;
         let target = self.stack.address_ref(target)?;
 
@@ -2099,7 +3215,7 @@ impl Vm {
             object.insert(key.clone(), value);
         }
 
-        self.stack.push(Shared::new(object));
+        self.stack.push(Shared::tracked(object));
         Ok(())
     }
 
@@ -2115,7 +3231,7 @@ impl Vm {
         };
 
         let range = Range::new(start, end, limits);
-        self.stack.push(Shared::new(range));
+        self.stack.push(Shared::tracked(range));
         Ok(())
     }
 
@@ -2209,6 +3325,20 @@ impl Vm {
         Ok(())
     }
 
+    #[cfg_attr(feature = "bench", inline(never))]
+    fn op_static_tuple(&mut self, slot: usize) -> Result<(), VmError> {
+        let tuple = self
+            .unit
+            .lookup_tuple(slot)?
+            .iter()
+            .cloned()
+            .map(ConstValue::into_value)
+            .collect::<vec::Vec<_>>();
+
+        self.stack.push(Tuple::from(tuple));
+        Ok(())
+    }
+
     /// Optimize operation to perform string concatenation.
     #[cfg_attr(feature = "bench", inline(never))]
     fn op_string_concat(&mut self, len: usize, size_hint: usize) -> Result<(), VmError> {
@@ -2411,18 +3541,18 @@ impl Vm {
         match variant {
             InstVariant::Some => {
                 let some = self.stack.pop()?;
-                self.stack.push(Value::Option(Shared::new(Some(some))));
+                self.stack.push(Value::Option(Shared::tracked(Some(some))));
             }
             InstVariant::None => {
-                self.stack.push(Value::Option(Shared::new(None)));
+                self.stack.push(Value::Option(Shared::tracked(None)));
             }
             InstVariant::Ok => {
                 let some = self.stack.pop()?;
-                self.stack.push(Value::Result(Shared::new(Ok(some))));
+                self.stack.push(Value::Result(Shared::tracked(Ok(some))));
             }
             InstVariant::Err => {
                 let some = self.stack.pop()?;
-                self.stack.push(Value::Result(Shared::new(Err(some))));
+                self.stack.push(Value::Result(Shared::tracked(Err(some))));
             }
         }
 
@@ -2432,60 +3562,28 @@ impl Vm {
     /// Load a function as a value onto the stack.
     #[cfg_attr(feature = "bench", inline(never))]
     fn op_load_fn(&mut self, hash: Hash) -> Result<(), VmError> {
-        let function = match self.unit.function(hash) {
-            Some(info) => match info {
-                UnitFn::Offset { offset, call, args } => Function::from_offset(
-                    self.context.clone(),
-                    self.unit.clone(),
-                    offset,
-                    call,
-                    args,
-                    hash,
-                ),
-                UnitFn::UnitStruct { hash } => {
-                    let rtti = self
-                        .unit
-                        .lookup_rtti(hash)
-                        .ok_or(VmErrorKind::MissingRtti { hash })?;
-
-                    Function::from_unit_struct(rtti.clone())
-                }
-                UnitFn::TupleStruct { hash, args } => {
-                    let rtti = self
-                        .unit
-                        .lookup_rtti(hash)
-                        .ok_or(VmErrorKind::MissingRtti { hash })?;
-
-                    Function::from_tuple_struct(rtti.clone(), args)
-                }
-                UnitFn::UnitVariant { hash } => {
-                    let rtti = self
-                        .unit
-                        .lookup_variant_rtti(hash)
-                        .ok_or(VmErrorKind::MissingVariantRtti { hash })?;
+        let function = Function::lookup(&self.context, &self.unit, hash)?
+            .ok_or(VmErrorKind::MissingFunction { hash })?;
 
-                    Function::from_unit_variant(rtti.clone())
-                }
-                UnitFn::TupleVariant { hash, args } => {
-                    let rtti = self
-                        .unit
-                        .lookup_variant_rtti(hash)
-                        .ok_or(VmErrorKind::MissingVariantRtti { hash })?;
+        self.stack.push(Value::Function(Shared::tracked(function)));
+        Ok(())
+    }
 
-                    Function::from_tuple_variant(rtti.clone(), args)
-                }
-            },
-            None => {
-                let handler = self
-                    .context
-                    .function(hash)
-                    .ok_or(VmErrorKind::MissingFunction { hash })?;
+    /// Load a global variable of the virtual machine.
+    #[cfg_attr(feature = "bench", inline(never))]
+    fn op_load_global(&mut self, slot: usize) -> Result<(), VmError> {
+        let name = self.unit.lookup_string(slot)?;
 
-                Function::from_handler(handler.clone(), hash)
-            }
+        let value = match &self.globals {
+            Some(globals) => globals.borrow_ref()?.get(name.as_str()).cloned(),
+            None => None,
         };
 
-        self.stack.push(Value::Function(Shared::new(function)));
+        let value = value.ok_or_else(|| VmErrorKind::MissingGlobal {
+            name: name.as_str().to_owned(),
+        })?;
+
+        self.stack.push(value);
         Ok(())
     }
 
@@ -2514,7 +3612,7 @@ impl Vm {
             hash,
         );
 
-        self.stack.push(Value::Function(Shared::new(function)));
+        self.stack.push(Value::Function(Shared::tracked(function)));
         Ok(())
     }
 
@@ -2586,7 +3684,17 @@ impl Vm {
                     .function(hash)
                     .ok_or(VmErrorKind::MissingFunction { hash })?;
 
-                handler(&mut self.stack, args)?;
+                self.enter_native(hash, args)?;
+
+                let _span = native_span(&self.context, hash).entered();
+                let stack = &mut self.stack;
+
+                match &self.metrics {
+                    Some(metrics) => {
+                        metrics.time(hash, || catch_native(|| handler(stack, args)))?
+                    }
+                    None => catch_native(|| handler(stack, args))?,
+                }
             }
         }
 
@@ -2595,25 +3703,73 @@ impl Vm {
 
     #[cfg_attr(feature = "bench", inline(never))]
     fn op_call_instance(&mut self, hash: Hash, args: usize) -> Result<(), VmError> {
+        let protocol = hash;
         // NB: +1 to include the instance itself.
         let args = args + 1;
         let instance = self.stack.at_offset_from_top(args)?;
         let type_hash = instance.type_hash()?;
         let hash = Hash::instance_function(type_hash, hash);
 
-        if let Some(UnitFn::Offset {
-            offset,
-            call,
-            args: expected,
-        }) = self.unit.function(hash)
-        {
-            Self::check_args(args, expected)?;
-            self.call_offset_fn(offset, call, args)?;
-            return Ok(());
+        let cached = match self.inline_cache.get(self.ip, type_hash) {
+            Some(cached) => Some(cached.clone()),
+            None => {
+                let cached = if let Some(UnitFn::Offset {
+                    offset,
+                    call,
+                    args: expected,
+                }) = self.unit.function(hash)
+                {
+                    Some(Cached::Offset {
+                        offset,
+                        call,
+                        args: expected,
+                    })
+                } else {
+                    self.context.function(hash).cloned().map(Cached::Handler)
+                };
+
+                if let Some(cached) = &cached {
+                    self.inline_cache.insert(self.ip, type_hash, cached.clone());
+                }
+
+                cached
+            }
+        };
+
+        match cached {
+            Some(Cached::Offset {
+                offset,
+                call,
+                args: expected,
+            }) => {
+                Self::check_args(args, expected)?;
+                self.call_offset_fn(offset, call, args)?;
+                return Ok(());
+            }
+            Some(Cached::Handler(handler)) => {
+                self.enter_native(hash, args)?;
+                let _span = native_span(&self.context, hash).entered();
+                let stack = &mut self.stack;
+
+                match &self.metrics {
+                    Some(metrics) => {
+                        metrics.time(hash, || catch_native(|| handler(stack, args)))?
+                    }
+                    None => catch_native(|| handler(stack, args))?,
+                }
+
+                return Ok(());
+            }
+            None => (),
         }
 
-        if let Some(handler) = self.context.function(hash) {
-            handler(&mut self.stack, args)?;
+        let instance = self.stack.at_offset_from_top(args)?;
+
+        // NB: values which don't implement the clone protocol are cloned
+        // deeply, except for external types which we know nothing about.
+        if protocol == Protocol::CLONE.hash && args == 1 && !matches!(instance, Value::Any(..)) {
+            let instance = self.stack.pop()?;
+            self.stack.push(crate::runtime::deep_clone(&instance)?);
             return Ok(());
         }
 
@@ -2708,17 +3864,51 @@ impl Vm {
     where
         F: FnOnce() -> T,
     {
-        let _guard = crate::runtime::env::Guard::new(&self.context, &self.unit);
-        f()
+        let guard = crate::runtime::env::Guard::new(self);
+        let output = f();
+        guard.exit(&self.drop_errors);
+        output
     }
 
     /// Evaluate a single instruction.
     pub(crate) fn run(&mut self) -> Result<VmHalt, VmError> {
         // NB: set up environment so that native function can access context and
         // unit.
-        let _guard = crate::runtime::env::Guard::new(&self.context, &self.unit);
+        let guard = crate::runtime::env::Guard::new(self);
+        let result = self.run_instructions();
+        guard.exit(&self.drop_errors);
+        result
+    }
+
+    fn run_instructions(&mut self) -> Result<VmHalt, VmError> {
+        // NB: reading the clock for every instruction is too expensive, so the
+        // deadline is only checked periodically.
+        let mut ticks = 0u32;
 
         loop {
+            if let Some(deadline) = &self.deadline {
+                if ticks == 0 {
+                    deadline.check()?;
+                    ticks = DEADLINE_INTERVAL;
+                }
+
+                ticks -= 1;
+            }
+
+            if let Some(fuel) = &self.fuel {
+                if !fuel.take() {
+                    return Ok(VmHalt::OutOfFuel);
+                }
+            }
+
+            if matches!(self.limits.stack_size, Some(limit) if self.stack.len() > limit) {
+                return Err(self.stack_overflow());
+            }
+
+            if self.debugger.is_some() && self.debug_hook() {
+                return Ok(VmHalt::Paused);
+            }
+
             if !budget::take() {
                 return Ok(VmHalt::Limited);
             }
@@ -2730,6 +3920,11 @@ impl Vm {
 
             tracing::trace!("{}: {}", self.ip, inst);
 
+            let charged = match &self.memory {
+                Some(memory) => Some(self.memory_before(memory, &inst)?),
+                None => None,
+            };
+
             match inst {
                 Inst::Not => {
                     self.op_not()?;
@@ -2743,6 +3938,12 @@ impl Vm {
                 Inst::Call { hash, args } => {
                     self.op_call(hash, args)?;
                 }
+                Inst::CallOffset { offset, call, args } => {
+                    self.call_offset_fn(offset, call, args)?;
+                }
+                Inst::TailCall { args, offset } => {
+                    self.op_tail_call(args, offset)?;
+                }
                 Inst::CallInstance { hash, args } => {
                     self.op_call_instance(hash, args)?;
                 }
@@ -2791,7 +3992,19 @@ impl Vm {
                     }
                 }
                 Inst::Await => {
+                    if self.op_suspend()? {
+                        return Ok(VmHalt::Yielded);
+                    }
+
                     let future = self.op_await()?;
+
+                    // NB: async functions which haven't been polled yet are
+                    // run as part of the execution, so that they can suspend
+                    // it.
+                    if let Some(vm) = future.borrow_mut()?.take_vm() {
+                        return Ok(VmHalt::VmCall(VmCall::new(Call::Immediate, vm)));
+                    }
+
                     // NB: the future itself will advance the virtual machine.
                     return Ok(VmHalt::Awaited(Awaited::Future(future)));
                 }
@@ -2804,6 +4017,9 @@ impl Vm {
                 Inst::LoadFn { hash } => {
                     self.op_load_fn(hash)?;
                 }
+                Inst::LoadGlobal { slot } => {
+                    self.op_load_global(slot)?;
+                }
                 Inst::Push { value } => {
                     self.op_push(value)?;
                 }
@@ -2894,6 +4110,9 @@ impl Vm {
                 Inst::Bytes { slot } => {
                     self.op_bytes(slot)?;
                 }
+                Inst::StaticTuple { slot } => {
+                    self.op_static_tuple(slot)?;
+                }
                 Inst::StringConcat { len, size_hint } => {
                     self.op_string_concat(len, size_hint)?;
                 }
@@ -2969,7 +4188,17 @@ impl Vm {
                 }
             }
 
+            if let Some(values) = charged {
+                self.memory_after(values);
+            }
+
             self.advance();
+
+            if let Some(preemption) = &mut self.preemption {
+                if is_yield_point(&inst) && preemption.tick() {
+                    return Ok(VmHalt::Preempted);
+                }
+            }
         }
     }
 }
@@ -3002,6 +4231,11 @@ pub struct CallFrame {
 }
 
 impl CallFrame {
+    /// Construct a call frame.
+    pub(crate) fn new(ip: usize, stack_bottom: usize) -> Self {
+        Self { ip, stack_bottom }
+    }
+
     /// Get the instruction pointer of the call frame.
     pub fn ip(&self) -> usize {
         self.ip
@@ -3013,6 +4247,85 @@ impl CallFrame {
     }
 }
 
+/// Perform an operation on two integers, returning `None` if the operation
+/// isn't supported on its fast path.
+#[inline]
+fn fast_integer_op(op: InstOp, lhs: i64, rhs: i64) -> Option<Result<Value, VmErrorKind>> {
+    let value = match op {
+        InstOp::Add => lhs.checked_add(rhs).ok_or(VmErrorKind::Overflow),
+        InstOp::Sub => lhs.checked_sub(rhs).ok_or(VmErrorKind::Underflow),
+        InstOp::Mul => lhs.checked_mul(rhs).ok_or(VmErrorKind::Overflow),
+        InstOp::Div => lhs.checked_div(rhs).ok_or(VmErrorKind::DivideByZero),
+        InstOp::Rem => lhs.checked_rem(rhs).ok_or(VmErrorKind::DivideByZero),
+        InstOp::Lt => return Some(Ok(Value::Bool(lhs < rhs))),
+        InstOp::Lte => return Some(Ok(Value::Bool(lhs <= rhs))),
+        InstOp::Gt => return Some(Ok(Value::Bool(lhs > rhs))),
+        InstOp::Gte => return Some(Ok(Value::Bool(lhs >= rhs))),
+        InstOp::Eq => return Some(Ok(Value::Bool(lhs == rhs))),
+        InstOp::Neq => return Some(Ok(Value::Bool(lhs != rhs))),
+        _ => return None,
+    };
+
+    Some(value.map(Value::Integer))
+}
+
+/// Perform an operation on two floats, returning `None` if the operation isn't
+/// supported on its fast path.
+#[inline]
+fn fast_float_op(op: InstOp, lhs: f64, rhs: f64) -> Option<Value> {
+    Some(match op {
+        InstOp::Add => Value::Float(lhs + rhs),
+        InstOp::Sub => Value::Float(lhs - rhs),
+        InstOp::Mul => Value::Float(lhs * rhs),
+        InstOp::Div => Value::Float(lhs / rhs),
+        InstOp::Rem => Value::Float(lhs % rhs),
+        InstOp::Lt => Value::Bool(lhs < rhs),
+        InstOp::Lte => Value::Bool(lhs <= rhs),
+        InstOp::Gt => Value::Bool(lhs > rhs),
+        InstOp::Gte => Value::Bool(lhs >= rhs),
+        InstOp::Eq => Value::Bool(lhs == rhs),
+        InstOp::Neq => Value::Bool(lhs != rhs),
+        _ => return None,
+    })
+}
+
+/// Test if the given instruction is a point where the virtual machine can be
+/// preempted.
+fn is_yield_point(inst: &Inst) -> bool {
+    match *inst {
+        Inst::Jump { offset }
+        | Inst::JumpIf { offset }
+        | Inst::JumpIfOrPop { offset }
+        | Inst::JumpIfNotOrPop { offset }
+        | Inst::JumpIfBranch { offset, .. }
+        | Inst::PopAndJumpIfNot { offset, .. } => offset < 0,
+        Inst::Call { .. }
+        | Inst::CallOffset { .. }
+        | Inst::TailCall { .. }
+        | Inst::CallInstance { .. }
+        | Inst::CallFn { .. } => true,
+        _ => false,
+    }
+}
+
+/// Limits on the stacks of a virtual machine.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct StackLimits {
+    /// The maximum number of call frames.
+    pub(crate) call_depth: Option<usize>,
+    /// The maximum number of values on the stack.
+    pub(crate) stack_size: Option<usize>,
+}
+
+impl StackLimits {
+    pub(crate) const fn new() -> Self {
+        Self {
+            call_depth: None,
+            stack_size: None,
+        }
+    }
+}
+
 /// Clear stack on drop.
 struct ClearStack<'a>(&'a mut Vm);
 
@@ -1,4 +1,7 @@
-use crate::runtime::{Call, Future, Generator, Stream, Value, Vm, VmError, VmExecution};
+use crate::runtime::{
+    Call, Future, Generator, Preemption, Stream, Value, Vm, VmError, VmExecution,
+};
+use std::sync::Arc;
 
 /// An instruction to push a virtual machine to the execution.
 #[derive(Debug)]
@@ -18,14 +21,45 @@ impl VmCall {
     where
         T: AsMut<Vm>,
     {
+        let mut vm = self.vm;
+
+        let current = execution.vm_mut();
+        vm.fuel = current.fuel.clone();
+        vm.memory = current.memory.clone();
+        vm.limits = current.limits;
+        vm.deadline = current.deadline.clone();
+        vm.preemption = current.preemption.as_ref().map(Preemption::spawn);
+        vm.determinism = current.determinism.clone();
+        vm.capabilities = current.capabilities;
+        vm.call_hook = current.call_hook.clone();
+        vm.extensions = current.extensions.clone();
+        vm.globals = current.globals.clone();
+        vm.collector = current.collector.clone();
+
+        // NB: breakpoints refer to the sources of a unit, so the debugger is
+        // only inherited by calls into the same unit.
+
+        if Arc::ptr_eq(vm.unit(), current.unit()) {
+            vm.debugger = current.debugger.clone();
+
+            // NB: generators and streams can be suspended, so their bodies
+            // aren't timed.
+            let offset = match self.call {
+                Call::Immediate | Call::Async => Some(vm.ip()),
+                Call::Stream | Call::Generator => None,
+            };
+
+            vm.metrics = current.metrics.as_ref().map(|m| m.spawn(offset));
+        }
+
         let value = match self.call {
-            Call::Async => Value::from(Future::new(self.vm.async_complete())),
+            Call::Async => Value::from(Future::from_vm(vm)),
             Call::Immediate => {
-                execution.push_vm(self.vm);
+                execution.push_vm(vm);
                 return Ok(());
             }
-            Call::Stream => Value::from(Stream::new(self.vm)),
-            Call::Generator => Value::from(Generator::new(self.vm)),
+            Call::Stream => Value::from(Stream::new(vm)),
+            Call::Generator => Value::from(Generator::new(vm)),
         };
 
         let vm = execution.vm_mut();
@@ -1,8 +1,8 @@
 use crate::compile::Item;
 use crate::runtime::panic::BoxedPanic;
 use crate::runtime::{
-    AccessError, CallFrame, ExecutionState, Key, Panic, Protocol, StackError, TypeInfo, TypeOf,
-    Unit, Value, VmHaltInfo,
+    AccessError, CallFrame, Capability, ExecutionState, Key, Panic, Protocol, StackError,
+    StackTrace, TypeInfo, TypeOf, Unit, Value, VmHaltInfo,
 };
 use crate::Hash;
 use std::fmt;
@@ -108,6 +108,17 @@ impl VmError {
         }
     }
 
+    /// The stack trace of the script functions which were being executed when
+    /// the error was raised, if it is available.
+    pub fn stack_trace(&self) -> Option<StackTrace> {
+        match &*self.kind {
+            VmErrorKind::Unwound {
+                unit, ip, frames, ..
+            } => Some(StackTrace::new(unit, *ip, frames)),
+            _ => None,
+        }
+    }
+
     /// Unsmuggles the vm error, returning Ok(Self) in case the error is
     /// critical and should be propagated unaltered.
     pub(crate) fn unpack_critical(self) -> Result<Self, Self> {
@@ -124,6 +135,7 @@ impl VmError {
     fn is_critical(&self) -> bool {
         match &*self.kind {
             VmErrorKind::Panic { .. } => true,
+            VmErrorKind::NativePanic { .. } => true,
             VmErrorKind::Unwound { .. } => true,
             _ => false,
         }
@@ -149,7 +161,7 @@ pub enum VmErrorKind {
     ///
     /// In order to represent this, we need to preserve the instruction pointer
     /// and eventually unit from where the error happened.
-    #[error("{kind} (at inst {ip})")]
+    #[error("{kind} (at inst {ip}){}", StackTrace::new(unit, *ip, frames))]
     Unwound {
         /// The wrapper error.
         kind: Box<VmErrorKind>,
@@ -167,17 +179,29 @@ pub enum VmErrorKind {
     },
     #[error("panicked: {reason}")]
     Panic { reason: Panic },
+    #[error("native code panicked: {message}")]
+    NativePanic { message: String },
     #[error("no running virtual machines")]
     NoRunningVm,
     #[error("halted for unexpected reason `{halt}`")]
     Halted { halt: VmHaltInfo },
     #[error("failed to format argument")]
     FormatError,
+    #[error("{message}")]
+    Serde { message: Box<str> },
     #[error("stack error: {error}")]
     StackError {
         #[from]
         error: StackError,
     },
+    #[error("ran out of fuel")]
+    OutOfFuel,
+    #[error("execution timed out")]
+    Timeout,
+    #[error("memory limit exceeded, {used} bytes used out of {limit}")]
+    MemoryLimitExceeded { used: usize, limit: usize },
+    #[error("stack overflow, call depth of {depth} with {size} values on the stack")]
+    StackOverflow { depth: usize, size: usize },
     #[error("numerical overflow")]
     Overflow,
     #[error("numerical underflow")]
@@ -213,6 +237,8 @@ pub enum VmErrorKind {
     MissingStaticString { slot: usize },
     #[error("static object keys slot `{slot}` does not exist")]
     MissingStaticObjectKeys { slot: usize },
+    #[error("static tuple slot `{slot}` does not exist")]
+    MissingStaticTuple { slot: usize },
     #[error("missing runtime information for variant with hash `{hash}`")]
     MissingVariantRtti { hash: Hash },
     #[error("missing runtime information for type with hash `{hash}`")]
@@ -319,10 +345,16 @@ pub enum VmErrorKind {
     ExpectedVariant { actual: TypeInfo },
     #[error("{actual} can't be converted to a constant value")]
     ConstNotSupported { actual: TypeInfo },
+    #[error("{actual} can't be converted to a sendable value")]
+    SendNotSupported { actual: TypeInfo },
     #[error("{actual} can't be converted to a hash key")]
     KeyNotSupported { actual: TypeInfo },
     #[error("missing interface environment")]
     MissingInterfaceEnvironment,
+    #[error("missing capability `{capability}`")]
+    MissingCapability { capability: Capability },
+    #[error("missing global `{name}`")]
+    MissingGlobal { name: String },
     #[error("index out of bounds")]
     IndexOutOfBounds,
     #[error("unsupported range")]
@@ -1,11 +1,14 @@
-use crate::runtime::budget;
+use crate::runtime::{budget, snapshot};
 use crate::runtime::{
-    Generator, GeneratorState, Stream, Value, Vm, VmError, VmErrorKind, VmHalt, VmHaltInfo,
+    Awaited, Deadline, DebugOutcome, Generator, GeneratorState, RuntimeContext, SnapshotError,
+    Stream, Unit, Value, Vm, VmError, VmErrorKind, VmHalt, VmHaltInfo, YieldNow,
 };
 use crate::shared::AssertSend;
 use std::fmt;
 use std::future::Future;
 use std::mem::take;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// The state of an execution. We keep track of this because it's important to
 /// correctly interact with functions that yield (like generators and streams)
@@ -30,6 +33,18 @@ impl fmt::Display for ExecutionState {
     }
 }
 
+/// Convert the outcome of a debugged execution into a generator state,
+/// treating a pause as an unexpected halt.
+fn into_generator_state(outcome: DebugOutcome) -> Result<GeneratorState, VmError> {
+    match outcome {
+        DebugOutcome::Yielded(value) => Ok(GeneratorState::Yielded(value)),
+        DebugOutcome::Complete(value) => Ok(GeneratorState::Complete(value)),
+        DebugOutcome::Paused => Err(VmError::from(VmErrorKind::Halted {
+            halt: VmHaltInfo::Paused,
+        })),
+    }
+}
+
 /// The execution environment for a virtual machine.
 ///
 /// When an execution is dropped, the stack of the stack of the head machine
@@ -39,12 +54,16 @@ where
     T: AsMut<Vm>,
 {
     /// The current head vm which holds the execution.
-    head: T,
+    pub(crate) head: T,
     /// The state of an execution.
-    state: ExecutionState,
+    pub(crate) state: ExecutionState,
     /// The current stack of virtual machines and the execution state that must
     /// be restored once one is popped.
-    vms: Vec<(Vm, ExecutionState)>,
+    pub(crate) vms: Vec<(Vm, ExecutionState)>,
+    /// If the execution was interrupted before an instruction was executed,
+    /// either by the debugger or by running out of fuel. Such an execution is
+    /// resumed without pushing a value.
+    pub(crate) interrupted: bool,
 }
 
 macro_rules! vm {
@@ -75,6 +94,7 @@ where
             head,
             vms: vec![],
             state: ExecutionState::Initial,
+            interrupted: false,
         }
     }
 
@@ -164,6 +184,120 @@ where
         vm_mut!(self)
     }
 
+    /// Cancel the execution with [VmErrorKind::Timeout] once the given
+    /// duration has passed, counting from now.
+    ///
+    /// This sets the deadline of every virtual machine which is part of the
+    /// execution, see [Vm::set_deadline]. Futures which the execution is
+    /// waiting on when the deadline passes are dropped, so the timeout also
+    /// applies to native futures which never complete.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::{Context, Module, Vm};
+    /// use rune::runtime::VmErrorKind;
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main] async fn main() -> rune::Result<()> {
+    /// let mut module = Module::new();
+    /// module.async_function(&["pending"], std::future::pending::<()>)?;
+    ///
+    /// let mut context = Context::new();
+    /// context.install(&module)?;
+    ///
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub async fn main() {
+    ///             pending().await
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    /// let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+    ///
+    /// let mut execution = vm.execute(&["main"], ())?;
+    /// execution.set_timeout(Duration::from_millis(10));
+    ///
+    /// let error = execution.async_complete().await.unwrap_err();
+    /// assert!(matches!(error.as_unwound().0, VmErrorKind::Timeout));
+    /// # Ok(()) }
+    /// ```
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        let deadline = Arc::new(Deadline::new(Instant::now() + timeout));
+
+        for (vm, _) in &mut self.vms {
+            vm.deadline = Some(deadline.clone());
+        }
+
+        self.head.as_mut().deadline = Some(deadline);
+    }
+
+    /// Serialize the suspended execution into bytes, which can later be
+    /// restored against the same unit with [VmExecution::restore].
+    ///
+    /// The snapshot captures the stacks, call frames and instruction pointers
+    /// of every virtual machine in the execution, together with all values
+    /// reachable from them. Values which are aliased are still aliased once
+    /// restored. Futures, iterators and external types can't be captured, nor
+    /// can values which refer to themselves.
+    ///
+    /// Settings of the virtual machines like fuel, limits and deadlines are
+    /// not part of the snapshot.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rune::{FromValue, Value, Vm};
+    /// use rune::runtime::{GeneratorState, VmExecution};
+    /// use std::sync::Arc;
+    ///
+    /// # fn main() -> rune::Result<()> {
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub fn main() {
+    ///             let a = yield 1;
+    ///             let b = yield a + 1;
+    ///             a + b
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = Arc::new(rune::prepare(&mut sources).build()?);
+    /// let context = Arc::new(Default::default());
+    ///
+    /// let mut vm = Vm::new(Arc::clone(&context), Arc::clone(&unit));
+    /// let mut execution = vm.execute(&["main"], ())?;
+    /// assert!(matches!(execution.resume()?, GeneratorState::Yielded(..)));
+    /// let snapshot = execution.snapshot()?;
+    /// drop(execution);
+    ///
+    /// let mut execution = VmExecution::restore(context, unit, &snapshot)?;
+    ///
+    /// let value = match execution.resume_with(Value::from(10i64))? {
+    ///     GeneratorState::Yielded(value) => i64::from_value(value)?,
+    ///     GeneratorState::Complete(..) => panic!("expected yield"),
+    /// };
+    ///
+    /// assert_eq!(value, 11);
+    ///
+    /// let value = match execution.resume_with(Value::from(20i64))? {
+    ///     GeneratorState::Complete(value) => i64::from_value(value)?,
+    ///     GeneratorState::Yielded(..) => panic!("expected completion"),
+    /// };
+    ///
+    /// assert_eq!(value, 30);
+    /// # Ok(()) }
+    /// ```
+    pub fn snapshot(&self) -> Result<Vec<u8>, SnapshotError>
+    where
+        T: AsRef<Vm>,
+    {
+        snapshot::take(self)
+    }
+
     /// Complete the current execution without support for async instructions.
     ///
     /// This will error if the execution is suspended through yielding.
@@ -209,16 +343,24 @@ where
     /// If the function being executed is a generator or stream this will resume
     /// it while returning a unit from the current `yield`.
     pub async fn async_resume(&mut self) -> Result<GeneratorState, VmError> {
-        if matches!(self.state, ExecutionState::Resumed) {
-            vm_mut!(self).stack_mut().push(Value::Unit);
-        } else {
-            self.state = ExecutionState::Resumed;
-        }
-
+        self.prepare_resume();
         self.inner_async_resume().await
     }
 
+    /// Resume the current execution with support for async instructions,
+    /// stopping if it's paused by the debugger of the virtual machine.
+    ///
+    /// See [Vm::set_debug_listener] and [Vm::add_breakpoint].
+    pub async fn async_resume_debug(&mut self) -> Result<DebugOutcome, VmError> {
+        self.prepare_resume();
+        self.inner_async_resume_debug().await
+    }
+
     async fn inner_async_resume(&mut self) -> Result<GeneratorState, VmError> {
+        into_generator_state(self.inner_async_resume_debug().await?)
+    }
+
+    async fn inner_async_resume_debug(&mut self) -> Result<DebugOutcome, VmError> {
         loop {
             let len = self.vms.len();
             let vm = vm_mut!(self);
@@ -226,7 +368,11 @@ where
             match Self::run(vm)? {
                 VmHalt::Exited => (),
                 VmHalt::Awaited(awaited) => {
-                    awaited.into_vm(vm).await?;
+                    Self::await_into_vm(awaited, vm).await?;
+                    continue;
+                }
+                VmHalt::Preempted => {
+                    YieldNow::default().await;
                     continue;
                 }
                 VmHalt::VmCall(vm_call) => {
@@ -235,7 +381,19 @@ where
                 }
                 VmHalt::Yielded => {
                     let value = vm.stack_mut().pop()?;
-                    return Ok(GeneratorState::Yielded(value));
+                    // NB: a virtual machine pushed onto the execution is
+                    // suspended, so it expects to be resumed with a value.
+                    self.state = ExecutionState::Resumed;
+                    return Ok(DebugOutcome::Yielded(value));
+                }
+                VmHalt::Paused => {
+                    self.interrupted = true;
+                    return Ok(DebugOutcome::Paused);
+                }
+                VmHalt::OutOfFuel => {
+                    let error = Self::out_of_fuel(vm);
+                    self.interrupted = true;
+                    return Err(error);
                 }
                 halt => {
                     return Err(VmError::from(VmErrorKind::Halted {
@@ -246,7 +404,7 @@ where
 
             if len == 0 {
                 let value = self.end()?;
-                return Ok(GeneratorState::Complete(value));
+                return Ok(DebugOutcome::Complete(value));
             }
 
             self.pop_vm()?;
@@ -276,16 +434,73 @@ where
     /// If any async instructions are encountered, this will error with
     /// [VmErrorKind::Halted].
     pub fn resume(&mut self) -> Result<GeneratorState, VmError> {
+        self.prepare_resume();
+        self.inner_resume()
+    }
+
+    /// Resume the current execution without support for async instructions,
+    /// stopping if it's paused by the debugger of the virtual machine.
+    ///
+    /// See [Vm::set_debug_listener] and [Vm::add_breakpoint].
+    ///
+    /// ```
+    /// use rune::runtime::DebugOutcome;
+    /// use rune::{FromValue, Vm};
+    /// use std::sync::Arc;
+    ///
+    /// # fn main() -> rune::Result<()> {
+    /// let mut sources = rune::sources! {
+    ///     entry => {
+    ///         pub fn main() {
+    ///             1 + 2
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let unit = rune::prepare(&mut sources).build()?;
+    ///
+    /// let mut vm = Vm::without_runtime(Arc::new(unit));
+    /// vm.set_single_step(true);
+    ///
+    /// let mut execution = vm.execute(&["main"], ())?;
+    /// let mut steps = 0;
+    ///
+    /// let value = loop {
+    ///     match execution.resume_debug()? {
+    ///         DebugOutcome::Paused => steps += 1,
+    ///         DebugOutcome::Complete(value) => break value,
+    ///         _ => panic!("unexpected outcome"),
+    ///     }
+    /// };
+    ///
+    /// assert!(steps > 0);
+    /// assert_eq!(i64::from_value(value)?, 3);
+    /// # Ok(()) }
+    /// ```
+    pub fn resume_debug(&mut self) -> Result<DebugOutcome, VmError> {
+        self.prepare_resume();
+        self.inner_resume_debug()
+    }
+
+    /// Prepare the execution to be resumed, pushing the result of the current
+    /// `yield` unless resuming from an interruption.
+    fn prepare_resume(&mut self) {
+        if take(&mut self.interrupted) {
+            return;
+        }
+
         if matches!(self.state, ExecutionState::Resumed) {
             vm_mut!(self).stack_mut().push(Value::Unit);
         } else {
             self.state = ExecutionState::Resumed;
         }
-
-        self.inner_resume()
     }
 
     fn inner_resume(&mut self) -> Result<GeneratorState, VmError> {
+        into_generator_state(self.inner_resume_debug()?)
+    }
+
+    fn inner_resume_debug(&mut self) -> Result<DebugOutcome, VmError> {
         loop {
             let len = self.vms.len();
             let vm = vm_mut!(self);
@@ -296,9 +511,22 @@ where
                     vm_call.into_execution(self)?;
                     continue;
                 }
+                VmHalt::Preempted => continue,
                 VmHalt::Yielded => {
                     let value = vm.stack_mut().pop()?;
-                    return Ok(GeneratorState::Yielded(value));
+                    // NB: a virtual machine pushed onto the execution is
+                    // suspended, so it expects to be resumed with a value.
+                    self.state = ExecutionState::Resumed;
+                    return Ok(DebugOutcome::Yielded(value));
+                }
+                VmHalt::Paused => {
+                    self.interrupted = true;
+                    return Ok(DebugOutcome::Paused);
+                }
+                VmHalt::OutOfFuel => {
+                    let error = Self::out_of_fuel(vm);
+                    self.interrupted = true;
+                    return Err(error);
                 }
                 halt => {
                     return Err(VmError::from(VmErrorKind::Halted {
@@ -309,7 +537,7 @@ where
 
             if len == 0 {
                 let value = self.end()?;
-                return Ok(GeneratorState::Complete(value));
+                return Ok(DebugOutcome::Complete(value));
             }
 
             self.pop_vm()?;
@@ -330,7 +558,16 @@ where
                 vm_call.into_execution(self)?;
                 return Ok(None);
             }
-            VmHalt::Limited => return Ok(None),
+            VmHalt::Limited | VmHalt::Preempted => return Ok(None),
+            VmHalt::Paused => {
+                self.interrupted = true;
+                return Ok(None);
+            }
+            VmHalt::OutOfFuel => {
+                let error = Self::out_of_fuel(vm);
+                self.interrupted = true;
+                return Err(error);
+            }
             halt => {
                 return Err(VmError::from(VmErrorKind::Halted {
                     halt: halt.into_info(),
@@ -356,7 +593,7 @@ where
         match budget::with(1, || Self::run(vm)).call()? {
             VmHalt::Exited => (),
             VmHalt::Awaited(awaited) => {
-                awaited.into_vm(vm).await?;
+                Self::await_into_vm(awaited, vm).await?;
                 return Ok(None);
             }
             VmHalt::VmCall(vm_call) => {
@@ -364,6 +601,19 @@ where
                 return Ok(None);
             }
             VmHalt::Limited => return Ok(None),
+            VmHalt::Preempted => {
+                YieldNow::default().await;
+                return Ok(None);
+            }
+            VmHalt::Paused => {
+                self.interrupted = true;
+                return Ok(None);
+            }
+            VmHalt::OutOfFuel => {
+                let error = Self::out_of_fuel(vm);
+                self.interrupted = true;
+                return Err(error);
+            }
             halt => {
                 return Err(VmError::from(VmErrorKind::Halted {
                     halt: halt.into_info(),
@@ -410,25 +660,86 @@ where
         Ok(())
     }
 
+    /// Construct the error raised when the given virtual machine runs out of
+    /// fuel.
+    fn out_of_fuel(vm: &Vm) -> VmError {
+        VmError::from(VmErrorKind::OutOfFuel).into_unwinded(
+            vm.unit(),
+            vm.ip(),
+            vm.call_frames().to_vec(),
+        )
+    }
+
+    /// Wait for the given awaited, cancelling it if the deadline of the
+    /// virtual machine expires.
+    async fn await_into_vm(awaited: Awaited, vm: &mut Vm) -> Result<(), VmError> {
+        let result = match vm.deadline.clone() {
+            Some(deadline) => deadline.wrap(awaited.into_vm(vm)).await,
+            None => awaited.into_vm(vm).await,
+        };
+
+        result.map_err(|error| error.into_unwinded(vm.unit(), vm.ip(), vm.call_frames().to_vec()))
+    }
+
     #[inline]
     fn run(vm: &mut Vm) -> Result<VmHalt, VmError> {
         match vm.run() {
             Ok(reason) => Ok(reason),
-            Err(error) => Err(error.into_unwinded(vm.unit(), vm.ip(), vm.call_frames().to_vec())),
+            Err(error) => {
+                // NB: the calls in progress never return, so they're not
+                // timed.
+                if let Some(metrics) = &mut vm.metrics {
+                    metrics.clear();
+                }
+
+                Err(error.into_unwinded(vm.unit(), vm.ip(), vm.call_frames().to_vec()))
+            }
         }
     }
 }
 
+impl VmExecution<Vm> {
+    /// Restore an execution from a snapshot taken with
+    /// [VmExecution::snapshot].
+    ///
+    /// The unit must be the same as the one the snapshot was taken against,
+    /// and the context must provide every native function referenced by it.
+    /// The unit is [verified][Unit::verify] and the restored virtual machines
+    /// are checked against it, so a malformed snapshot results in an error
+    /// rather than a broken execution.
+    pub fn restore(
+        context: Arc<RuntimeContext>,
+        unit: Arc<Unit>,
+        snapshot: &[u8],
+    ) -> Result<Self, SnapshotError> {
+        snapshot::restore(context, unit, snapshot)
+    }
+}
+
 impl VmExecution<&mut Vm> {
     /// Convert the current execution into one which owns its virtual machine.
     pub fn into_owned(self) -> VmExecution<Vm> {
         let stack = take(self.head.stack_mut());
-        let head = Vm::with_stack(self.head.context().clone(), self.head.unit().clone(), stack);
+        let mut head = Vm::with_stack(self.head.context().clone(), self.head.unit().clone(), stack);
+        head.debugger = self.head.debugger.clone();
+        head.metrics = self.head.metrics.clone();
+        head.fuel = self.head.fuel.clone();
+        head.memory = self.head.memory.clone();
+        head.limits = self.head.limits;
+        head.deadline = self.head.deadline.clone();
+        head.preemption = self.head.preemption;
+        head.determinism = self.head.determinism.clone();
+        head.capabilities = self.head.capabilities;
+        head.call_hook = self.head.call_hook.clone();
+        head.extensions = self.head.extensions.clone();
+        head.globals = self.head.globals.clone();
+        head.collector = self.head.collector.clone();
 
         VmExecution {
             head,
             vms: self.vms,
             state: self.state,
+            interrupted: self.interrupted,
         }
     }
 }
@@ -14,6 +14,13 @@ pub(crate) enum VmHalt {
     Awaited(Awaited),
     /// Call into a new virtual machine.
     VmCall(VmCall),
+    /// The virtual machine was paused by its debugger.
+    Paused,
+    /// The virtual machine ran out of fuel.
+    OutOfFuel,
+    /// The virtual machine reached a yield point where it should return
+    /// control to the executor.
+    Preempted,
 }
 
 impl VmHalt {
@@ -25,6 +32,9 @@ impl VmHalt {
             Self::Yielded => VmHaltInfo::Yielded,
             Self::Awaited(..) => VmHaltInfo::Awaited,
             Self::VmCall(..) => VmHaltInfo::VmCall,
+            Self::Paused => VmHaltInfo::Paused,
+            Self::OutOfFuel => VmHaltInfo::OutOfFuel,
+            Self::Preempted => VmHaltInfo::Preempted,
         }
     }
 }
@@ -42,6 +52,13 @@ pub enum VmHaltInfo {
     Awaited,
     /// Received instruction to push the inner virtual machine.
     VmCall,
+    /// The virtual machine was paused by its debugger.
+    Paused,
+    /// The virtual machine ran out of fuel.
+    OutOfFuel,
+    /// The virtual machine reached a yield point where it should return
+    /// control to the executor.
+    Preempted,
 }
 
 impl fmt::Display for VmHaltInfo {
@@ -52,6 +69,9 @@ impl fmt::Display for VmHaltInfo {
             Self::Yielded => write!(f, "yielded"),
             Self::Awaited => write!(f, "awaited"),
             Self::VmCall => write!(f, "calling into other vm"),
+            Self::Paused => write!(f, "paused"),
+            Self::OutOfFuel => write!(f, "out of fuel"),
+            Self::Preempted => write!(f, "preempted"),
         }
     }
 }
@@ -0,0 +1,194 @@
+//! Per-function call metrics.
+
+use crate::collections::HashMap;
+use crate::runtime::{Unit, UnitFn, VmError};
+use crate::Hash;
+use std::cmp::Reverse;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+/// The metrics recorded for a single function by [VmMetrics].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FunctionMetrics {
+    /// The number of completed calls to the function.
+    pub calls: u64,
+    /// The cumulative time spent in calls to the function, including the
+    /// functions it called.
+    pub duration: Duration,
+}
+
+/// Call counts and cumulative durations per function hash, recorded by the
+/// virtual machines it's attached to.
+///
+/// Unlike the [Profiler][crate::runtime::Profiler], metrics are only recorded
+/// when functions are called and return, which makes them cheap enough to keep
+/// enabled for long-running processes. The handle can be cloned and shared
+/// between virtual machines, in which case their metrics are aggregated.
+///
+/// Calls to script functions and native functions are both recorded. The
+/// bodies of generators and streams are not timed, since they can be
+/// suspended for an arbitrary amount of time, but the functions they call
+/// are.
+///
+/// # Examples
+///
+/// ```
+/// use rune::{Hash, Source, Sources, Vm};
+/// use rune::runtime::VmMetrics;
+/// use std::sync::Arc;
+///
+/// # fn main() -> rune::Result<()> {
+/// let mut sources = Sources::new();
+/// sources.insert(Source::new("entry", r#"
+/// fn square(n) {
+///     n * n
+/// }
+///
+/// pub fn main() {
+///     square(1) + square(2) + square(3)
+/// }
+/// "#));
+///
+/// let unit = rune::prepare(&mut sources).build()?;
+/// let mut vm = Vm::without_runtime(Arc::new(unit));
+///
+/// let metrics = VmMetrics::new();
+/// vm.set_metrics(metrics.clone());
+/// vm.call(&["main"], ())?;
+///
+/// assert_eq!(metrics.get(Hash::type_hash(&["square"])).unwrap().calls, 3);
+/// assert_eq!(metrics.get(Hash::type_hash(&["main"])).unwrap().calls, 1);
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct VmMetrics {
+    inner: Arc<Mutex<HashMap<Hash, FunctionMetrics>>>,
+}
+
+impl VmMetrics {
+    /// Construct a new collection of metrics.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the metrics recorded for the function with the given hash.
+    pub fn get(&self, hash: Hash) -> Option<FunctionMetrics> {
+        self.lock().get(&hash).copied()
+    }
+
+    /// Get a snapshot of the metrics of every recorded function, ordered by
+    /// the cumulative time spent in them.
+    pub fn snapshot(&self) -> Vec<(Hash, FunctionMetrics)> {
+        let mut functions = self
+            .lock()
+            .iter()
+            .map(|(hash, metrics)| (*hash, *metrics))
+            .collect::<Vec<_>>();
+
+        functions.sort_by_key(|(_, metrics)| Reverse(metrics.duration));
+        functions
+    }
+
+    /// Clear all recorded metrics.
+    pub fn reset(&self) {
+        self.lock().clear();
+    }
+
+    fn record(&self, hash: Hash, duration: Duration) {
+        let mut functions = self.lock();
+        let metrics = functions.entry(hash).or_default();
+        metrics.calls += 1;
+        metrics.duration += duration;
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<Hash, FunctionMetrics>> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// The metrics recording state of a single virtual machine.
+#[derive(Debug, Clone)]
+pub(crate) struct MetricsState {
+    metrics: VmMetrics,
+    /// Script functions of the unit, keyed by their offset.
+    functions: Arc<HashMap<usize, Hash>>,
+    /// Calls in progress, as the call depth they return at, the hash of the
+    /// function and when they started.
+    pending: Vec<(usize, Hash, Instant)>,
+}
+
+impl MetricsState {
+    pub(crate) fn new(metrics: VmMetrics, unit: &Unit) -> Self {
+        let functions = unit
+            .iter_functions()
+            .filter_map(|(hash, f)| match f {
+                UnitFn::Offset { offset, .. } => Some((*offset, hash)),
+                _ => None,
+            })
+            .collect();
+
+        Self {
+            metrics,
+            functions: Arc::new(functions),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Construct the state for a virtual machine spawned to run the function
+    /// at the given offset, which is timed if specified.
+    pub(crate) fn spawn(&self, offset: Option<usize>) -> Self {
+        let mut state = Self {
+            metrics: self.metrics.clone(),
+            functions: self.functions.clone(),
+            pending: Vec::new(),
+        };
+
+        if let Some(offset) = offset {
+            state.enter(offset, 0);
+        }
+
+        state
+    }
+
+    /// Get the metrics the state records into.
+    pub(crate) fn metrics(&self) -> &VmMetrics {
+        &self.metrics
+    }
+
+    /// Enter the script function at the given offset, which returns once the
+    /// virtual machine is back at the given call depth.
+    pub(crate) fn enter(&mut self, offset: usize, depth: usize) {
+        if let Some(hash) = self.functions.get(&offset) {
+            self.pending.push((depth, *hash, Instant::now()));
+        }
+    }
+
+    /// Return from the call at the given call depth.
+    pub(crate) fn exit(&mut self, depth: usize) {
+        if !matches!(self.pending.last(), Some((d, ..)) if *d == depth) {
+            return;
+        }
+
+        if let Some((_, hash, start)) = self.pending.pop() {
+            self.metrics.record(hash, start.elapsed());
+        }
+    }
+
+    /// Discard all calls in progress.
+    pub(crate) fn clear(&mut self) {
+        self.pending.clear();
+    }
+
+    /// Time a call to a native function.
+    pub(crate) fn time<T>(
+        &self,
+        hash: Hash,
+        f: impl FnOnce() -> Result<T, VmError>,
+    ) -> Result<T, VmError> {
+        let start = Instant::now();
+        let output = f()?;
+        self.metrics.record(hash, start.elapsed());
+        Ok(output)
+    }
+}
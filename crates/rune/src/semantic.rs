@@ -0,0 +1,461 @@
+//! Semantic classification of the tokens in a source.
+//!
+//! This is used to provide resolution-aware highlighting of sources, where
+//! for example a path is highlighted differently depending on whether it
+//! resolves to a function, a type or a constant.
+//!
+//! ```
+//! use rune::semantic::{SemanticKind, SemanticTokens};
+//! use rune::{Context, SourceId};
+//!
+//! # fn main() -> rune::Result<()> {
+//! let context = Context::with_default_modules()?;
+//!
+//! let mut sources = rune::sources! {
+//!     entry => {
+//!         const LIMIT = 10;
+//!
+//!         pub fn main(n) {
+//!             let total = n + LIMIT;
+//!             total
+//!         }
+//!     }
+//! };
+//!
+//! let mut semantic = SemanticTokens::new();
+//!
+//! rune::prepare(&mut sources)
+//!     .with_context(&context)
+//!     .with_visitor(&mut semantic)
+//!     .build()?;
+//!
+//! let source = sources.get(SourceId::new(0)).unwrap();
+//!
+//! let tokens = semantic
+//!     .tokens(&sources, SourceId::new(0))
+//!     .into_iter()
+//!     .map(|t| (source.get(t.span.range()).unwrap(), t.kind))
+//!     .collect::<Vec<_>>();
+//!
+//! assert!(tokens.contains(&("LIMIT", SemanticKind::Constant)));
+//! assert!(tokens.contains(&("main", SemanticKind::Function)));
+//! assert!(tokens.contains(&("n", SemanticKind::Parameter)));
+//! assert!(tokens.contains(&("total", SemanticKind::Variable)));
+//! # Ok(()) }
+//! ```
+
+use crate::ast::{self, Span, Spanned, Visit, Walk};
+use crate::collections::{HashMap, HashSet};
+use crate::compile::{CompileVisitor, MetaKind, MetaRef};
+use crate::parse::{self, Lexer};
+use crate::{SourceId, Sources};
+use std::collections::BTreeMap;
+
+/// The kind of a [SemanticToken].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SemanticKind {
+    /// A comment.
+    Comment,
+    /// A keyword.
+    Keyword,
+    /// A string, byte string, character or byte literal.
+    String,
+    /// A number literal.
+    Number,
+    /// A label, like `'outer`.
+    Label,
+    /// A module.
+    Module,
+    /// A type, like a struct or an enum.
+    Type,
+    /// A variant of an enum.
+    Variant,
+    /// A function.
+    Function,
+    /// An instance function called through `value.name()`.
+    Method,
+    /// A macro.
+    Macro,
+    /// A constant.
+    Constant,
+    /// A function or closure parameter.
+    Parameter,
+    /// A local variable.
+    Variable,
+}
+
+impl SemanticKind {
+    /// Every semantic kind, in the order of their [SemanticKind::index].
+    pub const ALL: [SemanticKind; 14] = [
+        SemanticKind::Comment,
+        SemanticKind::Keyword,
+        SemanticKind::String,
+        SemanticKind::Number,
+        SemanticKind::Label,
+        SemanticKind::Module,
+        SemanticKind::Type,
+        SemanticKind::Variant,
+        SemanticKind::Function,
+        SemanticKind::Method,
+        SemanticKind::Macro,
+        SemanticKind::Constant,
+        SemanticKind::Parameter,
+        SemanticKind::Variable,
+    ];
+
+    /// The index of the kind in [SemanticKind::ALL].
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// A stable name for the kind.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SemanticKind::Comment => "comment",
+            SemanticKind::Keyword => "keyword",
+            SemanticKind::String => "string",
+            SemanticKind::Number => "number",
+            SemanticKind::Label => "label",
+            SemanticKind::Module => "module",
+            SemanticKind::Type => "type",
+            SemanticKind::Variant => "variant",
+            SemanticKind::Function => "function",
+            SemanticKind::Method => "method",
+            SemanticKind::Macro => "macro",
+            SemanticKind::Constant => "constant",
+            SemanticKind::Parameter => "parameter",
+            SemanticKind::Variable => "variable",
+        }
+    }
+}
+
+/// A single classified token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SemanticToken {
+    /// The span of the token.
+    pub span: Span,
+    /// The kind of the token.
+    pub kind: SemanticKind,
+}
+
+/// Something the compiler resolved at a given span.
+#[derive(Debug, Clone, Copy)]
+enum Resolved {
+    /// The span resolved to an item with the given kind.
+    Meta(SemanticKind),
+    /// The span is the use of a variable declared at the given span.
+    Variable(Span),
+}
+
+/// A [CompileVisitor] which records what the compiler resolved, so that the
+/// tokens of a source can be classified by what they refer to through
+/// [SemanticTokens::tokens].
+#[derive(Default)]
+pub struct SemanticTokens {
+    resolved: HashMap<SourceId, Vec<(Span, Resolved)>>,
+}
+
+impl SemanticTokens {
+    /// Construct a new empty collection of semantic tokens.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Classify the tokens of the given source.
+    ///
+    /// Tokens are classified lexically first, then by the declarations found
+    /// in the syntax tree of the source, and finally by what the compiler
+    /// resolved them to. If the source fails to lex or parse only what could
+    /// be classified up until that point is returned.
+    ///
+    /// The returned tokens are ordered by their position in the source.
+    pub fn tokens(&self, sources: &Sources, source_id: SourceId) -> Vec<SemanticToken> {
+        let source = match sources.get(source_id) {
+            Some(source) => source.as_str(),
+            None => return Vec::new(),
+        };
+
+        let mut classifier = Classifier {
+            source,
+            entries: BTreeMap::new(),
+            parameters: HashSet::new(),
+        };
+
+        let mut lexer = Lexer::new(source, source_id, true);
+
+        while let Ok(Some(token)) = lexer.next() {
+            classifier.lexical(token);
+        }
+
+        if let Ok(file) = parse::parse_all::<ast::File>(source, source_id, true) {
+            file.accept(&mut classifier);
+        }
+
+        if let Some(resolved) = self.resolved.get(&source_id) {
+            for (span, resolved) in resolved {
+                let kind = match *resolved {
+                    Resolved::Meta(kind) => kind,
+                    Resolved::Variable(decl) if classifier.parameters.contains(&decl) => {
+                        SemanticKind::Parameter
+                    }
+                    Resolved::Variable(..) => SemanticKind::Variable,
+                };
+
+                classifier.resolve(*span, kind);
+            }
+        }
+
+        classifier
+            .entries
+            .into_values()
+            .filter_map(|entry| {
+                Some(SemanticToken {
+                    span: entry.span,
+                    kind: entry.kind?,
+                })
+            })
+            .collect()
+    }
+
+    fn insert(&mut self, source_id: SourceId, span: Span, resolved: Resolved) {
+        self.resolved
+            .entry(source_id)
+            .or_default()
+            .push((span, resolved));
+    }
+}
+
+impl CompileVisitor for SemanticTokens {
+    fn visit_meta(&mut self, source_id: SourceId, meta: MetaRef<'_>, span: Span) {
+        let kind = match meta.kind {
+            MetaKind::UnitStruct | MetaKind::TupleStruct | MetaKind::Struct | MetaKind::Enum => {
+                SemanticKind::Type
+            }
+            MetaKind::UnitVariant | MetaKind::TupleVariant | MetaKind::StructVariant => {
+                SemanticKind::Variant
+            }
+            MetaKind::Function { .. } | MetaKind::ConstFn => SemanticKind::Function,
+            MetaKind::Const => SemanticKind::Constant,
+            _ => return,
+        };
+
+        self.insert(source_id, span, Resolved::Meta(kind));
+    }
+
+    fn visit_variable_use(&mut self, source_id: SourceId, var_span: Span, span: Span) {
+        self.insert(source_id, span, Resolved::Variable(var_span));
+    }
+}
+
+/// A token which might be classified.
+struct Entry {
+    span: Span,
+    kind: Option<SemanticKind>,
+    ident: bool,
+}
+
+/// Helper which classifies the tokens of a single source.
+struct Classifier<'a> {
+    source: &'a str,
+    /// Tokens indexed by the offset they start at.
+    entries: BTreeMap<usize, Entry>,
+    /// Spans of bindings which are declared as parameters.
+    parameters: HashSet<Span>,
+}
+
+impl Classifier<'_> {
+    /// Classify a token from only its kind.
+    fn lexical(&mut self, mut token: ast::Token) {
+        let kind = match token.kind {
+            ast::Kind::Comment => {
+                // Line comments include their trailing newline.
+                let text = self.source.get(token.span.range()).unwrap_or_default();
+                let len = text.trim_end_matches(&['\n', '\r'][..]).len();
+                token.span = Span::new(token.span.start, token.span.start.into_usize() + len);
+                Some(SemanticKind::Comment)
+            }
+            ast::Kind::MultilineComment(..) => Some(SemanticKind::Comment),
+            ast::Kind::Str(..)
+            | ast::Kind::ByteStr(..)
+            | ast::Kind::Char(..)
+            | ast::Kind::Byte(..) => Some(SemanticKind::String),
+            ast::Kind::Number(..) => Some(SemanticKind::Number),
+            ast::Kind::Label(..) => Some(SemanticKind::Label),
+            ast::Kind::Ident(..) => None,
+            kind => {
+                let keyword = self
+                    .source
+                    .get(token.span.range())
+                    .and_then(ast::Kind::from_keyword);
+
+                if keyword == Some(kind) {
+                    Some(SemanticKind::Keyword)
+                } else {
+                    return;
+                }
+            }
+        };
+
+        let ident = matches!(token.kind, ast::Kind::Ident(..));
+
+        self.entries.insert(
+            token.span.start.into_usize(),
+            Entry {
+                span: token.span,
+                kind,
+                ident,
+            },
+        );
+    }
+
+    /// Classify the given identifier.
+    fn declare(&mut self, ident: &ast::Ident, kind: SemanticKind) {
+        if let Some(entry) = self.entries.get_mut(&ident.span.start.into_usize()) {
+            if entry.ident {
+                entry.kind = Some(kind);
+            }
+        }
+    }
+
+    /// Classify the last identifier in the given path.
+    fn declare_path(&mut self, path: &ast::Path, kind: SemanticKind) {
+        self.resolve(path.span(), kind);
+    }
+
+    /// Classify the last identifier inside of the given span.
+    fn resolve(&mut self, span: Span, kind: SemanticKind) {
+        let range = span.start.into_usize()..span.end.into_usize();
+
+        let entry = self
+            .entries
+            .range_mut(range)
+            .rev()
+            .map(|(_, entry)| entry)
+            .find(|entry| entry.ident && entry.span.end <= span.end);
+
+        if let Some(entry) = entry {
+            entry.kind = Some(kind);
+        }
+    }
+
+    /// Declare all bindings in the given pattern.
+    fn bindings(&mut self, pat: &ast::Pat, kind: SemanticKind) {
+        let mut bindings = Bindings::default();
+        pat.accept(&mut bindings);
+
+        for ident in bindings.0 {
+            if let SemanticKind::Parameter = kind {
+                self.parameters.insert(ident.span);
+            }
+
+            self.declare(&ident, kind);
+        }
+    }
+
+    /// Declare the parameters of a function or closure.
+    fn parameters<'a, I>(&mut self, args: I)
+    where
+        I: IntoIterator<Item = &'a ast::FnArg>,
+    {
+        for arg in args {
+            if let ast::FnArg::Pat(pat) = arg {
+                self.bindings(pat, SemanticKind::Parameter);
+            }
+        }
+    }
+}
+
+impl Visit for Classifier<'_> {
+    fn visit_item_fn(&mut self, node: &ast::ItemFn) {
+        self.declare(&node.name, SemanticKind::Function);
+        self.parameters(node.args.iter().map(|(arg, _)| arg));
+        node.walk(self);
+    }
+
+    fn visit_expr_closure(&mut self, node: &ast::ExprClosure) {
+        self.parameters(node.args.as_slice().iter().map(|(arg, _)| arg));
+        node.walk(self);
+    }
+
+    fn visit_item_struct(&mut self, node: &ast::ItemStruct) {
+        self.declare(&node.ident, SemanticKind::Type);
+        node.walk(self);
+    }
+
+    fn visit_item_enum(&mut self, node: &ast::ItemEnum) {
+        self.declare(&node.name, SemanticKind::Type);
+        node.walk(self);
+    }
+
+    fn visit_item_variant(&mut self, node: &ast::ItemVariant) {
+        self.declare(&node.name, SemanticKind::Variant);
+        node.walk(self);
+    }
+
+    fn visit_item_const(&mut self, node: &ast::ItemConst) {
+        self.declare(&node.name, SemanticKind::Constant);
+        node.walk(self);
+    }
+
+    fn visit_item_mod(&mut self, node: &ast::ItemMod) {
+        self.declare(&node.name, SemanticKind::Module);
+        node.walk(self);
+    }
+
+    fn visit_item_impl(&mut self, node: &ast::ItemImpl) {
+        self.declare_path(&node.path, SemanticKind::Type);
+        node.walk(self);
+    }
+
+    fn visit_macro_call(&mut self, node: &ast::MacroCall) {
+        self.declare_path(&node.path, SemanticKind::Macro);
+        node.walk(self);
+    }
+
+    fn visit_local(&mut self, node: &ast::Local) {
+        self.bindings(&node.pat, SemanticKind::Variable);
+        node.walk(self);
+    }
+
+    fn visit_expr_let(&mut self, node: &ast::ExprLet) {
+        self.bindings(&node.pat, SemanticKind::Variable);
+        node.walk(self);
+    }
+
+    fn visit_expr_for(&mut self, node: &ast::ExprFor) {
+        self.bindings(&node.binding, SemanticKind::Variable);
+        node.walk(self);
+    }
+
+    fn visit_expr_call(&mut self, node: &ast::ExprCall) {
+        match &*node.expr {
+            ast::Expr::Path(path) => {
+                self.declare_path(path, SemanticKind::Function);
+            }
+            ast::Expr::FieldAccess(access) => {
+                if let ast::ExprField::Path(path) = &access.expr_field {
+                    self.declare_path(path, SemanticKind::Method);
+                }
+            }
+            _ => (),
+        }
+
+        node.walk(self);
+    }
+}
+
+/// Collects every identifier bound by a pattern.
+#[derive(Default)]
+struct Bindings(Vec<ast::Ident>);
+
+impl Visit for Bindings {
+    fn visit_pat_path(&mut self, node: &ast::PatPath) {
+        if let Some(ident) = node.path.try_as_ident() {
+            self.0.push(*ident);
+        }
+    }
+
+    fn visit_expr(&mut self, _: &ast::Expr) {}
+}
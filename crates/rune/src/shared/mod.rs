@@ -10,5 +10,5 @@ pub(crate) use self::consts::Consts;
 pub(crate) use self::custom::Custom;
 pub(crate) use self::gen::Gen;
 pub(crate) use self::items::Items;
-pub(crate) use self::scopes::Scopes;
 pub(crate) use self::scopes::{ScopeError, ScopeErrorKind};
+pub(crate) use self::scopes::{ScopeGuard, Scopes};
@@ -133,6 +133,12 @@ impl<T> Scopes<T> {
         Ok(())
     }
 
+    /// Unwind any scopes which have been pushed on top of the scope associated
+    /// with the given guard, like when breaking out of a nested scope.
+    pub(crate) fn unwind(&mut self, guard: &ScopeGuard) {
+        self.scopes.truncate(guard.length + 1);
+    }
+
     /// Get the last scope mutably.
     pub(crate) fn last_mut(&mut self) -> Option<&mut Scope<T>> {
         self.scopes.last_mut()
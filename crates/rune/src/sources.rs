@@ -72,6 +72,11 @@ impl Sources {
         source.path()
     }
 
+    /// Iterate over all sources.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &Source> {
+        self.sources.iter()
+    }
+
     /// Get all available source ids.
     pub(crate) fn source_ids(&self) -> impl Iterator<Item = SourceId> {
         (0..self.sources.len()).map(|index| SourceId::new(index as u32))
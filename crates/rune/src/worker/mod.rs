@@ -55,7 +55,7 @@ impl<'a> Worker<'a> {
             options,
             diagnostics,
             source_loader,
-            q: Query::new(unit, consts, storage, sources, visitor, gen, inner),
+            q: Query::new(context, unit, consts, storage, sources, visitor, gen, inner),
             gen,
             loaded: HashMap::new(),
             queue: VecDeque::new(),
@@ -119,6 +119,7 @@ impl<'a> Worker<'a> {
                         scopes: IndexScopes::new(),
                         mod_item,
                         impl_item: Default::default(),
+                        impl_path: Default::default(),
                         source_loader: self.source_loader,
                         nested_item: None,
                     };
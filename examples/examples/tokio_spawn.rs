@@ -37,12 +37,10 @@ async fn main() -> rune::Result<()> {
         println!("timer ticked");
     });
 
-    let execution = vm.clone().send_execute(&["main"], (2u32,))?;
-    let t2 = tokio::spawn(async move {
-        execution.async_complete().await.unwrap();
-        println!("timer ticked");
-    });
+    let t2 = tokio::spawn(vm.send_call::<_, _, ()>(&["main"], (2u32,))?);
 
-    tokio::try_join!(t1, t2).unwrap();
+    let (_, result) = tokio::try_join!(t1, t2).unwrap();
+    result?;
+    println!("timer ticked");
     Ok(())
 }
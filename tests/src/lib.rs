@@ -58,6 +58,12 @@ pub fn vm(
         .build()
         .map_err(RunError::BuildError)?;
 
+    // NB: every unit produced by the compiler is expected to pass
+    // verification.
+    if let Err(error) = unit.verify() {
+        panic!("unit failed verification: {}", error);
+    }
+
     let context = Arc::new(context.runtime());
     Ok(Vm::new(context, Arc::new(unit)))
 }
@@ -155,6 +161,13 @@ pub fn build(context: &Context, source: &str) -> rune::Result<Arc<Unit>> {
     Ok(Arc::new(result?))
 }
 
+/// Construct a virtual machine for the given source, compiled with the given
+/// context.
+pub fn vm_from_source(context: &Context, source: &str) -> rune::Result<Vm> {
+    let unit = build(context, source)?;
+    Ok(Vm::new(Arc::new(context.runtime()), unit))
+}
+
 /// Construct a rune virtual machine from the given program.
 ///
 /// # Examples
@@ -207,6 +220,40 @@ macro_rules! rune_vm_capture {
     }};
 }
 
+/// Construct a rune virtual machine from the given source, with the given
+/// modules installed in addition to the default ones.
+///
+/// Errors raised while building the modules are propagated, so modules can
+/// be constructed with `?` directly in the macro.
+///
+/// # Examples
+///
+/// ```
+/// use rune::FromValue;
+///
+/// # fn main() -> rune::Result<()> {
+/// let source = "pub fn main() { json::to_string(42)? }";
+/// let mut vm = rune_tests::rune_vm_with!(rune_tests::modules::json::module(true)? => source)?;
+/// let output = String::from_value(vm.call(&["main"], ())?)?;
+/// assert_eq!(output, "42");
+/// # Ok(()) }
+/// ```
+#[macro_export]
+macro_rules! rune_vm_with {
+    ($($module:expr),* => $source:expr $(,)?) => {
+        (|| -> ::rune::Result<::rune::Vm> {
+            #[allow(unused_mut)]
+            let mut context = ::rune::Context::with_default_modules()?;
+            $(context.install(&$module)?;)*
+            $crate::vm_from_source(&context, $source)
+        })()
+    };
+
+    ($source:expr $(,)?) => {
+        $crate::rune_vm_with!(=> $source)
+    };
+}
+
 /// Same as [rune_s!] macro, except it takes a Rust token tree. This works
 /// fairly well because Rust and Rune has very similar token trees.
 ///
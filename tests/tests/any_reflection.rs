@@ -0,0 +1,77 @@
+//! Tests for the runtime reflection functions in `std::any`.
+
+use rune_tests::*;
+
+#[test]
+fn test_fields() {
+    type Fields = Option<Vec<String>>;
+
+    let out: (Fields, Fields, Fields, Fields) = rune! {
+        use std::any::fields;
+
+        struct Point { y, x }
+        enum Shape { Circle { radius }, Empty }
+
+        pub fn main() {
+            (
+                fields(#{b: 1, a: 2}),
+                fields(Point { x: 1, y: 2 }),
+                fields(Shape::Circle { radius: 1 }),
+                fields(Shape::Empty),
+            )
+        }
+    };
+
+    assert_eq!(
+        out,
+        (
+            Some(vec![String::from("a"), String::from("b")]),
+            Some(vec![String::from("x"), String::from("y")]),
+            Some(vec![String::from("radius")]),
+            None,
+        )
+    );
+}
+
+#[test]
+fn test_has_protocol() {
+    let out: (bool, bool, bool, bool) = rune! {
+        use std::any::{has_protocol, TypeId};
+
+        struct Counter { value }
+
+        impl Counter {
+            fn clone(self) {
+                Counter { value: self.value }
+            }
+        }
+
+        pub fn main() {
+            (
+                has_protocol(TypeId::of_val(1), "string_display"),
+                has_protocol(Counter { value: 1 }, "clone"),
+                has_protocol(Counter { value: 1 }, "string_display"),
+                has_protocol(1, "not_a_protocol"),
+            )
+        }
+    };
+
+    assert_eq!(out, (true, true, false, false));
+}
+
+#[test]
+fn test_function() {
+    let out: (i64, String, bool) = rune! {
+        use std::any::{function, type_name_of};
+
+        pub fn add(a, b) { a + b }
+
+        pub fn main() {
+            let add = function("add").unwrap();
+            let name = function("std::any::type_name_of").unwrap();
+            (add(1, 2), name(1), function("missing").is_none())
+        }
+    };
+
+    assert_eq!(out, (3, String::from("::std::int"), true));
+}
@@ -0,0 +1,107 @@
+//! Tests for walking the syntax tree with [Visit] and [VisitMut].
+
+use rune::ast::{self, Visit, VisitMut, Walk};
+use rune::SourceId;
+
+const SOURCE: &str = r#"
+fn main() {
+    let total = add(first, second);
+    let double = |n| n * factor;
+    if true { total } else { double(false) }
+}
+"#;
+
+fn parse() -> rune::Result<ast::File> {
+    Ok(rune::parse::parse_all::<ast::File>(
+        SOURCE,
+        SourceId::empty(),
+        false,
+    )?)
+}
+
+/// Collects identifiers in the order they're visited, optionally without
+/// descending into closures.
+struct Idents {
+    idents: Vec<String>,
+    skip_closures: bool,
+}
+
+impl Idents {
+    fn new(skip_closures: bool) -> Self {
+        Self {
+            idents: Vec::new(),
+            skip_closures,
+        }
+    }
+}
+
+impl Visit for Idents {
+    fn visit_ident(&mut self, node: &ast::Ident) {
+        self.idents.push(SOURCE[node.span.range()].to_owned());
+    }
+
+    fn visit_expr_closure(&mut self, node: &ast::ExprClosure) {
+        if !self.skip_closures {
+            node.walk(self);
+        }
+    }
+}
+
+#[derive(Default)]
+struct Bools(Vec<bool>);
+
+impl Visit for Bools {
+    fn visit_lit_bool(&mut self, node: &ast::LitBool) {
+        self.0.push(node.value);
+    }
+}
+
+struct Negate;
+
+impl VisitMut for Negate {
+    fn visit_lit_bool_mut(&mut self, node: &mut ast::LitBool) {
+        node.value = !node.value;
+    }
+}
+
+#[test]
+fn test_visit_in_source_order() -> rune::Result<()> {
+    let file = parse()?;
+
+    let mut visitor = Idents::new(false);
+    file.accept(&mut visitor);
+
+    assert_eq!(
+        visitor.idents,
+        [
+            "main", "total", "add", "first", "second", "double", "n", "n", "factor", "total",
+            "double",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_visit_without_descending() -> rune::Result<()> {
+    let file = parse()?;
+
+    let mut visitor = Idents::new(true);
+    file.accept(&mut visitor);
+
+    assert_eq!(
+        visitor.idents,
+        ["main", "total", "add", "first", "second", "double", "total", "double"]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_visit_mut() -> rune::Result<()> {
+    let mut file = parse()?;
+    file.accept_mut(&mut Negate);
+
+    let mut visitor = Bools::default();
+    file.accept(&mut visitor);
+    assert_eq!(visitor.0, [false, true]);
+    Ok(())
+}
@@ -50,3 +50,19 @@ fn test_string_pattern_in_instance_fn_bug() {
         }
     };
 }
+
+/// Computing the span of an expression used to visit single-field enum
+/// variants twice, making compile time exponential in the length of method
+/// chains.
+#[test]
+fn test_long_method_chain() {
+    let out: String = rune! {
+        pub fn main() {
+            " a ".trim().trim().trim().trim().trim().trim().trim().trim().trim().trim()
+                .trim().trim().trim().trim().trim().trim().trim().trim().trim().trim()
+                .trim().trim().trim().trim().trim().trim().trim().trim().trim().trim()
+        }
+    };
+
+    assert_eq!(out, "a");
+}
@@ -0,0 +1,118 @@
+//! Tests for hooks observing and vetoing calls into native functions.
+
+use rune_tests::*;
+use rune::runtime::{NativeCall, VmError, VmErrorKind};
+use rune::FromValue;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn test_call_hook_observes_arguments() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        r#"
+        pub fn main() {
+            let s = String::from_str("hello");
+            s.push_str(" world");
+            [1, 2].iter().map(|n| n.max(2)).collect::<Vec>()
+        }
+        "#,
+    )?;
+
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let log = calls.clone();
+
+    vm.set_call_hook(move |call: &NativeCall<'_>| {
+        let item = call.item().map(|item| item.to_string()).unwrap_or_default();
+        log.lock().unwrap().push((item, call.args().len()));
+        Ok(())
+    });
+
+    let output = Vec::<i64>::from_value(vm.call(&["main"], ())?)?;
+    assert_eq!(output, [2, 2]);
+
+    let calls = calls.lock().unwrap();
+
+    assert!(calls.contains(&(String::from("::std::string::String::from_str"), 1)));
+    assert!(calls.contains(&(String::from("::std::string::String::push_str"), 2)));
+    assert!(calls.contains(&(String::from("::std::int::max"), 2)));
+    Ok(())
+}
+
+#[test]
+fn test_call_hook_vetoes_calls() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        r#"
+        pub fn allowed() { [1, 2, 3].len() }
+        pub fn denied() { String::from_str("denied") }
+        "#,
+    )?;
+
+    vm.set_call_hook(|call: &NativeCall<'_>| {
+        match call.item() {
+            Some(item) if item.to_string() == "::std::string::String::from_str" => {
+                Err(VmError::panic("call denied"))
+            }
+            _ => Ok(()),
+        }
+    });
+
+    assert_eq!(i64::from_value(vm.call(&["allowed"], ())?)?, 3);
+
+    let error = vm.call(&["denied"], ()).unwrap_err();
+
+    match error.into_unwound().0.into_kind() {
+        VmErrorKind::Panic { reason } => assert_eq!(reason.to_string(), "call denied"),
+        actual => panic!("expected panic but got {:?}", actual),
+    }
+
+    vm.clear_call_hook();
+    assert_eq!(String::from_value(vm.call(&["denied"], ())?)?, "denied");
+    Ok(())
+}
+
+#[test]
+fn test_call_hook_observes_function_values() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        r#"
+        pub fn main() {
+            ["a", "b"].iter().map(String::from_str).collect::<Vec>()
+        }
+        "#,
+    )?;
+
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let log = calls.clone();
+
+    vm.set_call_hook(move |call: &NativeCall<'_>| {
+        let item = call.item().map(|item| item.to_string()).unwrap_or_default();
+
+        if item == "::std::string::String::from_str" {
+            let arg = String::from_value(call.args()[0].clone())?;
+            log.lock().unwrap().push(arg);
+        }
+
+        Ok(())
+    });
+
+    let output = Vec::<String>::from_value(vm.call(&["main"], ())?)?;
+    assert_eq!(output, ["a", "b"]);
+
+    // NB: the function is called by the native `map` iterator rather than by
+    // the virtual machine.
+    assert_eq!(*calls.lock().unwrap(), ["a", "b"]);
+
+    vm.set_call_hook(|call: &NativeCall<'_>| match call.item() {
+        Some(item) if item.to_string() == "::std::string::String::from_str" => {
+            Err(VmError::panic("call denied"))
+        }
+        _ => Ok(()),
+    });
+
+    let error = vm.call(&["main"], ()).unwrap_err();
+
+    match error.into_unwound().0.into_kind() {
+        VmErrorKind::Panic { reason } => assert_eq!(reason.to_string(), "call denied"),
+        actual => panic!("expected panic but got {:?}", actual),
+    }
+
+    Ok(())
+}
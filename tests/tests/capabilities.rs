@@ -0,0 +1,81 @@
+//! Tests for restricting the capabilities of contexts and virtual machines.
+
+use rune::runtime::{Capabilities, Capability, VmError, VmErrorKind};
+use rune::{Any, Context, ContextError, FromValue, Module, Value};
+use rune_tests::*;
+
+#[derive(Any)]
+struct File;
+
+impl File {
+    fn len(&self) -> i64 {
+        42
+    }
+}
+
+fn make_module() -> Result<Module, ContextError> {
+    let mut module = Module::with_crate("fs");
+    module.require(Capability::Filesystem);
+    module.ty::<File>()?;
+    module.function(&["open"], || File)?;
+    module.inst_fn("len", File::len)?;
+    Ok(module)
+}
+
+fn assert_missing_filesystem(result: Result<Value, VmError>) {
+    let error = result.expect_err("expected call to fail");
+
+    assert!(matches!(
+        error.into_unwound().0.into_kind(),
+        VmErrorKind::MissingCapability {
+            capability: Capability::Filesystem
+        }
+    ));
+}
+
+#[test]
+fn test_context_capabilities() -> rune::Result<()> {
+    let module = make_module()?;
+    assert_eq!(
+        module.capabilities(),
+        Capabilities::none().with(Capability::Filesystem)
+    );
+
+    let mut context = Context::new();
+    context.set_capabilities(Capabilities::all().without(Capability::Filesystem));
+
+    assert!(matches!(
+        context.install(&module),
+        Err(ContextError::MissingCapability {
+            capability: Capability::Filesystem,
+            ..
+        })
+    ));
+
+    let mut context = Context::new();
+    context.set_capabilities(Capabilities::none().with(Capability::Filesystem));
+    context.install(&module)?;
+    Ok(())
+}
+
+#[test]
+fn test_vm_capabilities() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        make_module()? => r#"
+        pub fn open() { fs::open() }
+        pub fn len(file) { file.len() }
+        pub fn nested() { [1].iter().map(|_| fs::open()).collect::<Vec>() }
+        "#,
+    )?;
+
+    let file = vm.call(&["open"], ())?;
+    assert_eq!(i64::from_value(vm.call(&["len"], (file.clone(),))?)?, 42);
+
+    vm.set_capabilities(Capabilities::all().without(Capability::Filesystem));
+    assert!(!vm.capabilities().contains(Capability::Filesystem));
+
+    assert_missing_filesystem(vm.call(&["open"], ()));
+    assert_missing_filesystem(vm.call(&["len"], (file,)));
+    assert_missing_filesystem(vm.call(&["nested"], ()));
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use rune::runtime::VmErrorKind::*;
+use rune_tests::*;
+
+#[test]
+fn test_deep_clone_containers() {
+    let out: (Vec<i64>, Vec<i64>, i64, i64) = rune! {
+        pub fn main() {
+            let inner = [1];
+            let a = [inner, #{ inner: inner }, (inner,)];
+            let b = a.clone();
+            b[0].push(2);
+
+            // Shared references are still shared within the clone.
+            (inner, b[0], b[1].inner.len(), b[2].0.len())
+        }
+    };
+
+    assert_eq!(out, (vec![1], vec![1, 2], 2, 2));
+}
+
+#[test]
+fn test_deep_clone_cycle() {
+    let out: (i64, i64) = rune! {
+        pub fn main() {
+            let a = #{};
+            a.me = a;
+
+            let b = a.clone();
+            b.me.value = 42;
+            (b.value, if a.get("value").is_some() { 1 } else { 0 })
+        }
+    };
+
+    assert_eq!(out, (42, 0));
+}
+
+#[test]
+fn test_deep_clone_script_types() {
+    let out: (Vec<i64>, Vec<i64>, String) = rune! {
+        struct Stack { items }
+        struct Named { name }
+
+        impl Named {
+            fn clone(self) {
+                Named { name: self.name + " (copy)" }
+            }
+        }
+
+        pub fn main() {
+            let a = Stack { items: [1, 2] };
+            let b = a.clone();
+            b.items.push(3);
+
+            let named = [Named { name: "a" }].clone();
+            (a.items, b.items, named[0].name)
+        }
+    };
+
+    assert_eq!(out, (vec![1, 2], vec![1, 2, 3], String::from("a (copy)")));
+}
+
+#[test]
+fn test_structural_eq() {
+    let out: (bool, bool, bool, bool) = rune! {
+        struct Point { x, y }
+        struct Labeled { label, value }
+
+        impl Labeled {
+            fn eq(self, other) {
+                self.value == other.value
+            }
+        }
+
+        enum Shape { Dot(point) }
+
+        pub fn main() {
+            let a = [Shape::Dot(Point { x: 1, y: 2 }), #{ p: Point { x: 3, y: 4 } }];
+            let b = [Shape::Dot(Point { x: 1, y: 2 }), #{ p: Point { x: 3, y: 4 } }];
+            let c = [Shape::Dot(Point { x: 1, y: 2 }), #{ p: Point { x: 3, y: 5 } }];
+
+            let l1 = [Labeled { label: "a", value: 1 }];
+            let l2 = [Labeled { label: "b", value: 1 }];
+            let l3 = [Labeled { label: "a", value: 2 }];
+
+            (a == b, a == c, l1 == l2, l1 == l3)
+        }
+    };
+
+    assert_eq!(out, (true, false, true, false));
+}
+
+#[test]
+fn test_external_eq_in_containers() {
+    let out: bool = rune! {
+        pub fn main() {
+            [(1.cmp(2), "a")] == [(3.cmp(4), "a")]
+        }
+    };
+
+    assert!(out);
+}
+
+#[test]
+fn test_unsupported_clone() {
+    assert_vm_error!(
+        r#"
+        async fn work() {}
+
+        pub fn main() {
+            [work()].clone()
+        }
+        "#,
+        UnsupportedUnaryOperation { op: "clone", .. } => {}
+    );
+}
@@ -1,3 +1,4 @@
+use rune::runtime::VmErrorKind::*;
 use rune_tests::*;
 
 #[test]
@@ -39,3 +40,101 @@ fn test_hash_set_tuple() {
         }
     };
 }
+
+#[test]
+fn test_btree_map() {
+    let _: () = rune! {
+        pub fn main() {
+            use std::collections::BTreeMap;
+
+            let m = BTreeMap::from([("b", 2), ("c", 3)]);
+            m.insert("a", 1);
+            m["d"] = 4;
+
+            assert_eq!(m.keys().collect::<Vec>(), ["a", "b", "c", "d"]);
+            assert_eq!(m.values().collect::<Vec>(), [1, 2, 3, 4]);
+            assert_eq!(m.first(), Some(("a", 1)));
+            assert_eq!(m.last(), Some(("d", 4)));
+            assert_eq!(m["b"], 2);
+            assert_eq!(m.get("e"), None);
+            assert_eq!(m.remove("c"), Some(3));
+            assert_eq!(m.remove("c"), None);
+            assert_eq!(m.pop_first(), Some(("a", 1)));
+            assert_eq!(m.pop_last(), Some(("d", 4)));
+            assert_eq!(m.len(), 1);
+
+            let entries = [];
+
+            for (key, value) in BTreeMap::from([(3, "c"), (1, "a"), (2, "b")]) {
+                entries.push((key, value));
+            }
+
+            assert_eq!(entries, [(1, "a"), (2, "b"), (3, "c")]);
+            assert_eq!(BTreeMap::from([(1, [1])]), BTreeMap::from([(1, [1])]));
+            assert!(BTreeMap::from([(1, [1])]) != BTreeMap::from([(1, [2])]));
+            assert_eq!(format!("{:?}", BTreeMap::from([(2, 'b'), (1, 'a')])), "{1: 'a', 2: 'b'}");
+        }
+    };
+}
+
+#[test]
+fn test_vec_deque() {
+    let _: () = rune! {
+        pub fn main() {
+            use std::collections::VecDeque;
+
+            let d = VecDeque::from([2, 3]);
+            d.push_front(1);
+            d.push_back(4);
+
+            assert_eq!(d.front(), Some(1));
+            assert_eq!(d.back(), Some(4));
+            assert!(d.contains(3));
+            assert!(!d.contains(5));
+            assert_eq!(d.pop_front(), Some(1));
+            assert_eq!(d.pop_back(), Some(4));
+            assert_eq!(d, VecDeque::from([2, 3]));
+            assert!(d != VecDeque::from([3, 2]));
+
+            d.rotate_right(1);
+            assert_eq!(d.iter().collect::<Vec>(), [3, 2]);
+            assert_eq!(d.remove(0), Some(3));
+            assert_eq!(d.remove(5), None);
+            assert_eq!(format!("{:?}", d), "[2]");
+
+            d.clear();
+            assert!(d.is_empty());
+        }
+    };
+}
+
+#[test]
+fn test_vec_deque_out_of_range() {
+    assert_vm_error!(
+        r#"pub fn main() { let d = std::collections::VecDeque::from([1]); d[1] }"#,
+        OutOfRange { index, len } => {
+            assert_eq!(index.to_string(), "1");
+            assert_eq!(len.to_string(), "1");
+        }
+    );
+}
+
+#[test]
+fn test_hash_set_relations() {
+    let _: () = rune! {
+        pub fn main() {
+            use std::collections::HashSet;
+
+            let a = HashSet::from([1, 2]);
+            let b = HashSet::from([1, 2, 3]);
+
+            assert!(a.is_subset(b));
+            assert!(b.is_superset(a));
+            assert!(!a.is_disjoint(b));
+            assert!(a.is_disjoint(HashSet::from([4])));
+            assert!(b.remove(3));
+            assert!(!b.remove(3));
+            assert_eq!(a, b);
+        }
+    };
+}
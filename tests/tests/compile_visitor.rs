@@ -0,0 +1,65 @@
+use rune::ast::Span;
+use rune::compile::{CompileVisitor, Item, Location};
+use rune::runtime::Inst;
+use rune::SourceId;
+
+#[derive(Default)]
+struct Recorder {
+    indexed: Vec<String>,
+    compiled: Vec<(String, usize)>,
+    instructions: usize,
+}
+
+impl CompileVisitor for Recorder {
+    fn visit_item_indexed(&mut self, _: Location, item: &Item) {
+        self.indexed.push(item.to_string());
+    }
+
+    fn visit_instruction(&mut self, _: SourceId, _: Span, _: &Inst) {
+        self.instructions += 1;
+    }
+
+    fn visit_function_compiled(&mut self, _: Location, item: &Item, instructions: &[Inst]) {
+        self.compiled.push((item.to_string(), instructions.len()));
+    }
+}
+
+#[test]
+fn test_compile_visitor_hooks() -> rune::Result<()> {
+    let context = rune::Context::with_default_modules()?;
+
+    let mut sources = rune::sources! {
+        entry => {
+            fn add(a, b) {
+                a + b
+            }
+
+            pub fn main() {
+                add(1, 2)
+            }
+        }
+    };
+
+    let mut recorder = Recorder::default();
+
+    rune::prepare(&mut sources)
+        .with_context(&context)
+        .with_visitor(&mut recorder)
+        .build()?;
+
+    assert!(recorder.indexed.iter().any(|item| item == "add"));
+    assert!(recorder.indexed.iter().any(|item| item == "main"));
+
+    let mut names = recorder
+        .compiled
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+    assert_eq!(names, ["add", "main"]);
+
+    assert!(recorder.compiled.iter().all(|(_, len)| *len > 0));
+    let total = recorder.compiled.iter().map(|(_, len)| len).sum::<usize>();
+    assert_eq!(recorder.instructions, total);
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use rune::runtime::Inst;
+use rune::{Context, FromValue, Source, Sources, Vm};
+use std::sync::Arc;
+
+fn run<T>(source: &str) -> rune::Result<(T, usize)>
+where
+    T: FromValue,
+{
+    let context = Context::with_default_modules()?;
+
+    let mut sources = Sources::new();
+    sources.insert(Source::new("main", source));
+
+    let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+
+    let closures = unit
+        .iter_instructions()
+        .filter(|inst| matches!(inst, Inst::Closure { .. }))
+        .count();
+
+    let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+    let output = T::from_value(vm.call(&["main"], ())?)?;
+    Ok((output, closures))
+}
+
+#[test]
+fn test_constant_captures_have_no_environment() -> rune::Result<()> {
+    let source = r#"
+    pub fn main() {
+        let factor = 3;
+        let offset = -1.5;
+        let enabled = true;
+
+        let scale = |n| n * factor;
+        let shift = move |n| n + offset;
+        let check = || || enabled;
+
+        let values = [1, 2, 3].iter().map(scale).collect::<Vec>();
+        (values, shift(2.0), check()())
+    }
+    "#;
+
+    assert_eq!(
+        run::<(Vec<i64>, f64, bool)>(source)?,
+        ((vec![3, 6, 9], 0.5, true), 0)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_non_constant_captures() -> rune::Result<()> {
+    let source = r#"
+    pub fn main() {
+        let a = 1;
+        a += 1;
+        let first = || a;
+
+        let b = 10;
+        let closures = [];
+
+        for n in 0..3 {
+            closures.push(|| b + n);
+            b += 10;
+        }
+
+        let c = 100;
+        let third = || c;
+        c = 200;
+
+        (first(), closures.iter().map(|f| f()).collect::<Vec>(), third(), c)
+    }
+    "#;
+
+    assert_eq!(
+        run::<(i64, Vec<i64>, i64, i64)>(source)?,
+        ((2, vec![10, 21, 32], 100, 200), 2)
+    );
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use rune::runtime::VmErrorKind::*;
+use rune::runtime::{Inst, InstValue};
+use rune::{Context, FromValue, Options, Source, Sources, Unit, Vm};
+use rune_tests::*;
+use std::sync::Arc;
+
+fn build(source: &str, fold_constants: bool) -> rune::Result<(Context, Unit)> {
+    let context = Context::with_default_modules()?;
+
+    let mut options = Options::default();
+    options.fold_constants(fold_constants);
+
+    let mut sources = Sources::new();
+    sources.insert(Source::new("main", source));
+
+    let unit = rune::prepare(&mut sources)
+        .with_context(&context)
+        .with_options(&options)
+        .build()?;
+
+    Ok((context, unit))
+}
+
+fn count_ops(unit: &Unit) -> usize {
+    unit.iter_instructions()
+        .filter(|inst| matches!(inst, Inst::Op { .. }))
+        .count()
+}
+
+#[test]
+fn test_constants_are_folded() -> rune::Result<()> {
+    let source = r#"
+    pub fn main() {
+        60 * 60 * 24
+    }
+    "#;
+
+    let (context, unit) = build(source, true)?;
+    assert_eq!(count_ops(&unit), 0);
+    assert!(unit.iter_instructions().any(|inst| matches!(
+        inst,
+        Inst::Push {
+            value: InstValue::Integer(86400)
+        }
+    )));
+
+    let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+    assert_eq!(i64::from_value(vm.call(&["main"], ())?)?, 86400);
+
+    let (_, unit) = build(source, false)?;
+    assert_eq!(count_ops(&unit), 2);
+    Ok(())
+}
+
+#[test]
+fn test_folded_expressions() {
+    let out: (i64, f64, bool, bool, i64, i64) = rune! {
+        pub fn main() {
+            let n = 2;
+            ((1 + 2) * 3 - 10 % 3, 1.5 * -(2.0), 10 != 3, 1 < 2, n * (3 + 4), -(2 + 3))
+        }
+    };
+
+    assert_eq!(out, (8, -3.0, true, true, 14, -5));
+}
+
+#[test]
+fn test_runtime_errors_are_preserved() {
+    assert_vm_error!(
+        r#"
+        pub fn main() {
+            9223372036854775807 + 1
+        }
+        "#,
+        Overflow => {}
+    );
+
+    assert_vm_error!(
+        r#"
+        pub fn main() {
+            1 / (2 - 2)
+        }
+        "#,
+        DivideByZero => {}
+    );
+}
+
+#[test]
+fn test_operands_of_unfolded_expressions() -> rune::Result<()> {
+    let source = r#"
+    pub fn main(n) {
+        n * (3 + 4) + -(1 + 1)
+    }
+    "#;
+
+    let (context, unit) = build(source, true)?;
+    assert_eq!(count_ops(&unit), 2);
+
+    let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+    assert_eq!(i64::from_value(vm.call(&["main"], (2i64,))?)?, 12);
+
+    // A long chain which can't be folded, since it starts with a variable.
+    let source = format!("pub fn main(n) {{ n{} }}", " + 1".repeat(100));
+    let (context, unit) = build(&source, true)?;
+    assert_eq!(count_ops(&unit), 100);
+
+    let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+    assert_eq!(i64::from_value(vm.call(&["main"], (2i64,))?)?, 102);
+    Ok(())
+}
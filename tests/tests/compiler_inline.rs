@@ -0,0 +1,95 @@
+use rune::runtime::Inst;
+use rune::{Context, FromValue, Options, Source, Sources, Unit, Vm};
+use std::sync::Arc;
+
+fn build(source: &str, options: &Options) -> rune::Result<(Context, Unit)> {
+    let context = Context::with_default_modules()?;
+
+    let mut sources = Sources::new();
+    sources.insert(Source::new("main", source));
+
+    let unit = rune::prepare(&mut sources)
+        .with_context(&context)
+        .with_options(options)
+        .build()?;
+
+    Ok((context, unit))
+}
+
+fn run<T>(source: &str, options: &Options) -> rune::Result<(T, usize)>
+where
+    T: FromValue,
+{
+    let (context, unit) = build(source, options)?;
+
+    let calls = unit
+        .iter_instructions()
+        .filter(|inst| matches!(inst, Inst::Call { .. } | Inst::CallOffset { .. }))
+        .count();
+
+    let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+    let output = T::from_value(vm.call(&["main"], ())?)?;
+    Ok((output, calls))
+}
+
+#[test]
+fn test_small_functions_are_inlined() -> rune::Result<()> {
+    let source = r#"
+    fn area(w, h) {
+        w * h
+    }
+
+    fn first(p) {
+        p.0
+    }
+
+    fn sub(a, b) {
+        a - b
+    }
+
+    pub fn main() {
+        let t = (3, 4);
+        let a = 10;
+        let b = 3;
+        (area(first(t), t.1) + area(2, 5), sub(b, a), sub(a, b))
+    }
+    "#;
+
+    let mut options = Options::default();
+    assert_eq!(run::<(i64, i64, i64)>(source, &options)?, ((22, -7, 7), 5));
+
+    options.inline(true);
+    assert_eq!(run::<(i64, i64, i64)>(source, &options)?, ((22, -7, 7), 0));
+    Ok(())
+}
+
+#[test]
+fn test_inline_threshold() -> rune::Result<()> {
+    let source = r#"
+    fn sum(a) {
+        a + a + a + a + a
+    }
+
+    fn greet(name) {
+        "Hello " + name
+    }
+
+    pub fn main() {
+        (sum(2), greet("World"))
+    }
+    "#;
+
+    let mut options = Options::default();
+    options.inline(true);
+    assert_eq!(
+        run::<(i64, String)>(source, &options)?,
+        ((10, String::from("Hello World")), 2)
+    );
+
+    options.inline_threshold(16);
+    assert_eq!(
+        run::<(i64, String)>(source, &options)?,
+        ((10, String::from("Hello World")), 1)
+    );
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use rune::{Context, FromValue, Options, Source, Sources, Unit, Vm};
+use std::sync::Arc;
+
+const SOURCE: &str = r#"
+fn classify(n) {
+    if n < 0 {
+        return "negative";
+    } else if n == 0 {
+        return "zero";
+    } else {
+        return "positive";
+    }
+}
+
+fn count(values) {
+    let total = 0;
+
+    for value in values {
+        if value % 2 == 0 {
+            continue;
+        }
+
+        total += value;
+    }
+
+    total
+}
+
+fn search(values, needle) {
+    let n = 0;
+
+    loop {
+        if n >= values.len() {
+            break None;
+        }
+
+        if values[n] == needle {
+            break Some(n);
+        }
+
+        n += 1;
+    }
+}
+
+pub fn main() {
+    1;
+    "unused";
+    let values = [1, 2, 3, 4, 5];
+    (classify(-1), classify(0), classify(1), count(values), search(values, 4), search(values, 6))
+}
+"#;
+
+fn build(peephole: bool) -> rune::Result<(Context, Unit)> {
+    let context = Context::with_default_modules()?;
+
+    let mut options = Options::default();
+    options.peephole(peephole);
+
+    let mut sources = Sources::new();
+    sources.insert(Source::new("main", SOURCE));
+
+    let unit = rune::prepare(&mut sources)
+        .with_context(&context)
+        .with_options(&options)
+        .build()?;
+
+    Ok((context, unit))
+}
+
+#[test]
+fn test_peephole_preserves_behavior() -> rune::Result<()> {
+    type Output = (String, String, String, i64, Option<i64>, Option<i64>);
+
+    let mut outputs = Vec::new();
+    let mut sizes = Vec::new();
+
+    for peephole in [false, true] {
+        let (context, unit) = build(peephole)?;
+        sizes.push(unit.iter_instructions().count());
+
+        let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+        outputs.push(Output::from_value(vm.call(&["main"], ())?)?);
+    }
+
+    assert_eq!(outputs[0], outputs[1]);
+    assert_eq!(
+        outputs[1],
+        (
+            String::from("negative"),
+            String::from("zero"),
+            String::from("positive"),
+            9,
+            Some(3),
+            None
+        )
+    );
+
+    assert!(
+        sizes[1] < sizes[0],
+        "expected {} to be less than {}",
+        sizes[1],
+        sizes[0]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use rune::compile::{ComponentRef, Item};
+use rune::Context;
+
+#[test]
+fn test_context_names() -> rune::Result<()> {
+    let context = Context::with_default_modules()?;
+
+    assert!(context.contains_name("std"));
+    assert!(context.contains_name("println"));
+    assert!(context.contains_name("Vec"));
+    assert!(!context.contains_name("not_a_name"));
+
+    let prelude = context.iter_prelude().collect::<Vec<_>>();
+    assert!(prelude
+        .iter()
+        .any(|(name, item)| *name == "Vec" && *item == Item::with_crate_item("std", &["vec", "Vec"])));
+
+    let std = Item::with_crate("std");
+    let components = context.iter_components(&std).collect::<Vec<_>>();
+    assert!(components.contains(&ComponentRef::Str("vec")));
+
+    let empty = Context::new();
+    assert!(!empty.contains_name("println"));
+    assert_eq!(empty.iter_prelude().count(), 0);
+    Ok(())
+}
@@ -49,3 +49,52 @@ fn test_parse_in_macro() -> rune::Result<()> {
     assert_eq!(output, (42, 42));
     Ok(())
 }
+
+#[test]
+fn test_emit_expansions() -> rune::Result<()> {
+    use rune::ast::Span;
+    use rune::compile::CompileVisitor;
+    use rune::{Options, SourceId};
+
+    #[derive(Default)]
+    struct Expansions(Vec<String>);
+
+    impl CompileVisitor for Expansions {
+        fn visit_macro_expansion(&mut self, _: SourceId, _: Span, expanded: &str) {
+            self.0.push(expanded.to_owned());
+        }
+    }
+
+    let mut m = Module::default();
+
+    m.macro_(&["double"], |ctx, stream| {
+        let mut p = Parser::from_token_stream(stream, ctx.stream_span());
+        let expr = p.parse_all::<ast::Expr>()?;
+        Ok(quote!(#expr * 2).into_token_stream(ctx))
+    })?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(&m)?;
+
+    let mut sources = rune::sources! {
+        entry => {
+            pub fn main() {
+                double!(1 + 2)
+            }
+        }
+    };
+
+    let mut options = Options::default();
+    options.emit_expansions(true);
+
+    let mut expansions = Expansions::default();
+
+    rune::prepare(&mut sources)
+        .with_context(&context)
+        .with_options(&options)
+        .with_visitor(&mut expansions)
+        .build()?;
+
+    assert_eq!(expansions.0, vec![String::from("1 + 2 * 2")]);
+    Ok(())
+}
@@ -0,0 +1,78 @@
+//! Tests for protocols defined by the embedder.
+
+use rune::runtime::{Protocol, VmError, VmErrorKind};
+use rune::{Any, ContextError, FromValue, Module, Value};
+use rune_tests::*;
+
+const VALIDATE: Protocol = Protocol::custom("validate");
+
+#[derive(Any)]
+struct Email {
+    address: String,
+}
+
+fn validate(email: &Email, strict: bool) -> bool {
+    email.address.contains('@') && (!strict || email.address.contains('.'))
+}
+
+fn check(value: Value) -> Result<String, VmError> {
+    let valid = bool::from_value(value.protocol_call(VALIDATE, (true,))?)?;
+    Ok(String::from(if valid { "valid" } else { "invalid" }))
+}
+
+fn make_module() -> Result<Module, ContextError> {
+    let mut module = Module::with_crate("mail");
+    module.ty::<Email>()?;
+    module.function(&["email"], |address: String| Email { address })?;
+    module.function(&["check"], check)?;
+    module.inst_fn(VALIDATE, validate)?;
+    Ok(module)
+}
+
+#[test]
+fn test_custom_protocol_hash() {
+    assert_eq!(VALIDATE.name, "validate");
+    assert_eq!(VALIDATE, Protocol::custom("validate"));
+    assert_ne!(VALIDATE.hash, Protocol::custom("serialize").hash);
+}
+
+#[test]
+fn test_custom_protocol_call() {
+    let out: (bool, bool, bool, String, String) = rune_n! {
+        make_module().expect("failed to build module"),
+        (),
+        (bool, bool, bool, String, String) =>
+        use std::any::{has_protocol, protocol_call};
+
+        pub fn main() {
+            let email = mail::email("user@localhost");
+
+            (
+                has_protocol(email, "validate"),
+                protocol_call(email, "validate", [false]),
+                protocol_call(email, "validate", [true]),
+                mail::check(email),
+                mail::check(mail::email("user@example.com")),
+            )
+        }
+    };
+
+    assert_eq!(
+        out,
+        (
+            true,
+            true,
+            false,
+            String::from("invalid"),
+            String::from("valid")
+        )
+    );
+}
+
+#[test]
+fn test_missing_custom_protocol() {
+    assert_vm_error!(
+        r#"pub fn main() { std::any::protocol_call(1, "validate", []) }"#,
+        VmErrorKind::MissingInstanceFunction { .. } => {}
+    );
+}
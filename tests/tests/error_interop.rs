@@ -0,0 +1,74 @@
+//! Tests for passing errors between native functions, scripts and the host.
+
+use rune_tests::*;
+use rune::{ContextError, FromValue, Module, Value};
+
+#[derive(Debug, thiserror::Error)]
+#[error("user {0} not found")]
+struct NotFound(i64);
+
+fn find_user(id: i64) -> rune::Result<String> {
+    if id == 1 {
+        Ok(String::from("john"))
+    } else {
+        Err(NotFound(id).into())
+    }
+}
+
+fn make_module() -> Result<Module, ContextError> {
+    let mut module = Module::with_crate("db");
+    module.function(&["find_user"], find_user)?;
+    Ok(module)
+}
+
+#[test]
+fn test_native_error_in_script() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        make_module()? => r#"
+        pub fn main() {
+            let found = db::find_user(1)?;
+            let error = match db::find_user(2) {
+                Ok(..) => panic("expected error"),
+                Err(error) => error.context("lookup failed"),
+            };
+            Ok((found, error.message(), error.chain(), error.root_cause(), `${error}`))
+        }
+        "#,
+    )?;
+
+    let output = vm.call(&["main"], ())?;
+    let output = Result::<(String, String, Vec<String>, String, String), Value>::from_value(output)?
+        .expect("expected ok");
+
+    assert_eq!(output.0, "john");
+    assert_eq!(output.1, "lookup failed");
+    assert_eq!(output.2, ["lookup failed", "user 2 not found"]);
+    assert_eq!(output.3, "user 2 not found");
+    assert_eq!(output.4, "lookup failed");
+    Ok(())
+}
+
+#[test]
+fn test_script_error_into_host() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        make_module()? => r#"
+        pub fn native() { db::find_user(3)?; Ok(()) }
+        pub fn created() { Err(std::error::Error::new("bad input")) }
+        pub fn string() { Err("oops") }
+        pub fn other() { Err(42) }
+        "#,
+    )?;
+
+    let error = Result::<(), rune::Error>::from_value(vm.call(&["native"], ())?)?.unwrap_err();
+    assert_eq!(error.downcast_ref::<NotFound>().map(|e| e.0), Some(3));
+
+    let error = Result::<(), Value>::from_value(vm.call(&["created"], ())?)?.unwrap_err();
+    assert_eq!(error.into_error()?.to_string(), "bad input");
+
+    let error = Result::<(), Value>::from_value(vm.call(&["string"], ())?)?.unwrap_err();
+    assert_eq!(error.into_error()?.to_string(), "oops");
+
+    let error = Result::<(), Value>::from_value(vm.call(&["other"], ())?)?.unwrap_err();
+    assert_eq!(error.into_error()?.to_string(), "42");
+    Ok(())
+}
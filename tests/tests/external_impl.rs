@@ -0,0 +1,82 @@
+//! Tests for script impl blocks which extend types declared elsewhere.
+
+use rune::{Any, ContextError, Module};
+use rune_tests::*;
+
+#[derive(Any)]
+struct Counter {
+    #[rune(get, set)]
+    value: i64,
+}
+
+fn make_module() -> Result<Module, ContextError> {
+    let mut module = Module::with_crate("native");
+    module.ty::<Counter>()?;
+    module.function(&["Counter", "new"], |value: i64| Counter { value })?;
+    Ok(module)
+}
+
+#[test]
+fn test_impl_native_std_type() {
+    let out: String = rune! {
+        impl String {
+            fn shout(self) {
+                let out = self.clone();
+                out.push_str("!");
+                out
+            }
+        }
+
+        pub fn main() {
+            String::from_str("hello").shout()
+        }
+    };
+
+    assert_eq!(out, "hello!");
+}
+
+#[test]
+fn test_impl_native_any_type() {
+    let out: i64 = rune_n! {
+        make_module().expect("failed to build module"),
+        (),
+        i64 =>
+        use native::Counter;
+
+        impl Counter {
+            fn add(self, n) {
+                self.value = self.value + n;
+                self
+            }
+        }
+
+        pub fn main() {
+            Counter::new(1).add(2).add(3).value
+        }
+    };
+
+    assert_eq!(out, 6);
+}
+
+#[test]
+fn test_impl_imported_struct() {
+    let out: i64 = rune! {
+        mod shapes {
+            pub struct Square { side }
+        }
+
+        use shapes::Square;
+
+        impl Square {
+            fn area(self) {
+                self.side * self.side
+            }
+        }
+
+        pub fn main() {
+            Square { side: 4 }.area()
+        }
+    };
+
+    assert_eq!(out, 16);
+}
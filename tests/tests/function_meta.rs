@@ -0,0 +1,96 @@
+//! Tests for functions registered through `#[rune::function]`.
+
+use rune::compile::Item;
+use rune::{Any, Context, ContextError, Hash, Module};
+use rune_tests::*;
+
+#[derive(Any)]
+struct Counter {
+    value: i64,
+}
+
+impl Counter {
+    /// Increment the counter by the given amount.
+    #[rune::function]
+    fn increment(&mut self, amount: i64) {
+        self.value += amount;
+    }
+
+    /// Get the value of the counter.
+    #[rune::function]
+    async fn get(&self) -> i64 {
+        self.value
+    }
+}
+
+/// Construct a new counter.
+#[rune::function]
+fn counter() -> Counter {
+    Counter { value: 0 }
+}
+
+/// Add two numbers.
+#[rune::function(keep)]
+fn add(a: i64, b: i64) -> i64 {
+    a + b
+}
+
+/// Reset the counter.
+#[rune::function(instance)]
+fn reset(this: &mut Counter) {
+    this.value = 0;
+}
+
+fn make_module() -> Result<Module, ContextError> {
+    let mut module = Module::with_crate("native");
+    module.ty::<Counter>()?;
+    module.function_meta(counter)?;
+    module.function_meta(add__meta)?;
+    module.function_meta(Counter::increment)?;
+    module.function_meta(Counter::get)?;
+    module.function_meta(reset)?;
+    Ok(module)
+}
+
+#[test]
+fn test_function_meta() {
+    let output: (i64, i64) = rune_n! {
+        make_module().expect("failed making native module"),
+        (),
+        (i64, i64) =>
+        pub async fn main() {
+            let counter = native::counter();
+            counter.increment(native::add(1, 2));
+            let before = counter.get().await;
+            counter.reset();
+            (before, counter.get().await)
+        }
+    };
+
+    assert_eq!(output, (3, 0));
+    assert_eq!(add(1, 2), 3);
+}
+
+#[test]
+fn test_function_meta_docs() -> rune::Result<()> {
+    let meta = add__meta();
+    assert_eq!(meta.name(), "add");
+    assert_eq!(meta.docs().args, Some(&["a", "b"][..]));
+    assert_eq!(meta.docs().lines, &[" Add two numbers."]);
+
+    let mut context = Context::new();
+    context.install(&make_module()?)?;
+
+    let docs = context
+        .docs(Hash::type_hash(&Item::with_crate_item("native", &["add"])))
+        .expect("missing docs");
+
+    assert_eq!(docs.lines, &[" Add two numbers."]);
+
+    let hash = Hash::instance_function(<Counter as rune::Any>::type_hash(), "increment");
+    let docs = context.docs(hash).expect("missing docs");
+
+    assert_eq!(docs.args, Some(&["self", "amount"][..]));
+    assert_eq!(docs.lines, &[" Increment the counter by the given amount."]);
+    Ok(())
+}
@@ -0,0 +1,85 @@
+//! Tests for consuming generators and streams with Rust combinators.
+
+use rune_tests::*;
+use futures_executor::block_on;
+use futures_util::{StreamExt, TryStreamExt};
+use rune::runtime::{Generator, Stream};
+use rune::{FromValue, Vm};
+
+#[test]
+fn test_generator_into_iter() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        r#"
+        fn numbers() {
+            yield 1;
+            yield 2;
+            yield 3;
+        }
+
+        fn failing() {
+            yield 1;
+            panic("boom");
+        }
+
+        pub fn main() { numbers() }
+        pub fn fail() { failing() }
+        "#,
+    )?;
+
+    let generator = Generator::<Vm>::from_value(vm.call(&["main"], ())?)?;
+
+    let values = generator
+        .into_iter()
+        .map(|value| i64::from_value(value?))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    assert_eq!(values, [1, 2, 3]);
+
+    let generator = Generator::<Vm>::from_value(vm.call(&["fail"], ())?)?;
+    let mut iter = generator.into_iter();
+
+    assert_eq!(i64::from_value(iter.next().unwrap()?)?, 1);
+    assert!(iter.next().unwrap().is_err());
+    assert!(iter.next().is_none());
+    Ok(())
+}
+
+#[test]
+fn test_stream_combinators() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        r#"
+        async fn numbers() {
+            for n in 0..5 {
+                yield n;
+            }
+        }
+
+        async fn failing() {
+            yield 1;
+            panic("boom");
+        }
+
+        pub fn main() { numbers() }
+        pub fn fail() { failing() }
+        "#,
+    )?;
+
+    let stream = Stream::<Vm>::from_value(vm.call(&["main"], ())?)?;
+
+    let values = block_on(
+        stream
+            .and_then(|value| async move { i64::from_value(value) })
+            .try_filter(|n| futures_util::future::ready(n % 2 == 0))
+            .try_collect::<Vec<_>>(),
+    )?;
+
+    assert_eq!(values, [0, 2, 4]);
+
+    let stream = Stream::<Vm>::from_value(vm.call(&["fail"], ())?)?;
+    let results = block_on(stream.collect::<Vec<_>>());
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(i64::from_value(results[0].as_ref().unwrap().clone())?, 1);
+    assert!(results[1].is_err());
+    Ok(())
+}
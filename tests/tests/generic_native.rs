@@ -116,3 +116,60 @@ fn test_generic_f64_set() {
         10.0
     );
 }
+
+#[derive(Any)]
+struct Pair<A, B> {
+    #[rune(get, set)]
+    first: A,
+    #[rune(get)]
+    second: B,
+}
+
+fn make_pair_module() -> Result<Module, ContextError> {
+    let mut module = Module::with_crate("native_crate");
+    module.ty::<Pair<i64, String>>()?;
+    module.ty::<Pair<f64, f64>>()?;
+    Ok(module)
+}
+
+#[test]
+fn test_generic_pair() {
+    let pair = Pair {
+        first: 1i64,
+        second: String::from("Hello"),
+    };
+
+    assert_eq!(
+        rune_n! {
+            make_pair_module().expect("failed making native module"),
+            (pair, ),
+            (i64, String) =>
+                pub fn main(v) { v.first = v.first + 1; (v.first, v.second) }
+        },
+        (2, String::from("Hello"))
+    );
+
+    let pair = Pair {
+        first: 1.5f64,
+        second: 2.5f64,
+    };
+
+    assert_eq!(
+        rune_n! {
+            make_pair_module().expect("failed making native module"),
+            (pair, ),
+            f64 =>
+                pub fn main(v) { v.first + v.second }
+        },
+        4.0
+    );
+}
+
+#[test]
+fn test_generic_names() {
+    assert_eq!(&*Pair::<i64, String>::full_name(), "Pair<int, String>");
+    assert_ne!(
+        <Pair<i64, String> as Any>::type_hash(),
+        <Pair<f64, f64> as Any>::type_hash()
+    );
+}
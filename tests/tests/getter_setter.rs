@@ -44,3 +44,53 @@ fn test_getter_setter() -> rune::Result<()> {
     assert!(matches!(output, Value::Unit));
     Ok(())
 }
+
+#[derive(Any, Debug, Default)]
+struct Stats {
+    #[rune(get, add_assign, index, copy)]
+    health: i64,
+    #[rune(index, name = "title")]
+    name: String,
+}
+
+#[test]
+fn test_index_and_assign_fields() -> rune::Result<()> {
+    let mut module = Module::new();
+    module.ty::<Stats>()?;
+
+    let mut context = rune_modules::default_context()?;
+    context.install(&module)?;
+
+    let mut sources = rune::sources! {
+        entry => {
+            pub fn main(stats) {
+                stats.health += 10;
+                stats["health"] = stats["health"] * 2;
+                stats["title"] = format!("{} the Brave", stats["title"]);
+
+                match stats["missing"] {
+                    _ => (),
+                }
+            }
+        }
+    };
+
+    let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+
+    let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+
+    let mut stats = Stats {
+        health: 1,
+        name: String::from("Bob"),
+    };
+
+    let error = vm.call(&["main"], (&mut stats,)).unwrap_err();
+
+    assert_eq!(stats.health, 22);
+    assert_eq!(stats.name, "Bob the Brave");
+    assert!(matches!(
+        error.into_unwound().0.into_kind(),
+        rune::runtime::VmErrorKind::MissingField { field, .. } if field == "missing"
+    ));
+    Ok(())
+}
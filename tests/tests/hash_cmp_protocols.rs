@@ -0,0 +1,245 @@
+use rune::runtime::Protocol;
+use rune::runtime::VmErrorKind::*;
+use rune::{Any, Module};
+use rune_tests::*;
+use std::cmp::Ordering;
+
+#[test]
+fn test_script_type_as_key() {
+    let out: (i64, bool, i64) = rune! {
+        use std::collections::{HashMap, HashSet};
+
+        struct Point { x, y, label }
+
+        impl Point {
+            fn hash(self) {
+                (self.x, self.y)
+            }
+        }
+
+        pub fn main() {
+            let map = HashMap::new();
+            map.insert(Point { x: 1, y: 2, label: "a" }, 10);
+            map.insert(Point { x: 1, y: 2, label: "b" }, 20);
+            map.insert(Point { x: 2, y: 1, label: "c" }, 30);
+
+            let set = HashSet::new();
+            set.insert(Point { x: 1, y: 2, label: "a" });
+
+            (map.len(), set.contains(Point { x: 1, y: 2, label: "z" }), map[Point { x: 1, y: 2, label: "z" }])
+        }
+    };
+
+    assert_eq!(out, (2, true, 20));
+}
+
+#[test]
+fn test_keys_are_original_values() {
+    let out: Vec<String> = rune! {
+        use std::collections::HashMap;
+
+        struct Named { name }
+
+        impl Named {
+            fn hash(self) {
+                self.name
+            }
+        }
+
+        pub fn main() {
+            let map = HashMap::new();
+            map.insert(Named { name: "a" }, ());
+            map.keys().map(|key| key.name).collect::<Vec>()
+        }
+    };
+
+    assert_eq!(out, vec!["a"]);
+}
+
+#[test]
+fn test_sort_with_cmp() {
+    let out: Vec<i64> = rune! {
+        struct Version { major, minor }
+
+        impl Version {
+            fn cmp(self, other) {
+                self.major.cmp(other.major).then(self.minor.cmp(other.minor))
+            }
+        }
+
+        pub fn main() {
+            let versions = [
+                Version { major: 1, minor: 2 },
+                Version { major: 0, minor: 9 },
+                Version { major: 1, minor: 0 },
+            ];
+
+            versions.sort();
+            versions.iter().map(|v| v.major * 10 + v.minor).collect::<Vec>()
+        }
+    };
+
+    assert_eq!(out, vec![9, 10, 12]);
+}
+
+#[test]
+fn test_sort_by_key() {
+    let out: (Vec<String>, Vec<i64>) = rune! {
+        pub fn main() {
+            let words = ["ccc", "a", "bb"];
+            words.sort_by_key(|w| w.len());
+
+            let numbers = [3, 1, 2];
+            numbers.sort_by_key(|n| -n);
+            (words, numbers)
+        }
+    };
+
+    assert_eq!(out.0, vec!["a", "bb", "ccc"]);
+    assert_eq!(out.1, vec![3, 2, 1]);
+}
+
+#[test]
+fn test_sort_builtin_values() {
+    let out: Vec<(i64, String)> = rune! {
+        pub fn main() {
+            let values = [(2, "b"), (1, "z"), (2, "a")];
+            values.sort();
+            values
+        }
+    };
+
+    assert_eq!(
+        out,
+        vec![(1, "z".into()), (2, "a".into()), (2, "b".into())]
+    );
+}
+
+#[test]
+fn test_native_type_protocols() {
+    #[derive(Any)]
+    struct Id {
+        value: i64,
+    }
+
+    impl Id {
+        fn new(value: i64) -> Self {
+            Self { value }
+        }
+
+        fn hash(&self) -> i64 {
+            self.value
+        }
+
+        fn cmp(&self, other: &Self) -> Ordering {
+            other.value.cmp(&self.value)
+        }
+    }
+
+    let mut module = Module::new();
+    module.ty::<Id>().unwrap();
+    module.function(&["Id", "new"], Id::new).unwrap();
+    module.inst_fn(Protocol::HASH, Id::hash).unwrap();
+    module.inst_fn(Protocol::CMP, Id::cmp).unwrap();
+    module.field_fn(Protocol::GET, "value", |id: &Id| id.value).unwrap();
+
+    let out: (bool, Vec<i64>) = rune_n! {
+        module,
+        (),
+        (bool, Vec<i64>) => pub fn main() {
+            use std::collections::HashSet;
+
+            let set = HashSet::new();
+            set.insert(Id::new(1));
+
+            let ids = [Id::new(1), Id::new(3), Id::new(2)];
+            ids.sort_by_key(|id| id);
+            (set.contains(Id::new(1)), ids.iter().map(|id| id.value).collect::<Vec>())
+        }
+    };
+
+    assert_eq!(out, (true, vec![3, 2, 1]));
+}
+
+#[test]
+fn test_unsupported() {
+    assert_vm_error!(
+        r#"
+        use std::collections::HashSet;
+        struct Point { x }
+
+        pub fn main() {
+            let set = HashSet::new();
+            set.insert(Point { x: 1 });
+        }
+        "#,
+        KeyNotSupported { .. } => {}
+    );
+
+    assert_vm_error!(
+        r#"
+        struct Point { x }
+
+        pub fn main() {
+            let points = [Point { x: 2 }, Point { x: 1 }];
+            points.sort();
+        }
+        "#,
+        UnsupportedBinaryOperation { op: "cmp", .. } => {}
+    );
+}
+
+#[test]
+fn test_sort_by_is_stable_and_reentrant() {
+    let out: Vec<(i64, String)> = rune! {
+        pub fn main() {
+            let key = |v| {
+                // NB: sort from inside the comparator to exercise re-entrant
+                // calls back into the virtual machine.
+                let digits = [v.0 % 10, v.0 / 10];
+                digits.sort_by(|a, b| a.cmp(b));
+                digits[0]
+            };
+
+            let values = [(21, "a"), (3, "b"), (12, "c"), (1, "d"), (30, "e")];
+            values.sort_by(|a, b| key(a).cmp(key(b)));
+            values
+        }
+    };
+
+    let expected = [(3, "b"), (1, "d"), (30, "e"), (21, "a"), (12, "c")];
+    let expected = expected
+        .iter()
+        .map(|(n, s)| (*n, s.to_string()))
+        .collect::<Vec<_>>();
+    assert_eq!(out, expected);
+}
+
+#[test]
+fn test_sort_by_error() {
+    assert_vm_error!(
+        r#"pub fn main() { let v = [2, 1]; v.sort_by(|a, b| panic("boom")); }"#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "boom");
+        }
+    );
+}
+
+#[test]
+fn test_min_by_max_by() {
+    type Pair = Option<(i64, i64)>;
+
+    let out: (Pair, Pair, Option<i64>) = rune! {
+        pub fn main() {
+            let values = [(1, 0), (3, 1), (1, 2), (3, 3)];
+
+            (
+                values.iter().min_by(|a, b| a.0.cmp(b.0)),
+                values.iter().max_by(|a, b| a.0.cmp(b.0)),
+                [].iter().max_by(|a, b| a.cmp(b)),
+            )
+        }
+    };
+
+    assert_eq!(out, (Some((1, 0)), Some((3, 3)), None));
+}
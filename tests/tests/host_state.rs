@@ -0,0 +1,64 @@
+//! Tests for native functions with access to shared host state.
+
+use futures_executor::block_on;
+use rune::{Context, FromValue, Module, Source, Sources, Vm};
+use std::sync::{Arc, Mutex};
+
+#[derive(Default)]
+struct Inventory {
+    items: Vec<(String, i64)>,
+}
+
+#[test]
+fn test_function_with_state() -> rune::Result<()> {
+    let inventory = Arc::new(Mutex::new(Inventory::default()));
+
+    let mut module = Module::with_item(&["inventory"]);
+
+    module.function_with_state(&["len"], inventory.clone(), |inventory: &Mutex<Inventory>| {
+        inventory.lock().unwrap().items.len()
+    })?;
+
+    module.function_with_state(
+        &["add"],
+        inventory.clone(),
+        |inventory: &Mutex<Inventory>, name: String, count: i64| {
+            inventory.lock().unwrap().items.push((name, count));
+        },
+    )?;
+
+    module.async_function_with_state(
+        &["total"],
+        inventory.clone(),
+        |inventory: Arc<Mutex<Inventory>>, extra: i64| async move {
+            let inventory = inventory.lock().unwrap();
+            inventory.items.iter().map(|(_, n)| n).sum::<i64>() + extra
+        },
+    )?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(&module)?;
+
+    let mut sources = Sources::new();
+    sources.insert(Source::new(
+        "main",
+        r#"
+        pub async fn main() {
+            inventory::add("apple", 3);
+            inventory::add("pear", 4);
+            (inventory::len(), inventory::total(1).await)
+        }
+        "#,
+    ));
+
+    let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+
+    let output = <(usize, i64)>::from_value(block_on(vm.async_call(&["main"], ()))?)?;
+    assert_eq!(output, (2, 8));
+
+    let inventory = inventory.lock().unwrap();
+    assert_eq!(inventory.items[0], (String::from("apple"), 3));
+    assert_eq!(inventory.items[1], (String::from("pear"), 4));
+    Ok(())
+}
@@ -163,3 +163,111 @@ fn test_flat_map() {
 
     assert_eq!(actual, expected);
 }
+
+#[test]
+fn test_zip_and_step_by() {
+    let actual: Vec<(i64, String)> = rune! {
+        use std::iter::range;
+
+        pub fn main() {
+            range(0, 10).step_by(3).zip(["a", "b", "c"]).collect::<Vec>()
+        }
+    };
+
+    let expected = (0..10)
+        .step_by(3)
+        .zip(["a", "b", "c"])
+        .map(|(n, s)| (n, s.to_owned()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(actual, expected);
+
+    assert_vm_error!(
+        r#"pub fn main() { [1, 2].iter().step_by(0) }"#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "step must be greater than zero");
+        }
+    );
+}
+
+#[test]
+fn test_chunks_and_windows() {
+    let actual: (Vec<Vec<i64>>, Vec<Vec<i64>>) = rune! {
+        pub fn main() {
+            let values = [1, 2, 3, 4, 5];
+            (values.iter().chunks(2).collect::<Vec>(), values.iter().windows(3).collect::<Vec>())
+        }
+    };
+
+    let values = [1, 2, 3, 4, 5];
+    let chunks = values.chunks(2).map(<[i64]>::to_vec).collect::<Vec<_>>();
+    let windows = values.windows(3).map(<[i64]>::to_vec).collect::<Vec<_>>();
+    assert_eq!(actual, (chunks, windows));
+
+    let actual: Vec<Vec<i64>> = rune! {
+        pub fn main() {
+            [1, 2].iter().windows(3).collect::<Vec>()
+        }
+    };
+
+    assert!(actual.is_empty());
+}
+
+#[test]
+fn test_scan_take_while_skip_while() {
+    let actual: (Vec<i64>, Vec<i64>, Vec<i64>) = rune! {
+        use std::iter::range;
+
+        pub fn main() {
+            (
+                range(1, 6).scan(0, |acc, n| acc + n).collect::<Vec>(),
+                [1, 2, 5, 1].iter().take_while(|n| n < 3).collect::<Vec>(),
+                [1, 2, 5, 1].iter().skip_while(|n| n < 3).collect::<Vec>(),
+            )
+        }
+    };
+
+    assert_eq!(actual, (vec![1, 3, 6, 10, 15], vec![1, 2], vec![5, 1]));
+}
+
+#[test]
+fn test_dedup_group_by_partition() {
+    type Groups = Vec<(bool, Vec<i64>)>;
+
+    let actual: (Vec<i64>, Groups, (Vec<i64>, Vec<i64>)) = rune! {
+        pub fn main() {
+            let values = [1, 1, 3, 2, 2, 2, 5];
+
+            (
+                values.iter().dedup().collect::<Vec>(),
+                values.iter().group_by(|n| n % 2 == 0).collect::<Vec>(),
+                values.iter().partition(|n| n > 1),
+            )
+        }
+    };
+
+    assert_eq!(
+        actual,
+        (
+            vec![1, 3, 2, 5],
+            vec![(false, vec![1, 1, 3]), (true, vec![2, 2, 2]), (false, vec![5])],
+            (vec![3, 2, 2, 2, 5], vec![1, 1]),
+        )
+    );
+}
+
+#[test]
+fn test_fold_and_any() {
+    let actual: (i64, bool, bool) = rune! {
+        pub fn main() {
+            let values = [1, 2, 3];
+            (
+                values.iter().fold(10, |acc, n| acc * n),
+                values.iter().any(|n| n == 2),
+                values.iter().any(|n| n > 3),
+            )
+        }
+    };
+
+    assert_eq!(actual, (60, true, false));
+}
@@ -0,0 +1,99 @@
+//! Tests for modules constructed through `#[rune::module]`.
+
+use rune::compile::Item;
+use rune::{Any, Context, ContextError, Hash};
+use rune_tests::*;
+
+#[rune::module(::geometry)]
+mod geometry {
+    /// The number of sides of a square.
+    pub const SQUARE: i64 = 4;
+
+    /// Calculate the area of a rectangle.
+    pub fn area(width: i64, height: i64) -> i64 {
+        width * height
+    }
+
+    /// Wait before calculating the perimeter of a rectangle.
+    pub async fn perimeter(width: i64, height: i64) -> i64 {
+        2 * (width + height)
+    }
+
+    #[rune(skip)]
+    #[allow(unused)]
+    pub fn skipped() {}
+
+    #[allow(unused)]
+    fn private() {}
+}
+
+#[derive(Any)]
+struct Vector {
+    x: i64,
+    y: i64,
+}
+
+#[rune::module(::geometry)]
+impl Vector {
+    /// The number of dimensions of a vector.
+    pub const DIMENSIONS: i64 = 2;
+
+    /// Construct a new vector.
+    pub fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    /// Get the length of the vector, squared.
+    pub fn length_squared(&self) -> i64 {
+        self.x * self.x + self.y * self.y
+    }
+
+    /// Scale the vector.
+    pub fn scale(&mut self, factor: i64) {
+        self.x *= factor;
+        self.y *= factor;
+    }
+}
+
+fn context() -> Result<Context, ContextError> {
+    let mut context = rune_modules::default_context()?;
+    context.install(&geometry::module()?)?;
+    context.install(&Vector::module()?)?;
+    Ok(context)
+}
+
+#[test]
+fn test_module_attribute() -> rune::Result<()> {
+    let context = context()?;
+
+    let output: (i64, i64, i64) = run(
+        &context,
+        r#"
+        pub async fn main() {
+            use geometry::{area, perimeter, Vector};
+
+            let v = Vector::new(1, 2);
+            v.scale(geometry::SQUARE);
+
+            let a = area(2, Vector::DIMENSIONS);
+            (a, perimeter(1, 2).await, v.length_squared())
+        }
+        "#,
+        &["main"],
+        (),
+    )?;
+
+    assert_eq!(output, (4, 6, 80));
+
+    let docs = context
+        .docs(Hash::type_hash(&Item::with_crate_item("geometry", &["area"])))
+        .expect("missing docs");
+
+    assert_eq!(docs.args, Some(&["width", "height"][..]));
+    assert_eq!(docs.lines, &[" Calculate the area of a rectangle."]);
+
+    assert!(context
+        .iter_functions()
+        .all(|(_, signature)| !signature.to_string().contains("skipped")));
+    Ok(())
+}
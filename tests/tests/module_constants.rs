@@ -0,0 +1,55 @@
+//! Tests for constants registered in native modules.
+
+use rune::runtime::Object;
+use rune::{Context, ContextError, Module, Value};
+use rune_tests::*;
+
+fn make_module() -> Result<Module, ContextError> {
+    let mut module = Module::with_item(&["math"]);
+    module.constant(&["PI"], 3.5f64)?;
+    module.constant(&["NAME"], String::from("Hello"))?;
+    module.constant(&["PRIMES"], vec![2i64, 3, 5])?;
+
+    let mut object = Object::new();
+    object.insert(String::from("answer"), Value::from(42i64));
+    module.constant(&["OBJECT"], object)?;
+    Ok(module)
+}
+
+#[test]
+fn test_module_constants() -> rune::Result<()> {
+    let mut context = rune_modules::default_context()?;
+    context.install(&make_module()?)?;
+
+    let output: (f64, String, i64, i64) = run(
+        &context,
+        r#"
+        const TWO_PI = math::PI * 2.0;
+        const GREETING = `${math::NAME} World`;
+
+        pub fn main() {
+            (TWO_PI, GREETING, math::PRIMES[2], math::OBJECT.answer)
+        }
+        "#,
+        &["main"],
+        (),
+    )?;
+
+    assert_eq!(output, (7.0, String::from("Hello World"), 5, 42));
+    Ok(())
+}
+
+#[test]
+fn test_conflicting_constants() -> rune::Result<()> {
+    let mut context = Context::new();
+    context.install(&make_module()?)?;
+
+    let mut module = Module::with_item(&["math"]);
+    module.constant(&["PI"], 3.0f64)?;
+
+    assert!(matches!(
+        context.install(&module),
+        Err(ContextError::ConflictingConstantName { .. })
+    ));
+    Ok(())
+}
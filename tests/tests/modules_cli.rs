@@ -0,0 +1,174 @@
+//! Tests for the `cli` module.
+
+use rune_tests::*;
+use rune::runtime::VmErrorKind;
+use rune::{FromValue, Vm};
+use rune_modules::env::Args;
+
+const COMMAND: &str = r#"
+fn command() {
+    cli::Command::new("greet")
+        .about("Greet some people")
+        .flag("loud", "Greet loudly").short('l')
+        .flag("verbose", "More output").short('v').multiple()
+        .option("greeting", "The greeting to use").short('g').default_value("Hello")
+        .option("output-file", "Where to write")
+        .option("tag", "Tags to add").multiple()
+        .positional("names", "Who to greet").required().multiple()
+"#;
+
+fn make_command_vm(body: &str) -> rune::Result<Vm> {
+    rune_vm_with!(rune_modules::cli::module(true)? => &format!("{}}}\n{}", COMMAND, body))
+}
+
+#[test]
+fn test_cli_parse() -> rune::Result<()> {
+    let mut vm = make_command_vm(
+        r#"
+        pub fn main(args) {
+            let args = command().parse(args)?;
+            (args.loud, args.verbose, args.greeting, args.output_file, args.tag, args.names)
+        }
+        "#,
+    )?;
+
+    type Output = (bool, i64, String, Option<String>, Vec<String>, Vec<String>);
+
+    let args = vec!["-vvl", "--tag=a", "ann", "-gHi", "--tag", "b", "--", "-bob"];
+    let output = Output::from_value(vm.call(&["main"], (args,))?)?;
+
+    assert_eq!(
+        output,
+        (
+            true,
+            2,
+            String::from("Hi"),
+            None,
+            vec![String::from("a"), String::from("b")],
+            vec![String::from("ann"), String::from("-bob")],
+        )
+    );
+
+    let args = vec!["--output-file", "out.txt", "ann"];
+    let output = Output::from_value(vm.call(&["main"], (args,))?)?;
+
+    assert_eq!(
+        output,
+        (
+            false,
+            0,
+            String::from("Hello"),
+            Some(String::from("out.txt")),
+            vec![],
+            vec![String::from("ann")],
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_errors_and_help() -> rune::Result<()> {
+    let mut vm = make_command_vm(
+        r#"
+        pub fn main(args) {
+            match command().parse(args) {
+                Ok(..) => (false, "ok"),
+                Err(error) => (error.is_help(), `${error}`),
+            }
+        }
+        "#,
+    )?;
+
+    let mut call = |args: Vec<&str>| -> rune::Result<(bool, String)> {
+        Ok(<(bool, String)>::from_value(vm.call(&["main"], (args,))?)?)
+    };
+
+    let (is_help, help) = call(vec!["ann", "--help"])?;
+    assert!(is_help);
+    assert_eq!(
+        help,
+        "Greet some people\n\
+         \n\
+         Usage: greet [OPTIONS] <names>...\n\
+         \n\
+         Arguments:\n\
+         \x20 <names>...                       Who to greet\n\
+         \n\
+         Options:\n\
+         \x20 -l, --loud                       Greet loudly\n\
+         \x20 -v, --verbose                    More output\n\
+         \x20 -g, --greeting <greeting>        The greeting to use [default: Hello]\n\
+         \x20     --output-file <output-file>  Where to write\n\
+         \x20     --tag <tag>...               Tags to add\n\
+         \x20 -h, --help                       Print help\n"
+    );
+
+    let error = |message: &str| {
+        (
+            false,
+            format!("error: {}\n\nFor more information, try `--help`", message),
+        )
+    };
+
+    assert_eq!(call(vec![])?, error("missing required argument `<names>`"));
+    assert_eq!(call(vec!["ann", "-x"])?, error("unexpected argument `-x`"));
+    assert_eq!(call(vec!["--nope"])?, error("unexpected argument `--nope`"));
+    assert_eq!(
+        call(vec!["ann", "--greeting"])?,
+        error("option `--greeting` requires a value")
+    );
+    assert_eq!(
+        call(vec!["ann", "--loud=yes"])?,
+        error("flag `--loud` doesn't take a value")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_cli_parse_env() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        rune_modules::cli::module(true)? => r#"
+        pub fn main() {
+            let args = cli::Command::new("copy")
+                .positional("from", "Source")
+                .positional("to", "Destination").default_value("out")
+                .parse_env()?;
+
+            (args.from, args.to)
+        }
+        "#,
+    )?;
+
+    vm.extensions_mut().insert(Args::new(["in"]));
+
+    let output = <(Option<String>, String)>::from_value(vm.call(&["main"], ())?)?;
+    assert_eq!(output, (Some(String::from("in")), String::from("out")));
+
+    vm.extensions_mut().insert(Args::new(["a", "b", "c"]));
+    let output = <Result<(Option<String>, String), String>>::from_value(vm.call(&["main"], ())?);
+    assert!(output.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_cli_invalid_declaration() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        rune_modules::cli::module(true)? => r#"
+        pub fn main() {
+            cli::Command::new("x").flag("a", "").short('a').flag("b", "").short('a')
+        }
+        "#,
+    )?;
+
+    let error = vm.call(&["main"], ()).unwrap_err();
+
+    match error.into_unwound().0.into_kind() {
+        VmErrorKind::Panic { reason } => {
+            assert_eq!(reason.to_string(), "short name `-a` is already defined")
+        }
+        actual => panic!("expected panic but got {:?}", actual),
+    }
+
+    Ok(())
+}
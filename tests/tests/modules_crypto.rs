@@ -0,0 +1,57 @@
+use rune::runtime::Bytes;
+use rune_tests::*;
+
+fn hex(bytes: &Bytes) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[test]
+fn test_crypto_digests() {
+    let (sha1, sha256, sha512): (Bytes, Bytes, Bytes) = rune! {
+        pub fn main() {
+            (crypto::sha1("abc"), crypto::sha256(b"abc"), crypto::sha512("abc"))
+        }
+    };
+
+    assert_eq!(hex(&sha1), "a9993e364706816aba3e25717850c26c9cd0d89d");
+    assert_eq!(
+        hex(&sha256),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    assert!(hex(&sha512).starts_with("ddaf35a193617aba"));
+    assert_eq!(sha512.len(), 64);
+}
+
+#[test]
+fn test_crypto_hmac() {
+    // Test case 2 from RFC 4231.
+    let (sha256, sha512): (Bytes, Bytes) = rune! {
+        pub fn main() {
+            let data = "what do ya want for nothing?";
+            (crypto::hmac_sha256("Jefe", data), crypto::hmac_sha512(b"Jefe", data))
+        }
+    };
+
+    assert_eq!(
+        hex(&sha256),
+        "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    );
+    assert!(hex(&sha512).starts_with("164b7a7bfcf819e2"));
+}
+
+#[test]
+fn test_crypto_constant_time_eq() {
+    let out: (bool, bool, bool, bool) = rune! {
+        pub fn main() {
+            let tag = crypto::hmac_sha1("key", "message");
+            (
+                crypto::constant_time_eq(tag, crypto::hmac_sha1(b"key", b"message")),
+                crypto::constant_time_eq(tag, crypto::hmac_sha1("key", "massage")),
+                crypto::constant_time_eq("abc", b"abc"),
+                crypto::constant_time_eq("abc", "abcd"),
+            )
+        }
+    };
+
+    assert_eq!(out, (true, false, true, false));
+}
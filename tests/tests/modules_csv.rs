@@ -0,0 +1,69 @@
+use rune::runtime::VmErrorKind::*;
+use rune_tests::*;
+
+#[test]
+fn test_csv_rows() {
+    let out: Vec<Vec<String>> = rune_s! { r#"
+        pub fn main() {
+            let data = "a,b\r\n\n\"x, \"\"y\"\"\",\"multi\nline\"\n,\n";
+            csv::Reader::new(data).rows().collect::<Vec>()
+        }
+    "# };
+
+    assert_eq!(
+        out,
+        [
+            vec!["a", "b"],
+            vec!["x, \"y\"", "multi\nline"],
+            vec!["", ""],
+        ]
+    );
+}
+
+#[test]
+fn test_csv_records_and_delimiter() {
+    let out: (String, i64, usize) = rune_s! { r#"
+        pub fn main() {
+            let records = csv::Reader::new("name;age\nAlice;42\nBob;7").delimiter(';').records().collect::<Vec>();
+            (records[0].name, std::string::parse_int(records[0].age)?, records.len())
+        }
+    "# };
+
+    assert_eq!(out, (String::from("Alice"), 42, 2));
+}
+
+#[test]
+fn test_csv_writer() {
+    let out: String = rune_s! { r#"
+        pub fn main() {
+            let writer = csv::Writer::new();
+            writer.write(["plain", "with,comma", "with \"quote\"", 1, 2.5, true, ()]);
+
+            let tabs = csv::Writer::new().delimiter('\t');
+            tabs.write(["a,b", "c\td"]);
+            `${writer.into_string()}${tabs.into_string()}`
+        }
+    "# };
+
+    assert_eq!(
+        out,
+        "plain,\"with,comma\",\"with \"\"quote\"\"\",1,2.5,true,\na,b\t\"c\td\"\n"
+    );
+}
+
+#[test]
+fn test_csv_malformed() {
+    assert_vm_error!(
+        r#"pub fn main() { csv::Reader::new("a\n\"b\"c").rows().collect::<Vec>() }"#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "unexpected `c` after a quoted field on line 2");
+        }
+    );
+
+    assert_vm_error!(
+        r#"pub fn main() { csv::Reader::new("a,b\n1,2\n3").records().collect::<Vec>() }"#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "row on line 3 has 1 fields, but the header has 2");
+        }
+    );
+}
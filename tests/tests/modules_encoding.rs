@@ -0,0 +1,67 @@
+use rune::runtime::Bytes;
+use rune_tests::*;
+
+#[test]
+fn test_encoding_base64() {
+    let out: (String, String, String, String) = rune! {
+        pub fn main() {
+            let data = b"\xfb\xff\xfe?";
+            (
+                encoding::base64::encode(data),
+                encoding::base64::encode_url(data),
+                encoding::base64::encode("hello"),
+                encoding::base64::encode_url("hello"),
+            )
+        }
+    };
+
+    assert_eq!(
+        out,
+        (
+            String::from("+//+Pw=="),
+            String::from("-__-Pw"),
+            String::from("aGVsbG8="),
+            String::from("aGVsbG8"),
+        )
+    );
+
+    let out: (Bytes, Bytes, Bytes, bool) = rune! {
+        pub fn main() {
+            (
+                encoding::base64::decode("+//+Pw==")?,
+                encoding::base64::decode("+//+Pw")?,
+                encoding::base64::decode_url("-__-Pw==")?,
+                encoding::base64::decode("-__-Pw").is_err(),
+            )
+        }
+    };
+
+    let expected = Bytes::from_vec(vec![0xfb, 0xff, 0xfe, b'?']);
+    assert_eq!(out, (expected.clone(), expected.clone(), expected, true));
+}
+
+#[test]
+fn test_encoding_hex() {
+    let out: (String, String, Bytes, bool, bool) = rune! {
+        pub fn main() {
+            (
+                encoding::hex::encode(b"\x00\x7f\xab"),
+                encoding::hex::encode("Hi"),
+                encoding::hex::decode("007fAB")?,
+                encoding::hex::decode("abc").is_err(),
+                encoding::hex::decode("zz").is_err(),
+            )
+        }
+    };
+
+    assert_eq!(
+        out,
+        (
+            String::from("007fab"),
+            String::from("4869"),
+            Bytes::from_vec(vec![0x00, 0x7f, 0xab]),
+            true,
+            true,
+        )
+    );
+}
@@ -0,0 +1,50 @@
+//! Tests for the `env` module.
+
+use rune_tests::*;
+use rune::FromValue;
+use rune_modules::env::Args;
+
+#[test]
+fn test_env_vars() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        rune_modules::env::module(true)? => r#"
+        pub fn main() {
+            let key = "RUNE_TEST_ENV_VARS";
+            let before = env::var(key);
+            env::set_var(key, "42");
+            let after = env::var(key);
+            let listed = env::vars()[key];
+            env::remove_var(key);
+            (before, after, listed, env::var(key))
+        }
+        "#,
+    )?;
+
+    let output = <(Option<String>, Option<String>, String, Option<String>)>::from_value(
+        vm.call(&["main"], ())?,
+    )?;
+
+    assert_eq!(output, (None, Some(String::from("42")), String::from("42"), None));
+    Ok(())
+}
+
+#[test]
+fn test_env_args_and_current_dir() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        rune_modules::env::module(true)? => r#"
+        pub fn main() {
+            (env::args(), env::current_dir()?)
+        }
+        "#,
+    )?;
+
+    let (args, _) = <(Vec<String>, String)>::from_value(vm.call(&["main"], ())?)?;
+    assert!(args.is_empty());
+
+    vm.extensions_mut().insert(Args::new(["a", "b c"]));
+
+    let (args, dir) = <(Vec<String>, String)>::from_value(vm.call(&["main"], ())?)?;
+    assert_eq!(args, ["a", "b c"]);
+    assert_eq!(dir, std::env::current_dir()?.to_string_lossy());
+    Ok(())
+}
@@ -0,0 +1,96 @@
+//! Tests for the `fs` module.
+
+use rune_tests::*;
+use rune::runtime::{Capabilities, Capability};
+use rune::{FromValue, Source, Sources};
+use std::path::PathBuf;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rune-fs-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn test_blocking_fs() -> rune::Result<()> {
+    let dir = temp_dir("blocking");
+
+    let mut vm = rune_vm_with!(
+        rune_modules::fs::module(true)? => r#"
+        pub fn main(dir) {
+            let nested = `${dir}/a/b`;
+            fs::blocking::create_dir_all(nested)?;
+            fs::blocking::write(`${nested}/file.txt`, "hello")?;
+            fs::blocking::append(`${nested}/file.txt`, " world")?;
+            fs::blocking::append(`${nested}/other.txt`, "new")?;
+
+            let content = fs::blocking::read_to_string(`${nested}/file.txt`)?;
+            let meta = fs::blocking::metadata(`${nested}/file.txt`)?;
+            let entries = fs::blocking::read_dir(nested)?.len();
+            let is_dir = fs::blocking::metadata(nested)?.is_dir();
+
+            fs::blocking::remove(`${dir}/a`)?;
+            let missing = fs::blocking::metadata(nested).is_err();
+
+            Ok((content, meta.is_file(), meta.len(), entries, is_dir, missing))
+        }
+        "#,
+    )?;
+
+    let output = vm.call(&["main"], (dir.to_string_lossy().into_owned(),))?;
+    let output = Result::<(String, bool, u64, usize, bool, bool), rune::Value>::from_value(output)?
+        .expect("expected ok");
+
+    assert_eq!(
+        output,
+        (String::from("hello world"), true, 11, 2, true, true)
+    );
+
+    let _ = std::fs::remove_dir_all(&dir);
+    Ok(())
+}
+
+#[test]
+fn test_fs_io_error() -> rune::Result<()> {
+    let dir = temp_dir("error");
+
+    let mut vm = rune_vm_with!(
+        rune_modules::fs::module(true)? => r#"
+        pub fn main(path) {
+            match fs::blocking::read_to_string(path) {
+                Ok(..) => None,
+                Err(error) => Some(`${error}`),
+            }
+        }
+        "#,
+    )?;
+
+    let path = dir.join("missing.txt").to_string_lossy().into_owned();
+    let output = Option::<String>::from_value(vm.call(&["main"], (path,))?)?;
+    assert!(output.is_some());
+    Ok(())
+}
+
+#[test]
+fn test_fs_requires_capability() -> rune::Result<()> {
+    let module = rune_modules::fs::module(true)?;
+    assert!(module.capabilities().contains(Capability::Filesystem));
+
+    let context = rune_modules::with_capabilities(
+        true,
+        Capabilities::all().without(Capability::Filesystem),
+    )?;
+
+    let mut sources = Sources::new();
+    sources.insert(Source::new(
+        "main",
+        r#"pub fn main() { fs::blocking::read_to_string("x") }"#,
+    ));
+
+    assert!(rune::prepare(&mut sources)
+        .with_context(&context)
+        .build()
+        .is_err());
+
+    Ok(())
+}
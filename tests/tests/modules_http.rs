@@ -0,0 +1,125 @@
+//! Tests for the `http` module, against a local server.
+
+use rune_tests::*;
+use rune::{FromValue, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+/// Serve a single request with the given response, returning the raw
+/// request which was received.
+async fn serve_once(response: &'static str) -> std::io::Result<(String, JoinHandle<String>)> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("http://{}", listener.local_addr()?);
+
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        let mut buf = [0; 1024];
+
+        loop {
+            let n = stream.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..n]);
+            let text = String::from_utf8_lossy(&request).into_owned();
+
+            if let Some(end) = text.find("\r\n\r\n") {
+                let length = text[..end]
+                    .lines()
+                    .find_map(|line| line.to_lowercase().strip_prefix("content-length: ").map(str::to_owned))
+                    .map(|length| length.parse::<usize>().unwrap())
+                    .unwrap_or_default();
+
+                if request.len() >= end + 4 + length {
+                    break;
+                }
+            }
+        }
+
+        stream.write_all(response.as_bytes()).await.unwrap();
+        String::from_utf8(request).unwrap()
+    });
+
+    Ok((url, handle))
+}
+
+#[tokio::test]
+async fn test_http_request_builder() -> rune::Result<()> {
+    let (url, handle) = serve_once(
+        "HTTP/1.1 201 Created\r\ncontent-length: 2\r\nx-answer: 42\r\nconnection: close\r\n\r\nok",
+    )
+    .await?;
+
+    let mut vm = rune_vm_with!(
+        rune_modules::http::module(true)? => r#"
+        pub async fn main(url) {
+            let client = http::Client::new();
+
+            let response = client.put(`${url}/items`).await?
+                .header("x-token", "secret")
+                .query(#{"page": 2, "q": "a b"})
+                .json(#{"name": "rune"})
+                .timeout(5)
+                .send().await?;
+
+            let status = response.status();
+            let header = response.header("x-answer");
+            let headers = response.headers();
+            let body = response.text().await?;
+
+            Ok((status.as_u16(), status.is_success(), header, headers["content-length"], body))
+        }
+        "#,
+    )?;
+
+    let output = vm.async_call(&["main"], (url,)).await?;
+    let output =
+        Result::<(u16, bool, Option<String>, String, String), Value>::from_value(output)?
+            .expect("expected ok");
+
+    assert_eq!(
+        output,
+        (
+            201,
+            true,
+            Some(String::from("42")),
+            String::from("2"),
+            String::from("ok")
+        )
+    );
+
+    let request = handle.await?;
+    assert!(request.starts_with("PUT /items?page=2&q=a+b HTTP/1.1\r\n"));
+    assert!(request.contains("x-token: secret\r\n"));
+    assert!(request.contains("content-type: application/json\r\n"));
+    assert!(request.ends_with("{\"name\":\"rune\"}"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_http_form_and_delete() -> rune::Result<()> {
+    let (url, handle) =
+        serve_once("HTTP/1.1 404 Not Found\r\ncontent-length: 0\r\nconnection: close\r\n\r\n")
+            .await?;
+
+    let mut vm = rune_vm_with!(
+        rune_modules::http::module(true)? => r#"
+        pub async fn main(url) {
+            let client = http::Client::new();
+            let response = client.delete(url).await?.form(#{"id": 7}).send().await?;
+            let status = response.status();
+            Ok((status.as_u16(), status.is_client_error(), `${status}`))
+        }
+        "#,
+    )?;
+
+    let output = vm.async_call(&["main"], (url,)).await?;
+    let output =
+        Result::<(u16, bool, String), Value>::from_value(output)?.expect("expected ok");
+    assert_eq!(output, (404, true, String::from("404 Not Found")));
+
+    let request = handle.await?;
+    assert!(request.starts_with("DELETE / HTTP/1.1\r\n"));
+    assert!(request.contains("content-type: application/x-www-form-urlencoded\r\n"));
+    assert!(request.ends_with("id=7"));
+    Ok(())
+}
@@ -0,0 +1,99 @@
+//! Tests for the `http::server` module, driven by the host.
+
+use rune_tests::*;
+use rune::FromValue;
+use rune_modules::http_server::Server;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::LocalSet;
+
+/// Send a raw request and return the raw response.
+async fn request(addr: &str, request: &str) -> std::io::Result<String> {
+    let mut stream = TcpStream::connect(addr).await?;
+    stream.write_all(request.as_bytes()).await?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await?;
+    Ok(String::from_utf8_lossy(&response).into_owned())
+}
+
+#[tokio::test]
+async fn test_http_server_routes() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        rune_modules::http::module(true)?, rune_modules::http_server::module(true)? => r#"
+        use http::server::{Response, Server};
+
+        pub fn main() {
+            let server = Server::new();
+
+            server.route("/hello", |req| {
+                let name = req.query("name").unwrap_or("world");
+                `Hello ${name}!`
+            });
+
+            server.route("/echo", async |req| {
+                let token = req.header("x-token").unwrap_or("none");
+
+                Response::new(201)
+                    .header("x-method", req.method())
+                    .header("x-token", token)
+                    .body(req.text())
+            });
+
+            server.route("/empty", |req| {});
+            server.route("/fail", |req| Err("broken"));
+            server
+        }
+        "#,
+    )?;
+
+    let server = Server::from_value(vm.call(&["main"], ())?)?;
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?.to_string();
+
+    LocalSet::new()
+        .run_until(async move {
+            tokio::task::spawn_local(async move { server.serve(listener).await });
+
+            let response = request(
+                &addr,
+                "GET /hello?name=rune%20lang HTTP/1.1\r\nhost: test\r\nconnection: close\r\n\r\n",
+            )
+            .await?;
+            assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+            assert!(response.ends_with("\r\n\r\nHello rune lang!"), "{}", response);
+
+            let response = request(
+                &addr,
+                "POST /echo HTTP/1.1\r\nhost: test\r\nx-token: secret\r\ncontent-length: 5\r\nconnection: close\r\n\r\nhello",
+            )
+            .await?;
+            assert!(response.starts_with("HTTP/1.1 201 Created\r\n"), "{}", response);
+            assert!(response.contains("x-method: POST\r\n"), "{}", response);
+            assert!(response.contains("x-token: secret\r\n"), "{}", response);
+            assert!(response.ends_with("\r\n\r\nhello"), "{}", response);
+
+            let response = request(
+                &addr,
+                "GET /empty HTTP/1.1\r\nhost: test\r\nconnection: close\r\n\r\n",
+            )
+            .await?;
+            assert!(response.starts_with("HTTP/1.1 204 No Content\r\n"), "{}", response);
+
+            let response = request(
+                &addr,
+                "GET /fail HTTP/1.1\r\nhost: test\r\nconnection: close\r\n\r\n",
+            )
+            .await?;
+            assert!(response.starts_with("HTTP/1.1 500 Internal Server Error\r\n"), "{}", response);
+
+            let response = request(
+                &addr,
+                "GET /missing HTTP/1.1\r\nhost: test\r\nconnection: close\r\n\r\n",
+            )
+            .await?;
+            assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"), "{}", response);
+            Ok(())
+        })
+        .await
+}
@@ -0,0 +1,101 @@
+use rune::runtime::VmErrorKind::*;
+use rune_tests::*;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Metadata, Subscriber};
+
+/// The level of an event along with its recorded fields.
+type Recorded = (Level, Vec<(String, String)>);
+
+/// A subscriber collecting the events emitted by scripts.
+#[derive(Default, Clone)]
+struct Collect {
+    events: Arc<Mutex<Vec<Recorded>>>,
+}
+
+impl Subscriber for Collect {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.target() == "rune::script"
+    }
+
+    fn new_span(&self, _: &Attributes<'_>) -> Id {
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        struct Fields(Vec<(String, String)>);
+
+        impl Visit for Fields {
+            fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+                self.0.push((field.name().to_owned(), format!("{:?}", value)));
+            }
+        }
+
+        let mut fields = Fields(Vec::new());
+        event.record(&mut fields);
+        let level = *event.metadata().level();
+        self.events.lock().unwrap().push((level, fields.0));
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+fn field<'a>(fields: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    fields
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+#[test]
+fn test_log_macros() {
+    let collect = Collect::default();
+
+    tracing::subscriber::with_default(collect.clone(), || {
+        let () = rune_s! { r#"
+            pub fn main() {
+                log::info!("processing {} items", 42);
+
+                log::warn!("careful");
+                log::error!("failed: {:?}", "oops");
+                log::log("debug", "dynamic");
+            }
+        "# };
+    });
+
+    let events = collect.events.lock().unwrap();
+    assert_eq!(events.len(), 4);
+
+    let (level, fields) = &events[0];
+    assert_eq!(*level, Level::INFO);
+    assert_eq!(field(fields, "message"), Some("processing 42 items"));
+    assert_eq!(field(fields, "line"), Some("3"));
+    assert!(field(fields, "file").is_some());
+
+    assert_eq!(events[1].0, Level::WARN);
+    assert_eq!(field(&events[1].1, "line"), Some("5"));
+    assert_eq!(events[2].0, Level::ERROR);
+    assert_eq!(field(&events[2].1, "message"), Some("failed: \"oops\""));
+
+    assert_eq!(events[3].0, Level::DEBUG);
+    assert_eq!(field(&events[3].1, "message"), Some("dynamic"));
+    assert_eq!(field(&events[3].1, "line"), None);
+}
+
+#[test]
+fn test_log_unsupported_level() {
+    assert_vm_error!(
+        r#"pub fn main() { log::log("fatal", "boom") }"#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "unsupported log level `fatal`");
+        }
+    );
+}
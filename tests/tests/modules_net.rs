@@ -0,0 +1,73 @@
+//! Tests for the `net` module.
+
+use rune_tests::*;
+use rune::runtime::Bytes;
+use rune::{FromValue, Value};
+
+#[tokio::test]
+async fn test_tcp() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        rune_modules::net::module(true)? => r#"
+        use net::{TcpListener, TcpStream};
+
+        pub async fn main() {
+            let listener = TcpListener::bind("127.0.0.1:0").await?;
+            let client = TcpStream::connect(listener.local_addr()?).await?;
+            let (server, peer) = listener.accept().await?;
+
+            client.write_all("ping").await?;
+            let request = server.read(16).await?;
+            server.write_all(b"pong").await?;
+            let response = client.read(16).await?;
+
+            let same_peer = peer == client.local_addr()?;
+            drop(client);
+            let rest = server.read_to_end().await?;
+
+            Ok((request, response, same_peer, rest))
+        }
+        "#,
+    )?;
+
+    let output = vm.async_call(&["main"], ()).await?;
+    let (request, response, same_peer, rest) =
+        Result::<(Bytes, Bytes, bool, Bytes), Value>::from_value(output)?.expect("expected ok");
+
+    assert_eq!(request.into_vec(), b"ping");
+    assert_eq!(response.into_vec(), b"pong");
+    assert!(same_peer);
+    assert!(rest.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_udp() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        rune_modules::net::module(true)? => r#"
+        use net::UdpSocket;
+
+        pub async fn main() {
+            let a = UdpSocket::bind("127.0.0.1:0").await?;
+            let b = UdpSocket::bind("127.0.0.1:0").await?;
+
+            a.send_to("hello", b.local_addr()?).await?;
+            let (data, from) = b.recv_from(16).await?;
+
+            b.connect(from).await?;
+            b.send(b"back").await?;
+            let reply = a.recv(16).await?;
+
+            Ok((data, from == a.local_addr()?, reply))
+        }
+        "#,
+    )?;
+
+    let output = vm.async_call(&["main"], ()).await?;
+    let (data, same_addr, reply) =
+        Result::<(Bytes, bool, Bytes), Value>::from_value(output)?.expect("expected ok");
+
+    assert_eq!(data.into_vec(), b"hello");
+    assert!(same_addr);
+    assert_eq!(reply.into_vec(), b"back");
+    Ok(())
+}
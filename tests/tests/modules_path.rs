@@ -0,0 +1,121 @@
+//! Tests for the `path` module.
+
+use rune_tests::*;
+use rune::FromValue;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn test_path_manipulation() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        rune_modules::path::module(true)? => r#"
+        pub fn main() {
+            let p = path::join("dir/sub", "file.tar.gz");
+
+            [
+                p,
+                path::parent(p).unwrap(),
+                path::file_name(p).unwrap(),
+                path::file_stem(p).unwrap(),
+                path::extension(p).unwrap(),
+                path::with_extension(p, "zip"),
+                path::with_file_name(p, "other.txt"),
+                path::join("dir", "/abs"),
+                path::normalize("./a/b/../c/./d/.."),
+                path::normalize("../a/../../b"),
+                path::normalize("/../a/.."),
+                path::normalize("a/.."),
+                `${path::components("/a/b").len()}`,
+                `${path::parent("/").is_none()}`,
+                `${path::extension("dir/file").is_none()}`,
+                `${path::is_absolute("/a")} ${path::is_relative("a")}`,
+            ]
+        }
+        "#,
+    )?;
+
+    let output = Vec::<String>::from_value(vm.call(&["main"], ())?)?;
+
+    assert_eq!(
+        output,
+        [
+            "dir/sub/file.tar.gz",
+            "dir/sub",
+            "file.tar.gz",
+            "file.tar",
+            "gz",
+            "dir/sub/file.tar.zip",
+            "dir/sub/other.txt",
+            "/abs",
+            "a/c",
+            "../../b",
+            "/",
+            ".",
+            "3",
+            "true",
+            "true",
+            "true true",
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_path_glob() -> rune::Result<()> {
+    let dir = std::env::temp_dir().join(format!("rune-path-glob-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    for file in [
+        "a.rn",
+        "b.rn",
+        "c.txt",
+        ".hidden.rn",
+        "[x].rn",
+        "sub/d.rn",
+        "sub/deep/e.rn",
+        "sub/deep/f2.rn",
+    ] {
+        let path = dir.join(file);
+        std::fs::create_dir_all(path.parent().unwrap())?;
+        std::fs::write(path, "")?;
+    }
+
+    let mut vm = rune_vm_with!(
+        rune_modules::path::module(true)? => r#"
+        pub fn main(dir) {
+            [
+                path::glob(path::join(dir, "*.rn")),
+                path::glob(path::join(dir, "**/*.rn")),
+                path::glob(path::join(dir, "sub/*/[e-f]?.rn")),
+                path::glob(path::join(dir, "[!ab]*.*")),
+                path::glob(path::join(dir, "missing/*")),
+                [`${path::exists(path::join(dir, "c.txt"))}`],
+            ]
+        }
+        "#,
+    )?;
+
+    let output = Vec::<Vec<String>>::from_value(vm.call(&["main"], (dir.to_string_lossy().into_owned(),))?)?;
+
+    let relative = |paths: &[String]| {
+        paths
+            .iter()
+            .map(|p| Path::new(p).strip_prefix(&dir).unwrap().to_owned())
+            .collect::<Vec<_>>()
+    };
+
+    let expected = |paths: &[&str]| paths.iter().map(PathBuf::from).collect::<Vec<_>>();
+
+    assert_eq!(relative(&output[0]), expected(&["[x].rn", "a.rn", "b.rn"]));
+    assert_eq!(
+        relative(&output[1]),
+        expected(&["[x].rn", "a.rn", "b.rn", "sub/d.rn", "sub/deep/e.rn", "sub/deep/f2.rn"])
+    );
+    assert_eq!(relative(&output[2]), expected(&["sub/deep/f2.rn"]));
+    assert_eq!(relative(&output[3]), expected(&["[x].rn", "c.txt"]));
+    assert!(output[4].is_empty());
+    assert_eq!(output[5], ["true"]);
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
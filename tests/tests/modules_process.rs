@@ -0,0 +1,62 @@
+//! Tests for the `process` module.
+
+use rune_tests::*;
+use rune::runtime::Bytes;
+use rune::{FromValue, Value};
+
+#[tokio::test]
+async fn test_process_output() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        rune_modules::process::module(true)? => r#"
+        use process::Command;
+
+        pub async fn main() {
+            let command = Command::new("sh");
+            command.args(["-c", "echo $GREETING; pwd; echo oops >&2; exit 3"]);
+            command.env("GREETING", "hello");
+            command.current_dir("/");
+
+            let output = command.output().await?;
+            Ok((output.stdout, output.stderr, output.status.code(), output.status.success()))
+        }
+        "#,
+    )?;
+
+    let output = vm.async_call(&["main"], ()).await?;
+    let (stdout, stderr, code, success) =
+        Result::<(Bytes, Bytes, Option<i32>, bool), Value>::from_value(output)?
+            .expect("expected ok");
+
+    assert_eq!(stdout.into_vec(), b"hello\n/\n");
+    assert_eq!(stderr.into_vec(), b"oops\n");
+    assert_eq!(code, Some(3));
+    assert!(!success);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_process_status() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        rune_modules::process::module(true)? => r#"
+        use process::Command;
+
+        pub async fn main() {
+            let command = Command::new("sh");
+            command.args(["-c", "test -z \"$HOME\""]);
+            command.env_clear();
+            let cleared = command.status().await?.success();
+
+            let child = Command::new("true").spawn()?;
+            let has_id = child.id().is_some();
+            let waited = child.wait().await?.success();
+
+            Ok((cleared, has_id, waited))
+        }
+        "#,
+    )?;
+
+    let output = vm.async_call(&["main"], ()).await?;
+    let output = Result::<(bool, bool, bool), Value>::from_value(output)?.expect("expected ok");
+    assert_eq!(output, (true, true, true));
+    Ok(())
+}
@@ -0,0 +1,144 @@
+//! Tests for the `sqlite` module.
+
+use rune_tests::*;
+use rune::runtime::Value;
+use rune::FromValue;
+
+#[test]
+fn test_sqlite_execute_and_query() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        rune_modules::sqlite::module(true)? => r#"
+        use sqlite::Connection;
+
+        pub fn main() {
+            let db = Connection::open_in_memory()?;
+            db.execute_batch("CREATE TABLE people (name TEXT, age INTEGER, score REAL, avatar BLOB)")?;
+
+            let insert = db.prepare("INSERT INTO people (name, age, score, avatar) VALUES (?, ?, ?, ?)")?;
+            let changed = insert.execute(("ann", 42, 1.5, b"\x01\x02"))?;
+            insert.execute(["bob", 17, None, ()])?;
+            db.execute("INSERT INTO people (name, age) VALUES (:name, :age)", #{ name: "cid", age: 30 })?;
+
+            let rows = [];
+
+            for row in db.query("SELECT * FROM people WHERE age > ? ORDER BY age", [18])? {
+                rows.push((row.name, row.age, row.score, row.avatar));
+            }
+
+            let bob = db.query_row("SELECT name, score FROM people WHERE age < :age", #{ age: 18 })?;
+            let nobody = db.query_row("SELECT name FROM people WHERE age > 100", ())?;
+
+            Ok((changed, db.last_insert_rowid(), rows, bob.map(|row| (row.name, row.score)), nobody.is_none()))
+        }
+        "#,
+    )?;
+
+    type Output = (
+        i64,
+        i64,
+        Vec<(String, i64, Value, Value)>,
+        Option<(String, Value)>,
+        bool,
+    );
+
+    let output = <Result<Output, Value>>::from_value(vm.call(&["main"], ())?)?;
+    let (changed, rowid, rows, bob, nobody) = output.expect("expected output");
+
+    assert_eq!((changed, rowid, nobody), (1, 3, true));
+
+    match rows.as_slice() {
+        [(cid, 30, Value::Unit, Value::Unit), (ann, 42, Value::Float(score), Value::Bytes(avatar))] =>
+        {
+            assert_eq!((cid.as_str(), ann.as_str(), *score), ("cid", "ann", 1.5));
+            assert_eq!(avatar.borrow_ref()?.to_vec(), vec![1, 2]);
+        }
+        rows => panic!("unexpected rows: {:?}", rows),
+    }
+
+    assert!(matches!(bob, Some((name, Value::Unit)) if name == "bob"));
+    Ok(())
+}
+
+#[test]
+fn test_sqlite_errors() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        rune_modules::sqlite::module(true)? => r#"
+        use sqlite::Connection;
+
+        pub fn main(sql, params) {
+            let db = Connection::open_in_memory()?;
+            db.execute_batch("CREATE TABLE t (x INTEGER)")?;
+
+            match db.execute(sql, params) {
+                Ok(..) => "ok",
+                Err(error) => `${error}`,
+            }
+        }
+        "#,
+    )?;
+
+    let mut call = |sql: &str, params: Vec<i64>| -> rune::Result<String> {
+        Ok(String::from_value(vm.call(&["main"], (sql, params))?)?)
+    };
+
+    assert_eq!(call("INSERT INTO t VALUES (?)", vec![1])?, "ok");
+    assert!(call("INSERT INTO missing VALUES (?)", vec![1])?.contains("no such table"));
+    assert_eq!(
+        call("INSERT INTO t VALUES (?)", vec![1, 2])?,
+        "Wrong number of parameters passed to query. Got 2, needed 1"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_sqlite_rows_and_named_parameters() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        rune_modules::sqlite::module(true)? => r#"
+        use sqlite::Connection;
+
+        fn rows() {
+            // NB: the rows outlive the connection they were queried from.
+            let db = Connection::open_in_memory()?;
+            db.execute_batch("CREATE TABLE t (x INTEGER)")?;
+
+            let insert = db.prepare("INSERT INTO t VALUES (?)")?;
+
+            for x in 0..100 {
+                insert.execute([x])?;
+            }
+
+            db.query("SELECT x FROM t ORDER BY x", ())
+        }
+
+        pub fn main() {
+            let first = rows()?.take(3).map(|row| row.x).collect::<Vec>();
+            let count = rows()?.count();
+
+            let db = Connection::open_in_memory()?;
+
+            let missing = match db.query_row("SELECT :a + :b AS x", #{ a: 1 }) {
+                Ok(..) => "ok",
+                Err(error) => `${error}`,
+            };
+
+            let row = db.query_row("SELECT :a + :b AS x", #{ a: 1, ":b": 2 })?;
+            Ok((first, count, missing, row.map(|row| row.x)))
+        }
+        "#,
+    )?;
+
+    let output = <Result<(Vec<i64>, usize, String, Option<i64>), Value>>::from_value(
+        vm.call(&["main"], ())?,
+    )?;
+
+    assert_eq!(
+        output.expect("expected output"),
+        (
+            vec![0, 1, 2],
+            100,
+            String::from("Wrong number of parameters passed to query. Got 1, needed 2"),
+            Some(3),
+        )
+    );
+    Ok(())
+}
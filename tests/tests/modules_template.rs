@@ -0,0 +1,179 @@
+//! Tests for the `template` module.
+
+use rune_tests::*;
+use rune::FromValue;
+
+/// Render the given template with the data produced by the given expression,
+/// returning either the output or the displayed error.
+fn render(template: &str, data: &str) -> rune::Result<Result<String, String>> {
+    let mut vm = rune_vm_with!(rune_modules::template::module(true)? => &format!(
+        r#"
+        struct User {{ name, admin }}
+
+        pub fn main(template) {{
+            match template::render(template, {}) {{
+                Ok(output) => Ok(output),
+                Err(error) => Err(`${{error}}`),
+            }}
+        }}
+        "#,
+        data
+    ))?;
+
+    Ok(<Result<String, String>>::from_value(
+        vm.call(&["main"], (template,))?,
+    )?)
+}
+
+#[test]
+fn test_template_substitution() -> rune::Result<()> {
+    let data = r#"#{
+        name: "  <rune>  ",
+        count: 42,
+        ratio: 0.5,
+        tags: ["a", "b"],
+        user: User { name: "ann", admin: true },
+        nothing: None,
+        something: Some("x"),
+    }"#;
+
+    assert_eq!(
+        render("Hello {{ name | trim | escape }}!", data)?,
+        Ok(String::from("Hello &lt;rune&gt;!"))
+    );
+
+    assert_eq!(
+        render(
+            "{{count}} {{ ratio }} {{ tags.1 | upper }} {{ user.name }}",
+            data
+        )?,
+        Ok(String::from("42 0.5 B ann"))
+    );
+
+    assert_eq!(
+        render("[{{ nothing }}] [{{ something }}]", data)?,
+        Ok(String::from("[] [x]"))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_template_blocks() -> rune::Result<()> {
+    let data = r#"#{
+        items: ["a", "b", "c"],
+        options: #{ port: 8080, host: "localhost" },
+        empty: [],
+        user: User { name: "ann", admin: false },
+    }"#;
+
+    assert_eq!(
+        render(
+            "{% for item in items %}{{ item }}{% if not loop.last %}, {% endif %}{% endfor %}",
+            data
+        )?,
+        Ok(String::from("a, b, c"))
+    );
+
+    let template = "\
+[server]
+{% for key, value in options %}
+  {{ key }} = {{ value }}
+{% endfor %}
+{# a comment #}
+{% if user.admin %}
+admin = true
+{% elif user.name %}
+user = {{ user.name }}
+{% else %}
+anonymous = true
+{% endif %}
+{% for item in empty %}
+{{ item }}
+{% else %}
+no items
+{% endfor %}
+";
+
+    assert_eq!(
+        render(template, data)?,
+        Ok(String::from(
+            "[server]\n  host = localhost\n  port = 8080\nuser = ann\nno items\n"
+        ))
+    );
+
+    assert_eq!(
+        render(
+            "{% for n, item in items %}{{ n }}={{ item }} {% endfor %}",
+            data
+        )?,
+        Ok(String::from("0=a 1=b 2=c "))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_template_errors() -> rune::Result<()> {
+    let data = r#"#{ items: [1], user: User { name: "ann", admin: false } }"#;
+
+    let error = |message: &str| Err(String::from(message));
+
+    assert_eq!(
+        render("a\n{{ missing }}", data)?,
+        error("undefined variable `missing` on line 2")
+    );
+    assert_eq!(
+        render("{{ user.email }}", data)?,
+        error("`user.email` is undefined on line 1")
+    );
+    assert_eq!(
+        render("{{ items }}", data)?,
+        error("`items` can't be rendered on line 1")
+    );
+    assert_eq!(
+        render("{% for item in user.name %}{% endfor %}", data)?,
+        error("`user.name` can't be looped over on line 1")
+    );
+    assert_eq!(
+        render("{{ user.name | shout }}", data)?,
+        error("unknown filter `shout` on line 1")
+    );
+    assert_eq!(
+        render("{% if items %}\n{% for item in items %}", data)?,
+        error("unclosed `for` on line 2")
+    );
+    assert_eq!(
+        render("{% endif %}", data)?,
+        error("unexpected `endif` on line 1")
+    );
+    assert_eq!(render("{{ items", data)?, error("unclosed `{{` on line 1"));
+    Ok(())
+}
+
+#[test]
+fn test_template_reuse() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        rune_modules::template::module(true)? => r#"
+        use template::Template;
+
+        pub fn main() {
+            let template = Template::new("<li>{{ name }}</li>")?;
+            let out = [];
+
+            for name in ["a", "b"] {
+                out.push(template.render(#{ name })?);
+            }
+
+            Ok(out)
+        }
+        "#,
+    )?;
+
+    let output = <Result<Vec<String>, rune::runtime::Value>>::from_value(vm.call(&["main"], ())?)?;
+    assert_eq!(
+        output.ok(),
+        Some(vec![String::from("<li>a</li>"), String::from("<li>b</li>")])
+    );
+    Ok(())
+}
@@ -0,0 +1,215 @@
+use rune_tests::*;
+use futures_executor::block_on;
+use rune::runtime::Determinism;
+use rune::{FromValue, Vm};
+
+fn vm(source: &str, determinism: Option<Determinism>) -> rune::Result<Vm> {
+    let mut vm = vm_from_source(&rune_modules::default_context()?, source)?;
+    vm.set_determinism(determinism);
+    Ok(vm)
+}
+
+#[test]
+fn test_virtual_time() -> rune::Result<()> {
+    let mut vm = vm(
+        r#"
+        use time::{Duration, Instant};
+
+        pub async fn main() {
+            let start = Instant::now();
+            let before = time::now();
+            time::sleep(Duration::from_millis(1500)).await;
+            let after = time::now();
+
+            (
+                `${start.elapsed()}`,
+                `${before}`,
+                after.unix_timestamp(),
+                after.duration_since(before).as_millis(),
+                after.cmp(before).is_gt(),
+            )
+        }
+        "#,
+        Some(Determinism::new(0)),
+    )?;
+
+    let output = block_on(vm.async_call(&["main"], ()))?;
+    let output = <(String, String, i64, u64, bool)>::from_value(output)?;
+
+    assert_eq!(
+        output,
+        (
+            String::from("1.5s"),
+            String::from("1970-01-01T00:00:00Z"),
+            1,
+            1500,
+            true
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn test_parse_and_format() -> rune::Result<()> {
+    let mut vm = vm(
+        r#"
+        use time::{DateTime, Duration};
+
+        pub fn main() {
+            let date = DateTime::parse_rfc3339("2021-11-25T12:30:05+01:00")?;
+            let later = date + Duration::from_secs(86400 + 3600);
+            let parsed = DateTime::parse("25 Nov 2021 11:30", "%d %b %Y %H:%M")?;
+            let day = DateTime::parse("2021-11-25", "%F")?;
+
+            (
+                date.format("%A %e %B %Y, %I:%M %p %z [%j]")?,
+                later.to_rfc3339()?,
+                `${date.to_utc()}`,
+                [date.year(), date.month(), date.day(), date.hour(), date.weekday()],
+                parsed.unix_timestamp() == date.unix_timestamp() - 5,
+                `${day}`,
+                (date - Duration::from_secs(5)).second(),
+                DateTime::parse("2021", "%Q").is_err(),
+            )
+        }
+        "#,
+        None,
+    )?;
+
+    let output = vm.call(&["main"], ())?;
+    let output = <(String, String, String, Vec<i64>, bool, String, i64, bool)>::from_value(output)?;
+
+    assert_eq!(output.0, "Thursday 25 November 2021, 12:30 PM +0100 [329]");
+    assert_eq!(output.1, "2021-11-26T13:30:05+01:00");
+    assert_eq!(output.2, "2021-11-25T11:30:05Z");
+    assert_eq!(output.3, [2021, 11, 25, 12, 4]);
+    assert!(output.4);
+    assert_eq!(output.5, "2021-11-25T00:00:00Z");
+    assert_eq!(output.6, 0);
+    assert!(output.7);
+    Ok(())
+}
+
+#[test]
+fn test_sort_durations() -> rune::Result<()> {
+    let mut vm = vm(
+        r#"
+        use time::Duration;
+
+        pub fn main() {
+            let durations = [Duration::from_secs(3), Duration::from_millis(10), Duration::from_secs_f64(1.5)];
+            durations.sort();
+            durations.iter().map(|d| d.as_millis()).collect::<Vec>()
+        }
+        "#,
+        None,
+    )?;
+
+    let output = Vec::<u64>::from_value(vm.call(&["main"], ())?)?;
+    assert_eq!(output, [10, 1500, 3000]);
+    Ok(())
+}
+
+#[test]
+fn test_virtual_interval() -> rune::Result<()> {
+    let mut vm = vm(
+        r#"
+        use time::{Duration, Instant};
+
+        pub async fn main() {
+            let start = Instant::now();
+            let interval = time::interval(Duration::from_millis(250));
+            let ticks = [];
+
+            for n in 0..4 {
+                let tick = interval.tick().await;
+                ticks.push(tick.duration_since(start).as_millis());
+
+                // Work which takes longer than the period delays the next tick.
+                if n == 1 {
+                    time::sleep(Duration::from_millis(400)).await;
+                }
+            }
+
+            (ticks, start.elapsed().as_millis(), interval.period().as_millis())
+        }
+        "#,
+        Some(Determinism::new(0)),
+    )?;
+
+    let output = block_on(vm.async_call(&["main"], ()))?;
+    let output = <(Vec<u64>, u64, u64)>::from_value(output)?;
+    assert_eq!(output, (vec![0, 250, 500, 750], 750, 250));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_interval() -> rune::Result<()> {
+    let mut vm = vm(
+        r#"
+        use time::{Duration, Instant};
+
+        pub async fn main() {
+            let start = Instant::now();
+            let interval = time::interval(Duration::from_millis(10));
+
+            for n in 0..3 {
+                interval.tick().await;
+            }
+
+            start.elapsed().as_millis()
+        }
+        "#,
+        None,
+    )?;
+
+    let elapsed = u64::from_value(vm.async_call(&["main"], ()).await?)?;
+    assert!(elapsed >= 20, "{}", elapsed);
+    Ok(())
+}
+
+#[test]
+fn test_virtual_timeout() -> rune::Result<()> {
+    let mut vm = vm(
+        r#"
+        use std::sync::channel;
+        use time::Duration;
+
+        pub async fn main() {
+            let (tx, rx) = channel::bounded(1);
+            let ready = time::timeout(async { 42 }, Duration::from_secs(1)).await;
+            let slow = time::timeout(rx.recv(), Duration::from_secs(2)).await;
+            let slept = time::now().unix_timestamp();
+
+            (ready, match slow { Ok(..) => "ok", Err(error) => `${error}` }, slept)
+        }
+        "#,
+        Some(Determinism::new(0)),
+    )?;
+
+    let output = <(Result<i64, rune::Value>, String, i64)>::from_value(block_on(vm.async_call(&["main"], ()))?)?;
+    assert!(matches!(output.0, Ok(42)));
+    assert_eq!(output.1, "deadline has elapsed");
+    assert_eq!(output.2, 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_timeout() -> rune::Result<()> {
+    let mut vm = vm(
+        r#"
+        use time::Duration;
+
+        pub async fn main() {
+            let fast = time::timeout(time::sleep(Duration::from_millis(1)), Duration::from_secs(10)).await;
+            let slow = time::timeout(time::sleep(Duration::from_secs(10)), Duration::from_millis(10)).await;
+            (fast.is_ok(), slow.is_err())
+        }
+        "#,
+        None,
+    )?;
+
+    let output = <(bool, bool)>::from_value(vm.async_call(&["main"], ()).await?)?;
+    assert_eq!(output, (true, true));
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use rune_tests::*;
+
+#[test]
+fn test_toml_round_trip() {
+    let out: String = rune_s! { r#"
+        pub fn main() {
+            let config = toml::from_string("name = \"rune\"\n[server]\nport = 8080\nhosts = [\"a\", \"b\"]\n")?;
+            config.server.port += 1;
+            config.version = 2;
+            toml::to_string(config)?
+        }
+    "# };
+
+    assert_eq!(
+        out,
+        "name = \"rune\"\nversion = 2\n\n[server]\nhosts = [\"a\", \"b\"]\nport = 8081\n"
+    );
+}
+
+#[test]
+fn test_toml_pretty() {
+    let out: (String, i64) = rune_s! { r#"
+        pub fn main() {
+            let pretty = toml::to_string_pretty(#{ list: [1, 2] })?;
+            let bytes = toml::to_bytes(#{ list: [1, 2] })?;
+            (pretty, toml::from_bytes(bytes)?.list[1])
+        }
+    "# };
+
+    assert_eq!(out, (String::from("list = [\n    1,\n    2,\n]\n"), 2));
+}
+
+#[test]
+fn test_toml_not_serializable() {
+    let out: (bool, bool) = rune! {
+        pub fn main() {
+            (toml::to_string(42).is_err(), toml::to_string(#{ unit: () }).is_err())
+        }
+    };
+
+    assert_eq!(out, (true, true));
+}
@@ -0,0 +1,142 @@
+//! Tests for the `websocket` module, against a local server.
+
+use rune_tests::*;
+use base64::Engine as _;
+use rune::runtime::Bytes;
+use rune::FromValue;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Read a frame sent by the client, which is always masked.
+async fn read_frame(stream: &mut BufReader<TcpStream>) -> (u8, u8, Vec<u8>) {
+    let mut head = [0; 2];
+    stream.read_exact(&mut head).await.unwrap();
+    assert_eq!(head[1] & 0x80, 0x80, "client frames must be masked");
+
+    let len = match head[1] & 0x7f {
+        126 => stream.read_u16().await.unwrap() as usize,
+        127 => stream.read_u64().await.unwrap() as usize,
+        len => len as usize,
+    };
+
+    let mut mask = [0; 4];
+    stream.read_exact(&mut mask).await.unwrap();
+
+    let mut payload = vec![0; len];
+    stream.read_exact(&mut payload).await.unwrap();
+
+    for (i, b) in payload.iter_mut().enumerate() {
+        *b ^= mask[i % 4];
+    }
+
+    (head[0] & 0x80, head[0] & 0x0f, payload)
+}
+
+async fn write_frame(stream: &mut BufReader<TcpStream>, head: u8, payload: &[u8]) {
+    let mut frame = vec![head, payload.len() as u8];
+    frame.extend_from_slice(payload);
+    stream.write_all(&frame).await.unwrap();
+}
+
+/// Accept a single connection, perform the handshake and run through a
+/// scripted exchange of frames.
+async fn serve(listener: TcpListener) -> Vec<(u8, u8, Vec<u8>)> {
+    let (stream, _) = listener.accept().await.unwrap();
+    let mut stream = BufReader::new(stream);
+    let mut key = None;
+
+    loop {
+        let mut line = String::new();
+        stream.read_line(&mut line).await.unwrap();
+
+        if line == "\r\n" {
+            break;
+        }
+
+        if let Some(value) = line.strip_prefix("Sec-WebSocket-Key: ") {
+            key = Some(value.trim().to_owned());
+        }
+    }
+
+    let digest = ring::digest::digest(
+        &ring::digest::SHA1_FOR_LEGACY_USE_ONLY,
+        format!("{}258EAFA5-E914-47DA-95CA-C5AB0DC85B11", key.unwrap()).as_bytes(),
+    );
+
+    let accept = base64::engine::general_purpose::STANDARD.encode(digest.as_ref());
+
+    let response = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        accept
+    );
+
+    stream.write_all(response.as_bytes()).await.unwrap();
+
+    let mut received = Vec::new();
+    received.push(read_frame(&mut stream).await);
+    received.push(read_frame(&mut stream).await);
+
+    // A ping, followed by a fragmented text message and a binary message.
+    write_frame(&mut stream, 0x89, b"ping").await;
+    write_frame(&mut stream, 0x01, b"hel").await;
+    write_frame(&mut stream, 0x80, b"lo!").await;
+    write_frame(&mut stream, 0x82, &[1, 2, 3]).await;
+
+    // The pong and the close.
+    received.push(read_frame(&mut stream).await);
+    received.push(read_frame(&mut stream).await);
+    write_frame(&mut stream, 0x88, &1000u16.to_be_bytes()).await;
+    received
+}
+
+#[tokio::test]
+async fn test_websocket_exchange() -> rune::Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("ws://{}/socket", listener.local_addr()?);
+    let server = tokio::spawn(serve(listener));
+
+    let mut vm = rune_vm_with!(
+        rune_modules::websocket::module(true)? => r#"
+        pub async fn main(url) {
+            let socket = websocket::connect(url).await?;
+            socket.send("hello").await?;
+            socket.send(b"\x00\xff").await?;
+
+            let text = socket.recv().await?.unwrap();
+            let binary = socket.recv().await?.unwrap();
+            socket.close().await?;
+            Ok((text, binary))
+        }
+        "#,
+    )?;
+
+    let output = vm.async_call(&["main"], (url,)).await?;
+    let (text, binary) = <Result<(String, Bytes), rune::Value>>::from_value(output)?.unwrap();
+    assert_eq!(text, "hello!");
+    assert_eq!(binary.into_vec(), [1, 2, 3]);
+
+    let received = server.await?;
+    assert_eq!(received[0], (0x80, 0x1, b"hello".to_vec()));
+    assert_eq!(received[1], (0x80, 0x2, vec![0x00, 0xff]));
+    assert_eq!(received[2], (0x80, 0xa, b"ping".to_vec()));
+    assert_eq!(received[3], (0x80, 0x8, 1000u16.to_be_bytes().to_vec()));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_websocket_invalid_scheme() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        rune_modules::websocket::module(true)? => r#"
+        pub async fn main() {
+            match websocket::connect("http://localhost").await {
+                Ok(..) => false,
+                Err(..) => true,
+            }
+        }
+        "#,
+    )?;
+
+    let output = vm.async_call(&["main"], ()).await?;
+    assert!(bool::from_value(output)?);
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use rune_tests::*;
+
+#[test]
+fn test_yaml_round_trip() {
+    let out: String = rune_s! { r#"
+        pub fn main() {
+            let config = yaml::from_string("name: rune\nserver:\n  port: 8080\n  hosts: [a, b]\n")?;
+            config.server.port += 1;
+            config.version = 2;
+            yaml::to_string(config)?
+        }
+    "# };
+
+    assert_eq!(
+        out,
+        "name: rune\nserver:\n  hosts:\n  - a\n  - b\n  port: 8081\nversion: 2\n"
+    );
+}
+
+#[test]
+fn test_yaml_bytes() {
+    let out: (i64, f64, bool) = rune_s! { r#"
+        pub fn main() {
+            let bytes = yaml::to_bytes(#{ list: [1, 2.5, ()] })?;
+            let data = yaml::from_bytes(bytes)?;
+            (data.list[0], data.list[1], data.list[2] is unit)
+        }
+    "# };
+
+    assert_eq!(out, (1, 2.5, true));
+}
+
+#[test]
+fn test_yaml_errors() {
+    let out: (bool, bool) = rune! {
+        pub fn main() {
+            (yaml::from_string("key: [1, 2").is_err(), yaml::to_string(|| 42).is_err())
+        }
+    };
+
+    assert_eq!(out, (true, true));
+}
@@ -0,0 +1,52 @@
+use rune_tests::*;
+
+#[test]
+fn test_object_api() {
+    let _: () = rune! {
+        pub fn main() {
+            let o = Object::from_pairs([("a", 1), ("b", 2)]);
+
+            assert_eq!(o.keys().collect::<Vec>(), ["a", "b"]);
+            assert_eq!(o.values().collect::<Vec>(), [1, 2]);
+            assert_eq!(o.entries().collect::<Vec>(), [("a", 1), ("b", 2)]);
+            assert!(o.contains_key("a"));
+            assert!(!o.is_empty());
+
+            assert_eq!(o.get_or_insert("a", 10), 1);
+            assert_eq!(o.get_or_insert("c", 3), 3);
+            assert_eq!(o.c, 3);
+
+            assert_eq!(o.remove("b"), Some(2));
+            assert_eq!(o.remove("b"), None);
+            assert!(!o.contains_key("b"));
+
+            o.extend([("d", 4), ("a", 5)]);
+            assert_eq!(o, #{a: 5, c: 3, d: 4});
+
+            o.merge(#{c: 30, e: 50});
+            assert_eq!(o, #{a: 5, c: 30, d: 4, e: 50});
+
+            let copy = Object::new();
+            copy.extend(o);
+            assert_eq!(copy, o);
+
+            copy.clear();
+            assert!(copy.is_empty());
+        }
+    };
+}
+
+#[test]
+fn test_object_get_or_insert_shares_value() {
+    let _: () = rune! {
+        pub fn main() {
+            let groups = #{};
+
+            for (key, value) in [("odd", 1), ("even", 2), ("odd", 3)] {
+                groups.get_or_insert(key, []).push(value);
+            }
+
+            assert_eq!(groups, #{odd: [1, 3], even: [2]});
+        }
+    };
+}
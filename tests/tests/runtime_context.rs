@@ -0,0 +1,59 @@
+use rune::{Context, FromValue, Hash, Module, Source, Sources, Vm};
+use std::sync::Arc;
+use std::thread;
+
+#[test]
+fn test_runtime_context_after_install() -> rune::Result<()> {
+    let mut context = Context::new();
+    let before = context.runtime();
+
+    let mut module = Module::new();
+    module.function(&["answer"], || 42i64)?;
+    context.install(&module)?;
+
+    let after = context.runtime();
+    let hash = Hash::type_hash(&["answer"]);
+    assert!(before.function(hash).is_none());
+    assert!(after.function(hash).is_some());
+    assert!(after.clone().function(hash).is_some());
+    Ok(())
+}
+
+#[test]
+fn test_vm_per_thread() -> rune::Result<()> {
+    let mut module = Module::new();
+    module.function(&["double"], |n: i64| n * 2)?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(&module)?;
+
+    let mut sources = Sources::new();
+    sources.insert(Source::new(
+        "entry",
+        r#"pub fn main(n) { double(n) + 1 }"#,
+    ));
+
+    let unit = Arc::new(rune::prepare(&mut sources).with_context(&context).build()?);
+    let runtime = Arc::new(context.runtime());
+
+    let handles = (0..8i64)
+        .map(|n| {
+            let runtime = runtime.clone();
+            let unit = unit.clone();
+
+            thread::spawn(move || -> rune::Result<i64> {
+                let mut vm = Vm::new(runtime, unit);
+                Ok(i64::from_value(vm.call(&["main"], (n,))?)?)
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut results = Vec::new();
+
+    for handle in handles {
+        results.push(handle.join().unwrap()?);
+    }
+
+    assert_eq!(results, vec![1, 3, 5, 7, 9, 11, 13, 15]);
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use rune::semantic::{SemanticKind, SemanticTokens};
+use rune::{Source, SourceId, Sources};
+
+#[test]
+fn test_semantic_tokens() -> rune::Result<()> {
+    let context = rune_modules::default_context()?;
+
+    let mut sources = Sources::new();
+
+    sources.insert(Source::new(
+        "entry",
+        r#"
+        enum Shape { Circle(r) }
+
+        struct Point { x, y }
+
+        fn area(shape) {
+            match shape {
+                Shape::Circle(r) => r * r * 3,
+            }
+        }
+
+        pub fn main() {
+            // Format the area.
+            let points = [Point { x: 1, y: 2 }];
+            points.push(Point { x: 3, y: 4 });
+            format!("{}", area(Shape::Circle(2)))
+        }
+        "#,
+    ));
+
+    let mut semantic = SemanticTokens::new();
+
+    rune::prepare(&mut sources)
+        .with_context(&context)
+        .with_visitor(&mut semantic)
+        .build()?;
+
+    let source = sources.get(SourceId::new(0)).expect("missing source");
+
+    let tokens = semantic
+        .tokens(&sources, SourceId::new(0))
+        .into_iter()
+        .map(|t| (source.get(t.span.range()).unwrap_or_default(), t.kind))
+        .collect::<Vec<_>>();
+
+    let kinds_of = |text: &str| {
+        tokens
+            .iter()
+            .filter(|(t, _)| *t == text)
+            .map(|(_, kind)| *kind)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(kinds_of("Shape"), [SemanticKind::Type; 1]);
+    assert_eq!(kinds_of("Circle"), [SemanticKind::Variant; 3]);
+    assert_eq!(kinds_of("Point"), [SemanticKind::Type; 3]);
+    assert_eq!(kinds_of("area"), [SemanticKind::Function; 2]);
+    assert_eq!(kinds_of("shape"), [SemanticKind::Parameter; 2]);
+    assert_eq!(kinds_of("points"), [SemanticKind::Variable; 2]);
+    assert_eq!(kinds_of("push"), [SemanticKind::Method]);
+    assert_eq!(kinds_of("format"), [SemanticKind::Macro]);
+    assert_eq!(kinds_of("fn"), [SemanticKind::Keyword; 2]);
+    assert_eq!(kinds_of("// Format the area."), [SemanticKind::Comment]);
+    assert_eq!(kinds_of("\"{}\""), [SemanticKind::String]);
+    Ok(())
+}
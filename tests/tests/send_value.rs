@@ -0,0 +1,86 @@
+use rune::runtime::{RuntimeContext, SendValue, VmErrorKind};
+use rune::{Context, FromValue, Source, Sources, Unit, Vm};
+use std::sync::Arc;
+use std::thread;
+
+const SOURCE: &str = r#"
+struct Point { x, y }
+enum Shape { Circle(radius), Rect { w, h }, Empty }
+
+pub fn produce() {
+    let shared = [1, 2];
+    let shapes = [Shape::Circle(2), Shape::Rect { w: 3, h: 4 }, Shape::Empty];
+    (Point { x: 1, y: 2 }, shapes, Ok(Some("yes")), shared, shared, 1..=3)
+}
+
+pub fn consume(value) {
+    let (point, shapes, result, a, b, range) = value;
+    a.push(3);
+
+    let area = 0;
+
+    for shape in shapes {
+        area += match shape {
+            Shape::Circle(r) => r * r * 3,
+            Shape::Rect { w, h } => w * h,
+            Shape::Empty => 0,
+        };
+    }
+
+    let sum = 0;
+
+    for n in range {
+        sum += n;
+    }
+
+    (point.x + point.y, area, result?.unwrap(), a.len(), b.len(), sum)
+}
+
+pub fn closure() {
+    || 1
+}
+"#;
+
+fn compile() -> rune::Result<(Arc<RuntimeContext>, Arc<Unit>)> {
+    let context = Context::with_default_modules()?;
+
+    let mut sources = Sources::new();
+    sources.insert(Source::new("entry", SOURCE));
+
+    let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    Ok((Arc::new(context.runtime()), Arc::new(unit)))
+}
+
+fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+#[test]
+fn test_send_value_across_threads() -> rune::Result<()> {
+    let (context, unit) = compile()?;
+
+    let mut vm = Vm::new(context.clone(), unit.clone());
+    let value = SendValue::from_value(&vm.call(&["produce"], ())?)?;
+    assert_send_sync(&value);
+
+    let output = thread::spawn(move || -> rune::Result<_> {
+        let mut vm = Vm::new(context, unit);
+        let output = vm.call(&["consume"], (value.into_value(),))?;
+        Ok(<(i64, i64, String, usize, usize, i64)>::from_value(output)?)
+    })
+    .join()
+    .unwrap()?;
+
+    // NB: aliased values are copied separately.
+    assert_eq!(output, (3, 24, String::from("yes"), 3, 2, 6));
+    Ok(())
+}
+
+#[test]
+fn test_send_value_unsupported() -> rune::Result<()> {
+    let (context, unit) = compile()?;
+
+    let mut vm = Vm::new(context, unit);
+    let value = vm.call(&["closure"], ())?;
+    let error = SendValue::from_value(&value).unwrap_err();
+    assert!(matches!(error.kind(), VmErrorKind::SendNotSupported { .. }));
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use rune_tests::*;
+use futures_executor::block_on;
+use rune::modules::sync::channel;
+use rune::runtime::{SendValue, VmErrorKind};
+use rune::FromValue;
+use std::thread;
+
+const SOURCE: &str = r#"
+use std::sync::channel;
+
+pub async fn produce(tx, n) {
+    for i in 0..n {
+        tx.send(i * i).await?;
+    }
+}
+
+pub async fn consume(rx) {
+    let sum = 0;
+
+    while let Some(n) = rx.recv().await {
+        sum += n;
+    }
+
+    sum
+}
+
+pub async fn local() {
+    let (tx, rx) = channel::bounded(1);
+    let other = tx.clone();
+    tx.send("a").await?;
+    other.send(["b", "c"]).await?;
+
+    let first = rx.try_recv();
+    let second = rx.recv().await;
+    let empty = rx.try_recv();
+    rx.close();
+
+    (first, second, empty, tx.is_closed(), tx.send(1).await)
+}
+
+pub fn unsupported() {
+    let (tx, rx) = channel::unbounded();
+    tx.try_send(|| 1)
+}
+"#;
+
+#[test]
+fn test_script_to_host() -> rune::Result<()> {
+    let (tx, mut rx) = channel::bounded(2);
+
+    let consumer = thread::spawn(move || {
+        block_on(async move {
+            let mut output = Vec::new();
+
+            while let Some(value) = rx.recv().await {
+                output.push(i64::from_value(value.into_value()).unwrap());
+            }
+
+            output
+        })
+    });
+
+    let mut vm = rune_vm_with!(SOURCE)?;
+    block_on(vm.async_call(&["produce"], (tx, 5i64)))?;
+    drop(vm);
+
+    assert_eq!(consumer.join().unwrap(), vec![0, 1, 4, 9, 16]);
+    Ok(())
+}
+
+#[test]
+fn test_host_to_script() -> rune::Result<()> {
+    let (tx, rx) = channel::unbounded();
+
+    let producer = thread::spawn(move || {
+        block_on(async move {
+            for n in 1..=10 {
+                tx.send(SendValue::Integer(n)).await.unwrap();
+            }
+        })
+    });
+
+    let mut vm = rune_vm_with!(SOURCE)?;
+    let output = block_on(vm.async_call(&["consume"], (rx,)))?;
+    producer.join().unwrap();
+
+    assert_eq!(i64::from_value(output)?, 55);
+    Ok(())
+}
+
+#[test]
+fn test_script_channel() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(SOURCE)?;
+    let output = block_on(vm.async_call(&["local"], ()))?;
+
+    let (first, second, empty, closed, send) = <(
+        Option<String>,
+        Option<Vec<String>>,
+        Option<i64>,
+        bool,
+        Result<(), i64>,
+    )>::from_value(output)?;
+
+    assert_eq!(first.as_deref(), Some("a"));
+    assert_eq!(second, Some(vec![String::from("b"), String::from("c")]));
+    assert_eq!(empty, None);
+    assert!(closed);
+    assert_eq!(send, Err(1));
+    Ok(())
+}
+
+#[test]
+fn test_unsupported_value() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(SOURCE)?;
+    let error = vm.call(&["unsupported"], ()).unwrap_err();
+    assert!(matches!(
+        error.as_unwound().0,
+        VmErrorKind::SendNotSupported { .. }
+    ));
+    Ok(())
+}
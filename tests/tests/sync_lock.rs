@@ -0,0 +1,78 @@
+use rune_tests::*;
+
+#[test]
+fn test_mutex_held_across_await() {
+    let out: (i64, bool, bool) = rune! {
+        use std::sync::{channel, Mutex};
+
+        async fn first(m, rx) {
+            let guard = m.lock().await;
+            let value = guard.get();
+            rx.recv().await;
+            guard.set(value + 1);
+        }
+
+        async fn second(m, tx) {
+            let contended = m.try_lock().is_none();
+            tx.send(1).await?;
+            let guard = m.lock().await;
+            guard.set(guard.get() * 10);
+            Ok(contended)
+        }
+
+        pub async fn main() {
+            let m = Mutex::new(0);
+            let (tx, rx) = channel::unbounded();
+            let (_, contended) = std::future::join(first(m, rx), second(m.clone(), tx)).await;
+            let guard = m.lock().await;
+            (guard.get(), contended?, m.is_locked())
+        }
+    };
+
+    assert_eq!(out, (10, true, true));
+}
+
+#[test]
+fn test_mutex_drop_releases() {
+    let out: (bool, bool, i64) = rune! {
+        use std::sync::Mutex;
+
+        pub fn main() {
+            let m = Mutex::new([1]);
+            let guard = m.try_lock().unwrap();
+            guard.get().push(2);
+            let locked = m.try_lock().is_none();
+            drop(guard);
+            let guard = m.try_lock();
+            (locked, guard.is_some(), guard.unwrap().replace(3).len())
+        }
+    };
+
+    assert_eq!(out, (true, true, 2));
+}
+
+#[test]
+fn test_rwlock() {
+    let out: (bool, bool, bool, i64) = rune! {
+        use std::sync::RwLock;
+
+        pub async fn main() {
+            let lock = RwLock::new(1);
+            let a = lock.read().await;
+            let b = lock.try_read().unwrap();
+            let blocked = lock.try_write().is_none();
+            drop(a);
+            let still_blocked = lock.try_write().is_none();
+            drop(b);
+
+            let writer = lock.write().await;
+            writer.set(writer.get() + 1);
+            let no_readers = lock.try_read().is_none();
+            drop(writer);
+
+            (blocked, still_blocked, no_readers, lock.read().await.get())
+        }
+    };
+
+    assert_eq!(out, (true, true, true, 2));
+}
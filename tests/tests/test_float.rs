@@ -58,3 +58,58 @@ fn test_float_fns() {
     };
     assert_eq!(n, 1728.0);
 }
+
+#[test]
+fn test_float_formatting() {
+    let _: () = rune! {
+        pub fn main() {
+            assert_eq!(3.14159.to_fixed(2), "3.14");
+            assert_eq!(2.5.to_fixed(0), "2");
+            assert_eq!(1.0.to_fixed(3), "1.000");
+
+            assert_eq!(123.456.to_precision(4), "123.5");
+            assert_eq!(0.000123.to_precision(2), "0.00012");
+            assert_eq!(99.99.to_precision(2), "1.0e2");
+            assert_eq!(123456.0.to_precision(2), "1.2e5");
+            assert_eq!(0.0000001234.to_precision(2), "1.2e-7");
+            assert_eq!(0.0.to_precision(3), "0.00");
+        }
+    };
+}
+
+#[test]
+fn test_float_classification() {
+    let _: () = rune! {
+        pub fn main() {
+            let nan = 0.0 / 0.0;
+            let inf = 1.0 / 0.0;
+
+            assert!(nan.is_nan());
+            assert!(!1.0.is_nan());
+            assert!(1.0.is_finite());
+            assert!(!inf.is_finite());
+            assert!(inf.is_infinite());
+
+            assert_eq!(5.0.clamp(0.0, 1.0), 1.0);
+            assert_eq!((-5.0).clamp(0.0, 1.0), 0.0);
+            assert_eq!(1.5.floor(), 1.0);
+            assert_eq!(1.5.ceil(), 2.0);
+            assert_eq!(1.5.round(), 2.0);
+            assert_eq!((-1.5).trunc(), -1.0);
+        }
+    };
+}
+
+#[test]
+fn test_float_parse() {
+    let _: () = rune_s! { r#"
+        pub fn main() {
+            assert_eq!(std::float::parse("1.5e3"), Ok(1500.0));
+
+            match std::float::parse("1,5") {
+                Err(error) => assert_eq!(`${error}`, "invalid float literal"),
+                Ok(..) => panic("expected an error"),
+            }
+        }
+    "# };
+}
@@ -1,3 +1,4 @@
+use rune::runtime::VmErrorKind::*;
 use rune_tests::*;
 
 #[test]
@@ -58,3 +59,46 @@ fn test_int_fns() {
     };
     assert_eq!(n, 1728);
 }
+
+#[test]
+fn test_int_overflow_fns() {
+    let _: () = rune! {
+        pub fn main() {
+            let max = 9223372036854775807;
+            let min = -max - 1;
+
+            assert_eq!(max.checked_add(1), None);
+            assert_eq!(min.checked_neg(), None);
+            assert_eq!(min.checked_abs(), None);
+            assert_eq!(2.checked_pow(62), Some(4611686018427387904));
+            assert_eq!(2.checked_pow(63), None);
+
+            assert_eq!(max.wrapping_add(1), min);
+            assert_eq!(min.wrapping_neg(), min);
+            assert_eq!(3.wrapping_pow(41), -420491770248316829);
+
+            assert_eq!(max.overflowing_add(1), (min, true));
+            assert_eq!(max.overflowing_mul(2), (-2, true));
+            assert_eq!(2.overflowing_mul(3), (6, false));
+            assert_eq!(min.overflowing_neg(), (min, true));
+            assert_eq!(min.overflowing_div(-1), (min, true));
+
+            assert_eq!(min.saturating_sub(1), min);
+            assert_eq!(min.saturating_neg(), max);
+            assert_eq!(min.saturating_div(-1), max);
+        }
+    };
+}
+
+#[test]
+fn test_int_fns_overflow_error() {
+    assert_vm_error!(
+        r#"pub fn main() { 2.pow(64) }"#,
+        Overflow => {}
+    );
+
+    assert_vm_error!(
+        r#"pub fn main() { let min = -9223372036854775807 - 1; min.abs() }"#,
+        Overflow => {}
+    );
+}
@@ -1,4 +1,6 @@
-use rune::Hash;
+use rune::runtime::Inst;
+use rune::{FromValue, Hash, Vm};
+use std::sync::Arc;
 
 #[test]
 fn test_get_const() -> rune::Result<()> {
@@ -77,3 +79,49 @@ fn test_get_const_nested() -> rune::Result<()> {
     );
     Ok(())
 }
+
+#[test]
+fn test_constant_pools_are_deduplicated() -> rune::Result<()> {
+    let context = rune_modules::default_context()?;
+
+    let mut sources = rune::sources! {
+        entry => {
+            const A = (1, "hello", [2.5]);
+            const B = (1, "hello", [2.5]);
+            const C = (2, "hello");
+
+            pub fn main() {
+                let a = A;
+                a.0 = 42;
+                (a, B, C, "hello" == "hello")
+            }
+        }
+    };
+
+    let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+
+    let strings = unit.iter_static_strings().filter(|s| s.as_str() == "hello");
+    assert_eq!(strings.count(), 1);
+
+    let tuples = unit
+        .iter_instructions()
+        .filter_map(|inst| match inst {
+            Inst::StaticTuple { slot } => Some(slot),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(tuples.len(), 3);
+    assert_eq!(tuples[0], tuples[1]);
+    assert_ne!(tuples[0], tuples[2]);
+
+    let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+    let output = vm.call(&["main"], ())?;
+    type Constant = (i64, String, Vec<f64>);
+    let (a, b, _, eq): (Constant, Constant, (i64, String), bool) = FromValue::from_value(output)?;
+
+    assert_eq!(a, (42, String::from("hello"), vec![2.5]));
+    assert_eq!(b, (1, String::from("hello"), vec![2.5]));
+    assert!(eq);
+    Ok(())
+}
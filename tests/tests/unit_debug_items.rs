@@ -0,0 +1,62 @@
+use rune::runtime::VmErrorKind;
+use rune::termcolor::NoColor;
+use rune::{Context, Hash, Module, Source, Sources, Vm};
+use std::sync::Arc;
+
+#[test]
+fn test_missing_function_item() -> rune::Result<()> {
+    let mut module = Module::with_crate("native");
+    module.function(&["missing"], || 42i64)?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(&module)?;
+
+    let mut sources = Sources::new();
+    sources.insert(Source::new(
+        "main",
+        r#"
+        pub fn main() {
+            native::missing()
+        }
+        "#,
+    ));
+
+    let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+
+    let debug = unit.debug_info().expect("missing debug info");
+    assert_eq!(
+        debug
+            .item_for_hash(Hash::type_hash(["main"]))
+            .map(|item| item.to_string()),
+        Some(String::from("main"))
+    );
+
+    // NB: run the unit without the module it was compiled against.
+    let runtime = Arc::new(Context::with_default_modules()?.runtime());
+    let mut vm = Vm::new(runtime, Arc::new(unit));
+    let error = vm.call(["main"], ()).unwrap_err();
+
+    let hash = match error.as_unwound().0 {
+        VmErrorKind::MissingFunction { hash } => *hash,
+        actual => panic!("expected missing function, got {:?}", actual),
+    };
+
+    assert_eq!(
+        context.item_for_hash(hash).map(|item| item.to_string()),
+        Some(String::from("::native::missing"))
+    );
+    assert_eq!(
+        vm.unit()
+            .debug_info()
+            .and_then(|debug| debug.item_for_hash(hash))
+            .map(|item| item.to_string()),
+        Some(String::from("::native::missing"))
+    );
+
+    let mut out = NoColor::new(Vec::new());
+    error.emit(&mut out, &sources)?;
+    let out = String::from_utf8(out.into_inner())?;
+    assert!(out.contains("missing function `::native::missing`"), "{}", out);
+
+    Ok(())
+}
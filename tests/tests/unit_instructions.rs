@@ -0,0 +1,56 @@
+use rune::runtime::{Inst, InstConstant};
+use rune::{Context, Hash, Options, Source, Sources};
+
+#[test]
+fn test_instruction_records() -> rune::Result<()> {
+    let context = Context::with_default_modules()?;
+
+    let mut options = Options::default();
+    options.debug_info(true);
+
+    let mut sources = Sources::new();
+    sources.insert(Source::new(
+        "main",
+        r#"
+        pub fn main(n) {
+            if n > 0 { "positive" } else { #{ a: 1, b: 2 } }
+        }
+        "#,
+    ));
+
+    let unit = rune::prepare(&mut sources)
+        .with_context(&context)
+        .with_options(&options)
+        .build()?;
+
+    let records = unit.instructions().collect::<Vec<_>>();
+    assert_eq!(records.len(), unit.iter_instructions().count());
+
+    let function = records[0].function.as_ref().expect("missing function");
+    assert_eq!(function.hash, Hash::type_hash(["main"]));
+    assert_eq!(
+        function.signature.map(|s| s.to_string()),
+        Some(String::from("main(n)"))
+    );
+
+    for record in &records {
+        assert!(record.span.is_some());
+
+        if let Some(jump) = record.jump {
+            assert!(jump < records.len());
+            assert!(records[jump].label.is_some());
+        }
+    }
+
+    assert!(records.iter().any(|record| matches!(
+        (&record.inst, &record.constant),
+        (Inst::String { .. }, Some(InstConstant::String("positive")))
+    )));
+
+    assert!(records.iter().any(|record| matches!(
+        (&record.inst, &record.constant),
+        (Inst::Object { .. }, Some(InstConstant::ObjectKeys(keys))) if keys == &["a", "b"]
+    )));
+
+    Ok(())
+}
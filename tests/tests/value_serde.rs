@@ -0,0 +1,145 @@
+use rune_tests::*;
+
+#[test]
+fn test_serialize_structs_and_variants() {
+    let out: String = rune! {
+        struct Point { x, y }
+        struct Pair(a, b);
+        enum Shape { Empty, Line(a, b), Circle { radius } }
+
+        pub fn main() {
+            let value = #{
+                point: Point { x: 1, y: 2 },
+                pair: Pair(3, 4),
+                empty: Shape::Empty,
+                line: Shape::Line(5, 6),
+                circle: Shape::Circle { radius: 7.5 },
+                ok: Ok(8),
+                err: Err("bad"),
+            };
+
+            json::to_string(value)?
+        }
+    };
+
+    assert_eq!(
+        out,
+        r#"{"circle":{"Circle":{"radius":7.5}},"empty":"Empty","err":{"Err":"bad"},"line":{"Line":[5,6]},"ok":{"Ok":8},"pair":[3,4],"point":{"x":1,"y":2}}"#
+    );
+}
+
+#[test]
+fn test_deserialize_values() {
+    let out: (f64, (), i64) = rune! {
+        pub fn main() {
+            let value = json::from_string("{\"a\": 1.5, \"b\": [null, 2]}")?;
+            (value.a, value.b[0], value.b[1])
+        }
+    };
+
+    assert_eq!(out, (1.5, (), 2));
+}
+
+#[test]
+fn test_json_pretty_and_round_trip() {
+    let out: (String, String, bool) = rune! {
+        pub fn main() {
+            let value = json::from_string("{\"name\": \"rune\", \"tags\": [1, 2]}")?;
+            value.tags.push(3);
+
+            let pretty = json::to_string_pretty(value)?;
+            let compact = json::to_string(json::from_string(pretty)?)?;
+            let bytes = json::to_bytes_pretty(value)?;
+            (pretty, compact, json::from_bytes(bytes)?.tags.len() == 3)
+        }
+    };
+
+    assert_eq!(
+        out.0,
+        "{\n  \"name\": \"rune\",\n  \"tags\": [\n    1,\n    2,\n    3\n  ]\n}"
+    );
+    assert_eq!(out.1, r#"{"name":"rune","tags":[1,2,3]}"#);
+    assert!(out.2);
+}
+
+#[test]
+fn test_not_serializable() {
+    let out: bool = rune! {
+        pub fn main() {
+            json::to_string(|| 42).is_err()
+        }
+    };
+
+    assert!(out);
+}
+
+#[test]
+fn test_to_value_from_value() -> rune::Result<()> {
+    use rune::{Context, Source, Sources, Vm};
+    use serde::{Deserialize, Serialize};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Shape {
+        Empty,
+        Circle(f64),
+        Line(i64, i64),
+        Rect { w: i64, h: i64 },
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Scene {
+        name: String,
+        shapes: Vec<Shape>,
+        tags: HashMap<String, u32>,
+        parent: Option<Box<Scene>>,
+    }
+
+    let context = Context::with_default_modules()?;
+
+    let mut sources = Sources::new();
+    sources.insert(Source::new(
+        "main",
+        r#"
+        pub fn main(scene) {
+            scene.name = `${scene.name} copy`;
+            scene.shapes.push(#{ Rect: #{ w: 3, h: 4 } });
+            scene.tags.count += 1;
+            scene
+        }
+        "#,
+    ));
+
+    let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+
+    let scene = Scene {
+        name: String::from("scene"),
+        shapes: vec![Shape::Empty, Shape::Circle(1.5), Shape::Line(1, 2)],
+        tags: [(String::from("count"), 1)].into_iter().collect(),
+        parent: None,
+    };
+
+    let output = vm.call(["main"], (rune::to_value(&scene)?,))?;
+    let output = rune::from_value::<Scene>(output)?;
+
+    assert_eq!(
+        output,
+        Scene {
+            name: String::from("scene copy"),
+            shapes: vec![
+                Shape::Empty,
+                Shape::Circle(1.5),
+                Shape::Line(1, 2),
+                Shape::Rect { w: 3, h: 4 },
+            ],
+            tags: [(String::from("count"), 2)].into_iter().collect(),
+            parent: None,
+        }
+    );
+
+    assert!(rune::to_value(u64::MAX).is_err());
+    assert!(rune::from_value::<Scene>(rune::to_value(42)?).is_err());
+    Ok(())
+}
@@ -136,6 +136,16 @@ fn test_bit_ops() {
     op_tests!(0b1100 << 2 = 0b1100 << 2);
     op_tests!(0b1100 >> 2 = 0b1100 >> 2);
     error_test!(0b1 << 64 = Overflow);
+    error_test!(0b1 >> 64 = Overflow);
+    error_test!(0b1 >> -1 = Overflow);
+}
+
+#[test]
+fn test_neg_overflow() {
+    assert_vm_error!(
+        r#"pub fn main() { let a = -9223372036854775807; let b = a - 1; -b }"#,
+        Overflow => {}
+    );
 }
 
 #[test]
@@ -145,3 +155,63 @@ fn test_bitwise_not() {
     };
     assert_eq!(out, !0b10100);
 }
+
+#[test]
+fn test_operand_addressing() {
+    let out: (i64, i64, i64, bool, bool) = rune! {
+        fn one() { 1 }
+
+        pub fn main() {
+            let a = 10;
+            (a - one(), one() - a, (one() + 2) * (one() + 3), a > one(), one() < a)
+        }
+    };
+    assert_eq!(out, (9, -9, 12, true, true));
+
+    let out: (f64, f64, bool, bool) = rune! {
+        pub fn main() {
+            let a = 1.5;
+            let b = 2.0;
+            (a * b, (a + b) / b, a < b, a == 1.5)
+        }
+    };
+    assert_eq!(out, (3.0, 1.75, true, true));
+}
+
+#[test]
+fn test_mixed_operands() {
+    assert_vm_error!(
+        r#"pub fn main() { let a = 1; let b = 2.0; a + b }"#,
+        UnsupportedBinaryOperation { op: "+", .. } => {}
+    );
+
+    assert_vm_error!(
+        r#"pub fn main() { let a = 1; let b = 2.0; a < b }"#,
+        UnsupportedBinaryOperation { op: "<", .. } => {}
+    );
+
+    let out: String = rune!(pub fn main() { let a = "a"; let b = "b"; a + b });
+    assert_eq!(out, "ab");
+}
+
+#[test]
+fn test_vec_index() {
+    let out: (i64, i64) = rune! {
+        pub fn main() {
+            let values = [1, 2, 3];
+            let total = 0;
+
+            for n in 0..3 {
+                total += values[n];
+            }
+
+            (total, [4, 5][1])
+        }
+    };
+    assert_eq!(out, (6, 5));
+
+    assert_vm_error!(
+        r#"pub fn main() { let values = [1, 2, 3]; let n = -1; values[n] }"#,
+        MissingIndex { .. } => {}
+    );
+}
@@ -0,0 +1,76 @@
+use rune::runtime::VmErrorKind::*;
+use rune_tests::*;
+
+#[test]
+fn test_bigint_arithmetic() {
+    let out: (String, String, String) = rune_s! { r#"
+        use std::num::BigInt;
+
+        pub fn main() {
+            let n = BigInt::new(9223372036854775807);
+            n += 1;
+            n = n * n;
+
+            let factorial = BigInt::new(1);
+
+            for i in 1..=30 {
+                factorial *= i;
+            }
+
+            let x = BigInt::parse("-123456789012345678901234567890").unwrap();
+            (`${n}`, `${factorial}`, `${x / 1000 % 1000 - 1}`)
+        }
+    "# };
+
+    assert_eq!(
+        out,
+        (
+            String::from("85070591730234615865843651857942052864"),
+            String::from("265252859812191058636308480000000"),
+            String::from("-568")
+        )
+    );
+}
+
+#[test]
+fn test_bigint_methods() {
+    let _: () = rune! {
+        use std::num::BigInt;
+
+        pub fn main() {
+            let a = BigInt::parse_radix("ff", 16).unwrap();
+            assert_eq!(a, 255);
+            assert_eq!(a, BigInt::new(255));
+            assert_eq!(a.to_int(), Some(255));
+            assert_eq!(a.pow(20).to_int(), None);
+            assert_eq!(a.to_string_radix(2), "11111111");
+            assert_eq!(a.neg().abs(), a);
+            assert_eq!(a.neg().signum(), -1);
+            assert!((a - a).is_zero());
+            assert_eq!(a.gcd(85), 85);
+            assert_eq!(BigInt::new(4).modpow(13, 497), 445);
+            assert_eq!(BigInt::parse("nope"), None);
+            assert_eq!(a.to_float(), 255.0);
+
+            let values = [BigInt::new(3), BigInt::new(1), BigInt::new(2)];
+            values.sort();
+            assert_eq!(values, [1, 2, 3]);
+            assert!(a.cmp(300).is_lt());
+        }
+    };
+}
+
+#[test]
+fn test_bigint_errors() {
+    assert_vm_error!(
+        r#"pub fn main() { std::num::BigInt::new(1) / 0 }"#,
+        DivideByZero => {}
+    );
+
+    assert_vm_error!(
+        r#"pub fn main() { std::num::BigInt::new(1).to_string_radix(1) }"#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "radix must be between 2 and 36, but was 1");
+        }
+    );
+}
@@ -0,0 +1,84 @@
+use rune_tests::*;
+
+#[test]
+fn test_bytes_api() {
+    let _: () = rune! {
+        use std::bytes::Bytes;
+
+        pub fn main() {
+            let b = Bytes::from_str("GIF89a");
+            b.push(b'\0');
+            assert_eq!(b.len(), 7);
+            assert_eq!(b.get(0), Some(b'G'));
+            assert_eq!(b.get(7), None);
+            assert_eq!(b.slice(3, 6), Some(b"89a"));
+            assert_eq!(b.slice(3, 8), None);
+
+            assert!(b.starts_with(b"GIF"));
+            assert!(b.ends_with(b"a\0"));
+            assert_eq!(b.find(b"89"), Some(3));
+            assert_eq!(b.find(b"87"), None);
+            assert!(b.contains(b"F8"));
+
+            assert_eq!(b"ab" + b"cd", b"abcd");
+            assert_eq!(b"ab".concat(b"cd"), b"abcd");
+            assert_eq!(b"abc".iter().rev().collect::<Vec>(), [b'c', b'b', b'a']);
+
+            let out = [];
+
+            for b in b"\x01\x02" {
+                out.push(b);
+            }
+
+            assert_eq!(out, [b'\x01', b'\x02']);
+        }
+    };
+}
+
+#[test]
+fn test_bytes_conversions() {
+    let _: () = rune! {
+        use std::bytes::Bytes;
+
+        pub fn main() {
+            assert_eq!(b"\x00\xffA".to_hex(), "00ff41");
+            assert_eq!(Bytes::from_hex("00FF41"), Some(b"\x00\xffA"));
+            assert_eq!(Bytes::from_hex("0"), None);
+            assert_eq!(Bytes::from_hex("zz"), None);
+
+            assert_eq!(b"hello".to_string(), Ok("hello"));
+            assert!(b"\xff".to_string().is_err());
+            assert_eq!(b"a\xffb".to_string_lossy(), "a\u{fffd}b");
+        }
+    };
+}
+
+#[test]
+fn test_bytes_reader() {
+    let _: () = rune! {
+        use std::bytes::Bytes;
+
+        pub fn main() {
+            let r = b"\x01\x02\x00\x00\x03\xff\xfe\x00\x00\xc0\x3f\x05".reader();
+
+            assert_eq!(r.read_u8(), Some(1));
+            assert_eq!(r.read_u16_le(), Some(2));
+            assert_eq!(r.read_u16_be(), Some(3));
+            assert_eq!(r.read_i16_be(), Some(-2));
+            assert_eq!(r.read_f32_le(), Some(1.5));
+            assert_eq!(r.position(), 11);
+            assert_eq!(r.remaining(), 1);
+
+            assert_eq!(r.read_u32_le(), None);
+            assert_eq!(r.position(), 11);
+            assert_eq!(r.read_bytes(1), Some(b"\x05"));
+            assert_eq!(r.read_u8(), None);
+
+            assert!(r.seek(1));
+            assert!(!r.seek(13));
+            assert!(r.skip(2));
+            assert_eq!(r.read_u32_be(), Some(262142));
+            assert!(!r.skip(10));
+        }
+    };
+}
@@ -1,5 +1,7 @@
 use rune::runtime::{Object, Tuple, Vec};
 use rune_tests::*;
+use rune::{Context, FromValue, Options, Source, Sources, Vm};
+use std::sync::Arc;
 
 macro_rules! test_op {
     ($ty:ty => $lhs:literal $op:tt $rhs:literal = $result:literal) => {{
@@ -265,3 +267,160 @@ fn test_const_block() {
 
     assert_eq!(result, "Hello World");
 }
+
+#[test]
+fn test_const_for_loops() {
+    let result: (i64, i64, i64, i64) = rune! {
+        const PRIMES = {
+            let sum = 0;
+
+            'outer: for n in 2..50 {
+                for d in 2..n {
+                    if d * d > n {
+                        break;
+                    }
+
+                    if n % d == 0 {
+                        continue 'outer;
+                    }
+                }
+
+                sum += n;
+            }
+
+            sum
+        };
+
+        const fn factorial(n) {
+            let out = 1;
+
+            for i in 1..=n {
+                out *= i;
+            }
+
+            out
+        }
+
+        const EVENS = {
+            let count = 0;
+            let n = 0;
+
+            while n < 10 {
+                n += 1;
+
+                if n % 2 == 1 {
+                    continue;
+                }
+
+                count += 1;
+            }
+
+            count
+        };
+
+        const SUM = {
+            let sum = 0;
+
+            for value in [1, 2, 3] {
+                sum += value;
+            }
+
+            for n in 0..100 {
+                if n == 3 {
+                    break;
+                }
+
+                sum += n;
+            }
+
+            sum
+        };
+
+        pub fn main() { (PRIMES, factorial(10), EVENS, SUM) }
+    };
+
+    assert_eq!(result, (328, 3628800, 5, 9));
+}
+
+#[test]
+fn test_const_budget() -> rune::Result<()> {
+    let source = r#"
+    const VALUE = {
+        let sum = 0;
+
+        for n in 0..1000 {
+            sum += n;
+        }
+
+        sum
+    };
+
+    pub fn main() { VALUE }
+    "#;
+
+    let context = Context::with_default_modules()?;
+
+    let mut options = Options::default();
+    options.const_budget(1000);
+
+    let mut sources = Sources::new();
+    sources.insert(Source::new("main", source));
+
+    let result = rune::prepare(&mut sources)
+        .with_context(&context)
+        .with_options(&options)
+        .build();
+
+    assert!(result.is_err());
+
+    let mut sources = Sources::new();
+    sources.insert(Source::new("main", source));
+
+    let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+    assert_eq!(i64::from_value(vm.call(["main"], ())?)?, 499500);
+    Ok(())
+}
+
+#[test]
+fn test_const_fn_across_modules() {
+    let result: (i64, i64, i64, i64) = rune! {
+        mod math {
+            pub const fn square(n) {
+                n * n
+            }
+
+            pub const fn sum_of_squares(n) {
+                let sum = 0;
+
+                for i in 0..n {
+                    sum += square(i) * FACTOR;
+                }
+
+                sum
+            }
+
+            const FACTOR = 2;
+
+            pub mod nested {
+                pub const fn cube(n) {
+                    super::square(n) * n
+                }
+            }
+        }
+
+        mod tables {
+            use crate::math::square;
+
+            pub const SQUARE = square(7);
+            pub const CUBE = super::math::nested::cube(3);
+        }
+
+        const SUM = math::sum_of_squares(4);
+        const DOUBLE = crate::math::square(tables::SQUARE);
+
+        pub fn main() { (tables::SQUARE, tables::CUBE, SUM, DOUBLE) }
+    };
+
+    assert_eq!(result, (49, 27, 28, 2401));
+}
@@ -0,0 +1,93 @@
+use rune_tests::*;
+use rune::FromValue;
+
+#[test]
+fn test_collect_object_cycle() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(r#"
+        pub fn main() {
+            let a = #{};
+            let b = #{ a: a };
+            a.b = b;
+        }
+    "#)?;
+
+    assert_eq!(vm.collect_cycles(), 0);
+    assert_eq!(vm.tracked_values(), None);
+
+    vm.set_cycle_collection(true);
+    vm.call(&["main"], ())?;
+    assert_eq!(vm.collect_cycles(), 2);
+    assert_eq!(vm.tracked_values(), Some(0));
+    assert_eq!(vm.collect_cycles(), 0);
+    Ok(())
+}
+
+#[test]
+fn test_collect_closure_cycle() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(r#"
+        struct Node { children, callback }
+
+        pub fn main() {
+            let node = Node { children: [], callback: () };
+            node.callback = || node.children.len();
+            node.children.push(node);
+            let callback = node.callback;
+            callback()
+        }
+    "#)?;
+
+    vm.set_cycle_collection(true);
+    let output = vm.call(&["main"], ())?;
+    assert_eq!(i64::from_value(output)?, 1);
+
+    // The struct, its vector of children and the closure.
+    assert_eq!(vm.collect_cycles(), 3);
+    assert_eq!(vm.tracked_values(), Some(0));
+    Ok(())
+}
+
+#[test]
+fn test_reachable_cycles_are_kept() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(r#"
+        pub fn main() {
+            let a = [];
+            a.push(a);
+            (a, [1, 2, 3])
+        }
+    "#)?;
+
+    vm.set_cycle_collection(true);
+    let (a, b) = <(rune::Value, Vec<i64>)>::from_value(vm.call(&["main"], ())?)?;
+    assert_eq!(vm.collect_cycles(), 0);
+    assert_eq!(b, vec![1, 2, 3]);
+
+    let a = a.into_vec()?;
+    assert_eq!(a.borrow_ref()?.len(), 1);
+
+    drop(a);
+    assert_eq!(vm.collect_cycles(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_collect_releases_memory() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(r#"
+        pub fn main() {
+            for n in 0..100 {
+                let a = #{ n: n };
+                a.a = a;
+            }
+        }
+    "#)?;
+
+    vm.set_cycle_collection(true);
+    vm.set_memory_limit(Some(usize::MAX));
+
+    vm.call(&["main"], ())?;
+    let used = vm.memory_used().unwrap();
+    assert_eq!(vm.tracked_values(), Some(100));
+
+    assert_eq!(vm.collect_cycles(), 100);
+    assert!(vm.memory_used().unwrap() < used);
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use rune_tests::*;
+use rune::ast::Span;
+use rune::runtime::{DebugAction, DebugContext, DebugListener, DebugOutcome};
+use rune::{Context, FromValue, SourceId};
+use std::sync::{Arc, Mutex};
+
+const SOURCE: &str = r#"
+pub fn main() {
+    let a = 1;
+    let b = a + 2;
+    b * 2
+}
+"#;
+
+fn span_of(needle: &str) -> Span {
+    let start = SOURCE.find(needle).expect("missing needle");
+    Span::new(start, start + needle.len())
+}
+
+#[derive(Clone, Default)]
+struct Recorder {
+    instructions: Arc<Mutex<Vec<usize>>>,
+    breakpoints: Arc<Mutex<Vec<Span>>>,
+}
+
+impl DebugListener for Recorder {
+    fn before_instruction(&mut self, cx: &DebugContext<'_>) -> DebugAction {
+        self.instructions.lock().unwrap().push(cx.ip());
+        DebugAction::Continue
+    }
+
+    fn breakpoint(&mut self, cx: &DebugContext<'_>) -> DebugAction {
+        self.breakpoints.lock().unwrap().extend(cx.span());
+        DebugAction::Pause
+    }
+}
+
+#[test]
+fn test_debug_listener() -> rune::Result<()> {
+    let mut vm = vm_from_source(&Context::new(), SOURCE)?;
+    let recorder = Recorder::default();
+    vm.set_debug_listener(recorder.clone());
+
+    let value = vm.execute(&["main"], ())?.complete()?;
+    assert_eq!(i64::from_value(value)?, 6);
+
+    let instructions = recorder.instructions.lock().unwrap();
+    assert!(!instructions.is_empty());
+    assert!(recorder.breakpoints.lock().unwrap().is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_breakpoint_pause_resume() -> rune::Result<()> {
+    let mut vm = vm_from_source(&Context::new(), SOURCE)?;
+    let recorder = Recorder::default();
+    vm.set_debug_listener(recorder.clone());
+
+    let breakpoint = span_of("let b = a + 2;");
+    vm.add_breakpoint(SourceId::new(0), breakpoint);
+
+    let mut execution = vm.execute(&["main"], ())?;
+
+    assert!(matches!(execution.resume_debug()?, DebugOutcome::Paused));
+
+    {
+        let hits = recorder.breakpoints.lock().unwrap();
+        assert_eq!(hits.len(), 1);
+        assert!(breakpoint.start <= hits[0].start && hits[0].end <= breakpoint.end);
+    }
+
+    let value = match execution.resume_debug()? {
+        DebugOutcome::Complete(value) => value,
+        outcome => panic!("unexpected outcome: {:?}", outcome),
+    };
+
+    assert_eq!(i64::from_value(value)?, 6);
+    assert_eq!(recorder.breakpoints.lock().unwrap().len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_single_step() -> rune::Result<()> {
+    let mut vm = vm_from_source(&Context::new(), SOURCE)?;
+    vm.set_single_step(true);
+
+    let mut execution = vm.execute(&["main"], ())?;
+    let mut pauses = 0;
+
+    let value = loop {
+        match execution.resume_debug()? {
+            DebugOutcome::Paused => pauses += 1,
+            DebugOutcome::Complete(value) => break value,
+            outcome => panic!("unexpected outcome: {:?}", outcome),
+        }
+    };
+
+    assert!(pauses > 3);
+    assert_eq!(i64::from_value(value)?, 6);
+    Ok(())
+}
+
+#[test]
+fn test_pause_without_debug_resume() -> rune::Result<()> {
+    let mut vm = vm_from_source(&Context::new(), SOURCE)?;
+    vm.set_single_step(true);
+
+    let error = vm.execute(&["main"], ())?.complete().unwrap_err();
+    assert!(error.to_string().contains("paused"));
+    Ok(())
+}
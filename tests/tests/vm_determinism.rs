@@ -0,0 +1,81 @@
+use rune_tests::*;
+use futures_executor::block_on;
+use rune::runtime::Determinism;
+use rune::{FromValue, Vm};
+use std::time::Duration;
+
+const SOURCE: &str = r#"
+use std::collections::{HashMap, HashSet};
+
+pub fn random() {
+    let rng = rand::WyRand::new();
+    let pcg = rand::Pcg64::new();
+    [rng.int(), pcg.int(), rand::Rng::new().int(), rand::int()?, rand::int_range(0, 1000)?]
+}
+
+pub fn ordered() {
+    let map = HashMap::new();
+    let set = HashSet::new();
+
+    for n in 0..64 {
+        map.insert(n * 7 % 64, n);
+        set.insert(n * 7 % 64);
+    }
+
+    (map.keys().collect::<Vec>(), set.iter().collect::<Vec>(), format!("{:?}", map))
+}
+
+pub async fn sleep() {
+    time::sleep(time::Duration::from_secs(10)).await;
+    time::sleep(time::Duration::from_secs(5)).await;
+}
+"#;
+
+fn vm(seed: Option<u64>) -> rune::Result<Vm> {
+    let mut vm = vm_from_source(&rune_modules::default_context()?, SOURCE)?;
+    vm.set_determinism(seed.map(Determinism::new));
+    Ok(vm)
+}
+
+fn random(seed: u64) -> rune::Result<Vec<i64>> {
+    Ok(Vec::<i64>::from_value(vm(Some(seed))?.call(&["random"], ())?)?)
+}
+
+#[test]
+fn test_seeded_rng() -> rune::Result<()> {
+    assert_eq!(random(1)?, random(1)?);
+    assert_ne!(random(1)?, random(2)?);
+    Ok(())
+}
+
+#[test]
+fn test_stable_iteration_order() -> rune::Result<()> {
+    let mut vm = vm(Some(0))?;
+    let value = vm.call(&["ordered"], ())?;
+    let (keys, set, debug) = <(Vec<i64>, Vec<i64>, String)>::from_value(value)?;
+
+    assert_eq!(keys, (0..64).collect::<Vec<_>>());
+    assert_eq!(set, (0..64).collect::<Vec<_>>());
+    assert!(debug.starts_with("{0: 0, 1: 55, 2: 46, 3: 37,"));
+    Ok(())
+}
+
+#[test]
+fn test_virtual_clock() -> rune::Result<()> {
+    let mut vm = vm(Some(0))?;
+    let determinism = vm.determinism().cloned().expect("missing determinism");
+
+    block_on(vm.async_call(&["sleep"], ()))?;
+    assert_eq!(determinism.now(), Duration::from_secs(15));
+    Ok(())
+}
+
+#[test]
+fn test_nondeterministic() -> rune::Result<()> {
+    let mut vm = vm(None)?;
+    assert!(vm.determinism().is_none());
+
+    let values = Vec::<i64>::from_value(vm.call(&["random"], ())?)?;
+    assert_eq!(values.len(), 5);
+    Ok(())
+}
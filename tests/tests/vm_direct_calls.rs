@@ -0,0 +1,100 @@
+use rune::runtime::Inst;
+use rune::runtime::VmErrorKind::*;
+use rune::Context;
+use rune_tests::*;
+
+#[test]
+fn test_calls_are_specialized() -> rune::Result<()> {
+    let context = Context::with_default_modules()?;
+
+    let unit = build(
+        &context,
+        r#"
+        fn add(a, b) {
+            a + b
+        }
+
+        pub fn main() {
+            let n = add(1, 2);
+            std::string::String::new();
+            n
+        }
+        "#,
+    )?;
+
+    let mut offsets = 0;
+    let mut calls = 0;
+
+    for inst in unit.iter_instructions() {
+        match inst {
+            Inst::CallOffset { args, .. } => {
+                assert_eq!(args, 2);
+                offsets += 1;
+            }
+            Inst::Call { .. } => {
+                calls += 1;
+            }
+            _ => (),
+        }
+    }
+
+    assert_eq!((offsets, calls), (1, 1));
+    Ok(())
+}
+
+#[test]
+fn test_calling_conventions() {
+    let out: (i64, i64, i64, i64) = rune! {
+        fn fib(n) {
+            if n < 2 { n } else { fib(n - 1) + fib(n - 2) }
+        }
+
+        fn numbers() {
+            yield 1;
+            yield 2;
+        }
+
+        async fn double(n) {
+            n * 2
+        }
+
+        async fn run() {
+            double(21).await
+        }
+
+        pub fn main() {
+            let total = 0;
+
+            for n in numbers() {
+                total += n;
+            }
+
+            let future = run();
+            (fib(15), total, if future is std::future::Future { 1 } else { 0 }, reexported())
+        }
+
+        use self::fib as reexported_fib;
+
+        fn reexported() {
+            reexported_fib(10)
+        }
+    };
+
+    assert_eq!(out, (610, 3, 1, 55));
+}
+
+#[test]
+fn test_bad_argument_count() {
+    assert_vm_error!(
+        r#"
+        fn add(a, b) {
+            a + b
+        }
+
+        pub fn main() {
+            add(1)
+        }
+        "#,
+        BadArgumentCount { actual: 1, expected: 2 } => {}
+    );
+}
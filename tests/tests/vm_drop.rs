@@ -0,0 +1,143 @@
+use rune_tests::*;
+use rune::{Module, Vm};
+use std::sync::{Arc, Mutex};
+
+fn vm(source: &str) -> rune::Result<(Vm, Arc<Mutex<Vec<String>>>)> {
+    let log = Arc::new(Mutex::new(Vec::new()));
+
+    let mut module = Module::with_crate("native");
+
+    let output = log.clone();
+    module.function(&["log"], move |message: String| {
+        output.lock().unwrap().push(message);
+    })?;
+
+    let vm = rune_vm_with!(module => source)?;
+    Ok((vm, log))
+}
+
+#[test]
+fn test_drop_on_last_reference() -> rune::Result<()> {
+    let (mut vm, log) = vm(r#"
+        struct Connection { name }
+
+        impl Connection {
+            fn drop(self) {
+                native::log(`closed ${self.name}`);
+            }
+        }
+
+        fn open(name) {
+            let conn = Connection { name };
+            native::log(`opened ${conn.name}`);
+            conn
+        }
+
+        pub fn main() {
+            let a = open("a");
+            let b = a;
+            open("b");
+            native::log("end");
+        }
+    "#)?;
+
+    vm.call(&["main"], ())?;
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        ["opened a", "opened b", "closed b", "end", "closed a"]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_explicit_drop() -> rune::Result<()> {
+    let (mut vm, log) = vm(r#"
+        use std::mem;
+
+        enum Resource { File(path), Socket }
+
+        impl Resource {
+            fn drop(self) {
+                match self {
+                    Resource::File(path) => native::log(`closed ${path}`),
+                    Resource::Socket => native::log("closed socket"),
+                }
+            }
+        }
+
+        pub fn main() {
+            let file = Resource::File("a.txt");
+            let socket = Resource::Socket;
+            mem::drop(file);
+            native::log("dropped");
+        }
+    "#)?;
+
+    vm.call(&["main"], ())?;
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        ["closed a.txt", "dropped", "closed socket"]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_drop_errors_are_recorded() -> rune::Result<()> {
+    let (mut vm, log) = vm(r#"
+        struct Broken;
+
+        impl Broken {
+            fn drop(self) {
+                native::log("dropping");
+                1 + "a"
+            }
+        }
+
+        pub fn main() {
+            let broken = Broken;
+            native::log("end");
+        }
+
+        pub fn nested() {
+            [1, 2].iter().map(|n| { let broken = Broken; n }).collect::<Vec>()
+        }
+    "#)?;
+
+    vm.call(&["main"], ())?;
+    assert_eq!(*log.lock().unwrap(), ["end", "dropping"]);
+    assert_eq!(vm.take_drop_errors().len(), 1);
+    assert!(vm.take_drop_errors().is_empty());
+
+    // Errors raised in virtual machines spawned to call closures are handed
+    // over to the one which spawned them.
+    vm.call(&["nested"], ())?;
+    assert_eq!(vm.take_drop_errors().len(), 2);
+    Ok(())
+}
+
+#[test]
+fn test_drop_not_called_for_collected_cycles() -> rune::Result<()> {
+    let (mut vm, log) = vm(r#"
+        struct Node { other }
+
+        impl Node {
+            fn drop(self) {
+                native::log("dropped");
+            }
+        }
+
+        pub fn main() {
+            let a = Node { other: () };
+            let b = Node { other: a };
+            a.other = b;
+        }
+    "#)?;
+
+    vm.set_cycle_collection(true);
+    vm.call(&["main"], ())?;
+    assert_eq!(vm.collect_cycles(), 2);
+    assert!(log.lock().unwrap().is_empty());
+    Ok(())
+}
@@ -0,0 +1,88 @@
+//! Tests for ambient host data which native functions can access.
+
+use rune_tests::*;
+use futures_executor::block_on;
+use rune::runtime::Extensions;
+use rune::{ContextError, FromValue, Module};
+use std::sync::{Arc, Mutex};
+
+struct RequestId(i64);
+
+#[derive(Default)]
+struct Logger {
+    lines: Mutex<Vec<String>>,
+}
+
+fn request_id() -> Option<i64> {
+    Extensions::current().get::<RequestId>().map(|id| id.0)
+}
+
+fn log(message: &str) {
+    if let Some(logger) = Extensions::current().get::<Arc<Logger>>() {
+        logger.lines.lock().unwrap().push(message.to_owned());
+    }
+}
+
+fn make_module() -> Result<Module, ContextError> {
+    let mut module = Module::with_crate("host");
+    module.function(&["request_id"], request_id)?;
+    module.function(&["log"], log)?;
+    Ok(module)
+}
+
+#[test]
+fn test_extensions_map() {
+    let mut extensions = Extensions::new();
+    assert!(extensions.is_empty());
+
+    assert!(extensions.insert(RequestId(1)).is_none());
+    let old = extensions.insert(RequestId(2)).expect("expected old value");
+    assert_eq!(old.0, 1);
+
+    let copy = extensions.clone();
+    assert!(extensions.remove::<RequestId>().is_some());
+    assert!(!extensions.contains::<RequestId>());
+    assert_eq!(copy.get::<RequestId>().map(|id| id.0), Some(2));
+    assert_eq!(copy.len(), 1);
+}
+
+#[test]
+fn test_vm_extensions() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        make_module()? => r#"
+        pub fn main() {
+            host::log("start");
+            let ids = [1, 2].iter().map(|_| host::request_id()).collect::<Vec>();
+            host::log("end");
+            ids
+        }
+
+        pub async fn nested() {
+            let f = async { host::request_id() };
+            f.await
+        }
+        "#,
+    )?;
+
+    let logger = Arc::new(Logger::default());
+
+    assert_eq!(
+        Vec::<Option<i64>>::from_value(vm.call(&["main"], ())?)?,
+        [None, None]
+    );
+
+    vm.extensions_mut().insert(RequestId(42));
+    vm.extensions_mut().insert(logger.clone());
+    assert_eq!(vm.extensions().len(), 2);
+
+    assert_eq!(
+        Vec::<Option<i64>>::from_value(vm.call(&["main"], ())?)?,
+        [Some(42), Some(42)]
+    );
+
+    let output = Option::<i64>::from_value(block_on(vm.async_call(&["nested"], ()))?)?;
+    assert_eq!(output, Some(42));
+
+    assert_eq!(*logger.lines.lock().unwrap(), ["start", "end"]);
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use rune_tests::*;
+use rune::runtime::VmErrorKind;
+use rune::FromValue;
+
+fn is_out_of_fuel(error: &rune::runtime::VmError) -> bool {
+    matches!(error.as_unwound().0, VmErrorKind::OutOfFuel)
+}
+
+#[test]
+fn test_infinite_loop() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(r#"pub fn main() { loop {} }"#)?;
+    vm.set_fuel(Some(1000));
+
+    let error = vm.call(&["main"], ()).unwrap_err();
+    assert!(is_out_of_fuel(&error));
+    assert_eq!(vm.fuel(), Some(0));
+    Ok(())
+}
+
+#[test]
+fn test_unlimited() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(r#"pub fn main() { let n = 0; while n < 1000 { n += 1; } n }"#)?;
+    assert_eq!(vm.fuel(), None);
+    vm.add_fuel(10);
+    assert_eq!(vm.fuel(), None);
+
+    let value = vm.call(&["main"], ())?;
+    assert_eq!(i64::from_value(value)?, 1000);
+    Ok(())
+}
+
+#[test]
+fn test_refuel_and_resume() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(r#"pub fn main() { let n = 0; while n < 1000 { n += 1; } n }"#)?;
+    vm.set_fuel(Some(10));
+
+    let mut execution = vm.execute(&["main"], ())?;
+    let mut refuels = 0;
+
+    let value = loop {
+        match execution.complete() {
+            Ok(value) => break value,
+            Err(error) if is_out_of_fuel(&error) => {
+                refuels += 1;
+                execution.vm_mut().add_fuel(10);
+            }
+            Err(error) => return Err(error.into()),
+        }
+    };
+
+    assert!(refuels > 100);
+    assert_eq!(i64::from_value(value)?, 1000);
+    Ok(())
+}
+
+#[test]
+fn test_closure_called_from_native() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(r#"
+        pub fn main() {
+            [1, 2, 3].iter().map(|n| { loop {} }).collect::<Vec>()
+        }
+    "#)?;
+
+    vm.set_fuel(Some(1000));
+
+    let error = vm.call(&["main"], ()).unwrap_err();
+    assert!(is_out_of_fuel(&error));
+    Ok(())
+}
+
+#[test]
+fn test_generator() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(r#"
+        fn counter() {
+            loop { yield 1; }
+        }
+
+        pub fn main() {
+            let sum = 0;
+
+            for n in counter() {
+                sum += n;
+            }
+
+            sum
+        }
+    "#)?;
+
+    vm.set_fuel(Some(1000));
+
+    let error = vm.call(&["main"], ()).unwrap_err();
+    assert!(is_out_of_fuel(&error));
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use rune::runtime::VmErrorKind::*;
+use rune_tests::*;
+
+#[test]
+fn test_future_join() {
+    let out: ((i64, i64, i64), (i64, i64), (i64,)) = rune! {
+        async fn double(n) {
+            n * 2
+        }
+
+        pub async fn main() {
+            (
+                std::future::join(double(1), double(2), double(3)).await,
+                std::future::join((double(4), double(5))).await,
+                std::future::join(double(6)).await,
+            )
+        }
+    };
+
+    assert_eq!(out, ((2, 4, 6), (8, 10), (12,)));
+}
+
+#[test]
+fn test_future_join_all() {
+    let out: Vec<i64> = rune! {
+        async fn double(n) {
+            n * 2
+        }
+
+        pub async fn main() {
+            std::future::join_all((0..4).iter().map(double)).await
+        }
+    };
+
+    assert_eq!(out, vec![0, 2, 4, 6]);
+}
+
+#[test]
+fn test_future_race() {
+    let out: i64 = rune! {
+        use std::sync::channel;
+
+        pub async fn main() {
+            let (tx, rx) = channel::bounded(1);
+
+            let pending = async {
+                // NB: nothing is ever sent.
+                rx.recv().await;
+                1
+            };
+
+            let ready = async { 2 };
+            let out = std::future::race([pending, ready]).await;
+            drop(tx);
+            out
+        }
+    };
+
+    assert_eq!(out, 2);
+
+    assert_vm_error!(
+        r#"pub async fn main() { std::future::race([]).await }"#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "cannot race an empty collection of futures");
+        }
+    );
+}
@@ -0,0 +1,78 @@
+//! Tests for global variables defined on a virtual machine.
+
+use rune_tests::*;
+use futures_executor::block_on;
+use rune::runtime::VmErrorKind;
+use rune::FromValue;
+
+#[test]
+fn test_vm_globals() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        r#"
+        mod config {
+            pub fn limit() { global::limit }
+        }
+
+        pub fn main() {
+            let values = [1, 2, 3].iter().map(|n| n * global::scale).collect::<Vec>();
+            (values, config::limit(), globals().name)
+        }
+
+        pub async fn later() {
+            let f = async { global::scale };
+            f.await
+        }
+        "#,
+    )?;
+
+    vm.set_global("scale", 10i64)?;
+    vm.set_global("limit", 100i64)?;
+    vm.set_global("name", "rune")?;
+
+    let output = <(Vec<i64>, i64, String)>::from_value(vm.call(&["main"], ())?)?;
+    assert_eq!(output, (vec![10, 20, 30], 100, String::from("rune")));
+
+    let output = i64::from_value(block_on(vm.async_call(&["later"], ()))?)?;
+    assert_eq!(output, 10);
+
+    assert!(vm.global("scale")?.is_some());
+    assert!(vm.remove_global("scale")?.is_some());
+    assert!(vm.global("scale")?.is_none());
+    Ok(())
+}
+
+#[test]
+fn test_vm_missing_global() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        r#"
+        pub fn main() { global::missing }
+        pub fn object() { globals() }
+        "#,
+    )?;
+
+    let error = vm.call(&["main"], ()).unwrap_err();
+
+    match error.into_unwound().0.into_kind() {
+        VmErrorKind::MissingGlobal { name } => assert_eq!(name, "missing"),
+        actual => panic!("expected missing global but got {:?}", actual),
+    }
+
+    let object = rune::runtime::Object::from_value(vm.call(&["object"], ())?)?;
+    assert!(object.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_global_module_takes_precedence() {
+    let out: i64 = rune_tests::rune! {
+        mod global {
+            pub const scale = 2;
+        }
+
+        pub fn main() {
+            global::scale
+        }
+    };
+
+    assert_eq!(out, 2);
+}
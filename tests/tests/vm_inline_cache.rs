@@ -0,0 +1,100 @@
+use rune::runtime::VmErrorKind::*;
+use rune::{Any, Module};
+use rune_tests::*;
+
+#[test]
+fn test_polymorphic_call_site() {
+    let out: (i64, i64) = rune! {
+        struct Circle { r }
+        struct Square { side }
+
+        impl Circle {
+            fn area(self) {
+                self.r * self.r * 3
+            }
+        }
+
+        impl Square {
+            fn area(self) {
+                self.side * self.side
+            }
+        }
+
+        pub fn main() {
+            let shapes = [Circle { r: 1 }, Square { side: 2 }];
+            let total = 0;
+            let lengths = 0;
+
+            for n in 0..100 {
+                total += shapes[n % 2].area();
+                lengths += [n].len() + "ab".len();
+            }
+
+            (total, lengths)
+        }
+    };
+
+    assert_eq!(out, (350, 300));
+}
+
+#[test]
+fn test_cached_field_functions() {
+    #[derive(Any)]
+    struct Counter {
+        #[rune(get, set)]
+        value: i64,
+    }
+
+    let mut module = Module::new();
+    module.ty::<Counter>().unwrap();
+    module
+        .function(&["Counter", "new"], || Counter { value: 0 })
+        .unwrap();
+
+    let out: i64 = rune_n! {
+        module,
+        (),
+        i64 => pub fn main() {
+            let counter = Counter::new();
+
+            for n in 0..100 {
+                counter.value = counter.value + n;
+            }
+
+            counter.value
+        }
+    };
+
+    assert_eq!(out, 4950);
+}
+
+#[test]
+fn test_missing_function_after_warmup() {
+    assert_vm_error!(
+        r#"
+        struct Foo;
+        struct Bar;
+
+        impl Foo {
+            fn name(self) {
+                "foo"
+            }
+        }
+
+        pub fn main() {
+            let values = [];
+
+            for n in 0..50 {
+                values.push(Foo);
+            }
+
+            values.push(Bar);
+
+            for value in values {
+                value.name();
+            }
+        }
+        "#,
+        MissingInstanceFunction { .. } => {}
+    );
+}
@@ -0,0 +1,135 @@
+use rune::runtime::Bytes;
+use rune::{Context, FromValue, Source, Sources, Unit, Vm};
+use std::sync::Arc;
+
+fn build(context: &Context, name: &str, source: &str) -> rune::Result<Unit> {
+    let mut sources = Sources::new();
+    sources.insert(Source::new(name, source));
+    Ok(rune::prepare(&mut sources).with_context(context).build()?)
+}
+
+#[test]
+fn test_load_unit() -> rune::Result<()> {
+    let context = Context::with_default_modules()?;
+
+    let host = build(
+        &context,
+        "host",
+        r#"
+        struct Point { x, y }
+
+        fn describe(p) {
+            `${p.x}, ${p.y}`
+        }
+
+        pub fn main() {
+            describe(Point { x: 1, y: 2 })
+        }
+        "#,
+    )?;
+
+    let plugin = build(
+        &context,
+        "plugin",
+        r#"
+        struct Rect { w, h }
+
+        fn area(r) {
+            r.w * r.h
+        }
+
+        pub fn plugin(n) {
+            let r = Rect { w: n, h: 3 };
+            let o = #{ name: "rect", area: area(r) };
+            (o.name, o.area, b"ok", [1, 2].iter().map(|v| v * n).collect::<Vec>())
+        }
+        "#,
+    )?;
+
+    let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(host));
+    vm.load_unit(&plugin)?;
+    vm.unit().verify()?;
+
+    assert_eq!(String::from_value(vm.call(["main"], ())?)?, "1, 2");
+
+    let output = <(String, i64, Bytes, Vec<i64>)>::from_value(vm.call(["plugin"], (2i64,))?)?;
+    let output = (output.0, output.1, output.2.into_vec(), output.3);
+    assert_eq!(
+        output,
+        (String::from("rect"), 6, b"ok".to_vec(), vec![2, 4])
+    );
+    Ok(())
+}
+
+#[test]
+fn test_load_unit_conflict() -> rune::Result<()> {
+    let context = Context::with_default_modules()?;
+
+    let host = build(&context, "host", "pub fn main() { 1 }")?;
+    let plugin = build(&context, "plugin", "pub fn main() { 2 }")?;
+
+    let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(host));
+    assert!(vm.load_unit(&plugin).is_err());
+    assert_eq!(i64::from_value(vm.call(["main"], ())?)?, 1);
+    Ok(())
+}
+
+#[test]
+fn test_load_unit_after_warmup() -> rune::Result<()> {
+    let context = Context::with_default_modules()?;
+
+    let host = build(
+        &context,
+        "host",
+        r#"
+        struct Host;
+
+        impl Host {
+            fn name(self) {
+                "host"
+            }
+        }
+
+        pub fn host() {
+            Host
+        }
+
+        pub fn names(values) {
+            values.iter().map(|value| value.name()).collect::<Vec>()
+        }
+        "#,
+    )?;
+
+    let plugin = build(
+        &context,
+        "plugin",
+        r#"
+        struct Plugin;
+
+        impl Plugin {
+            fn name(self) {
+                "plugin"
+            }
+        }
+
+        pub fn plugin() {
+            Plugin
+        }
+        "#,
+    )?;
+
+    let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(host));
+
+    // NB: warm up the inline cache of the call site in `names`.
+    let values = (0..32)
+        .map(|_| vm.call(["host"], ()))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(Vec::<String>::from_value(vm.call(["names"], (values,))?)?.len(), 32);
+
+    vm.load_unit(&plugin)?;
+
+    let values = vec![vm.call(["host"], ())?, vm.call(["plugin"], ())?];
+    let names = Vec::<String>::from_value(vm.call(["names"], (values,))?)?;
+    assert_eq!(names, ["host", "plugin"]);
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use rune_tests::*;
+use rune::runtime::VmErrorKind;
+use rune::FromValue;
+
+fn is_memory_limit_exceeded(error: &rune::runtime::VmError) -> bool {
+    matches!(
+        error.as_unwound().0,
+        VmErrorKind::MemoryLimitExceeded { .. }
+    )
+}
+
+#[test]
+fn test_vec_growth() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(r#"pub fn main() { let v = []; loop { v.push([1, 2, 3]); } }"#)?;
+    vm.set_memory_limit(Some(1 << 20));
+
+    let error = vm.call(&["main"], ()).unwrap_err();
+    assert!(is_memory_limit_exceeded(&error));
+    Ok(())
+}
+
+#[test]
+fn test_string_growth() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(r#"
+        pub fn main() {
+            let s = String::new();
+            loop { s.push_str("hello world"); }
+        }
+    "#)?;
+
+    vm.set_memory_limit(Some(1 << 20));
+
+    let error = vm.call(&["main"], ()).unwrap_err();
+    assert!(is_memory_limit_exceeded(&error));
+    Ok(())
+}
+
+#[test]
+fn test_released() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(r#"
+        pub fn main() {
+            let n = 0;
+
+            while n < 10000 {
+                let v = [n, n + 1, n + 2];
+                let o = #{ a: v, b: `{n}` };
+                n += 1;
+            }
+
+            n
+        }
+    "#)?;
+
+    vm.set_memory_limit(Some(1 << 16));
+
+    let value = vm.call(&["main"], ())?;
+    assert_eq!(i64::from_value(value)?, 10000);
+    assert_eq!(vm.memory_used(), Some(0));
+    Ok(())
+}
+
+#[test]
+fn test_unlimited() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(r#"pub fn main() { let v = []; for n in 0..1000 { v.push(n); } v.len() }"#)?;
+    assert_eq!(vm.memory_limit(), None);
+    assert_eq!(vm.memory_used(), None);
+
+    let value = vm.call(&["main"], ())?;
+    assert_eq!(i64::from_value(value)?, 1000);
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use rune_tests::*;
+use futures_executor::block_on;
+use rune::compile::Item;
+use rune::runtime::{Protocol, VmMetrics};
+use rune::{Any, Context, FromValue, Hash, Module, Vm};
+
+const SOURCE: &str = r#"
+fn fib(n) {
+    if n <= 1 { n } else { fib(n - 1) + fib(n - 2) }
+}
+
+async fn twice(n) {
+    native::double(n)
+}
+
+pub fn main() {
+    fib(10) + native::double(2)
+}
+
+pub async fn entry() {
+    twice(1).await + twice(2).await
+}
+"#;
+
+fn vm() -> rune::Result<Vm> {
+    let mut module = Module::with_crate("native");
+    module.function(&["double"], |n: i64| n * 2)?;
+
+    let mut context = Context::new();
+    context.install(&module)?;
+
+    vm_from_source(&context, SOURCE)
+}
+
+#[test]
+fn test_metrics() -> rune::Result<()> {
+    let mut vm = vm()?;
+    let metrics = VmMetrics::new();
+    vm.set_metrics(metrics.clone());
+
+    vm.call(&["main"], ())?;
+
+    let fib = metrics.get(Hash::type_hash(&["fib"])).expect("missing fib");
+    assert_eq!(fib.calls, 177);
+
+    let main = metrics.get(Hash::type_hash(&["main"])).expect("missing main");
+    assert_eq!(main.calls, 1);
+    assert!(main.duration >= fib.duration / 177);
+
+    let double = Hash::type_hash(&Item::with_crate_item("native", &["double"]));
+    assert_eq!(metrics.get(double).expect("missing double").calls, 1);
+
+    vm.call(&["main"], ())?;
+    assert_eq!(metrics.get(Hash::type_hash(&["fib"])).unwrap().calls, 354);
+
+    let snapshot = metrics.snapshot();
+    assert_eq!(snapshot.len(), 3);
+
+    metrics.reset();
+    assert!(metrics.get(Hash::type_hash(&["fib"])).is_none());
+    Ok(())
+}
+
+#[test]
+fn test_metrics_async() -> rune::Result<()> {
+    let mut vm = vm()?;
+    let metrics = VmMetrics::new();
+    vm.set_metrics(metrics.clone());
+
+    block_on(vm.async_call(&["entry"], ()))?;
+
+    let twice = metrics.get(Hash::type_hash(&["twice"])).expect("missing twice");
+    assert_eq!(twice.calls, 2);
+
+    let double = Hash::type_hash(&Item::with_crate_item("native", &["double"]));
+    assert_eq!(metrics.get(double).expect("missing double").calls, 2);
+    assert_eq!(metrics.get(Hash::type_hash(&["entry"])).unwrap().calls, 1);
+    Ok(())
+}
+
+#[derive(Any)]
+struct Counter {
+    #[rune(get, set, add_assign)]
+    value: i64,
+}
+
+#[test]
+fn test_metrics_field_functions() -> rune::Result<()> {
+    let mut module = Module::with_crate("native");
+    module.ty::<Counter>()?;
+    module.function(&["Counter", "new"], || Counter { value: 0 })?;
+
+    let mut context = Context::new();
+    context.install(&module)?;
+
+    let mut vm = vm_from_source(
+        &context,
+        r#"
+        pub fn main() {
+            let counter = native::Counter::new();
+            counter.value = counter.value + 1;
+            counter.value += 2;
+            counter.value
+        }
+        "#,
+    )?;
+
+    let metrics = VmMetrics::new();
+    vm.set_metrics(metrics.clone());
+
+    assert_eq!(i64::from_value(vm.call(&["main"], ())?)?, 3);
+
+    let calls = |protocol| {
+        let hash = Hash::field_fn(protocol, Counter::type_hash(), "value");
+        metrics.get(hash).map(|metrics| metrics.calls)
+    };
+
+    assert_eq!(calls(Protocol::GET), Some(2));
+    assert_eq!(calls(Protocol::SET), Some(1));
+    assert_eq!(calls(Protocol::ADD_ASSIGN), Some(1));
+    Ok(())
+}
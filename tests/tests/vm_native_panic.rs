@@ -0,0 +1,98 @@
+use futures_executor::block_on;
+use rune_tests::*;
+use rune::runtime::{VmError, VmErrorKind};
+use rune::{ContextError, Any, FromValue, Module};
+
+#[derive(Any)]
+struct Widget;
+
+impl Widget {
+    fn poke(&self) -> i64 {
+        panic!("poked a widget")
+    }
+}
+
+async fn explode_later() -> i64 {
+    panic!("exploded later")
+}
+
+fn make_module() -> Result<Module, ContextError> {
+    let mut module = Module::with_crate("native");
+    module.function(&["explode"], |n: i64| -> i64 { panic!("exploded with {}", n) })?;
+    module.async_function(&["explode_later"], explode_later)?;
+    module.ty::<Widget>()?;
+    module.function(&["widget"], || Widget)?;
+    module.inst_fn("poke", Widget::poke)?;
+    Ok(module)
+}
+
+fn panic_message(error: &VmError) -> Option<&str> {
+    match error.as_unwound().0 {
+        VmErrorKind::NativePanic { message } => Some(message),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_native_function_panic() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(make_module()? => r#"
+        pub fn main() { native::explode(42) }
+        pub fn ok() { 1 }
+    "#)?;
+
+    let error = vm.call(&["main"], ()).unwrap_err();
+    assert_eq!(panic_message(&error), Some("exploded with 42"));
+    assert!(error
+        .to_string()
+        .starts_with("native code panicked: exploded with 42"));
+
+    let value = vm.call(&["ok"], ())?;
+    assert_eq!(i64::from_value(value)?, 1);
+    Ok(())
+}
+
+#[test]
+fn test_instance_function_panic() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(make_module()? => r#"pub fn main() { native::widget().poke() }"#)?;
+    let error = vm.call(&["main"], ()).unwrap_err();
+    assert_eq!(panic_message(&error), Some("poked a widget"));
+    Ok(())
+}
+
+#[test]
+fn test_panic_through_closure() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(make_module()? => r#"
+        pub fn main() {
+            [1, 2, 3].iter().map(|n| native::explode(n)).collect::<Vec>()
+        }
+    "#)?;
+
+    let error = vm.call(&["main"], ()).unwrap_err();
+    assert_eq!(panic_message(&error), Some("exploded with 1"));
+    Ok(())
+}
+
+#[test]
+fn test_async_function_panic() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(make_module()? => r#"pub async fn main() { native::explode_later().await }"#)?;
+    let error = block_on(vm.async_call(&["main"], ())).unwrap_err();
+    assert_eq!(panic_message(&error), Some("exploded later"));
+    Ok(())
+}
+
+#[test]
+fn test_call_with_recovery() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(make_module()? => r#"
+        pub fn main() { native::explode(1) }
+        pub fn ok() { 2 }
+    "#)?;
+
+    let error = vm.call_with_recovery(&["main"], ()).unwrap_err();
+    assert_eq!(panic_message(&error), Some("exploded with 1"));
+    assert!(vm.stack().is_empty());
+    assert!(vm.call_frames().is_empty());
+
+    let value = vm.call_with_recovery(&["ok"], ())?;
+    assert_eq!(i64::from_value(value)?, 2);
+    Ok(())
+}
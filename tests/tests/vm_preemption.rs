@@ -0,0 +1,131 @@
+use rune_tests::*;
+use futures_executor::block_on;
+use rune::{FromValue, Source, Sources, Vm};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+const SOURCE: &str = r#"
+fn add(a, b) {
+    a + b
+}
+
+pub async fn count(n) {
+    let total = 0;
+
+    while total < n {
+        total = add(total, 1);
+    }
+
+    total
+}
+"#;
+
+/// Counts the number of times the wrapped future is polled.
+struct Polls<F> {
+    future: Pin<Box<F>>,
+    polls: usize,
+}
+
+impl<F> Future for Polls<F>
+where
+    F: Future,
+{
+    type Output = (F::Output, usize);
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.polls += 1;
+
+        match self.future.as_mut().poll(cx) {
+            Poll::Ready(output) => Poll::Ready((output, self.polls)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Polls both futures in order, resolving to the index of the first one to
+/// complete.
+struct Race<A, B>(Pin<Box<A>>, Pin<Box<B>>);
+
+impl<A, B> Future for Race<A, B>
+where
+    A: Future,
+    B: Future,
+{
+    type Output = usize;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.0.as_mut().poll(cx).is_ready() {
+            return Poll::Ready(0);
+        }
+
+        if self.1.as_mut().poll(cx).is_ready() {
+            return Poll::Ready(1);
+        }
+
+        Poll::Pending
+    }
+}
+
+#[test]
+fn test_preemption() -> rune::Result<()> {
+    let mut vm = vm_from_source(&rune::Context::new(), SOURCE)?;
+    vm.set_preemption_interval(Some(100));
+    assert_eq!(vm.preemption_interval(), Some(100));
+
+    let future = Polls {
+        future: Box::pin(vm.async_call(&["count"], (1000,))),
+        polls: 0,
+    };
+
+    let (value, polls) = block_on(future);
+    assert_eq!(i64::from_value(value?)?, 1000);
+    assert!(polls >= 20, "expected at least 20 polls, got {}", polls);
+    Ok(())
+}
+
+#[test]
+fn test_not_preempted() -> rune::Result<()> {
+    let mut vm = vm_from_source(&rune::Context::new(), SOURCE)?;
+    assert_eq!(vm.preemption_interval(), None);
+
+    let future = Polls {
+        future: Box::pin(vm.async_call(&["count"], (1000,))),
+        polls: 0,
+    };
+
+    let (value, polls) = block_on(future);
+    assert_eq!(i64::from_value(value?)?, 1000);
+    assert_eq!(polls, 1);
+    Ok(())
+}
+
+#[test]
+fn test_multiplexing() -> rune::Result<()> {
+    let mut long = vm_from_source(&rune::Context::new(), SOURCE)?;
+    let mut short = vm_from_source(&rune::Context::new(), SOURCE)?;
+    long.set_preemption_interval(Some(100));
+    short.set_preemption_interval(Some(100));
+
+    let first = block_on(Race(
+        Box::pin(long.async_call(&["count"], (100_000,))),
+        Box::pin(short.async_call(&["count"], (10,))),
+    ));
+
+    assert_eq!(first, 1);
+    Ok(())
+}
+
+#[test]
+fn test_sync_call() -> rune::Result<()> {
+    let mut sources = Sources::new();
+    sources.insert(Source::new("entry", "pub fn main() { let n = 0; while n < 100 { n += 1; } n }"));
+    let unit = rune::prepare(&mut sources).build()?;
+
+    let mut vm = Vm::without_runtime(Arc::new(unit));
+    vm.set_preemption_interval(Some(1));
+    let value = vm.call(&["main"], ())?;
+    assert_eq!(i64::from_value(value)?, 100);
+    Ok(())
+}
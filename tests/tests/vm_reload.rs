@@ -0,0 +1,96 @@
+use rune::runtime::GeneratorState;
+use rune::{Context, FromValue, Reloader, Source, Sources, Unit, Vm};
+use std::fs;
+use std::sync::Arc;
+
+fn build(context: &Context, source: &str) -> rune::Result<Unit> {
+    let mut sources = Sources::new();
+    sources.insert(Source::new("main", source));
+    Ok(rune::prepare(&mut sources).with_context(context).build()?)
+}
+
+#[test]
+fn test_reload_preserves_generators() -> rune::Result<()> {
+    let context = Context::with_default_modules()?;
+
+    let unit = build(
+        &context,
+        r#"
+        fn value() { 1 }
+
+        pub fn main() {
+            yield value();
+            yield value();
+        }
+        "#,
+    )?;
+
+    let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+    let mut execution = vm.execute(["main"], ())?;
+
+    assert!(matches!(
+        execution.resume()?,
+        GeneratorState::Yielded(value) if i64::from_value(value.clone())? == 1
+    ));
+
+    let unit = build(
+        &context,
+        r#"
+        fn value() { 2 }
+
+        pub fn main() {
+            yield value() * 10;
+        }
+        "#,
+    )?;
+
+    execution.vm_mut().reload(&unit);
+    execution.vm_mut().unit().verify()?;
+
+    // NB: the generator keeps running the code it was started with, but calls
+    // the new version of `value`.
+    assert!(matches!(
+        execution.resume()?,
+        GeneratorState::Yielded(value) if i64::from_value(value.clone())? == 2
+    ));
+
+    assert!(matches!(execution.resume()?, GeneratorState::Complete(..)));
+
+    let mut execution = vm.execute(["main"], ())?;
+
+    assert!(matches!(
+        execution.resume()?,
+        GeneratorState::Yielded(value) if i64::from_value(value.clone())? == 20
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_reloader() -> rune::Result<()> {
+    let context = Context::with_default_modules()?;
+
+    let dir = std::env::temp_dir().join(format!("rune-reload-{}", std::process::id()));
+    fs::create_dir_all(&dir)?;
+    let path = dir.join("script.rn");
+    fs::write(&path, "pub fn main() { 1 }")?;
+
+    let mut sources = Sources::new();
+    sources.insert(Source::from_path(&path)?);
+
+    let mut reloader = Reloader::new(sources);
+    let unit = reloader.build(|sources| rune::prepare(sources).with_context(&context).build())?;
+    let mut vm = Vm::new(Arc::new(context.runtime()), Arc::new(unit));
+
+    let build = |sources: &mut Sources| rune::prepare(sources).with_context(&context).build();
+
+    assert!(!reloader.reload(&mut vm, build)?);
+    assert_eq!(i64::from_value(vm.call(["main"], ())?)?, 1);
+
+    fs::write(&path, "pub fn main() { 2 }")?;
+    assert!(reloader.reload(&mut vm, build)?);
+    assert_eq!(i64::from_value(vm.call(["main"], ())?)?, 2);
+
+    fs::remove_dir_all(&dir)?;
+    Ok(())
+}
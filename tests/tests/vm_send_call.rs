@@ -0,0 +1,135 @@
+use rune_tests::*;
+use futures_executor::block_on;
+use rune::runtime::VmSendExecution;
+use rune::{FromValue, Module, Vm};
+use std::cell::RefCell;
+use std::thread;
+
+const SOURCE: &str = r#"
+async fn square(n) {
+    n * n
+}
+
+pub async fn main(n, name) {
+    let squared = square(n).await;
+    `${name}: ${squared}`
+}
+
+pub fn fail(n) {
+    n + "bad"
+}
+
+pub fn sum(values) {
+    let sum = 0;
+
+    for value in values {
+        sum += value;
+    }
+
+    `${global::name}: ${sum}`
+}
+
+pub fn greet() {
+    let globals = globals();
+    globals.name = `${global::name}!`;
+    globals.name
+}
+"#;
+
+#[test]
+fn test_send_call_on_threads() -> rune::Result<()> {
+    let vm = rune_vm_with!(SOURCE)?;
+
+    let handles = (0..4i64)
+        .map(|n| {
+            let future = vm.send_call::<_, _, String>(&["main"], (n, format!("task{}", n)))?;
+            Ok(thread::spawn(move || block_on(future)))
+        })
+        .collect::<rune::Result<Vec<_>>>()?;
+
+    let mut output = Vec::new();
+
+    for handle in handles {
+        output.push(handle.join().unwrap()?);
+    }
+
+    assert_eq!(output, ["task0: 0", "task1: 1", "task2: 4", "task3: 9"]);
+    Ok(())
+}
+
+#[test]
+fn test_send_call_errors() -> rune::Result<()> {
+    let vm = rune_vm_with!(SOURCE)?;
+
+    assert!(vm.send_call::<_, _, ()>(&["missing"], ()).is_err());
+
+    let future = vm.send_call::<_, _, ()>(&["fail"], (1i64,))?;
+    assert!(thread::spawn(move || block_on(future))
+        .join()
+        .unwrap()
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_send_execute_with_globals() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(SOURCE)?;
+    vm.set_global("name", "rune")?;
+
+    let execution = vm.clone().send_execute(&["greet"], ())?;
+
+    let output = thread::spawn(move || -> rune::Result<String> {
+        let value = block_on(execution.async_complete())?;
+        Ok(String::from_value(value)?)
+    })
+    .join()
+    .unwrap()?;
+
+    assert_eq!(output, "rune!");
+
+    // NB: the globals are copied into the sent execution.
+    let name = vm.global("name")?.expect("global is defined");
+    assert_eq!(String::from_value(name)?, "rune");
+    Ok(())
+}
+
+thread_local! {
+    static SENDER: RefCell<Option<Vm>> = RefCell::new(None);
+    static SENT: RefCell<Option<VmSendExecution>> = RefCell::new(None);
+}
+
+#[test]
+fn test_send_execute_from_native_function() -> rune::Result<()> {
+    let mut module = Module::new();
+
+    module.function(&["send"], || {
+        let vm = SENDER.with(|vm| vm.borrow_mut().take()).expect("a virtual machine to send");
+        let execution = vm.send_execute(&["sum"], (vec![1i64, 2, 3],));
+        let execution = execution.expect("execution to be sent");
+        SENT.with(|sent| *sent.borrow_mut() = Some(execution));
+    })?;
+
+    let source = format!("{}\npub fn send_sum() {{ send() }}", SOURCE);
+    let mut vm = rune_vm_with!(module => &source)?;
+    vm.set_cycle_collection(true);
+    vm.set_global("name", "sum")?;
+
+    SENDER.with(|sender| *sender.borrow_mut() = Some(vm.clone()));
+    vm.call(&["send_sum"], ())?;
+
+    // NB: the values sent along aren't tracked by the virtual machine which
+    // was executing when they were allocated.
+    assert_eq!(vm.tracked_values(), Some(0));
+
+    let execution = SENT.with(|sent| sent.borrow_mut().take()).expect("an execution");
+
+    let output = thread::spawn(move || -> rune::Result<String> {
+        let value = block_on(execution.async_complete())?;
+        Ok(String::from_value(value)?)
+    })
+    .join()
+    .unwrap()?;
+
+    assert_eq!(output, "sum: 6");
+    Ok(())
+}
@@ -0,0 +1,178 @@
+use rune::runtime::{GeneratorState, RuntimeContext, VmErrorKind, VmExecution};
+use rune::{Context, FromValue, Source, Sources, Unit, Value, Vm};
+use std::sync::Arc;
+
+fn compile(source: &str) -> rune::Result<(Arc<RuntimeContext>, Arc<Unit>)> {
+    let context = Context::with_default_modules()?;
+
+    let mut sources = Sources::new();
+    sources.insert(Source::new("entry", source));
+
+    let unit = rune::prepare(&mut sources).with_context(&context).build()?;
+    Ok((Arc::new(context.runtime()), Arc::new(unit)))
+}
+
+fn yielded(state: GeneratorState) -> Value {
+    match state {
+        GeneratorState::Yielded(value) => value,
+        GeneratorState::Complete(value) => panic!("expected yield, got {:?}", value),
+    }
+}
+
+fn complete(state: GeneratorState) -> Value {
+    match state {
+        GeneratorState::Complete(value) => value,
+        GeneratorState::Yielded(value) => panic!("expected completion, got {:?}", value),
+    }
+}
+
+#[test]
+fn test_snapshot_call_frames() -> rune::Result<()> {
+    let (context, unit) = compile(
+        r#"
+        fn fib(n) {
+            if n <= 1 { n } else { fib(n - 1) + fib(n - 2) }
+        }
+
+        pub fn main() {
+            fib(15)
+        }
+        "#,
+    )?;
+
+    let mut vm = Vm::new(context.clone(), unit.clone());
+    vm.set_fuel(Some(500));
+
+    let mut execution = vm.execute(&["main"], ())?;
+    let error = execution.complete().unwrap_err();
+    assert!(matches!(error.as_unwound().0, VmErrorKind::OutOfFuel));
+    assert!(!execution.vm().call_frames().is_empty());
+
+    let snapshot = execution.snapshot()?;
+    drop(execution);
+
+    let mut execution = VmExecution::restore(context, unit, &snapshot)?;
+    assert_eq!(i64::from_value(execution.complete()?)?, 610);
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_values() -> rune::Result<()> {
+    let (context, unit) = compile(
+        r#"
+        struct Point { x, y }
+        enum Shape { Circle(r), Empty }
+
+        pub fn main() {
+            let shared = [1, 2];
+            let point = Point { x: 1, y: 2 };
+            let values = (shared, shared, point, Shape::Circle(3), 1..4, #{ a: b"ab" });
+            let f = |n| n + shared.len();
+            yield;
+            shared.push(3);
+
+            let circle = match values.3 { Shape::Circle(r) => r, _ => 0 };
+            let range = 0;
+
+            for _ in values.4 {
+                range += 1;
+            }
+
+            (values.1.len(), values.2.x + values.2.y, circle, f(1), range, values.5.a.len())
+        }
+        "#,
+    )?;
+
+    let mut vm = Vm::new(context.clone(), unit.clone());
+    let mut execution = vm.execute(&["main"], ())?;
+    yielded(execution.resume()?);
+    let snapshot = execution.snapshot()?;
+    drop(execution);
+
+    let mut execution = VmExecution::restore(context, unit, &snapshot)?;
+    let output = complete(execution.resume_with(Value::Unit)?);
+    let output = <(i64, i64, i64, i64, i64, i64)>::from_value(output)?;
+    assert_eq!(output, (3, 3, 3, 4, 3, 2));
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_generator() -> rune::Result<()> {
+    let (context, unit) = compile(
+        r#"
+        fn counter() {
+            let n = 0;
+            loop { n += 1; yield n; }
+        }
+
+        pub fn main() {
+            let g = counter();
+            g.next();
+            yield;
+            g.next()
+        }
+        "#,
+    )?;
+
+    let mut vm = Vm::new(context.clone(), unit.clone());
+    let mut execution = vm.execute(&["main"], ())?;
+    yielded(execution.resume()?);
+    let snapshot = execution.snapshot()?;
+
+    let mut execution = VmExecution::restore(context, unit, &snapshot)?;
+    let output = complete(execution.resume_with(Value::Unit)?);
+    assert_eq!(Option::<i64>::from_value(output)?, Some(2));
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_errors() -> rune::Result<()> {
+    let (context, unit) = compile(
+        r#"
+        pub fn main() {
+            let it = [1, 2].iter();
+            yield;
+        }
+        "#,
+    )?;
+
+    let mut vm = Vm::new(context.clone(), unit.clone());
+    let mut execution = vm.execute(&["main"], ())?;
+    yielded(execution.resume()?);
+    let error = execution.snapshot().unwrap_err();
+    assert_eq!(error.to_string(), "values of type `Iterator` can't be snapshotted");
+
+    let (context, other) = compile(r#"pub fn main() { let a = yield; a }"#)?;
+    let mut vm = Vm::new(context.clone(), other);
+    let mut execution = vm.execute(&["main"], ())?;
+    yielded(execution.resume()?);
+    let snapshot = execution.snapshot()?;
+
+    let error = VmExecution::restore(context, unit, &snapshot).err().unwrap();
+    assert_eq!(error.to_string(), "snapshot was taken against a different unit");
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_malformed() -> rune::Result<()> {
+    let (context, unit) = compile(r#"pub fn main() { let a = yield; a }"#)?;
+
+    let mut vm = Vm::new(context.clone(), unit.clone());
+    let mut execution = vm.execute(&["main"], ())?;
+    yielded(execution.resume()?);
+    let mut snapshot = execution.snapshot()?;
+
+    // The version and fingerprint are followed by an empty heap and the
+    // instruction pointer of the head.
+    assert_eq!(snapshot[12..20], 0u64.to_le_bytes());
+    snapshot[20..28].copy_from_slice(&u64::MAX.to_le_bytes());
+
+    let error = VmExecution::restore(context, unit, &snapshot).err().unwrap();
+
+    assert_eq!(
+        error.to_string(),
+        format!("malformed snapshot: instruction pointer {} is out of bounds", u64::MAX)
+    );
+
+    Ok(())
+}
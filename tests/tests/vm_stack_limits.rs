@@ -0,0 +1,106 @@
+use rune_tests::*;
+use rune::runtime::{VmError, VmErrorKind};
+use rune::FromValue;
+
+fn is_stack_overflow(error: &VmError) -> bool {
+    matches!(error.as_unwound().0, VmErrorKind::StackOverflow { .. })
+}
+
+#[test]
+fn test_call_depth() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(r#"
+        fn recurse(n) { if n == 0 { 0 } else { recurse(n - 1) + 1 } }
+        pub fn main(n) { recurse(n) }
+    "#)?;
+
+    vm.set_call_depth_limit(Some(64));
+    assert_eq!(vm.call_depth_limit(), Some(64));
+
+    let value = vm.call(&["main"], (32,))?;
+    assert_eq!(i64::from_value(value)?, 32);
+
+    let error = vm.call(&["main"], (1000,)).unwrap_err();
+    assert!(is_stack_overflow(&error));
+
+    let trace = error.stack_trace().expect("missing stack trace");
+    assert_eq!(trace.frames().len(), 65);
+
+    let message = error.to_string();
+    assert!(message.contains("stack overflow, call depth of 64"));
+    assert!(message.contains("... repeated 63 more times"));
+    Ok(())
+}
+
+#[test]
+fn test_recursion_through_native() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(r#"
+        fn recurse(n) { [n].iter().map(recurse).collect::<Vec>() }
+        pub fn main() { recurse(0) }
+    "#)?;
+
+    vm.set_call_depth_limit(Some(64));
+
+    let error = vm.call(&["main"], ()).unwrap_err();
+    assert!(is_stack_overflow(&error));
+    Ok(())
+}
+
+#[test]
+fn test_stack_size() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(r#"
+        fn recurse(a, b, c, d) { recurse(a, b, c, d) + 1 }
+        pub fn main() { recurse(1, 2, 3, 4) }
+    "#)?;
+
+    vm.set_stack_size_limit(Some(1024));
+    assert_eq!(vm.stack_size_limit(), Some(1024));
+
+    let error = vm.call(&["main"], ()).unwrap_err();
+    assert!(is_stack_overflow(&error));
+    assert!(vm.stack().len() <= 1024 + 8);
+    Ok(())
+}
+
+#[test]
+fn test_unlimited() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(r#"
+        fn recurse(n) { if n == 0 { 0 } else { recurse(n - 1) + 1 } }
+        pub fn main(n) { recurse(n) }
+    "#)?;
+
+    assert_eq!(vm.call_depth_limit(), None);
+    assert_eq!(vm.stack_size_limit(), None);
+
+    let value = vm.call(&["main"], (10000,))?;
+    assert_eq!(i64::from_value(value)?, 10000);
+    Ok(())
+}
+
+#[test]
+fn test_tail_calls() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(r#"
+        fn sum(n, acc) { if n == 0 { acc } else { sum(n - 1, acc + n) } }
+
+        fn count(n) {
+            if n == 0 {
+                return 0;
+            }
+
+            let m = n - 1;
+            return count(m);
+        }
+
+        fn parity(n) { match n { 0 => true, 1 => false, n => parity(n - 2) } }
+
+        pub fn main(n) { (sum(n, 0), count(n), parity(n)) }
+    "#)?;
+
+    vm.set_call_depth_limit(Some(64));
+
+    let value = vm.call(&["main"], (100000,))?;
+    assert_eq!(
+        <(i64, i64, bool)>::from_value(value)?,
+        (5000050000, 0, true)
+    );
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use rune::{Source, Sources, Vm};
+use std::sync::Arc;
+
+const SOURCE: &str = r#"
+fn divide(a, b) {
+    a / b
+}
+
+fn compute(n) {
+    divide(n, 0)
+}
+
+pub fn main() {
+    compute(10)
+}
+"#;
+
+#[test]
+fn test_stack_trace() -> rune::Result<()> {
+    let mut sources = Sources::new();
+    sources.insert(Source::new("entry", SOURCE));
+    let unit = rune::prepare(&mut sources).build()?;
+    let mut vm = Vm::without_runtime(Arc::new(unit));
+
+    let error = vm.execute(&["main"], ())?.complete().unwrap_err();
+    let trace = error.stack_trace().expect("missing stack trace");
+
+    let items = trace
+        .frames()
+        .iter()
+        .map(|frame| frame.item.as_ref().map(|item| item.to_string()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        items,
+        vec![
+            Some(String::from("divide")),
+            Some(String::from("compute")),
+            Some(String::from("main")),
+        ]
+    );
+
+    let innermost = &trace.frames()[0];
+    let span = innermost.span.expect("missing span");
+    assert_eq!(&SOURCE[span.range()], "a / b");
+
+    let caller = &trace.frames()[1];
+    let span = caller.span.expect("missing span");
+    assert!(SOURCE[span.range()].contains("divide(n, 0)"));
+
+    let message = error.to_string();
+    assert!(message.starts_with("division by zero"));
+    assert!(message.contains("\n    at divide (inst "));
+    assert!(message.contains("\n    at compute (inst "));
+    assert!(message.contains("\n    at main (inst "));
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use rune_tests::*;
+
+#[test]
+fn test_string_split_and_trim() {
+    let _: () = rune! {
+        pub fn main() {
+            assert_eq!("  a b\tc\n".split_whitespace().collect::<Vec>(), ["a", "b", "c"]);
+            assert_eq!("a=b=c".splitn(2, '=').collect::<Vec>(), ["a", "b=c"]);
+            assert_eq!("a::b::c".splitn(5, "::").collect::<Vec>(), ["a", "b", "c"]);
+
+            assert_eq!("  a  ".trim_start(), "a  ");
+            assert_eq!("xxaxx".trim_start_matches('x'), "axx");
+            assert_eq!("abab-ab".trim_end_matches("ab"), "abab-");
+
+            assert_eq!("v1.2".strip_prefix("v"), Some("1.2"));
+            assert_eq!("1.2".strip_prefix("v"), None);
+            assert_eq!("file.rn".strip_suffix(".rn"), Some("file"));
+        }
+    };
+}
+
+#[test]
+fn test_string_formatting() {
+    let _: () = rune! {
+        pub fn main() {
+            assert_eq!("7".pad_start(3, '0'), "007");
+            assert_eq!("åb".pad_end(4, '.'), "åb..");
+            assert_eq!("long".pad_start(2, ' '), "long");
+            assert_eq!("ab".repeat(3), "ababab");
+            assert_eq!("Straße".to_uppercase(), "STRASSE");
+            assert_eq!("ÅBC".to_lowercase(), "åbc");
+        }
+    };
+}
+
+#[test]
+fn test_string_unicode() {
+    let _: () = rune! {
+        pub fn main() {
+            let s = "he\u{301}llo wörld";
+            assert_eq!(s.chars().count(), 12);
+            assert_eq!(s.char_count(), 12);
+            assert_eq!(s.graphemes().count(), 11);
+            assert_eq!(s.graphemes().next(), Some("h"));
+            assert_eq!(s.graphemes().skip(1).next(), Some("e\u{301}"));
+
+            assert_eq!(s.char_slice(7, 12), Some("wörld"));
+            assert_eq!(s.char_slice(12, 12), Some(""));
+            assert_eq!(s.char_slice(0, 13), None);
+            assert_eq!(s.char_slice(3, 2), None);
+        }
+    };
+}
@@ -0,0 +1,134 @@
+//! Tests for suspending executions and resuming them with values from the
+//! host.
+
+use rune_tests::*;
+use futures_executor::block_on;
+use rune::runtime::{GeneratorState, Suspend};
+use rune::{ContextError, FromValue, Module, Value};
+
+fn make_module() -> Result<Module, ContextError> {
+    let mut module = Module::with_crate("host");
+    module.function(&["ask"], |question: String| Suspend::new(Value::from(question)))?;
+    module.async_function(&["double"], |n: i64| async move { n * 2 })?;
+    Ok(module)
+}
+
+fn expect_yielded(state: GeneratorState) -> rune::Result<String> {
+    match state {
+        GeneratorState::Yielded(value) => Ok(String::from_value(value)?),
+        GeneratorState::Complete(..) => panic!("expected suspension"),
+    }
+}
+
+#[test]
+fn test_suspend_resume_with() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        make_module()? => r#"
+        pub async fn main() {
+            let a = host::ask("a").await;
+            let b = host::ask("b").await;
+            let c = std::future::suspend("c").await;
+            (a, b, c)
+        }
+        "#,
+    )?;
+
+    let mut execution = vm.execute(&["main"], ())?;
+
+    assert_eq!(expect_yielded(execution.resume()?)?, "a");
+    assert_eq!(expect_yielded(execution.resume_with(Value::from(1i64))?)?, "b");
+    assert_eq!(expect_yielded(execution.resume_with(Value::from(2i64))?)?, "c");
+
+    let output = match execution.resume()? {
+        GeneratorState::Complete(value) => <(i64, i64, ())>::from_value(value)?,
+        GeneratorState::Yielded(..) => panic!("expected completion"),
+    };
+
+    assert_eq!(output, (1, 2, ()));
+    Ok(())
+}
+
+#[test]
+fn test_suspend_async() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        make_module()? => r#"
+        pub async fn main() {
+            let n = host::ask("n").await;
+            host::double(n).await + host::double(n).await
+        }
+        "#,
+    )?;
+
+    let mut execution = vm.execute(&["main"], ())?;
+
+    let question = expect_yielded(block_on(execution.async_resume())?)?;
+    assert_eq!(question, "n");
+
+    let output = match block_on(execution.async_resume_with(Value::from(5i64)))? {
+        GeneratorState::Complete(value) => i64::from_value(value)?,
+        GeneratorState::Yielded(..) => panic!("expected completion"),
+    };
+
+    assert_eq!(output, 20);
+    Ok(())
+}
+
+#[test]
+fn test_suspend_in_called_async_function() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        make_module()? => r#"
+        async fn inner() {
+            host::ask("inner").await
+        }
+
+        pub async fn main() {
+            inner().await
+        }
+        "#,
+    )?;
+
+    let mut execution = vm.execute(&["main"], ())?;
+    assert_eq!(expect_yielded(block_on(execution.async_resume())?)?, "inner");
+
+    let output = match block_on(execution.async_resume_with(Value::from(42i64)))? {
+        GeneratorState::Complete(value) => i64::from_value(value)?,
+        GeneratorState::Yielded(..) => panic!("expected completion"),
+    };
+
+    assert_eq!(output, 42);
+    Ok(())
+}
+
+#[test]
+fn test_suspend_in_nested_async_functions() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        make_module()? => r#"
+        async fn ask_twice(question) {
+            host::ask(question).await + host::ask(question).await
+        }
+
+        async fn outer() {
+            let sum = ask_twice("x");
+            host::double(sum.await).await
+        }
+
+        pub async fn main() {
+            outer().await + 1
+        }
+        "#,
+    )?;
+
+    let mut execution = vm.execute(&["main"], ())?;
+    assert_eq!(expect_yielded(block_on(execution.async_resume())?)?, "x");
+
+    let state = block_on(execution.async_resume_with(Value::from(1i64)))?;
+    assert_eq!(expect_yielded(state)?, "x");
+
+    let output = match block_on(execution.async_resume_with(Value::from(2i64)))? {
+        GeneratorState::Complete(value) => i64::from_value(value)?,
+        GeneratorState::Yielded(..) => panic!("expected completion"),
+    };
+
+    assert_eq!(output, 7);
+    Ok(())
+}
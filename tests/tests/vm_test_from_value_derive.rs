@@ -1,5 +1,5 @@
 use rune::runtime::VmErrorKind::*;
-use rune::FromValue;
+use rune::{FromValue, ToValue};
 use rune_tests::*;
 
 #[test]
@@ -125,3 +125,102 @@ fn test_enum_proxy() {
         }
     );
 }
+
+#[test]
+fn test_rename_and_default() {
+    fn answer() -> u32 {
+        42
+    }
+
+    #[derive(Debug, PartialEq, Eq, FromValue)]
+    struct Proxy {
+        #[rune(name = "renamed")]
+        field: u32,
+        #[rune(default)]
+        missing: u32,
+        #[rune(default = "answer")]
+        custom: u32,
+    }
+
+    let proxy: Proxy = rune! {
+        pub fn main() { #{ renamed: 1 } }
+    };
+
+    assert_eq!(
+        proxy,
+        Proxy {
+            field: 1,
+            missing: 0,
+            custom: 42
+        }
+    );
+
+    #[derive(Debug, PartialEq, Eq, FromValue)]
+    struct ProxyTuple(u32, #[rune(default)] u32);
+
+    let proxy: ProxyTuple = rune! {
+        pub fn main() { (1,) }
+    };
+
+    assert_eq!(proxy, ProxyTuple(1, 0));
+}
+
+#[test]
+fn test_enum_round_trip() {
+    #[derive(Debug, Clone, PartialEq, Eq, FromValue, ToValue)]
+    enum Proxy {
+        #[rune(name = "unit")]
+        Unit,
+        Tuple(u32, String),
+        Struct {
+            #[rune(name = "renamed")]
+            field: String,
+        },
+    }
+
+    let proxy: Proxy = rune! {
+        pub fn main() { "unit" }
+    };
+
+    assert_eq!(proxy, Proxy::Unit);
+
+    let proxy: Proxy = rune! {
+        pub fn main() { #{ Struct: #{ renamed: "Hello" } } }
+    };
+
+    assert_eq!(
+        proxy,
+        Proxy::Struct {
+            field: String::from("Hello")
+        }
+    );
+
+    let values = vec![
+        Proxy::Unit,
+        Proxy::Tuple(42, String::from("Hello")),
+        Proxy::Struct {
+            field: String::from("World"),
+        },
+    ];
+
+    for proxy in values {
+        let value = proxy.clone().to_value().unwrap();
+        let back = Proxy::from_value(value).unwrap();
+        assert_eq!(back, proxy);
+    }
+
+    let value = Proxy::Tuple(42, String::from("Hello")).to_value().unwrap();
+    let context = rune::Context::with_default_modules().unwrap();
+    let output: (u32, String) =
+        run(&context, r#"pub fn main(v) { v["Tuple"] }"#, ["main"], (value,)).unwrap();
+    assert_eq!(output, (42, String::from("Hello")));
+}
+
+#[test]
+fn test_unit_struct_to_value() {
+    #[derive(ToValue)]
+    struct Unit;
+
+    let value = Unit.to_value().unwrap();
+    assert!(matches!(value, rune::Value::Unit));
+}
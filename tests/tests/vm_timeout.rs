@@ -0,0 +1,126 @@
+use futures_executor::block_on;
+use rune_tests::*;
+use rune::runtime::{VmError, VmErrorKind};
+use rune::{ContextError, FromValue, Module};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const SOURCE: &str = r#"
+async fn spin() {
+    loop {}
+}
+
+pub fn sync_loop() {
+    loop {}
+}
+
+pub fn closure_loop() {
+    [1, 2, 3].iter().map(|n| { loop {} }).collect::<Vec>()
+}
+
+pub async fn async_loop() {
+    spin().await
+}
+
+pub async fn async_pending() {
+    native::pending().await
+}
+
+pub async fn async_ready() {
+    native::ready().await
+}
+"#;
+
+fn make_module() -> Result<Module, ContextError> {
+    let mut module = Module::with_crate("native");
+    module.async_function(&["pending"], std::future::pending::<()>)?;
+    module.async_function(&["ready"], || async { 42i64 })?;
+    Ok(module)
+}
+
+fn is_timeout(error: &VmError) -> bool {
+    matches!(error.as_unwound().0, VmErrorKind::Timeout)
+}
+
+#[test]
+fn test_sync_loop() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(make_module()? => SOURCE)?;
+    vm.set_deadline(Some(Instant::now() + Duration::from_millis(20)));
+
+    let error = vm.call(&["sync_loop"], ()).unwrap_err();
+    assert!(is_timeout(&error));
+    assert!(error.stack_trace().is_some());
+
+    let error = vm.call(&["closure_loop"], ()).unwrap_err();
+    assert!(is_timeout(&error));
+    Ok(())
+}
+
+#[test]
+fn test_async_loop() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(make_module()? => SOURCE)?;
+
+    let mut execution = vm.execute(&["async_loop"], ())?;
+    execution.set_timeout(Duration::from_millis(20));
+
+    let error = block_on(execution.async_complete()).unwrap_err();
+    assert!(is_timeout(&error));
+    Ok(())
+}
+
+#[test]
+fn test_async_pending() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(make_module()? => SOURCE)?;
+
+    let start = Instant::now();
+    let mut execution = vm.execute(&["async_pending"], ())?;
+    execution.set_timeout(Duration::from_millis(20));
+
+    let error = block_on(execution.async_complete()).unwrap_err();
+    assert!(is_timeout(&error));
+    assert!(start.elapsed() >= Duration::from_millis(20));
+    Ok(())
+}
+
+#[test]
+fn test_within_deadline() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(make_module()? => SOURCE)?;
+
+    let mut execution = vm.execute(&["async_ready"], ())?;
+    execution.set_timeout(Duration::from_secs(60));
+
+    let value = block_on(execution.async_complete())?;
+    assert_eq!(i64::from_value(value)?, 42);
+    Ok(())
+}
+
+#[test]
+fn test_concurrent_deadlines() -> rune::Result<()> {
+    fn spawn(timeout: Duration) -> thread::JoinHandle<rune::Result<Duration>> {
+        thread::spawn(move || {
+            let mut vm = rune_vm_with!(make_module()? => SOURCE)?;
+
+            let start = Instant::now();
+            let mut execution = vm.execute(&["async_pending"], ())?;
+            execution.set_timeout(timeout);
+
+            let error = block_on(execution.async_complete()).unwrap_err();
+            assert!(is_timeout(&error));
+            Ok(start.elapsed())
+        })
+    }
+
+    // NB: the later deadline is registered first, so the timer has to wake up
+    // early for the one registered after it.
+    let late = spawn(Duration::from_millis(1000));
+    thread::sleep(Duration::from_millis(50));
+    let early = spawn(Duration::from_millis(20));
+
+    let early = early.join().unwrap()?;
+    assert!(early >= Duration::from_millis(20));
+    assert!(early < Duration::from_millis(500));
+
+    let late = late.join().unwrap()?;
+    assert!(late >= Duration::from_millis(1000));
+    Ok(())
+}
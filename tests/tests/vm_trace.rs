@@ -0,0 +1,68 @@
+use rune_tests::*;
+use rune::runtime::{DebugContext, TraceWriter};
+use rune::{Context, FromValue};
+use std::io;
+use std::sync::{Arc, Mutex};
+
+const SOURCE: &str = r#"
+fn add(a, b) {
+    a + b
+}
+
+pub fn main() {
+    add(1, 2)
+}
+"#;
+
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_trace_sink() -> rune::Result<()> {
+    let mut vm = vm_from_source(&Context::new(), SOURCE)?;
+    let depths = Arc::new(Mutex::new(Vec::new()));
+
+    let sink = depths.clone();
+    vm.set_trace_sink(move |cx: &DebugContext<'_>| {
+        sink.lock().unwrap().push(cx.depth());
+    });
+
+    let value = vm.execute(&["main"], ())?.complete()?;
+    assert_eq!(i64::from_value(value)?, 3);
+
+    let depths = depths.lock().unwrap();
+    assert!(depths.contains(&0));
+    assert!(depths.contains(&1));
+    Ok(())
+}
+
+#[test]
+fn test_trace_toggle() -> rune::Result<()> {
+    let mut vm = vm_from_source(&Context::new(), SOURCE)?;
+    let buffer = Buffer::default();
+    vm.set_trace_sink(TraceWriter::new(buffer.clone()));
+
+    vm.set_tracing(false);
+    vm.execute(&["main"], ())?.complete()?;
+    assert!(buffer.0.lock().unwrap().is_empty());
+
+    vm.set_tracing(true);
+    vm.execute(&["main"], ())?.complete()?;
+
+    let output = String::from_utf8(buffer.0.lock().unwrap().clone())?;
+    let lines = output.lines().collect::<Vec<_>>();
+    assert!(lines.len() > 3);
+    assert!(lines.iter().any(|line| line.contains("depth=1")));
+    assert!(lines.iter().all(|line| line.contains(" // 0:")));
+    Ok(())
+}
@@ -0,0 +1,140 @@
+//! Tests for the tracing spans emitted around calls into the virtual machine
+//! and into native functions.
+
+use rune_tests::*;
+use rune::{FromValue, Hash};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+#[derive(Default)]
+struct State {
+    /// The name, item and parent of every span created.
+    spans: Vec<(&'static str, Option<String>, Option<usize>)>,
+    /// The stack of entered spans.
+    entered: Vec<usize>,
+}
+
+/// A subscriber collecting the spans emitted by the virtual machine.
+#[derive(Default, Clone)]
+struct Collect {
+    state: Arc<Mutex<State>>,
+}
+
+impl Collect {
+    /// Get the item and the name of the parent of every span with the given
+    /// name.
+    fn spans(&self, name: &str) -> Vec<(Option<String>, Option<&'static str>)> {
+        let state = self.state.lock().unwrap();
+
+        state
+            .spans
+            .iter()
+            .filter(|(n, ..)| *n == name)
+            .map(|(_, item, parent)| (item.clone(), parent.map(|p| state.spans[p].0)))
+            .collect()
+    }
+}
+
+struct ItemVisitor<'a>(&'a mut Option<String>);
+
+impl Visit for ItemVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "item" {
+            *self.0 = Some(format!("{:?}", value));
+        }
+    }
+}
+
+impl Subscriber for Collect {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.target().starts_with("rune::")
+    }
+
+    fn new_span(&self, attributes: &Attributes<'_>) -> Id {
+        let mut state = self.state.lock().unwrap();
+        let mut item = None;
+        attributes.record(&mut ItemVisitor(&mut item));
+        let parent = state.entered.last().copied();
+        state
+            .spans
+            .push((attributes.metadata().name(), item, parent));
+        Id::from_u64(state.spans.len() as u64)
+    }
+
+    fn record(&self, id: &Id, values: &Record<'_>) {
+        let mut state = self.state.lock().unwrap();
+        let span = &mut state.spans[id.into_u64() as usize - 1];
+        values.record(&mut ItemVisitor(&mut span.1));
+    }
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, _: &Event<'_>) {}
+
+    fn enter(&self, id: &Id) {
+        let mut state = self.state.lock().unwrap();
+        state.entered.push(id.into_u64() as usize - 1);
+    }
+
+    fn exit(&self, _: &Id) {
+        self.state.lock().unwrap().entered.pop();
+    }
+}
+
+#[test]
+fn test_tracing_spans() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(
+        r#"
+        pub fn main() {
+            let s = String::from_str("hello");
+            s.push_str(" world");
+            s.len()
+        }
+        "#,
+    )?;
+
+    let collect = Collect::default();
+
+    let output = tracing::subscriber::with_default(collect.clone(), || {
+        let a = i64::from_value(vm.call(&["main"], ())?)?;
+        let b = i64::from_value(vm.call(Hash::type_hash(&["main"]), ())?)?;
+        Ok::<_, rune::Error>((a, b))
+    })?;
+
+    assert_eq!(output, (11, 11));
+
+    let main = Some(String::from("main"));
+    assert_eq!(collect.spans("vm_call"), [(main.clone(), None), (main, None)]);
+
+    let native = collect.spans("native_call");
+    assert_eq!(native.len(), 6);
+    assert!(native.iter().all(|(_, parent)| *parent == Some("vm_call")));
+
+    let items = native
+        .iter()
+        .take(3)
+        .map(|(item, _)| item.as_deref().unwrap_or_default())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        items,
+        [
+            "::std::string::String::from_str",
+            "::std::string::String::push_str",
+            "::std::string::String::len"
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_tracing_spans_disabled() -> rune::Result<()> {
+    let mut vm = rune_vm_with!("pub fn main() { [1, 2, 3].len() }")?;
+    let output = i64::from_value(vm.call(&["main"], ())?)?;
+    assert_eq!(output, 3);
+    Ok(())
+}
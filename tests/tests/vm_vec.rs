@@ -0,0 +1,80 @@
+use rune::runtime::VmErrorKind::*;
+use rune_tests::*;
+
+#[test]
+fn test_vec_api() {
+    let _: () = rune! {
+        pub fn main() {
+            let v = [5, 1, 1, 3, 3, 3, 2];
+            v.dedup();
+            assert_eq!(v, [5, 1, 3, 2]);
+
+            v.retain(|n| n != 3);
+            assert_eq!(v, [5, 1, 2]);
+
+            assert_eq!(v.swap_remove(0), 5);
+            assert_eq!(v, [2, 1]);
+
+            v.insert(2, 4);
+            v.insert(0, 0);
+            assert_eq!(v, [0, 2, 1, 4]);
+
+            v.truncate(3);
+            assert_eq!(v, [0, 2, 1]);
+
+            v.resize(5, 7);
+            assert_eq!(v, [0, 2, 1, 7, 7]);
+            v.resize(2, 7);
+            assert_eq!(v, [0, 2]);
+
+            let v = [1, 2, 3, 4];
+            let tail = v.split_off(1);
+            assert_eq!((v, tail), ([1], [2, 3, 4]));
+
+            let sorted = [1, 3, 5, 7];
+            assert_eq!(sorted.binary_search(5), Ok(2));
+            assert_eq!(sorted.binary_search(4), Err(2));
+            assert_eq!(sorted.binary_search(9), Err(4));
+        }
+    };
+}
+
+#[test]
+fn test_vec_concat_join() {
+    let _: () = rune! {
+        pub fn main() {
+            assert_eq!(["a", "b", "c"].concat(), "abc");
+            assert_eq!(["a", "b", "c"].join(", "), "a, b, c");
+            assert_eq!([].join(", "), "");
+            assert_eq!([[1, 2], [], [3]].concat(), [1, 2, 3]);
+            assert_eq!([[1, 2], [3]].join(0), [1, 2, 0, 3]);
+            assert_eq!([].concat(), []);
+        }
+    };
+}
+
+#[test]
+fn test_vec_errors() {
+    assert_vm_error!(
+        r#"pub fn main() { [1, 2].insert(3, 0) }"#,
+        OutOfRange { index, len } => {
+            assert_eq!(index.to_string(), "3");
+            assert_eq!(len.to_string(), "2");
+        }
+    );
+
+    assert_vm_error!(
+        r#"pub fn main() { [].swap_remove(0) }"#,
+        OutOfRange { .. } => {}
+    );
+
+    assert_vm_error!(
+        r#"pub fn main() { [1].split_off(2) }"#,
+        OutOfRange { .. } => {}
+    );
+
+    assert_vm_error!(
+        r#"pub fn main() { ["a", 1].join(", ") }"#,
+        Expected { .. } => {}
+    );
+}
@@ -0,0 +1,64 @@
+use rune_tests::*;
+use rune::FromValue;
+
+#[test]
+fn test_weak_upgrade() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(r#"
+        use std::mem;
+
+        fn make() {
+            let value = [1, 2, 3];
+            let weak = mem::weak(value);
+            (weak, weak.upgrade().map(|v| v.len()))
+        }
+
+        pub fn main() {
+            let (weak, before) = make();
+            let other = weak.clone();
+            let number = mem::weak(42);
+            (before, weak.upgrade().is_none(), other.is_dropped(), number.upgrade())
+        }
+    "#)?;
+
+    let output = vm.call(&["main"], ())?;
+    let output = <(Option<usize>, bool, bool, Option<i64>)>::from_value(output)?;
+    assert_eq!(output, (Some(3), true, true, Some(42)));
+    Ok(())
+}
+
+#[test]
+fn test_weak_parent_pointers() -> rune::Result<()> {
+    let mut vm = rune_vm_with!(r#"
+        use std::mem;
+
+        struct Node { name, parent, children }
+
+        fn add(parent, name) {
+            let child = Node { name, parent: Some(mem::weak(parent)), children: [] };
+            parent.children.push(child);
+            child
+        }
+
+        fn path(node) {
+            match node.parent.and_then(|parent| parent.upgrade()) {
+                Some(parent) => `${path(parent)}/${node.name}`,
+                None => node.name,
+            }
+        }
+
+        pub fn main() {
+            let root = Node { name: "root", parent: None, children: [] };
+            let a = add(root, "a");
+            let b = add(a, "b");
+            path(b)
+        }
+    "#)?;
+
+    vm.set_cycle_collection(true);
+    let output = vm.call(&["main"], ())?;
+    assert_eq!(String::from_value(output)?, "root/a/b");
+
+    // Everything was freed without having to collect cycles.
+    assert_eq!(vm.tracked_values(), Some(0));
+    Ok(())
+}
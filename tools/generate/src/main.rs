@@ -135,6 +135,7 @@ fn main() -> Result<()> {
     let to_tokens= &rust::import("crate::macros", "ToTokens");
     let token = &rust::import("crate::ast", "Token");
     let token_stream = &rust::import("crate::macros", "TokenStream");
+    let walk = &rust::import("crate::ast", "Walk");
 
     write_tokens(
         Path::new("crates/rune/src/ast/generated.rs"),
@@ -144,7 +145,7 @@ fn main() -> Result<()> {
 
             #(for t in &non_syntax join(#<line>) =>
                 #(format!("/// {}", t.doc()))
-                #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+                #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize)]
                 #[non_exhaustive]
                 pub struct #(t.variant()) {
                     #("/// Associated span.")
@@ -182,6 +183,8 @@ fn main() -> Result<()> {
                         });
                     }
                 }
+
+                impl #walk for #(t.variant()) {}
             )
 
             #("/// Helper macro to reference a specific token.")
@@ -253,7 +256,7 @@ fn main() -> Result<()> {
             }
 
             #("/// The kind of the token.")
-            #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize)]
             pub enum Kind {
                 #("/// En end-of-file marker.")
                 Eof,